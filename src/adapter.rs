@@ -1,2 +1,4 @@
+#[cfg(unix)]
+pub mod daemon;
 pub mod gateway;
 pub mod mcp;
@@ -1,6 +1,9 @@
 #[path = "schronu/command.rs"]
 mod command;
 
+#[path = "schronu/gantt.rs"]
+mod gantt;
+
 #[path = "schronu/handler.rs"]
 mod handler;
 
@@ -13,6 +16,9 @@ mod renderer;
 #[path = "schronu/runtime.rs"]
 mod runtime;
 
+#[path = "schronu/storage_watcher.rs"]
+mod storage_watcher;
+
 #[cfg(test)]
 #[path = "schronu/command_contract_tests.rs"]
 mod command_contract_tests;
@@ -1,3 +1,6 @@
+use schronu::adapter::gateway::task_export::ExportFormat;
+use schronu::entity::allowed_window::AllowedWindow;
+use schronu::entity::datetime::temporal::{ClockTime, DateTerm, TemporalExpression};
 use uuid::Uuid;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -57,6 +60,21 @@ pub(super) enum CommandKind {
     FocusHighest,
     FocusLowest,
     Verify,
+    LoadHistory,
+    FlowMetrics,
+    Revision,
+    Trash,
+    When,
+    AtRisk,
+    Gantt,
+    Move,
+    Copy,
+    Merge,
+    Window,
+    BusyBlock,
+    Import,
+    Export,
+    Lock,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -177,6 +195,129 @@ pub(super) enum CommandAction {
         canonical_name: &'static str,
         recent_days: Option<i64>,
     },
+    DateRange {
+        kind: CommandKind,
+        canonical_name: &'static str,
+        values: Vec<String>,
+    },
+    CriticalPath,
+    ObsidianSync,
+    Verify {
+        fix: bool,
+    },
+    Gantt {
+        days: u64,
+        format: GanttFormat,
+    },
+    Revision(RevisionCommand),
+    Trash(TrashCommand),
+    Wait(WaitCommand),
+    Subtree(SubtreeCommand),
+    Window(WindowCommand),
+    BusyBlock(BusyBlockCommand),
+    Import(ImportCommand),
+    Export(ExportCommand),
+    Lock(LockCommand),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum GanttFormat {
+    Html,
+    Svg,
+}
+
+// 版はUUIDまたはその先頭部分の文字列のまま持ち、保存履歴と照合して解決する
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum RevisionCommand {
+    List,
+    Diff {
+        from: String,
+        until: Option<String>,
+    },
+    Restore {
+        revision: String,
+        project: Option<String>,
+    },
+}
+
+// 捨てた記録も版と同じく先頭部分の文字列のまま持ち、ゴミ箱の一覧と照合して解決する
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum TrashCommand {
+    Delete { task_id: Option<Uuid> },
+    List,
+    Restore { entry: String },
+    Purge { entry: String },
+}
+
+// 保存先の.lockを調べる。どちらもlockを取らずに実行する
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum LockCommand {
+    Status,
+}
+
+// 対象はfocus中のtask。移動先と複製先のNoneは新しいprojectを表す
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum SubtreeCommand {
+    Move { parent_id: Option<Uuid> },
+    Copy { parent_id: Option<Uuid> },
+    Merge { source_task_id: Uuid },
+}
+
+// 返答予定は日時表現として解釈だけしておき、実行時の現在時刻で日付へ解決する
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum WaitCommand {
+    Mark,
+    Start {
+        counterpart: String,
+        expected_reply: Option<TemporalExpression>,
+        follow_up_interval_days: Option<i64>,
+    },
+    Clear,
+    FollowUp,
+    List,
+}
+
+// 対象はfocus中のtask。枠は読み取りの時点で検証しておく
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum WindowCommand {
+    Show,
+    Set(AllowedWindow),
+    Clear,
+}
+
+// 日付は日時表現として解釈だけしておき、実行時の現在時刻で解決する。記録は先頭部分の文字列のまま持つ
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum BusyBlockCommand {
+    List,
+    AddTimed {
+        date: DateTerm,
+        start: ClockTime,
+        end: ClockTime,
+        name: String,
+    },
+    AddAllDay {
+        start_date: DateTerm,
+        end_date: Option<DateTerm>,
+        name: String,
+    },
+    Remove {
+        block: String,
+    },
+}
+
+// 確定がなければ読み込んだ木を見せるだけにする。下ならfocus中のtaskの下に作る
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct ImportCommand {
+    pub(super) path: String,
+    pub(super) under_focus: bool,
+    pub(super) commit: bool,
+}
+
+// focus中のtaskの部分木を書き出す。fileがなければ画面に出し、形式はfileの拡張子かmarkdownにする
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct ExportCommand {
+    pub(super) format: Option<ExportFormat>,
+    pub(super) path: Option<String>,
 }
 
 impl Command {
@@ -209,7 +350,8 @@ impl CommandAction {
             | Self::IntegerValue { kind, .. }
             | Self::OptionalInteger { kind, .. }
             | Self::ClearOrGather { kind, .. }
-            | Self::FocusMode { kind, .. } => *kind,
+            | Self::FocusMode { kind, .. }
+            | Self::DateRange { kind, .. } => *kind,
             Self::Sequential { .. } => CommandKind::Sequential,
             Self::Repeat { .. } => CommandKind::Repeat,
             Self::Pick { .. } => CommandKind::Pick,
@@ -218,6 +360,21 @@ impl CommandAction {
             Self::Escape { .. } => CommandKind::Escape,
             Self::Extrude { .. } => CommandKind::Extrude,
             Self::Finish { .. } => CommandKind::Finish,
+            Self::CriticalPath => CommandKind::Tree,
+            Self::ObsidianSync => CommandKind::Obsidian,
+            Self::Verify { .. } => CommandKind::Verify,
+            Self::Gantt { .. } => CommandKind::Gantt,
+            Self::Revision(_) => CommandKind::Revision,
+            Self::Trash(_) => CommandKind::Trash,
+            Self::Wait(_) => CommandKind::Wait,
+            Self::Subtree(SubtreeCommand::Move { .. }) => CommandKind::Move,
+            Self::Subtree(SubtreeCommand::Copy { .. }) => CommandKind::Copy,
+            Self::Subtree(SubtreeCommand::Merge { .. }) => CommandKind::Merge,
+            Self::Window(_) => CommandKind::Window,
+            Self::BusyBlock(_) => CommandKind::BusyBlock,
+            Self::Import(_) => CommandKind::Import,
+            Self::Export(_) => CommandKind::Export,
+            Self::Lock(_) => CommandKind::Lock,
         }
     }
 }
@@ -522,12 +679,14 @@ fn parse_action(
                 name: arguments[1].clone(),
             }
         }
-        CommandKind::Deadline => CommandAction::StringValue {
-            kind,
-            canonical_name,
-            value: required_argument(arguments, canonical_name, "deadline", "〆 <日付または時刻>")?
-                .to_string(),
-        },
+        CommandKind::Deadline => {
+            required_argument(arguments, canonical_name, "deadline", "〆 <日付または時刻>")?;
+            CommandAction::StringValue {
+                kind,
+                canonical_name,
+                value: arguments.join(" "),
+            }
+        }
         CommandKind::Category => CommandAction::StringValue {
             kind,
             canonical_name,
@@ -606,8 +765,116 @@ fn parse_action(
                 recent_days,
             }
         }
-        CommandKind::Tree
-        | CommandKind::Ancestor
+        CommandKind::LoadHistory => {
+            require_count(arguments, 0, 2, canonical_name, "履 [日数|開始日] [終了日]")?;
+            CommandAction::DateRange {
+                kind,
+                canonical_name,
+                values: arguments.to_vec(),
+            }
+        }
+        CommandKind::FlowMetrics => {
+            require_count(arguments, 0, 2, canonical_name, "流 [日数|開始日] [終了日]")?;
+            CommandAction::DateRange {
+                kind,
+                canonical_name,
+                values: arguments.to_vec(),
+            }
+        }
+        CommandKind::Revision => {
+            CommandAction::Revision(parse_revision_command(arguments, canonical_name)?)
+        }
+        CommandKind::Trash => CommandAction::Trash(parse_trash_command(arguments, canonical_name)?),
+        CommandKind::Wait => CommandAction::Wait(parse_wait_command(arguments, canonical_name)?),
+        CommandKind::Window => {
+            CommandAction::Window(parse_window_command(arguments, canonical_name)?)
+        }
+        CommandKind::BusyBlock => {
+            CommandAction::BusyBlock(parse_busy_block_command(arguments, canonical_name)?)
+        }
+        CommandKind::Import => {
+            CommandAction::Import(parse_import_command(arguments, canonical_name)?)
+        }
+        CommandKind::Export => {
+            CommandAction::Export(parse_export_command(arguments, canonical_name)?)
+        }
+        CommandKind::Move | CommandKind::Copy | CommandKind::Merge => {
+            CommandAction::Subtree(parse_subtree_command(kind, arguments, canonical_name)?)
+        }
+        CommandKind::When => {
+            if arguments.is_empty() {
+                return Err(parse_error(
+                    canonical_name,
+                    "expression",
+                    "日時表現が必要です",
+                    WHEN_USAGE,
+                ));
+            }
+            CommandAction::TimeExpression {
+                kind,
+                canonical_name,
+                values: arguments.to_vec(),
+            }
+        }
+        // 樹 経 は同じ木に臨界経路と余裕を添えて表示する
+        CommandKind::Tree => match arguments.first().map(String::as_str) {
+            None => CommandAction::NoArguments {
+                kind,
+                canonical_name,
+            },
+            Some("経" | "path" | "critical") => CommandAction::CriticalPath,
+            Some(_) => {
+                return Err(parse_error(
+                    "樹",
+                    "mode",
+                    "経 を指定すると臨界経路を表示します",
+                    "樹 [経]",
+                ))
+            }
+        },
+        // 黒 同 はproject noteをvaultと同期する
+        CommandKind::Obsidian => match arguments {
+            [] => CommandAction::NoArguments {
+                kind,
+                canonical_name,
+            },
+            [mode] if mode == "同" || mode == "sync" => CommandAction::ObsidianSync,
+            _ => {
+                return Err(parse_error(
+                    "黒",
+                    "mode",
+                    "同 を指定するとproject noteを同期します",
+                    "黒 [同]",
+                ))
+            }
+        },
+        // 検証 --fix は安全に直せる食い違いだけを直す
+        CommandKind::Verify => match arguments {
+            [] => CommandAction::Verify { fix: false },
+            [mode] if mode == "--fix" || mode == "直" => CommandAction::Verify { fix: true },
+            _ => {
+                return Err(parse_error(
+                    "検証",
+                    "mode",
+                    "--fix を指定すると直せる食い違いを直します",
+                    "検証 [--fix]",
+                ))
+            }
+        },
+        CommandKind::Lock => match arguments {
+            [] => CommandAction::Lock(LockCommand::Status),
+            [mode] if mode == "状" || mode == "status" => CommandAction::Lock(LockCommand::Status),
+            _ => {
+                return Err(parse_error(
+                    "錠",
+                    "mode",
+                    "状 で保持者を表示します",
+                    "錠 [状]",
+                ))
+            }
+        },
+        CommandKind::Gantt => parse_gantt_command(arguments, canonical_name)?,
+        CommandKind::Ancestor
         | CommandKind::Root
         | CommandKind::Leaves
         | CommandKind::Today
@@ -615,16 +882,14 @@ fn parse_action(
         | CommandKind::Calendar
         | CommandKind::Band
         | CommandKind::Open
-        | CommandKind::Obsidian
         | CommandKind::Unfocus
         | CommandKind::Parent
         | CommandKind::Children
         | CommandKind::Deepest
-        | CommandKind::Wait
         | CommandKind::DeferRoutines
         | CommandKind::Flatten
         | CommandKind::Pack
-        | CommandKind::Verify => CommandAction::NoArguments {
+        | CommandKind::AtRisk => CommandAction::NoArguments {
             kind,
             canonical_name,
         },
@@ -637,6 +902,399 @@ fn parse_action(
     Ok(Command::Action(action))
 }
 
+const REVISION_USAGE: &str = "版 [差 <版> [版] | 戻 <版> [project]]";
+
+fn parse_revision_command(
+    arguments: &[String],
+    canonical_name: &'static str,
+) -> Result<RevisionCommand, CommandParseError> {
+    match arguments.first().map(String::as_str) {
+        None => Ok(RevisionCommand::List),
+        Some("差" | "diff") => {
+            require_count(&arguments[1..], 1, 2, canonical_name, REVISION_USAGE)?;
+            Ok(RevisionCommand::Diff {
+                from: arguments[1].clone(),
+                until: arguments.get(2).cloned(),
+            })
+        }
+        Some("戻" | "restore") => {
+            require_count(&arguments[1..], 1, 2, canonical_name, REVISION_USAGE)?;
+            Ok(RevisionCommand::Restore {
+                revision: arguments[1].clone(),
+                project: arguments.get(2).cloned(),
+            })
+        }
+        Some(_) => Err(parse_error(
+            canonical_name,
+            "subcommand",
+            "差または戻を指定してください",
+            REVISION_USAGE,
+        )),
+    }
+}
+
+const GANTT_USAGE: &str = "図 [日数] [svg|html]";
+// 週の計画に使うので、既定では今日から7日分を描く
+const GANTT_DEFAULT_DAYS: u64 = 7;
+const GANTT_MAX_DAYS: u64 = 62;
+
+fn parse_gantt_command(
+    arguments: &[String],
+    canonical_name: &'static str,
+) -> Result<CommandAction, CommandParseError> {
+    require_count(arguments, 0, 2, canonical_name, GANTT_USAGE)?;
+    let mut days = None;
+    let mut format = None;
+    for argument in arguments {
+        match argument.to_lowercase().as_str() {
+            "svg" if format.is_none() => format = Some(GanttFormat::Svg),
+            "html" if format.is_none() => format = Some(GanttFormat::Html),
+            value
+                if days.is_none() && value.chars().all(|character| character.is_ascii_digit()) =>
+            {
+                days = Some(
+                    value
+                        .parse::<u64>()
+                        .ok()
+                        .filter(|days| (1..=GANTT_MAX_DAYS).contains(days))
+                        .ok_or_else(|| {
+                            parse_error(
+                                canonical_name,
+                                "days",
+                                "1から62までの整数で指定してください",
+                                GANTT_USAGE,
+                            )
+                        })?,
+                )
+            }
+            _ => {
+                return Err(parse_error(
+                    canonical_name,
+                    "arguments",
+                    "日数とsvgまたはhtmlを指定してください",
+                    GANTT_USAGE,
+                ))
+            }
+        }
+    }
+    Ok(CommandAction::Gantt {
+        days: days.unwrap_or(GANTT_DEFAULT_DAYS),
+        format: format.unwrap_or(GanttFormat::Html),
+    })
+}
+
+pub(super) const WHEN_USAGE: &str = "時 <日時表現>";
+const TRASH_USAGE: &str = "捨 [task_id | 一覧 | 戻 <記録> | 消 <記録>]";
+
+fn parse_trash_command(
+    arguments: &[String],
+    canonical_name: &'static str,
+) -> Result<TrashCommand, CommandParseError> {
+    match arguments.first().map(String::as_str) {
+        None => Ok(TrashCommand::Delete { task_id: None }),
+        Some("一覧" | "list" | "ls") => {
+            require_count(&arguments[1..], 0, 0, canonical_name, TRASH_USAGE)?;
+            Ok(TrashCommand::List)
+        }
+        Some("戻" | "restore") => {
+            require_count(&arguments[1..], 1, 1, canonical_name, TRASH_USAGE)?;
+            Ok(TrashCommand::Restore {
+                entry: arguments[1].clone(),
+            })
+        }
+        Some("消" | "purge") => {
+            require_count(&arguments[1..], 1, 1, canonical_name, TRASH_USAGE)?;
+            Ok(TrashCommand::Purge {
+                entry: arguments[1].clone(),
+            })
+        }
+        Some(value) => {
+            require_count(arguments, 1, 1, canonical_name, TRASH_USAGE)?;
+            let task_id = Uuid::parse_str(value).map_err(|_| {
+                parse_error(
+                    canonical_name,
+                    "task_id",
+                    "UUIDまたは一覧・戻・消を指定してください",
+                    TRASH_USAGE,
+                )
+            })?;
+            Ok(TrashCommand::Delete {
+                task_id: Some(task_id),
+            })
+        }
+    }
+}
+
+const MOVE_USAGE: &str = "移 <親task_id | 根>";
+const COPY_USAGE: &str = "複 <親task_id | 根>";
+const MERGE_USAGE: &str = "併 <兄弟task_id>";
+
+fn parse_subtree_command(
+    kind: CommandKind,
+    arguments: &[String],
+    canonical_name: &'static str,
+) -> Result<SubtreeCommand, CommandParseError> {
+    let usage = match kind {
+        CommandKind::Move => MOVE_USAGE,
+        CommandKind::Copy => COPY_USAGE,
+        _ => MERGE_USAGE,
+    };
+    require_count(arguments, 1, 1, canonical_name, usage)?;
+    let value = arguments[0].as_str();
+    let parse_task_id = |reason| {
+        Uuid::parse_str(value).map_err(|_| parse_error(canonical_name, "task_id", reason, usage))
+    };
+    let parse_destination = || match value {
+        "根" | "root" => Ok(None),
+        _ => parse_task_id("UUIDまたは根を指定してください").map(Some),
+    };
+    Ok(match kind {
+        CommandKind::Move => SubtreeCommand::Move {
+            parent_id: parse_destination()?,
+        },
+        CommandKind::Copy => SubtreeCommand::Copy {
+            parent_id: parse_destination()?,
+        },
+        _ => SubtreeCommand::Merge {
+            source_task_id: parse_task_id("UUIDで指定してください")?,
+        },
+    })
+}
+
+pub(super) const WAIT_USAGE: &str =
+    "待 [<相手> [返答予定の日時表現] [催促間隔日数] | 催 | 消 | 一覧]";
+
+fn parse_wait_command(
+    arguments: &[String],
+    canonical_name: &'static str,
+) -> Result<WaitCommand, CommandParseError> {
+    match arguments.first().map(String::as_str) {
+        None => Ok(WaitCommand::Mark),
+        Some("一覧" | "list" | "ls") => {
+            require_count(&arguments[1..], 0, 0, canonical_name, WAIT_USAGE)?;
+            Ok(WaitCommand::List)
+        }
+        Some("催" | "nudge") => {
+            require_count(&arguments[1..], 0, 0, canonical_name, WAIT_USAGE)?;
+            Ok(WaitCommand::FollowUp)
+        }
+        Some("消" | "clear") => {
+            require_count(&arguments[1..], 0, 0, canonical_name, WAIT_USAGE)?;
+            Ok(WaitCommand::Clear)
+        }
+        Some(counterpart) => {
+            let mut rest = &arguments[1..];
+            // 末尾の整数は催促間隔とし、残りを返答予定の日時表現として読む
+            let follow_up_interval_days = match rest.split_last() {
+                Some((last, init)) if last.chars().all(|character| character.is_ascii_digit()) => {
+                    rest = init;
+                    let days = parse_i64(
+                        last,
+                        canonical_name,
+                        "follow_up_interval_days",
+                        "1以上の整数で指定してください",
+                        WAIT_USAGE,
+                    )?;
+                    if days < 1 {
+                        return Err(parse_error(
+                            canonical_name,
+                            "follow_up_interval_days",
+                            "1以上の整数で指定してください",
+                            WAIT_USAGE,
+                        ));
+                    }
+                    Some(days)
+                }
+                _ => None,
+            };
+            let expected_reply = if rest.is_empty() {
+                None
+            } else {
+                let tokens = rest.iter().map(String::as_str).collect::<Vec<_>>();
+                Some(TemporalExpression::parse(&tokens).map_err(|_| {
+                    parse_error(
+                        canonical_name,
+                        "expected_reply_date",
+                        "日時表現として解釈できません",
+                        WAIT_USAGE,
+                    )
+                })?)
+            };
+            Ok(WaitCommand::Start {
+                counterpart: counterpart.to_string(),
+                expected_reply,
+                follow_up_interval_days,
+            })
+        }
+    }
+}
+
+pub(super) const WINDOW_USAGE: &str = "枠 [<曜日> <HH:MM-HH:MM>[,...] | 消]";
+
+fn parse_window_command(
+    arguments: &[String],
+    canonical_name: &'static str,
+) -> Result<WindowCommand, CommandParseError> {
+    match arguments {
+        [] => Ok(WindowCommand::Show),
+        [clear] if matches!(clear.as_str(), "消" | "clear") => Ok(WindowCommand::Clear),
+        _ => AllowedWindow::parse(&arguments.join(" "))
+            .map(WindowCommand::Set)
+            .map_err(|_| {
+                parse_error(
+                    canonical_name,
+                    "allowed_window",
+                    "曜日と時間帯として解釈できません",
+                    WINDOW_USAGE,
+                )
+            }),
+    }
+}
+
+pub(super) const BUSY_BLOCK_USAGE: &str =
+    "埋 [一覧 | <日付> <HH:MM-HH:MM> <名前> | <日付>[~<日付>] <名前> | 消 <記録>]";
+
+// 2つ目が時間帯なら時刻を決めた予定、そうでなければ日付の範囲を終日塞ぐ
+fn parse_busy_block_command(
+    arguments: &[String],
+    canonical_name: &'static str,
+) -> Result<BusyBlockCommand, CommandParseError> {
+    let parse_date = |value: &str, field| match TemporalExpression::parse_str(value) {
+        Ok(TemporalExpression {
+            date: Some(date),
+            time: None,
+        }) => Ok(date),
+        _ => Err(parse_error(
+            canonical_name,
+            field,
+            "日付として解釈できません",
+            BUSY_BLOCK_USAGE,
+        )),
+    };
+    match arguments.first().map(String::as_str) {
+        None => Ok(BusyBlockCommand::List),
+        Some("一覧" | "list" | "ls") => {
+            require_count(&arguments[1..], 0, 0, canonical_name, BUSY_BLOCK_USAGE)?;
+            Ok(BusyBlockCommand::List)
+        }
+        Some("消" | "remove" | "rm") => {
+            require_count(&arguments[1..], 1, 1, canonical_name, BUSY_BLOCK_USAGE)?;
+            Ok(BusyBlockCommand::Remove {
+                block: arguments[1].clone(),
+            })
+        }
+        Some(first) => {
+            require_count(arguments, 2, usize::MAX, canonical_name, BUSY_BLOCK_USAGE)?;
+            if let Some((start, end)) = arguments[1]
+                .split_once('-')
+                .filter(|_| arguments[1].contains(':'))
+            {
+                require_count(arguments, 3, usize::MAX, canonical_name, BUSY_BLOCK_USAGE)?;
+                let parse_time = |value: &str| match TemporalExpression::parse_str(value) {
+                    Ok(TemporalExpression {
+                        date: None,
+                        time: Some(time),
+                    }) => Ok(time),
+                    _ => Err(parse_error(
+                        canonical_name,
+                        "time_range",
+                        "HH:MM-HH:MMの時間帯として解釈できません",
+                        BUSY_BLOCK_USAGE,
+                    )),
+                };
+                return Ok(BusyBlockCommand::AddTimed {
+                    date: parse_date(first, "date")?,
+                    start: parse_time(start)?,
+                    end: parse_time(end)?,
+                    name: arguments[2..].join(" "),
+                });
+            }
+            let (start_date, end_date) = match first.split_once(['~', '〜']) {
+                Some((start_date, end_date)) => (
+                    parse_date(start_date, "start_date")?,
+                    Some(parse_date(end_date, "end_date")?),
+                ),
+                None => (parse_date(first, "start_date")?, None),
+            };
+            Ok(BusyBlockCommand::AddAllDay {
+                start_date,
+                end_date,
+                name: arguments[1..].join(" "),
+            })
+        }
+    }
+}
+
+pub(super) const IMPORT_USAGE: &str = "取 <ファイル(.md|.org|.txt)> [下] [確定]";
+
+fn parse_import_command(
+    arguments: &[String],
+    canonical_name: &'static str,
+) -> Result<ImportCommand, CommandParseError> {
+    require_count(arguments, 1, 3, canonical_name, IMPORT_USAGE)?;
+    let mut command = ImportCommand {
+        path: arguments[0].clone(),
+        under_focus: false,
+        commit: false,
+    };
+    for option in &arguments[1..] {
+        let flag = match option.as_str() {
+            "下" | "under" => &mut command.under_focus,
+            "確定" | "apply" => &mut command.commit,
+            _ => {
+                return Err(parse_error(
+                    canonical_name,
+                    "option",
+                    "下か確定を指定してください",
+                    IMPORT_USAGE,
+                ))
+            }
+        };
+        if std::mem::replace(flag, true) {
+            return Err(parse_error(
+                canonical_name,
+                "option",
+                "同じ指定を繰り返せません",
+                IMPORT_USAGE,
+            ));
+        }
+    }
+    Ok(command)
+}
+
+pub(super) const EXPORT_USAGE: &str = "出 [md|opml] [ファイル]";
+
+fn parse_export_command(
+    arguments: &[String],
+    canonical_name: &'static str,
+) -> Result<ExportCommand, CommandParseError> {
+    require_count(arguments, 0, 2, canonical_name, EXPORT_USAGE)?;
+    let mut command = ExportCommand {
+        format: None,
+        path: None,
+    };
+    for argument in arguments {
+        let format = match argument.to_lowercase().as_str() {
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            "opml" => Some(ExportFormat::Opml),
+            _ => None,
+        };
+        match format {
+            Some(format) if command.format.is_none() => command.format = Some(format),
+            None if command.path.is_none() => command.path = Some(argument.clone()),
+            _ => {
+                return Err(parse_error(
+                    canonical_name,
+                    "arguments",
+                    "形式とファイルを1つずつ指定してください",
+                    EXPORT_USAGE,
+                ))
+            }
+        }
+    }
+    Ok(command)
+}
+
 fn required_argument<'a>(
     arguments: &'a [String],
     command: &'static str,
@@ -760,6 +1418,21 @@ fn known_command(name: &str) -> Option<(CommandKind, &'static str)> {
         "高" | "high" | "hi" | "highest" => (CommandKind::FocusHighest, "高"),
         "低" | "low" | "lo" | "lowest" => (CommandKind::FocusLowest, "低"),
         "検証" => (CommandKind::Verify, "検証"),
+        "履" | "history" => (CommandKind::LoadHistory, "履"),
+        "流" | "flow" | "metrics" => (CommandKind::FlowMetrics, "流"),
+        "版" | "revision" | "rev" => (CommandKind::Revision, "版"),
+        "捨" | "trash" | "delete" | "del" => (CommandKind::Trash, "捨"),
+        "時" | "when" => (CommandKind::When, "時"),
+        "危" | "risk" | "at_risk" => (CommandKind::AtRisk, "危"),
+        "図" | "gantt" => (CommandKind::Gantt, "図"),
+        "移" | "move" | "mv" => (CommandKind::Move, "移"),
+        "複" | "copy" | "cp" => (CommandKind::Copy, "複"),
+        "併" | "merge" => (CommandKind::Merge, "併"),
+        "枠" | "window" | "win" => (CommandKind::Window, "枠"),
+        "埋" | "busy" | "block" => (CommandKind::BusyBlock, "埋"),
+        "取" | "import" => (CommandKind::Import, "取"),
+        "出" | "export" => (CommandKind::Export, "出"),
+        "錠" | "lock" => (CommandKind::Lock, "錠"),
         _ => return None,
     };
     Some(command)
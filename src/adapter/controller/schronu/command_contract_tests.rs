@@ -1,6 +1,11 @@
 use super::command::{
-    parse_command, Command, CommandAction, CommandKind, InteractiveShortcut, ParseMode,
+    parse_command, BusyBlockCommand, Command, CommandAction, CommandKind, ExportCommand,
+    GanttFormat, ImportCommand, InteractiveShortcut, LockCommand, ParseMode, RevisionCommand,
+    SubtreeCommand, TrashCommand, WaitCommand, WindowCommand, EXPORT_USAGE, IMPORT_USAGE,
 };
+use schronu::adapter::gateway::task_export::ExportFormat;
+use schronu::entity::allowed_window::AllowedWindow;
+use schronu::entity::datetime::temporal::TemporalExpression;
 use uuid::Uuid;
 
 #[test]
@@ -55,6 +60,21 @@ fn all_aliases_parse_to_the_same_typed_command_kind() {
         (&["集", "gather"][..], CommandKind::Gather),
         (&["終", "finish", "fin"][..], CommandKind::Finish),
         (&["検証"][..], CommandKind::Verify),
+        (&["履", "history"][..], CommandKind::LoadHistory),
+        (&["流", "flow", "metrics"][..], CommandKind::FlowMetrics),
+        (&["版", "revision", "rev"][..], CommandKind::Revision),
+        (&["捨", "trash", "delete", "del"][..], CommandKind::Trash),
+        (&["埋", "busy", "block"][..], CommandKind::BusyBlock),
+        (&["時", "when"][..], CommandKind::When),
+        (&["危", "risk", "at_risk"][..], CommandKind::AtRisk),
+        (&["図", "gantt"][..], CommandKind::Gantt),
+        (&["移", "move", "mv"][..], CommandKind::Move),
+        (&["複", "copy", "cp"][..], CommandKind::Copy),
+        (&["併", "merge"][..], CommandKind::Merge),
+        (&["枠", "window", "win"][..], CommandKind::Window),
+        (&["取", "import"][..], CommandKind::Import),
+        (&["出", "export"][..], CommandKind::Export),
+        (&["錠", "lock"][..], CommandKind::Lock),
     ];
 
     for (names, expected) in aliases {
@@ -250,6 +270,433 @@ fn extrude_without_an_argument_is_noop_and_invalid_days_fall_back_to_one() {
     );
 }
 
+#[test]
+fn revision_subcommands_keep_revision_references_as_text() {
+    assert_eq!(
+        parse_command("版", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Revision(RevisionCommand::List))
+    );
+    assert_eq!(
+        parse_command("rev diff abcd", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Revision(RevisionCommand::Diff {
+            from: "abcd".to_string(),
+            until: None,
+        }))
+    );
+    assert_eq!(
+        parse_command("版 戻 abcd 20261018-a", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::Revision(RevisionCommand::Restore {
+            revision: "abcd".to_string(),
+            project: Some("20261018-a".to_string()),
+        }))
+    );
+
+    for input in ["版 差", "版 戻 a b c", "版 消 abcd"] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), "版", "{input}");
+        assert_eq!(error.usage(), "版 [差 <版> [版] | 戻 <版> [project]]");
+    }
+}
+
+#[test]
+fn trash_subcommands_parse_task_ids_and_keep_entry_references_as_text() {
+    assert_eq!(
+        parse_command("捨", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Trash(TrashCommand::Delete { task_id: None }))
+    );
+    let task_id = Uuid::new_v4();
+    assert_eq!(
+        parse_command(&format!("del {task_id}"), ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Trash(TrashCommand::Delete {
+            task_id: Some(task_id),
+        }))
+    );
+    assert_eq!(
+        parse_command("捨 一覧", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::Trash(TrashCommand::List))
+    );
+    assert_eq!(
+        parse_command("trash restore abcd", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Trash(TrashCommand::Restore {
+            entry: "abcd".to_string(),
+        }))
+    );
+    assert_eq!(
+        parse_command("捨 消 abcd", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Trash(TrashCommand::Purge {
+            entry: "abcd".to_string(),
+        }))
+    );
+
+    for input in ["捨 戻", "捨 消 a b", "捨 一覧 a", "捨 abcd"] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), "捨", "{input}");
+        assert_eq!(error.usage(), "捨 [task_id | 一覧 | 戻 <記録> | 消 <記録>]");
+    }
+}
+
+#[test]
+fn 埋は時間帯つきなら時刻指定の予定に日付だけなら終日の予定に解釈する() {
+    let date = |value: &str| TemporalExpression::parse_str(value).unwrap().date.unwrap();
+    let time = |value: &str| TemporalExpression::parse_str(value).unwrap().time.unwrap();
+    assert_eq!(
+        parse_command("埋", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::BusyBlock(BusyBlockCommand::List))
+    );
+    assert_eq!(
+        parse_command(
+            "埋 10/22 14:00-15:30 歯医者 定期",
+            ParseMode::NonInteractive
+        )
+        .unwrap(),
+        Command::Action(CommandAction::BusyBlock(BusyBlockCommand::AddTimed {
+            date: date("10/22"),
+            start: time("14:00"),
+            end: time("15:30"),
+            name: "歯医者 定期".to_string(),
+        }))
+    );
+    assert_eq!(
+        parse_command("busy 8/10~8/14 夏休み", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::BusyBlock(BusyBlockCommand::AddAllDay {
+            start_date: date("8/10"),
+            end_date: Some(date("8/14")),
+            name: "夏休み".to_string(),
+        }))
+    );
+    assert_eq!(
+        parse_command("埋 消 abcd", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::BusyBlock(BusyBlockCommand::Remove {
+            block: "abcd".to_string(),
+        }))
+    );
+
+    for (input, field) in [
+        ("埋 明日", "arguments"),
+        ("埋 10/22 14:00-xx 歯医者", "time_range"),
+        ("埋 10/22 14:00-15:00", "arguments"),
+        ("埋 8/10~だめ 夏休み", "end_date"),
+    ] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), "埋", "{input}");
+        assert_eq!(error.field(), field, "{input}");
+    }
+}
+
+#[test]
+fn 移と複は親のtask_idか根を受け取り併は兄弟のtask_idを受け取る() {
+    let task_id = Uuid::new_v4();
+    assert_eq!(
+        parse_command(&format!("移 {task_id}"), ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Subtree(SubtreeCommand::Move {
+            parent_id: Some(task_id),
+        }))
+    );
+    assert_eq!(
+        parse_command("copy root", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::Subtree(SubtreeCommand::Copy {
+            parent_id: None
+        }))
+    );
+    assert_eq!(
+        parse_command(&format!("併 {task_id}"), ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Subtree(SubtreeCommand::Merge {
+            source_task_id: task_id,
+        }))
+    );
+
+    for (input, command, field, usage) in [
+        ("移", "移", "arguments", "移 <親task_id | 根>"),
+        ("複 abcd", "複", "task_id", "複 <親task_id | 根>"),
+        ("併 根", "併", "task_id", "併 <兄弟task_id>"),
+    ] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), command, "{input}");
+        assert_eq!(error.field(), field, "{input}");
+        assert_eq!(error.usage(), usage, "{input}");
+    }
+}
+
+#[test]
+fn 取はファイルと下と確定を順不同で受け取り既定は確認だけにする() {
+    assert_eq!(
+        parse_command("取 plan.md", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Import(ImportCommand {
+            path: "plan.md".to_string(),
+            under_focus: false,
+            commit: false,
+        }))
+    );
+    assert_eq!(
+        parse_command("import todo.txt apply under", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::Import(ImportCommand {
+            path: "todo.txt".to_string(),
+            under_focus: true,
+            commit: true,
+        }))
+    );
+
+    for (input, field) in [
+        ("取", "arguments"),
+        ("取 plan.md 上", "option"),
+        ("取 plan.md 確定 確定", "option"),
+    ] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), "取", "{input}");
+        assert_eq!(error.field(), field, "{input}");
+        assert_eq!(error.usage(), IMPORT_USAGE, "{input}");
+    }
+}
+
+#[test]
+fn 時と〆は複数語の日時表現をまとめて受け取る() {
+    assert_eq!(
+        parse_command("時 明 25:30", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::TimeExpression {
+            kind: CommandKind::When,
+            canonical_name: "時",
+            values: vec!["明".to_string(), "25:30".to_string()],
+        })
+    );
+    assert_eq!(
+        parse_command("〆 金 19:00", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::StringValue {
+            kind: CommandKind::Deadline,
+            canonical_name: "〆",
+            value: "金 19:00".to_string(),
+        })
+    );
+
+    let error = parse_command("when", ParseMode::NonInteractive).unwrap_err();
+    assert_eq!(error.command(), "時");
+    assert_eq!(error.usage(), "時 <日時表現>");
+}
+
+#[test]
+fn 待は相手と返答予定と催促間隔を受け取る() {
+    assert_eq!(
+        parse_command("待", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::Wait(WaitCommand::Mark))
+    );
+    assert_eq!(
+        parse_command("待 田中 金 3", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Wait(WaitCommand::Start {
+            counterpart: "田中".to_string(),
+            expected_reply: Some(TemporalExpression::parse(&["金"]).unwrap()),
+            follow_up_interval_days: Some(3),
+        }))
+    );
+    assert_eq!(
+        parse_command("wait 田中", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Wait(WaitCommand::Start {
+            counterpart: "田中".to_string(),
+            expected_reply: None,
+            follow_up_interval_days: None,
+        }))
+    );
+    assert_eq!(
+        parse_command("待 一覧", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Wait(WaitCommand::List))
+    );
+    assert_eq!(
+        parse_command("待 催", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::Wait(WaitCommand::FollowUp))
+    );
+    assert_eq!(
+        parse_command("wait clear", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::Wait(WaitCommand::Clear))
+    );
+
+    for (input, field) in [
+        ("待 田中 0", "follow_up_interval_days"),
+        ("待 田中 そのうち", "expected_reply_date"),
+        ("待 一覧 田中", "arguments"),
+    ] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), "待", "{input}");
+        assert_eq!(error.field(), field, "{input}");
+        assert_eq!(
+            error.usage(),
+            "待 [<相手> [返答予定の日時表現] [催促間隔日数] | 催 | 消 | 一覧]"
+        );
+    }
+}
+
+#[test]
+fn 枠は曜日と時間帯を検証して受け取る() {
+    assert_eq!(
+        parse_command("枠", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::Window(WindowCommand::Show))
+    );
+    assert_eq!(
+        parse_command("枠 平日 09:00-12:00,13:00-18:00", ParseMode::NonInteractive).unwrap(),
+        Command::Action(CommandAction::Window(WindowCommand::Set(
+            AllowedWindow::parse("月火水木金 09:00-12:00,13:00-18:00").unwrap()
+        )))
+    );
+    assert_eq!(
+        parse_command("window clear", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::Window(WindowCommand::Clear))
+    );
+
+    for input in ["枠 朝", "枠 18:00-09:00"] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), "枠", "{input}");
+        assert_eq!(error.field(), "allowed_window", "{input}");
+        assert_eq!(error.usage(), "枠 [<曜日> <HH:MM-HH:MM>[,...] | 消]");
+    }
+}
+
+#[test]
+fn 樹は経を指定すると臨界経路の表示になる() {
+    assert_eq!(
+        parse_command("樹", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::NoArguments {
+            kind: CommandKind::Tree,
+            canonical_name: "樹",
+        })
+    );
+    for input in ["樹 経", "tree path", "樹 critical"] {
+        assert_eq!(
+            parse_command(input, ParseMode::Interactive).unwrap(),
+            Command::Action(CommandAction::CriticalPath),
+            "{input}"
+        );
+    }
+
+    let error = parse_command("樹 葉", ParseMode::Interactive).unwrap_err();
+    assert_eq!(error.command(), "樹");
+    assert_eq!(error.field(), "mode");
+    assert_eq!(error.usage(), "樹 [経]");
+}
+
+#[test]
+fn 図は日数と書き出す形式を受け取る() {
+    for (input, days, format) in [
+        ("図", 7, GanttFormat::Html),
+        ("図 14", 14, GanttFormat::Html),
+        ("gantt svg", 7, GanttFormat::Svg),
+        ("図 3 SVG", 3, GanttFormat::Svg),
+        ("図 html 5", 5, GanttFormat::Html),
+    ] {
+        assert_eq!(
+            parse_command(input, ParseMode::Interactive).unwrap(),
+            Command::Action(CommandAction::Gantt { days, format }),
+            "{input}"
+        );
+    }
+
+    for (input, field) in [
+        ("図 0", "days"),
+        ("図 63", "days"),
+        ("図 png", "arguments"),
+        ("図 svg html", "arguments"),
+        ("図 7 svg 1", "arguments"),
+    ] {
+        let error = parse_command(input, ParseMode::Interactive).unwrap_err();
+        assert_eq!(error.command(), "図", "{input}");
+        assert_eq!(error.field(), field, "{input}");
+        assert_eq!(error.usage(), "図 [日数] [svg|html]", "{input}");
+    }
+}
+
+#[test]
+fn 出は形式とファイルを順不同で受け取り省略できる() {
+    for (input, format, path) in [
+        ("出", None, None),
+        ("出 opml", Some(ExportFormat::Opml), None),
+        (
+            "export out.md MD",
+            Some(ExportFormat::Markdown),
+            Some("out.md"),
+        ),
+        ("出 plan.opml", None, Some("plan.opml")),
+    ] {
+        assert_eq!(
+            parse_command(input, ParseMode::NonInteractive).unwrap(),
+            Command::Action(CommandAction::Export(ExportCommand {
+                format,
+                path: path.map(str::to_string),
+            })),
+            "{input}"
+        );
+    }
+
+    for (input, field) in [
+        ("出 md opml", "arguments"),
+        ("出 a.md b.md", "arguments"),
+        ("出 md a.md b.md", "arguments"),
+    ] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), "出", "{input}");
+        assert_eq!(error.field(), field, "{input}");
+        assert_eq!(error.usage(), EXPORT_USAGE, "{input}");
+    }
+}
+
+#[test]
+fn 黒は同でproject_noteの同期になり他の引数は受け付けない() {
+    assert_eq!(
+        parse_command("黒", ParseMode::Interactive).unwrap(),
+        Command::Action(CommandAction::NoArguments {
+            kind: CommandKind::Obsidian,
+            canonical_name: "黒",
+        })
+    );
+    for input in ["黒 同", "obs sync"] {
+        assert_eq!(
+            parse_command(input, ParseMode::NonInteractive).unwrap(),
+            Command::Action(CommandAction::ObsidianSync),
+            "{input}"
+        );
+    }
+
+    for input in ["黒 x", "黒 同 同"] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), "黒", "{input}");
+        assert_eq!(error.usage(), "黒 [同]", "{input}");
+    }
+}
+
+#[test]
+fn 検証は_fixか直で直す指定を受け取る() {
+    for (input, fix) in [("検証", false), ("検証 --fix", true), ("検証 直", true)] {
+        assert_eq!(
+            parse_command(input, ParseMode::NonInteractive).unwrap(),
+            Command::Action(CommandAction::Verify { fix }),
+            "{input}"
+        );
+    }
+
+    for input in ["検証 fix", "検証 --fix --fix"] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), "検証", "{input}");
+        assert_eq!(error.field(), "mode", "{input}");
+        assert_eq!(error.usage(), "検証 [--fix]", "{input}");
+    }
+}
+
+#[test]
+fn 錠は状で保持者を表示する() {
+    for (input, lock_command) in [
+        ("錠", LockCommand::Status),
+        ("錠 状", LockCommand::Status),
+        ("lock status", LockCommand::Status),
+    ] {
+        assert_eq!(
+            parse_command(input, ParseMode::NonInteractive).unwrap(),
+            Command::Action(CommandAction::Lock(lock_command)),
+            "{input}"
+        );
+    }
+
+    for input in ["錠 x", "錠 譲", "錠 状 状"] {
+        let error = parse_command(input, ParseMode::NonInteractive).unwrap_err();
+        assert_eq!(error.command(), "錠", "{input}");
+        assert_eq!(error.usage(), "錠 [状]", "{input}");
+    }
+}
+
 #[test]
 fn runtime_routes_both_product_entry_paths_through_the_shared_parser() {
     let source = include_str!("runtime.rs");
@@ -273,6 +720,10 @@ fn command_with_minimum_valid_arguments(command: &str) -> String {
         "後" | "defer" | "逃" | "escape" | "esc" => " 1 秒",
         "空" | "clear" | "集" | "gather" => " 明",
         "終" | "finish" | "fin" => " 今",
+        "時" | "when" => " 明",
+        "移" | "move" | "mv" | "複" | "copy" | "cp" => " 根",
+        "併" | "merge" => " 00000000-0000-0000-0000-000000000001",
+        "取" | "import" => " plan.md",
         _ => "",
     };
     format!("{command}{arguments}")
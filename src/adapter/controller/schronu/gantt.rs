@@ -0,0 +1,430 @@
+use super::renderer::project_category_symbol;
+use chrono::{DateTime, Datelike, Duration, Local};
+use schronu::application::gantt::{GanttChartView, GanttLaneView, GanttSegmentView};
+use schronu::entity::task::ProjectCategory;
+use std::fmt::Write as _;
+use unicode_width::UnicodeWidthChar;
+
+const LABEL_WIDTH: i64 = 96;
+const HOUR_WIDTH: i64 = 40;
+const HEADER_HEIGHT: i64 = 44;
+const LANE_HEIGHT: i64 = 40;
+const LANE_PADDING: i64 = 5;
+const LEGEND_HEIGHT: i64 = 32;
+const FONT_SIZE: i64 = 11;
+// 半角1文字ぶんの幅。全角は2文字ぶんとして名前を切り詰める
+const HALF_WIDTH_CHAR_PIXELS: i64 = 6;
+
+const WAITING_FILL: &str = "#d0d0d0";
+const WAITING_TEXT: &str = "#707070";
+const BUSY_FILL: &str = "#9e9e9e";
+const DEADLINE_STROKE: &str = "#d62728";
+
+const CATEGORIES: [Option<ProjectCategory>; 6] = [
+    Some(ProjectCategory::Earning),
+    Some(ProjectCategory::Sustaining),
+    Some(ProjectCategory::Recovery),
+    Some(ProjectCategory::Investment),
+    Some(ProjectCategory::Consumption),
+    None,
+];
+
+// 端末の類の記号と同じ分類で塗り分ける
+fn project_category_color(project_category_opt: Option<ProjectCategory>) -> &'static str {
+    match project_category_opt {
+        Some(ProjectCategory::Earning) => "#4e79a7",
+        Some(ProjectCategory::Sustaining) => "#59a14f",
+        Some(ProjectCategory::Recovery) => "#76b7b2",
+        Some(ProjectCategory::Investment) => "#f28e2b",
+        Some(ProjectCategory::Consumption) => "#e15759",
+        None => "#b07aa1",
+    }
+}
+
+// 日ごとのlaneを縦に並べ、横軸を主観日の開始からの時刻にする。
+// 埋まっている時間は灰色の帯、〆切は赤い縦線、待ちのtaskは灰色の破線枠で描く
+pub(super) fn render_gantt_svg(chart: &GanttChartView) -> String {
+    let chart_minutes = chart
+        .lanes
+        .iter()
+        .map(|lane| (lane.end - lane.start).num_minutes())
+        .max()
+        .unwrap_or(24 * 60);
+    let hours = (chart_minutes + 59) / 60;
+    let width = LABEL_WIDTH + hours * HOUR_WIDTH + LANE_PADDING * 2;
+    let lanes_bottom = HEADER_HEIGHT + chart.lanes.len() as i64 * LANE_HEIGHT;
+    let height = lanes_bottom + LEGEND_HEIGHT;
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="{FONT_SIZE}">"#
+    )
+    .expect("writing to String is infallible");
+    writeln!(
+        svg,
+        r##"<rect width="{width}" height="{height}" fill="#ffffff"/>"##
+    )
+    .expect("writing to String is infallible");
+    writeln!(
+        svg,
+        r#"<text x="{LANE_PADDING}" y="16" font-size="13" font-weight="bold">{}</text>"#,
+        escape_xml(&chart_title(chart))
+    )
+    .expect("writing to String is infallible");
+
+    if let Some(first_lane) = chart.lanes.first() {
+        for hour in 0..=hours {
+            let x = LABEL_WIDTH + hour * HOUR_WIDTH;
+            writeln!(
+                svg,
+                r##"<line x1="{x}" y1="{}" x2="{x}" y2="{lanes_bottom}" stroke="#e0e0e0"/>"##,
+                HEADER_HEIGHT - 6
+            )
+            .expect("writing to String is infallible");
+            if hour < hours {
+                writeln!(
+                    svg,
+                    r##"<text x="{}" y="{}" fill="#606060">{}</text>"##,
+                    x + 2,
+                    HEADER_HEIGHT - 10,
+                    (first_lane.start + Duration::hours(hour)).format("%H")
+                )
+                .expect("writing to String is infallible");
+            }
+        }
+    }
+
+    for (index, lane) in chart.lanes.iter().enumerate() {
+        write_lane(
+            &mut svg,
+            lane,
+            HEADER_HEIGHT + index as i64 * LANE_HEIGHT,
+            hours * HOUR_WIDTH,
+        );
+    }
+    write_legend(&mut svg, lanes_bottom + 8);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+pub(super) fn render_gantt_html(chart: &GanttChartView) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>body {{ margin: 16px; font-family: sans-serif; }} svg {{ max-width: 100%; height: auto; }}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_xml(&chart_title(chart)),
+        render_gantt_svg(chart)
+    )
+}
+
+fn chart_title(chart: &GanttChartView) -> String {
+    let range = match (chart.lanes.first(), chart.lanes.last()) {
+        (Some(first), Some(last)) => format!(
+            "{}-{}",
+            first.date.format("%Y/%m/%d"),
+            last.date.format("%m/%d")
+        ),
+        _ => String::new(),
+    };
+    format!(
+        "予定図 {range} (作成 {})",
+        chart.generated_at.format("%Y/%m/%d %H:%M")
+    )
+}
+
+fn write_lane(svg: &mut String, lane: &GanttLaneView, top: i64, lane_width: i64) {
+    let days_of_week = ["月", "火", "水", "木", "金", "土", "日"];
+    let bar_top = top + LANE_PADDING;
+    let bar_height = LANE_HEIGHT - LANE_PADDING * 2;
+    let x_of =
+        |time: DateTime<Local>| LABEL_WIDTH + (time - lane.start).num_minutes() * HOUR_WIDTH / 60;
+
+    writeln!(svg, r#"<g class="lane" data-date="{}">"#, lane.date)
+        .expect("writing to String is infallible");
+    writeln!(
+        svg,
+        r#"<text x="{LANE_PADDING}" y="{}">{}({})</text>"#,
+        top + LANE_HEIGHT / 2 + FONT_SIZE / 2 - 1,
+        lane.date.format("%m/%d"),
+        days_of_week[lane.date.weekday().num_days_from_monday() as usize]
+    )
+    .expect("writing to String is infallible");
+    writeln!(
+        svg,
+        r##"<rect x="{LABEL_WIDTH}" y="{bar_top}" width="{lane_width}" height="{bar_height}" fill="#f7f7f7"/>"##
+    )
+    .expect("writing to String is infallible");
+
+    for busy_slot in &lane.busy_slots {
+        let x = x_of(busy_slot.start);
+        writeln!(
+            svg,
+            r#"<rect class="busy" x="{x}" y="{bar_top}" width="{}" height="{bar_height}" fill="{BUSY_FILL}" fill-opacity="0.35"><title>予定あり {}-{}</title></rect>"#,
+            x_of(busy_slot.end) - x,
+            busy_slot.start.format("%H:%M"),
+            busy_slot.end.format("%H:%M")
+        )
+        .expect("writing to String is infallible");
+    }
+
+    for segment in &lane.segments {
+        write_segment(
+            svg,
+            segment,
+            x_of(segment.start),
+            x_of(segment.end),
+            bar_top,
+            bar_height,
+        );
+    }
+
+    for deadline in &lane.deadlines {
+        let x = x_of(deadline.deadline_time);
+        writeln!(
+            svg,
+            r#"<g class="deadline"><title>〆 {} {}</title><line x1="{x}" y1="{top}" x2="{x}" y2="{}" stroke="{DEADLINE_STROKE}" stroke-width="2"/><path d="M{} {top} L{} {top} L{x} {} Z" fill="{DEADLINE_STROKE}"/></g>"#,
+            deadline.deadline_time.format("%m/%d %H:%M"),
+            escape_xml(&deadline.name),
+            top + LANE_HEIGHT,
+            x - 4,
+            x + 4,
+            top + 6
+        )
+        .expect("writing to String is infallible");
+    }
+    svg.push_str("</g>\n");
+}
+
+fn write_segment(
+    svg: &mut String,
+    segment: &GanttSegmentView,
+    x: i64,
+    x_end: i64,
+    bar_top: i64,
+    bar_height: i64,
+) {
+    let width = (x_end - x).max(1);
+    let waiting = segment.task.waiting.is_some();
+    let (fill, text_fill, stroke) = if waiting {
+        (
+            WAITING_FILL,
+            WAITING_TEXT,
+            r##" stroke="#808080" stroke-dasharray="3 2""##,
+        )
+    } else {
+        (
+            project_category_color(segment.task.project_category),
+            "#ffffff",
+            "",
+        )
+    };
+
+    let mut tooltip = format!(
+        "[{}] {} {}-{}",
+        project_category_symbol(segment.task.project_category),
+        segment.task.name,
+        segment.start.format("%H:%M"),
+        segment.end.format("%H:%M")
+    );
+    if segment.segment_count > 1 {
+        write!(
+            tooltip,
+            " ({}/{})",
+            segment.segment_index, segment.segment_count
+        )
+        .expect("writing to String is infallible");
+    }
+    if let Some(waiting) = &segment.task.waiting {
+        write!(tooltip, " 待: {}", waiting.counterpart).expect("writing to String is infallible");
+    }
+
+    writeln!(
+        svg,
+        r#"<g class="segment{}" data-task-id="{}"><title>{}</title><rect x="{x}" y="{bar_top}" width="{width}" height="{bar_height}" rx="3" fill="{fill}"{stroke}/>"#,
+        if waiting { " waiting" } else { "" },
+        segment.task.id,
+        escape_xml(&tooltip)
+    )
+    .expect("writing to String is infallible");
+    let label = truncate_label(&segment.task.name, width - 6);
+    if !label.is_empty() {
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" fill="{text_fill}">{}</text>"#,
+            x + 3,
+            bar_top + bar_height / 2 + FONT_SIZE / 2 - 1,
+            escape_xml(&label)
+        )
+        .expect("writing to String is infallible");
+    }
+    svg.push_str("</g>\n");
+}
+
+fn write_legend(svg: &mut String, top: i64) {
+    let mut x = LABEL_WIDTH;
+    let text_y = top + FONT_SIZE;
+    for category in CATEGORIES {
+        writeln!(
+            svg,
+            r#"<rect x="{x}" y="{top}" width="12" height="12" rx="2" fill="{}"/><text x="{}" y="{text_y}">{}</text>"#,
+            project_category_color(category),
+            x + 16,
+            project_category_symbol(category)
+        )
+        .expect("writing to String is infallible");
+        x += 40;
+    }
+    writeln!(
+        svg,
+        r##"<rect x="{x}" y="{top}" width="12" height="12" rx="2" fill="{WAITING_FILL}" stroke="#808080" stroke-dasharray="3 2"/><text x="{}" y="{text_y}">待ち</text>"##,
+        x + 16
+    )
+    .expect("writing to String is infallible");
+    x += 56;
+    writeln!(
+        svg,
+        r#"<rect x="{x}" y="{top}" width="12" height="12" fill="{BUSY_FILL}" fill-opacity="0.35"/><text x="{}" y="{text_y}">予定あり</text>"#,
+        x + 16
+    )
+    .expect("writing to String is infallible");
+    x += 80;
+    writeln!(
+        svg,
+        r#"<line x1="{}" y1="{top}" x2="{}" y2="{}" stroke="{DEADLINE_STROKE}" stroke-width="2"/><text x="{}" y="{text_y}">〆切</text>"#,
+        x + 6,
+        x + 6,
+        top + 12,
+        x + 16
+    )
+    .expect("writing to String is infallible");
+}
+
+// 枠に収まる分だけ名前を残し、切ったときは末尾を…にする
+fn truncate_label(name: &str, max_pixels: i64) -> String {
+    let max_columns = max_pixels / HALF_WIDTH_CHAR_PIXELS;
+    if max_columns < 2 {
+        return String::new();
+    }
+    let column_of = |character: char| character.width().unwrap_or(0) as i64;
+    if name.chars().map(column_of).sum::<i64>() <= max_columns {
+        return name.to_string();
+    }
+
+    let mut label = String::new();
+    let mut columns = 0;
+    for character in name.chars() {
+        columns += column_of(character);
+        if columns > max_columns - 1 {
+            break;
+        }
+        label.push(character);
+    }
+    label.push('…');
+    label
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+    use schronu::application::gantt::{GanttBusySlotView, GanttDeadlineView};
+    use schronu::application::task_use_case::TaskView;
+    use schronu::entity::task::TaskHandle;
+    use schronu::entity::waiting::WaitingRecord;
+    use uuid::Uuid;
+
+    fn lane_start() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 4, 0, 0).unwrap()
+    }
+
+    fn segment(name: &str, start_hours: i64, end_hours: i64) -> GanttSegmentView {
+        let task = TaskHandle::with_identity(name, Uuid::new_v4(), lane_start()).unwrap();
+        GanttSegmentView {
+            task: TaskView::try_from(&task).unwrap(),
+            start: lane_start() + Duration::hours(start_hours),
+            end: lane_start() + Duration::hours(end_hours),
+            segment_index: 1,
+            segment_count: 1,
+        }
+    }
+
+    fn chart(segments: Vec<GanttSegmentView>) -> GanttChartView {
+        GanttChartView {
+            generated_at: lane_start(),
+            lanes: vec![GanttLaneView {
+                date: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+                start: lane_start(),
+                end: lane_start() + Duration::days(1),
+                segments,
+                busy_slots: vec![GanttBusySlotView {
+                    start: lane_start() + Duration::hours(8),
+                    end: lane_start() + Duration::hours(9),
+                }],
+                deadlines: vec![GanttDeadlineView {
+                    task_id: Uuid::new_v4(),
+                    name: "提出 <最終>".to_string(),
+                    deadline_time: lane_start() + Duration::hours(14),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn 片は類の色で塗り待ちのtaskは灰色の破線で描く() {
+        let mut investment = segment("資料作成", 5, 7);
+        investment.task.project_category = Some(ProjectCategory::Investment);
+        investment.segment_count = 2;
+        let mut waiting = segment("返事待ち", 10, 11);
+        waiting.task.project_category = Some(ProjectCategory::Investment);
+        let task = TaskHandle::with_identity("返事待ち", waiting.task.id, lane_start()).unwrap();
+        task.set_waiting_opt(Some(WaitingRecord::new("佐藤", lane_start(), None, None)))
+            .unwrap();
+        waiting.task = TaskView::try_from(&task).unwrap();
+
+        let svg = render_gantt_svg(&chart(vec![investment, waiting]));
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains(
+            "<title>[資] 資料作成 09:00-11:00 (1/2)</title><rect x=\"296\" y=\"49\" width=\"80\" height=\"30\" rx=\"3\" fill=\"#f28e2b\"/>"
+        ));
+        assert!(svg.contains("class=\"segment waiting\""));
+        assert!(svg.contains("待: 佐藤"));
+        assert!(svg.contains("fill=\"#d0d0d0\" stroke=\"#808080\" stroke-dasharray=\"3 2\"/>"));
+        assert!(svg.contains("<title>予定あり 12:00-13:00</title>"));
+        assert!(svg.contains("<title>〆 10/19 18:00 提出 &lt;最終&gt;</title><line x1=\"656\""));
+        assert!(svg.contains(">10/19(月)</text>"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn htmlはsvgを埋め込んだ単体のfileにする() {
+        let html = render_gantt_html(&chart(vec![]));
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<meta charset=\"utf-8\">"));
+        assert!(html.contains("<title>予定図 2026/10/19-10/19 (作成 2026/10/19 04:00)</title>"));
+        assert!(html.contains("<svg "));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn 名前は枠に収まる幅で切り詰める() {
+        assert_eq!(truncate_label("short", 60), "short");
+        assert_eq!(truncate_label("長い名前のタスク", 48), "長い名…");
+        assert_eq!(truncate_label("名前", 6), "");
+    }
+}
@@ -1,24 +1,65 @@
-use super::command::{Command, CommandAction, CommandKind, CommandParseError, InteractiveShortcut};
-use super::renderer::{DisplayModel, DisplayRecorder, SchronuWriter};
+use super::command::{
+    BusyBlockCommand, Command, CommandAction, CommandKind, CommandParseError, ExportCommand,
+    GanttFormat, ImportCommand, InteractiveShortcut, LockCommand, RevisionCommand, SubtreeCommand,
+    TrashCommand, WaitCommand, WindowCommand, WHEN_USAGE,
+};
+use super::gantt::{render_gantt_html, render_gantt_svg};
+use super::renderer::{project_category_symbol, DisplayModel, DisplayRecorder, SchronuWriter};
 use chrono::{DateTime, Datelike, Days, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime};
-use regex::Regex;
+use schronu::adapter::gateway::storage_consistency::StorageConsistencyError;
+use schronu::adapter::gateway::storage_lock::{LockHolder, StorageLockError, StorageLockStatus};
+use schronu::adapter::gateway::task_export::{render_task_tree, ExportFormat, TaskExportError};
+use schronu::application::at_risk::AtRiskTaskView;
+use schronu::application::busy_block::{resolve_busy_block, BusyBlockError};
+use schronu::application::consistency::{ConsistencyCode, ConsistencyFinding, STALE_PENDING_DAYS};
+use schronu::application::critical_path::{CriticalPathTaskView, CriticalPathView};
 use schronu::application::daily_capacity::{
     try_local_date_and_time, try_next_business_day_start, try_subjective_date,
 };
 use schronu::application::flatten_use_case::{FlattenResult, UnresolvedReason};
+use schronu::application::flow_metrics::{
+    FlowDistributionView, FlowDurationStats, FlowMetricsView, FlowTaskLevel,
+};
+use schronu::application::gantt::GanttChartView;
+use schronu::application::interface::{LoadHistoryStoreError, StorageHistoryError};
+use schronu::application::load_history::LoadSnapshot;
 use schronu::application::pack_use_case::PackResult;
+use schronu::application::project_note::{
+    ProjectNoteConflict, ProjectNoteConflictKind, ProjectNoteSyncError, ProjectNoteSyncReport,
+};
+use schronu::application::storage_history::{
+    resolve_storage_revision, ProjectRevisionDiff, StorageHistoryUseCaseError, StorageRevision,
+    TaskRevisionChange,
+};
+use schronu::application::task_import::{
+    ImportedTask, TaskImportError, TaskOutline, TaskOutlineError,
+};
+use schronu::application::task_tree::TaskTreeNodeView;
 use schronu::application::task_use_case::{
     estimated_work_seconds_from_minutes, validate_task_name, ApplicationError, BreakdownTaskInput,
     CompleteTaskInput, CreateTaskInput,
 };
-use schronu::entity::task::{TaskAttr, TaskHandle};
+use schronu::application::trash::{resolve_trash_entry, PurgedTrashEntry, TrashEntry, TrashError};
+use schronu::application::waiting::{
+    clear_waiting, record_follow_up, start_waiting, WaitingEntry, WaitingGroup,
+};
+use schronu::entity::busy_block::{BusyBlock, BusyBlockSpan};
+use schronu::entity::datetime::temporal::{
+    ClockTime, DateTerm, TemporalExpression, TemporalParseError, TemporalResolveError,
+};
+use schronu::entity::datetime::BusinessDateTimePolicy;
+use schronu::entity::task::{Status, TaskAttr, TaskHandle};
+use schronu::entity::waiting::WaitingRecord;
 use std::cmp::min;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub(super) enum ExternalRequest {
     OpenFocusedLink,
     OpenObsidianRootSearch,
+    BrowseFile(PathBuf),
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -38,6 +79,7 @@ pub(super) struct CommandOutcome {
 
 pub(super) trait ProjectCommandContext {
     fn last_synced_time(&self) -> DateTime<Local>;
+    fn datetime_policy(&self) -> BusinessDateTimePolicy;
     fn focused_task(&mut self) -> Result<Option<TaskHandle>, ApplicationError>;
     fn create_task(&mut self, input: CreateTaskInput) -> Result<Uuid, ApplicationError>;
     fn breakdown_task(&mut self, input: BreakdownTaskInput) -> Result<Vec<Uuid>, ApplicationError>;
@@ -45,6 +87,7 @@ pub(super) trait ProjectCommandContext {
     fn set_estimate(&mut self, task_id: Uuid, minutes: i64) -> Result<(), ApplicationError>;
     fn focused_task_id(&self) -> Option<Uuid>;
     fn set_focused_task_id(&mut self, task_id_opt: Option<Uuid>);
+    fn list_waiting_tasks(&self) -> Result<Vec<WaitingGroup>, ApplicationError>;
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -55,7 +98,8 @@ pub(super) enum TaskListOrder {
 
 pub(super) trait TaskTreeCommandContext {
     fn supports_ansi_color(&self) -> bool;
-    fn show_tree(&mut self, display: &mut dyn SchronuWriter) -> Result<(), ApplicationError>;
+    fn task_tree(&mut self) -> Result<Option<TaskTreeNodeView>, ApplicationError>;
+    fn critical_path(&mut self) -> Result<Option<CriticalPathView>, ApplicationError>;
     fn show_ancestor(&mut self, display: &mut dyn SchronuWriter) -> Result<(), ApplicationError>;
     fn focus_root(&mut self) -> Result<(), ApplicationError>;
     fn show_leaves(&mut self, display: &mut dyn SchronuWriter) -> Result<(), ApplicationError>;
@@ -113,6 +157,7 @@ pub(super) trait DeferCommandContext {
 pub(super) trait FinishPlacementCommandContext {
     fn supports_ansi_color(&self) -> bool;
     fn last_synced_time(&self) -> DateTime<Local>;
+    fn datetime_policy(&self) -> BusinessDateTimePolicy;
     fn focus_started_datetime(&self) -> DateTime<Local>;
     fn focused_task(&self) -> Result<Option<TaskHandle>, ApplicationError>;
     fn show_focused_tree(
@@ -128,6 +173,151 @@ pub(super) trait FinishPlacementCommandContext {
     fn flatten(&mut self) -> Result<FlattenResult, ApplicationError>;
 }
 
+pub(super) trait ReportCommandContext {
+    fn last_synced_time(&self) -> DateTime<Local>;
+    fn datetime_policy(&self) -> BusinessDateTimePolicy;
+    fn load_history(
+        &mut self,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<Vec<LoadSnapshot>, LoadHistoryStoreError>;
+    fn flow_metrics(
+        &mut self,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<FlowMetricsView, ApplicationError>;
+    fn list_at_risk_tasks(&mut self) -> Result<Vec<AtRiskTaskView>, ApplicationError>;
+    fn gantt_chart(&mut self, days: u64) -> Result<GanttChartView, ApplicationError>;
+    // 書き出したfileの場所を返す。置き場所はcontextが決める
+    fn write_report_file(&mut self, file_name: &str, contents: &str) -> std::io::Result<PathBuf>;
+}
+
+// 版の一覧は新しい順に返す。restoreは保存先lockの内側で呼ばれ、戻した後のtask treeを読み直す
+pub(super) trait StorageHistoryCommandContext {
+    fn list_revisions(&self) -> Result<Vec<StorageRevision>, StorageHistoryError>;
+    fn diff_revisions(
+        &self,
+        from: Uuid,
+        until: Option<Uuid>,
+    ) -> Result<Vec<ProjectRevisionDiff>, StorageHistoryError>;
+    fn restore_revision(
+        &mut self,
+        revision: Uuid,
+        project: Option<&str>,
+    ) -> Result<StorageRevision, StorageHistoryUseCaseError>;
+}
+
+// 捨てた記録の一覧は新しい順に返す。捨てたtaskにfocusしていれば、contextがfocusを外す
+pub(super) trait TrashCommandContext {
+    fn focused_task_id(&self) -> Option<Uuid>;
+    fn delete_task(&mut self, task_id: Uuid) -> Result<TrashEntry, TrashError>;
+    fn list_trash(&self) -> Result<Vec<TrashEntry>, TrashError>;
+    fn restore_trash_entry(&mut self, trash_id: Uuid) -> Result<TrashEntry, TrashError>;
+    fn purge_trash_entry(&mut self, trash_id: Uuid) -> Result<PurgedTrashEntry, TrashError>;
+}
+
+// 一覧は始まる順に返す。追加した時間は、contextが今の空き時間にも反映する
+pub(super) trait BusyBlockCommandContext {
+    fn last_synced_time(&self) -> DateTime<Local>;
+    fn datetime_policy(&self) -> BusinessDateTimePolicy;
+    fn list_busy_blocks(&self) -> Result<Vec<BusyBlock>, BusyBlockError>;
+    fn add_busy_block(
+        &mut self,
+        name: &str,
+        span: BusyBlockSpan,
+    ) -> Result<BusyBlock, BusyBlockError>;
+    fn remove_busy_block(&mut self, busy_block_id: Uuid) -> Result<BusyBlock, BusyBlockError>;
+}
+
+// vaultの場所が設定されていなければ同期しない
+pub(super) trait ObsidianSyncCommandContext {
+    fn has_obsidian_vault(&self) -> bool;
+    fn sync_project_notes(&mut self) -> Result<ProjectNoteSyncReport, ProjectNoteSyncError>;
+}
+
+// fixなら直せる食い違いを直して保存したうえで、見つけた食い違いをすべて返す
+pub(super) trait VerifyCommandContext {
+    fn check_consistency(
+        &mut self,
+        fix: bool,
+    ) -> Result<Vec<ConsistencyFinding>, VerifyCommandError>;
+}
+
+#[derive(Debug)]
+pub(super) enum VerifyCommandError {
+    Application(ApplicationError),
+    Save(Box<dyn std::error::Error + Send + Sync>),
+    Storage(StorageConsistencyError),
+    Unresolved { count: usize },
+}
+
+impl std::fmt::Display for VerifyCommandError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Application(error) => error.fmt(formatter),
+            Self::Save(error) => error.fmt(formatter),
+            Self::Storage(error) => error.fmt(formatter),
+            Self::Unresolved { count } => {
+                write!(formatter, "直していない食い違いが{count}件あります")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyCommandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Application(error) => Some(error),
+            Self::Save(error) => Some(error.as_ref()),
+            Self::Storage(error) => Some(error),
+            Self::Unresolved { .. } => None,
+        }
+    }
+}
+
+// 自分でlockを取ると自分が保持者に見えるため、lockを取らずに調べる
+pub(super) trait LockCommandContext {
+    fn lock_status(&mut self) -> Result<StorageLockStatus, StorageLockError>;
+}
+
+// 書き出しはfocus中のtaskの部分木を文字列にし、fileの指定があればそこへ書く
+pub(super) trait ExportCommandContext {
+    fn focused_task_id(&self) -> Option<Uuid>;
+    fn task_tree(&mut self, task_id: Uuid) -> Result<TaskTreeNodeView, ApplicationError>;
+    fn write_task_export(&mut self, path: &str, text: &str) -> Result<(), TaskExportError>;
+}
+
+// 取り込みはファイルを読んで木にし、確定の時だけtaskを作る
+pub(super) trait ImportCommandContext {
+    fn focused_task_id(&self) -> Option<Uuid>;
+    fn read_task_outline(&self, path: &str) -> Result<TaskOutline, TaskOutlineError>;
+    fn import_task_outline(
+        &mut self,
+        outline: &TaskOutline,
+        parent_id: Option<Uuid>,
+    ) -> Result<Vec<Uuid>, ApplicationError>;
+}
+
+// 移動と複製と併合はfocus中のtaskを対象にする。併合では兄弟のほうが消え、focusは残る
+pub(super) trait SubtreeCommandContext {
+    fn focused_task_id(&self) -> Option<Uuid>;
+    fn move_subtree(
+        &mut self,
+        task_id: Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<(), ApplicationError>;
+    fn copy_subtree(
+        &mut self,
+        task_id: Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<Uuid, ApplicationError>;
+    fn merge_sibling_tasks(
+        &mut self,
+        task_id: Uuid,
+        source_task_id: Uuid,
+    ) -> Result<(), ApplicationError>;
+}
+
 #[derive(Debug)]
 pub(super) enum DeferCommandError {
     Parse(CommandParseError),
@@ -149,6 +339,20 @@ impl From<ApplicationError> for DeferCommandError {
     }
 }
 
+#[derive(Debug)]
+pub(super) enum ReportCommandError {
+    Parse(CommandParseError),
+    Application(ApplicationError),
+    LoadHistory(LoadHistoryStoreError),
+    Output(std::io::Error),
+}
+
+impl From<ApplicationError> for ReportCommandError {
+    fn from(error: ApplicationError) -> Self {
+        Self::Application(error)
+    }
+}
+
 impl CommandOutcome {
     fn empty(kind: CommandKind) -> Self {
         Self {
@@ -227,7 +431,10 @@ pub(super) fn handle_task_tree_command(
         Command::Action(CommandAction::NoArguments {
             kind: CommandKind::Tree,
             ..
-        }) => context.show_tree(&mut display)?,
+        }) => write_task_tree(&mut display, context.task_tree()?.as_ref()),
+        Command::Action(CommandAction::CriticalPath) => {
+            write_critical_path(&mut display, context.critical_path()?.as_ref())
+        }
         Command::Action(CommandAction::NoArguments {
             kind: CommandKind::Ancestor,
             ..
@@ -397,7 +604,8 @@ pub(super) fn handle_project_command(
             ..
         } => {
             let now = context.last_synced_time();
-            if let Some(start_time) = decide_time_values(values, &now)? {
+            if let Some(start_time) = decide_time_values(values, &now, &context.datetime_policy())?
+            {
                 let focused_task_opt = context.focused_task()?;
                 execute_make_appointment(&focused_task_opt, start_time)?;
             }
@@ -409,7 +617,8 @@ pub(super) fn handle_project_command(
             ..
         } => {
             let now = context.last_synced_time();
-            if let Some(start_time) = decide_time_values(values, &now)? {
+            if let Some(start_time) = decide_time_values(values, &now, &context.datetime_policy())?
+            {
                 if let Some(task) = context.focused_task()? {
                     task.set_start_time(start_time)
                         .map_err(ApplicationError::TaskTree)?;
@@ -445,14 +654,18 @@ pub(super) fn handle_breakdown_split_command(
             let result = execute_split(context, &focused_task, name, *minutes, &mut display);
             report_result(&mut display, result);
         }
-        CommandAction::NoArguments {
-            kind: CommandKind::Wait,
-            ..
-        } => {
+        CommandAction::Wait(WaitCommand::List) => {
+            let now = context.last_synced_time();
+            write_waiting_groups(&mut display, &context.list_waiting_tasks()?, now);
+        }
+        CommandAction::Wait(wait_command) => {
             if let Some(focused_task) = context.focused_task()? {
-                let _result = focused_task
-                    .set_is_on_other_side(true)
-                    .map_err(ApplicationError::TaskTree);
+                execute_wait(&mut display, context, &focused_task, wait_command)?;
+            }
+        }
+        CommandAction::Window(window_command) => {
+            if let Some(focused_task) = context.focused_task()? {
+                execute_window(&mut display, &focused_task, window_command)?;
             }
         }
         _ => return Ok(None),
@@ -463,6 +676,157 @@ pub(super) fn handle_breakdown_split_command(
     Ok(Some(outcome))
 }
 
+fn execute_wait(
+    display: &mut dyn SchronuWriter,
+    context: &mut dyn ProjectCommandContext,
+    focused_task: &TaskHandle,
+    wait_command: &WaitCommand,
+) -> Result<(), ApplicationError> {
+    let now = context.last_synced_time();
+    let next_follow_up_time_opt = match wait_command {
+        WaitCommand::Mark => {
+            focused_task
+                .set_is_on_other_side(true)
+                .map_err(ApplicationError::TaskTree)?;
+            return Ok(());
+        }
+        WaitCommand::Clear => return clear_waiting(focused_task),
+        WaitCommand::List => unreachable!("list is handled without a focused task"),
+        WaitCommand::Start {
+            counterpart,
+            expected_reply,
+            follow_up_interval_days,
+        } => {
+            let expected_reply_date = match expected_reply {
+                None => None,
+                Some(expression) => Some(
+                    resolve_temporal_expression(
+                        expression,
+                        now,
+                        &context.datetime_policy(),
+                        ClockTime::business_day_start(),
+                    )?
+                    .ok_or(ApplicationError::InvalidInput {
+                        field: "expected_reply_date",
+                        reason: "must resolve to an existing date",
+                    })?
+                    .date(),
+                ),
+            };
+            let waiting = WaitingRecord::new(
+                counterpart,
+                now,
+                expected_reply_date,
+                *follow_up_interval_days,
+            );
+            start_waiting(focused_task, waiting)?
+        }
+        WaitCommand::FollowUp => record_follow_up(focused_task, now)?,
+    };
+
+    let counterpart = focused_task
+        .get_waiting_opt()
+        .map_err(ApplicationError::TaskTree)?
+        .map(|waiting| waiting.get_counterpart().to_string())
+        .unwrap_or_default();
+    let next_follow_up = next_follow_up_time_opt
+        .map(|time| format!("次の催促 {}", time.format("%Y/%m/%d %H:%M")))
+        .unwrap_or_else(|| "催促の予定なし".to_string());
+    display
+        .writeln_newline(&format!("待\t{counterpart}\t{next_follow_up}"))
+        .expect("display recording is infallible");
+    Ok(())
+}
+
+// 設定や解除の後も、実際に効いている枠を表示する。祖先から継いだ枠にはその旨を添える
+fn execute_window(
+    display: &mut dyn SchronuWriter,
+    focused_task: &TaskHandle,
+    window_command: &WindowCommand,
+) -> Result<(), ApplicationError> {
+    match window_command {
+        WindowCommand::Show => {}
+        WindowCommand::Set(allowed_window) => focused_task
+            .set_allowed_window_opt(Some(allowed_window.clone()))
+            .map_err(ApplicationError::TaskTree)?,
+        WindowCommand::Clear => focused_task
+            .set_allowed_window_opt(None)
+            .map_err(ApplicationError::TaskTree)?,
+    }
+
+    let own_window_opt = focused_task
+        .get_allowed_window_opt()
+        .map_err(ApplicationError::TaskTree)?;
+    let line = match focused_task
+        .get_effective_allowed_window_opt()
+        .map_err(ApplicationError::TaskTree)?
+    {
+        None => "[Info] 枠はありません。".to_string(),
+        Some(window) if own_window_opt.is_some() => format!("枠\t{window}"),
+        Some(window) => format!("枠\t{window}\t(祖先から継承)"),
+    };
+    display
+        .writeln_newline(&line)
+        .expect("display recording is infallible");
+    Ok(())
+}
+
+// 相手ごとに、長く待っている順で並べる。催促の時刻を過ぎたものには印を付ける
+fn write_waiting_groups(
+    display: &mut dyn SchronuWriter,
+    groups: &[WaitingGroup],
+    now: DateTime<Local>,
+) {
+    if groups.is_empty() {
+        display
+            .writeln_newline("[Info] 待っているタスクはありません。")
+            .expect("display recording is infallible");
+        return;
+    }
+
+    for group in groups {
+        display
+            .writeln_newline(&format!(
+                "[{}] {}件",
+                group.counterpart.as_deref().unwrap_or("相手未記録"),
+                group.entries.len(),
+            ))
+            .expect("display recording is infallible");
+        for entry in &group.entries {
+            display
+                .writeln_newline(&format_waiting_entry(entry, now))
+                .expect("display recording is infallible");
+        }
+    }
+}
+
+fn format_waiting_entry(entry: &WaitingEntry, now: DateTime<Local>) -> String {
+    let age = entry
+        .age_days
+        .map(|age_days| format!("{age_days}日"))
+        .unwrap_or_else(|| "-".to_string());
+    let waiting = entry.task.waiting.as_ref();
+    let expected_reply = waiting
+        .and_then(|waiting| waiting.expected_reply_date)
+        .map(|date| format!("返答予定 {}", date.format("%Y/%m/%d")))
+        .unwrap_or_default();
+    let follow_up = waiting
+        .and_then(|waiting| waiting.next_follow_up_at)
+        .map(|time| {
+            if time <= now {
+                format!("催促 {} 超過", time.format("%Y/%m/%d %H:%M"))
+            } else {
+                format!("催促 {}", time.format("%Y/%m/%d %H:%M"))
+            }
+        })
+        .unwrap_or_default();
+    format!(
+        "待\t{age}\t{}\t{expected_reply}\t{follow_up}\t{}",
+        if entry.follow_up_due { "[催促]" } else { "" },
+        entry.task.name,
+    )
+}
+
 pub(super) fn handle_task_attribute_command(
     command: &Command,
     context: &mut dyn TaskAttributeCommandContext,
@@ -581,7 +945,9 @@ pub(super) fn handle_finish_placement_command(
                 context.show_focused_tree(&mut display)?;
             } else {
                 let now = context.last_synced_time();
-                if let Some(finished_at) = decide_finish_time_values(values, &now)? {
+                if let Some(finished_at) =
+                    decide_finish_time_values(values, &now, &context.datetime_policy())?
+                {
                     let additional_actual_work_seconds = if values.is_empty() {
                         let focus_duration_seconds =
                             (now - context.focus_started_datetime()).num_seconds();
@@ -626,125 +992,1170 @@ pub(super) fn handle_finish_placement_command(
     Ok(Some(outcome))
 }
 
-pub(super) fn decide_finish_time_values(
-    values: &[String],
-    now: &DateTime<Local>,
-) -> Result<Option<DateTime<Local>>, ApplicationError> {
-    let hhmmss_reg = Regex::new(r"^(\d{1,2}):(\d{1,2})(?::(\d{1,2}))?$").unwrap();
-    let yyyymmdd_reg = Regex::new(r"^\d{2,4}/\d{1,2}/\d{1,2}$").unwrap();
-    let mmdd_reg = Regex::new(r"^\d{1,2}/\d{1,2}$").unwrap();
-    let days_of_week = ["月", "火", "水", "木", "金", "土", "日"];
-
-    let build_finish_time = |hhmmss: &str,
-                             date: Option<&String>|
-     -> Result<Option<DateTime<Local>>, ApplicationError> {
-        let Some(captures) = hhmmss_reg.captures(hhmmss) else {
-            return Ok(None);
-        };
-        let Some(hours) = captures[1].parse::<u32>().ok() else {
-            return Ok(None);
-        };
-        let Some(minutes) = captures[2].parse::<u32>().ok() else {
-            return Ok(None);
-        };
-        let Some(seconds) = captures
-            .get(3)
-            .map(|value| value.as_str().parse().ok())
-            .unwrap_or(Some(0))
-        else {
-            return Ok(None);
-        };
-        let mut time_values = vec![format!("{hours}:{minutes}")];
-        if let Some(date) = date {
-            time_values.push(date.clone());
-        }
-        let Some(naive_datetime) = decide_naive_datetime_values(&time_values, now, seconds)? else {
-            return Ok(None);
-        };
-        resolve_date_and_time(naive_datetime.date(), naive_datetime.time()).map(Some)
-    };
+pub(super) fn handle_report_command(
+    command: &Command,
+    context: &mut dyn ReportCommandContext,
+) -> Result<Option<CommandOutcome>, ReportCommandError> {
+    let kind = command.kind();
+    let mut display = DisplayRecorder::default();
+    let mut external_request = None;
 
-    match values {
-        [] => Ok(Some(*now)),
-        [value] if matches!(value.as_str(), "今" | "now") => Ok(Some(*now)),
-        [time] if hhmmss_reg.is_match(time) => build_finish_time(time, None),
-        [time, date]
-            if hhmmss_reg.is_match(time)
-                && (yyyymmdd_reg.is_match(date)
-                    || mmdd_reg.is_match(date)
-                    || date.starts_with('明')
-                    || days_of_week.contains(&date.as_str())) =>
-        {
-            build_finish_time(time, Some(date))
+    match command {
+        Command::Action(CommandAction::DateRange {
+            kind: CommandKind::LoadHistory,
+            values,
+            ..
+        }) => {
+            let today = try_subjective_date(context.last_synced_time())?;
+            let (from, until) = decide_report_date_range(
+                values,
+                today,
+                LOAD_HISTORY_DEFAULT_DAYS,
+                "履",
+                "履 [日数|開始日] [終了日]",
+            )
+            .map_err(ReportCommandError::Parse)?;
+            let snapshots = context
+                .load_history(from, until)
+                .map_err(ReportCommandError::LoadHistory)?;
+            write_load_history(&mut display, &snapshots);
         }
-        _ => Ok(None),
+        Command::Action(CommandAction::DateRange {
+            kind: CommandKind::FlowMetrics,
+            values,
+            ..
+        }) => {
+            let today = try_subjective_date(context.last_synced_time())?;
+            let (from, until) = decide_report_date_range(
+                values,
+                today,
+                FLOW_METRICS_DEFAULT_DAYS,
+                "流",
+                "流 [日数|開始日] [終了日]",
+            )
+            .map_err(ReportCommandError::Parse)?;
+            write_flow_metrics(&mut display, &context.flow_metrics(from, until)?);
+        }
+        Command::Action(CommandAction::TimeExpression {
+            kind: CommandKind::When,
+            values,
+            ..
+        }) => {
+            let tokens = values.iter().map(String::as_str).collect::<Vec<_>>();
+            let expression = TemporalExpression::parse(&tokens).map_err(|error| {
+                ReportCommandError::Parse(CommandParseError::new(
+                    "時",
+                    "expression",
+                    temporal_parse_error_reason(&error),
+                    WHEN_USAGE,
+                ))
+            })?;
+            let now = context.last_synced_time();
+            let Some(resolved) = resolve_temporal_expression(
+                &expression,
+                now,
+                &context.datetime_policy(),
+                ClockTime::business_day_start(),
+            )?
+            else {
+                return Err(ReportCommandError::Parse(CommandParseError::new(
+                    "時",
+                    "expression",
+                    "存在しない日付です",
+                    WHEN_USAGE,
+                )));
+            };
+            write_temporal_preview(&mut display, &values.join(" "), resolved);
+        }
+        Command::Action(CommandAction::NoArguments {
+            kind: CommandKind::AtRisk,
+            ..
+        }) => write_at_risk_tasks(&mut display, &context.list_at_risk_tasks()?),
+        Command::Action(CommandAction::Gantt { days, format }) => {
+            let chart = context.gantt_chart(*days)?;
+            let (file_name, contents) = match format {
+                GanttFormat::Html => (GANTT_HTML_FILE_NAME, render_gantt_html(&chart)),
+                GanttFormat::Svg => (GANTT_SVG_FILE_NAME, render_gantt_svg(&chart)),
+            };
+            let path = context
+                .write_report_file(file_name, &contents)
+                .map_err(ReportCommandError::Output)?;
+            display
+                .writeln_newline(&format!(
+                    "[Info] 予定図を書き出しました: {}",
+                    path.display()
+                ))
+                .expect("display recording is infallible");
+            external_request = Some(ExternalRequest::BrowseFile(path));
+        }
+        _ => return Ok(None),
     }
+
+    let mut outcome = CommandOutcome::empty(kind);
+    outcome.display = display.model().clone();
+    outcome.external_request = external_request;
+    Ok(Some(outcome))
 }
 
-pub(super) fn write_pack_result(display: &mut dyn SchronuWriter, result: &PackResult) {
-    let total_work_seconds = result
-        .packed_tasks
-        .iter()
-        .map(|packed| packed.work_seconds)
-        .sum::<i64>();
+// 書き出すfile名の元。実際のfileには毎回別の乱数が付く
+const GANTT_HTML_FILE_NAME: &str = "schronu_gantt.html";
+const GANTT_SVG_FILE_NAME: &str = "schronu_gantt.svg";
 
-    for packed in &result.packed_tasks {
+fn write_at_risk_tasks(display: &mut dyn SchronuWriter, rows: &[AtRiskTaskView]) {
+    if rows.is_empty() {
         display
-            .writeln_newline(&format!(
-                "詰\t{}\t{}\t{}\t優先度{}\t{}\t{}",
-                packed.source_date,
-                packed.target_date,
-                format_work_seconds_as_hours_minutes(packed.work_seconds),
-                packed.priority,
-                packed.task_id,
-                packed.name,
-            ))
+            .writeln_newline("[Info] 〆切のある未完了タスクはありません。")
             .expect("display recording is infallible");
+        return;
     }
-    if result.packed_tasks.is_empty() && result.skipped_tasks.is_empty() {
-        display
-            .writeln_newline("[Info] 詰められるタスクはありません。")
-            .expect("display recording is infallible");
-    } else {
+
+    for row in rows {
         display
-            .writeln_newline(&format!(
-                "詰: {}件 {} (スキップ{}件)",
-                result.packed_tasks.len(),
-                format_work_seconds_as_hours_minutes(total_work_seconds),
-                result.skipped_tasks.len(),
-            ))
+            .writeln_newline(&format_at_risk_task(row))
             .expect("display recording is infallible");
     }
 }
 
-fn write_flatten_result(display: &mut dyn SchronuWriter, result: &FlattenResult) {
-    let total_work_seconds = result
-        .flattened_tasks
+// 余裕は〆切から予定終了を引いた時間で、負なら予定どおりでは〆切に間に合わない
+fn format_at_risk_task(row: &AtRiskTaskView) -> String {
+    let slack_minutes = row.slack_seconds.unsigned_abs() / 60;
+    format!(
+        "危\t{}{:02}:{:02}\t〆 {}{}\t終 {}\t{}\t{}",
+        if row.slack_seconds < 0 { '-' } else { '+' },
+        slack_minutes / 60,
+        slack_minutes % 60,
+        row.deadline_time.format("%Y/%m/%d %H:%M"),
+        if row.deadline_task_id == row.task.id {
+            ""
+        } else {
+            "(祖先)"
+        },
+        row.scheduled_end.format("%Y/%m/%d %H:%M"),
+        if row.recoverable_by_deferring {
+            "[後回しで回復]"
+        } else {
+            ""
+        },
+        row.task.name,
+    )
+}
+
+// 完了したtaskは部分木ごと省き、各行に部分木全体の集計を添える
+pub(super) fn write_task_tree(
+    display: &mut dyn SchronuWriter,
+    tree_opt: Option<&TaskTreeNodeView>,
+) {
+    display
+        .writeln_newline("")
+        .expect("display recording is infallible");
+    if let Some(tree) = tree_opt {
+        write_task_tree_node(display, tree, "", "");
+    }
+    display
+        .writeln_newline("")
+        .expect("display recording is infallible");
+}
+
+fn write_task_tree_node(
+    display: &mut dyn SchronuWriter,
+    node: &TaskTreeNodeView,
+    header: &str,
+    child_indent: &str,
+) {
+    display
+        .writeln_newline(&format!("{header}{}", format_task_tree_node(node)))
+        .expect("display recording is infallible");
+    let open_children = node
+        .children
         .iter()
-        .map(|flattened| flattened.work_seconds)
-        .sum::<i64>();
+        .filter(|child| child.task.status != Status::Done)
+        .collect::<Vec<_>>();
+    for (index, child) in open_children.iter().enumerate() {
+        let is_last = index + 1 == open_children.len();
+        write_task_tree_node(
+            display,
+            child,
+            &format!("{child_indent}{}", if is_last { "`-- " } else { "|-- " }),
+            &format!("{child_indent}{}", if is_last { "    " } else { "|   " }),
+        );
+    }
+}
 
-    for flattened in &result.flattened_tasks {
-        display
-            .writeln_newline(&format!(
-                "平\t{}\t{}\t{}\t優先度{}\t{}\t{}",
-                flattened.source_date,
-                flattened.target_date,
-                format_work_seconds_as_hours_minutes(flattened.work_seconds),
-                flattened.priority,
-                flattened.task_id,
-                flattened.name,
-            ))
-            .expect("display recording is infallible");
+fn format_task_tree_node(node: &TaskTreeNodeView) -> String {
+    let to_minutes = |seconds: i64| (seconds + 59).div_euclid(60);
+    let rollup = &node.rollup;
+    let remaining_minutes = to_minutes(rollup.remaining_estimated_work_seconds);
+    let mut summary = format!(
+        "残{}:{:02} 完{}/{}",
+        remaining_minutes / 60,
+        remaining_minutes % 60,
+        rollup.done_leaf_count,
+        rollup.leaf_count
+    );
+    if let Some(deadline_time) = rollup.earliest_deadline_time {
+        summary.push_str(&format!(" 〆{}", deadline_time.format("%m/%d %H:%M")));
     }
+    if let Some(scheduled_finish) = rollup.scheduled_finish {
+        summary.push_str(&format!(" 終{}", scheduled_finish.format("%m/%d %H:%M")));
+    }
+    format!(
+        "{} {:02}m/{:02}m {}{}\t[{summary}]",
+        match node.task.status {
+            Status::Todo => "[ ]",
+            Status::Pending => "[-]",
+            Status::Done => "[+]",
+        },
+        to_minutes(node.task.actual_work_seconds),
+        to_minutes(node.task.estimated_work_seconds),
+        if node.task.is_on_other_side {
+            "[待ち]"
+        } else {
+            ""
+        },
+        node.task.name,
+    )
+}
 
-    if !result.had_overload {
+fn write_critical_path(display: &mut dyn SchronuWriter, view_opt: Option<&CriticalPathView>) {
+    let Some(view) = view_opt else {
         display
-            .writeln_newline("[Info] 100%を超過している日はありません。")
+            .writeln_newline("[Info] フォーカス中のタスクがありません。")
             .expect("display recording is infallible");
-    } else {
-        display
-            .writeln_newline(&format!(
+        return;
+    };
+
+    display
+        .writeln_newline(&format!(
+            "[Info] 完了見込み: {}",
+            view.project_finish.format("%Y/%m/%d %H:%M")
+        ))
+        .expect("display recording is infallible");
+    for row in &view.tasks {
+        display
+            .writeln_newline(&format_critical_path_task(row))
+            .expect("display recording is infallible");
+    }
+}
+
+// 臨界経路上のtaskには★を付け、それ以外は全体を遅らせずにずらせる余裕を示す
+fn format_critical_path_task(row: &CriticalPathTaskView) -> String {
+    let float_minutes = row.float_seconds / 60;
+    format!(
+        "{}{}\t{}-{}\t余裕 {:02}:{:02}\t{}",
+        "  ".repeat(row.depth),
+        if row.critical { '★' } else { '・' },
+        row.earliest_start.format("%m/%d %H:%M"),
+        row.earliest_finish.format("%m/%d %H:%M"),
+        float_minutes / 60,
+        float_minutes % 60,
+        row.task.name,
+    )
+}
+
+fn temporal_parse_error_reason(error: &TemporalParseError) -> &'static str {
+    match error {
+        TemporalParseError::Empty => "日時表現が必要です",
+        TemporalParseError::UnknownToken(_) => "解釈できない語があります",
+        TemporalParseError::DuplicateDate(_) => "日付が重複しています",
+        TemporalParseError::DuplicateTime(_) => "時刻が重複しています",
+        TemporalParseError::InvalidDate(_) => "日付が不正です",
+        TemporalParseError::InvalidTime(_) => "時刻が不正です",
+    }
+}
+
+fn write_temporal_preview(
+    display: &mut dyn SchronuWriter,
+    expression: &str,
+    resolved: NaiveDateTime,
+) {
+    let weekday = ["月", "火", "水", "木", "金", "土", "日"]
+        [resolved.weekday().num_days_from_monday() as usize];
+    display
+        .writeln_newline(&format!(
+            "時\t{expression}\t{}({weekday}) {}",
+            resolved.format("%Y/%m/%d"),
+            resolved.format("%H:%M:%S"),
+        ))
+        .expect("display recording is infallible");
+}
+
+pub(super) fn handle_storage_history_command(
+    command: &Command,
+    context: &mut dyn StorageHistoryCommandContext,
+) -> Result<Option<CommandOutcome>, StorageHistoryUseCaseError> {
+    let Command::Action(CommandAction::Revision(revision_command)) = command else {
+        return Ok(None);
+    };
+    let mut display = DisplayRecorder::default();
+
+    match revision_command {
+        RevisionCommand::List => write_storage_revisions(&mut display, &context.list_revisions()?),
+        RevisionCommand::Diff { from, until } => {
+            let revisions = context.list_revisions()?;
+            let from = resolve_storage_revision(&revisions, from)?;
+            let until = until
+                .as_deref()
+                .map(|until| resolve_storage_revision(&revisions, until))
+                .transpose()?;
+            write_storage_revision_diffs(&mut display, &context.diff_revisions(from, until)?);
+        }
+        RevisionCommand::Restore { revision, project } => {
+            let revisions = context.list_revisions()?;
+            let revision = resolve_storage_revision(&revisions, revision)?;
+            let project = project.as_deref().map(project_yaml_relative_path);
+            let restored = context.restore_revision(revision, project.as_deref())?;
+            display
+                .writeln_newline(&format!(
+                    "[Info] 版 {} から{}を戻しました。新しい版: {}",
+                    short_revision(revision),
+                    project
+                        .as_deref()
+                        .map_or("全project".to_string(), |project| format!(
+                            " {} ",
+                            project_directory_name(project)
+                        )),
+                    short_revision(restored.revision),
+                ))
+                .expect("display recording is infallible");
+        }
+    }
+
+    let mut outcome = CommandOutcome::empty(command.kind());
+    outcome.display = display.model().clone();
+    Ok(Some(outcome))
+}
+
+pub(super) fn handle_trash_command(
+    command: &Command,
+    context: &mut dyn TrashCommandContext,
+) -> Result<Option<CommandOutcome>, TrashError> {
+    let Command::Action(CommandAction::Trash(trash_command)) = command else {
+        return Ok(None);
+    };
+    let mut display = DisplayRecorder::default();
+
+    match trash_command {
+        TrashCommand::Delete { task_id } => {
+            let Some(task_id) = task_id.or_else(|| context.focused_task_id()) else {
+                return Ok(Some(CommandOutcome::empty(command.kind())));
+            };
+            let entry = context.delete_task(task_id)?;
+            display
+                .writeln_newline(&format!(
+                    "[Info] {} ({}件) を捨てました。記録: {}",
+                    entry.name,
+                    entry.task_count,
+                    short_revision(entry.trash_id),
+                ))
+                .expect("display recording is infallible");
+        }
+        TrashCommand::List => write_trash_entries(&mut display, &context.list_trash()?),
+        TrashCommand::Restore { entry } => {
+            let entry = resolve_trash_entry(&context.list_trash()?, entry)?;
+            let restored = context.restore_trash_entry(entry.trash_id)?;
+            display
+                .writeln_newline(&format!(
+                    "[Info] {} ({}件) を戻しました。",
+                    restored.name, restored.task_count,
+                ))
+                .expect("display recording is infallible");
+        }
+        TrashCommand::Purge { entry } => {
+            let entry = resolve_trash_entry(&context.list_trash()?, entry)?;
+            let purged = context.purge_trash_entry(entry.trash_id)?;
+            display
+                .writeln_newline(&format!(
+                    "[Info] {} ({}件) を完全に消しました。{}",
+                    purged.entry.name,
+                    purged.entry.task_count,
+                    if purged.removed_project_directory {
+                        "空のproject directoryも消しました。"
+                    } else {
+                        ""
+                    },
+                ))
+                .expect("display recording is infallible");
+        }
+    }
+
+    let mut outcome = CommandOutcome::empty(command.kind());
+    outcome.display = display.model().clone();
+    Ok(Some(outcome))
+}
+
+pub(super) fn handle_busy_block_command(
+    command: &Command,
+    context: &mut dyn BusyBlockCommandContext,
+) -> Result<Option<CommandOutcome>, BusyBlockError> {
+    let Command::Action(CommandAction::BusyBlock(busy_block_command)) = command else {
+        return Ok(None);
+    };
+    let mut display = DisplayRecorder::default();
+
+    let message = match busy_block_command {
+        BusyBlockCommand::List => {
+            write_busy_blocks(&mut display, &context.list_busy_blocks()?);
+            None
+        }
+        BusyBlockCommand::AddTimed {
+            date,
+            start,
+            end,
+            name,
+        } => {
+            let now = context.last_synced_time();
+            let policy = context.datetime_policy();
+            let date = resolve_busy_block_date(date, "date", now, &policy)?;
+            let span = BusyBlockSpan::Timed {
+                start: resolve_busy_block_time(date, *start, now, &policy)?,
+                end: resolve_busy_block_time(date, *end, now, &policy)?,
+            };
+            let block = context.add_busy_block(name, span)?;
+            Some(format!(
+                "[Info] {} {} を入れました。記録: {}",
+                format_busy_block_span(&block.span),
+                block.name,
+                short_revision(block.busy_block_id),
+            ))
+        }
+        BusyBlockCommand::AddAllDay {
+            start_date,
+            end_date,
+            name,
+        } => {
+            let now = context.last_synced_time();
+            let policy = context.datetime_policy();
+            let start_date = resolve_busy_block_date(start_date, "start_date", now, &policy)?;
+            let end_date = match end_date {
+                Some(end_date) => resolve_busy_block_date(end_date, "end_date", now, &policy)?,
+                None => start_date,
+            };
+            let block = context.add_busy_block(
+                name,
+                BusyBlockSpan::AllDay {
+                    start_date,
+                    end_date,
+                },
+            )?;
+            Some(format!(
+                "[Info] {} {} を入れました。記録: {}",
+                format_busy_block_span(&block.span),
+                block.name,
+                short_revision(block.busy_block_id),
+            ))
+        }
+        BusyBlockCommand::Remove { block } => {
+            let block = resolve_busy_block(&context.list_busy_blocks()?, block)?;
+            let removed = context.remove_busy_block(block.busy_block_id)?;
+            Some(format!(
+                "[Info] {} {} を消しました。空き時間への反映は次の起動からです。",
+                format_busy_block_span(&removed.span),
+                removed.name,
+            ))
+        }
+    };
+    if let Some(message) = message {
+        display
+            .writeln_newline(&message)
+            .expect("display recording is infallible");
+    }
+
+    let mut outcome = CommandOutcome::empty(command.kind());
+    outcome.display = display.model().clone();
+    Ok(Some(outcome))
+}
+
+pub(super) fn handle_import_command(
+    command: &Command,
+    context: &mut dyn ImportCommandContext,
+) -> Result<Option<CommandOutcome>, TaskImportError> {
+    let Command::Action(CommandAction::Import(ImportCommand {
+        path,
+        under_focus,
+        commit,
+    })) = command
+    else {
+        return Ok(None);
+    };
+    let parent_id = match (under_focus, context.focused_task_id()) {
+        (false, _) => None,
+        (true, Some(task_id)) => Some(task_id),
+        (true, None) => return Ok(Some(CommandOutcome::empty(command.kind()))),
+    };
+    let outline = context.read_task_outline(path)?;
+    let mut display = DisplayRecorder::default();
+    for task in &outline.tasks {
+        write_imported_task(&mut display, task, 0);
+    }
+    let skipped = if outline.skipped_done_count > 0 {
+        format!(
+            "完了済みの{}件は読み飛ばしました。",
+            outline.skipped_done_count
+        )
+    } else {
+        String::new()
+    };
+    let message = if *commit {
+        context.import_task_outline(&outline, parent_id)?;
+        format!(
+            "[Info] {}件を取り込みました。{skipped}",
+            outline.task_count()
+        )
+    } else {
+        format!(
+            "[Info] {}件を読みました。{skipped}取り込むには確定を付けて実行してください。",
+            outline.task_count()
+        )
+    };
+    display
+        .writeln_newline(&message)
+        .expect("display recording is infallible");
+
+    let mut outcome = CommandOutcome::empty(command.kind());
+    outcome.display = display.model().clone();
+    Ok(Some(outcome))
+}
+
+pub(super) fn handle_export_command(
+    command: &Command,
+    context: &mut dyn ExportCommandContext,
+) -> Result<Option<CommandOutcome>, TaskExportError> {
+    let Command::Action(CommandAction::Export(ExportCommand { format, path })) = command else {
+        return Ok(None);
+    };
+    let Some(task_id) = context.focused_task_id() else {
+        return Ok(Some(CommandOutcome::empty(command.kind())));
+    };
+    let format = format
+        .or_else(|| {
+            path.as_deref()
+                .and_then(|path| ExportFormat::from_path(Path::new(path)))
+        })
+        .unwrap_or(ExportFormat::Markdown);
+    let text = render_task_tree(&context.task_tree(task_id)?, format);
+    let mut display = DisplayRecorder::default();
+    match path {
+        Some(path) => {
+            context.write_task_export(path, &text)?;
+            display
+                .writeln_newline(&format!("[Info] {path}に書き出しました。"))
+                .expect("display recording is infallible");
+        }
+        None => {
+            for line in text.lines() {
+                display
+                    .writeln_newline(line)
+                    .expect("display recording is infallible");
+            }
+        }
+    }
+
+    let mut outcome = CommandOutcome::empty(command.kind());
+    outcome.display = display.model().clone();
+    Ok(Some(outcome))
+}
+
+pub(super) fn handle_obsidian_sync_command(
+    command: &Command,
+    context: &mut dyn ObsidianSyncCommandContext,
+) -> Result<Option<CommandOutcome>, ProjectNoteSyncError> {
+    let Command::Action(CommandAction::ObsidianSync) = command else {
+        return Ok(None);
+    };
+    let mut display = DisplayRecorder::default();
+    if !context.has_obsidian_vault() {
+        display
+            .writeln_newline("[Info] obsidian_vault_pathを設定すると、project noteを同期できます。")
+            .expect("display recording is infallible");
+    } else {
+        let report = context.sync_project_notes()?;
+        for completion in &report.completed {
+            display
+                .writeln_newline(&format!(
+                    "[Info] noteのcheckから「{}」を完了にしました。",
+                    completion.name
+                ))
+                .expect("display recording is infallible");
+        }
+        for conflict in &report.conflicts {
+            display
+                .writeln_newline(&format!(
+                    "[Warn] {}",
+                    project_note_conflict_message(conflict)
+                ))
+                .expect("display recording is infallible");
+        }
+        let mut message = format!(
+            "[Info] {}件のnoteを書き出しました。",
+            report.written_file_names.len()
+        );
+        let conflicted_notes = report
+            .conflicts
+            .iter()
+            .map(|conflict| conflict.file_name.as_str())
+            .collect::<BTreeSet<_>>();
+        if !conflicted_notes.is_empty() {
+            message.push_str(&format!(
+                "食い違いのあった{}件のnoteは書き直していません。",
+                conflicted_notes.len()
+            ));
+        }
+        display
+            .writeln_newline(&message)
+            .expect("display recording is infallible");
+    }
+
+    let mut outcome = CommandOutcome::empty(command.kind());
+    outcome.display = display.model().clone();
+    Ok(Some(outcome))
+}
+
+pub(super) fn handle_verify_command(
+    command: &Command,
+    context: &mut dyn VerifyCommandContext,
+) -> Result<Option<CommandOutcome>, VerifyCommandError> {
+    let Command::Action(CommandAction::Verify { fix }) = command else {
+        return Ok(None);
+    };
+    let findings = context.check_consistency(*fix)?;
+    let mut display = DisplayRecorder::default();
+    for finding in &findings {
+        let mut line = format!(
+            "[{}] {} {}",
+            if finding.fixed { "Info" } else { "Warn" },
+            finding.code.as_str(),
+            finding.location
+        );
+        if let Some(task_id) = finding.task_id {
+            line.push_str(&format!(" ({task_id})"));
+        }
+        line.push_str(&format!(": {}", consistency_finding_message(finding)));
+        display
+            .writeln_newline(&line)
+            .expect("display recording is infallible");
+    }
+    let fixed_count = findings.iter().filter(|finding| finding.fixed).count();
+    let fixable_count = findings.iter().filter(|finding| finding.fixable).count();
+    let summary = if findings.is_empty() {
+        "検証: OK".to_string()
+    } else if *fix {
+        format!(
+            "検証: {}件の食い違いのうち{fixed_count}件を直しました。",
+            findings.len()
+        )
+    } else {
+        format!(
+            "検証: {}件の食い違いがあります。{fixable_count}件は検証 --fixで直せます。",
+            findings.len()
+        )
+    };
+    display
+        .writeln_newline(&summary)
+        .expect("display recording is infallible");
+
+    let mut outcome = CommandOutcome::empty(command.kind());
+    outcome.display = display.model().clone();
+    Ok(Some(outcome))
+}
+
+pub(super) fn handle_lock_command(
+    command: &Command,
+    context: &mut dyn LockCommandContext,
+) -> Result<Option<CommandOutcome>, StorageLockError> {
+    let Command::Action(CommandAction::Lock(lock_command)) = command else {
+        return Ok(None);
+    };
+    let lines = match lock_command {
+        LockCommand::Status => match context.lock_status()? {
+            StorageLockStatus::Free { last_holder: None } => vec!["錠: 空いています".to_string()],
+            StorageLockStatus::Free {
+                last_holder: Some(holder),
+            } => vec![format!(
+                "錠: 空いています(最後の保持者: {})",
+                describe_lock_holder(&holder)
+            )],
+            StorageLockStatus::Held { holder: None } => {
+                vec!["錠: 保持されていますが、保持者を読めません".to_string()]
+            }
+            StorageLockStatus::Held {
+                holder: Some(holder),
+            } => vec![format!(
+                "錠: {}が保持しています",
+                describe_lock_holder(&holder)
+            )],
+        },
+    };
+    let mut display = DisplayRecorder::default();
+    for line in lines {
+        display
+            .writeln_newline(&line)
+            .expect("display recording is infallible");
+    }
+
+    let mut outcome = CommandOutcome::empty(command.kind());
+    outcome.display = display.model().clone();
+    Ok(Some(outcome))
+}
+
+fn describe_lock_holder(holder: &LockHolder) -> String {
+    format!(
+        "{}({}から)",
+        holder,
+        holder.started_at.format("%Y/%m/%d %H:%M:%S")
+    )
+}
+
+fn consistency_finding_message(finding: &ConsistencyFinding) -> String {
+    let stale_pending;
+    let (problem, repair) = match finding.code {
+        ConsistencyCode::DuplicateTaskId => ("同じidのtaskが先に出ています", ""),
+        ConsistencyCode::DoneParentWithUndoneChildren => {
+            ("完了したtaskに未完了の子があります", "未完了に戻しました")
+        }
+        ConsistencyCode::ChildDeadlineAfterAncestor => ("〆切が祖先の〆切より後です", ""),
+        ConsistencyCode::RepetitionWithoutTodo => ("繰り返しに未完了の回がありません", ""),
+        ConsistencyCode::StalePendingUntil => {
+            stale_pending = format!("保留の期限を{STALE_PENDING_DAYS}日以上過ぎています");
+            (stale_pending.as_str(), "todoに戻しました")
+        }
+        ConsistencyCode::OrphanDirectory if finding.fixable => {
+            ("project.yamlのない空のdirectoryです", "消しました")
+        }
+        ConsistencyCode::OrphanDirectory => (
+            "project.yamlのないdirectoryです。fileが残っているので消しません",
+            "",
+        ),
+        ConsistencyCode::StaleStorageRevision => (
+            "project.yamlが保存の版より後に書き換えられています",
+            "版を新しくしました",
+        ),
+    };
+    if finding.fixed {
+        format!("{problem}。{repair}。")
+    } else {
+        format!("{problem}。")
+    }
+}
+
+fn project_note_conflict_message(conflict: &ProjectNoteConflict) -> String {
+    let mut location = conflict.file_name.clone();
+    if let Some(line) = conflict.line {
+        location.push_str(&format!(":{line}"));
+    }
+    if let Some(task_name) = &conflict.task_name {
+        location.push_str(&format!(" 「{task_name}」"));
+    }
+    let reason = match conflict.kind {
+        ProjectNoteConflictKind::DuplicateProject => "同じprojectのnoteが複数あります",
+        ProjectNoteConflictKind::MissingTaskId => "Schronuのtaskと対応しない行です",
+        ProjectNoteConflictKind::UnknownTask => "Schronuにないtaskのcheckが変わっています",
+        ProjectNoteConflictKind::Reopened => "Schronuでは完了したtaskのcheckが外されています",
+        ProjectNoteConflictKind::HasUndoneChildren => "未完了の子があるので完了にできません",
+    };
+    format!("{location}: {reason}")
+}
+
+fn write_imported_task(display: &mut dyn SchronuWriter, task: &ImportedTask, depth: usize) {
+    let mut line = format!("{}{}", "  ".repeat(depth), task.name);
+    if let Some(minutes) = task.estimated_work_minutes {
+        line.push_str(&format!(" ({minutes}分)"));
+    }
+    if let Some(minutes) = task.actual_work_minutes {
+        line.push_str(&format!(" 実績{minutes}分"));
+    }
+    if let Some(deadline_time) = task.deadline_time {
+        line.push_str(&format!(" 〆{}", deadline_time.format("%Y/%m/%d %H:%M")));
+    }
+    if let Some(pending_until) = task.pending_until {
+        line.push_str(&format!(" 保留{}", pending_until.format("%Y/%m/%d %H:%M")));
+    } else if task.status == Status::Pending {
+        line.push_str(" 保留");
+    }
+    if task.status == Status::Done {
+        line.push_str(" 完了");
+    }
+    display
+        .writeln_newline(&line)
+        .expect("display recording is infallible");
+    for child in &task.children {
+        write_imported_task(display, child, depth + 1);
+    }
+}
+
+// 年を省いた月日が今日を指すように、日付は主観日の終わり際の時刻で解決する
+fn resolve_busy_block_date(
+    date: &DateTerm,
+    field: &'static str,
+    now: DateTime<Local>,
+    policy: &BusinessDateTimePolicy,
+) -> Result<NaiveDate, ApplicationError> {
+    let expression = TemporalExpression {
+        date: Some(*date),
+        time: None,
+    };
+    let end_of_date = ClockTime::new(23, 59, 59).expect("23:59:59 must be a valid clock time");
+    resolve_temporal_expression(&expression, now, policy, end_of_date)?
+        .map(|datetime| datetime.date())
+        .ok_or(ApplicationError::InvalidInput {
+            field,
+            reason: "must resolve to an existing date",
+        })
+}
+
+// 24時以降の時刻は翌暦日の時刻として扱う
+fn resolve_busy_block_time(
+    date: NaiveDate,
+    time: ClockTime,
+    now: DateTime<Local>,
+    policy: &BusinessDateTimePolicy,
+) -> Result<DateTime<Local>, ApplicationError> {
+    let expression = TemporalExpression {
+        date: Some(DateTerm::Date(date)),
+        time: Some(time),
+    };
+    let datetime = resolve_temporal_expression(&expression, now, policy, time)?.ok_or(
+        ApplicationError::InvalidInput {
+            field: "time_range",
+            reason: "must resolve to an existing date",
+        },
+    )?;
+    try_local_date_and_time(datetime.date(), datetime.time())
+}
+
+fn format_busy_block_span(span: &BusyBlockSpan) -> String {
+    match *span {
+        BusyBlockSpan::Timed { start, end } if start.date_naive() == end.date_naive() => {
+            format!("{}-{}", start.format("%Y/%m/%d %H:%M"), end.format("%H:%M"))
+        }
+        BusyBlockSpan::Timed { start, end } => format!(
+            "{} - {}",
+            start.format("%Y/%m/%d %H:%M"),
+            end.format("%Y/%m/%d %H:%M")
+        ),
+        BusyBlockSpan::AllDay {
+            start_date,
+            end_date,
+        } if start_date == end_date => format!("{} 終日", start_date.format("%Y/%m/%d")),
+        BusyBlockSpan::AllDay {
+            start_date,
+            end_date,
+        } => format!(
+            "{} - {} 終日",
+            start_date.format("%Y/%m/%d"),
+            end_date.format("%Y/%m/%d")
+        ),
+    }
+}
+
+fn write_busy_blocks(display: &mut dyn SchronuWriter, blocks: &[BusyBlock]) {
+    if blocks.is_empty() {
+        display
+            .writeln_newline("[Info] 埋まっている予定はありません。")
+            .expect("display recording is infallible");
+        return;
+    }
+
+    display
+        .writeln_newline("記録	期間	名前")
+        .expect("display recording is infallible");
+    for block in blocks {
+        display
+            .writeln_newline(&format!(
+                "埋	{}	{}	{}",
+                short_revision(block.busy_block_id),
+                format_busy_block_span(&block.span),
+                block.name,
+            ))
+            .expect("display recording is infallible");
+    }
+}
+
+pub(super) fn handle_subtree_command(
+    command: &Command,
+    context: &mut dyn SubtreeCommandContext,
+) -> Result<Option<CommandOutcome>, ApplicationError> {
+    let Command::Action(CommandAction::Subtree(subtree_command)) = command else {
+        return Ok(None);
+    };
+    let Some(task_id) = context.focused_task_id() else {
+        return Ok(Some(CommandOutcome::empty(command.kind())));
+    };
+    let message = match *subtree_command {
+        SubtreeCommand::Move { parent_id } => {
+            context.move_subtree(task_id, parent_id)?;
+            match parent_id {
+                Some(parent_id) => format!("[Info] {parent_id} の下へ移しました。"),
+                None => "[Info] 新しいprojectにしました。".to_string(),
+            }
+        }
+        SubtreeCommand::Copy { parent_id } => {
+            let copy_id = context.copy_subtree(task_id, parent_id)?;
+            format!("[Info] 複製しました: {copy_id}")
+        }
+        SubtreeCommand::Merge { source_task_id } => {
+            context.merge_sibling_tasks(task_id, source_task_id)?;
+            format!("[Info] {source_task_id} を併合しました。")
+        }
+    };
+
+    let mut display = DisplayRecorder::default();
+    display
+        .writeln_newline(&message)
+        .expect("display recording is infallible");
+    let mut outcome = CommandOutcome::empty(command.kind());
+    outcome.display = display.model().clone();
+    Ok(Some(outcome))
+}
+
+fn write_trash_entries(display: &mut dyn SchronuWriter, entries: &[TrashEntry]) {
+    if entries.is_empty() {
+        display
+            .writeln_newline("[Info] ゴミ箱は空です。")
+            .expect("display recording is infallible");
+        return;
+    }
+
+    display
+        .writeln_newline("記録	捨てた日時	件数	元の場所	名前")
+        .expect("display recording is infallible");
+    for entry in entries {
+        let origin = match (&entry.original_parent_id, &entry.project_key) {
+            (Some(parent_id), _) => format!("{parent_id} の{}番目", entry.position + 1),
+            (None, Some(project_key)) => project_key.clone(),
+            (None, None) => "(不明)".to_string(),
+        };
+        display
+            .writeln_newline(&format!(
+                "捨	{}	{}	{}	{}	{}",
+                short_revision(entry.trash_id),
+                entry.deleted_at.format("%Y/%m/%d %H:%M:%S"),
+                entry.task_count,
+                origin,
+                entry.name,
+            ))
+            .expect("display recording is infallible");
+    }
+}
+
+// projectは保存先直下のdirectory名で指定し、保存履歴の"<dir>/project.yaml"へ読み替える
+fn project_yaml_relative_path(project: &str) -> String {
+    let project = project.trim_end_matches('/');
+    if project.ends_with("/project.yaml") {
+        project.to_string()
+    } else {
+        format!("{project}/project.yaml")
+    }
+}
+
+fn project_directory_name(project: &str) -> &str {
+    project.strip_suffix("/project.yaml").unwrap_or(project)
+}
+
+fn short_revision(revision: Uuid) -> String {
+    revision.simple().to_string()[..8].to_string()
+}
+
+fn write_storage_revisions(display: &mut dyn SchronuWriter, revisions: &[StorageRevision]) {
+    if revisions.is_empty() {
+        display
+            .writeln_newline("[Info] 保存履歴はありません。")
+            .expect("display recording is infallible");
+        return;
+    }
+
+    display
+        .writeln_newline("版	保存日時	project数	変更project")
+        .expect("display recording is infallible");
+    for revision in revisions {
+        display
+            .writeln_newline(&format!(
+                "版	{}	{}	{}	{}",
+                short_revision(revision.revision),
+                revision.saved_at.format("%Y/%m/%d %H:%M:%S"),
+                revision.projects.len(),
+                revision
+                    .changed_projects
+                    .iter()
+                    .map(|project| project_directory_name(project))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ))
+            .expect("display recording is infallible");
+    }
+}
+
+fn write_storage_revision_diffs(display: &mut dyn SchronuWriter, diffs: &[ProjectRevisionDiff]) {
+    if diffs.is_empty() {
+        display
+            .writeln_newline("[Info] 差分はありません。")
+            .expect("display recording is infallible");
+        return;
+    }
+
+    for diff in diffs {
+        display
+            .writeln_newline(&format!("[{}]", project_directory_name(&diff.project)))
+            .expect("display recording is infallible");
+        for change in &diff.changes {
+            let (mark, name, fields) = match change {
+                TaskRevisionChange::Added { name, .. } => ("+", name, &[][..]),
+                TaskRevisionChange::Removed { name, .. } => ("-", name, &[][..]),
+                TaskRevisionChange::Changed { name, fields, .. } => ("~", name, fields.as_slice()),
+            };
+            display
+                .writeln_newline(&format!("{mark} {name}"))
+                .expect("display recording is infallible");
+            for field in fields {
+                display
+                    .writeln_newline(&format!(
+                        "    {}: {} -> {}",
+                        field.field,
+                        field.before.as_deref().unwrap_or("(なし)"),
+                        field.after.as_deref().unwrap_or("(なし)"),
+                    ))
+                    .expect("display recording is infallible");
+            }
+        }
+    }
+}
+
+// 「暦」と同じく、既定では今日を含む直近28日を対象にする
+const LOAD_HISTORY_DEFAULT_DAYS: i64 = 28;
+
+// [] は既定日数、[日数] は今日までの日数、[開始日] と [開始日, 終了日] は両端を含む範囲を表す
+pub(super) fn decide_report_date_range(
+    values: &[String],
+    today: NaiveDate,
+    default_days: i64,
+    command: &'static str,
+    usage: &'static str,
+) -> Result<(NaiveDate, NaiveDate), CommandParseError> {
+    let parse_date = |value: &str, field: &'static str| {
+        NaiveDate::parse_from_str(value, "%Y/%m/%d").map_err(|_| {
+            CommandParseError::new(command, field, "YYYY/MM/DD形式で指定してください", usage)
+        })
+    };
+    let days_before = |days: i64| {
+        today
+            .checked_sub_days(Days::new((days - 1) as u64))
+            .ok_or_else(|| CommandParseError::new(command, "days", "日数が大きすぎます", usage))
+    };
+
+    let (from, until) = match values {
+        [] => (days_before(default_days)?, today),
+        [value] if value.chars().all(|character| character.is_ascii_digit()) => {
+            let days = value
+                .parse::<i64>()
+                .ok()
+                .filter(|days| *days > 0)
+                .ok_or_else(|| {
+                    CommandParseError::new(command, "days", "1以上の整数で指定してください", usage)
+                })?;
+            (days_before(days)?, today)
+        }
+        [from] => (parse_date(from, "from")?, today),
+        [from, until] => (parse_date(from, "from")?, parse_date(until, "until")?),
+        _ => {
+            return Err(CommandParseError::new(
+                command,
+                "arguments",
+                "引数の個数が正しくありません",
+                usage,
+            ))
+        }
+    };
+    if from > until {
+        return Err(CommandParseError::new(
+            command,
+            "until",
+            "開始日以降の日付を指定してください",
+            usage,
+        ));
+    }
+    Ok((from, until))
+}
+
+pub(super) fn decide_finish_time_values(
+    values: &[String],
+    now: &DateTime<Local>,
+    policy: &BusinessDateTimePolicy,
+) -> Result<Option<DateTime<Local>>, ApplicationError> {
+    match values {
+        [] => Ok(Some(*now)),
+        [value] if matches!(value.as_str(), "今" | "now") => Ok(Some(*now)),
+        _ => {
+            let Some(naive_datetime) = decide_naive_datetime_values(values, now, policy)? else {
+                return Ok(None);
+            };
+            resolve_date_and_time(naive_datetime.date(), naive_datetime.time()).map(Some)
+        }
+    }
+}
+
+pub(super) fn write_pack_result(display: &mut dyn SchronuWriter, result: &PackResult) {
+    let total_work_seconds = result
+        .packed_tasks
+        .iter()
+        .map(|packed| packed.work_seconds)
+        .sum::<i64>();
+
+    for packed in &result.packed_tasks {
+        display
+            .writeln_newline(&format!(
+                "詰\t{}\t{}\t{}\t優先度{}\t{}\t{}",
+                packed.source_date,
+                packed.target_date,
+                format_work_seconds_as_hours_minutes(packed.work_seconds),
+                packed.priority,
+                packed.task_id,
+                packed.name,
+            ))
+            .expect("display recording is infallible");
+    }
+    if result.packed_tasks.is_empty() && result.skipped_tasks.is_empty() {
+        display
+            .writeln_newline("[Info] 詰められるタスクはありません。")
+            .expect("display recording is infallible");
+    } else {
+        display
+            .writeln_newline(&format!(
+                "詰: {}件 {} (スキップ{}件)",
+                result.packed_tasks.len(),
+                format_work_seconds_as_hours_minutes(total_work_seconds),
+                result.skipped_tasks.len(),
+            ))
+            .expect("display recording is infallible");
+    }
+}
+
+fn write_flatten_result(display: &mut dyn SchronuWriter, result: &FlattenResult) {
+    let total_work_seconds = result
+        .flattened_tasks
+        .iter()
+        .map(|flattened| flattened.work_seconds)
+        .sum::<i64>();
+
+    for flattened in &result.flattened_tasks {
+        display
+            .writeln_newline(&format!(
+                "平\t{}\t{}\t{}\t優先度{}\t{}\t{}",
+                flattened.source_date,
+                flattened.target_date,
+                format_work_seconds_as_hours_minutes(flattened.work_seconds),
+                flattened.priority,
+                flattened.task_id,
+                flattened.name,
+            ))
+            .expect("display recording is infallible");
+    }
+
+    if !result.had_overload {
+        display
+            .writeln_newline("[Info] 100%を超過している日はありません。")
+            .expect("display recording is infallible");
+    } else {
+        display
+            .writeln_newline(&format!(
                 "平: {}件 {}{}",
                 result.flattened_tasks.len(),
                 format_work_seconds_as_hours_minutes(total_work_seconds),
@@ -795,6 +2206,139 @@ fn write_flatten_result(display: &mut dyn SchronuWriter, result: &FlattenResult)
     }
 }
 
+// 1.0を20文字で表し、1.0を超えた分は10文字まで > で表す
+const LOAD_HISTORY_BAR_WIDTH: usize = 20;
+const LOAD_HISTORY_OVERFLOW_BAR_WIDTH: usize = 10;
+
+fn write_load_history(display: &mut dyn SchronuWriter, snapshots: &[LoadSnapshot]) {
+    if snapshots.is_empty() {
+        display
+            .writeln_newline("[Info] 指定した範囲の負荷履歴はありません。")
+            .expect("display recording is infallible");
+        return;
+    }
+
+    display
+        .writeln_newline(
+            "履	日付	空き	予定	繰返	ρ	one ρ	Lq	余差	超過	todo	pending	done	ρ(1.0=20文字)",
+        )
+        .expect("display recording is infallible");
+    for snapshot in snapshots {
+        display
+            .writeln_newline(&format!(
+                "履	{}	{}	{}	{}	{}	{}	{}	{}	{}	{}	{}	{}	{}",
+                snapshot.date,
+                format_work_seconds_as_hours_minutes(snapshot.free_minutes * 60),
+                format_work_seconds_as_hours_minutes(snapshot.scheduled_work_seconds),
+                format_work_seconds_as_hours_minutes(snapshot.repetitive_work_seconds),
+                format_optional_ratio(snapshot.rho),
+                format_optional_ratio(snapshot.non_repetitive_rho),
+                format_optional_ratio(snapshot.lq),
+                format_work_seconds_as_hours_minutes(snapshot.leeway_seconds),
+                format_work_seconds_as_hours_minutes(snapshot.overflow_seconds),
+                snapshot.status_counts.todo,
+                snapshot.status_counts.pending,
+                snapshot.status_counts.done,
+                format_load_history_bar(snapshot.rho),
+            ))
+            .expect("display recording is infallible");
+    }
+}
+
+const FLOW_METRICS_DEFAULT_DAYS: i64 = 28;
+
+// 分布はlead time(作成→完了)とcycle time(着手可能→完了)のp50/p85/最大を並べる
+fn write_flow_metrics(display: &mut dyn SchronuWriter, metrics: &FlowMetricsView) {
+    let mut lines = vec![
+        format!("流\t{} 〜 {}", metrics.from, metrics.until),
+        "流\t区分\t完了\tlead p50\tlead p85\tlead 最大\tcycle p50\tcycle p85\tcycle 最大"
+            .to_string(),
+        format_flow_distribution("全体", &metrics.overall),
+    ];
+    for row in &metrics.by_level {
+        let label = match row.level {
+            FlowTaskLevel::Root => "root",
+            FlowTaskLevel::Leaf => "葉",
+        };
+        lines.push(format_flow_distribution(label, &row.distribution));
+    }
+    for row in &metrics.by_category {
+        lines.push(format_flow_distribution(
+            project_category_symbol(row.category),
+            &row.distribution,
+        ));
+    }
+    lines.push("流\t週\t完了\t葉".to_string());
+    for week in &metrics.weekly_throughput {
+        lines.push(format!(
+            "流\t{}〜\t{}\t{}",
+            week.week_start, week.completed, week.completed_leaves
+        ));
+    }
+    lines.push("流\t日付\t仕掛(葉)\t仕掛(project)".to_string());
+    for day in &metrics.work_in_progress {
+        lines.push(format!(
+            "流\t{}\t{}\t{}",
+            day.date, day.leaves, day.projects
+        ));
+    }
+    for line in lines {
+        display
+            .writeln_newline(&line)
+            .expect("display recording is infallible");
+    }
+}
+
+fn format_flow_distribution(label: &str, distribution: &FlowDistributionView) -> String {
+    format!(
+        "流\t{label}\t{}\t{}\t{}",
+        distribution.completed,
+        format_flow_duration_stats(distribution.lead_time.as_ref()),
+        format_flow_duration_stats(distribution.cycle_time.as_ref()),
+    )
+}
+
+fn format_flow_duration_stats(stats: Option<&FlowDurationStats>) -> String {
+    stats.map_or_else(
+        || ["-"; 3].join("\t"),
+        |stats| {
+            [stats.p50_seconds, stats.p85_seconds, stats.max_seconds]
+                .map(format_flow_duration)
+                .join("\t")
+        },
+    )
+}
+
+// 1日以上は日数を前に付ける
+fn format_flow_duration(seconds: i64) -> String {
+    let days = seconds / (24 * 60 * 60);
+    let rest = format_work_seconds_as_hours_minutes(seconds % (24 * 60 * 60));
+    if days > 0 {
+        format!("{days}日{rest}")
+    } else {
+        rest
+    }
+}
+
+fn format_optional_ratio(value: Option<f64>) -> String {
+    value.map_or_else(|| "inf".to_string(), |value| format!("{value:.2}"))
+}
+
+fn format_load_history_bar(rho: Option<f64>) -> String {
+    let segments = rho.map_or(
+        LOAD_HISTORY_BAR_WIDTH + LOAD_HISTORY_OVERFLOW_BAR_WIDTH,
+        |rho| (rho.max(0.0) * LOAD_HISTORY_BAR_WIDTH as f64).round() as usize,
+    );
+    let filled = segments.min(LOAD_HISTORY_BAR_WIDTH);
+    let overflow = (segments - filled).min(LOAD_HISTORY_OVERFLOW_BAR_WIDTH);
+    format!(
+        "[{}{}]{}",
+        "#".repeat(filled),
+        ".".repeat(LOAD_HISTORY_BAR_WIDTH - filled),
+        ">".repeat(overflow)
+    )
+}
+
 fn format_work_seconds_as_hours_minutes(work_seconds: i64) -> String {
     let total_minutes = work_seconds.max(0) / 60;
     format!("{:02}:{:02}", total_minutes / 60, total_minutes % 60)
@@ -813,6 +2357,7 @@ fn unresolved_reason_label(reason: UnresolvedReason) -> &'static str {
         UnresolvedReason::ExceedsDailyCapacity => "1日の最大容量を超える",
         UnresolvedReason::OwnDeadline => "自身の期限により翌日06:00を維持できない",
         UnresolvedReason::RelatedDeadline => "仮延期によって関連taskの期限を超える",
+        UnresolvedReason::OutsideAllowedWindow => "許可された時間帯に収まらない",
         UnresolvedReason::Other => "その他",
     }
 }
@@ -1072,114 +2617,46 @@ fn execute_create_repetition_task(
 pub(super) fn decide_time_values(
     values: &[String],
     now: &DateTime<Local>,
+    policy: &BusinessDateTimePolicy,
 ) -> Result<Option<DateTime<Local>>, ApplicationError> {
-    let Some(naive_datetime) = decide_naive_datetime_values(values, now, 0)? else {
+    let Some(naive_datetime) = decide_naive_datetime_values(values, now, policy)? else {
         return Ok(None);
     };
     resolve_date_and_time(naive_datetime.date(), naive_datetime.time()).map(Some)
 }
 
+// 時刻を必須とする日時指定。解釈できない語や存在しない日付はNone、計算範囲外はerrorにする
 fn decide_naive_datetime_values(
     values: &[String],
     now: &DateTime<Local>,
-    seconds: u32,
+    policy: &BusinessDateTimePolicy,
 ) -> Result<Option<NaiveDateTime>, ApplicationError> {
-    let Some(start_hhmm_str) = values.first() else {
-        return Ok(None);
-    };
-    let start_date_str = values.get(1).map_or("dummy", String::as_str);
-    let hhmm_reg = Regex::new(r"^(\d{1,2}):(\d{1,2})$").unwrap();
-    let Some(captures) = hhmm_reg.captures(start_hhmm_str) else {
+    let tokens = values.iter().map(String::as_str).collect::<Vec<_>>();
+    let Ok(expression) = TemporalExpression::parse(&tokens) else {
         return Ok(None);
     };
-    let (Some(hh), Some(mm)) = (
-        captures[1].parse::<u32>().ok(),
-        captures[2].parse::<u32>().ok(),
-    ) else {
+    if expression.time.is_none() {
         return Ok(None);
-    };
-    let Some(time) = NaiveTime::from_hms_opt(hh, mm, seconds) else {
-        return Ok(None);
-    };
-    let yyyymmdd_reg = Regex::new(r"^(\d{2,4})/(\d{1,2})/(\d{1,2})$").unwrap();
-    let mmdd_reg = Regex::new(r"^(\d{1,2})/(\d{1,2})$").unwrap();
-
-    if let Some(captures) = yyyymmdd_reg.captures(start_date_str) {
-        let (Some(raw_year), Some(month), Some(day)) = (
-            captures[1].parse::<i32>().ok(),
-            captures[2].parse::<u32>().ok(),
-            captures[3].parse::<u32>().ok(),
-        ) else {
-            return Ok(None);
-        };
-        let year = if raw_year < 100 {
-            raw_year + 2000
-        } else {
-            raw_year
-        };
-        let Some(date) = NaiveDate::from_ymd_opt(year, month, day) else {
-            return Ok(None);
-        };
-        return Ok(Some(date.and_time(time)));
-    }
-    if let Some(captures) = mmdd_reg.captures(start_date_str) {
-        let (Some(month), Some(day)) = (
-            captures[1].parse::<u32>().ok(),
-            captures[2].parse::<u32>().ok(),
-        ) else {
-            return Ok(None);
-        };
-        let Some(mut date) = NaiveDate::from_ymd_opt(now.year(), month, day) else {
-            return Ok(None);
-        };
-        let mut answer = date.and_time(time);
-        if answer < now.naive_local() {
-            let next_year =
-                now.year()
-                    .checked_add(1)
-                    .ok_or(ApplicationError::SubjectiveDateOutOfRange {
-                        operation: "upcoming_calendar_date",
-                        datetime: *now,
-                    })?;
-            let Some(next_date) = NaiveDate::from_ymd_opt(next_year, month, day) else {
-                return Ok(None);
-            };
-            date = next_date;
-            answer = date.and_time(time);
-        }
-        return Ok(Some(answer));
-    }
-    if start_date_str.starts_with('明') {
-        let next_day = try_next_business_day_start(*now)?;
-        return Ok(Some(next_day.date_naive().and_time(time)));
     }
-    let days_of_week = ["月", "火", "水", "木", "金", "土", "日"];
-    if days_of_week.contains(&start_date_str) {
-        let next_business_day_start = try_next_business_day_start(*now)?;
-        let subjective_date = try_subjective_date(*now)?;
-        let current_index = subjective_date.weekday().num_days_from_monday() as usize;
-        let target_index = days_of_week
-            .iter()
-            .position(|day| day == &start_date_str)
-            .unwrap();
-        let difference = (7 + target_index - current_index) % 7;
-        let days = if difference == 0 {
-            7
-        } else {
-            difference as i64
-        };
-        let Some(target_date) = next_business_day_start
-            .date_naive()
-            .checked_add_days(Days::new((days - 1) as u64))
-        else {
-            return Err(ApplicationError::SubjectiveDateOutOfRange {
-                operation: "weekday_date",
-                datetime: *now,
-            });
-        };
-        return Ok(Some(target_date.and_time(time)));
+    resolve_temporal_expression(&expression, *now, policy, ClockTime::business_day_start())
+}
+
+pub(super) fn resolve_temporal_expression(
+    expression: &TemporalExpression,
+    now: DateTime<Local>,
+    policy: &BusinessDateTimePolicy,
+    default_time: ClockTime,
+) -> Result<Option<NaiveDateTime>, ApplicationError> {
+    match expression.resolve(now, policy, default_time) {
+        Ok(naive_datetime) => Ok(Some(naive_datetime)),
+        Err(TemporalResolveError::InvalidMonthDay { .. }) => Ok(None),
+        Err(TemporalResolveError::OutOfRange { operation }) => {
+            Err(ApplicationError::SubjectiveDateOutOfRange {
+                operation,
+                datetime: now,
+            })
+        }
     }
-    Ok(Some(now.date_naive().and_time(time)))
 }
 
 fn resolve_date_and_time(
@@ -1197,19 +2674,19 @@ mod datetime_resolution_tests {
     #[test]
     fn 完了時刻は指定秒を含むnaive日時を構築してから1回だけlocal変換する() {
         let now = Local.with_ymd_and_hms(2026, 10, 30, 12, 0, 0).unwrap();
-        let date_values = ["1:30".to_string(), "2026/11/1".to_string()];
+        let date_values = ["1:30:45".to_string(), "2026/11/1".to_string()];
+        let policy = BusinessDateTimePolicy::new(30);
         let expected_naive = NaiveDate::from_ymd_opt(2026, 11, 1)
             .unwrap()
             .and_hms_opt(1, 30, 45)
             .unwrap();
 
-        let naive = decide_naive_datetime_values(&date_values, &now, 45)
+        let naive = decide_naive_datetime_values(&date_values, &now, &policy)
             .expect("date resolution must succeed")
             .expect("explicit date and time must resolve to a naive datetime");
-        let localized =
-            decide_finish_time_values(&["1:30:45".to_string(), "2026/11/1".to_string()], &now)
-                .expect("application local datetime resolution must succeed")
-                .expect("the local datetime must be Single in the test timezone");
+        let localized = decide_finish_time_values(&date_values, &now, &policy)
+            .expect("application local datetime resolution must succeed")
+            .expect("the local datetime must be Single in the test timezone");
 
         assert_eq!(naive, expected_naive);
         assert_eq!(naive.second(), 45);
@@ -1221,7 +2698,9 @@ mod datetime_resolution_tests {
 #[cfg(test)]
 mod task_generation_context_tests {
     use super::*;
+    use crate::renderer::DisplayFragment;
     use chrono::TimeZone;
+    use schronu::entity::allowed_window::AllowedWindow;
     use std::collections::VecDeque;
 
     struct FixedIdentityProjectCommandContext {
@@ -1251,6 +2730,10 @@ mod task_generation_context_tests {
             self.now
         }
 
+        fn datetime_policy(&self) -> BusinessDateTimePolicy {
+            BusinessDateTimePolicy::new(30)
+        }
+
         fn focused_task(&mut self) -> Result<Option<TaskHandle>, ApplicationError> {
             unreachable!("this contract test passes the focused task explicitly")
         }
@@ -1293,6 +2776,9 @@ mod task_generation_context_tests {
             self.focused_task_id_opt = task_id_opt;
             self.focused_task_updates.push(task_id_opt);
         }
+        fn list_waiting_tasks(&self) -> Result<Vec<WaitingGroup>, ApplicationError> {
+            unreachable!("this contract test does not list waiting tasks")
+        }
     }
 
     fn task(name: &str, id: u128, now: DateTime<Local>) -> TaskHandle {
@@ -1355,6 +2841,36 @@ mod task_generation_context_tests {
         assert!(context.next_ids.is_empty());
     }
 
+    #[test]
+    fn 枠は設定と解除の後に実際に効いている枠を表示する() {
+        let now = Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap();
+        let root = task("root", 1, now);
+        root.set_allowed_window_opt(Some(AllowedWindow::parse("平日").unwrap()))
+            .unwrap();
+        let child = root
+            .create_child(TaskAttr::with_identity("電話", Uuid::from_u128(2), now))
+            .unwrap();
+        let mut display = DisplayRecorder::default();
+
+        for window_command in [
+            WindowCommand::Show,
+            WindowCommand::Set(AllowedWindow::parse("10:00-12:00").unwrap()),
+            WindowCommand::Clear,
+        ] {
+            execute_window(&mut display, &child, &window_command).unwrap();
+        }
+
+        assert_eq!(
+            display.model().fragments(),
+            [
+                DisplayFragment::Newline("枠\t月火水木金\t(祖先から継承)".to_string()),
+                DisplayFragment::Newline("枠\t10:00-12:00".to_string()),
+                DisplayFragment::Newline("枠\t月火水木金\t(祖先から継承)".to_string()),
+            ]
+        );
+        assert_eq!(child.get_allowed_window_opt().unwrap(), None);
+    }
+
     #[test]
     fn sequentialは実生成順にcontextのidentityを消費して同じ時刻を共有する() {
         let now = Local.with_ymd_and_hms(2026, 8, 19, 12, 34, 56).unwrap();
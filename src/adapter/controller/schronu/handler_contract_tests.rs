@@ -1,16 +1,52 @@
-use super::command::{Command, CommandAction, CommandKind, InteractiveShortcut};
+use super::command::{
+    BusyBlockCommand, Command, CommandAction, CommandKind, ExportCommand, GanttFormat,
+    ImportCommand, InteractiveShortcut, RevisionCommand, SubtreeCommand, TrashCommand,
+};
 use super::handler::{
-    decide_finish_time_values, decide_time_values, handle, handle_defer_command,
-    handle_task_attribute_command, handle_task_tree_command, DeferCommandContext,
-    DeferCommandError, ExternalRequest, FocusRequest, TaskAttributeCommandContext, TaskListOrder,
-    TaskTreeCommandContext,
+    decide_finish_time_values, decide_report_date_range, decide_time_values, handle,
+    handle_busy_block_command, handle_defer_command, handle_export_command, handle_import_command,
+    handle_obsidian_sync_command, handle_report_command, handle_storage_history_command,
+    handle_subtree_command, handle_task_attribute_command, handle_task_tree_command,
+    handle_trash_command, BusyBlockCommandContext, DeferCommandContext, DeferCommandError,
+    ExportCommandContext, ExternalRequest, FocusRequest, ImportCommandContext,
+    ObsidianSyncCommandContext, ReportCommandContext, ReportCommandError,
+    StorageHistoryCommandContext, SubtreeCommandContext, TaskAttributeCommandContext,
+    TaskListOrder, TaskTreeCommandContext, TrashCommandContext,
 };
 use super::renderer::{
     render_display_model, DisplayFragment, DisplayModel, DisplayRecorder, SchronuWriter,
 };
-use chrono::{Local, NaiveDate, TimeZone};
-use schronu::application::task_use_case::ApplicationError;
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Weekday};
+use schronu::adapter::gateway::task_export::{ExportFormat, TaskExportError};
+use schronu::application::at_risk::AtRiskTaskView;
+use schronu::application::busy_block::BusyBlockError;
+use schronu::application::critical_path::{CriticalPathTaskView, CriticalPathView};
+use schronu::application::flow_metrics::{
+    FlowCategoryDistributionView, FlowDistributionView, FlowDurationStats,
+    FlowLevelDistributionView, FlowMetricsView, FlowTaskLevel, WeeklyThroughputView,
+    WorkInProgressView,
+};
+use schronu::application::gantt::GanttChartView;
+use schronu::application::interface::{LoadHistoryStoreError, StorageHistoryError};
+use schronu::application::load_history::{build_load_snapshot, LoadSnapshot, StatusCounts};
+use schronu::application::project_note::{
+    NoteCompletion, ProjectNoteConflict, ProjectNoteConflictKind, ProjectNoteSyncError,
+    ProjectNoteSyncReport,
+};
+use schronu::application::storage_history::{
+    FieldRevisionChange, ProjectRevisionDiff, StorageHistoryUseCaseError, StorageRevision,
+    TaskRevisionChange,
+};
+use schronu::application::task_import::{ImportedTask, TaskOutline, TaskOutlineError};
+use schronu::application::task_tree::{TaskTreeNodeView, TaskTreeRollupView};
+use schronu::application::task_use_case::{ApplicationError, TaskView};
+use schronu::application::trash::{PurgedTrashEntry, TrashEntry, TrashError};
+use schronu::entity::busy_block::{BusyBlock, BusyBlockSpan};
+use schronu::entity::datetime::temporal::TemporalExpression;
+use schronu::entity::datetime::BusinessDateTimePolicy;
+use schronu::entity::task::{Status, TaskHandle};
 use std::io::Write;
+use std::path::PathBuf;
 use uuid::Uuid;
 
 fn no_arguments(kind: CommandKind, canonical_name: &'static str) -> Command {
@@ -20,6 +56,10 @@ fn no_arguments(kind: CommandKind, canonical_name: &'static str) -> Command {
     })
 }
 
+fn policy() -> BusinessDateTimePolicy {
+    BusinessDateTimePolicy::new(30)
+}
+
 fn maximum_local_business_day_start() -> chrono::DateTime<Local> {
     let local_datetime = NaiveDate::MAX
         .and_hms_opt(6, 0, 0)
@@ -36,7 +76,11 @@ fn 明日と曜日の日時指定は次の業務日境界errorを保持する()
 
     for date_expression in ["明", "月"] {
         assert_eq!(
-            decide_time_values(&["09:30".to_string(), date_expression.to_string()], &now),
+            decide_time_values(
+                &["09:30".to_string(), date_expression.to_string()],
+                &now,
+                &policy()
+            ),
             Err(ApplicationError::SubjectiveDateOutOfRange {
                 operation: "next_business_day_start",
                 datetime: now,
@@ -50,19 +94,27 @@ fn 明示日付と月日はsingleのlocal日時だけを返し不正日付はnon
     let now = Local.with_ymd_and_hms(2026, 8, 21, 12, 0, 0).unwrap();
 
     assert_eq!(
-        decide_time_values(&["09:30".to_string(), "2026/8/22".to_string()], &now),
+        decide_time_values(
+            &["09:30".to_string(), "2026/8/22".to_string()],
+            &now,
+            &policy()
+        ),
         Ok(Some(Local.with_ymd_and_hms(2026, 8, 22, 9, 30, 0).unwrap()))
     );
     assert_eq!(
-        decide_time_values(&["09:30".to_string(), "8/23".to_string()], &now),
+        decide_time_values(&["09:30".to_string(), "8/23".to_string()], &now, &policy()),
         Ok(Some(Local.with_ymd_and_hms(2026, 8, 23, 9, 30, 0).unwrap()))
     );
     assert_eq!(
-        decide_time_values(&["09:30".to_string()], &now),
+        decide_time_values(&["09:30".to_string()], &now, &policy()),
         Ok(Some(Local.with_ymd_and_hms(2026, 8, 21, 9, 30, 0).unwrap()))
     );
     assert_eq!(
-        decide_time_values(&["09:30".to_string(), "2026/2/30".to_string()], &now),
+        decide_time_values(
+            &["09:30".to_string(), "2026/2/30".to_string()],
+            &now,
+            &policy()
+        ),
         Ok(None)
     );
 }
@@ -71,24 +123,30 @@ fn 明示日付と月日はsingleのlocal日時だけを返し不正日付はnon
 fn 不正な時刻構文は正午へ補正せずnoneにする() {
     let now = Local.with_ymd_and_hms(2026, 8, 21, 12, 0, 0).unwrap();
 
-    assert_eq!(decide_time_values(&["invalid".to_string()], &now), Ok(None));
+    assert_eq!(
+        decide_time_values(&["invalid".to_string()], &now, &policy()),
+        Ok(None)
+    );
 }
 
 #[test]
 fn 完了時刻指定は日時errorを保持し省略と今と不正構文を区別する() {
     let now = maximum_local_business_day_start();
 
-    assert_eq!(decide_finish_time_values(&[], &now), Ok(Some(now)));
     assert_eq!(
-        decide_finish_time_values(&["今".to_string()], &now),
+        decide_finish_time_values(&[], &now, &policy()),
         Ok(Some(now))
     );
     assert_eq!(
-        decide_finish_time_values(&["invalid".to_string()], &now),
+        decide_finish_time_values(&["今".to_string()], &now, &policy()),
+        Ok(Some(now))
+    );
+    assert_eq!(
+        decide_finish_time_values(&["invalid".to_string()], &now, &policy()),
         Ok(None)
     );
     assert_eq!(
-        decide_finish_time_values(&["09:30".to_string(), "明".to_string()], &now),
+        decide_finish_time_values(&["09:30".to_string(), "明".to_string()], &now, &policy()),
         Err(ApplicationError::SubjectiveDateOutOfRange {
             operation: "next_business_day_start",
             datetime: now,
@@ -222,6 +280,8 @@ fn project作成commandはhandlerがtyped_fieldを直接matchして所有する(
 #[derive(Default)]
 struct TraceTaskTreeContext {
     calls: Vec<String>,
+    task_tree: Option<TaskTreeNodeView>,
+    critical_path: Option<CriticalPathView>,
 }
 
 impl TaskTreeCommandContext for TraceTaskTreeContext {
@@ -229,10 +289,14 @@ impl TaskTreeCommandContext for TraceTaskTreeContext {
         true
     }
 
-    fn show_tree(&mut self, display: &mut dyn SchronuWriter) -> Result<(), ApplicationError> {
+    fn task_tree(&mut self) -> Result<Option<TaskTreeNodeView>, ApplicationError> {
         self.calls.push("tree".to_string());
-        display.write_all(b"tree").unwrap();
-        Ok(())
+        Ok(self.task_tree.clone())
+    }
+
+    fn critical_path(&mut self) -> Result<Option<CriticalPathView>, ApplicationError> {
+        self.calls.push("critical_path".to_string());
+        Ok(self.critical_path.clone())
     }
 
     fn show_ancestor(&mut self, display: &mut dyn SchronuWriter) -> Result<(), ApplicationError> {
@@ -382,6 +446,164 @@ fn task_tree表示commandはhandlerがtyped_fieldから表示modelと操作要
     }
 }
 
+fn critical_path_row(
+    name: &str,
+    depth: usize,
+    start_minutes: i64,
+    float_minutes: i64,
+) -> CriticalPathTaskView {
+    let now = Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap();
+    let task = TaskHandle::with_identity(name, Uuid::new_v4(), now).unwrap();
+    let earliest_start = now + chrono::Duration::minutes(start_minutes);
+    let latest_start = earliest_start + chrono::Duration::minutes(float_minutes);
+    CriticalPathTaskView {
+        task: TaskView::try_from(&task).unwrap(),
+        depth,
+        remaining_work_seconds: 30 * 60,
+        earliest_start,
+        earliest_finish: earliest_start + chrono::Duration::minutes(30),
+        latest_start,
+        latest_finish: latest_start + chrono::Duration::minutes(30),
+        float_seconds: float_minutes * 60,
+        critical: float_minutes == 0,
+    }
+}
+
+#[test]
+fn 樹_経commandは臨界経路に印を付けて最早の開始終了と余裕を表示する() {
+    let tasks = vec![
+        critical_path_row("project", 0, 60, 0),
+        critical_path_row("下書き", 1, 30, 0),
+        critical_path_row("スライド", 1, 0, 90),
+    ];
+    let mut context = TraceTaskTreeContext {
+        critical_path: Some(CriticalPathView {
+            root_id: tasks[0].task.id,
+            project_finish: tasks[0].earliest_finish,
+            critical_chain: vec![tasks[1].task.id, tasks[0].task.id],
+            tasks,
+        }),
+        ..TraceTaskTreeContext::default()
+    };
+
+    let outcome =
+        handle_task_tree_command(&Command::Action(CommandAction::CriticalPath), &mut context)
+            .unwrap()
+            .expect("樹 経 is handled by the task tree handler");
+
+    assert_eq!(outcome.kind, CommandKind::Tree);
+    assert_eq!(context.calls, ["critical_path"]);
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        [
+            "newline:[Info] 完了見込み: 2026/10/19 10:30",
+            "newline:★\t10/19 10:00-10/19 10:30\t余裕 00:00\tproject",
+            "newline:  ★\t10/19 09:30-10/19 10:00\t余裕 00:00\t下書き",
+            "newline:  ・\t10/19 09:00-10/19 09:30\t余裕 01:30\tスライド",
+        ]
+    );
+
+    context.critical_path = None;
+    let outcome =
+        handle_task_tree_command(&Command::Action(CommandAction::CriticalPath), &mut context)
+            .unwrap()
+            .expect("樹 経 is handled by the task tree handler");
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] フォーカス中のタスクがありません。"]
+    );
+}
+
+fn task_tree_node(
+    name: &str,
+    status: Status,
+    rollup: TaskTreeRollupView,
+    children: Vec<TaskTreeNodeView>,
+) -> TaskTreeNodeView {
+    let now = Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap();
+    let task = TaskHandle::with_identity(name, Uuid::new_v4(), now).unwrap();
+    task.set_estimated_work_seconds(rollup.remaining_estimated_work_seconds)
+        .unwrap();
+    let mut task = TaskView::try_from(&task).unwrap();
+    task.status = status;
+    TaskTreeNodeView {
+        task,
+        rollup,
+        children_truncated: false,
+        children,
+    }
+}
+
+#[test]
+fn 樹commandは完了したtaskを省いて部分木の集計を添えた木を表示する() {
+    let now = Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap();
+    let leaf_rollup = |minutes: i64| TaskTreeRollupView {
+        remaining_estimated_work_seconds: minutes * 60,
+        leaf_count: 1,
+        scheduled_finish: Some(now + chrono::Duration::minutes(minutes)),
+        ..TaskTreeRollupView::default()
+    };
+    let draft = task_tree_node(
+        "下書き",
+        Status::Todo,
+        TaskTreeRollupView {
+            remaining_estimated_work_seconds: 30 * 60,
+            done_leaf_count: 1,
+            leaf_count: 2,
+            earliest_deadline_time: Some(now + chrono::Duration::days(1)),
+            scheduled_finish: Some(now + chrono::Duration::minutes(30)),
+            ..TaskTreeRollupView::default()
+        },
+        vec![
+            task_tree_node("構成", Status::Todo, leaf_rollup(30), vec![]),
+            task_tree_node(
+                "調査",
+                Status::Done,
+                TaskTreeRollupView {
+                    done_leaf_count: 1,
+                    leaf_count: 1,
+                    ..TaskTreeRollupView::default()
+                },
+                vec![],
+            ),
+        ],
+    );
+    let mut context = TraceTaskTreeContext {
+        task_tree: Some(task_tree_node(
+            "発表",
+            Status::Todo,
+            TaskTreeRollupView {
+                remaining_estimated_work_seconds: 90 * 60,
+                done_leaf_count: 1,
+                leaf_count: 3,
+                earliest_deadline_time: Some(now + chrono::Duration::days(1)),
+                scheduled_finish: Some(now + chrono::Duration::minutes(90)),
+                ..TaskTreeRollupView::default()
+            },
+            vec![
+                draft,
+                task_tree_node("スライド", Status::Pending, leaf_rollup(60), vec![]),
+            ],
+        )),
+        ..TraceTaskTreeContext::default()
+    };
+    let outcome = handle_task_tree_command(&no_arguments(CommandKind::Tree, "樹"), &mut context)
+        .unwrap()
+        .expect("樹 is handled by the task tree handler");
+
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        [
+            "newline:",
+            "newline:[ ] 00m/90m 発表\t[残1:30 完1/3 〆10/20 09:00 終10/19 10:30]",
+            "newline:|-- [ ] 00m/30m 下書き\t[残0:30 完1/2 〆10/20 09:00 終10/19 09:30]",
+            "newline:|   `-- [ ] 00m/30m 構成\t[残0:30 完0/1 終10/19 09:30]",
+            "newline:`-- [-] 00m/60m スライド\t[残1:00 完0/1 終10/19 10:00]",
+            "newline:",
+        ]
+    );
+}
+
 #[test]
 fn task_tree表示commandはruntime_fallbackに残さない() {
     let runtime_source = include_str!("runtime.rs");
@@ -480,7 +702,7 @@ fn breakdownとsplitはhandlerがtyped_fieldを直接matchして所有する() {
     for action_pattern in [
         "CommandAction::TaskNames { names }",
         "CommandAction::Split { minutes, name }",
-        "kind: CommandKind::Wait",
+        "CommandAction::Wait(wait_command)",
     ] {
         assert!(
             handler_source.contains(action_pattern),
@@ -1007,3 +1229,1307 @@ fn display_recorder_and_renderer_preserve_raw_newline_write_order_and_flush() {
     );
     assert_eq!(writer.flush_count, 1);
 }
+
+#[test]
+fn 履の範囲指定は日数と開始日と終了日を両端を含む主観日の範囲にする() {
+    let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+    let date = |day| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+    let decide = |values: &[&str]| {
+        let values = values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>();
+        decide_report_date_range(&values, today, 28, "履", "履 [日数|開始日] [終了日]")
+    };
+
+    assert_eq!(
+        decide(&[]).unwrap(),
+        (date(18) - chrono::Days::new(27), today)
+    );
+    assert_eq!(decide(&["1"]).unwrap(), (today, today));
+    assert_eq!(decide(&["7"]).unwrap(), (date(12), today));
+    assert_eq!(decide(&["2026/10/01"]).unwrap(), (date(1), today));
+    assert_eq!(
+        decide(&["2026/10/01", "2026/10/05"]).unwrap(),
+        (date(1), date(5))
+    );
+
+    assert_eq!(decide(&["0"]).unwrap_err().field(), "days");
+    assert_eq!(decide(&["10/01"]).unwrap_err().field(), "from");
+    assert_eq!(
+        decide(&["2026/10/05", "2026/10/01"]).unwrap_err().field(),
+        "until"
+    );
+}
+
+struct TraceReportContext {
+    snapshots: Vec<LoadSnapshot>,
+    requested_ranges: Vec<(NaiveDate, NaiveDate)>,
+    at_risk_tasks: Vec<AtRiskTaskView>,
+    requested_gantt_days: Vec<u64>,
+    written_files: Vec<(String, String)>,
+}
+
+impl ReportCommandContext for TraceReportContext {
+    fn last_synced_time(&self) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap()
+    }
+
+    fn datetime_policy(&self) -> BusinessDateTimePolicy {
+        BusinessDateTimePolicy::new(30).with_non_business_weekdays(&[Weekday::Sat, Weekday::Sun])
+    }
+
+    fn load_history(
+        &mut self,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<Vec<LoadSnapshot>, LoadHistoryStoreError> {
+        self.requested_ranges.push((from, until));
+        Ok(self.snapshots.clone())
+    }
+
+    // 完了1件の固定の集計を返す
+    fn flow_metrics(
+        &mut self,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<FlowMetricsView, ApplicationError> {
+        self.requested_ranges.push((from, until));
+        let stats = FlowDurationStats {
+            min_seconds: 90 * 60,
+            p50_seconds: 90 * 60,
+            p85_seconds: 90 * 60,
+            max_seconds: 90 * 60,
+            mean_seconds: 90 * 60,
+        };
+        let distribution = FlowDistributionView {
+            completed: 1,
+            lead_time: Some(FlowDurationStats {
+                max_seconds: 26 * 60 * 60,
+                ..stats.clone()
+            }),
+            cycle_time: Some(stats),
+        };
+        Ok(FlowMetricsView {
+            from,
+            until,
+            overall: distribution.clone(),
+            by_level: vec![
+                FlowLevelDistributionView {
+                    level: FlowTaskLevel::Root,
+                    distribution: FlowDistributionView::default(),
+                },
+                FlowLevelDistributionView {
+                    level: FlowTaskLevel::Leaf,
+                    distribution: distribution.clone(),
+                },
+            ],
+            by_category: vec![FlowCategoryDistributionView {
+                category: None,
+                distribution,
+            }],
+            weekly_throughput: vec![WeeklyThroughputView {
+                week_start: from,
+                completed: 1,
+                completed_leaves: 1,
+            }],
+            work_in_progress: vec![WorkInProgressView {
+                date: until,
+                leaves: 3,
+                projects: 2,
+            }],
+        })
+    }
+
+    fn list_at_risk_tasks(&mut self) -> Result<Vec<AtRiskTaskView>, ApplicationError> {
+        Ok(self.at_risk_tasks.clone())
+    }
+
+    fn gantt_chart(&mut self, days: u64) -> Result<GanttChartView, ApplicationError> {
+        self.requested_gantt_days.push(days);
+        Ok(GanttChartView {
+            generated_at: self.last_synced_time(),
+            lanes: vec![],
+        })
+    }
+
+    fn write_report_file(&mut self, file_name: &str, contents: &str) -> std::io::Result<PathBuf> {
+        self.written_files
+            .push((file_name.to_string(), contents.to_string()));
+        Ok(PathBuf::from("/tmp").join(file_name))
+    }
+}
+
+fn load_history_command(values: &[&str]) -> Command {
+    Command::Action(CommandAction::DateRange {
+        kind: CommandKind::LoadHistory,
+        canonical_name: "履",
+        values: values.iter().map(|value| value.to_string()).collect(),
+    })
+}
+
+fn rendered_lines(display: &DisplayModel) -> Vec<String> {
+    let mut writer = TraceWriter::default();
+    render_display_model(&mut writer, display).unwrap();
+    writer.writes
+}
+
+#[test]
+fn 履commandは負荷履歴を日付ごとの表とrhoの棒で表示する() {
+    let snapshot = |day, scheduled_work_seconds| {
+        build_load_snapshot(
+            NaiveDate::from_ymd_opt(2026, 10, day).unwrap(),
+            Local.with_ymd_and_hms(2026, 10, day, 9, 0, 0).unwrap(),
+            10 * 60,
+            scheduled_work_seconds,
+            2 * 3600,
+            StatusCounts {
+                todo: 4,
+                pending: 2,
+                done: 9,
+            },
+        )
+    };
+    let mut context = TraceReportContext {
+        snapshots: vec![snapshot(17, 6 * 3600), snapshot(18, 12 * 3600)],
+        requested_ranges: vec![],
+        at_risk_tasks: vec![],
+        requested_gantt_days: vec![],
+        written_files: vec![],
+    };
+
+    let outcome = handle_report_command(&load_history_command(&["2"]), &mut context)
+        .unwrap()
+        .expect("履 is handled by the report handler");
+
+    assert_eq!(outcome.kind, CommandKind::LoadHistory);
+    assert_eq!(
+        context.requested_ranges,
+        [(
+            NaiveDate::from_ymd_opt(2026, 10, 17).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+        )]
+    );
+    let lines = rendered_lines(&outcome.display);
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("newline:履\t日付\t空き"));
+    assert_eq!(
+        lines[1],
+        "newline:履\t2026-10-17\t10:00\t06:00\t02:00\t0.60\t0.50\t1.50\t01:36\t00:00\t4\t2\t9\t[############........]"
+    );
+    assert!(lines[2].contains("\t1.20\t1.25\tinf\t00:00\t02:00\t"));
+    assert!(lines[2].ends_with("[####################]>>>>"));
+}
+
+#[test]
+fn 履commandは範囲errorを構文errorとして返し記録がなければinfoを表示する() {
+    let mut context = TraceReportContext {
+        snapshots: vec![],
+        requested_ranges: vec![],
+        at_risk_tasks: vec![],
+        requested_gantt_days: vec![],
+        written_files: vec![],
+    };
+
+    let error = handle_report_command(&load_history_command(&["abc"]), &mut context).unwrap_err();
+    assert!(matches!(error, ReportCommandError::Parse(error) if error.field() == "from"));
+    assert!(context.requested_ranges.is_empty());
+
+    let outcome = handle_report_command(&load_history_command(&[]), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 指定した範囲の負荷履歴はありません。"]
+    );
+    assert!(handle_report_command(&Command::Noop, &mut context)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn 流commandは範囲を決めてlead_timeとcycle_timeの分布と週ごとの完了と仕掛りを表にする() {
+    let mut context = TraceReportContext {
+        snapshots: vec![],
+        requested_ranges: vec![],
+        at_risk_tasks: vec![],
+        requested_gantt_days: vec![],
+        written_files: vec![],
+    };
+    let command = Command::Action(CommandAction::DateRange {
+        kind: CommandKind::FlowMetrics,
+        canonical_name: "流",
+        values: vec!["2026/10/12".to_string()],
+    });
+
+    let outcome = handle_report_command(&command, &mut context)
+        .unwrap()
+        .expect("流 is handled by the report handler");
+
+    assert_eq!(outcome.kind, CommandKind::FlowMetrics);
+    assert_eq!(
+        context.requested_ranges,
+        [(
+            NaiveDate::from_ymd_opt(2026, 10, 12).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+        )]
+    );
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        [
+            "newline:流\t2026-10-12 〜 2026-10-18",
+            "newline:流\t区分\t完了\tlead p50\tlead p85\tlead 最大\tcycle p50\tcycle p85\tcycle 最大",
+            "newline:流\t全体\t1\t01:30\t01:30\t1日02:00\t01:30\t01:30\t01:30",
+            "newline:流\troot\t0\t-\t-\t-\t-\t-\t-",
+            "newline:流\t葉\t1\t01:30\t01:30\t1日02:00\t01:30\t01:30\t01:30",
+            "newline:流\t_\t1\t01:30\t01:30\t1日02:00\t01:30\t01:30\t01:30",
+            "newline:流\t週\t完了\t葉",
+            "newline:流\t2026-10-12〜\t1\t1",
+            "newline:流\t日付\t仕掛(葉)\t仕掛(project)",
+            "newline:流\t2026-10-18\t3\t2",
+        ]
+    );
+}
+
+struct TraceStorageHistoryContext {
+    revisions: Vec<StorageRevision>,
+    diffs: Vec<ProjectRevisionDiff>,
+    requested_restores: Vec<(Uuid, Option<String>)>,
+}
+
+impl StorageHistoryCommandContext for TraceStorageHistoryContext {
+    fn list_revisions(&self) -> Result<Vec<StorageRevision>, StorageHistoryError> {
+        Ok(self.revisions.clone())
+    }
+
+    fn diff_revisions(
+        &self,
+        from: Uuid,
+        until: Option<Uuid>,
+    ) -> Result<Vec<ProjectRevisionDiff>, StorageHistoryError> {
+        assert_eq!(
+            from,
+            Uuid::parse_str("aaaa1111-0000-4000-8000-000000000000").unwrap()
+        );
+        assert!(until.is_none_or(|until| until == self.revisions[0].revision));
+        Ok(self.diffs.clone())
+    }
+
+    fn restore_revision(
+        &mut self,
+        revision: Uuid,
+        project: Option<&str>,
+    ) -> Result<StorageRevision, StorageHistoryUseCaseError> {
+        self.requested_restores
+            .push((revision, project.map(str::to_string)));
+        Ok(self.revisions[0].clone())
+    }
+}
+
+fn storage_history_context() -> TraceStorageHistoryContext {
+    let revision = |text: &str, hour, changed_projects: &[&str]| StorageRevision {
+        revision: Uuid::parse_str(text).unwrap(),
+        saved_at: Local.with_ymd_and_hms(2026, 10, 18, hour, 0, 0).unwrap(),
+        projects: vec![
+            "20261018-a/project.yaml".to_string(),
+            "20261018-b/project.yaml".to_string(),
+        ],
+        changed_projects: changed_projects
+            .iter()
+            .map(|project| project.to_string())
+            .collect(),
+    };
+    TraceStorageHistoryContext {
+        revisions: vec![
+            revision(
+                "bbbb2222-0000-4000-8000-000000000000",
+                10,
+                &["20261018-a/project.yaml"],
+            ),
+            revision(
+                "aaaa1111-0000-4000-8000-000000000000",
+                9,
+                &["20261018-a/project.yaml", "20261018-b/project.yaml"],
+            ),
+        ],
+        diffs: vec![],
+        requested_restores: vec![],
+    }
+}
+
+fn revision_command(command: RevisionCommand) -> Command {
+    Command::Action(CommandAction::Revision(command))
+}
+
+#[test]
+fn 版commandは保存履歴を新しい順の表で表示する() {
+    let mut context = storage_history_context();
+
+    let outcome =
+        handle_storage_history_command(&revision_command(RevisionCommand::List), &mut context)
+            .unwrap()
+            .expect("版 is handled by the storage history handler");
+
+    assert_eq!(outcome.kind, CommandKind::Revision);
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        [
+            "newline:版\t保存日時\tproject数\t変更project",
+            "newline:版\tbbbb2222\t2026/10/18 10:00:00\t2\t20261018-a",
+            "newline:版\taaaa1111\t2026/10/18 09:00:00\t2\t20261018-a, 20261018-b",
+        ]
+    );
+    assert!(handle_storage_history_command(&Command::Noop, &mut context)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn 版_差はtask単位の追加削除と変更fieldを表示する() {
+    let mut context = storage_history_context();
+    context.diffs = vec![ProjectRevisionDiff {
+        project: "20261018-a/project.yaml".to_string(),
+        changes: vec![
+            TaskRevisionChange::Added {
+                id: "added".to_string(),
+                name: "追加".to_string(),
+            },
+            TaskRevisionChange::Changed {
+                id: "changed".to_string(),
+                name: "変更".to_string(),
+                fields: vec![FieldRevisionChange {
+                    field: "status".to_string(),
+                    before: Some("todo".to_string()),
+                    after: None,
+                }],
+            },
+            TaskRevisionChange::Removed {
+                id: "removed".to_string(),
+                name: "削除".to_string(),
+            },
+        ],
+    }];
+
+    let outcome = handle_storage_history_command(
+        &revision_command(RevisionCommand::Diff {
+            from: "aaaa".to_string(),
+            until: Some("BBBB2222".to_string()),
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        [
+            "newline:[20261018-a]",
+            "newline:+ 追加",
+            "newline:~ 変更",
+            "newline:    status: todo -> (なし)",
+            "newline:- 削除",
+        ]
+    );
+
+    context.diffs = vec![];
+    let outcome = handle_storage_history_command(
+        &revision_command(RevisionCommand::Diff {
+            from: "aaaa".to_string(),
+            until: None,
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 差分はありません。"]
+    );
+}
+
+#[test]
+fn 版_戻はproject名をproject_yamlのpathへ読み替え未知の版では戻さない() {
+    let mut context = storage_history_context();
+
+    let outcome = handle_storage_history_command(
+        &revision_command(RevisionCommand::Restore {
+            revision: "aaaa".to_string(),
+            project: Some("20261018-b".to_string()),
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(
+        context.requested_restores,
+        [(
+            Uuid::parse_str("aaaa1111-0000-4000-8000-000000000000").unwrap(),
+            Some("20261018-b/project.yaml".to_string())
+        )]
+    );
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 版 aaaa1111 から 20261018-b を戻しました。新しい版: bbbb2222"]
+    );
+
+    let error = handle_storage_history_command(
+        &revision_command(RevisionCommand::Restore {
+            revision: "cccc".to_string(),
+            project: None,
+        }),
+        &mut context,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        StorageHistoryUseCaseError::RevisionNotFound(reference) if reference == "cccc"
+    ));
+    assert_eq!(context.requested_restores.len(), 1);
+}
+
+struct TraceTrashContext {
+    focused_task_id: Option<Uuid>,
+    entries: Vec<TrashEntry>,
+    deleted_task_ids: Vec<Uuid>,
+    restored_trash_ids: Vec<Uuid>,
+    purged_trash_ids: Vec<Uuid>,
+}
+
+impl TrashCommandContext for TraceTrashContext {
+    fn focused_task_id(&self) -> Option<Uuid> {
+        self.focused_task_id
+    }
+
+    fn delete_task(&mut self, task_id: Uuid) -> Result<TrashEntry, TrashError> {
+        self.deleted_task_ids.push(task_id);
+        Ok(self.entries[0].clone())
+    }
+
+    fn list_trash(&self) -> Result<Vec<TrashEntry>, TrashError> {
+        Ok(self.entries.clone())
+    }
+
+    fn restore_trash_entry(&mut self, trash_id: Uuid) -> Result<TrashEntry, TrashError> {
+        self.restored_trash_ids.push(trash_id);
+        Ok(self.entries[1].clone())
+    }
+
+    fn purge_trash_entry(&mut self, trash_id: Uuid) -> Result<PurgedTrashEntry, TrashError> {
+        self.purged_trash_ids.push(trash_id);
+        Ok(PurgedTrashEntry {
+            entry: self.entries[1].clone(),
+            removed_project_directory: true,
+        })
+    }
+}
+
+fn trash_context() -> TraceTrashContext {
+    let entry = |text: &str, hour, name: &str, parent: Option<&str>| TrashEntry {
+        trash_id: Uuid::parse_str(text).unwrap(),
+        deleted_at: Local.with_ymd_and_hms(2026, 10, 18, hour, 0, 0).unwrap(),
+        task_id: Uuid::new_v4(),
+        name: name.to_string(),
+        task_count: 2,
+        original_parent_id: parent.map(|parent| Uuid::parse_str(parent).unwrap()),
+        position: 1,
+        project_key: parent.is_none().then(|| "20261018-計画".to_string()),
+    };
+    TraceTrashContext {
+        focused_task_id: None,
+        entries: vec![
+            entry(
+                "dddd4444-0000-4000-8000-000000000000",
+                10,
+                "下書き",
+                Some("00000000-0000-4000-8000-000000000001"),
+            ),
+            entry("cccc3333-0000-4000-8000-000000000000", 9, "計画", None),
+        ],
+        deleted_task_ids: vec![],
+        restored_trash_ids: vec![],
+        purged_trash_ids: vec![],
+    }
+}
+
+fn trash_command(command: TrashCommand) -> Command {
+    Command::Action(CommandAction::Trash(command))
+}
+
+#[test]
+fn 捨commandは指定がなければfocus中のtaskを捨て一覧に元の場所を表示する() {
+    let mut context = trash_context();
+
+    let outcome = handle_trash_command(
+        &trash_command(TrashCommand::Delete { task_id: None }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+    assert!(context.deleted_task_ids.is_empty());
+    assert_eq!(outcome.kind, CommandKind::Trash);
+    assert!(rendered_lines(&outcome.display).is_empty());
+
+    let focused_task_id = Uuid::new_v4();
+    context.focused_task_id = Some(focused_task_id);
+    let outcome = handle_trash_command(
+        &trash_command(TrashCommand::Delete { task_id: None }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(context.deleted_task_ids, [focused_task_id]);
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 下書き (2件) を捨てました。記録: dddd4444"]
+    );
+
+    let outcome = handle_trash_command(&trash_command(TrashCommand::List), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        [
+            "newline:記録\t捨てた日時\t件数\t元の場所\t名前",
+            "newline:捨\tdddd4444\t2026/10/18 10:00:00\t2\t00000000-0000-4000-8000-000000000001 の2番目\t下書き",
+            "newline:捨\tcccc3333\t2026/10/18 09:00:00\t2\t20261018-計画\t計画",
+        ]
+    );
+    assert!(handle_trash_command(&Command::Noop, &mut context)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn 捨_戻と消は記録を先頭部分で解決し未知の記録では何もしない() {
+    let mut context = trash_context();
+    let planned = Uuid::parse_str("cccc3333-0000-4000-8000-000000000000").unwrap();
+
+    let outcome = handle_trash_command(
+        &trash_command(TrashCommand::Restore {
+            entry: "CCCC".to_string(),
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(context.restored_trash_ids, [planned]);
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 計画 (2件) を戻しました。"]
+    );
+
+    let outcome = handle_trash_command(
+        &trash_command(TrashCommand::Purge {
+            entry: "cccc3333".to_string(),
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(context.purged_trash_ids, [planned]);
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 計画 (2件) を完全に消しました。空のproject directoryも消しました。"]
+    );
+
+    let error = handle_trash_command(
+        &trash_command(TrashCommand::Purge {
+            entry: "eeee".to_string(),
+        }),
+        &mut context,
+    )
+    .unwrap_err();
+    assert!(matches!(error, TrashError::EntryNotFound(reference) if reference == "eeee"));
+    assert_eq!(context.purged_trash_ids.len(), 1);
+}
+
+#[derive(Default)]
+struct TraceImportContext {
+    focused_task_id: Option<Uuid>,
+    outline: TaskOutline,
+    calls: Vec<String>,
+}
+
+impl ImportCommandContext for TraceImportContext {
+    fn focused_task_id(&self) -> Option<Uuid> {
+        self.focused_task_id
+    }
+
+    fn read_task_outline(&self, path: &str) -> Result<TaskOutline, TaskOutlineError> {
+        if path == "missing.md" {
+            return Err(TaskOutlineError::Line {
+                line: 3,
+                reason: "task name must not be empty".to_string(),
+            });
+        }
+        Ok(self.outline.clone())
+    }
+
+    fn import_task_outline(
+        &mut self,
+        outline: &TaskOutline,
+        parent_id: Option<Uuid>,
+    ) -> Result<Vec<Uuid>, ApplicationError> {
+        self.calls
+            .push(format!("import:{}:{parent_id:?}", outline.task_count()));
+        Ok(vec![Uuid::from_u128(0x44)])
+    }
+}
+
+#[test]
+fn 取の表示は書き出した実績と保留と完了を添える() {
+    let mut project = ImportedTask::new("旅行");
+    let mut waiting = ImportedTask::new("返事待ち");
+    waiting.status = Status::Pending;
+    let mut done = ImportedTask::new("宿");
+    done.status = Status::Done;
+    done.actual_work_minutes = Some(20);
+    project.children = vec![waiting, done];
+    let mut context = TraceImportContext {
+        outline: TaskOutline {
+            tasks: vec![project],
+            skipped_done_count: 0,
+        },
+        ..TraceImportContext::default()
+    };
+
+    let preview = handle_import_command(
+        &Command::Action(CommandAction::Import(ImportCommand {
+            path: "plan.md".to_string(),
+            under_focus: false,
+            commit: false,
+        })),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(
+        rendered_lines(&preview.display),
+        [
+            "newline:旅行",
+            "newline:  返事待ち 保留",
+            "newline:  宿 実績20分 完了",
+            "newline:[Info] 3件を読みました。取り込むには確定を付けて実行してください。",
+        ]
+    );
+}
+
+#[test]
+fn 取は読み込んだ木を見せ確定の時だけfocus中のtaskの下へ取り込む() {
+    let import_command = |under_focus, commit| {
+        Command::Action(CommandAction::Import(ImportCommand {
+            path: "plan.md".to_string(),
+            under_focus,
+            commit,
+        }))
+    };
+    let mut project = ImportedTask::new("旅行");
+    project.deadline_time = Some(Local.with_ymd_and_hms(2026, 10, 23, 19, 0, 0).unwrap());
+    let mut child = ImportedTask::new("切符");
+    child.estimated_work_minutes = Some(30);
+    project.children = vec![child];
+    let mut context = TraceImportContext {
+        outline: TaskOutline {
+            tasks: vec![project],
+            skipped_done_count: 1,
+        },
+        ..TraceImportContext::default()
+    };
+
+    let preview = handle_import_command(&import_command(false, false), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(preview.kind, CommandKind::Import);
+    assert_eq!(
+        rendered_lines(&preview.display),
+        [
+            "newline:旅行 〆2026/10/23 19:00",
+            "newline:  切符 (30分)",
+            "newline:[Info] 2件を読みました。完了済みの1件は読み飛ばしました。取り込むには確定を付けて実行してください。",
+        ]
+    );
+    assert!(context.calls.is_empty());
+
+    let without_focus = handle_import_command(&import_command(true, true), &mut context)
+        .unwrap()
+        .unwrap();
+    assert!(rendered_lines(&without_focus.display).is_empty());
+    assert!(context.calls.is_empty());
+
+    context.focused_task_id = Some(Uuid::from_u128(1));
+    let imported = handle_import_command(&import_command(true, true), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        rendered_lines(&imported.display).last().unwrap(),
+        "newline:[Info] 2件を取り込みました。完了済みの1件は読み飛ばしました。"
+    );
+    assert_eq!(
+        context.calls,
+        ["import:2:Some(00000000-0000-0000-0000-000000000001)"]
+    );
+
+    let error = handle_import_command(
+        &Command::Action(CommandAction::Import(ImportCommand {
+            path: "missing.md".to_string(),
+            under_focus: false,
+            commit: true,
+        })),
+        &mut context,
+    )
+    .unwrap_err();
+    assert_eq!(error.to_string(), "line 3: task name must not be empty");
+    assert_eq!(context.calls.len(), 1);
+}
+
+#[derive(Default)]
+struct TraceExportContext {
+    focused_task_id: Option<Uuid>,
+    calls: Vec<String>,
+}
+
+impl ExportCommandContext for TraceExportContext {
+    fn focused_task_id(&self) -> Option<Uuid> {
+        self.focused_task_id
+    }
+
+    fn task_tree(&mut self, task_id: Uuid) -> Result<TaskTreeNodeView, ApplicationError> {
+        self.calls.push(format!("tree:{task_id}"));
+        let mut done = task_tree_node("宿", Status::Done, TaskTreeRollupView::default(), vec![]);
+        done.task.original_status = Status::Done;
+        Ok(task_tree_node(
+            "旅行",
+            Status::Todo,
+            TaskTreeRollupView {
+                remaining_estimated_work_seconds: 30 * 60,
+                ..TaskTreeRollupView::default()
+            },
+            vec![done],
+        ))
+    }
+
+    fn write_task_export(&mut self, path: &str, text: &str) -> Result<(), TaskExportError> {
+        self.calls
+            .push(format!("write:{path}:{}", text.lines().count()));
+        Ok(())
+    }
+}
+
+#[test]
+fn 出はfocus中の部分木を画面に出すかfileの拡張子の形式で書き出す() {
+    let export_command = |format, path: Option<&str>| {
+        Command::Action(CommandAction::Export(ExportCommand {
+            format,
+            path: path.map(str::to_string),
+        }))
+    };
+    let mut context = TraceExportContext::default();
+
+    let without_focus = handle_export_command(&export_command(None, None), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(without_focus.kind, CommandKind::Export);
+    assert!(rendered_lines(&without_focus.display).is_empty());
+    assert!(context.calls.is_empty());
+
+    context.focused_task_id = Some(Uuid::from_u128(1));
+    let printed = handle_export_command(&export_command(None, None), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        rendered_lines(&printed.display),
+        ["newline:- [ ] 旅行 (30m)", "newline:  - [x] 宿"]
+    );
+
+    let written = handle_export_command(&export_command(None, Some("plan.opml")), &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        rendered_lines(&written.display),
+        ["newline:[Info] plan.opmlに書き出しました。"]
+    );
+    handle_export_command(
+        &export_command(Some(ExportFormat::Markdown), Some("plan.opml")),
+        &mut context,
+    )
+    .unwrap();
+    assert_eq!(
+        context.calls,
+        [
+            "tree:00000000-0000-0000-0000-000000000001",
+            "tree:00000000-0000-0000-0000-000000000001",
+            "write:plan.opml:11",
+            "tree:00000000-0000-0000-0000-000000000001",
+            "write:plan.opml:2",
+        ]
+    );
+}
+
+#[derive(Default)]
+struct TraceObsidianSyncContext {
+    has_vault: bool,
+    sync_count: usize,
+}
+
+impl ObsidianSyncCommandContext for TraceObsidianSyncContext {
+    fn has_obsidian_vault(&self) -> bool {
+        self.has_vault
+    }
+
+    fn sync_project_notes(&mut self) -> Result<ProjectNoteSyncReport, ProjectNoteSyncError> {
+        self.sync_count += 1;
+        let conflict = |line, kind| ProjectNoteConflict {
+            file_name: "旅行.md".to_string(),
+            line,
+            task_name: Some("宿".to_string()),
+            kind,
+        };
+        Ok(ProjectNoteSyncReport {
+            completed: vec![NoteCompletion {
+                task_id: Uuid::from_u128(1),
+                name: "切符".to_string(),
+            }],
+            written_file_names: vec!["引越.md".to_string()],
+            conflicts: vec![
+                conflict(Some(8), ProjectNoteConflictKind::Reopened),
+                conflict(Some(9), ProjectNoteConflictKind::HasUndoneChildren),
+            ],
+        })
+    }
+}
+
+#[test]
+fn 黒_同はvaultが未設定なら案内しnoteのcheckによる完了と食い違いを報告する() {
+    let command = Command::Action(CommandAction::ObsidianSync);
+    let mut context = TraceObsidianSyncContext::default();
+
+    let without_vault = handle_obsidian_sync_command(&command, &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(without_vault.kind, CommandKind::Obsidian);
+    assert_eq!(
+        rendered_lines(&without_vault.display),
+        ["newline:[Info] obsidian_vault_pathを設定すると、project noteを同期できます。"]
+    );
+    assert_eq!(context.sync_count, 0);
+
+    context.has_vault = true;
+    let synced = handle_obsidian_sync_command(&command, &mut context)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        rendered_lines(&synced.display),
+        [
+            "newline:[Info] noteのcheckから「切符」を完了にしました。",
+            "newline:[Warn] 旅行.md:8 「宿」: Schronuでは完了したtaskのcheckが外されています",
+            "newline:[Warn] 旅行.md:9 「宿」: 未完了の子があるので完了にできません",
+            "newline:[Info] 1件のnoteを書き出しました。食い違いのあった1件のnoteは書き直していません。",
+        ]
+    );
+    assert_eq!(context.sync_count, 1);
+    assert!(handle_obsidian_sync_command(
+        &Command::Action(CommandAction::NoArguments {
+            kind: CommandKind::Obsidian,
+            canonical_name: "黒",
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .is_none());
+}
+
+#[derive(Default)]
+struct TraceSubtreeContext {
+    focused_task_id: Option<Uuid>,
+    calls: Vec<String>,
+}
+
+impl SubtreeCommandContext for TraceSubtreeContext {
+    fn focused_task_id(&self) -> Option<Uuid> {
+        self.focused_task_id
+    }
+
+    fn move_subtree(
+        &mut self,
+        task_id: Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<(), ApplicationError> {
+        self.calls.push(format!("move:{task_id}:{parent_id:?}"));
+        Ok(())
+    }
+
+    fn copy_subtree(
+        &mut self,
+        task_id: Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<Uuid, ApplicationError> {
+        self.calls.push(format!("copy:{task_id}:{parent_id:?}"));
+        Ok(Uuid::from_u128(0x39))
+    }
+
+    fn merge_sibling_tasks(
+        &mut self,
+        task_id: Uuid,
+        source_task_id: Uuid,
+    ) -> Result<(), ApplicationError> {
+        self.calls.push(format!("merge:{task_id}:{source_task_id}"));
+        Err(ApplicationError::InvalidInput {
+            field: "source_task_id",
+            reason: "must be a sibling of task_id",
+        })
+    }
+}
+
+#[test]
+fn 移と複と併はfocus中のtaskを対象にしfocusがなければ何もしない() {
+    let subtree_command = |command| Command::Action(CommandAction::Subtree(command));
+    let parent_id = Uuid::from_u128(2);
+    let mut context = TraceSubtreeContext::default();
+
+    let outcome = handle_subtree_command(
+        &subtree_command(SubtreeCommand::Move { parent_id: None }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+    assert!(context.calls.is_empty());
+    assert!(rendered_lines(&outcome.display).is_empty());
+
+    context.focused_task_id = Some(Uuid::from_u128(1));
+    let moved = handle_subtree_command(
+        &subtree_command(SubtreeCommand::Move {
+            parent_id: Some(parent_id),
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+    let copied = handle_subtree_command(
+        &subtree_command(SubtreeCommand::Copy { parent_id: None }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+    let error = handle_subtree_command(
+        &subtree_command(SubtreeCommand::Merge {
+            source_task_id: parent_id,
+        }),
+        &mut context,
+    )
+    .unwrap_err();
+
+    assert_eq!(moved.kind, CommandKind::Move);
+    assert_eq!(
+        rendered_lines(&moved.display),
+        ["newline:[Info] 00000000-0000-0000-0000-000000000002 の下へ移しました。"]
+    );
+    assert_eq!(copied.kind, CommandKind::Copy);
+    assert_eq!(
+        rendered_lines(&copied.display),
+        ["newline:[Info] 複製しました: 00000000-0000-0000-0000-000000000039"]
+    );
+    assert!(matches!(error, ApplicationError::InvalidInput { .. }));
+    assert_eq!(
+        context.calls,
+        [
+            "move:00000000-0000-0000-0000-000000000001:Some(00000000-0000-0000-0000-000000000002)",
+            "copy:00000000-0000-0000-0000-000000000001:None",
+            "merge:00000000-0000-0000-0000-000000000001:00000000-0000-0000-0000-000000000002",
+        ]
+    );
+    assert!(handle_subtree_command(&Command::Noop, &mut context)
+        .unwrap()
+        .is_none());
+}
+
+fn when_command(values: &[&str]) -> Command {
+    Command::Action(CommandAction::TimeExpression {
+        kind: CommandKind::When,
+        canonical_name: "時",
+        values: values.iter().map(|value| value.to_string()).collect(),
+    })
+}
+
+#[test]
+fn 時commandは日時表現の解決結果を曜日付きで表示する() {
+    let mut context = TraceReportContext {
+        snapshots: vec![],
+        requested_ranges: vec![],
+        at_risk_tasks: vec![],
+        requested_gantt_days: vec![],
+        written_files: vec![],
+    };
+
+    for (values, expected) in [
+        (&["+1bd"][..], "newline:時\t+1bd\t2026/10/19(月) 06:00:00"),
+        (
+            &["末", "25:30"][..],
+            "newline:時\t末 25:30\t2026/11/01(日) 01:30:00",
+        ),
+        (&["2週後"][..], "newline:時\t2週後\t2026/11/01(日) 06:00:00"),
+    ] {
+        let outcome = handle_report_command(&when_command(values), &mut context)
+            .unwrap()
+            .expect("時 is handled by the report handler");
+
+        assert_eq!(outcome.kind, CommandKind::When);
+        assert_eq!(rendered_lines(&outcome.display), [expected]);
+    }
+}
+
+#[test]
+fn 時commandは解釈できない表現と存在しない日付を構文errorにする() {
+    let mut context = TraceReportContext {
+        snapshots: vec![],
+        requested_ranges: vec![],
+        at_risk_tasks: vec![],
+        requested_gantt_days: vec![],
+        written_files: vec![],
+    };
+
+    for (values, reason) in [
+        (&["そのうち"][..], "解釈できない語があります"),
+        (&["明", "金"][..], "日付が重複しています"),
+        (&["2/30"][..], "存在しない日付です"),
+    ] {
+        let error = handle_report_command(&when_command(values), &mut context).unwrap_err();
+
+        assert!(
+            matches!(&error, ReportCommandError::Parse(error) if error.reason() == reason),
+            "{values:?}"
+        );
+    }
+}
+
+fn at_risk_row(
+    name: &str,
+    slack_seconds: i64,
+    inherited: bool,
+    recoverable_by_deferring: bool,
+) -> AtRiskTaskView {
+    let now = Local.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+    let task = TaskHandle::with_identity(name, Uuid::new_v4(), now).unwrap();
+    let task = TaskView::try_from(&task).unwrap();
+    let deadline_time = Local.with_ymd_and_hms(2026, 10, 19, 18, 0, 0).unwrap();
+    AtRiskTaskView {
+        deadline_task_id: if inherited { Uuid::nil() } else { task.id },
+        task,
+        deadline_time,
+        scheduled_end: deadline_time - chrono::Duration::seconds(slack_seconds),
+        slack_seconds,
+        recoverable_by_deferring,
+    }
+}
+
+#[test]
+fn 危commandは余裕と〆切の出どころと回復の可否を表示する() {
+    let mut context = TraceReportContext {
+        snapshots: vec![],
+        requested_ranges: vec![],
+        at_risk_tasks: vec![
+            at_risk_row("遅れる作業", -90 * 60, true, true),
+            at_risk_row("余裕のある作業", 26 * 60 * 60, false, false),
+        ],
+        requested_gantt_days: vec![],
+        written_files: vec![],
+    };
+
+    let outcome = handle_report_command(&no_arguments(CommandKind::AtRisk, "危"), &mut context)
+        .unwrap()
+        .expect("危 is handled by the report handler");
+
+    assert_eq!(outcome.kind, CommandKind::AtRisk);
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        [
+            "newline:危\t-01:30\t〆 2026/10/19 18:00(祖先)\t終 2026/10/19 19:30\t[後回しで回復]\t遅れる作業",
+            "newline:危\t+26:00\t〆 2026/10/19 18:00\t終 2026/10/18 16:00\t\t余裕のある作業",
+        ]
+    );
+
+    context.at_risk_tasks.clear();
+    let outcome = handle_report_command(&no_arguments(CommandKind::AtRisk, "危"), &mut context)
+        .unwrap()
+        .expect("危 is handled by the report handler");
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 〆切のある未完了タスクはありません。"]
+    );
+}
+
+#[test]
+fn 図commandは予定図を書き出してbrowserで開く要求を返す() {
+    let mut context = TraceReportContext {
+        snapshots: vec![],
+        requested_ranges: vec![],
+        at_risk_tasks: vec![],
+        requested_gantt_days: vec![],
+        written_files: vec![],
+    };
+
+    let outcome = handle_report_command(
+        &Command::Action(CommandAction::Gantt {
+            days: 7,
+            format: GanttFormat::Html,
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .expect("図 is handled by the report handler");
+
+    assert_eq!(outcome.kind, CommandKind::Gantt);
+    assert_eq!(context.requested_gantt_days, [7]);
+    assert_eq!(context.written_files.len(), 1);
+    assert_eq!(context.written_files[0].0, "schronu_gantt.html");
+    assert!(context.written_files[0].1.starts_with("<!DOCTYPE html>"));
+    assert_eq!(
+        outcome.external_request,
+        Some(ExternalRequest::BrowseFile(PathBuf::from(
+            "/tmp/schronu_gantt.html"
+        )))
+    );
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 予定図を書き出しました: /tmp/schronu_gantt.html"]
+    );
+
+    handle_report_command(
+        &Command::Action(CommandAction::Gantt {
+            days: 3,
+            format: GanttFormat::Svg,
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .expect("図 is handled by the report handler");
+    assert_eq!(context.written_files[1].0, "schronu_gantt.svg");
+    assert!(context.written_files[1].1.starts_with("<svg "));
+}
+
+#[derive(Default)]
+struct TraceBusyBlockContext {
+    blocks: Vec<BusyBlock>,
+}
+
+impl BusyBlockCommandContext for TraceBusyBlockContext {
+    fn last_synced_time(&self) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap()
+    }
+
+    fn datetime_policy(&self) -> BusinessDateTimePolicy {
+        BusinessDateTimePolicy::new(30)
+    }
+
+    fn list_busy_blocks(&self) -> Result<Vec<BusyBlock>, BusyBlockError> {
+        Ok(self.blocks.clone())
+    }
+
+    fn add_busy_block(
+        &mut self,
+        name: &str,
+        span: BusyBlockSpan,
+    ) -> Result<BusyBlock, BusyBlockError> {
+        let block = BusyBlock {
+            busy_block_id: Uuid::parse_str(if self.blocks.is_empty() {
+                "aaaa1111-0000-4000-8000-000000000000"
+            } else {
+                "bbbb2222-0000-4000-8000-000000000000"
+            })
+            .unwrap(),
+            name: name.to_string(),
+            span,
+        };
+        self.blocks.push(block.clone());
+        Ok(block)
+    }
+
+    fn remove_busy_block(&mut self, busy_block_id: Uuid) -> Result<BusyBlock, BusyBlockError> {
+        let index = self
+            .blocks
+            .iter()
+            .position(|block| block.busy_block_id == busy_block_id)
+            .ok_or_else(|| BusyBlockError::BlockNotFound(busy_block_id.to_string()))?;
+        Ok(self.blocks.remove(index))
+    }
+}
+
+fn busy_block_command(command: BusyBlockCommand) -> Command {
+    Command::Action(CommandAction::BusyBlock(command))
+}
+
+#[test]
+fn 埋commandは日時表現を解決して入れ一覧に並べ記録の先頭部分で消す() {
+    let date = |value: &str| TemporalExpression::parse_str(value).unwrap().date.unwrap();
+    let time = |value: &str| TemporalExpression::parse_str(value).unwrap().time.unwrap();
+    let mut context = TraceBusyBlockContext::default();
+
+    let outcome =
+        handle_busy_block_command(&busy_block_command(BusyBlockCommand::List), &mut context)
+            .unwrap()
+            .unwrap();
+    assert_eq!(outcome.kind, CommandKind::BusyBlock);
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 埋まっている予定はありません。"]
+    );
+
+    let outcome = handle_busy_block_command(
+        &busy_block_command(BusyBlockCommand::AddTimed {
+            date: date("10/22"),
+            start: time("14:00"),
+            end: time("15:30"),
+            name: "歯医者".to_string(),
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 2026/10/22 14:00-15:30 歯医者 を入れました。記録: aaaa1111"]
+    );
+    handle_busy_block_command(
+        &busy_block_command(BusyBlockCommand::AddAllDay {
+            start_date: date("10/20"),
+            end_date: Some(date("10/21")),
+            name: "休暇".to_string(),
+        }),
+        &mut context,
+    )
+    .unwrap();
+
+    let outcome =
+        handle_busy_block_command(&busy_block_command(BusyBlockCommand::List), &mut context)
+            .unwrap()
+            .unwrap();
+    assert_eq!(
+        rendered_lines(&outcome.display)[0],
+        "newline:記録\t期間\t名前"
+    );
+    assert_eq!(rendered_lines(&outcome.display).len(), 3);
+
+    let outcome = handle_busy_block_command(
+        &busy_block_command(BusyBlockCommand::Remove {
+            block: "BBBB".to_string(),
+        }),
+        &mut context,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        rendered_lines(&outcome.display),
+        ["newline:[Info] 2026/10/20 - 2026/10/21 終日 休暇 を消しました。空き時間への反映は次の起動からです。"]
+    );
+    assert_eq!(context.blocks.len(), 1);
+    assert!(matches!(
+        handle_busy_block_command(
+            &busy_block_command(BusyBlockCommand::Remove {
+                block: "cccc".to_string(),
+            }),
+            &mut context,
+        ),
+        Err(BusyBlockError::BlockNotFound(_))
+    ));
+}
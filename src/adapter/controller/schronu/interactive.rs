@@ -1,7 +1,9 @@
 use super::renderer::{writeln_newline, SchronuWriter, MAX_COL};
+use super::storage_watcher::spawn_storage_watcher;
 use chrono::{DateTime, Local};
 use std::fmt::Display;
 use std::io::{stdout, Write};
+use std::path::Path;
 use std::sync::mpsc::Receiver;
 use std::sync::mpsc::{self, RecvTimeoutError};
 use std::thread;
@@ -17,6 +19,8 @@ const IDLE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
 pub(super) enum DriverEvent<'a> {
     RenderScreen { now: DateTime<Local> },
     Refresh,
+    // 他processが保存先を更新した
+    StorageChanged,
     Submit { line: &'a str },
     Exit,
     Interrupted,
@@ -26,6 +30,8 @@ pub(super) enum DriverEvent<'a> {
 
 pub(super) enum DriverOutcome<R, E> {
     Continue,
+    // 再読込の結果、画面を描き直す必要がない
+    Unchanged,
     Submitted,
     Retry(R),
     Exit,
@@ -61,20 +67,25 @@ fn control_key(key: &Key, line_is_empty: bool) -> Option<ControlKey> {
     }
 }
 
+// key入力と保存先の変更通知を1つのchannelで待つ
+enum DriverInput {
+    Key(std::io::Result<Key>),
+    StorageChanged,
+}
+
 enum ReceivedInput {
     Key(Key),
     Refresh,
+    StorageChanged,
     ReadError(std::io::Error),
     Disconnected,
 }
 
-fn receive_input(
-    receiver: &Receiver<std::io::Result<Key>>,
-    wait_duration: Duration,
-) -> ReceivedInput {
+fn receive_input(receiver: &Receiver<DriverInput>, wait_duration: Duration) -> ReceivedInput {
     match receiver.recv_timeout(wait_duration) {
-        Ok(Ok(key)) => ReceivedInput::Key(key),
-        Ok(Err(error)) => ReceivedInput::ReadError(error),
+        Ok(DriverInput::Key(Ok(key))) => ReceivedInput::Key(key),
+        Ok(DriverInput::Key(Err(error))) => ReceivedInput::ReadError(error),
+        Ok(DriverInput::StorageChanged) => ReceivedInput::StorageChanged,
         Err(RecvTimeoutError::Timeout) => ReceivedInput::Refresh,
         Err(RecvTimeoutError::Disconnected) => ReceivedInput::Disconnected,
     }
@@ -179,6 +190,7 @@ fn clear_screen(stdout: &mut dyn SchronuWriter) {
 
 pub(super) fn run<R, E>(
     initial_now: DateTime<Local>,
+    storage_directory: &Path,
     mut handle_event: impl FnMut(&mut dyn SchronuWriter, DriverEvent<'_>) -> DriverOutcome<R, E>,
 ) -> Result<(), E>
 where
@@ -200,13 +212,17 @@ where
     render_prompt(&mut stdout, header, &line, cursor_x);
 
     let (key_sender, key_receiver) = mpsc::channel();
+    let storage_sender = key_sender.clone();
     thread::spawn(move || {
         for key_result in std::io::stdin().keys() {
-            if key_sender.send(key_result).is_err() {
+            if key_sender.send(DriverInput::Key(key_result)).is_err() {
                 break;
             }
         }
     });
+    spawn_storage_watcher(storage_directory.to_path_buf(), move || {
+        storage_sender.send(DriverInput::StorageChanged).is_ok()
+    });
 
     let mut next_refresh_at = idle_refresh_deadline(Instant::now());
     let mut loop_error_opt = None;
@@ -224,9 +240,15 @@ where
                 ));
                 break;
             }
-            ReceivedInput::Refresh => {
-                match handle_event(&mut stdout, DriverEvent::Refresh) {
+            received @ (ReceivedInput::Refresh | ReceivedInput::StorageChanged) => {
+                let event = match received {
+                    ReceivedInput::StorageChanged => DriverEvent::StorageChanged,
+                    _ => DriverEvent::Refresh,
+                };
+                match handle_event(&mut stdout, event) {
                     DriverOutcome::Continue => {}
+                    // 変更がなければ入力途中の行を消さず、idle timerもそのまま進める
+                    DriverOutcome::Unchanged => continue,
                     DriverOutcome::Retry(error) => {
                         writeln_newline(&mut stdout, &format!("[Error] {error}")).unwrap();
                         render_prompt(&mut stdout, header, &line, cursor_x);
@@ -420,22 +442,27 @@ mod tests {
     #[test]
     fn receiver_maps_key_read_error_disconnect_and_refresh() {
         let (sender, receiver) = mpsc::channel();
-        sender.send(Ok(Key::Ctrl('c'))).unwrap();
+        sender.send(DriverInput::Key(Ok(Key::Ctrl('c')))).unwrap();
         assert!(matches!(
             receive_input(&receiver, Duration::ZERO),
             ReceivedInput::Key(Key::Ctrl('c'))
         ));
 
         sender
-            .send(Err(std::io::Error::new(
+            .send(DriverInput::Key(Err(std::io::Error::new(
                 std::io::ErrorKind::BrokenPipe,
                 "read failure",
-            )))
+            ))))
             .unwrap();
         assert!(matches!(
             receive_input(&receiver, Duration::ZERO),
             ReceivedInput::ReadError(error) if error.kind() == std::io::ErrorKind::BrokenPipe
         ));
+        sender.send(DriverInput::StorageChanged).unwrap();
+        assert!(matches!(
+            receive_input(&receiver, Duration::ZERO),
+            ReceivedInput::StorageChanged
+        ));
         assert!(matches!(
             receive_input(&receiver, Duration::ZERO),
             ReceivedInput::Refresh
@@ -1,3 +1,4 @@
+use schronu::entity::task::ProjectCategory;
 use std::io::{IsTerminal, Stdout, Write};
 use termion::raw::RawTerminal;
 
@@ -33,6 +34,19 @@ pub(super) fn format_spreadsheet_task_row(row: &SpreadsheetTaskRow<'_>) -> Strin
     )
 }
 
+pub(super) fn project_category_symbol(
+    project_category_opt: Option<ProjectCategory>,
+) -> &'static str {
+    match project_category_opt {
+        Some(ProjectCategory::Earning) => "獲",
+        Some(ProjectCategory::Sustaining) => "維",
+        Some(ProjectCategory::Recovery) => "回",
+        Some(ProjectCategory::Investment) => "資",
+        Some(ProjectCategory::Consumption) => "消",
+        None => "_",
+    }
+}
+
 pub(super) trait SchronuWriter: Write {
     fn writeln_newline(&mut self, message: &str) -> Result<(), std::io::Error>;
 
@@ -246,6 +260,49 @@ impl SchronuWriter for Stdout {
     }
 }
 
+// schronudで実行したcommandの出力を溜めて、接続してきたCLIへまとめて返す
+#[cfg(unix)]
+pub(super) struct CapturedOutput {
+    buffer: Vec<u8>,
+    supports_ansi_color: bool,
+}
+
+#[cfg(unix)]
+impl CapturedOutput {
+    pub(super) fn new(supports_ansi_color: bool) -> Self {
+        Self {
+            buffer: Vec::new(),
+            supports_ansi_color,
+        }
+    }
+
+    pub(super) fn into_string(self) -> String {
+        String::from_utf8_lossy(&self.buffer).into_owned()
+    }
+}
+
+#[cfg(unix)]
+impl Write for CapturedOutput {
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        self.buffer.write(buffer)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+impl SchronuWriter for CapturedOutput {
+    fn writeln_newline(&mut self, message: &str) -> Result<(), std::io::Error> {
+        writeln!(self, "{}", message)
+    }
+
+    fn supports_ansi_color(&self) -> bool {
+        self.supports_ansi_color
+    }
+}
+
 pub(super) fn writeln_newline(
     writer: &mut dyn SchronuWriter,
     message: &str,
@@ -6,11 +6,18 @@ use super::command::{
 #[cfg(test)]
 use super::handler::{decide_finish_time_values, decide_time_values, write_pack_result};
 use super::handler::{
-    handle, handle_breakdown_split_command, handle_defer_command, handle_finish_placement_command,
-    handle_project_command, handle_task_attribute_command, handle_task_tree_command,
-    CommandOutcome, DeferCommandContext, DeferCommandError, ExternalRequest,
-    FinishPlacementCommandContext, FocusRequest, ProjectCommandContext,
-    TaskAttributeCommandContext, TaskListOrder, TaskTreeCommandContext,
+    handle, handle_breakdown_split_command, handle_busy_block_command, handle_defer_command,
+    handle_export_command, handle_finish_placement_command, handle_import_command,
+    handle_lock_command, handle_obsidian_sync_command, handle_project_command,
+    handle_report_command, handle_storage_history_command, handle_subtree_command,
+    handle_task_attribute_command, handle_task_tree_command, handle_trash_command,
+    handle_verify_command, resolve_temporal_expression, write_task_tree, BusyBlockCommandContext,
+    CommandOutcome, DeferCommandContext, DeferCommandError, ExportCommandContext, ExternalRequest,
+    FinishPlacementCommandContext, FocusRequest, ImportCommandContext, LockCommandContext,
+    ObsidianSyncCommandContext, ProjectCommandContext, ReportCommandContext, ReportCommandError,
+    StorageHistoryCommandContext, SubtreeCommandContext, TaskAttributeCommandContext,
+    TaskListOrder, TaskTreeCommandContext, TrashCommandContext, VerifyCommandContext,
+    VerifyCommandError,
 };
 use super::interactive;
 #[cfg(test)]
@@ -18,19 +25,54 @@ use super::interactive::{
     backward_width, get_byte_offset_for_deletion, get_byte_offset_for_insert, get_forward_width,
     get_width_for_rerender, idle_refresh_deadline, idle_wait_duration,
 };
+#[cfg(unix)]
+use super::renderer::CapturedOutput;
 use super::renderer::{
-    format_spreadsheet_task_row, render_display_model, render_plain_display_model, writeln_newline,
-    DisplayModel, ErrorCapturingWriter, SchronuWriter, SpreadsheetTaskRow,
+    format_spreadsheet_task_row, project_category_symbol, render_display_model,
+    render_plain_display_model, writeln_newline, DisplayModel, ErrorCapturingWriter, SchronuWriter,
+    SpreadsheetTaskRow,
 };
 use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 #[cfg(test)]
 use chrono::{FixedOffset, TimeZone, Timelike};
 use percent_encoding::{percent_encode, AsciiSet, CONTROLS};
 use regex::Regex;
+#[cfg(unix)]
+use schronu::adapter::daemon::{
+    bind_daemon_socket, remove_daemon_socket, spawn_daemon_listener, DaemonClient, DaemonEvent,
+    DaemonRequest, DaemonResponse,
+};
+use schronu::adapter::gateway::busy_block::BusyBlockFile;
 use schronu::adapter::gateway::free_time_manager::FreeTimeManager;
-use schronu::adapter::gateway::schronu_config::{load_schronu_config, SchronuConfig};
-use schronu::adapter::gateway::storage_lock::{LockMode, StorageLock, StorageLockError};
+use schronu::adapter::gateway::load_history::LoadHistoryFile;
+use schronu::adapter::gateway::project_note::ProjectNoteDirectory;
+use schronu::adapter::gateway::schronu_config::{
+    load_schronu_config, SchronuConfig, StorageBackend,
+};
+use schronu::adapter::gateway::sqlite_task_repository::SqliteTaskRepository;
+use schronu::adapter::gateway::storage_consistency::check_storage_consistency;
+use schronu::adapter::gateway::storage_encryption::{
+    ensure_plaintext_output_allowed, ensure_unencrypted_storage, open_storage_cipher,
+    StorageCipher, StorageEncryptionError, StorageKeySource,
+};
+use schronu::adapter::gateway::storage_history::{task_tree_revision_nodes, StorageHistory};
+use schronu::adapter::gateway::storage_lock::{
+    inspect_storage_lock, LockMode, StorageLock, StorageLockError, StorageLockStatus,
+};
+use schronu::adapter::gateway::task_export::{write_task_export, TaskExportError};
+use schronu::adapter::gateway::task_import::{read_task_outline, ImportClock};
 use schronu::adapter::gateway::task_repository::TaskRepository;
+use schronu::adapter::gateway::trash::TrashFile;
+use schronu::adapter::gateway::work_session::WorkSession;
+#[cfg(unix)]
+use schronu::adapter::mcp::{McpServer, McpSession};
+use schronu::application::at_risk::{list_at_risk_tasks, AtRiskTaskView};
+use schronu::application::busy_block::{
+    add_busy_block, list_busy_blocks, load_busy_blocks, register_busy_blocks, reload_busy_blocks,
+    remove_busy_block, BusyBlockError,
+};
+use schronu::application::consistency::{check_task_consistency, ConsistencyFinding};
+use schronu::application::critical_path::{compute_critical_path, CriticalPathView};
 use schronu::application::daily_capacity::{
     calculate_daily_rho_diff_hours,
     calculate_free_time_minutes_for_subjective_date_with_end_of_day_offset_minutes,
@@ -41,36 +83,70 @@ use schronu::application::daily_capacity::{
 use schronu::application::flatten_use_case::{
     flatten_tasks_with_end_of_day_offset_minutes, FlattenResult,
 };
+use schronu::application::flow_metrics::{calculate_flow_metrics, FlowMetricsView};
+use schronu::application::gantt::{build_gantt_chart, GanttChartView};
 use schronu::application::interface::{BusyTimeSlotLoadError, FreeTimeManagerTrait};
 #[cfg(test)]
+use schronu::application::interface::{BusyTimeSlotRegistrationError, TaskRepositoryOperation};
 use schronu::application::interface::{
-    BusyTimeSlotRegistrationError, RepositoryReloadOutcome, TaskRepositoryOperation,
+    LoadHistoryStoreError, ProjectNoteStoreError, RepositoryReloadOutcome, StorageHistoryError,
+    TaskRepositoryError, TaskRepositoryTrait,
+};
+use schronu::application::load_history::{
+    capture_load_snapshot, list_load_history, record_load_snapshot_if_absent, LoadHistoryError,
+    LoadSnapshot,
 };
-use schronu::application::interface::{TaskRepositoryError, TaskRepositoryTrait};
 use schronu::application::pack_use_case::{pack_tasks_with_end_of_day_offset_minutes, PackResult};
+use schronu::application::project_note::{
+    sync_project_notes, ProjectNoteSyncError, ProjectNoteSyncReport,
+};
 use schronu::application::repository_transaction::{
     run_repository_transaction, RepositoryTransactionError,
 };
-use schronu::application::schedule_use_case::get_schedule;
+use schronu::application::schedule_use_case::{
+    get_back_to_back_schedule, get_schedule, get_schedule_with_unscheduled, UnscheduledReason,
+};
+use schronu::application::storage_history::{
+    diff_storage_revisions, diff_task_nodes, list_storage_revisions, restore_storage_revision,
+    ProjectRevisionDiff, StorageHistoryUseCaseError, StorageRevision, TaskRevisionChange,
+};
+use schronu::application::subtree::{copy_subtree, merge_sibling_tasks, move_subtree};
+use schronu::application::task_import::{
+    import_task_outline, TaskImportError, TaskOutline, TaskOutlineError,
+};
+use schronu::application::task_tree::{
+    get_task_tree, get_task_tree_without_schedule, TaskTreeNodeView,
+};
 use schronu::application::task_use_case::{
     breakdown_task, complete_task, create_task, defer_task, estimated_work_seconds_from_minutes,
     get_focus, set_category, set_deadline, set_estimate, validate_task_name, ApplicationError,
     BreakdownTaskInput, CompleteTaskInput, CreateTaskInput, TaskFactory,
 };
-use schronu::entity::datetime::parse_local_datetime;
+use schronu::application::trash::{
+    delete_task, list_trash, purge_trash_entry, restore_trash_entry, PurgedTrashEntry, TrashEntry,
+    TrashError,
+};
+use schronu::application::waiting::{list_waiting_tasks, WaitingGroup};
+use schronu::entity::busy_block::{BusyBlock, BusyBlockSpan};
+use schronu::entity::datetime::holiday::is_japanese_holiday;
+use schronu::entity::datetime::temporal::{
+    ClockTime, DateTerm, TemporalExpression, TemporalParseError,
+};
+use schronu::entity::datetime::{parse_local_datetime, BusinessDateTimePolicy};
 use schronu::entity::task::{
     extract_leaf_tasks_from_project, extract_leaf_tasks_from_project_with_pending,
     read_project_category, round_up_sec_as_minute, ProjectCategory, Status, TaskAttr, TaskHandle,
     TaskTreeError,
 };
 #[cfg(test)]
+use schronu::entity::waiting::WaitingRecord;
+#[cfg(test)]
 use std::cell::{Cell, RefCell};
 use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::io::{stdout, Write};
-#[cfg(test)]
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::OnceLock;
 
@@ -93,6 +169,7 @@ const FOCUS_PROGRESS_BAR_SEGMENTS: usize = 100;
 const CLI_LOCK_TIMEOUT: StdDuration = StdDuration::from_secs(1);
 
 static ACTIVE_CONFIG: OnceLock<SchronuConfig> = OnceLock::new();
+static ACTIVE_STORAGE_CIPHER: OnceLock<Option<StorageCipher>> = OnceLock::new();
 
 #[cfg(test)]
 trait TaskHandleTestExt {
@@ -142,6 +219,38 @@ fn active_config() -> &'static SchronuConfig {
     ACTIVE_CONFIG.get_or_init(SchronuConfig::default)
 }
 
+// 暗号化したYAML保存先なら鍵を確かめ、保存履歴や捨てた記録を読むときのために覚えておく
+fn open_active_storage_cipher(
+    storage_directory: &Path,
+) -> Result<Option<StorageCipher>, StorageEncryptionError> {
+    let cipher = open_storage_cipher(
+        storage_directory,
+        &StorageKeySource::from_env(active_config().storage_encryption_key_file_path.clone()),
+    )?;
+    let _ = ACTIVE_STORAGE_CIPHER.set(cipher.clone());
+    Ok(cipher)
+}
+
+// SQLite保存先は暗号化できないので、.encryptionや鍵の設定があれば開かずに止める
+fn ensure_active_storage_unencrypted(
+    storage_directory: &Path,
+) -> Result<(), StorageEncryptionError> {
+    ensure_unencrypted_storage(
+        storage_directory,
+        &StorageKeySource::from_env(active_config().storage_encryption_key_file_path.clone()),
+    )
+}
+
+fn active_storage_cipher() -> Option<StorageCipher> {
+    ACTIVE_STORAGE_CIPHER.get().cloned().flatten()
+}
+
+fn datetime_policy(config: &SchronuConfig) -> BusinessDateTimePolicy {
+    BusinessDateTimePolicy::new(config.end_of_day_offset_minutes)
+        .with_non_business_weekdays(&config.non_business_weekdays)
+        .with_holiday_weekday(config.holiday_weekday)
+}
+
 // パーセントエンコーディングする対象にスペースを追加する
 const MY_ASCII_SET: &AsciiSet = &CONTROLS.add(b' ');
 const OBSIDIAN_VAULT_ASCII_SET: &AsciiSet = &MY_ASCII_SET.add(b'&').add(b'=');
@@ -157,6 +266,7 @@ enum FocusSelectionMode {
 enum RunError {
     Command(CommandError),
     BusyTimeSlots(BusyTimeSlotLoadError),
+    BusyBlocks(BusyBlockError),
     Repository(TaskRepositoryError),
     CliRepositoryTransaction(CliRepositoryTransactionError),
     InputDisconnected {
@@ -185,6 +295,15 @@ enum CommandError {
         target: &'static str,
         source: Box<dyn std::error::Error + Send + Sync>,
     },
+    LoadHistory(LoadHistoryStoreError),
+    StorageHistory(StorageHistoryUseCaseError),
+    Trash(TrashError),
+    BusyBlock(BusyBlockError),
+    TaskImport(TaskImportError),
+    TaskExport(TaskExportError),
+    ProjectNoteSync(ProjectNoteSyncError),
+    Verify(VerifyCommandError),
+    StorageLock(StorageLockError),
 }
 
 impl std::fmt::Display for CommandError {
@@ -196,6 +315,15 @@ impl std::fmt::Display for CommandError {
             Self::ExternalOpen { target, source } => {
                 write!(formatter, "外部起動エラー ({target}): {source}")
             }
+            Self::LoadHistory(error) => write!(formatter, "負荷履歴エラー: {error}"),
+            Self::StorageHistory(error) => write!(formatter, "保存履歴エラー: {error}"),
+            Self::Trash(error) => write!(formatter, "ゴミ箱エラー: {error}"),
+            Self::BusyBlock(error) => write!(formatter, "予定エラー: {error}"),
+            Self::TaskImport(error) => write!(formatter, "取り込みエラー: {error}"),
+            Self::TaskExport(error) => write!(formatter, "書き出しエラー: {error}"),
+            Self::ProjectNoteSync(error) => write!(formatter, "note同期エラー: {error}"),
+            Self::Verify(error) => write!(formatter, "検証エラー: {error}"),
+            Self::StorageLock(error) => write!(formatter, "錠エラー: {error}"),
         }
     }
 }
@@ -207,6 +335,15 @@ impl std::error::Error for CommandError {
             Self::Application(error) => Some(error),
             Self::Output(error) => Some(error),
             Self::ExternalOpen { source, .. } => Some(source.as_ref()),
+            Self::LoadHistory(error) => Some(error),
+            Self::StorageHistory(error) => Some(error),
+            Self::Trash(error) => Some(error),
+            Self::BusyBlock(error) => Some(error),
+            Self::TaskImport(error) => Some(error),
+            Self::TaskExport(error) => Some(error),
+            Self::ProjectNoteSync(error) => Some(error),
+            Self::Verify(error) => Some(error),
+            Self::StorageLock(error) => Some(error),
         }
     }
 }
@@ -292,6 +429,65 @@ impl From<ApplicationError> for CommandError {
     }
 }
 
+impl From<ReportCommandError> for CommandError {
+    fn from(error: ReportCommandError) -> Self {
+        match error {
+            ReportCommandError::Parse(error) => Self::Parse(error),
+            ReportCommandError::Application(error) => Self::Application(error),
+            ReportCommandError::LoadHistory(error) => Self::LoadHistory(error),
+            ReportCommandError::Output(error) => Self::Output(error),
+        }
+    }
+}
+
+impl From<StorageHistoryUseCaseError> for CommandError {
+    fn from(error: StorageHistoryUseCaseError) -> Self {
+        Self::StorageHistory(error)
+    }
+}
+
+impl From<TrashError> for CommandError {
+    fn from(error: TrashError) -> Self {
+        Self::Trash(error)
+    }
+}
+
+impl From<BusyBlockError> for CommandError {
+    fn from(error: BusyBlockError) -> Self {
+        Self::BusyBlock(error)
+    }
+}
+
+impl From<TaskImportError> for CommandError {
+    fn from(error: TaskImportError) -> Self {
+        Self::TaskImport(error)
+    }
+}
+
+impl From<TaskExportError> for CommandError {
+    fn from(error: TaskExportError) -> Self {
+        Self::TaskExport(error)
+    }
+}
+
+impl From<ProjectNoteSyncError> for CommandError {
+    fn from(error: ProjectNoteSyncError) -> Self {
+        Self::ProjectNoteSync(error)
+    }
+}
+
+impl From<VerifyCommandError> for CommandError {
+    fn from(error: VerifyCommandError) -> Self {
+        Self::Verify(error)
+    }
+}
+
+impl From<StorageLockError> for CommandError {
+    fn from(error: StorageLockError) -> Self {
+        Self::StorageLock(error)
+    }
+}
+
 fn command_parse_error(
     command: &'static str,
     field: &'static str,
@@ -355,6 +551,11 @@ impl From<BusyTimeSlotLoadError> for RunError {
         Self::BusyTimeSlots(error)
     }
 }
+impl From<BusyBlockError> for RunError {
+    fn from(error: BusyBlockError) -> Self {
+        Self::BusyBlocks(error)
+    }
+}
 
 impl From<CliRepositoryTransactionError> for RunError {
     fn from(error: CliRepositoryTransactionError) -> Self {
@@ -389,6 +590,7 @@ impl std::fmt::Display for RunError {
         match self {
             Self::Command(error) => error.fmt(formatter),
             Self::BusyTimeSlots(error) => error.fmt(formatter),
+            Self::BusyBlocks(error) => error.fmt(formatter),
             Self::Repository(error) => error.fmt(formatter),
             Self::CliRepositoryTransaction(error) => error.fmt(formatter),
             Self::InputDisconnected {
@@ -432,6 +634,7 @@ impl std::error::Error for RunError {
         match self {
             Self::Command(error) => Some(error),
             Self::BusyTimeSlots(error) => Some(error),
+            Self::BusyBlocks(error) => Some(error),
             Self::Repository(error) => Some(error),
             Self::CliRepositoryTransaction(error) => Some(error),
             Self::InputDisconnected { save_error_opt } => save_error_opt
@@ -461,77 +664,74 @@ fn get_weekday_jp_from_weekday(weekday: Weekday) -> &'static str {
     }
 }
 
-fn resolve_upcoming_mmdd(
-    mmdd: &str,
+fn resolve_temporal_datetime(
+    expression: &TemporalExpression,
     now: DateTime<Local>,
+    default_time: ClockTime,
 ) -> Result<Option<DateTime<Local>>, ApplicationError> {
-    let mmdd_reg = Regex::new(r"^(\d{1,2})/(\d{1,2})$").unwrap();
-    let Some(caps) = mmdd_reg.captures(mmdd) else {
-        return Ok(None);
-    };
-    let (Some(month), Some(day)) = (caps[1].parse::<u32>().ok(), caps[2].parse::<u32>().ok())
+    let Some(naive_datetime) = resolve_temporal_expression(
+        expression,
+        now,
+        &datetime_policy(active_config()),
+        default_time,
+    )?
     else {
         return Ok(None);
     };
+    try_local_date_and_time(naive_datetime.date(), naive_datetime.time()).map(Some)
+}
 
-    let validation_year = 2000 + now.year().rem_euclid(400);
-    if NaiveDate::from_ymd_opt(validation_year, month, day).is_none() {
-        return Ok(None);
-    }
-    let out_of_range = || ApplicationError::SubjectiveDateOutOfRange {
-        operation: "upcoming_calendar_date",
-        datetime: now,
-    };
-    let current_year_date =
-        NaiveDate::from_ymd_opt(now.year(), month, day).ok_or_else(out_of_range)?;
-    let current_year_start = try_subjective_date_start(current_year_date)?;
-    if current_year_start >= now {
-        return Ok(Some(current_year_start));
+// 呼び出し元ごとに固定している計算範囲外errorの操作名へ付け替える
+fn rename_out_of_range_operation(
+    error: ApplicationError,
+    operation: &'static str,
+) -> ApplicationError {
+    match error {
+        ApplicationError::SubjectiveDateOutOfRange { datetime, .. } => {
+            ApplicationError::SubjectiveDateOutOfRange {
+                operation,
+                datetime,
+            }
+        }
+        error => error,
     }
+}
 
-    let next_year = now.year().checked_add(1).ok_or_else(out_of_range)?;
-    let next_validation_year = 2000 + next_year.rem_euclid(400);
-    if NaiveDate::from_ymd_opt(next_validation_year, month, day).is_none() {
-        return Ok(None);
+fn resolve_upcoming_mmdd(
+    mmdd: &str,
+    now: DateTime<Local>,
+) -> Result<Option<DateTime<Local>>, ApplicationError> {
+    match TemporalExpression::parse_str(mmdd) {
+        Ok(
+            expression @ TemporalExpression {
+                date: Some(DateTerm::MonthDay { .. }),
+                time: None,
+            },
+        ) => resolve_temporal_datetime(&expression, now, ClockTime::business_day_start()),
+        _ => Ok(None),
     }
-    let next_year_date = NaiveDate::from_ymd_opt(next_year, month, day).ok_or_else(out_of_range)?;
-    Ok(Some(try_subjective_date_start(next_year_date)?))
 }
 
+// 日付だけの表現を、その業務日の開始時刻へ解決する
 fn resolve_upcoming_clear_or_gather_day(
     date: &str,
     now: DateTime<Local>,
 ) -> Result<Option<DateTime<Local>>, ApplicationError> {
-    if date == "明" {
-        return Ok(Some(try_next_business_day_start(now)?));
-    }
-
-    let days_of_week = ["月", "火", "水", "木", "金", "土", "日"];
-    if let Some(target_days_of_week_ind) = days_of_week.iter().position(|day| *day == date) {
-        let subjective_date = try_subjective_date(now)?;
-        let now_days_of_week_ind = days_of_week
-            .iter()
-            .position(|day| *day == get_weekday_jp(&subjective_date))
-            .expect("subjective weekday must be in the Japanese weekday table");
-        let days_until_target =
-            (7 + target_days_of_week_ind - now_days_of_week_ind) % days_of_week.len();
-        let days = if days_until_target == 0 {
-            7
-        } else {
-            days_until_target
-        };
-
-        let target_date = subjective_date
-            .checked_add_signed(Duration::days(days as i64))
-            .ok_or(ApplicationError::SubjectiveDateOutOfRange {
-                operation: "weekday_date",
-                datetime: now,
-            })?;
-        let target_datetime = try_subjective_date_start(target_date)?;
-        return Ok(Some(target_datetime));
+    let Ok(expression) = TemporalExpression::parse_str(date) else {
+        return Ok(None);
+    };
+    match expression {
+        TemporalExpression {
+            date: Some(DateTerm::Weekday(_)),
+            time: None,
+        } => resolve_temporal_datetime(&expression, now, ClockTime::business_day_start())
+            .map_err(|error| rename_out_of_range_operation(error, "weekday_date")),
+        TemporalExpression {
+            date: Some(_),
+            time: None,
+        } => resolve_temporal_datetime(&expression, now, ClockTime::business_day_start()),
+        _ => Ok(None),
     }
-
-    resolve_upcoming_mmdd(date, now)
 }
 
 fn resolve_show_all_pattern(
@@ -798,23 +998,16 @@ fn parse_clear_or_gather_defer_to_datetime(
     arg: &str,
     now: DateTime<Local>,
 ) -> Result<Option<DateTime<Local>>, ApplicationError> {
-    let hhmm_reg = Regex::new(r"^(\d{1,2}):(\d{1,2})$").unwrap();
-    if let Some(caps) = hhmm_reg.captures(arg) {
-        let (Some(hour), Some(minute)) = (caps[1].parse::<u32>().ok(), caps[2].parse::<u32>().ok())
-        else {
-            return Ok(None);
-        };
-        let Some(calendar_time) = NaiveTime::from_hms_opt(hour % 24, minute, 0) else {
-            return Ok(None);
-        };
-        let subjective_date = try_subjective_date(now)?;
-        let target_date = subjective_date
-            .checked_add_signed(Duration::days(i64::from(hour / 24)))
-            .ok_or(ApplicationError::SubjectiveDateOutOfRange {
-                operation: "clear_or_gather_time",
-                datetime: now,
-            })?;
-        return Ok(Some(try_local_date_and_time(target_date, calendar_time)?));
+    match TemporalExpression::parse_str(arg) {
+        // 時刻だけの指定は、calendar日ではなく主観日の時刻として扱う
+        Ok(mut expression) => {
+            if expression.date.is_none() {
+                expression.date = Some(DateTerm::Today);
+            }
+            return resolve_temporal_datetime(&expression, now, ClockTime::business_day_start());
+        }
+        Err(TemporalParseError::UnknownToken(_)) => {}
+        Err(_) => return Ok(None),
     }
 
     let integer_reg = Regex::new(r"^\d+$").unwrap();
@@ -895,7 +1088,7 @@ fn scheduled_leaf_starts_on_schronu_day(
     }
 
     let mut starts = HashMap::new();
-    for scheduled in get_schedule(task_repository)? {
+    for scheduled in get_back_to_back_schedule(task_repository)? {
         if !leaf_task_ids.contains(&scheduled.task.id) {
             continue;
         }
@@ -2288,17 +2481,6 @@ fn replace_task_list_icon(message_prefix: &str, icon: &str) -> String {
 
 const PROJECT_CATEGORY_SUMMARY_LEN: usize = 6;
 
-fn project_category_symbol(project_category_opt: Option<ProjectCategory>) -> &'static str {
-    match project_category_opt {
-        Some(ProjectCategory::Earning) => "獲",
-        Some(ProjectCategory::Sustaining) => "維",
-        Some(ProjectCategory::Recovery) => "回",
-        Some(ProjectCategory::Investment) => "資",
-        Some(ProjectCategory::Consumption) => "消",
-        None => "_",
-    }
-}
-
 fn format_focused_task_header(project_category_opt: Option<ProjectCategory>) -> String {
     format!(
         "focused task is: project_category={}",
@@ -2683,26 +2865,37 @@ fn test_get_forward_width_正常系1() {
 
 fn execute_show_tree(
     stdout: &mut dyn SchronuWriter,
+    task_repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
     focused_task_opt: &Option<TaskHandle>,
 ) -> Result<(), ApplicationError> {
-    writeln!(stdout).unwrap();
-    if let Some(focused_task) = focused_task_opt.as_ref() {
-        let s = focused_task
-            .tree_debug_pretty_print()
-            .map_err(ApplicationError::TaskTree)?;
-        let lines: Vec<_> = s.split('\n').collect();
-        for line in lines.iter() {
-            // Done([+])のタスクは表示しない
-            // 恒久的には、tree_debug_pretty_print()に似た関数を自分で実装してカスタマイズする
-            if line.contains("[ ]") || line.contains("[-]") {
-                writeln_newline(stdout, line).unwrap()
-            }
-        }
-    }
-    writeln!(stdout).unwrap();
+    write_task_tree(
+        stdout,
+        project_task_tree(task_repository, free_time_manager, policy, focused_task_opt)?.as_ref(),
+    );
     Ok(())
 }
 
+// フォーカス中のtaskを含むproject全体を、集計付きの木にする
+fn project_task_tree(
+    task_repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
+    focused_task_opt: &Option<TaskHandle>,
+) -> Result<Option<TaskTreeNodeView>, ApplicationError> {
+    focused_task_opt
+        .as_ref()
+        .map(|focused_task| {
+            let root_id = focused_task
+                .root()
+                .and_then(|root| root.get_id())
+                .map_err(ApplicationError::TaskTree)?;
+            get_task_tree(task_repository, free_time_manager, policy, root_id, None)
+        })
+        .transpose()
+}
+
 fn execute_show_ancestor(
     stdout: &mut dyn SchronuWriter,
     focused_task_opt: &Option<TaskHandle>,
@@ -2823,6 +3016,16 @@ fn execute_show_all_tasks(
 }
 
 #[allow(clippy::type_complexity)]
+fn unscheduled_reason_label(reason: UnscheduledReason) -> &'static str {
+    match reason {
+        UnscheduledReason::LongerThanFreeTime => "分けられないtaskが途切れない空き時間より長い",
+        UnscheduledReason::LongerThanAllowedWindow => {
+            "分けられないtaskが許可された時間帯の枠より長い"
+        }
+        UnscheduledReason::DependencyUnscheduled => "先に終える子taskを置けない",
+    }
+}
+
 fn execute_show_all_tasks_with_config(
     stdout: &mut dyn SchronuWriter,
     focused_task_id_opt: &mut Option<Uuid>,
@@ -2854,7 +3057,12 @@ fn execute_show_all_tasks_with_config(
             NaiveDate::from_ymd_opt(year, month, day).ok_or_else(invalid_calendar_date)
         })
         .transpose()?;
-    let scheduled_tasks = get_schedule(task_repository)?;
+    let schedule = get_schedule_with_unscheduled(
+        task_repository,
+        free_time_manager,
+        &datetime_policy(config),
+    )?;
+    let scheduled_tasks = schedule.scheduled_tasks;
     let mut task_list_display_rows: Vec<TaskListDisplayRow> = vec![];
     let mut available_biggest_row_opt: Option<TaskListDisplayRow> = None;
     let mut available_biggest_task_estimate_work_seconds = 0;
@@ -3817,6 +4025,18 @@ fn execute_show_all_tasks_with_config(
             *focused_task_id_opt = Some(row.id);
             writeln_newline(stdout, &row.render_message()).unwrap();
         }
+        for unscheduled in &schedule.unscheduled_tasks {
+            writeln_newline(
+                stdout,
+                &format!(
+                    "[Warn] 予定に置けません: {} ({}分) {}",
+                    unscheduled.task.name,
+                    unscheduled.total_work_seconds / 60,
+                    unscheduled_reason_label(unscheduled.reason)
+                ),
+            )
+            .unwrap();
+        }
 
         writeln_newline(stdout, "").unwrap();
         let project_category_summary =
@@ -4350,21 +4570,52 @@ fn seconds_until_next_business_day_start_with_offset(
         })
 }
 
+// 日時表現で指定した時刻の1秒後まで延期する。過去の日時は無視する
+fn defer_seconds_until_expression(
+    tokens: &[&str],
+    now: DateTime<Local>,
+) -> Result<Option<i64>, ApplicationError> {
+    let Ok(expression) = TemporalExpression::parse(tokens) else {
+        return Ok(None);
+    };
+    let Some(defer_dst_time) =
+        resolve_temporal_datetime(&expression, now, ClockTime::business_day_start())?
+    else {
+        return Ok(None);
+    };
+    let seconds = (defer_dst_time - now).num_seconds().checked_add(1).ok_or(
+        ApplicationError::SubjectiveDateOutOfRange {
+            operation: "defer_time",
+            datetime: now,
+        },
+    )?;
+    Ok((seconds > 0).then_some(seconds))
+}
+
 fn execute_defer_expression(
     task_repository: &mut dyn TaskRepositoryTrait,
     focused_task_id_opt: &mut Option<Uuid>,
     values: &[String],
 ) -> Result<(), DeferCommandError> {
+    let now = task_repository.get_last_synced_time();
     match values {
         [amount, unit, ..] => {
-            let amount = amount.parse::<i64>().map_err(|_| {
-                DeferCommandError::Parse(CommandParseError::new(
+            let Ok(amount) = amount.parse::<i64>() else {
+                // 「後 明 9:00」のような複数語の日時表現も受け付ける
+                let tokens = values.iter().map(String::as_str).collect::<Vec<_>>();
+                if TemporalExpression::parse(&tokens).is_ok() {
+                    if let Some(seconds) = defer_seconds_until_expression(&tokens, now)? {
+                        execute_defer(task_repository, focused_task_id_opt, seconds, "秒")?;
+                    }
+                    return Ok(());
+                }
+                return Err(DeferCommandError::Parse(CommandParseError::new(
                     "後",
                     "amount",
                     "整数で指定してください",
                     "後 <数値> <単位>",
-                ))
-            })?;
+                )));
+            };
             execute_defer(
                 task_repository,
                 focused_task_id_opt,
@@ -4374,100 +4625,27 @@ fn execute_defer_expression(
             .map_err(DeferCommandError::from)
         }
         [value] => {
-            let yyyymmdd_reg = Regex::new(r"^\d{4}/\d{2}/\d{2}$").unwrap();
-            let hhmm_reg = Regex::new(r"^(\d{1,2}):(\d{1,2})$").unwrap();
-            let now = task_repository.get_last_synced_time();
-            let seconds = if yyyymmdd_reg.is_match(value) {
-                match NaiveDate::parse_from_str(value, "%Y/%m/%d") {
-                    Ok(date) => {
-                        let defer_dst_time = try_subjective_date_start(date)?;
-                        Some((defer_dst_time - now).num_seconds().checked_add(1).ok_or(
-                            ApplicationError::SubjectiveDateOutOfRange {
-                                operation: "defer_date",
-                                datetime: now,
-                            },
-                        )?)
+            match TemporalExpression::parse_str(value) {
+                Ok(_) => {
+                    if let Some(seconds) = defer_seconds_until_expression(&[value], now)? {
+                        execute_defer(task_repository, focused_task_id_opt, seconds, "秒")?;
                     }
-                    Err(_) => None,
-                }
-            } else if let Some(defer_dst_time) = resolve_upcoming_mmdd(value, now)? {
-                let seconds = (defer_dst_time - now).num_seconds() + 1;
-                (seconds > 0).then_some(seconds)
-            } else if let Some(captures) = hhmm_reg.captures(value) {
-                let (Some(hour), Some(minute)) = (
-                    captures[1].parse::<i64>().ok(),
-                    captures[2].parse::<u32>().ok(),
-                ) else {
-                    return Ok(());
-                };
-                let Some(calendar_hour) = u32::try_from(hour % 24).ok() else {
                     return Ok(());
-                };
-                let Some(calendar_time) = NaiveTime::from_hms_opt(calendar_hour, minute, 0) else {
-                    return Ok(());
-                };
-                let out_of_range = || ApplicationError::SubjectiveDateOutOfRange {
-                    operation: "defer_time",
-                    datetime: now,
-                };
-                let day_offset = Duration::try_days(hour / 24).ok_or_else(out_of_range)?;
-                let target_date = now
-                    .date_naive()
-                    .checked_add_signed(day_offset)
-                    .ok_or_else(out_of_range)?;
-                let defer_dst_time = try_local_date_and_time(target_date, calendar_time)?;
-                let seconds = (defer_dst_time - now)
-                    .num_seconds()
-                    .checked_add(1)
-                    .ok_or_else(out_of_range)?;
-                (seconds > 0).then_some(seconds)
-            } else if ["月", "火", "水", "木", "金", "土", "日"].contains(&value.as_str()) {
-                let days_of_week = ["月", "火", "水", "木", "金", "土", "日"];
-                let today = try_subjective_date(now)?;
-                let current_index = days_of_week
-                    .iter()
-                    .position(|day| *day == get_weekday_jp(&today))
-                    .unwrap();
-                let target_index = days_of_week.iter().position(|day| *day == value).unwrap();
-                let difference = (7 + target_index - current_index) % 7;
-                let days = if difference == 0 {
-                    7
-                } else {
-                    difference as i64
-                };
-                let offset_seconds = days
-                    .checked_sub(1)
-                    .and_then(|days| days.checked_mul(86_400))
-                    .and_then(|seconds| seconds.checked_add(1))
-                    .ok_or(ApplicationError::SubjectiveDateOutOfRange {
-                        operation: "next_business_day_start",
-                        datetime: now,
-                    })?;
-                Some(seconds_until_next_business_day_start_with_offset(
-                    now,
-                    offset_seconds,
-                )?)
-            } else {
-                let split = split_amount_and_unit(value);
-                if split.len() == 2 && !split[0].is_empty() {
-                    split[0]
-                        .parse::<i64>()
-                        .ok()
-                        .map(|amount| (amount, split[1].clone()))
-                        .map_or(Ok(()), |(amount, unit)| {
-                            execute_defer(
-                                task_repository,
-                                focused_task_id_opt,
-                                amount,
-                                &unit.to_lowercase(),
-                            )
-                        })?;
                 }
-                return Ok(());
-            };
-
-            if let Some(seconds) = seconds {
-                execute_defer(task_repository, focused_task_id_opt, seconds, "秒")?;
+                // 「3日」「30分」のような期間指定は、日時表現ではないため従来の単位付き延期にする
+                Err(TemporalParseError::UnknownToken(_)) => {}
+                Err(_) => return Ok(()),
+            }
+            let split = split_amount_and_unit(value);
+            if split.len() == 2 && !split[0].is_empty() {
+                if let Ok(amount) = split[0].parse::<i64>() {
+                    execute_defer(
+                        task_repository,
+                        focused_task_id_opt,
+                        amount,
+                        &split[1].to_lowercase(),
+                    )?;
+                }
             }
             Ok(())
         }
@@ -4501,10 +4679,7 @@ fn execute_extrude_with_config(
                     .map_err(ApplicationError::TaskTree)?;
 
                 pending_until_datetime += Duration::days(step_days as i64);
-                while config
-                    .extrude_skip_weekdays
-                    .contains(&pending_until_datetime.weekday())
-                {
+                while is_extrude_skip_date(config, pending_until_datetime.date_naive()) {
                     pending_until_datetime += Duration::days(1);
                 }
             }
@@ -4513,6 +4688,12 @@ fn execute_extrude_with_config(
     Ok(())
 }
 
+// 祝日の曜日を設定していれば、祝日も割当日として飛ばす
+fn is_extrude_skip_date(config: &SchronuConfig, date: NaiveDate) -> bool {
+    config.extrude_skip_weekdays.contains(&date.weekday())
+        || (config.holiday_weekday.is_some() && is_japanese_holiday(date))
+}
+
 // 〆切をrepetition_interval_daysのぶん伸ばし、pendingにする
 // start_timeも伸ばすが、時刻は元のstart_timeを維持する
 fn execute_defer_routine(
@@ -4670,77 +4851,44 @@ fn execute_defer_all_frequent_routines(
     Ok(())
 }
 
+fn invalid_deadline_error() -> CommandError {
+    command_parse_error("〆", "deadline", "日時が不正です", "〆 <日付または時刻>")
+}
+
+// 年なし月日は、同日正午を過ぎていれば翌年の締切として扱う
+fn resolve_deadline_naive(
+    expression: &TemporalExpression,
+    now: DateTime<Local>,
+) -> Result<NaiveDateTime, CommandError> {
+    let operation = match expression.date {
+        Some(DateTerm::Weekday(_)) => Some("deadline_weekday_date"),
+        Some(DateTerm::MonthDay { .. }) => Some("deadline_calendar_date"),
+        _ => None,
+    };
+    let noon = ClockTime::new(12, 0, 0).expect("noon must be a valid clock time");
+    resolve_temporal_expression(expression, now, &datetime_policy(active_config()), noon)
+        .map_err(|error| match operation {
+            Some(operation) => rename_out_of_range_operation(error, operation),
+            None => error,
+        })?
+        .ok_or_else(invalid_deadline_error)
+}
+
 fn resolve_deadline_date(value: &str, now: DateTime<Local>) -> Result<String, CommandError> {
     if value == "消" {
         return Ok(value.to_string());
     }
-    if value.starts_with('今') {
-        return Ok(try_subjective_date(now)?.format("%Y/%m/%d").to_string());
-    }
-    if value.starts_with('明') {
-        return Ok(try_next_business_day_start(now)?
-            .format("%Y/%m/%d")
-            .to_string());
-    }
-
-    let days_of_week = ["月", "火", "水", "木", "金", "土", "日"];
-    if days_of_week.contains(&value) {
-        let today = try_subjective_date(now)?;
-        let current_index = days_of_week
-            .iter()
-            .position(|day| *day == get_weekday_jp(&today))
-            .expect("current weekday must be in the Japanese weekday table");
-        let target_index = days_of_week
-            .iter()
-            .position(|day| *day == value)
-            .expect("matched deadline weekday must be in the weekday table");
-        let difference = (7 + target_index - current_index) % 7;
-        let days = if difference == 0 {
-            7
-        } else {
-            difference as i64
-        };
-        let deadline_date = today.checked_add_signed(Duration::days(days)).ok_or(
-            ApplicationError::SubjectiveDateOutOfRange {
-                operation: "deadline_weekday_date",
-                datetime: now,
+    match TemporalExpression::parse_str(value) {
+        Ok(
+            expression @ TemporalExpression {
+                date: Some(_),
+                time: None,
             },
-        )?;
-        return Ok(deadline_date.format("%Y/%m/%d").to_string());
-    }
-
-    let mmdd = Regex::new(r"^(\d{1,2})/(\d{1,2})$").expect("valid deadline regex");
-    if let Some(captures) = mmdd.captures(value) {
-        let invalid_deadline =
-            || command_parse_error("〆", "deadline", "日時が不正です", "〆 <日付または時刻>");
-        let month = captures[1].parse::<u32>().map_err(|_| invalid_deadline())?;
-        let day = captures[2].parse::<u32>().map_err(|_| invalid_deadline())?;
-        let validation_year = 2000 + now.year().rem_euclid(400);
-        if NaiveDate::from_ymd_opt(validation_year, month, day).is_none() {
-            return Err(invalid_deadline());
-        }
-        let out_of_range = || ApplicationError::SubjectiveDateOutOfRange {
-            operation: "deadline_calendar_date",
-            datetime: now,
-        };
-        let mut deadline_date =
-            NaiveDate::from_ymd_opt(now.year(), month, day).ok_or_else(out_of_range)?;
-        let deadline_noon = deadline_date
-            .and_hms_opt(12, 0, 0)
-            .ok_or_else(out_of_range)?;
-        if deadline_noon < now.naive_local() {
-            let next_year = now.year().checked_add(1).ok_or_else(out_of_range)?;
-            let next_validation_year = 2000 + next_year.rem_euclid(400);
-            if NaiveDate::from_ymd_opt(next_validation_year, month, day).is_none() {
-                return Err(invalid_deadline());
-            }
-            deadline_date =
-                NaiveDate::from_ymd_opt(next_year, month, day).ok_or_else(out_of_range)?;
-        }
-        return Ok(deadline_date.format("%Y/%m/%d").to_string());
+        ) => Ok(resolve_deadline_naive(&expression, now)?
+            .format("%Y/%m/%d")
+            .to_string()),
+        _ => Ok(value.to_string()),
     }
-
-    Ok(value.to_string())
 }
 
 fn resolve_deadline_time(
@@ -4753,25 +4901,14 @@ fn resolve_deadline_time(
         return Ok(None);
     }
 
-    let hhmm_reg = Regex::new(r"^(\d{1,2}):(\d{1,2})$").unwrap();
-    let invalid_datetime =
-        || command_parse_error("〆", "deadline", "日時が不正です", "〆 <日付または時刻>");
-    let (date, time) = if hhmm_reg.is_match(&deadline_date_str) {
-        let caps = hhmm_reg
-            .captures(&deadline_date_str)
-            .expect("matched deadline time must have captures");
-        let hh: u32 = caps[1].parse().map_err(|_| {
-            command_parse_error("〆", "deadline", "時刻が不正です", "〆 <日付または時刻>")
-        })?;
-        let mm: u32 = caps[2].parse().map_err(|_| {
-            command_parse_error("〆", "deadline", "時刻が不正です", "〆 <日付または時刻>")
-        })?;
-        let time = NaiveTime::from_hms_opt(hh, mm, 0).ok_or_else(invalid_datetime)?;
-        (now.date_naive(), time)
-    } else {
-        let date = NaiveDate::parse_from_str(&deadline_date_str, "%Y/%m/%d")
-            .map_err(|_| invalid_datetime())?;
-        (date, config.default_deadline_time)
+    let (date, time) = match NaiveDate::parse_from_str(&deadline_date_str, "%Y/%m/%d") {
+        Ok(date) => (date, config.default_deadline_time),
+        Err(_) => {
+            let expression = TemporalExpression::parse_str(&deadline_date_str)
+                .map_err(|_| invalid_deadline_error())?;
+            let deadline = resolve_deadline_naive(&expression, now)?;
+            (deadline.date(), deadline.time())
+        }
     };
     Ok(Some(try_local_date_and_time(date, time)?))
 }
@@ -4872,7 +5009,8 @@ fn decide_time(tokens: &[&str], now: &DateTime<Local>) -> Option<DateTime<Local>
         .skip(1)
         .map(|value| (*value).to_string())
         .collect::<Vec<_>>();
-    decide_time_values(&values, now).expect("test datetime input must resolve")
+    decide_time_values(&values, now, &datetime_policy(active_config()))
+        .expect("test datetime input must resolve")
 }
 
 #[cfg(test)]
@@ -4882,7 +5020,8 @@ fn decide_finish_time(tokens: &Vec<&str>, now: &DateTime<Local>) -> Option<DateT
         .skip(1)
         .map(|value| (*value).to_string())
         .collect::<Vec<_>>();
-    decide_finish_time_values(&values, now).expect("test finish datetime input must resolve")
+    decide_finish_time_values(&values, now, &datetime_policy(active_config()))
+        .expect("test finish datetime input must resolve")
 }
 
 #[test]
@@ -5821,6 +5960,20 @@ impl TaskRepositoryTrait for TestTaskRepository {
         self.task = root_task;
         Ok(())
     }
+
+    // 1つのtaskだけを持つため、projectを取り除くことはできない
+    fn remove_project(&mut self, _root_task_id: Uuid) -> Result<Option<String>, TaskTreeError> {
+        Ok(None)
+    }
+
+    fn restore_project(
+        &mut self,
+        project_key: &str,
+        root_task: TaskHandle,
+    ) -> Result<String, TaskTreeError> {
+        self.task = root_task;
+        Ok(project_key.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -5885,6 +6038,8 @@ impl FreeTimeManagerTrait for TestFreeTimeManager {
     ) -> Result<(), BusyTimeSlotLoadError> {
         Ok(())
     }
+
+    fn clear_registered_busy_time_slots(&mut self) {}
 }
 
 #[cfg(test)]
@@ -5931,6 +6086,8 @@ impl FreeTimeManagerTrait for TestFreeTimeManagerWithLoadError {
             std::io::Error::new(std::io::ErrorKind::InvalidData, "test load error"),
         ))
     }
+
+    fn clear_registered_busy_time_slots(&mut self) {}
 }
 
 #[cfg(test)]
@@ -5962,6 +6119,8 @@ impl FreeTimeManagerTrait for TestFreeTimeManagerWithFreeMinutes {
     ) -> Result<(), BusyTimeSlotLoadError> {
         Ok(())
     }
+
+    fn clear_registered_busy_time_slots(&mut self) {}
 }
 
 #[test]
@@ -6500,6 +6659,8 @@ impl FreeTimeManagerTrait for TestFreeTimeManagerForBand {
     ) -> Result<(), BusyTimeSlotLoadError> {
         Ok(())
     }
+
+    fn clear_registered_busy_time_slots(&mut self) {}
 }
 
 #[cfg(test)]
@@ -6534,6 +6695,8 @@ impl FreeTimeManagerTrait for TestFreeTimeManagerByDate {
     ) -> Result<(), BusyTimeSlotLoadError> {
         Ok(())
     }
+
+    fn clear_registered_busy_time_slots(&mut self) {}
 }
 
 #[cfg(test)]
@@ -6770,6 +6933,31 @@ fn assert_show_all_spreadsheet_formatter_contract() {
     );
 }
 
+#[test]
+fn test_execute_all_どの空きにも収まらないatomic_taskは分けずに置けないと表示する() {
+    let now = Local.with_ymd_and_hms(2026, 8, 11, 12, 0, 0).unwrap();
+    let task = new_test_task_handle("丸一日の作業").unwrap();
+    task.set_estimated_work_seconds(24 * 60 * 60);
+    task.set_start_time(now);
+    task.set_atomic(true).unwrap();
+    task.sync_clock(now);
+    let task_id = task.get_id().unwrap();
+
+    let result = execute_command_for_test(task, now, Some(task_id), "全");
+
+    assert!(
+        !result
+            .output
+            .lines()
+            .any(|line| line.contains(&task_id.to_string())),
+        "unexpected schedule output: {}",
+        result.output
+    );
+    assert!(result.output.contains(
+        "[Warn] 予定に置けません: 丸一日の作業 (1440分) 分けられないtaskが途切れない空き時間より長い"
+    ));
+}
+
 #[test]
 fn test_execute_all_締切順の予定時刻を表示する() {
     let now = Local.with_ymd_and_hms(2026, 8, 11, 12, 0, 0).unwrap();
@@ -7519,20 +7707,25 @@ fn test_execute_defer_expression_不正なcalendar時刻を変更せず拒否す
 }
 
 #[test]
-fn test_execute_defer_expression_同日と24時超過を現在calendar日基準で解釈する() {
+fn test_execute_defer_expression_相対日時と営業日の表現を解釈する() {
+    // 2026/8/14は金曜日で、既定では土日を営業日から除く
     let now = Local.with_ymd_and_hms(2026, 8, 14, 12, 0, 0).unwrap();
 
-    for (value, expected) in [
+    for (values, expected) in [
         (
-            "13:30",
-            Local.with_ymd_and_hms(2026, 8, 14, 13, 30, 1).unwrap(),
+            &["+1bd"][..],
+            Local.with_ymd_and_hms(2026, 8, 17, 6, 0, 1).unwrap(),
         ),
         (
-            "25:30",
-            Local.with_ymd_and_hms(2026, 8, 15, 1, 30, 1).unwrap(),
+            &["2週後"][..],
+            Local.with_ymd_and_hms(2026, 8, 28, 6, 0, 1).unwrap(),
+        ),
+        (
+            &["明", "9:00"][..],
+            Local.with_ymd_and_hms(2026, 8, 15, 9, 0, 1).unwrap(),
         ),
     ] {
-        let task = new_test_task_handle("時刻指定の延期対象").unwrap();
+        let task = new_test_task_handle("日時表現の延期対象").unwrap();
         let task_id = task.get_id().unwrap();
         let mut task_repository = TestTaskRepository::new(task, now);
         let mut focused_task_id_opt = Some(task_id);
@@ -7541,26 +7734,81 @@ fn test_execute_defer_expression_同日と24時超過を現在calendar日基準
             focused_task_id_opt: &mut focused_task_id_opt,
             config: active_config(),
         };
+        let values = values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>();
 
-        let actual = context.defer_expression(&[value.to_string()]);
+        let actual = context.defer_expression(&values);
 
-        assert!(actual.is_ok());
+        assert!(actual.is_ok(), "{values:?}");
         assert_eq!(task_repository.task.get_pending_until().unwrap(), expected);
         assert_eq!(focused_task_id_opt, None);
     }
 }
 
 #[test]
-fn test_execute_defer_routine_翌朝計算不能を情報付きerrorにして親子とfocusを変更しない() {
-    let orig_deadline = maximum_local_datetime();
+fn test_resolve_deadline_time_日付と時刻を組み合わせた表現は明示時刻を使う() {
     let now = Local.with_ymd_and_hms(2026, 8, 14, 12, 0, 0).unwrap();
-    let parent = new_test_task_handle("反復routine親").unwrap();
-    parent.set_repetition_interval_days_opt(Some(7)).unwrap();
-    parent
-        .set_deadline_time_opt(Some(Local.with_ymd_and_hms(2026, 8, 20, 18, 0, 0).unwrap()))
-        .unwrap();
-    let mut child_attr = new_test_task_attr("延期対象routine子");
-    child_attr.set_deadline_time_opt(Some(orig_deadline));
+    let config = SchronuConfig::default();
+
+    assert!(matches!(
+        resolve_deadline_time("金 19:00", now, &config),
+        Ok(Some(actual)) if actual == Local.with_ymd_and_hms(2026, 8, 21, 19, 0, 0).unwrap()
+    ));
+    assert!(matches!(
+        resolve_deadline_time("末", now, &config),
+        Ok(Some(actual)) if actual == Local.with_ymd_and_hms(2026, 8, 31, 23, 59, 59).unwrap()
+    ));
+    assert!(matches!(
+        resolve_deadline_time("明 明", now, &config),
+        Err(CommandError::Parse(_))
+    ));
+}
+
+#[test]
+fn test_execute_defer_expression_同日と24時超過を現在calendar日基準で解釈する() {
+    let now = Local.with_ymd_and_hms(2026, 8, 14, 12, 0, 0).unwrap();
+
+    for (value, expected) in [
+        (
+            "13:30",
+            Local.with_ymd_and_hms(2026, 8, 14, 13, 30, 1).unwrap(),
+        ),
+        (
+            "25:30",
+            Local.with_ymd_and_hms(2026, 8, 15, 1, 30, 1).unwrap(),
+        ),
+    ] {
+        let task = new_test_task_handle("時刻指定の延期対象").unwrap();
+        let task_id = task.get_id().unwrap();
+        let mut task_repository = TestTaskRepository::new(task, now);
+        let mut focused_task_id_opt = Some(task_id);
+        let mut context = RuntimeDeferCommandContext {
+            task_repository: &mut task_repository,
+            focused_task_id_opt: &mut focused_task_id_opt,
+            config: active_config(),
+        };
+
+        let actual = context.defer_expression(&[value.to_string()]);
+
+        assert!(actual.is_ok());
+        assert_eq!(task_repository.task.get_pending_until().unwrap(), expected);
+        assert_eq!(focused_task_id_opt, None);
+    }
+}
+
+#[test]
+fn test_execute_defer_routine_翌朝計算不能を情報付きerrorにして親子とfocusを変更しない() {
+    let orig_deadline = maximum_local_datetime();
+    let now = Local.with_ymd_and_hms(2026, 8, 14, 12, 0, 0).unwrap();
+    let parent = new_test_task_handle("反復routine親").unwrap();
+    parent.set_repetition_interval_days_opt(Some(7)).unwrap();
+    parent
+        .set_deadline_time_opt(Some(Local.with_ymd_and_hms(2026, 8, 20, 18, 0, 0).unwrap()))
+        .unwrap();
+    let mut child_attr = new_test_task_attr("延期対象routine子");
+    child_attr.set_deadline_time_opt(Some(orig_deadline));
     let child = parent.create_as_last_child(child_attr);
     let child_id = child.get_id().unwrap();
     let parent_snapshot = parent.snapshot().unwrap();
@@ -8673,6 +8921,7 @@ fn execute_parsed(
             task_repository,
             focused_task_id_opt,
             task_factory: &mut task_factory,
+            config: active_config(),
         };
         match handle_project_command(parsed_command, &mut context)? {
             Some(outcome) => Some(outcome),
@@ -8762,6 +9011,133 @@ fn execute_parsed(
             outcome,
             active_config(),
         )?;
+    } else if let Some(outcome) = {
+        let mut context = RuntimeReportCommandContext {
+            task_repository: &*task_repository,
+            free_time_manager,
+            config: active_config(),
+        };
+        handle_report_command(parsed_command, &mut context)?
+    } {
+        apply_command_outcome(
+            &mut output,
+            task_repository,
+            focused_task_id_opt,
+            OutcomeApplicationMode::Flushed,
+            outcome,
+            active_config(),
+        )?;
+    } else if let Some(outcome) = {
+        let mut context = RuntimeStorageHistoryCommandContext {
+            task_repository,
+            focused_task_id_opt,
+            retention_revisions: active_config().storage_history_retention_revisions,
+        };
+        handle_storage_history_command(parsed_command, &mut context)?
+    } {
+        apply_command_outcome(
+            &mut output,
+            task_repository,
+            focused_task_id_opt,
+            OutcomeApplicationMode::Flushed,
+            outcome,
+            active_config(),
+        )?;
+    } else if let Some(outcome) = {
+        let mut context = RuntimeTrashCommandContext {
+            task_repository,
+            focused_task_id_opt,
+        };
+        handle_trash_command(parsed_command, &mut context)?
+    } {
+        apply_command_outcome(
+            &mut output,
+            task_repository,
+            focused_task_id_opt,
+            OutcomeApplicationMode::Flushed,
+            outcome,
+            active_config(),
+        )?;
+    } else if let Some(outcome) = {
+        let mut context = RuntimeBusyBlockCommandContext {
+            task_repository: &*task_repository,
+            free_time_manager,
+            config: active_config(),
+        };
+        handle_busy_block_command(parsed_command, &mut context)?
+    } {
+        apply_command_outcome(
+            &mut output,
+            task_repository,
+            focused_task_id_opt,
+            OutcomeApplicationMode::Flushed,
+            outcome,
+            active_config(),
+        )?;
+    } else if let Some(outcome) = {
+        let mut context = RuntimeImportCommandContext {
+            task_repository,
+            focused_task_id_opt,
+            task_factory: &mut task_factory,
+            config: active_config(),
+        };
+        handle_import_command(parsed_command, &mut context)?
+    } {
+        apply_command_outcome(
+            &mut output,
+            task_repository,
+            focused_task_id_opt,
+            OutcomeApplicationMode::Flushed,
+            outcome,
+            active_config(),
+        )?;
+    } else if let Some(outcome) = {
+        let mut context = RuntimeObsidianSyncCommandContext {
+            task_repository,
+            task_factory: &mut task_factory,
+            config: active_config(),
+        };
+        handle_obsidian_sync_command(parsed_command, &mut context)?
+    } {
+        apply_command_outcome(
+            &mut output,
+            task_repository,
+            focused_task_id_opt,
+            OutcomeApplicationMode::Flushed,
+            outcome,
+            active_config(),
+        )?;
+    } else if let Some(outcome) = {
+        let mut context = RuntimeExportCommandContext {
+            task_repository: &*task_repository,
+            focused_task_id_opt,
+        };
+        handle_export_command(parsed_command, &mut context)?
+    } {
+        apply_command_outcome(
+            &mut output,
+            task_repository,
+            focused_task_id_opt,
+            OutcomeApplicationMode::Flushed,
+            outcome,
+            active_config(),
+        )?;
+    } else if let Some(outcome) = {
+        let mut context = RuntimeSubtreeCommandContext {
+            task_repository,
+            focused_task_id_opt,
+            task_factory: &mut task_factory,
+        };
+        handle_subtree_command(parsed_command, &mut context)?
+    } {
+        apply_command_outcome(
+            &mut output,
+            task_repository,
+            focused_task_id_opt,
+            OutcomeApplicationMode::Flushed,
+            outcome,
+            active_config(),
+        )?;
     } else if let Some(outcome) = handle(parsed_command) {
         apply_command_outcome(
             &mut output,
@@ -8783,10 +9159,457 @@ fn execute_parsed(
             active_config(),
         )?;
     }
-    match output.take_error() {
-        Some(error) if error.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
-        Some(error) => Err(CommandError::Output(error)),
-        None => Ok(()),
+    match output.take_error() {
+        Some(error) if error.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        Some(error) => Err(CommandError::Output(error)),
+        None => Ok(()),
+    }
+}
+
+struct RuntimeReportCommandContext<'repository> {
+    task_repository: &'repository dyn TaskRepositoryTrait,
+    free_time_manager: &'repository mut dyn FreeTimeManagerTrait,
+    config: &'repository SchronuConfig,
+}
+
+impl ReportCommandContext for RuntimeReportCommandContext<'_> {
+    fn last_synced_time(&self) -> DateTime<Local> {
+        self.task_repository.get_last_synced_time()
+    }
+
+    fn datetime_policy(&self) -> BusinessDateTimePolicy {
+        datetime_policy(self.config)
+    }
+
+    fn load_history(
+        &mut self,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<Vec<LoadSnapshot>, LoadHistoryStoreError> {
+        let store = LoadHistoryFile::in_storage_directory(
+            self.task_repository.get_project_storage_dir_name(),
+        )
+        .with_cipher(active_storage_cipher());
+        list_load_history(&store, from, until)
+    }
+
+    fn flow_metrics(
+        &mut self,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<FlowMetricsView, ApplicationError> {
+        calculate_flow_metrics(self.task_repository, from, until)
+    }
+
+    fn list_at_risk_tasks(&mut self) -> Result<Vec<AtRiskTaskView>, ApplicationError> {
+        let policy = self.datetime_policy();
+        list_at_risk_tasks(self.task_repository, self.free_time_manager, &policy, None)
+    }
+
+    fn gantt_chart(&mut self, days: u64) -> Result<GanttChartView, ApplicationError> {
+        let now = self.task_repository.get_last_synced_time();
+        let policy = self.datetime_policy();
+        let schedule = get_schedule(self.task_repository, self.free_time_manager, &policy)?;
+        build_gantt_chart(
+            &schedule,
+            self.free_time_manager,
+            try_subjective_date(now)?,
+            days,
+            now,
+        )
+    }
+
+    // 保存先directoryの監視に拾われないよう、一時directoryへ書き出す
+    // 予定図にはtask名が平文で載るので、暗号化した保存先では書き出さない
+    fn write_report_file(&mut self, file_name: &str, contents: &str) -> std::io::Result<PathBuf> {
+        let directory = env::temp_dir();
+        ensure_plaintext_output_allowed(active_storage_cipher().as_ref(), &directory)
+            .map_err(std::io::Error::other)?;
+        create_report_file(&directory, file_name, contents)
+    }
+}
+
+// 共有の一時directoryでは決まった名前のfileを他のuserが先にsymlinkで置けるため、
+// 毎回乱数を付けた名前で新しく作り、既にあるfileやsymlinkには書かない
+fn create_report_file(
+    directory: &Path,
+    file_name: &str,
+    contents: &str,
+) -> std::io::Result<PathBuf> {
+    let file_name = Path::new(file_name);
+    let stem = file_name
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("schronu_report");
+    let path = directory.join(format!(
+        "{stem}-{}.{}",
+        Uuid::new_v4().simple(),
+        file_name
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or("txt")
+    ));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)?;
+    file.write_all(contents.as_bytes())?;
+    Ok(path)
+}
+
+#[test]
+fn create_report_fileは毎回別の名前で新しく作り既存のfileを書き換えない() {
+    let storage_dir = TestStorageDir::new();
+    std::fs::create_dir_all(&storage_dir.path).unwrap();
+
+    let first = create_report_file(&storage_dir.path, "schronu_gantt.html", "first").unwrap();
+    let second = create_report_file(&storage_dir.path, "schronu_gantt.html", "second").unwrap();
+
+    assert_ne!(first, second);
+    for path in [&first, &second] {
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("schronu_gantt-"));
+        assert!(name.ends_with(".html"));
+    }
+    assert_eq!(std::fs::read_to_string(&first).unwrap(), "first");
+    assert_eq!(std::fs::read_to_string(&second).unwrap(), "second");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&first).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+struct RuntimeStorageHistoryCommandContext<'repository> {
+    task_repository: &'repository mut dyn TaskRepositoryTrait,
+    focused_task_id_opt: &'repository mut Option<Uuid>,
+    retention_revisions: usize,
+}
+
+impl RuntimeStorageHistoryCommandContext<'_> {
+    // 保存履歴はproject.yamlの版なので、sqliteの保存先では扱わない
+    fn storage_history(&self) -> Result<StorageHistory, StorageHistoryError> {
+        let storage_directory = self.task_repository.get_project_storage_dir_name();
+        if active_config().storage_backend != StorageBackend::Yaml {
+            return Err(StorageHistoryError::new(
+                storage_directory,
+                std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "storage history is only recorded for the yaml storage backend",
+                ),
+            ));
+        }
+        Ok(
+            StorageHistory::new(storage_directory, self.retention_revisions)
+                .with_cipher(active_storage_cipher()),
+        )
+    }
+}
+
+impl StorageHistoryCommandContext for RuntimeStorageHistoryCommandContext<'_> {
+    fn list_revisions(&self) -> Result<Vec<StorageRevision>, StorageHistoryError> {
+        list_storage_revisions(&self.storage_history()?)
+    }
+
+    fn diff_revisions(
+        &self,
+        from: Uuid,
+        until: Option<Uuid>,
+    ) -> Result<Vec<ProjectRevisionDiff>, StorageHistoryError> {
+        diff_storage_revisions(&self.storage_history()?, from, until)
+    }
+
+    // 保存先lockを保持したまま書き戻し、.revisionの更新を検出させてtask treeを読み直す
+    fn restore_revision(
+        &mut self,
+        revision: Uuid,
+        project: Option<&str>,
+    ) -> Result<StorageRevision, StorageHistoryUseCaseError> {
+        let now = self.task_repository.get_last_synced_time();
+        let restored =
+            restore_storage_revision(&mut self.storage_history()?, revision, project, now)?;
+        self.task_repository
+            .reload_if_changed(now)
+            .map_err(|error| {
+                StorageHistoryError::new(self.task_repository.get_project_storage_dir_name(), error)
+            })?;
+        if let Some(focused_task_id) = *self.focused_task_id_opt {
+            if !matches!(self.task_repository.get_by_id(focused_task_id), Ok(Some(_))) {
+                *self.focused_task_id_opt = None;
+            }
+        }
+        Ok(restored)
+    }
+}
+
+struct RuntimeTrashCommandContext<'repository> {
+    task_repository: &'repository mut dyn TaskRepositoryTrait,
+    focused_task_id_opt: &'repository mut Option<Uuid>,
+}
+
+impl RuntimeTrashCommandContext<'_> {
+    fn trash_file(&self) -> TrashFile {
+        TrashFile::in_storage_directory(self.task_repository.get_project_storage_dir_name())
+            .with_cipher(active_storage_cipher())
+    }
+}
+
+impl TrashCommandContext for RuntimeTrashCommandContext<'_> {
+    fn focused_task_id(&self) -> Option<Uuid> {
+        *self.focused_task_id_opt
+    }
+
+    fn delete_task(&mut self, task_id: Uuid) -> Result<TrashEntry, TrashError> {
+        let now = self.task_repository.get_last_synced_time();
+        let mut trash_file = self.trash_file();
+        let entry = delete_task(self.task_repository, &mut trash_file, task_id, now)?;
+        if let Some(focused_task_id) = *self.focused_task_id_opt {
+            if !matches!(self.task_repository.get_by_id(focused_task_id), Ok(Some(_))) {
+                *self.focused_task_id_opt = None;
+            }
+        }
+        Ok(entry)
+    }
+
+    fn list_trash(&self) -> Result<Vec<TrashEntry>, TrashError> {
+        Ok(list_trash(&self.trash_file())?)
+    }
+
+    fn restore_trash_entry(&mut self, trash_id: Uuid) -> Result<TrashEntry, TrashError> {
+        let now = self.task_repository.get_last_synced_time();
+        let mut trash_file = self.trash_file();
+        restore_trash_entry(self.task_repository, &mut trash_file, trash_id, now)
+    }
+
+    fn purge_trash_entry(&mut self, trash_id: Uuid) -> Result<PurgedTrashEntry, TrashError> {
+        purge_trash_entry(&mut self.trash_file(), trash_id)
+    }
+}
+
+struct RuntimeBusyBlockCommandContext<'repository> {
+    task_repository: &'repository dyn TaskRepositoryTrait,
+    free_time_manager: &'repository mut dyn FreeTimeManagerTrait,
+    config: &'repository SchronuConfig,
+}
+
+impl RuntimeBusyBlockCommandContext<'_> {
+    fn busy_block_file(&self) -> BusyBlockFile {
+        BusyBlockFile::in_storage_directory(self.task_repository.get_project_storage_dir_name())
+            .with_cipher(active_storage_cipher())
+    }
+}
+
+impl BusyBlockCommandContext for RuntimeBusyBlockCommandContext<'_> {
+    fn last_synced_time(&self) -> DateTime<Local> {
+        self.task_repository.get_last_synced_time()
+    }
+
+    fn datetime_policy(&self) -> BusinessDateTimePolicy {
+        datetime_policy(self.config)
+    }
+
+    fn list_busy_blocks(&self) -> Result<Vec<BusyBlock>, BusyBlockError> {
+        Ok(list_busy_blocks(&self.busy_block_file())?)
+    }
+
+    fn add_busy_block(
+        &mut self,
+        name: &str,
+        span: BusyBlockSpan,
+    ) -> Result<BusyBlock, BusyBlockError> {
+        let block = add_busy_block(&mut self.busy_block_file(), name, span)?;
+        register_busy_blocks(self.free_time_manager, std::slice::from_ref(&block))?;
+        Ok(block)
+    }
+
+    fn remove_busy_block(&mut self, busy_block_id: Uuid) -> Result<BusyBlock, BusyBlockError> {
+        let mut busy_block_file = self.busy_block_file();
+        let removed = remove_busy_block(&mut busy_block_file, busy_block_id)?;
+        reload_busy_blocks(self.free_time_manager, &busy_block_file)?;
+        Ok(removed)
+    }
+}
+
+struct RuntimeImportCommandContext<'repository, 'factory, 'generator> {
+    task_repository: &'repository mut dyn TaskRepositoryTrait,
+    focused_task_id_opt: &'repository Option<Uuid>,
+    task_factory: &'factory mut TaskFactory<'generator>,
+    config: &'repository SchronuConfig,
+}
+
+impl ImportCommandContext for RuntimeImportCommandContext<'_, '_, '_> {
+    fn focused_task_id(&self) -> Option<Uuid> {
+        *self.focused_task_id_opt
+    }
+
+    fn read_task_outline(&self, path: &str) -> Result<TaskOutline, TaskOutlineError> {
+        let policy = datetime_policy(self.config);
+        let clock = ImportClock {
+            now: self.task_repository.get_last_synced_time(),
+            policy: &policy,
+            default_deadline_time: ClockTime::from_naive_time(self.config.default_deadline_time),
+        };
+        read_task_outline(Path::new(path), &clock)
+    }
+
+    fn import_task_outline(
+        &mut self,
+        outline: &TaskOutline,
+        parent_id: Option<Uuid>,
+    ) -> Result<Vec<Uuid>, ApplicationError> {
+        import_task_outline(self.task_repository, outline, parent_id, self.task_factory)
+    }
+}
+
+// 直した後に残った食い違いの数を、非0で終えるかの判断のために覚えておく
+struct RuntimeVerifyCommandContext<'repository> {
+    task_repository: &'repository mut dyn TaskRepositoryTrait,
+    now: DateTime<Local>,
+    unresolved_count: usize,
+}
+
+impl VerifyCommandContext for RuntimeVerifyCommandContext<'_> {
+    fn check_consistency(
+        &mut self,
+        fix: bool,
+    ) -> Result<Vec<ConsistencyFinding>, VerifyCommandError> {
+        let mut findings = check_task_consistency(self.task_repository, self.now, fix)
+            .map_err(|error| VerifyCommandError::Application(ApplicationError::TaskTree(error)))?;
+        if findings.iter().any(|finding| finding.fixed) {
+            self.task_repository
+                .save()
+                .map_err(|error| VerifyCommandError::Save(Box::new(error)))?;
+        }
+        // 保存先のfileはYAMLで保存しているときだけ検査する
+        if active_config().storage_backend == StorageBackend::Yaml {
+            findings.extend(
+                check_storage_consistency(
+                    Path::new(self.task_repository.get_project_storage_dir_name()),
+                    fix,
+                )
+                .map_err(VerifyCommandError::Storage)?,
+            );
+        }
+        self.unresolved_count = findings.iter().filter(|finding| !finding.fixed).count();
+        Ok(findings)
+    }
+}
+
+struct RuntimeLockCommandContext<'storage> {
+    storage_directory: &'storage Path,
+}
+
+impl LockCommandContext for RuntimeLockCommandContext<'_> {
+    fn lock_status(&mut self) -> Result<StorageLockStatus, StorageLockError> {
+        inspect_storage_lock(self.storage_directory)
+    }
+}
+
+// 錠はcommandのためのlockを取る前に実行する。錠でなければfalseを返す
+fn execute_lock_command(
+    stdout: &mut dyn SchronuWriter,
+    storage_directory: &Path,
+    parsed_command: &Command,
+) -> Result<bool, CommandError> {
+    let mut context = RuntimeLockCommandContext { storage_directory };
+    let Some(outcome) = handle_lock_command(parsed_command, &mut context)? else {
+        return Ok(false);
+    };
+    render_display_model(stdout, &outcome.display).map_err(CommandError::Output)?;
+    Ok(true)
+}
+
+struct RuntimeObsidianSyncCommandContext<'repository, 'factory, 'generator> {
+    task_repository: &'repository mut dyn TaskRepositoryTrait,
+    task_factory: &'factory mut TaskFactory<'generator>,
+    config: &'repository SchronuConfig,
+}
+
+impl ObsidianSyncCommandContext for RuntimeObsidianSyncCommandContext<'_, '_, '_> {
+    fn has_obsidian_vault(&self) -> bool {
+        self.config.obsidian_vault_path.is_some()
+    }
+
+    fn sync_project_notes(&mut self) -> Result<ProjectNoteSyncReport, ProjectNoteSyncError> {
+        let Some(vault_path) = &self.config.obsidian_vault_path else {
+            return Ok(ProjectNoteSyncReport::default());
+        };
+        // noteはObsidianで読むため平文でしか書けない。暗号化した保存先のtaskは書き出さない
+        ensure_plaintext_output_allowed(active_storage_cipher().as_ref(), vault_path)
+            .map_err(|error| ProjectNoteStoreError::new(vault_path, error))?;
+        let finished_at = self.task_repository.get_last_synced_time();
+        sync_project_notes(
+            self.task_repository,
+            &mut ProjectNoteDirectory::in_vault(vault_path),
+            finished_at,
+            self.task_factory,
+        )
+    }
+}
+
+struct RuntimeExportCommandContext<'repository> {
+    task_repository: &'repository dyn TaskRepositoryTrait,
+    focused_task_id_opt: &'repository Option<Uuid>,
+}
+
+impl ExportCommandContext for RuntimeExportCommandContext<'_> {
+    fn focused_task_id(&self) -> Option<Uuid> {
+        *self.focused_task_id_opt
+    }
+
+    fn task_tree(&mut self, task_id: Uuid) -> Result<TaskTreeNodeView, ApplicationError> {
+        get_task_tree_without_schedule(self.task_repository, task_id, None)
+    }
+
+    // 書き出したfileは平文なので、暗号化した保存先ではnoteと同じく書かない
+    fn write_task_export(&mut self, path: &str, text: &str) -> Result<(), TaskExportError> {
+        ensure_plaintext_output_allowed(active_storage_cipher().as_ref(), Path::new(path))
+            .map_err(|error| TaskExportError::Write {
+                path: path.into(),
+                message: error.to_string(),
+            })?;
+        write_task_export(Path::new(path), text)
+    }
+}
+
+struct RuntimeSubtreeCommandContext<'repository, 'factory, 'generator> {
+    task_repository: &'repository mut dyn TaskRepositoryTrait,
+    focused_task_id_opt: &'repository Option<Uuid>,
+    task_factory: &'factory mut TaskFactory<'generator>,
+}
+
+impl SubtreeCommandContext for RuntimeSubtreeCommandContext<'_, '_, '_> {
+    fn focused_task_id(&self) -> Option<Uuid> {
+        *self.focused_task_id_opt
+    }
+
+    fn move_subtree(
+        &mut self,
+        task_id: Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<(), ApplicationError> {
+        move_subtree(self.task_repository, task_id, parent_id)
+    }
+
+    fn copy_subtree(
+        &mut self,
+        task_id: Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<Uuid, ApplicationError> {
+        copy_subtree(self.task_repository, task_id, parent_id, self.task_factory)
+    }
+
+    fn merge_sibling_tasks(
+        &mut self,
+        task_id: Uuid,
+        source_task_id: Uuid,
+    ) -> Result<(), ApplicationError> {
+        merge_sibling_tasks(self.task_repository, task_id, source_task_id)
     }
 }
 
@@ -8794,6 +9617,7 @@ struct RuntimeProjectCommandContext<'repository, 'factory, 'generator> {
     task_repository: &'repository mut dyn TaskRepositoryTrait,
     focused_task_id_opt: &'repository mut Option<Uuid>,
     task_factory: &'factory mut TaskFactory<'generator>,
+    config: &'repository SchronuConfig,
 }
 
 impl ProjectCommandContext for RuntimeProjectCommandContext<'_, '_, '_> {
@@ -8801,6 +9625,10 @@ impl ProjectCommandContext for RuntimeProjectCommandContext<'_, '_, '_> {
         self.task_repository.get_last_synced_time()
     }
 
+    fn datetime_policy(&self) -> BusinessDateTimePolicy {
+        datetime_policy(self.config)
+    }
+
     fn focused_task(&mut self) -> Result<Option<TaskHandle>, ApplicationError> {
         match self.focused_task_id_opt {
             Some(id) => self
@@ -8834,6 +9662,10 @@ impl ProjectCommandContext for RuntimeProjectCommandContext<'_, '_, '_> {
     fn set_focused_task_id(&mut self, task_id_opt: Option<Uuid>) {
         *self.focused_task_id_opt = task_id_opt;
     }
+
+    fn list_waiting_tasks(&self) -> Result<Vec<WaitingGroup>, ApplicationError> {
+        list_waiting_tasks(&*self.task_repository, self.last_synced_time())
+    }
 }
 
 struct RuntimeTaskAttributeCommandContext<'a> {
@@ -9053,6 +9885,10 @@ impl FinishPlacementCommandContext for RuntimeFinishPlacementCommandContext<'_,
         self.task_repository.get_last_synced_time()
     }
 
+    fn datetime_policy(&self) -> BusinessDateTimePolicy {
+        datetime_policy(self.config)
+    }
+
     fn focus_started_datetime(&self) -> DateTime<Local> {
         self.focus_started_datetime
     }
@@ -9071,7 +9907,14 @@ impl FinishPlacementCommandContext for RuntimeFinishPlacementCommandContext<'_,
         &mut self,
         display: &mut dyn SchronuWriter,
     ) -> Result<(), ApplicationError> {
-        execute_show_tree(display, &self.focused_task()?)
+        let focused_task_opt = self.focused_task()?;
+        execute_show_tree(
+            display,
+            self.task_repository,
+            self.free_time_manager,
+            &datetime_policy(self.config),
+            &focused_task_opt,
+        )
     }
 
     fn complete_focused_task(
@@ -9129,8 +9972,24 @@ impl TaskTreeCommandContext for RuntimeTaskTreeCommandContext<'_, '_, '_> {
         self.supports_ansi_color
     }
 
-    fn show_tree(&mut self, display: &mut dyn SchronuWriter) -> Result<(), ApplicationError> {
-        execute_show_tree(display, &self.focused_task()?)
+    fn task_tree(&mut self) -> Result<Option<TaskTreeNodeView>, ApplicationError> {
+        let focused_task_opt = self.focused_task()?;
+        project_task_tree(
+            self.task_repository,
+            self.free_time_manager,
+            &datetime_policy(self.config),
+            &focused_task_opt,
+        )
+    }
+
+    fn critical_path(&mut self) -> Result<Option<CriticalPathView>, ApplicationError> {
+        match *self.focused_task_id_opt {
+            Some(task_id) => {
+                compute_critical_path(self.task_repository, self.free_time_manager, task_id)
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
     }
 
     fn show_ancestor(&mut self, display: &mut dyn SchronuWriter) -> Result<(), ApplicationError> {
@@ -9227,7 +10086,13 @@ impl TaskTreeCommandContext for RuntimeTaskTreeCommandContext<'_, '_, '_> {
                     *self.focused_task_id_opt =
                         Some(child.get_id().map_err(ApplicationError::TaskTree)?);
                 }
-                [_, _, ..] => execute_show_tree(display, &focused_task_opt)?,
+                [_, _, ..] => execute_show_tree(
+                    display,
+                    self.task_repository,
+                    self.free_time_manager,
+                    &datetime_policy(self.config),
+                    &focused_task_opt,
+                )?,
                 _ => {}
             }
         }
@@ -9264,7 +10129,13 @@ impl TaskTreeCommandContext for RuntimeTaskTreeCommandContext<'_, '_, '_> {
             .len()
             > 1
         {
-            execute_show_tree(display, &Some(deepest_task))?;
+            execute_show_tree(
+                display,
+                self.task_repository,
+                self.free_time_manager,
+                &datetime_policy(self.config),
+                &Some(deepest_task),
+            )?;
         }
         Ok(())
     }
@@ -9313,6 +10184,8 @@ fn apply_command_outcome(
             ExternalRequest::OpenObsidianRootSearch => {
                 execute_open_obsidian_root_task_search_with_config(&focused_task_opt, config)?
             }
+            ExternalRequest::BrowseFile(path) => webbrowser::open(&path.to_string_lossy())
+                .map_err(|source| external_open_error("browser", source))?,
         }
     }
 
@@ -10407,6 +11280,24 @@ fn test_calculate_daily_band_durations_経過した空き時間を当日だけ
     assert_eq!(future.elapsed_seconds, 0);
 }
 
+#[test]
+fn is_extrude_skip_date_祝日の曜日を設定したときだけ祝日を飛ばす() {
+    // 2026/11/3(火)は文化の日
+    let holiday = NaiveDate::from_ymd_opt(2026, 11, 3).unwrap();
+    let saturday = NaiveDate::from_ymd_opt(2026, 11, 7).unwrap();
+    let mut config = SchronuConfig {
+        extrude_skip_weekdays: vec![Weekday::Sat],
+        ..SchronuConfig::default()
+    };
+
+    assert!(!is_extrude_skip_date(&config, holiday));
+    assert!(is_extrude_skip_date(&config, saturday));
+
+    config.holiday_weekday = Some(Weekday::Sun);
+    assert!(is_extrude_skip_date(&config, holiday));
+    assert!(!is_extrude_skip_date(&config, holiday.succ_opt().unwrap()));
+}
+
 #[test]
 fn test_format_signed_hours_minutes_符号付きで時分を2桁ゼロ埋めする() {
     assert_eq!(format_signed_hours_minutes(Duration::zero()), "+00:00");
@@ -10599,6 +11490,10 @@ fn test_execute_band_全日空き差分と繰り返し判定を帯へ反映す
 fn test_should_suppress_leaf_tasks_after_command_帯とbandでは葉を追加表示しない() {
     assert!(should_suppress_leaf_tasks_after_command("帯"));
     assert!(should_suppress_leaf_tasks_after_command("band"));
+    assert!(should_suppress_leaf_tasks_after_command("履 7"));
+    assert!(should_suppress_leaf_tasks_after_command("history"));
+    assert!(should_suppress_leaf_tasks_after_command("版 差 abcd"));
+    assert!(should_suppress_leaf_tasks_after_command("rev"));
     assert!(!should_suppress_leaf_tasks_after_command("見"));
 }
 
@@ -10659,16 +11554,50 @@ fn reload_repository_for_cli(
     task_repository: &mut dyn TaskRepositoryTrait,
     now: DateTime<Local>,
 ) -> Result<StorageLock, CliRepositoryTransactionError> {
+    reload_repository_for_cli_with_outcome(task_repository, now)
+        .map(|(storage_lock, _outcome)| storage_lock)
+}
+
+fn reload_repository_for_cli_with_outcome(
+    task_repository: &mut dyn TaskRepositoryTrait,
+    now: DateTime<Local>,
+) -> Result<(StorageLock, RepositoryReloadOutcome), CliRepositoryTransactionError> {
     let storage_lock = StorageLock::acquire_with_timeout(
         task_repository.get_project_storage_dir_name().as_ref(),
         LockMode::Cli,
         CLI_LOCK_TIMEOUT,
     )
     .map_err(CliRepositoryTransactionError::Lock)?;
-    task_repository
+    let outcome = task_repository
         .reload_if_changed(now)
         .map_err(CliRepositoryTransactionError::Load)?;
-    Ok(storage_lock)
+    Ok((storage_lock, outcome))
+}
+
+// 主観日の最初の実行で、command適用前の負荷を記録する。
+// 記録に失敗してもcommand自体は続行し、errorだけを報告する
+fn record_daily_load_snapshot(
+    task_repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+) {
+    let result = try_subjective_date(task_repository.get_last_synced_time())
+        .map_err(LoadHistoryError::from)
+        .and_then(|date| {
+            let mut store = LoadHistoryFile::in_storage_directory(
+                task_repository.get_project_storage_dir_name(),
+            )
+            .with_cipher(active_storage_cipher());
+            record_load_snapshot_if_absent(&mut store, date, || {
+                capture_load_snapshot(
+                    task_repository,
+                    free_time_manager,
+                    &datetime_policy(active_config()),
+                )
+            })
+        });
+    if let Err(error) = result {
+        eprintln!("[Error] 負荷履歴エラー: {error}");
+    }
 }
 
 fn run_cli_repository_transaction<T>(
@@ -10743,8 +11672,78 @@ fn reconcile_focus_after_reload(
     Ok(previous_focus != *focused_task_id_opt)
 }
 
+// schronu binaryの入口。schronudからは呼ばない
+#[allow(dead_code)]
 pub(super) fn application() {
     let command_opt = parse_non_interactive_command(env::args().skip(1).collect());
+    let project_storage_directory = load_config_and_storage_directory();
+    // 対話モードは焦点や画面の状態をこのprocessに持つため、schronudへは渡さず保存先を直接扱う
+    #[cfg(unix)]
+    if let Some(command) = &command_opt {
+        match forward_command_to_daemon(&project_storage_directory, command) {
+            Ok(Some(true)) => return,
+            Ok(Some(false)) => process::exit(1),
+            Ok(None) => {}
+            Err(error) => {
+                eprintln!("[Error] schronud: {error}");
+                process::exit(1);
+            }
+        }
+    }
+    let project_storage_directory = project_storage_directory
+        .to_str()
+        .expect("storage path was validated");
+    let mut task_repository: Box<dyn TaskRepositoryTrait> = match active_config().storage_backend {
+        StorageBackend::Yaml => {
+            match open_active_storage_cipher(Path::new(project_storage_directory)) {
+                Ok(cipher) => Box::new(
+                    TaskRepository::new(project_storage_directory)
+                        .with_history_retention_revisions(
+                            active_config().storage_history_retention_revisions,
+                        )
+                        .with_storage_cipher(cipher),
+                ),
+                Err(error) => {
+                    eprintln!("[Error] {error}");
+                    process::exit(1);
+                }
+            }
+        }
+        StorageBackend::Sqlite => {
+            if let Err(error) =
+                ensure_active_storage_unencrypted(Path::new(project_storage_directory))
+            {
+                eprintln!("[Error] {error}");
+                process::exit(1);
+            }
+            match SqliteTaskRepository::open(project_storage_directory) {
+                Ok(repository) => Box::new(repository),
+                Err(error) => {
+                    eprintln!("[Error] {error}");
+                    process::exit(1);
+                }
+            }
+        }
+    };
+    let mut free_time_manager =
+        FreeTimeManager::new().with_holiday_weekday(active_config().holiday_weekday);
+
+    // controllerで実体を見るのを避けるために、1つ関数を切る
+    let result = match command_opt {
+        Some(command) => execute_non_interactive_command(
+            task_repository.as_mut(),
+            &mut free_time_manager,
+            &command,
+        ),
+        None => interactive_application(task_repository.as_mut(), &mut free_time_manager),
+    };
+    if !report_run_result(&mut std::io::stderr(), result) {
+        process::exit(1);
+    }
+}
+
+// 設定を読み込み、保存先directoryを決める。どちらかが不正ならerrorを表示して終了する
+fn load_config_and_storage_directory() -> PathBuf {
     let config = match load_schronu_config(env::var_os("SCHRONU_CONFIG_PATH")) {
         Ok(config) => config,
         Err(error) => {
@@ -10753,30 +11752,301 @@ pub(super) fn application() {
         }
     };
     let _ = ACTIVE_CONFIG.set(config);
-    let project_storage_directory =
-        match resolve_project_storage_directory(env::var_os("SCHRONU_STORAGE_DIR")) {
-            Ok(directory) => directory,
-            Err(error) => {
-                eprintln!("[Error] {error}");
-                process::exit(1);
+    match resolve_project_storage_directory(env::var_os("SCHRONU_STORAGE_DIR")) {
+        Ok(directory) => directory,
+        Err(error) => {
+            eprintln!("[Error] {error}");
+            process::exit(1);
+        }
+    }
+}
+
+// schronud binaryの入口。schronuからは呼ばない
+#[allow(dead_code)]
+pub(super) fn daemon_application() {
+    let control_opt = parse_non_interactive_command(env::args().skip(1).collect());
+    let storage_directory = load_config_and_storage_directory();
+    #[cfg(unix)]
+    let result = run_daemon(&storage_directory, control_opt.as_deref());
+    #[cfg(not(unix))]
+    let result = {
+        let _ = (storage_directory, control_opt);
+        Err::<(), _>(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "schronud requires Unix domain sockets",
+        ))
+    };
+    if let Err(error) = result {
+        eprintln!("[Error] {error}");
+        process::exit(1);
+    }
+}
+
+#[cfg(unix)]
+fn run_daemon(storage_directory: &Path, control_opt: Option<&str>) -> std::io::Result<()> {
+    match control_opt {
+        None => {}
+        Some("status") => {
+            match DaemonClient::connect(storage_directory)? {
+                Some(mut client) => match client.request(&DaemonRequest::Ping)? {
+                    DaemonResponse::Pong { pid } => println!("schronud: 起動中 (pid: {pid})"),
+                    response => return Err(unexpected_daemon_response(&response)),
+                },
+                None => println!("schronud: 停止中"),
+            }
+            return Ok(());
+        }
+        Some("stop") => {
+            match DaemonClient::connect(storage_directory)? {
+                Some(mut client) => match client.request(&DaemonRequest::Shutdown)? {
+                    DaemonResponse::ShuttingDown => println!("schronud: 停止しました"),
+                    response => return Err(unexpected_daemon_response(&response)),
+                },
+                None => println!("schronud: 停止中"),
+            }
+            return Ok(());
+        }
+        Some(_) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "usage: schronud [status|stop]",
+            ))
+        }
+    }
+
+    let config = active_config();
+    let datetime_policy = BusinessDateTimePolicy::new(config.end_of_day_offset_minutes)
+        .with_non_business_weekdays(&config.non_business_weekdays)
+        .with_holiday_weekday(config.holiday_weekday);
+    let storage_directory_text = storage_directory
+        .to_str()
+        .expect("storage path was validated");
+    match config.storage_backend {
+        StorageBackend::Yaml => {
+            let cipher =
+                open_active_storage_cipher(storage_directory).map_err(std::io::Error::other)?;
+            serve_daemon(
+                storage_directory,
+                McpServer::with_storage_directory(
+                    TaskRepository::new(storage_directory_text)
+                        .with_history_retention_revisions(
+                            config.storage_history_retention_revisions,
+                        )
+                        .with_storage_cipher(cipher.clone()),
+                    storage_directory,
+                )
+                .with_storage_cipher(cipher)
+                .with_datetime_policy(datetime_policy, config.default_deadline_time)
+                .with_busy_time_slots_path(&config.busy_time_slots_yaml_path)
+                .with_lock_wait(StdDuration::from_secs(config.mcp_lock_wait_seconds)),
+            )
+        }
+        StorageBackend::Sqlite => {
+            ensure_active_storage_unencrypted(storage_directory).map_err(std::io::Error::other)?;
+            serve_daemon(
+                storage_directory,
+                McpServer::with_storage_directory(
+                    SqliteTaskRepository::open(storage_directory_text)
+                        .map_err(std::io::Error::other)?,
+                    storage_directory,
+                )
+                .with_datetime_policy(datetime_policy, config.default_deadline_time)
+                .with_busy_time_slots_path(&config.busy_time_slots_yaml_path)
+                .with_lock_wait(StdDuration::from_secs(config.mcp_lock_wait_seconds)),
+            )
+        }
+    }
+}
+
+// 非対話のCLIとmcpの要求を1つのrepositoryで順に処理する。
+// 対話モードなど直接file操作するprocessと共存できるよう、要求ごとのlockと変更の再読込は続ける
+#[cfg(unix)]
+fn serve_daemon<R: TaskRepositoryTrait>(
+    storage_directory: &Path,
+    mut server: McpServer<R>,
+) -> std::io::Result<()> {
+    let events = spawn_daemon_listener(bind_daemon_socket(storage_directory)?);
+    println!(
+        "schronud: {} で待ち受けます (pid: {})",
+        storage_directory.display(),
+        process::id()
+    );
+    let mut mcp_sessions: HashMap<u64, McpSession> = HashMap::new();
+    // 停止を頼んだ接続が応答を読み終えて切れるまで待ってから終わる
+    let mut stopping_connection_id_opt = None;
+    for event in events {
+        let (connection_id, request, reply) = match event {
+            DaemonEvent::Request {
+                connection_id,
+                request,
+                reply,
+            } => (connection_id, request, reply),
+            DaemonEvent::Disconnected { connection_id } => {
+                mcp_sessions.remove(&connection_id);
+                if stopping_connection_id_opt == Some(connection_id) {
+                    break;
+                }
+                continue;
             }
         };
-    let mut task_repository = TaskRepository::new(
-        project_storage_directory
-            .to_str()
-            .expect("storage path was validated"),
+        let response = match request {
+            _ if stopping_connection_id_opt.is_some() => DaemonResponse::Error {
+                message: "schronud is shutting down".to_string(),
+            },
+            DaemonRequest::Ping => DaemonResponse::Pong { pid: process::id() },
+            DaemonRequest::Cli {
+                command,
+                supports_ansi_color,
+            } => execute_daemon_cli_command(server.repository_mut(), &command, supports_ansi_color),
+            DaemonRequest::Mcp { message } => DaemonResponse::Mcp {
+                message: server.handle_session_request(
+                    mcp_sessions.entry(connection_id).or_default(),
+                    message,
+                ),
+            },
+            DaemonRequest::Shutdown => {
+                remove_daemon_socket(storage_directory)?;
+                stopping_connection_id_opt = Some(connection_id);
+                DaemonResponse::ShuttingDown
+            }
+        };
+        let _ = reply.send(response);
+    }
+    remove_daemon_socket(storage_directory)
+}
+
+#[cfg(unix)]
+fn execute_daemon_cli_command(
+    task_repository: &mut dyn TaskRepositoryTrait,
+    command: &str,
+    supports_ansi_color: bool,
+) -> DaemonResponse {
+    let mut stdout = CapturedOutput::new(supports_ansi_color);
+    let mut stderr = Vec::new();
+    let result = execute_non_interactive_command_with_output(
+        &mut stdout,
+        task_repository,
+        &mut FreeTimeManager::new().with_holiday_weekday(active_config().holiday_weekday),
+        command,
+        Local::now(),
     );
-    let mut free_time_manager = FreeTimeManager::new();
+    let success = report_run_result(&mut stderr, result);
+    DaemonResponse::Cli {
+        stdout: stdout.into_string(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        success,
+    }
+}
 
-    // controllerで実体を見るのを避けるために、1つ関数を切る
-    let result = match command_opt {
-        Some(command) => {
-            execute_non_interactive_command(&mut task_repository, &mut free_time_manager, &command)
+#[cfg(unix)]
+#[test]
+fn test_execute_daemon_cli_command_出力と失敗をまとめて返す() {
+    let storage_dir = TestStorageDir::new();
+    std::fs::create_dir_all(&storage_dir.path).unwrap();
+    let mut task_repository =
+        TestTaskRepository::new(new_test_task_handle("既存project").unwrap(), Local::now())
+            .with_storage_directory(&storage_dir.path);
+
+    let verified = execute_daemon_cli_command(&mut task_repository, "検証", false);
+    let DaemonResponse::Cli {
+        stderr, success, ..
+    } = execute_daemon_cli_command(&mut task_repository, "存在しないcommand", false)
+    else {
+        panic!("cli response expected");
+    };
+
+    assert_eq!(
+        verified,
+        DaemonResponse::Cli {
+            stdout: "検証: OK\n".to_string(),
+            stderr: String::new(),
+            success: true,
         }
-        None => interactive_application(&mut task_repository, &mut free_time_manager),
+    );
+    assert!(!success);
+    assert!(stderr.contains("[Error]"));
+}
+
+// 対話モードはschronudを経由しないため、commandの間だけ持つ.lockでschronudと順番を譲り合う
+#[cfg(unix)]
+#[test]
+fn test_execute_daemon_cli_command_対話モードがlockを持つ間は待って失敗し手放した後は保存を読み直す(
+) {
+    let storage_dir = TestStorageDir::new();
+    std::fs::create_dir_all(&storage_dir.path).unwrap();
+    let now = Local.with_ymd_and_hms(2026, 8, 12, 12, 0, 0).unwrap();
+    let storage_path = storage_dir.path.to_str().unwrap();
+    {
+        let mut repository = TaskRepository::new(storage_path);
+        repository.sync_clock(now);
+        repository
+            .start_new_project(new_test_task_handle("既存").unwrap())
+            .unwrap();
+        repository.save().unwrap();
+    }
+    let mut daemon_repository = TaskRepository::new(storage_path);
+    daemon_repository.sync_clock(now);
+    daemon_repository.load().unwrap();
+
+    let mut interactive_repository = TaskRepository::new(storage_path);
+    let interactive_lock = reload_repository_for_cli(&mut interactive_repository, now).unwrap();
+    interactive_repository
+        .start_new_project(new_test_task_handle("対話追加").unwrap())
+        .unwrap();
+    interactive_repository.save().unwrap();
+
+    let DaemonResponse::Cli {
+        stderr, success, ..
+    } = execute_daemon_cli_command(&mut daemon_repository, "検証", false)
+    else {
+        panic!("cli response expected");
     };
-    if !report_run_result(&mut std::io::stderr(), result) {
-        process::exit(1);
+    assert!(!success);
+    assert!(stderr.contains("[Error]"));
+    assert_eq!(daemon_repository.get_all_projects().len(), 1);
+
+    drop(interactive_lock);
+    let DaemonResponse::Cli { success, .. } =
+        execute_daemon_cli_command(&mut daemon_repository, "検証", false)
+    else {
+        panic!("cli response expected");
+    };
+    assert!(success);
+    assert_eq!(daemon_repository.get_all_projects().len(), 2);
+}
+
+// schronudが動いていれば、commandをそちらで実行して出力だけを受け取る。動いていなければNoneを返す
+#[cfg(unix)]
+fn forward_command_to_daemon(
+    storage_directory: &Path,
+    command: &str,
+) -> std::io::Result<Option<bool>> {
+    let Some(mut client) = DaemonClient::connect(storage_directory)? else {
+        return Ok(None);
+    };
+    let request = DaemonRequest::Cli {
+        command: command.to_string(),
+        supports_ansi_color: stdout().supports_ansi_color(),
+    };
+    match client.request(&request)? {
+        DaemonResponse::Cli {
+            stdout,
+            stderr,
+            success,
+        } => {
+            print!("{stdout}");
+            eprint!("{stderr}");
+            Ok(Some(success))
+        }
+        response => Err(unexpected_daemon_response(&response)),
+    }
+}
+
+#[cfg(unix)]
+fn unexpected_daemon_response(response: &DaemonResponse) -> std::io::Error {
+    match response {
+        DaemonResponse::Error { message } => std::io::Error::other(message.clone()),
+        response => std::io::Error::other(format!("unexpected schronud response: {response:?}")),
     }
 }
 
@@ -10885,14 +12155,51 @@ fn execute_non_interactive_command_at(
     free_time_manager: &mut dyn FreeTimeManagerTrait,
     command: &str,
     operation_now: DateTime<Local>,
+) -> Result<(), RunError> {
+    execute_non_interactive_command_with_output(
+        &mut stdout(),
+        task_repository,
+        free_time_manager,
+        command,
+        operation_now,
+    )
+}
+
+fn execute_non_interactive_command_with_output(
+    stdout: &mut dyn SchronuWriter,
+    task_repository: &mut dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    command: &str,
+    operation_now: DateTime<Local>,
 ) -> Result<(), RunError> {
     let parsed_command = parse_command(command, ParseMode::NonInteractive)
         .map_err(map_command_parse_error)
         .map_err(RunError::Command)?;
     validate_non_interactive_command(&parsed_command).map_err(RunError::Command)?;
+    if execute_lock_command(
+        stdout,
+        Path::new(task_repository.get_project_storage_dir_name()),
+        &parsed_command,
+    )? {
+        return Ok(());
+    }
     if parsed_command.kind() == CommandKind::Verify {
         let _storage_lock = reload_repository_for_cli(task_repository, operation_now)?;
-        println!("検証: OK");
+        let mut context = RuntimeVerifyCommandContext {
+            task_repository,
+            now: operation_now,
+            unresolved_count: 0,
+        };
+        let outcome = handle_verify_command(&parsed_command, &mut context)
+            .map_err(CommandError::from)?
+            .expect("verify command is handled");
+        render_display_model(stdout, &outcome.display).map_err(CommandError::Output)?;
+        if context.unresolved_count > 0 {
+            return Err(CommandError::from(VerifyCommandError::Unresolved {
+                count: context.unresolved_count,
+            })
+            .into());
+        }
         return Ok(());
     }
     free_time_manager.load_busy_time_slots_from_file(
@@ -10901,14 +12208,19 @@ fn execute_non_interactive_command_at(
             .to_str()
             .expect("config path was validated"),
     )?;
+    load_busy_blocks(
+        free_time_manager,
+        &BusyBlockFile::in_storage_directory(task_repository.get_project_storage_dir_name())
+            .with_cipher(active_storage_cipher()),
+    )?;
 
     let focus_started_datetime = operation_now;
-    let mut stdout = stdout();
     run_cli_repository_transaction(task_repository, operation_now, |task_repository| {
+        record_daily_load_snapshot(task_repository, free_time_manager);
         let mut focused_task_id_opt: Option<Uuid> =
             select_focus_task_id(task_repository, FocusSelectionMode::HighestPriority)?;
         execute_parsed(
-            &mut stdout,
+            stdout,
             task_repository,
             free_time_manager,
             &mut focused_task_id_opt,
@@ -10996,6 +12308,80 @@ fn test_execute_non_interactive_command_finishはoperation時刻を共有する(
     assert_eq!(next_repetition.get_create_time().unwrap(), operation_now);
 }
 
+#[test]
+fn test_execute_non_interactive_command_待は相手と次の催促までのpendingを記録する() {
+    let storage_dir = TestStorageDir::new();
+    std::fs::create_dir_all(&storage_dir.path).unwrap();
+    let previous_synced_time = Local.with_ymd_and_hms(2026, 8, 19, 9, 0, 0).unwrap();
+    let operation_now = Local.with_ymd_and_hms(2026, 8, 19, 14, 30, 0).unwrap();
+    let focused = new_test_task_handle("見積もりの返事").unwrap();
+    let mut task_repository = TestTaskRepository::new(focused, previous_synced_time)
+        .with_storage_directory(&storage_dir.path);
+    let mut free_time_manager = TestFreeTimeManager;
+
+    execute_non_interactive_command_at(
+        &mut task_repository,
+        &mut free_time_manager,
+        "待 田中 金 3",
+        operation_now,
+    )
+    .unwrap();
+
+    let waiting = task_repository
+        .task
+        .get_waiting_opt()
+        .unwrap()
+        .expect("待の相手を記録すべきです");
+    assert_eq!(waiting.get_counterpart(), "田中");
+    assert_eq!(waiting.get_since(), operation_now);
+    assert_eq!(
+        waiting.get_expected_reply_date_opt(),
+        NaiveDate::from_ymd_opt(2026, 8, 21)
+    );
+    assert_eq!(waiting.get_follow_up_interval_days_opt(), Some(3));
+    assert!(task_repository.task.get_is_on_other_side().unwrap());
+    assert_eq!(
+        task_repository.task.get_orig_status().unwrap(),
+        Status::Pending
+    );
+    assert_eq!(
+        task_repository.task.get_pending_until().unwrap(),
+        Local.with_ymd_and_hms(2026, 8, 22, 6, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn test_format_follow_up_nudge_催促の予定を過ぎたら相手と経過日数を示す() {
+    let since = Local.with_ymd_and_hms(2026, 8, 19, 14, 30, 0).unwrap();
+    let task = new_test_task_handle("見積もりの返事").unwrap();
+    task.set_waiting_opt(Some(WaitingRecord::new(
+        "田中",
+        since,
+        NaiveDate::from_ymd_opt(2026, 8, 21),
+        Some(3),
+    )))
+    .unwrap();
+
+    assert_eq!(
+        format_follow_up_nudge(
+            &task,
+            Local.with_ymd_and_hms(2026, 8, 22, 5, 59, 0).unwrap()
+        ),
+        Ok(None)
+    );
+    assert_eq!(
+        format_follow_up_nudge(&task, Local.with_ymd_and_hms(2026, 8, 22, 7, 0, 0).unwrap()),
+        Ok(Some(
+            "[催促] 田中を待って2日です(返答予定 2026/08/21)。催促したら「待 催」、返事が来たら「待 消」"
+                .to_string()
+        ))
+    );
+    assert_eq!(
+        format_follow_up_nudge(&new_test_task_handle("待っていない").unwrap(), since),
+        Ok(None)
+    );
+}
+
 #[test]
 fn test_execute_non_interactive_command_省略作業時間はoperation時刻を使う() {
     let storage_dir = TestStorageDir::new();
@@ -11077,6 +12463,87 @@ fn test_execute_non_interactive_command_検証はsaveとfree_time読込を行わ
     assert_eq!(task_repository.save_attempt_count.get(), 0);
 }
 
+#[cfg(unix)]
+#[test]
+fn test_execute_daemon_cli_command_検証は食い違いをcode付きで示し_fixで直して保存する() {
+    let storage_dir = TestStorageDir::new();
+    std::fs::create_dir_all(&storage_dir.path).unwrap();
+    let parent = new_test_task_handle("親").unwrap();
+    let child = parent.create_as_last_child(new_test_task_attr("子"));
+    parent.set_orig_status(Status::Done).unwrap();
+    let (parent_id, child_id) = (parent.get_id().unwrap(), child.get_id().unwrap());
+    let mut task_repository =
+        TestTaskRepository::new(parent, Local::now()).with_storage_directory(&storage_dir.path);
+
+    let checked = execute_daemon_cli_command(&mut task_repository, "検証", false);
+    assert_eq!(
+        checked,
+        DaemonResponse::Cli {
+            stdout: format!(
+                "[Warn] done_parent_with_undone_children 親 ({parent_id}): 完了したtaskに未完了の子があります。\n\
+                 検証: 1件の食い違いがあります。1件は検証 --fixで直せます。\n"
+            ),
+            stderr: "[Error] 検証エラー: 直していない食い違いが1件あります\n".to_string(),
+            success: false,
+        }
+    );
+    assert_eq!(task_repository.save_attempt_count.get(), 0);
+
+    let fixed = execute_daemon_cli_command(&mut task_repository, "検証 --fix", false);
+    assert_eq!(
+        fixed,
+        DaemonResponse::Cli {
+            stdout: format!(
+                "[Info] done_parent_with_undone_children 親 ({parent_id}): 完了したtaskに未完了の子があります。未完了に戻しました。\n\
+                 検証: 1件の食い違いのうち1件を直しました。\n"
+            ),
+            stderr: String::new(),
+            success: true,
+        }
+    );
+    assert_eq!(task_repository.save_attempt_count.get(), 1);
+    let parent = task_repository.get_by_id(parent_id).unwrap().unwrap();
+    assert_eq!(parent.get_orig_status().unwrap(), Status::Todo);
+    assert!(task_repository.get_by_id(child_id).unwrap().is_some());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_execute_daemon_cli_command_錠はlockを取らずに保持者を示す() {
+    let storage_dir = TestStorageDir::new();
+    std::fs::create_dir_all(&storage_dir.path).unwrap();
+    let mut task_repository =
+        TestTaskRepository::new(new_test_task_handle("既存").unwrap(), Local::now())
+            .with_storage_directory(&storage_dir.path);
+    let succeeded = |stdout: String| DaemonResponse::Cli {
+        stdout,
+        stderr: String::new(),
+        success: true,
+    };
+
+    assert_eq!(
+        execute_daemon_cli_command(&mut task_repository, "錠", false),
+        succeeded("錠: 空いています\n".to_string())
+    );
+
+    let mcp_lock = StorageLock::acquire(&storage_dir.path, LockMode::Mcp).unwrap();
+    let holder = mcp_lock.holder().clone();
+    let since = holder.started_at.format("%Y/%m/%d %H:%M:%S");
+    assert_eq!(
+        execute_daemon_cli_command(&mut task_repository, "lock status", false),
+        succeeded(format!("錠: {holder}({since}から)が保持しています\n"))
+    );
+
+    drop(mcp_lock);
+    assert_eq!(
+        execute_daemon_cli_command(&mut task_repository, "錠 状", false),
+        succeeded(format!(
+            "錠: 空いています(最後の保持者: {holder}({since}から))\n"
+        ))
+    );
+    assert_eq!(task_repository.save_attempt_count.get(), 0);
+}
+
 #[test]
 fn test_execute_non_interactive_command_gatewayの変換errorをstderrへ表示する() {
     let storage_dir = TestStorageDir::new();
@@ -11187,6 +12654,110 @@ fn test_cli_repository初期load後はmcpがlockを取得できる() {
     assert!(mcp_lock.is_ok());
 }
 
+#[test]
+fn test_interactive_storage_changedは他processの保存を読み込み変わったtaskを返す() {
+    let storage_dir = TestStorageDir::new();
+    std::fs::create_dir_all(&storage_dir.path).unwrap();
+    let now = Local.with_ymd_and_hms(2026, 8, 12, 12, 0, 0).unwrap();
+    {
+        let mut repository = TaskRepository::new(storage_dir.path.to_str().unwrap());
+        repository.sync_clock(now);
+        repository
+            .start_new_project(new_test_task_handle("既存").unwrap())
+            .unwrap();
+        repository.save().unwrap();
+    }
+    let mut cli_repository = TaskRepository::new(storage_dir.path.to_str().unwrap());
+    drop(reload_repository_for_cli(&mut cli_repository, now).unwrap());
+
+    {
+        let _mcp_lock = StorageLock::acquire(&storage_dir.path, LockMode::Mcp).unwrap();
+        let mut mcp_repository = TaskRepository::new(storage_dir.path.to_str().unwrap());
+        mcp_repository.sync_clock(now);
+        mcp_repository.load().unwrap();
+        mcp_repository
+            .start_new_project(new_test_task_handle("MCP追加").unwrap())
+            .unwrap();
+        mcp_repository.save().unwrap();
+    }
+
+    let mut free_time_manager = TestFreeTimeManager;
+    let mut stdout = TestWriter::new();
+    let mut focused_task_id_opt = None;
+    let mut last_focused_task_id_opt = None;
+    let mut focus_started_datetime = now;
+    let mut focus_selection_mode = FocusSelectionMode::HighestPriority;
+    let mut handle_storage_changed = |repository: &mut TaskRepository| {
+        handle_interactive_repository_event(
+            &mut stdout,
+            repository,
+            &mut free_time_manager,
+            InteractiveRepositoryState {
+                focused_task_id_opt: &mut focused_task_id_opt,
+                last_focused_task_id_opt: &mut last_focused_task_id_opt,
+                focus_started_datetime: &mut focus_started_datetime,
+                focus_selection_mode: &mut focus_selection_mode,
+            },
+            InteractiveRepositoryEvent::StorageChanged,
+        )
+    };
+
+    let outcome = handle_storage_changed(&mut cli_repository);
+    let InteractiveRepositoryEventOutcome::Reloaded(changes) = outcome else {
+        panic!("他processの保存を読み込んだ結果を返す");
+    };
+    assert_eq!(changes.len(), 1);
+    assert!(matches!(
+        &changes[0],
+        TaskRevisionChange::Added { name, .. } if name == "MCP追加"
+    ));
+    assert_eq!(cli_repository.get_all_projects().len(), 2);
+
+    assert!(matches!(
+        handle_storage_changed(&mut cli_repository),
+        InteractiveRepositoryEventOutcome::Unchanged
+    ));
+}
+
+#[test]
+fn test_write_storage_change_bannerは変更の種類と件数を表示し多い場合は省略する() {
+    let mut changes = vec![
+        TaskRevisionChange::Added {
+            id: "a".to_string(),
+            name: "追加".to_string(),
+        },
+        TaskRevisionChange::Removed {
+            id: "r".to_string(),
+            name: "削除".to_string(),
+        },
+        TaskRevisionChange::Changed {
+            id: "c".to_string(),
+            name: "変更".to_string(),
+            fields: vec![],
+        },
+    ];
+    let mut stdout = TestWriter::new();
+    write_storage_change_banner(&mut stdout, &changes);
+    let output = stdout.into_string();
+    assert!(output.contains("[更新] 他processの保存により3件のtaskが変わりました"));
+    assert!(output.contains("  + 追加\n"));
+    assert!(output.contains("  - 削除\n"));
+    assert!(output.contains("  ~ 変更\n"));
+    assert!(!output.contains("... 他"));
+
+    changes.extend((0..10).map(|index| TaskRevisionChange::Added {
+        id: format!("extra-{index}"),
+        name: format!("追加{index}"),
+    }));
+    let mut stdout = TestWriter::new();
+    write_storage_change_banner(&mut stdout, &changes);
+    let output = stdout.into_string();
+    assert!(output.contains("13件のtaskが変わりました"));
+    assert!(output.contains("  + 追加6\n"));
+    assert!(!output.contains("  + 追加7\n"));
+    assert!(output.contains("  ... 他3件"));
+}
+
 #[test]
 fn test_cli_repository_transactionは外部更新を再読込してcommandを即時保存する() {
     let storage_dir = TestStorageDir::new();
@@ -11445,6 +13016,73 @@ fn test_interactive_submitは製品event経路でload実行保存する() {
     assert!(StorageLock::acquire(&storage_dir.path, LockMode::Mcp).is_ok());
 }
 
+#[test]
+fn test_interactive_submitは他processが入れた予定とcommandで消した予定を空き時間へ反映する() {
+    let storage_dir = TestStorageDir::new();
+    std::fs::create_dir_all(&storage_dir.path).unwrap();
+    let operation_now = Local.with_ymd_and_hms(2026, 8, 20, 9, 0, 0).unwrap();
+    let existing = new_test_task_handle("既存project").unwrap();
+    let mut repository =
+        TestTaskRepository::new(existing, operation_now).with_storage_directory(&storage_dir.path);
+    let mut free_time_manager = FreeTimeManager::new();
+    let mut focused_task_id_opt = None;
+    let mut last_focused_task_id_opt = None;
+    let mut focus_started_datetime = operation_now;
+    let mut focus_selection_mode = FocusSelectionMode::HighestPriority;
+    let busy_start = Local.with_ymd_and_hms(2026, 8, 20, 15, 0, 0).unwrap();
+    let busy_end = Local.with_ymd_and_hms(2026, 8, 20, 16, 0, 0).unwrap();
+    // MCPが別processから入れた予定
+    let block = add_busy_block(
+        &mut BusyBlockFile::in_storage_directory(&storage_dir.path),
+        "歯医者",
+        BusyBlockSpan::Timed {
+            start: busy_start,
+            end: busy_end,
+        },
+    )
+    .unwrap();
+    let mut submit = |free_time_manager: &mut FreeTimeManager, line: &str| {
+        handle_interactive_submit_at(
+            &mut TestWriter::new(),
+            &mut repository,
+            free_time_manager,
+            InteractiveRepositoryState {
+                focused_task_id_opt: &mut focused_task_id_opt,
+                last_focused_task_id_opt: &mut last_focused_task_id_opt,
+                focus_started_datetime: &mut focus_started_datetime,
+                focus_selection_mode: &mut focus_selection_mode,
+            },
+            line,
+            operation_now,
+        )
+    };
+
+    let listed = submit(&mut free_time_manager, "埋");
+
+    assert!(matches!(
+        listed,
+        InteractiveRepositoryEventOutcome::CommandExecuted(..)
+    ));
+    assert_eq!(
+        free_time_manager.get_busy_minutes(&busy_start, &busy_end),
+        60
+    );
+
+    let removed = submit(
+        &mut free_time_manager,
+        &format!("埋 消 {}", &block.busy_block_id.to_string()[..8]),
+    );
+
+    assert!(matches!(
+        removed,
+        InteractiveRepositoryEventOutcome::CommandExecuted(..)
+    ));
+    assert_eq!(
+        free_time_manager.get_busy_minutes(&busy_start, &busy_end),
+        0
+    );
+}
+
 #[test]
 fn test_interactive_submitはoperation時刻をcommandと直後renderへ共有する() {
     let storage_dir = TestStorageDir::new();
@@ -12013,6 +13651,33 @@ fn make_messages_about_focus(
     Ok([summary, progress])
 }
 
+// 催促の時刻を過ぎて再びfocusに上がった相手ボールには、催促を促す行を出す
+fn format_follow_up_nudge(
+    focused_task: &TaskHandle,
+    now: DateTime<Local>,
+) -> Result<Option<String>, ApplicationError> {
+    let Some(waiting) = focused_task
+        .get_waiting_opt()
+        .map_err(ApplicationError::TaskTree)?
+    else {
+        return Ok(None);
+    };
+    if !waiting.is_follow_up_due(now) {
+        return Ok(None);
+    }
+
+    let expected_reply = waiting
+        .get_expected_reply_date_opt()
+        .map(|date| format!("(返答予定 {})", date.format("%Y/%m/%d")))
+        .unwrap_or_default();
+    Ok(Some(format!(
+        "[催促] {}を待って{}日です{}。催促したら「待 催」、返事が来たら「待 消」",
+        waiting.get_counterpart(),
+        waiting.age_days(now),
+        expected_reply,
+    )))
+}
+
 fn format_focus_progress(
     estimated_work_seconds: i64,
     actual_work_seconds: i64,
@@ -12288,10 +13953,33 @@ fn render_focused_task(
         for message in messages {
             writeln_newline(stdout, &message).unwrap();
         }
+        match format_follow_up_nudge(&focused_task, now) {
+            Ok(Some(nudge)) => writeln_newline(stdout, &nudge).unwrap(),
+            Ok(None) => {}
+            Err(error) => report_application_result::<()>(stdout, Err(error)),
+        }
         stdout.flush().unwrap();
     }
 }
 
+// 計時はrender_focused_taskがfocusの切り替わりを見たときに始まるので、表示後のfocusを記録する
+fn record_work_session(
+    stdout: &mut dyn SchronuWriter,
+    work_session_opt: Option<&mut WorkSession>,
+    focused_task_id_opt: Option<Uuid>,
+    last_focused_task_id_opt: Option<Uuid>,
+    focus_started_datetime: DateTime<Local>,
+) {
+    let Some(work_session) = work_session_opt else {
+        return;
+    };
+    let working_task_id_opt =
+        focused_task_id_opt.filter(|_| focused_task_id_opt == last_focused_task_id_opt);
+    if let Err(error) = work_session.record_focus(working_task_id_opt, focus_started_datetime) {
+        writeln_newline(stdout, &format!("[Error] 作業記録エラー: {error}")).unwrap();
+    }
+}
+
 struct FocusRenderState<'a> {
     focused_task_id_opt: &'a mut Option<Uuid>,
     last_focused_task_id_opt: &'a mut Option<Uuid>,
@@ -12389,7 +14077,38 @@ fn should_suppress_leaf_tasks_after_command(line: &str) -> bool {
             | Some('葉')
             | Some('樹')
             | Some('清')
-    ) || matches!(line.split_whitespace().next(), Some("band" | "pack"))
+            | Some('履')
+            | Some('流')
+            | Some('版')
+            | Some('捨')
+            | Some('時')
+            | Some('危')
+            | Some('錠')
+    ) || matches!(
+        line.split_whitespace().next(),
+        Some(
+            "band"
+                | "pack"
+                | "history"
+                | "flow"
+                | "metrics"
+                | "revision"
+                | "rev"
+                | "trash"
+                | "delete"
+                | "del"
+                | "when"
+                | "risk"
+                | "at_risk"
+                | "lock"
+        )
+    ) || matches!(
+        line.split_whitespace()
+            .take(2)
+            .collect::<Vec<_>>()
+            .as_slice(),
+        ["待" | "wait", "一覧" | "list" | "ls"]
+    )
 }
 
 #[test]
@@ -12667,6 +14386,7 @@ struct InteractiveRepositoryState<'a> {
 enum InteractiveRepositoryEvent<'a> {
     Submit { line: &'a str },
     Refresh,
+    StorageChanged,
     Exit,
     InputDisconnected,
     InputRead(std::io::Error),
@@ -12675,6 +14395,10 @@ enum InteractiveRepositoryEvent<'a> {
 
 enum InteractiveRepositoryEventOutcome {
     Continue,
+    // 他processの保存を読み込んだ。描き直した画面の上に変更を表示する
+    Reloaded(Vec<TaskRevisionChange>),
+    // 通知はあったが保存内容は読込済みのものと同じだった
+    Unchanged,
     CommandExecuted(String, DateTime<Local>),
     Retry(CliRepositoryTransactionError),
     Exit,
@@ -12690,23 +14414,32 @@ fn handle_interactive_submit_at(
     operation_now: DateTime<Local>,
 ) -> InteractiveRepositoryEventOutcome {
     let command = line.trim().to_string();
+    if let Ok(parsed_command) = parse_command(&command, ParseMode::Interactive) {
+        if parsed_command.kind() == CommandKind::Lock {
+            write_interactive_command_header(stdout, operation_now, &command);
+            if let Err(error) = execute_lock_command(
+                stdout,
+                Path::new(task_repository.get_project_storage_dir_name()),
+                &parsed_command,
+            ) {
+                let _output_error = render_display_model(stdout, &error_display_model(&error))
+                    .map_err(CommandError::Output);
+            }
+            return InteractiveRepositoryEventOutcome::CommandExecuted(command, operation_now);
+        }
+    }
     let transaction_result =
         run_cli_repository_transaction(task_repository, operation_now, |task_repository| {
             reconcile_interactive_state_after_reload(task_repository, &mut state, operation_now)?;
-            writeln_newline(stdout, "").unwrap();
-            writeln_newline(
-                stdout,
-                &format!(
-                    "{}{}> {}{}",
-                    style::Bold,
-                    operation_now.format("%Y/%m/%d %H:%M:%S.%f"),
-                    command,
-                    style::Reset
-                ),
-            )
-            .unwrap();
-            writeln_newline(stdout, "").unwrap();
-            stdout.flush().unwrap();
+            // MCPなど他processが入れたり消したりした予定も、command前に読み直して空き時間へ反映する
+            reload_busy_blocks(
+                free_time_manager,
+                &BusyBlockFile::in_storage_directory(
+                    task_repository.get_project_storage_dir_name(),
+                )
+                .with_cipher(active_storage_cipher()),
+            )?;
+            write_interactive_command_header(stdout, operation_now, &command);
 
             if execute_interactive_command(
                 stdout,
@@ -12737,6 +14470,27 @@ fn handle_interactive_submit_at(
     }
 }
 
+fn write_interactive_command_header(
+    stdout: &mut dyn SchronuWriter,
+    operation_now: DateTime<Local>,
+    command: &str,
+) {
+    writeln_newline(stdout, "").unwrap();
+    writeln_newline(
+        stdout,
+        &format!(
+            "{}{}> {}{}",
+            style::Bold,
+            operation_now.format("%Y/%m/%d %H:%M:%S.%f"),
+            command,
+            style::Reset
+        ),
+    )
+    .unwrap();
+    writeln_newline(stdout, "").unwrap();
+    stdout.flush().unwrap();
+}
+
 fn reconcile_interactive_state_after_reload(
     task_repository: &mut dyn TaskRepositoryTrait,
     state: &mut InteractiveRepositoryState<'_>,
@@ -12780,6 +14534,27 @@ fn handle_interactive_repository_event(
                 Err(error) => InteractiveRepositoryEventOutcome::Retry(error),
             }
         }
+        InteractiveRepositoryEvent::StorageChanged => {
+            let now = Local::now();
+            let projects_before = task_repository
+                .get_all_projects()
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>();
+            match reload_repository_for_cli_with_outcome(task_repository, now) {
+                Ok((_storage_lock, RepositoryReloadOutcome::Cached)) => {
+                    InteractiveRepositoryEventOutcome::Unchanged
+                }
+                Ok((_storage_lock, RepositoryReloadOutcome::Reloaded)) => {
+                    reconcile_interactive_state_after_reload(task_repository, &mut state, now);
+                    InteractiveRepositoryEventOutcome::Reloaded(changed_tasks_after_reload(
+                        &projects_before,
+                        &task_repository.get_all_projects(),
+                    ))
+                }
+                Err(error) => InteractiveRepositoryEventOutcome::Retry(error),
+            }
+        }
         InteractiveRepositoryEvent::Exit => {
             let now = Local::now();
             match reload_repository_for_cli(task_repository, now) {
@@ -12815,6 +14590,78 @@ fn handle_interactive_repository_event(
     }
 }
 
+// 再読込で差し替わったprojectだけを比べる。読込済みのnodeをそのまま使うprojectは変わっていない
+fn changed_tasks_after_reload(
+    projects_before: &[TaskHandle],
+    projects_after: &[&TaskHandle],
+) -> Vec<TaskRevisionChange> {
+    let revision_nodes = |projects: Vec<&TaskHandle>| {
+        projects
+            .into_iter()
+            .flat_map(|project| task_tree_revision_nodes(project).unwrap_or_default())
+            .collect::<Vec<_>>()
+    };
+    let replaced_before = projects_before
+        .iter()
+        .filter(|before| !projects_after.iter().any(|after| after.ptr_eq(before)))
+        .collect();
+    let replaced_after = projects_after
+        .iter()
+        .copied()
+        .filter(|after| !projects_before.iter().any(|before| before.ptr_eq(after)))
+        .collect();
+    diff_task_nodes(
+        &revision_nodes(replaced_before),
+        &revision_nodes(replaced_after),
+    )
+}
+
+const STORAGE_CHANGE_BANNER_MAX_TASKS: usize = 10;
+
+fn write_storage_change_banner(stdout: &mut dyn SchronuWriter, changes: &[TaskRevisionChange]) {
+    writeln_newline(stdout, "").unwrap();
+    if changes.is_empty() {
+        writeln_newline(
+            stdout,
+            &format!(
+                "{}[更新] 他processの保存を読み込みました(taskの変更はありません){}",
+                style::Bold,
+                style::Reset
+            ),
+        )
+        .unwrap();
+        return;
+    }
+    writeln_newline(
+        stdout,
+        &format!(
+            "{}[更新] 他processの保存により{}件のtaskが変わりました{}",
+            style::Bold,
+            changes.len(),
+            style::Reset
+        ),
+    )
+    .unwrap();
+    for change in changes.iter().take(STORAGE_CHANGE_BANNER_MAX_TASKS) {
+        let (mark, name) = match change {
+            TaskRevisionChange::Added { name, .. } => ("+", name),
+            TaskRevisionChange::Removed { name, .. } => ("-", name),
+            TaskRevisionChange::Changed { name, .. } => ("~", name),
+        };
+        writeln_newline(stdout, &format!("  {mark} {name}")).unwrap();
+    }
+    if changes.len() > STORAGE_CHANGE_BANNER_MAX_TASKS {
+        writeln_newline(
+            stdout,
+            &format!(
+                "  ... 他{}件",
+                changes.len() - STORAGE_CHANGE_BANNER_MAX_TASKS
+            ),
+        )
+        .unwrap();
+    }
+}
+
 fn load_busy_time_slots_for_interactive_application(
     free_time_manager: &mut dyn FreeTimeManagerTrait,
     busy_time_slots_file_path: &str,
@@ -12828,7 +14675,7 @@ fn interactive_application(
     free_time_manager: &mut dyn FreeTimeManagerTrait,
 ) -> Result<(), RunError> {
     let now = Local::now();
-    drop(reload_repository_for_cli(task_repository, now)?);
+    let storage_lock = reload_repository_for_cli(task_repository, now)?;
     load_busy_time_slots_for_interactive_application(
         free_time_manager,
         active_config()
@@ -12836,6 +14683,13 @@ fn interactive_application(
             .to_str()
             .expect("config path was validated"),
     )?;
+    load_busy_blocks(
+        free_time_manager,
+        &BusyBlockFile::in_storage_directory(task_repository.get_project_storage_dir_name())
+            .with_cipher(active_storage_cipher()),
+    )?;
+    record_daily_load_snapshot(task_repository, free_time_manager);
+    drop(storage_lock);
 
     let mut focus_selection_mode = FocusSelectionMode::HighestPriority;
     let mut focused_task_id_opt = select_focus_task_id(task_repository, focus_selection_mode)
@@ -12843,8 +14697,17 @@ fn interactive_application(
         .map_err(RunError::from)?;
     let mut last_focused_task_id_opt = None;
     let mut focus_started_datetime = now;
+    let mut pending_storage_changes_opt: Option<Vec<TaskRevisionChange>> = None;
+    let storage_directory = PathBuf::from(task_repository.get_project_storage_dir_name());
+    let mut work_session_opt = match WorkSession::start(&storage_directory) {
+        Ok(work_session) => Some(work_session),
+        Err(error) => {
+            eprintln!("[Error] 作業記録エラー: {error}");
+            None
+        }
+    };
 
-    interactive::run(now, |stdout, event| {
+    interactive::run(now, &storage_directory, |stdout, event| {
         if let interactive::DriverEvent::RenderScreen { now } = event {
             render_interactive_screen(
                 stdout,
@@ -12857,11 +14720,22 @@ fn interactive_application(
                 },
                 now,
             );
+            record_work_session(
+                stdout,
+                work_session_opt.as_mut(),
+                focused_task_id_opt,
+                last_focused_task_id_opt,
+                focus_started_datetime,
+            );
+            if let Some(changes) = pending_storage_changes_opt.take() {
+                write_storage_change_banner(stdout, &changes);
+            }
             return interactive::DriverOutcome::Continue;
         }
 
         let repository_event = match event {
             interactive::DriverEvent::Refresh => InteractiveRepositoryEvent::Refresh,
+            interactive::DriverEvent::StorageChanged => InteractiveRepositoryEvent::StorageChanged,
             interactive::DriverEvent::Submit { line } => {
                 InteractiveRepositoryEvent::Submit { line }
             }
@@ -12890,6 +14764,11 @@ fn interactive_application(
 
         match outcome {
             InteractiveRepositoryEventOutcome::Continue => interactive::DriverOutcome::Continue,
+            InteractiveRepositoryEventOutcome::Reloaded(changes) => {
+                pending_storage_changes_opt = Some(changes);
+                interactive::DriverOutcome::Continue
+            }
+            InteractiveRepositoryEventOutcome::Unchanged => interactive::DriverOutcome::Unchanged,
             InteractiveRepositoryEventOutcome::CommandExecuted(command, operation_now) => {
                 if !should_suppress_leaf_tasks_after_command(&command) {
                     let result =
@@ -12904,6 +14783,13 @@ fn interactive_application(
                     &mut focus_started_datetime,
                     operation_now,
                 );
+                record_work_session(
+                    stdout,
+                    work_session_opt.as_mut(),
+                    focused_task_id_opt,
+                    last_focused_task_id_opt,
+                    focus_started_datetime,
+                );
                 interactive::DriverOutcome::Submitted
             }
             InteractiveRepositoryEventOutcome::Retry(error) => {
@@ -0,0 +1,262 @@
+use schronu::adapter::gateway::sqlite_task_repository::SQLITE_DATABASE_FILE_NAME;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+// 保存の完了を表すfile。.lockは読込だけでも書き換わるため監視しない
+const WATCHED_FILE_NAMES: [&str; 2] = [".revision", SQLITE_DATABASE_FILE_NAME];
+
+// 1回の保存で続けて届く通知を、この時間だけ待ってまとめる
+const CHANGE_DEBOUNCE: Duration = Duration::from_millis(200);
+
+const POLLING_INTERVAL: Duration = Duration::from_secs(1);
+
+// 保存先directoryを監視するthreadを起動し、保存を検出するたびにnotifyを呼ぶ。
+// notifyがfalseを返したら監視を終える。inotifyを使えない環境ではpollingで監視する
+pub(super) fn spawn_storage_watcher(
+    storage_directory: PathBuf,
+    mut notify: impl FnMut() -> bool + Send + 'static,
+) {
+    thread::spawn(move || {
+        #[cfg(target_os = "linux")]
+        if inotify::watch(&storage_directory, &mut notify).is_ok() {
+            return;
+        }
+        watch_by_polling(&storage_directory, &mut notify, POLLING_INTERVAL);
+    });
+}
+
+fn is_watched_file_name(name: &[u8]) -> bool {
+    WATCHED_FILE_NAMES
+        .iter()
+        .any(|watched| watched.as_bytes() == name)
+}
+
+type StorageSignature = Vec<Option<(Option<SystemTime>, u64)>>;
+
+fn storage_signature(storage_directory: &Path) -> StorageSignature {
+    WATCHED_FILE_NAMES
+        .iter()
+        .map(|name| {
+            fs::metadata(storage_directory.join(name))
+                .ok()
+                .map(|metadata| (metadata.modified().ok(), metadata.len()))
+        })
+        .collect()
+}
+
+fn watch_by_polling(
+    storage_directory: &Path,
+    notify: &mut dyn FnMut() -> bool,
+    interval: Duration,
+) {
+    let mut previous = storage_signature(storage_directory);
+    loop {
+        thread::sleep(interval);
+        let current = storage_signature(storage_directory);
+        if current != previous {
+            previous = current;
+            if !notify() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use super::{is_watched_file_name, CHANGE_DEBOUNCE};
+    use std::ffi::CString;
+    use std::fs::File;
+    use std::io::Read;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::thread;
+
+    const EVENT_HEADER_LENGTH: usize = std::mem::size_of::<libc::inotify_event>();
+
+    // 初期化に失敗した場合と、監視中にreadが失敗した場合はErrを返し、呼び出し側でpollingへ切り替える
+    pub(super) fn watch(
+        storage_directory: &Path,
+        notify: &mut dyn FnMut() -> bool,
+    ) -> std::io::Result<()> {
+        let path = CString::new(storage_directory.as_os_str().as_bytes())?;
+        // SAFETY: inotify_init1は引数にpointerを取らず、成功時は新しいfdを返す
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        // SAFETY: fdは直前に作成し、他に所有者がいない
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        // SAFETY: pathはNUL終端済みで、呼び出しの間有効
+        let watch_descriptor = unsafe {
+            libc::inotify_add_watch(
+                fd.as_raw_fd(),
+                path.as_ptr(),
+                libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO | libc::IN_DELETE | libc::IN_MODIFY,
+            )
+        };
+        if watch_descriptor < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let mut file = File::from(fd);
+        let mut buffer = vec![0; 64 * 1024];
+        loop {
+            let length = file.read(&mut buffer)?;
+            if !touches_watched_file(&buffer[..length]) {
+                continue;
+            }
+            thread::sleep(CHANGE_DEBOUNCE);
+            drain_pending_events(&mut file, &mut buffer)?;
+            if !notify() {
+                return Ok(());
+            }
+        }
+    }
+
+    // まとめて待った間に届いた通知は、次の変更として数えずに読み捨てる
+    fn drain_pending_events(file: &mut File, buffer: &mut [u8]) -> std::io::Result<()> {
+        loop {
+            let mut poll_fd = libc::pollfd {
+                fd: file.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: poll_fdは呼び出しの間有効な1要素の配列として渡す
+            let ready = unsafe { libc::poll(&mut poll_fd, 1, 0) };
+            if ready < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if ready == 0 {
+                return Ok(());
+            }
+            if file.read(buffer)? == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    pub(super) fn touches_watched_file(events: &[u8]) -> bool {
+        let mut offset = 0;
+        while offset + EVENT_HEADER_LENGTH <= events.len() {
+            let name_length = u32::from_ne_bytes(
+                events[offset + EVENT_HEADER_LENGTH - 4..offset + EVENT_HEADER_LENGTH]
+                    .try_into()
+                    .expect("inotify event length field is 4 bytes"),
+            ) as usize;
+            let name_start = offset + EVENT_HEADER_LENGTH;
+            let name_end = (name_start + name_length).min(events.len());
+            let name = &events[name_start..name_end];
+            let name = &name[..name
+                .iter()
+                .position(|byte| *byte == 0)
+                .unwrap_or(name.len())];
+            if is_watched_file_name(name) {
+                return true;
+            }
+            offset = name_start + name_length;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use uuid::Uuid;
+
+    struct TestStorageDir {
+        path: PathBuf,
+    }
+
+    impl TestStorageDir {
+        fn new() -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("schronu-storage-watcher-test-{}", Uuid::new_v4()));
+            fs::create_dir_all(&path).unwrap();
+            Self { path }
+        }
+    }
+
+    impl Drop for TestStorageDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[test]
+    fn storage_signatureはrevisionとdatabaseだけを見てlockの更新を無視する() {
+        let storage_dir = TestStorageDir::new();
+        let before = storage_signature(&storage_dir.path);
+
+        fs::write(storage_dir.path.join(".lock"), "pid: 1\n").unwrap();
+        assert_eq!(storage_signature(&storage_dir.path), before);
+
+        fs::write(storage_dir.path.join(".revision"), "revision\n").unwrap();
+        assert_ne!(storage_signature(&storage_dir.path), before);
+    }
+
+    #[test]
+    fn pollingはrevisionの更新を1回通知しnotifyがfalseなら終了する() {
+        let storage_dir = TestStorageDir::new();
+        let path = storage_dir.path.clone();
+        let (sender, receiver) = mpsc::channel();
+        let watcher = thread::spawn(move || {
+            watch_by_polling(
+                &path,
+                &mut || {
+                    sender.send(()).unwrap();
+                    false
+                },
+                Duration::from_millis(10),
+            );
+        });
+
+        thread::sleep(Duration::from_millis(30));
+        fs::write(storage_dir.path.join(".revision"), "revision\n").unwrap();
+
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        watcher.join().unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn inotifyの通知からrevisionとdatabaseの変更だけを拾う() {
+        fn event(name: &str) -> Vec<u8> {
+            let mut padded_name = name.as_bytes().to_vec();
+            padded_name.resize(16, 0);
+            let mut bytes = Vec::new();
+            bytes.extend_from_slice(&1i32.to_ne_bytes());
+            bytes.extend_from_slice(&libc::IN_MOVED_TO.to_ne_bytes());
+            bytes.extend_from_slice(&0u32.to_ne_bytes());
+            bytes.extend_from_slice(&(padded_name.len() as u32).to_ne_bytes());
+            bytes.extend_from_slice(&padded_name);
+            bytes
+        }
+
+        assert!(!inotify::touches_watched_file(&event(".lock")));
+        assert!(!inotify::touches_watched_file(&event(".revision.tmp")));
+        assert!(inotify::touches_watched_file(
+            &[event(".lock"), event(".revision")].concat()
+        ));
+        assert!(inotify::touches_watched_file(&event(
+            SQLITE_DATABASE_FILE_NAME
+        )));
+    }
+
+    #[test]
+    fn spawn_storage_watcherは保存先の更新を通知する() {
+        let storage_dir = TestStorageDir::new();
+        let (sender, receiver) = mpsc::channel();
+        spawn_storage_watcher(storage_dir.path.clone(), move || sender.send(()).is_ok());
+
+        thread::sleep(Duration::from_millis(100));
+        fs::write(storage_dir.path.join(".revision"), "revision\n").unwrap();
+
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+}
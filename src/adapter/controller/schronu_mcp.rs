@@ -1,11 +1,19 @@
-use schronu::adapter::gateway::schronu_config::load_schronu_config;
+#[cfg(unix)]
+use schronu::adapter::daemon::{DaemonClient, DaemonRequest, DaemonResponse};
+use schronu::adapter::gateway::schronu_config::{load_schronu_config, StorageBackend};
+use schronu::adapter::gateway::sqlite_task_repository::SqliteTaskRepository;
+use schronu::adapter::gateway::storage_encryption::{
+    ensure_unencrypted_storage, open_storage_cipher, StorageKeySource,
+};
 use schronu::adapter::gateway::task_repository::TaskRepository;
 use schronu::adapter::mcp::McpServer;
 use schronu::application::interface::TaskRepositoryTrait;
-use serde_json::json;
+use schronu::entity::datetime::BusinessDateTimePolicy;
+use serde_json::{json, Value};
 use std::error::Error;
 use std::io::{self, BufRead, Write};
 use std::process;
+use std::time::Duration;
 
 mod storage_directory;
 use storage_directory::resolve_project_storage_directory;
@@ -18,18 +26,55 @@ fn main() {
 }
 
 fn run() -> Result<(), Box<dyn Error>> {
-    load_schronu_config(std::env::var_os("SCHRONU_CONFIG_PATH"))?;
+    let config = load_schronu_config(std::env::var_os("SCHRONU_CONFIG_PATH"))?;
     let storage_directory =
         resolve_project_storage_directory(std::env::var_os("SCHRONU_STORAGE_DIR"))?;
     let storage_directory_text = storage_directory
         .to_str()
         .ok_or("storage directory path must be valid UTF-8")?;
-    let repository = TaskRepository::new(storage_directory_text);
-    serve_stdio(
-        McpServer::with_storage_directory(repository, storage_directory),
-        io::stdin().lock(),
-        io::stdout().lock(),
-    )
+    // schronudが動いていれば、repositoryを読まずにそちらへ中継する
+    #[cfg(unix)]
+    if let Some(client) = DaemonClient::connect(&storage_directory)? {
+        return relay_stdio(client, io::stdin().lock(), io::stdout().lock());
+    }
+    let datetime_policy = BusinessDateTimePolicy::new(config.end_of_day_offset_minutes)
+        .with_non_business_weekdays(&config.non_business_weekdays)
+        .with_holiday_weekday(config.holiday_weekday);
+    match config.storage_backend {
+        StorageBackend::Yaml => {
+            let cipher = open_storage_cipher(
+                &storage_directory,
+                &StorageKeySource::from_env(config.storage_encryption_key_file_path.clone()),
+            )?;
+            let repository = TaskRepository::new(storage_directory_text)
+                .with_history_retention_revisions(config.storage_history_retention_revisions)
+                .with_storage_cipher(cipher.clone());
+            serve_stdio(
+                McpServer::with_storage_directory(repository, storage_directory)
+                    .with_storage_cipher(cipher)
+                    .with_datetime_policy(datetime_policy, config.default_deadline_time)
+                    .with_busy_time_slots_path(&config.busy_time_slots_yaml_path)
+                    .with_lock_wait(Duration::from_secs(config.mcp_lock_wait_seconds)),
+                io::stdin().lock(),
+                io::stdout().lock(),
+            )
+        }
+        StorageBackend::Sqlite => {
+            ensure_unencrypted_storage(
+                &storage_directory,
+                &StorageKeySource::from_env(config.storage_encryption_key_file_path.clone()),
+            )?;
+            let repository = SqliteTaskRepository::open(storage_directory_text)?;
+            serve_stdio(
+                McpServer::with_storage_directory(repository, storage_directory)
+                    .with_datetime_policy(datetime_policy, config.default_deadline_time)
+                    .with_busy_time_slots_path(&config.busy_time_slots_yaml_path)
+                    .with_lock_wait(Duration::from_secs(config.mcp_lock_wait_seconds)),
+                io::stdin().lock(),
+                io::stdout().lock(),
+            )
+        }
+    }
 }
 
 fn serve_stdio<R: TaskRepositoryTrait>(
@@ -41,20 +86,54 @@ fn serve_stdio<R: TaskRepositoryTrait>(
         let line = line?;
         let response = match serde_json::from_str(&line) {
             Ok(request) => server.handle_request(request),
-            Err(_) => Some(json!({
-                "jsonrpc": "2.0",
-                "id": null,
-                "error": {
-                    "code": -32700,
-                    "message": "Parse error"
+            Err(_) => Some(parse_error_response()),
+        };
+        if let Some(response) = response {
+            write_response(&mut output, &response)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn relay_stdio(
+    mut client: DaemonClient,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), Box<dyn Error>> {
+    for line in input.lines() {
+        let line = line?;
+        let response = match serde_json::from_str(&line) {
+            Ok(message) => match client.request(&DaemonRequest::Mcp { message })? {
+                DaemonResponse::Mcp { message } => message,
+                DaemonResponse::Error { message } => return Err(message.into()),
+                response => {
+                    return Err(format!("unexpected schronud response: {response:?}").into())
                 }
-            })),
+            },
+            Err(_) => Some(parse_error_response()),
         };
         if let Some(response) = response {
-            serde_json::to_writer(&mut output, &response)?;
-            writeln!(output)?;
-            output.flush()?;
+            write_response(&mut output, &response)?;
         }
     }
     Ok(())
 }
+
+fn parse_error_response() -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": {
+            "code": -32700,
+            "message": "Parse error"
+        }
+    })
+}
+
+fn write_response(output: &mut impl Write, response: &Value) -> Result<(), Box<dyn Error>> {
+    serde_json::to_writer(&mut *output, response)?;
+    writeln!(output)?;
+    output.flush()?;
+    Ok(())
+}
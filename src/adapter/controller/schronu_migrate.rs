@@ -0,0 +1,67 @@
+use chrono::Local;
+use schronu::adapter::gateway::schronu_config::load_schronu_config;
+use schronu::adapter::gateway::sqlite_task_repository::SQLITE_DATABASE_FILE_NAME;
+use schronu::adapter::gateway::storage_encryption::StorageKeySource;
+use schronu::adapter::gateway::storage_lock::{LockMode, StorageLock};
+use schronu::adapter::gateway::storage_migration::{
+    decrypt_yaml_storage, encrypt_yaml_storage, migrate_sqlite_to_yaml, migrate_yaml_to_sqlite,
+    verify_storage_backends,
+};
+use std::error::Error;
+use std::process;
+
+mod storage_directory;
+use storage_directory::resolve_project_storage_directory;
+
+const USAGE: &str = "usage: schronu-migrate <to-sqlite|to-yaml|verify|to-encrypted|to-plain>";
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("[Error] {error}");
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let [direction] = args.as_slice() else {
+        return Err(USAGE.into());
+    };
+    let config = load_schronu_config(std::env::var_os("SCHRONU_CONFIG_PATH"))?;
+    let storage_directory =
+        resolve_project_storage_directory(std::env::var_os("SCHRONU_STORAGE_DIR"))?;
+    let storage_directory_text = storage_directory
+        .to_str()
+        .ok_or("storage directory path must be valid UTF-8")?;
+    let database_path = storage_directory.join(SQLITE_DATABASE_FILE_NAME);
+
+    // 移行中に他processが片方の保存先だけを更新しないよう、検証まで排他lockを保持する
+    let _lock = StorageLock::acquire(&storage_directory, LockMode::Cli)?;
+    let now = Local::now();
+    let report = match direction.as_str() {
+        "to-sqlite" => migrate_yaml_to_sqlite(storage_directory_text, &database_path, now)?,
+        "to-yaml" => migrate_sqlite_to_yaml(
+            storage_directory_text,
+            &database_path,
+            config.storage_history_retention_revisions,
+            now,
+        )?,
+        "verify" => verify_storage_backends(storage_directory_text, &database_path, now)?,
+        "to-encrypted" => encrypt_yaml_storage(
+            storage_directory_text,
+            &StorageKeySource::from_env(config.storage_encryption_key_file_path),
+            now,
+        )?,
+        "to-plain" => decrypt_yaml_storage(
+            storage_directory_text,
+            &StorageKeySource::from_env(config.storage_encryption_key_file_path),
+            now,
+        )?,
+        _ => return Err(USAGE.into()),
+    };
+    println!(
+        "{direction}: OK ({} projects, {} tasks)",
+        report.projects, report.tasks
+    );
+    Ok(())
+}
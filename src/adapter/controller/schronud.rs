@@ -0,0 +1,26 @@
+// 常駐process。非対話commandをschronuと同じ処理で実行するため、schronuのruntime moduleを読み込む
+
+#[path = "schronu/command.rs"]
+mod command;
+
+#[path = "schronu/gantt.rs"]
+mod gantt;
+
+#[path = "schronu/handler.rs"]
+mod handler;
+
+#[path = "schronu/interactive.rs"]
+mod interactive;
+
+#[path = "schronu/renderer.rs"]
+mod renderer;
+
+#[path = "schronu/runtime.rs"]
+mod runtime;
+
+#[path = "schronu/storage_watcher.rs"]
+mod storage_watcher;
+
+fn main() {
+    runtime::daemon_application();
+}
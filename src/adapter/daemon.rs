@@ -0,0 +1,270 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+pub const DAEMON_SOCKET_FILE_NAME: &str = ".schronud.sock";
+
+pub fn daemon_socket_path(storage_directory: &Path) -> PathBuf {
+    storage_directory.join(DAEMON_SOCKET_FILE_NAME)
+}
+
+// 1行に1つのJSONを置いてやり取りする。mcpのmessageはJSON-RPCのまま中継する
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DaemonRequest {
+    Ping,
+    Cli {
+        command: String,
+        supports_ansi_color: bool,
+    },
+    Mcp {
+        message: Value,
+    },
+    Shutdown,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DaemonResponse {
+    Pong {
+        pid: u32,
+    },
+    Cli {
+        stdout: String,
+        stderr: String,
+        success: bool,
+    },
+    Mcp {
+        message: Option<Value>,
+    },
+    ShuttingDown,
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug)]
+pub enum DaemonEvent {
+    Request {
+        connection_id: u64,
+        request: DaemonRequest,
+        reply: Sender<DaemonResponse>,
+    },
+    Disconnected {
+        connection_id: u64,
+    },
+}
+
+pub struct DaemonClient {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl DaemonClient {
+    // 常駐processがいなければNoneを返す。落ちたprocessが残したsocketも接続できないので同じ扱いにする
+    pub fn connect(storage_directory: &Path) -> io::Result<Option<Self>> {
+        match UnixStream::connect(daemon_socket_path(storage_directory)) {
+            Ok(stream) => Ok(Some(Self {
+                reader: BufReader::new(stream.try_clone()?),
+                writer: stream,
+            })),
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    ErrorKind::NotFound | ErrorKind::ConnectionRefused
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn request(&mut self, request: &DaemonRequest) -> io::Result<DaemonResponse> {
+        write_message(&mut self.writer, request)?;
+        read_message(&mut self.reader)?.ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "schronud closed the connection before responding",
+            )
+        })
+    }
+}
+
+// 応答する常駐processがいれば使用中として失敗し、応答のない古いsocketは消して作り直す
+pub fn bind_daemon_socket(storage_directory: &Path) -> io::Result<UnixListener> {
+    let path = daemon_socket_path(storage_directory);
+    if UnixStream::connect(&path).is_ok() {
+        return Err(io::Error::new(
+            ErrorKind::AddrInUse,
+            format!("schronud is already running at {}", path.display()),
+        ));
+    }
+    remove_daemon_socket(storage_directory)?;
+    let listener = UnixListener::bind(&path)?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+pub fn remove_daemon_socket(storage_directory: &Path) -> io::Result<()> {
+    match fs::remove_file(daemon_socket_path(storage_directory)) {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+// 接続ごとのthreadは読み書きだけを受け持ち、要求はすべて1本のchannelに流す。
+// 受け取る側が1つずつ処理するので、更新は届いた順に直列で適用される
+pub fn spawn_daemon_listener(listener: UnixListener) -> Receiver<DaemonEvent> {
+    let (events, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for (connection_id, stream) in (0..).zip(listener.incoming()) {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let events = events.clone();
+            thread::spawn(move || {
+                let _ = relay_requests(connection_id, stream, &events);
+                let _ = events.send(DaemonEvent::Disconnected { connection_id });
+            });
+        }
+    });
+    receiver
+}
+
+fn relay_requests(
+    connection_id: u64,
+    stream: UnixStream,
+    events: &Sender<DaemonEvent>,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => {
+                let (reply, response) = mpsc::channel();
+                let event = DaemonEvent::Request {
+                    connection_id,
+                    request,
+                    reply,
+                };
+                if events.send(event).is_err() {
+                    return Ok(());
+                }
+                match response.recv() {
+                    Ok(response) => response,
+                    Err(_) => return Ok(()),
+                }
+            }
+            Err(error) => DaemonResponse::Error {
+                message: format!("invalid daemon request: {error}"),
+            },
+        };
+        write_message(&mut writer, &response)?;
+    }
+}
+
+fn write_message(writer: &mut impl Write, message: &impl Serialize) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, message)?;
+    writeln!(writer)?;
+    writer.flush()
+}
+
+fn read_message<T: DeserializeOwned>(reader: &mut impl BufRead) -> io::Result<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestStorageDir;
+    use serde_json::json;
+
+    #[test]
+    fn 常駐processがいなければ接続せずに何も返さない() {
+        let directory = TestStorageDir::new("daemon");
+
+        assert!(DaemonClient::connect(&directory.path).unwrap().is_none());
+
+        // 落ちたprocessが残したsocketは応答しない
+        drop(UnixListener::bind(daemon_socket_path(&directory.path)).unwrap());
+        assert!(DaemonClient::connect(&directory.path).unwrap().is_none());
+    }
+
+    #[test]
+    fn 複数の接続からの要求を1本のchannelで順に受け取る() {
+        let directory = TestStorageDir::new("daemon");
+        let events = spawn_daemon_listener(bind_daemon_socket(&directory.path).unwrap());
+        let server = thread::spawn(move || {
+            let mut handled = Vec::new();
+            while handled.len() < 2 {
+                if let DaemonEvent::Request {
+                    connection_id,
+                    request,
+                    reply,
+                } = events.recv().unwrap()
+                {
+                    let response = match request {
+                        DaemonRequest::Mcp { message } => DaemonResponse::Mcp {
+                            message: Some(message),
+                        },
+                        _ => DaemonResponse::Pong { pid: 1 },
+                    };
+                    reply.send(response).unwrap();
+                    handled.push(connection_id);
+                }
+            }
+            handled
+        });
+
+        let mut first = DaemonClient::connect(&directory.path).unwrap().unwrap();
+        let mut second = DaemonClient::connect(&directory.path).unwrap().unwrap();
+        let echoed = first
+            .request(&DaemonRequest::Mcp {
+                message: json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}),
+            })
+            .unwrap();
+        let pong = second.request(&DaemonRequest::Ping).unwrap();
+
+        assert_eq!(
+            echoed,
+            DaemonResponse::Mcp {
+                message: Some(json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}))
+            }
+        );
+        assert_eq!(pong, DaemonResponse::Pong { pid: 1 });
+        let handled = server.join().unwrap();
+        assert_ne!(handled[0], handled[1]);
+    }
+
+    #[test]
+    fn 応答する常駐processがいればsocketを作り直さない() {
+        let directory = TestStorageDir::new("daemon");
+        let _listener = bind_daemon_socket(&directory.path).unwrap();
+
+        let error = bind_daemon_socket(&directory.path).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::AddrInUse);
+    }
+}
@@ -1,5 +1,17 @@
+pub mod busy_block;
 pub mod free_time_manager;
+pub mod load_history;
+pub mod project_note;
 pub mod schronu_config;
+pub mod sqlite_task_repository;
+pub mod storage_consistency;
+pub mod storage_encryption;
+pub mod storage_history;
 pub mod storage_lock;
+pub mod storage_migration;
+pub mod task_export;
+pub mod task_import;
 pub mod task_repository;
+pub mod trash;
+pub mod work_session;
 pub mod yaml;
@@ -0,0 +1,370 @@
+use crate::adapter::gateway::free_time_manager::{invalid, yaml_error_value};
+use crate::adapter::gateway::storage_encryption::{
+    decode_storage_file, encode_storage_file, StorageCipher,
+};
+use crate::adapter::gateway::task_repository::write_file_atomically;
+use crate::application::interface::{
+    BusyBlockStoreError, BusyBlockStoreTrait, BusyTimeSlotLoadError,
+};
+use crate::entity::busy_block::{BusyBlock, BusyBlockSpan, EMPTY_NAME_REASON};
+use crate::entity::datetime::parse_local_datetime;
+use chrono::{DateTime, Local, LocalResult, NaiveDate};
+use linked_hash_map::LinkedHashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+
+pub const BUSY_BLOCKS_FILE_NAME: &str = ".busy_blocks.yaml";
+
+const DATETIME_FORMAT: &str = "%Y/%m/%d %H:%M";
+const DATE_FORMAT: &str = "%Y/%m/%d";
+
+// 日付を決めた埋まっている時間を、保存先直下の1つのYAMLに持つ。
+// 手で書き足せるように、週ごとの予定表と同じくfield単位で誤りを報告する
+pub struct BusyBlockFile {
+    path: PathBuf,
+    // 予定の名前も他人に見せたくない内容なので、暗号化した保存先ではproject.yamlと同じ鍵の暗号文で持つ
+    cipher: Option<StorageCipher>,
+}
+
+impl BusyBlockFile {
+    pub fn in_storage_directory(storage_directory: impl AsRef<Path>) -> Self {
+        Self {
+            path: storage_directory.as_ref().join(BUSY_BLOCKS_FILE_NAME),
+            cipher: None,
+        }
+    }
+
+    pub fn with_cipher(mut self, cipher: Option<StorageCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl BusyBlockStoreTrait for BusyBlockFile {
+    fn list_busy_blocks(&self) -> Result<Vec<BusyBlock>, BusyTimeSlotLoadError> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(BusyTimeSlotLoadError::new(&self.path, "$", None, error)),
+        };
+        let text = decode_storage_file(self.cipher.as_ref(), &bytes)
+            .map_err(|error| BusyTimeSlotLoadError::new(&self.path, "$", None, error))
+            .and_then(|bytes| {
+                String::from_utf8(bytes)
+                    .map_err(|error| BusyTimeSlotLoadError::new(&self.path, "$", None, error))
+            })?;
+        busy_blocks_from_str(&self.path, &text)
+    }
+
+    fn save_busy_blocks(&mut self, blocks: &[BusyBlock]) -> Result<(), BusyBlockStoreError> {
+        let mut document = LinkedHashMap::new();
+        document.insert(
+            Yaml::String("busy_blocks".to_string()),
+            Yaml::Array(blocks.iter().map(busy_block_to_yaml).collect()),
+        );
+        let mut contents = String::new();
+        YamlEmitter::new(&mut contents)
+            .dump(&Yaml::Hash(document))
+            .map_err(|error| {
+                BusyBlockStoreError::new(
+                    &self.path,
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+                )
+            })?;
+        contents.push('\n');
+        let bytes = encode_storage_file(self.cipher.as_ref(), contents.into_bytes());
+        write_file_atomically(&self.path, &bytes)
+            .map(|_| ())
+            .map_err(|error| BusyBlockStoreError::new(&self.path, error))
+    }
+}
+
+fn busy_block_to_yaml(block: &BusyBlock) -> Yaml {
+    let mut hash = LinkedHashMap::new();
+    let mut insert = |key: &str, value: String| {
+        hash.insert(Yaml::String(key.to_string()), Yaml::String(value));
+    };
+    insert("id", block.busy_block_id.to_string());
+    insert("name", block.name.clone());
+    match block.span {
+        BusyBlockSpan::Timed { start, end } => {
+            insert("start", start.format(DATETIME_FORMAT).to_string());
+            insert("end", end.format(DATETIME_FORMAT).to_string());
+        }
+        BusyBlockSpan::AllDay {
+            start_date,
+            end_date,
+        } => {
+            insert("start_date", start_date.format(DATE_FORMAT).to_string());
+            insert("end_date", end_date.format(DATE_FORMAT).to_string());
+        }
+    }
+    Yaml::Hash(hash)
+}
+
+fn busy_blocks_from_str(path: &Path, text: &str) -> Result<Vec<BusyBlock>, BusyTimeSlotLoadError> {
+    let docs = YamlLoader::load_from_str(text)
+        .map_err(|error| BusyTimeSlotLoadError::new(path, "$", None, error))?;
+    let Some(document) = docs.first() else {
+        return Ok(vec![]);
+    };
+    let blocks_yaml = &document["busy_blocks"];
+    let blocks = blocks_yaml.as_vec().ok_or_else(|| {
+        invalid(
+            path,
+            "busy_blocks",
+            yaml_error_value(blocks_yaml),
+            "must be an array",
+        )
+    })?;
+
+    let mut busy_blocks = Vec::with_capacity(blocks.len());
+    for (index, block) in blocks.iter().enumerate() {
+        let block_path = format!("busy_blocks[{index}]");
+        if block.as_hash().is_none() {
+            return Err(invalid(
+                path,
+                &block_path,
+                yaml_error_value(block),
+                "must be a mapping",
+            ));
+        }
+        let field = |key: &str| -> Result<Option<&str>, BusyTimeSlotLoadError> {
+            match &block[key] {
+                Yaml::BadValue => Ok(None),
+                value => value.as_str().map(Some).ok_or_else(|| {
+                    invalid(
+                        path,
+                        &format!("{block_path}.{key}"),
+                        yaml_error_value(value),
+                        "must be a string",
+                    )
+                }),
+            }
+        };
+        let required = |key: &str| {
+            field(key)?
+                .ok_or_else(|| invalid(path, &format!("{block_path}.{key}"), None, "is required"))
+        };
+
+        let id = required("id")?;
+        let busy_block_id = Uuid::parse_str(id).map_err(|error| {
+            BusyTimeSlotLoadError::new(path, format!("{block_path}.id"), Some(id.into()), error)
+        })?;
+        if busy_blocks
+            .iter()
+            .any(|existing: &BusyBlock| existing.busy_block_id == busy_block_id)
+        {
+            return Err(invalid(
+                path,
+                &format!("{block_path}.id"),
+                Some(id.into()),
+                "duplicate id",
+            ));
+        }
+        let name = required("name")?;
+        if name.trim().is_empty() {
+            return Err(invalid(
+                path,
+                &format!("{block_path}.name"),
+                Some(name.into()),
+                EMPTY_NAME_REASON,
+            ));
+        }
+
+        let span = match (field("start")?, field("start_date")?) {
+            (Some(start), None) => BusyBlockSpan::Timed {
+                start: parse_datetime(path, &format!("{block_path}.start"), start)?,
+                end: parse_datetime(path, &format!("{block_path}.end"), required("end")?)?,
+            },
+            (None, Some(start_date)) => {
+                let start_date = parse_date(path, &format!("{block_path}.start_date"), start_date)?;
+                let end_date = match field("end_date")? {
+                    Some(end_date) => {
+                        parse_date(path, &format!("{block_path}.end_date"), end_date)?
+                    }
+                    None => start_date,
+                };
+                BusyBlockSpan::AllDay {
+                    start_date,
+                    end_date,
+                }
+            }
+            _ => {
+                return Err(invalid(
+                    path,
+                    &block_path,
+                    None,
+                    "must have either start and end or start_date",
+                ))
+            }
+        };
+        if let Err((key, reason)) = span.validate() {
+            let value = field(key)?.map(str::to_string);
+            return Err(invalid(path, &format!("{block_path}.{key}"), value, reason));
+        }
+
+        busy_blocks.push(BusyBlock {
+            busy_block_id,
+            name: name.to_string(),
+            span,
+        });
+    }
+    Ok(busy_blocks)
+}
+
+fn parse_datetime(
+    path: &Path,
+    field_path: &str,
+    value: &str,
+) -> Result<DateTime<Local>, BusyTimeSlotLoadError> {
+    match parse_local_datetime(value, DATETIME_FORMAT) {
+        Ok(LocalResult::Single(datetime)) => Ok(datetime),
+        Ok(_) => Err(invalid(
+            path,
+            field_path,
+            Some(value.into()),
+            "must be a single local date-time",
+        )),
+        Err(error) => Err(BusyTimeSlotLoadError::new(
+            path,
+            field_path,
+            Some(value.into()),
+            error,
+        )),
+    }
+}
+
+fn parse_date(
+    path: &Path,
+    field_path: &str,
+    value: &str,
+) -> Result<NaiveDate, BusyTimeSlotLoadError> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT)
+        .map_err(|error| BusyTimeSlotLoadError::new(path, field_path, Some(value.into()), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestStorageDir;
+    use chrono::TimeZone;
+
+    #[test]
+    fn 時刻指定と終日の埋まっている時間を書き出して読み直せる() {
+        let storage_dir = TestStorageDir::new("busy-block");
+        let mut file = BusyBlockFile::in_storage_directory(&storage_dir.path);
+        let blocks = vec![
+            BusyBlock {
+                busy_block_id: Uuid::new_v4(),
+                name: "歯医者: 定期".to_string(),
+                span: BusyBlockSpan::Timed {
+                    start: Local.with_ymd_and_hms(2026, 10, 22, 14, 0, 0).unwrap(),
+                    end: Local.with_ymd_and_hms(2026, 10, 22, 15, 30, 0).unwrap(),
+                },
+            },
+            BusyBlock {
+                busy_block_id: Uuid::new_v4(),
+                name: "夏休み".to_string(),
+                span: BusyBlockSpan::AllDay {
+                    start_date: NaiveDate::from_ymd_opt(2026, 8, 10).unwrap(),
+                    end_date: NaiveDate::from_ymd_opt(2026, 8, 14).unwrap(),
+                },
+            },
+        ];
+
+        assert_eq!(file.list_busy_blocks().unwrap(), vec![]);
+        file.save_busy_blocks(&blocks).unwrap();
+
+        assert_eq!(file.list_busy_blocks().unwrap(), blocks);
+    }
+
+    #[test]
+    fn 暗号化した保存先では埋まっている時間を暗号文で書き平文は読まない() {
+        use crate::adapter::gateway::storage_encryption::{
+            initialize_storage_encryption, is_encrypted, StorageKeySource,
+        };
+
+        let storage_dir = TestStorageDir::new("busy-block");
+        let key_file_path = storage_dir.path.join("schronu.key");
+        fs::write(&key_file_path, [9; 32]).unwrap();
+        let cipher = initialize_storage_encryption(
+            &storage_dir.path,
+            &StorageKeySource::new(Some(key_file_path), None),
+        )
+        .unwrap();
+        let mut file =
+            BusyBlockFile::in_storage_directory(&storage_dir.path).with_cipher(Some(cipher));
+        let blocks = vec![BusyBlock {
+            busy_block_id: Uuid::new_v4(),
+            name: "通院".to_string(),
+            span: BusyBlockSpan::AllDay {
+                start_date: NaiveDate::from_ymd_opt(2026, 10, 22).unwrap(),
+                end_date: NaiveDate::from_ymd_opt(2026, 10, 22).unwrap(),
+            },
+        }];
+
+        file.save_busy_blocks(&blocks).unwrap();
+
+        let bytes = fs::read(file.path()).unwrap();
+        assert!(is_encrypted(&bytes));
+        assert!(!String::from_utf8_lossy(&bytes).contains("通院"));
+        assert_eq!(file.list_busy_blocks().unwrap(), blocks);
+        assert!(BusyBlockFile::in_storage_directory(&storage_dir.path)
+            .list_busy_blocks()
+            .is_err());
+
+        fs::write(file.path(), "busy_blocks: []\n").unwrap();
+        assert!(file.list_busy_blocks().is_err());
+    }
+
+    #[test]
+    fn 不正なfieldはpathとfield_pathと値を含むエラーになる() {
+        let storage_dir = TestStorageDir::new("busy-block");
+        let file = BusyBlockFile::in_storage_directory(&storage_dir.path);
+        let id = Uuid::new_v4();
+        fs::write(
+            file.path(),
+            format!(
+                "busy_blocks:\n  - id: {id}\n    name: 休暇\n    start_date: 2026/08/14\n    end_date: 2026/08/10\n"
+            ),
+        )
+        .unwrap();
+
+        let error = file.list_busy_blocks().unwrap_err();
+
+        assert_eq!(error.path(), file.path());
+        assert_eq!(error.field_path(), "busy_blocks[0].end_date");
+        assert_eq!(error.value(), Some("2026/08/10"));
+        assert!(error
+            .to_string()
+            .contains("busy_blocks[0].end_date (value: 2026/08/10)"));
+    }
+
+    #[test]
+    fn 開始のない記録はfield_pathを含むエラーになる() {
+        let storage_dir = TestStorageDir::new("busy-block");
+        let file = BusyBlockFile::in_storage_directory(&storage_dir.path);
+        fs::write(
+            file.path(),
+            format!(
+                "busy_blocks:\n  - id: {}\n    name: 歯医者\n",
+                Uuid::new_v4()
+            ),
+        )
+        .unwrap();
+
+        let error = file.list_busy_blocks().unwrap_err();
+
+        assert_eq!(error.field_path(), "busy_blocks[0]");
+        assert!(error
+            .to_string()
+            .contains("must have either start and end or start_date"));
+    }
+}
@@ -2,7 +2,8 @@ use crate::application::interface::{
     BusyTimeSlotLoadError, BusyTimeSlotRegistrationError, FreeTimeManagerTrait,
 };
 use crate::entity::busy_time_slot::BusyTimeSlot;
-use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, Weekday};
+use crate::entity::datetime::holiday::weekday_profile;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Timelike, Weekday};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::prelude::*;
@@ -23,6 +24,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 pub struct FreeTimeManager {
     weekly_busy_time_slots: HashMap<Weekday, Vec<BusyTimeSlot>>,
     registered_busy_time_slots_map: HashMap<NaiveDate, Vec<i64>>,
+    holiday_weekday: Option<Weekday>,
 }
 
 impl Default for FreeTimeManager {
@@ -36,9 +38,16 @@ impl FreeTimeManager {
         Self {
             weekly_busy_time_slots: HashMap::new(),
             registered_busy_time_slots_map: HashMap::new(),
+            holiday_weekday: None,
         }
     }
 
+    // 祝日には、その曜日の毎週定期slotを当てる
+    pub fn with_holiday_weekday(mut self, holiday_weekday: Option<Weekday>) -> Self {
+        self.holiday_weekday = holiday_weekday;
+        self
+    }
+
     fn load_busy_time_slots_from_file(
         &mut self,
         busy_time_slots_file_path: &str,
@@ -228,7 +237,10 @@ impl FreeTimeManager {
     fn get_free_time_slot(&self, date: NaiveDate) -> Vec<i64> {
         let mut free_time_slot = vec![1; 24 * 60];
 
-        if let Some(busy_time_slots) = self.weekly_busy_time_slots.get(&date.weekday()) {
+        if let Some(busy_time_slots) = self
+            .weekly_busy_time_slots
+            .get(&weekday_profile(date, self.holiday_weekday))
+        {
             for busy_time_slot in busy_time_slots {
                 mark_busy_time_slot(&mut free_time_slot, busy_time_slot);
             }
@@ -246,7 +258,7 @@ impl FreeTimeManager {
     }
 }
 
-fn invalid(
+pub(super) fn invalid(
     path: &Path,
     field_path: &str,
     value: Option<String>,
@@ -260,7 +272,7 @@ fn invalid(
     )
 }
 
-fn yaml_error_value(value: &Yaml) -> Option<String> {
+pub(super) fn yaml_error_value(value: &Yaml) -> Option<String> {
     (!matches!(value, Yaml::BadValue)).then(|| format!("{value:?}"))
 }
 
@@ -322,25 +334,28 @@ impl FreeTimeManagerTrait for FreeTimeManager {
         (*end - *start).num_minutes() - free_minutes
     }
 
-    // [start, end)
+    // [start, end)。endは同じ日か、翌日の0時ちょうどまで
     // TODO: エラー処理
     fn register_busy_time_slot(
         &mut self,
         start: &DateTime<Local>,
         end: &DateTime<Local>,
     ) -> Result<(), BusyTimeSlotRegistrationError> {
-        if start.date_naive() != end.date_naive() {
+        let date = start.date_naive();
+        let end_index = if end.date_naive() == date {
+            end.hour() * 60 + end.minute()
+        } else if date.succ_opt() == Some(end.date_naive()) && end.time() == NaiveTime::MIN {
+            24 * 60
+        } else {
             return Err(BusyTimeSlotRegistrationError);
-        }
+        };
 
-        let date = start.date_naive();
         let free_time_slot = self
             .registered_busy_time_slots_map
             .entry(date)
             .or_insert(vec![1; 24 * 60]);
 
         let start_index = start.hour() * 60 + start.minute();
-        let end_index = end.hour() * 60 + end.minute();
 
         for ind in start_index..end_index {
             free_time_slot[ind as usize] = 0;
@@ -354,6 +369,10 @@ impl FreeTimeManagerTrait for FreeTimeManager {
     ) -> Result<(), BusyTimeSlotLoadError> {
         FreeTimeManager::load_busy_time_slots_from_file(self, busy_time_slots_file_path)
     }
+
+    fn clear_registered_busy_time_slots(&mut self) {
+        self.registered_busy_time_slots_map.clear();
+    }
 }
 
 #[test]
@@ -437,6 +456,37 @@ fn write_busy_time_slots_yaml() -> PathBuf {
     path
 }
 
+#[test]
+fn get_free_minutes_祝日の曜日を設定すると祝日にその曜日の定期slotを当てる() {
+    let directory = BusyTimeSlotsYamlDirectory::new();
+    let path = directory.path().join("busy_time_slots.yaml");
+    let mut days = String::new();
+    for day_of_week in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"] {
+        let duration_minutes = if day_of_week == "Sun" { 0 } else { 480 };
+        write!(
+            days,
+            "  - day_of_week: {day_of_week}\n    busy_time_slots:\n      - start_time: \"09:00\"\n        duration_minutes: {duration_minutes}\n        name: work\n"
+        )
+        .unwrap();
+    }
+    fs::write(&path, format!("days_of_week:\n{days}")).unwrap();
+    // 2026/11/3(火)は文化の日
+    let start = Local.with_ymd_and_hms(2026, 11, 3, 9, 0, 0).unwrap();
+    let end = Local.with_ymd_and_hms(2026, 11, 3, 17, 0, 0).unwrap();
+
+    let mut weekday_manager = FreeTimeManager::new();
+    weekday_manager
+        .load_busy_time_slots_from_file(path.to_str().unwrap())
+        .unwrap();
+    let mut holiday_manager = FreeTimeManager::new().with_holiday_weekday(Some(Weekday::Sun));
+    holiday_manager
+        .load_busy_time_slots_from_file(path.to_str().unwrap())
+        .unwrap();
+
+    assert_eq!(weekday_manager.get_free_minutes(&start, &end), 0);
+    assert_eq!(holiday_manager.get_free_minutes(&start, &end), 480);
+}
+
 #[test]
 fn load_busy_time_slots_from_file_70日を超える将来日にも毎週定期slotを適用する() {
     let path = write_busy_time_slots_yaml();
@@ -452,6 +502,26 @@ fn load_busy_time_slots_from_file_70日を超える将来日にも毎週定期sl
     fs::remove_file(path).unwrap();
 }
 
+#[test]
+fn clear_registered_busy_time_slotsは日付を決めたslotだけを捨て定期slotを残す() {
+    let path = write_busy_time_slots_yaml();
+    let mut manager = FreeTimeManager::new();
+    manager
+        .load_busy_time_slots_from_file(path.to_str().unwrap())
+        .expect("正常なbusy_time_slots.yamlは読み込めるべきです");
+    let start = Local.with_ymd_and_hms(2026, 10, 20, 0, 0, 0).unwrap();
+    let registered_end = Local.with_ymd_and_hms(2026, 10, 20, 2, 0, 0).unwrap();
+    manager
+        .register_busy_time_slot(&start, &registered_end)
+        .unwrap();
+    assert_eq!(manager.get_busy_minutes(&start, &registered_end), 120);
+
+    manager.clear_registered_busy_time_slots();
+
+    assert_eq!(manager.get_busy_minutes(&start, &registered_end), 60);
+    fs::remove_file(path).unwrap();
+}
+
 #[test]
 fn get_free_minutes_日跨ぎ照会でも各日の定期slotを差し引く() {
     let path = write_busy_time_slots_yaml();
@@ -1144,6 +1214,19 @@ fn test_register_busy_time_slot_日跨ぎは回復可能なエラーになる()
     assert!(error.to_string().contains("different date"));
 }
 
+#[test]
+fn test_register_busy_time_slot_翌日0時までは同じ日の終わりとして登録する() {
+    let mut manager = FreeTimeManager::new();
+    let start = Local.with_ymd_and_hms(2000, 1, 1, 23, 30, 0).unwrap();
+    let end = Local.with_ymd_and_hms(2000, 1, 2, 0, 0, 0).unwrap();
+
+    manager
+        .register_busy_time_slot(&start, &end)
+        .expect("翌日0時で終わるslotは登録できるべきです");
+
+    assert_eq!(manager.get_busy_minutes(&start, &end), 30);
+}
+
 #[test]
 fn test_register_busy_time_slot_日跨ぎエラー後も既存状態を維持する() {
     let mut manager = FreeTimeManager::new();
@@ -0,0 +1,189 @@
+use crate::adapter::gateway::storage_encryption::{decode_storage_file, StorageCipher};
+use crate::adapter::gateway::task_repository::write_file_atomically;
+use crate::application::interface::{LoadHistoryStoreError, LoadHistoryStoreTrait};
+use crate::application::load_history::LoadSnapshot;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const LOAD_HISTORY_FILE_NAME: &str = ".load_history.jsonl";
+
+// 1行に1件のsnapshotをJSONで追記する。project.yamlとは独立したmetadataなので、
+// repositoryのloadやrevisionの対象にはならない
+pub struct LoadHistoryFile {
+    path: PathBuf,
+    cipher: Option<StorageCipher>,
+}
+
+impl LoadHistoryFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            cipher: None,
+        }
+    }
+
+    // 暗号化した保存先では、file全体をproject.yamlと同じ鍵の暗号文で持つ
+    pub fn with_cipher(mut self, cipher: Option<StorageCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    pub fn in_storage_directory(storage_directory: impl AsRef<Path>) -> Self {
+        Self::new(storage_directory.as_ref().join(LOAD_HISTORY_FILE_NAME))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_contents(&self) -> Result<String, LoadHistoryStoreError> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(String::new()),
+            Err(error) => return Err(LoadHistoryStoreError::new(&self.path, None, error)),
+        };
+        decode_storage_file(self.cipher.as_ref(), &bytes)
+            .map_err(|error| LoadHistoryStoreError::new(&self.path, None, error))
+            .and_then(|bytes| {
+                String::from_utf8(bytes)
+                    .map_err(|error| LoadHistoryStoreError::new(&self.path, None, error))
+            })
+    }
+}
+
+impl LoadHistoryStoreTrait for LoadHistoryFile {
+    fn list_load_snapshots(&self) -> Result<Vec<LoadSnapshot>, LoadHistoryStoreError> {
+        self.read_contents()?
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line)
+                    .map_err(|error| LoadHistoryStoreError::new(&self.path, Some(index + 1), error))
+            })
+            .collect()
+    }
+
+    fn append_load_snapshot(
+        &mut self,
+        snapshot: &LoadSnapshot,
+    ) -> Result<(), LoadHistoryStoreError> {
+        let mut line = serde_json::to_string(snapshot)
+            .map_err(|error| LoadHistoryStoreError::new(&self.path, None, error))?;
+        line.push('\n');
+
+        // 暗号文には1行ずつ追記できないので、読み直した全体を書き換える
+        if let Some(cipher) = &self.cipher {
+            let mut contents = self.read_contents()?;
+            contents.push_str(&line);
+            return write_file_atomically(&self.path, &cipher.encrypt(contents.as_bytes()))
+                .map(|_| ())
+                .map_err(|error| LoadHistoryStoreError::new(&self.path, None, error));
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|error| LoadHistoryStoreError::new(&self.path, None, error))?;
+        file.write_all(line.as_bytes())
+            .and_then(|()| file.sync_all())
+            .map_err(|error| LoadHistoryStoreError::new(&self.path, None, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::load_history::{build_load_snapshot, StatusCounts};
+    use crate::test_support::TestStorageDir;
+    use chrono::{Local, NaiveDate, TimeZone};
+
+    fn snapshot(day: u32) -> LoadSnapshot {
+        build_load_snapshot(
+            NaiveDate::from_ymd_opt(2026, 10, day).unwrap(),
+            Local.with_ymd_and_hms(2026, 10, day, 9, 0, 0).unwrap(),
+            0,
+            1800,
+            0,
+            StatusCounts {
+                todo: 3,
+                pending: 2,
+                done: 1,
+            },
+        )
+    }
+
+    #[test]
+    fn 負荷履歴fileがなければ空の履歴を返す() {
+        let storage_dir = TestStorageDir::new("load-history");
+        let history = LoadHistoryFile::in_storage_directory(&storage_dir.path);
+
+        assert_eq!(history.list_load_snapshots().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn 負荷履歴fileへ追記したsnapshotを順に読み直せる() {
+        let storage_dir = TestStorageDir::new("load-history");
+        let mut history = LoadHistoryFile::in_storage_directory(&storage_dir.path);
+
+        history.append_load_snapshot(&snapshot(17)).unwrap();
+        history.append_load_snapshot(&snapshot(18)).unwrap();
+
+        assert_eq!(
+            history.list_load_snapshots().unwrap(),
+            vec![snapshot(17), snapshot(18)]
+        );
+        assert_eq!(
+            fs::read_to_string(history.path()).unwrap().lines().count(),
+            2
+        );
+    }
+
+    #[test]
+    fn 暗号化した保存先では負荷履歴を暗号文のまま追記し読み直せる() {
+        use crate::adapter::gateway::storage_encryption::{
+            initialize_storage_encryption, is_encrypted, StorageKeySource,
+        };
+
+        let storage_dir = TestStorageDir::new("load-history");
+        let key_file_path = storage_dir.path.join("schronu.key");
+        fs::write(&key_file_path, [9; 32]).unwrap();
+        let cipher = initialize_storage_encryption(
+            &storage_dir.path,
+            &StorageKeySource::new(Some(key_file_path), None),
+        )
+        .unwrap();
+        let mut history =
+            LoadHistoryFile::in_storage_directory(&storage_dir.path).with_cipher(Some(cipher));
+
+        history.append_load_snapshot(&snapshot(17)).unwrap();
+        history.append_load_snapshot(&snapshot(18)).unwrap();
+
+        assert!(is_encrypted(&fs::read(history.path()).unwrap()));
+        assert_eq!(
+            history.list_load_snapshots().unwrap(),
+            vec![snapshot(17), snapshot(18)]
+        );
+        assert!(LoadHistoryFile::in_storage_directory(&storage_dir.path)
+            .list_load_snapshots()
+            .is_err());
+    }
+
+    #[test]
+    fn 負荷履歴fileの壊れた行は行番号付きerrorにする() {
+        let storage_dir = TestStorageDir::new("load-history");
+        let mut history = LoadHistoryFile::in_storage_directory(&storage_dir.path);
+        history.append_load_snapshot(&snapshot(17)).unwrap();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(history.path())
+            .unwrap();
+        file.write_all(b"{broken\n").unwrap();
+
+        let error = history.list_load_snapshots().unwrap_err();
+
+        assert_eq!(error.path(), history.path());
+        assert_eq!(error.line_number(), Some(2));
+    }
+}
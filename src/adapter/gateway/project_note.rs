@@ -0,0 +1,99 @@
+use crate::adapter::gateway::task_repository::write_file_atomically;
+use crate::application::interface::{ProjectNoteStoreError, ProjectNoteStoreTrait};
+use crate::application::project_note::ProjectNote;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PROJECT_NOTE_DIRECTORY_NAME: &str = "Schronu";
+
+// vault直下のSchronu/にproject 1つにつき1つのMarkdownを置く。下の階層は見ない
+pub struct ProjectNoteDirectory {
+    path: PathBuf,
+}
+
+impl ProjectNoteDirectory {
+    pub fn in_vault(vault_directory: impl AsRef<Path>) -> Self {
+        Self {
+            path: vault_directory.as_ref().join(PROJECT_NOTE_DIRECTORY_NAME),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl ProjectNoteStoreTrait for ProjectNoteDirectory {
+    fn list_project_notes(&self) -> Result<Vec<ProjectNote>, ProjectNoteStoreError> {
+        let entries = match fs::read_dir(&self.path) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(ProjectNoteStoreError::new(&self.path, error)),
+        };
+        let mut paths = vec![];
+        for entry in entries {
+            let path = entry
+                .map_err(|error| ProjectNoteStoreError::new(&self.path, error))?
+                .path();
+            if path.is_file() && path.extension().is_some_and(|extension| extension == "md") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let file_name = path.file_name()?.to_str()?.to_string();
+                Some(
+                    fs::read_to_string(&path)
+                        .map(|text| ProjectNote { file_name, text })
+                        .map_err(|error| ProjectNoteStoreError::new(&path, error)),
+                )
+            })
+            .collect()
+    }
+
+    fn write_project_note(&mut self, note: &ProjectNote) -> Result<(), ProjectNoteStoreError> {
+        fs::create_dir_all(&self.path)
+            .map_err(|error| ProjectNoteStoreError::new(&self.path, error))?;
+        let path = self.path.join(&note.file_name);
+        write_file_atomically(&path, note.text.as_bytes())
+            .map(|_| ())
+            .map_err(|error| ProjectNoteStoreError::new(&path, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use uuid::Uuid;
+
+    #[test]
+    fn vaultのschronu直下のmarkdownだけを名前順に読み書き込みでdirectoryを作る() {
+        let vault = env::temp_dir().join(format!("schronu-vault-test-{}", Uuid::new_v4()));
+        let mut directory = ProjectNoteDirectory::in_vault(&vault);
+        assert!(directory.list_project_notes().unwrap().is_empty());
+
+        for file_name in ["b.md", "a.md"] {
+            directory
+                .write_project_note(&ProjectNote {
+                    file_name: file_name.to_string(),
+                    text: format!("{file_name}\n"),
+                })
+                .unwrap();
+        }
+        fs::write(directory.path().join("memo.txt"), "skip").unwrap();
+        fs::create_dir_all(directory.path().join("nested.md")).unwrap();
+
+        let notes = directory.list_project_notes().unwrap();
+        assert_eq!(
+            notes
+                .iter()
+                .map(|note| (note.file_name.as_str(), note.text.as_str()))
+                .collect::<Vec<_>>(),
+            [("a.md", "a.md\n"), ("b.md", "b.md\n")]
+        );
+        fs::remove_dir_all(vault).unwrap();
+    }
+}
@@ -6,9 +6,10 @@ mod tests {
     use std::path::{Path, PathBuf};
     use uuid::Uuid;
 
+    use crate::adapter::gateway::storage_history::DEFAULT_STORAGE_HISTORY_RETENTION_REVISIONS;
     use crate::entity::datetime::DEFAULT_END_OF_DAY_OFFSET_MINUTES;
 
-    use super::{load_schronu_config, SchronuConfig};
+    use super::{load_schronu_config, SchronuConfig, StorageBackend};
 
     fn write_config(directory: &Path, contents: &str) -> PathBuf {
         let path = directory.join("schronu.yaml");
@@ -27,6 +28,7 @@ mod tests {
         let actual = load_schronu_config(None).unwrap();
 
         assert_eq!(actual.obsidian_vault_name, "Obsidian-Work");
+        assert_eq!(actual.obsidian_vault_path, None);
         assert_eq!(
             actual.busy_time_slots_yaml_path,
             PathBuf::from("../Schronu-private/busy_time_slots.yaml")
@@ -37,10 +39,22 @@ mod tests {
         );
         assert_eq!(actual.calendar_blank_line_weekday, Weekday::Mon);
         assert!(actual.extrude_skip_weekdays.is_empty());
+        assert_eq!(
+            actual.non_business_weekdays,
+            vec![Weekday::Sat, Weekday::Sun]
+        );
+        assert_eq!(actual.holiday_weekday, None);
         assert_eq!(
             actual.default_deadline_time,
             NaiveTime::from_hms_opt(23, 59, 59).unwrap()
         );
+        assert_eq!(
+            actual.storage_history_retention_revisions,
+            DEFAULT_STORAGE_HISTORY_RETENTION_REVISIONS
+        );
+        assert_eq!(actual.storage_backend, StorageBackend::Yaml);
+        assert_eq!(actual.mcp_lock_wait_seconds, 0);
+        assert_eq!(actual.storage_encryption_key_file_path, None);
     }
 
     #[test]
@@ -48,12 +62,13 @@ mod tests {
         let directory = test_directory();
         let path = write_config(
             &directory,
-            "obsidian_vault_name: Work\nbusy_time_slots_yaml_path: schedules/busy.yaml\nend_of_day_offset_minutes: -120\ncalendar_blank_line_weekday: Fri\nextrude_skip_weekdays: [Sat, Sun]\ndefault_deadline_time: '19:00'\n",
+            "obsidian_vault_name: Work\nobsidian_vault_path: vault\nbusy_time_slots_yaml_path: schedules/busy.yaml\nend_of_day_offset_minutes: -120\ncalendar_blank_line_weekday: Fri\nextrude_skip_weekdays: [Sat, Sun]\nnon_business_weekdays: [Fri, Sat]\nholiday_weekday: Sun\ndefault_deadline_time: '19:00'\nstorage_history_retention_revisions: 20\nstorage_backend: sqlite\nmcp_lock_wait_seconds: 30\nstorage_encryption_key_file_path: keys/schronu.key\n",
         );
 
         let actual = load_schronu_config(Some(path.into_os_string())).unwrap();
 
         assert_eq!(actual.obsidian_vault_name, "Work");
+        assert_eq!(actual.obsidian_vault_path, Some(directory.join("vault")));
         assert_eq!(
             actual.busy_time_slots_yaml_path,
             directory.join("schedules/busy.yaml")
@@ -64,10 +79,22 @@ mod tests {
             actual.extrude_skip_weekdays,
             vec![Weekday::Sat, Weekday::Sun]
         );
+        assert_eq!(
+            actual.non_business_weekdays,
+            vec![Weekday::Fri, Weekday::Sat]
+        );
+        assert_eq!(actual.holiday_weekday, Some(Weekday::Sun));
         assert_eq!(
             actual.default_deadline_time,
             NaiveTime::from_hms_opt(19, 0, 0).unwrap()
         );
+        assert_eq!(actual.storage_history_retention_revisions, 20);
+        assert_eq!(actual.storage_backend, StorageBackend::Sqlite);
+        assert_eq!(actual.mcp_lock_wait_seconds, 30);
+        assert_eq!(
+            actual.storage_encryption_key_file_path,
+            Some(directory.join("keys/schronu.key"))
+        );
     }
 
     #[test]
@@ -77,6 +104,10 @@ mod tests {
         let actual = load_schronu_config(Some(path.clone().into_os_string())).unwrap();
 
         assert_eq!(actual.obsidian_vault_name, "Obsidian-Work");
+        assert_eq!(
+            actual.obsidian_vault_path,
+            Some(path.parent().unwrap().join("../../Obsidian-Work"))
+        );
         assert_eq!(
             actual.busy_time_slots_yaml_path,
             path.parent().unwrap().join("busy_time_slots.yaml")
@@ -87,10 +118,19 @@ mod tests {
             actual.extrude_skip_weekdays,
             vec![Weekday::Sat, Weekday::Sun]
         );
+        assert_eq!(
+            actual.non_business_weekdays,
+            vec![Weekday::Sat, Weekday::Sun]
+        );
         assert_eq!(
             actual.default_deadline_time,
             NaiveTime::from_hms_opt(19, 0, 0).unwrap()
         );
+        assert_eq!(actual.holiday_weekday, Some(Weekday::Sun));
+        assert_eq!(actual.storage_history_retention_revisions, 100);
+        assert_eq!(actual.storage_backend, StorageBackend::Yaml);
+        assert_eq!(actual.mcp_lock_wait_seconds, 10);
+        assert_eq!(actual.storage_encryption_key_file_path, None);
     }
 
     #[test]
@@ -114,6 +154,19 @@ mod tests {
             "default_deadline_time: '25:00'\n",
             "extrude_skip_weekdays: [Sat, Sat]\n",
             "extrude_skip_weekdays: [Mon, Tue, Wed, Thu, Fri, Sat, Sun]\n",
+            "non_business_weekdays: [Sun, Sun]\n",
+            "non_business_weekdays: [Mon, Tue, Wed, Thu, Fri, Sat, Sun]\n",
+            "non_business_weekdays: Sat\n",
+            "holiday_weekday: Sunday\n",
+            "holiday_weekday: [Sun]\n",
+            "storage_history_retention_revisions: -1\n",
+            "storage_history_retention_revisions: '10'\n",
+            "storage_backend: SQLite\n",
+            "storage_backend: 1\n",
+            "mcp_lock_wait_seconds: -1\n",
+            "mcp_lock_wait_seconds: 1.5\n",
+            "storage_encryption_key_file_path: ''\n",
+            "storage_encryption_key_file_path: [key]\n",
         ] {
             let directory = test_directory();
             let path = write_config(&directory, contents);
@@ -143,30 +196,56 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use yaml_rust::{Yaml, YamlLoader};
 
+use crate::adapter::gateway::storage_history::DEFAULT_STORAGE_HISTORY_RETENTION_REVISIONS;
 use crate::entity::datetime::DEFAULT_END_OF_DAY_OFFSET_MINUTES;
 
 const DEFAULT_OBSIDIAN_VAULT_NAME: &str = "Obsidian-Work";
 const DEFAULT_BUSY_TIME_SLOTS_YAML_PATH: &str = "../Schronu-private/busy_time_slots.yaml";
 
+// taskの保存形式。sqliteでは保存先directory直下のdatabase fileにtaskを保存する
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageBackend {
+    Yaml,
+    Sqlite,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SchronuConfig {
     pub obsidian_vault_name: String,
+    // 設定するとproject noteをこのvaultへ同期できる
+    pub obsidian_vault_path: Option<PathBuf>,
     pub busy_time_slots_yaml_path: PathBuf,
     pub end_of_day_offset_minutes: i64,
     pub calendar_blank_line_weekday: Weekday,
     pub extrude_skip_weekdays: Vec<Weekday>,
+    pub non_business_weekdays: Vec<Weekday>,
+    // 設定すると祝日を休みとし、空き時間はこの曜日の予定表で数える
+    pub holiday_weekday: Option<Weekday>,
     pub default_deadline_time: NaiveTime,
+    pub storage_history_retention_revisions: usize,
+    pub storage_backend: StorageBackend,
+    // MCPの書き込みがCLIなどのlockの解放を待つ秒数。0なら待たずに競合を返す
+    pub mcp_lock_wait_seconds: u64,
+    // 暗号化した保存先の鍵を読むfile。設定しなければ環境変数のpassphraseを使う
+    pub storage_encryption_key_file_path: Option<PathBuf>,
 }
 
 impl Default for SchronuConfig {
     fn default() -> Self {
         Self {
             obsidian_vault_name: DEFAULT_OBSIDIAN_VAULT_NAME.to_string(),
+            obsidian_vault_path: None,
             busy_time_slots_yaml_path: PathBuf::from(DEFAULT_BUSY_TIME_SLOTS_YAML_PATH),
             end_of_day_offset_minutes: DEFAULT_END_OF_DAY_OFFSET_MINUTES,
             calendar_blank_line_weekday: Weekday::Mon,
             extrude_skip_weekdays: vec![],
+            non_business_weekdays: vec![Weekday::Sat, Weekday::Sun],
+            holiday_weekday: None,
             default_deadline_time: NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
+            storage_history_retention_revisions: DEFAULT_STORAGE_HISTORY_RETENTION_REVISIONS,
+            storage_backend: StorageBackend::Yaml,
+            mcp_lock_wait_seconds: 0,
+            storage_encryption_key_file_path: None,
         }
     }
 }
@@ -196,11 +275,18 @@ fn parse_schronu_config(contents: &str, config_directory: &Path) -> Result<Schro
         .ok_or_else(|| "config YAML root must be a mapping".to_string())?;
     let known_keys = [
         "obsidian_vault_name",
+        "obsidian_vault_path",
         "busy_time_slots_yaml_path",
         "end_of_day_offset_minutes",
         "calendar_blank_line_weekday",
         "extrude_skip_weekdays",
+        "non_business_weekdays",
+        "holiday_weekday",
         "default_deadline_time",
+        "storage_history_retention_revisions",
+        "storage_backend",
+        "mcp_lock_wait_seconds",
+        "storage_encryption_key_file_path",
     ];
     for key in mapping.keys() {
         let key = key
@@ -218,16 +304,17 @@ fn parse_schronu_config(contents: &str, config_directory: &Path) -> Result<Schro
         }
         config.obsidian_vault_name = value.to_string();
     }
+    if let Some(value) = optional_string(yaml, "obsidian_vault_path")? {
+        if value.is_empty() {
+            return Err("obsidian_vault_path must not be empty".to_string());
+        }
+        config.obsidian_vault_path = Some(resolve_config_path(config_directory, value));
+    }
     if let Some(value) = optional_string(yaml, "busy_time_slots_yaml_path")? {
         if value.is_empty() {
             return Err("busy_time_slots_yaml_path must not be empty".to_string());
         }
-        let path = PathBuf::from(value);
-        config.busy_time_slots_yaml_path = if path.is_absolute() {
-            path
-        } else {
-            config_directory.join(path)
-        };
+        config.busy_time_slots_yaml_path = resolve_config_path(config_directory, value);
     }
     if !matches!(yaml["end_of_day_offset_minutes"], Yaml::BadValue) {
         config.end_of_day_offset_minutes = yaml["end_of_day_offset_minutes"]
@@ -240,34 +327,64 @@ fn parse_schronu_config(contents: &str, config_directory: &Path) -> Result<Schro
     if let Some(value) = optional_string(yaml, "calendar_blank_line_weekday")? {
         config.calendar_blank_line_weekday = parse_weekday(value)?;
     }
-    if let Some(value) = yaml["extrude_skip_weekdays"].as_vec() {
-        let mut weekdays = Vec::with_capacity(value.len());
-        let mut seen = HashSet::new();
-        for weekday in value {
-            let weekday = weekday
-                .as_str()
-                .ok_or_else(|| "extrude_skip_weekdays must contain weekday strings".to_string())?;
-            let weekday = parse_weekday(weekday)?;
-            if !seen.insert(weekday) {
-                return Err(format!(
-                    "extrude_skip_weekdays contains duplicate weekday: {weekday:?}"
-                ));
-            }
-            weekdays.push(weekday);
-        }
+    if let Some(weekdays) = optional_weekdays(yaml, "extrude_skip_weekdays")? {
         config.extrude_skip_weekdays = weekdays;
-    } else if !matches!(yaml["extrude_skip_weekdays"], Yaml::BadValue) {
-        return Err("extrude_skip_weekdays must be an array".to_string());
     }
     if config.extrude_skip_weekdays.len() == 7 {
         return Err("extrude_skip_weekdays must leave at least one weekday".to_string());
     }
+    if let Some(weekdays) = optional_weekdays(yaml, "non_business_weekdays")? {
+        config.non_business_weekdays = weekdays;
+    }
+    if config.non_business_weekdays.len() == 7 {
+        return Err("non_business_weekdays must leave at least one weekday".to_string());
+    }
+    if let Some(value) = optional_string(yaml, "holiday_weekday")? {
+        config.holiday_weekday = Some(parse_weekday(value)?);
+    }
     if let Some(value) = optional_string(yaml, "default_deadline_time")? {
         config.default_deadline_time = parse_deadline_time(value)?;
     }
+    if !matches!(yaml["storage_history_retention_revisions"], Yaml::BadValue) {
+        config.storage_history_retention_revisions = yaml["storage_history_retention_revisions"]
+            .as_i64()
+            .and_then(|revisions| usize::try_from(revisions).ok())
+            .ok_or_else(|| {
+                "storage_history_retention_revisions must be a non-negative integer".to_string()
+            })?;
+    }
+    if let Some(value) = optional_string(yaml, "storage_backend")? {
+        config.storage_backend = match value {
+            "yaml" => StorageBackend::Yaml,
+            "sqlite" => StorageBackend::Sqlite,
+            _ => return Err(format!("storage_backend must be yaml or sqlite: {value}")),
+        };
+    }
+    if !matches!(yaml["mcp_lock_wait_seconds"], Yaml::BadValue) {
+        config.mcp_lock_wait_seconds = yaml["mcp_lock_wait_seconds"]
+            .as_i64()
+            .and_then(|seconds| u64::try_from(seconds).ok())
+            .ok_or_else(|| "mcp_lock_wait_seconds must be a non-negative integer".to_string())?;
+    }
+    if let Some(value) = optional_string(yaml, "storage_encryption_key_file_path")? {
+        if value.is_empty() {
+            return Err("storage_encryption_key_file_path must not be empty".to_string());
+        }
+        config.storage_encryption_key_file_path =
+            Some(resolve_config_path(config_directory, value));
+    }
     Ok(config)
 }
 
+fn resolve_config_path(config_directory: &Path, value: &str) -> PathBuf {
+    let path = PathBuf::from(value);
+    if path.is_absolute() {
+        path
+    } else {
+        config_directory.join(path)
+    }
+}
+
 fn optional_string<'a>(yaml: &'a Yaml, key: &str) -> Result<Option<&'a str>, String> {
     match &yaml[key] {
         Yaml::BadValue => Ok(None),
@@ -278,6 +395,27 @@ fn optional_string<'a>(yaml: &'a Yaml, key: &str) -> Result<Option<&'a str>, Str
     }
 }
 
+fn optional_weekdays(yaml: &Yaml, key: &str) -> Result<Option<Vec<Weekday>>, String> {
+    let value = match &yaml[key] {
+        Yaml::BadValue => return Ok(None),
+        Yaml::Array(value) => value,
+        _ => return Err(format!("{key} must be an array")),
+    };
+    let mut weekdays = Vec::with_capacity(value.len());
+    let mut seen = HashSet::new();
+    for weekday in value {
+        let weekday = weekday
+            .as_str()
+            .ok_or_else(|| format!("{key} must contain weekday strings"))?;
+        let weekday = parse_weekday(weekday)?;
+        if !seen.insert(weekday) {
+            return Err(format!("{key} contains duplicate weekday: {weekday:?}"));
+        }
+        weekdays.push(weekday);
+    }
+    Ok(Some(weekdays))
+}
+
 fn parse_deadline_time(value: &str) -> Result<NaiveTime, String> {
     NaiveTime::parse_from_str(value, "%H:%M:%S")
         .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
@@ -0,0 +1,1294 @@
+use crate::adapter::gateway::task_repository::{
+    available_project_key, new_project_key, select_defer_candidate_leaf_task_id,
+    select_highest_priority_leaf_task_id,
+};
+use crate::adapter::gateway::yaml::yaml_to_task;
+use crate::application::interface::{
+    RepositoryReloadOutcome, TaskRepositoryError,
+    TaskRepositoryOperation as ApplicationRepositoryOperation, TaskRepositoryTrait,
+};
+use crate::application::task_use_case::TaskPeriodField;
+use crate::entity::task::{task_to_yaml, TaskHandle, TaskTreeError};
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+
+pub const SQLITE_DATABASE_FILE_NAME: &str = "schronu.sqlite3";
+
+// 他のprocessが書き込み中なら、この時間まで待ってからerrorにする
+const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 列の時刻はUTCに直し、offsetの違う時刻が混ざっても文字列比較で範囲検索できる形式で持つ
+const SQLITE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+// UTCへ移す前の列はoffsetのないlocal timeだった
+const LEGACY_LOCAL_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// storage_metadataにこのkeyがなければ、時刻列はまだlocal timeのまま
+const TIME_ZONE_METADATA_KEY: &str = "time_zone";
+const TIME_ZONE_METADATA_VALUE: &str = "UTC";
+
+// tasksの1行は1 taskで、attributesにはchildrenを除いたYAML表現を持つ。
+// 期間や状態で絞るための列は、attributesと同じ値を検索用に複製したもの
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS storage_metadata (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS projects (
+    project_key TEXT PRIMARY KEY,
+    root_task_id TEXT NOT NULL,
+    priority INTEGER NOT NULL,
+    revision TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS tasks (
+    id TEXT PRIMARY KEY,
+    project_key TEXT NOT NULL REFERENCES projects(project_key) ON DELETE CASCADE,
+    parent_id TEXT,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    status TEXT NOT NULL,
+    deadline_time TEXT,
+    end_time TEXT,
+    attributes TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS tasks_project_key ON tasks(project_key);
+CREATE INDEX IF NOT EXISTS tasks_parent_id ON tasks(parent_id, position);
+CREATE INDEX IF NOT EXISTS tasks_deadline_time ON tasks(deadline_time);
+CREATE INDEX IF NOT EXISTS tasks_end_time ON tasks(end_time);
+CREATE INDEX IF NOT EXISTS tasks_status ON tasks(status);
+";
+
+pub struct SqliteTaskRepository {
+    connection: Connection,
+    database_path: PathBuf,
+    projects: Vec<SqliteProject>,
+    project_storage_dir_name: String,
+    last_synced_time: DateTime<Local>,
+    id_to_task_map: RefCell<HashMap<Uuid, TaskHandle>>,
+    storage_revision: Cell<Option<Uuid>>,
+    has_loaded: bool,
+    // remove_projectで取り除き、次のsaveで行を消すproject
+    removed_project_keys: RefCell<Vec<String>>,
+}
+
+struct SqliteProject {
+    project_key: String,
+    root_task: TaskHandle,
+    priority: i64,
+    revision: Cell<Option<Uuid>>,
+    persisted_mutation_revision: Cell<Option<u64>>,
+}
+
+impl SqliteProject {
+    fn new(project_key: impl Into<String>, root_task: TaskHandle, priority: i64) -> Self {
+        Self {
+            project_key: project_key.into(),
+            root_task,
+            priority,
+            revision: Cell::new(None),
+            persisted_mutation_revision: Cell::new(None),
+        }
+    }
+
+    fn mark_clean(&self) -> Result<(), TaskTreeError> {
+        self.persisted_mutation_revision
+            .set(Some(self.root_task.get_persistent_mutation_revision()?));
+        Ok(())
+    }
+
+    fn needs_save(&self) -> Result<bool, TaskTreeError> {
+        Ok(self.persisted_mutation_revision.get()
+            != Some(self.root_task.get_persistent_mutation_revision()?))
+    }
+}
+
+// 差分reloadで1つのproject行をどう扱ったか
+enum LoadedProject {
+    Kept(usize),
+    Parsed(SqliteProject),
+}
+
+// 保存済みtaskを期間で検索するときに参照する時刻列
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum SqliteTaskTimeColumn {
+    Deadline,
+    EndTime,
+}
+
+impl SqliteTaskTimeColumn {
+    fn column_name(self) -> &'static str {
+        match self {
+            Self::Deadline => "deadline_time",
+            Self::EndTime => "end_time",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum SqliteRepositoryOperation {
+    OpenDatabase,
+    Query,
+    ParseProject,
+    SerializeProject,
+    Write,
+}
+
+#[derive(Debug)]
+pub(super) struct SqliteRepositoryError {
+    operation: SqliteRepositoryOperation,
+    path: PathBuf,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl SqliteRepositoryError {
+    fn new<E>(operation: SqliteRepositoryOperation, path: impl Into<PathBuf>, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Self {
+            operation,
+            path: path.into(),
+            source: source.into(),
+        }
+    }
+}
+
+impl fmt::Display for SqliteRepositoryError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "sqlite repository {:?} failed for {}: {}",
+            self.operation,
+            self.path.display(),
+            self.source
+        )
+    }
+}
+
+impl Error for SqliteRepositoryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+// 1 task分の行。attributesはchildrenを除いたYAML文字列
+struct TaskRow {
+    id: String,
+    parent_id: Option<String>,
+    position: i64,
+    name: String,
+    status: String,
+    deadline_time: Option<String>,
+    end_time: Option<String>,
+    attributes: String,
+}
+
+impl SqliteTaskRepository {
+    // 保存先directory直下のSQLITE_DATABASE_FILE_NAMEを開く
+    pub fn open(project_storage_dir_name: &str) -> Result<Self, TaskRepositoryError> {
+        Self::open_database(
+            project_storage_dir_name,
+            Path::new(project_storage_dir_name).join(SQLITE_DATABASE_FILE_NAME),
+        )
+    }
+
+    // databaseがなければschemaごと作る。保存先directoryはlockやmetadataの置き場として使う
+    pub fn open_database(
+        project_storage_dir_name: &str,
+        database_path: impl Into<PathBuf>,
+    ) -> Result<Self, TaskRepositoryError> {
+        let database_path = database_path.into();
+        let open_error = |error: rusqlite::Error| {
+            TaskRepositoryError::new(
+                ApplicationRepositoryOperation::Load,
+                SqliteRepositoryError::new(
+                    SqliteRepositoryOperation::OpenDatabase,
+                    &database_path,
+                    error,
+                ),
+            )
+        };
+        let mut connection = Connection::open(&database_path).map_err(open_error)?;
+        connection
+            .busy_timeout(SQLITE_BUSY_TIMEOUT)
+            .and_then(|()| connection.execute_batch("PRAGMA foreign_keys = ON;"))
+            .and_then(|()| connection.execute_batch(SCHEMA))
+            .map_err(open_error)?;
+        migrate_time_columns_to_utc(&mut connection).map_err(|error| {
+            TaskRepositoryError::new(
+                ApplicationRepositoryOperation::Load,
+                SqliteRepositoryError::new(SqliteRepositoryOperation::Write, &database_path, error),
+            )
+        })?;
+
+        Ok(Self {
+            connection,
+            database_path,
+            projects: vec![],
+            project_storage_dir_name: project_storage_dir_name.to_string(),
+            last_synced_time: DateTime::<Local>::MIN_UTC.into(),
+            id_to_task_map: RefCell::new(HashMap::new()),
+            storage_revision: Cell::new(None),
+            has_loaded: false,
+            removed_project_keys: RefCell::new(vec![]),
+        })
+    }
+
+    pub fn database_path(&self) -> &Path {
+        &self.database_path
+    }
+
+    // 保存済みのtaskから、指定した時刻列がfrom以上until未満のもののidを時刻順に返す。
+    // indexを使って検索するため、memory上の未保存の変更は反映されない
+    fn find_saved_task_ids_between(
+        &self,
+        column: SqliteTaskTimeColumn,
+        from: DateTime<Local>,
+        until: DateTime<Local>,
+    ) -> Result<Vec<Uuid>, TaskRepositoryError> {
+        let column_name = column.column_name();
+        let sql = format!(
+            "SELECT id FROM tasks WHERE {column_name} >= ?1 AND {column_name} < ?2 ORDER BY {column_name}, id"
+        );
+        let ids = self
+            .connection
+            .prepare(&sql)
+            .and_then(|mut statement| {
+                statement
+                    .query_map(params![format_time(from), format_time(until)], |row| {
+                        row.get::<_, String>(0)
+                    })?
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|error| self.load_error(SqliteRepositoryOperation::Query, error))?;
+        ids.into_iter()
+            .map(|id| {
+                Uuid::parse_str(&id).map_err(|error| {
+                    self.load_error(SqliteRepositoryOperation::ParseProject, error)
+                })
+            })
+            .collect()
+    }
+
+    // 他の保存形式から移したprojectを、元のkeyのまま未保存として登録する
+    pub(super) fn import_project(
+        &mut self,
+        project_key: &str,
+        root_task: TaskHandle,
+    ) -> Result<(), TaskTreeError> {
+        let priority = root_task.get_priority()?;
+        let project = SqliteProject::new(project_key, root_task, priority);
+        self.cache_task_and_descendants(&project.root_task)?;
+        self.projects.push(project);
+        Ok(())
+    }
+
+    pub(super) fn project_entries(&self) -> Vec<(String, &TaskHandle)> {
+        self.projects
+            .iter()
+            .map(|project| (project.project_key.clone(), &project.root_task))
+            .collect()
+    }
+
+    fn load_error<E>(&self, operation: SqliteRepositoryOperation, error: E) -> TaskRepositoryError
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        TaskRepositoryError::new(
+            ApplicationRepositoryOperation::Load,
+            SqliteRepositoryError::new(operation, &self.database_path, error),
+        )
+    }
+
+    fn save_error<E>(&self, operation: SqliteRepositoryOperation, error: E) -> TaskRepositoryError
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        TaskRepositoryError::new(
+            ApplicationRepositoryOperation::Save,
+            SqliteRepositoryError::new(operation, &self.database_path, error),
+        )
+    }
+
+    fn cache_task_and_descendants(&self, task: &TaskHandle) -> Result<(), TaskTreeError> {
+        self.id_to_task_map
+            .borrow_mut()
+            .insert(task.get_id()?, task.clone());
+
+        for child_task in task.get_children()? {
+            self.cache_task_and_descendants(&child_task)?;
+        }
+        Ok(())
+    }
+
+    fn sync_task_and_descendants(
+        task: &TaskHandle,
+        now: DateTime<Local>,
+    ) -> Result<(), TaskTreeError> {
+        task.sync_clock(now)?;
+        for child_task in task.get_children()? {
+            Self::sync_task_and_descendants(&child_task, now)?;
+        }
+        Ok(())
+    }
+
+    fn read_storage_revision(&self) -> Result<Option<Uuid>, TaskRepositoryError> {
+        let revision_text = self
+            .connection
+            .query_row(
+                "SELECT value FROM storage_metadata WHERE key = 'revision'",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(|error| self.load_error(SqliteRepositoryOperation::Query, error))?;
+        revision_text
+            .map(|text| {
+                Uuid::parse_str(&text).map_err(|error| {
+                    self.load_error(SqliteRepositoryOperation::ParseProject, error)
+                })
+            })
+            .transpose()
+    }
+
+    // reuses_unchanged_projectsなら、revisionが一致し未保存の変更もないprojectのtask treeをそのまま使い、
+    // 追加・変更されたprojectの行だけを読み直す。途中で失敗した場合はmemoryを変更しない
+    fn load_projects(
+        &mut self,
+        reuses_unchanged_projects: bool,
+    ) -> Result<(), TaskRepositoryError> {
+        let transaction = self
+            .connection
+            .unchecked_transaction()
+            .map_err(|error| self.load_error(SqliteRepositoryOperation::Query, error))?;
+        let storage_revision = self.read_storage_revision()?;
+        let project_rows = transaction
+            .prepare(
+                "SELECT project_key, root_task_id, revision FROM projects ORDER BY project_key",
+            )
+            .and_then(|mut statement| {
+                statement
+                    .query_map([], |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, String>(2)?,
+                        ))
+                    })?
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|error| self.load_error(SqliteRepositoryOperation::Query, error))?;
+
+        let mut reusable_projects = HashMap::new();
+        if reuses_unchanged_projects {
+            for (index, project) in self.projects.iter().enumerate() {
+                let needs_save = project.needs_save().map_err(|error| {
+                    TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
+                })?;
+                if !needs_save {
+                    reusable_projects.insert(project.project_key.clone(), index);
+                }
+            }
+        }
+
+        let mut loaded_projects = Vec::new();
+        for (project_key, root_task_id, revision_text) in project_rows {
+            let revision = Uuid::parse_str(&revision_text)
+                .map_err(|error| self.load_error(SqliteRepositoryOperation::ParseProject, error))?;
+            if let Some(index) = reusable_projects.remove(&project_key) {
+                if self.projects[index].revision.get() == Some(revision) {
+                    loaded_projects.push(LoadedProject::Kept(index));
+                    continue;
+                }
+            }
+            let project = self.read_project(&project_key, &root_task_id)?;
+            project.revision.set(Some(revision));
+            loaded_projects.push(LoadedProject::Parsed(project));
+        }
+        transaction
+            .finish()
+            .map_err(|error| self.load_error(SqliteRepositoryOperation::Query, error))?;
+
+        let mut previous_projects = std::mem::take(&mut self.projects)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        let mut kept_projects = Vec::new();
+        for loaded_project in loaded_projects {
+            match loaded_project {
+                LoadedProject::Kept(index) => {
+                    let project = previous_projects[index]
+                        .take()
+                        .expect("each project is kept at most once");
+                    kept_projects.push(self.projects.len());
+                    self.projects.push(project);
+                }
+                LoadedProject::Parsed(project) => self.projects.push(project),
+            }
+        }
+        for index in kept_projects {
+            Self::sync_task_and_descendants(&self.projects[index].root_task, self.last_synced_time)
+                .map_err(|error| {
+                    TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
+                })?;
+        }
+        self.id_to_task_map.borrow_mut().clear();
+        for project in &self.projects {
+            self.cache_task_and_descendants(&project.root_task)
+                .map_err(|error| {
+                    TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
+                })?;
+        }
+        self.storage_revision.set(storage_revision);
+        self.removed_project_keys.borrow_mut().clear();
+        self.has_loaded = true;
+        Ok(())
+    }
+
+    // 親子の行をYAMLのtask木に組み立て直し、YAML保存と同じ変換でtask treeにする
+    fn read_project(
+        &self,
+        project_key: &str,
+        root_task_id: &str,
+    ) -> Result<SqliteProject, TaskRepositoryError> {
+        let rows = self
+            .connection
+            .prepare(
+                "SELECT id, parent_id, attributes FROM tasks WHERE project_key = ?1 ORDER BY position",
+            )
+            .and_then(|mut statement| {
+                statement
+                    .query_map(params![project_key], |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, Option<String>>(1)?,
+                            row.get::<_, String>(2)?,
+                        ))
+                    })?
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|error| self.load_error(SqliteRepositoryOperation::Query, error))?;
+
+        let parse_error = |message: String| {
+            self.load_error(
+                SqliteRepositoryOperation::ParseProject,
+                format!("project {project_key}: {message}"),
+            )
+        };
+        let mut attributes_by_id = HashMap::new();
+        let mut children_by_parent_id: HashMap<String, Vec<String>> = HashMap::new();
+        for (id, parent_id, attributes) in rows {
+            let yaml = YamlLoader::load_from_str(&attributes)
+                .map_err(|error| parse_error(error.to_string()))?
+                .into_iter()
+                .next()
+                .filter(|yaml| yaml.as_hash().is_some())
+                .ok_or_else(|| parse_error(format!("task {id} attributes must be a mapping")))?;
+            if let Some(parent_id) = parent_id {
+                children_by_parent_id
+                    .entry(parent_id)
+                    .or_default()
+                    .push(id.clone());
+            }
+            attributes_by_id.insert(id, yaml);
+        }
+
+        let project_yaml =
+            assemble_task_yaml(root_task_id, &mut attributes_by_id, &children_by_parent_id)
+                .ok_or_else(|| parse_error(format!("root task {root_task_id} is missing")))?;
+        if let Some(id) = attributes_by_id.keys().next() {
+            return Err(parse_error(format!(
+                "task {id} is not reachable from the root task"
+            )));
+        }
+        let root_task = yaml_to_task(&project_yaml, self.last_synced_time)
+            .map_err(|error| parse_error(error.to_string()))?;
+        let priority = root_task.get_priority().map_err(|error| {
+            TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
+        })?;
+        let project = SqliteProject::new(project_key, root_task, priority);
+        project.mark_clean().map_err(|error| {
+            TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
+        })?;
+        Ok(project)
+    }
+
+    fn task_rows(&self, project: &SqliteProject) -> Result<Vec<TaskRow>, TaskRepositoryError> {
+        let project_yaml = task_to_yaml(&project.root_task).map_err(|error| {
+            TaskRepositoryError::new(ApplicationRepositoryOperation::Save, error)
+        })?;
+        let mut rows = Vec::new();
+        flatten_task(&project.root_task, &project_yaml, None, 0, &mut rows)
+            .map_err(|error| self.save_error(SqliteRepositoryOperation::SerializeProject, error))?;
+        Ok(rows)
+    }
+}
+
+fn format_time(time: DateTime<Local>) -> String {
+    time.with_timezone(&Utc)
+        .format(SQLITE_TIME_FORMAT)
+        .to_string()
+}
+
+// local timeで書かれた列をUTCへ書き直す。他processと同時に開いても一度だけ書き直すよう、
+// 書き込みlockを取ってから移行済みかを確かめる
+fn migrate_time_columns_to_utc(
+    connection: &mut Connection,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let migrated = transaction
+        .query_row(
+            "SELECT value FROM storage_metadata WHERE key = ?1",
+            params![TIME_ZONE_METADATA_KEY],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()?;
+    match migrated.as_deref() {
+        Some(TIME_ZONE_METADATA_VALUE) => return Ok(()),
+        Some(other) => return Err(format!("unknown time zone of time columns: {other}").into()),
+        None => {}
+    }
+
+    let rows = transaction
+        .prepare(
+            "SELECT id, deadline_time, end_time FROM tasks
+             WHERE deadline_time IS NOT NULL OR end_time IS NOT NULL",
+        )?
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    {
+        let mut statement = transaction
+            .prepare("UPDATE tasks SET deadline_time = ?2, end_time = ?3 WHERE id = ?1")?;
+        for (id, deadline_time, end_time) in rows {
+            statement.execute(params![
+                id,
+                deadline_time
+                    .as_deref()
+                    .map(legacy_time_to_utc)
+                    .transpose()?,
+                end_time.as_deref().map(legacy_time_to_utc).transpose()?,
+            ])?;
+        }
+    }
+    transaction.execute(
+        "INSERT INTO storage_metadata (key, value) VALUES (?1, ?2)",
+        params![TIME_ZONE_METADATA_KEY, TIME_ZONE_METADATA_VALUE],
+    )?;
+    transaction.commit()?;
+    Ok(())
+}
+
+// 夏時間の切り替わりで重なる時刻は早い方、存在しない時刻は切り替わり後の時刻として扱う
+fn legacy_time_to_utc(text: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let naive = NaiveDateTime::parse_from_str(text, LEGACY_LOCAL_TIME_FORMAT)?;
+    let time = naive
+        .and_local_timezone(Local)
+        .earliest()
+        .or_else(|| {
+            (naive + chrono::Duration::hours(1))
+                .and_local_timezone(Local)
+                .earliest()
+        })
+        .ok_or_else(|| format!("time {text} does not exist in the local time zone"))?;
+    Ok(format_time(time))
+}
+
+// task木とそのYAML表現を同時にたどり、1 task 1行に平坦化する
+fn flatten_task(
+    task: &TaskHandle,
+    task_yaml: &Yaml,
+    parent_id: Option<&str>,
+    position: i64,
+    rows: &mut Vec<TaskRow>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut attributes = task_yaml
+        .as_hash()
+        .cloned()
+        .ok_or("task YAML must be a mapping")?;
+    let children_yaml = attributes.remove(&Yaml::String(String::from("children")));
+    let mut attributes_text = String::new();
+    YamlEmitter::new(&mut attributes_text).dump(&Yaml::Hash(attributes))?;
+
+    let id = task.get_id()?.to_string();
+    rows.push(TaskRow {
+        id: id.clone(),
+        parent_id: parent_id.map(str::to_string),
+        position,
+        name: task.get_name()?,
+        status: task.get_orig_status()?.to_string(),
+        deadline_time: task.get_deadline_time_opt()?.map(format_time),
+        end_time: task.get_end_time_opt()?.map(format_time),
+        attributes: attributes_text,
+    });
+
+    let children = task.get_children()?;
+    let children_yaml = children_yaml
+        .as_ref()
+        .and_then(Yaml::as_vec)
+        .map_or(&[][..], Vec::as_slice);
+    if children.len() != children_yaml.len() {
+        return Err("task YAML children must match the task tree".into());
+    }
+    for (child_position, (child, child_yaml)) in children.iter().zip(children_yaml).enumerate() {
+        flatten_task(child, child_yaml, Some(&id), child_position as i64, rows)?;
+    }
+    Ok(())
+}
+
+// 使った行はattributes_by_idから取り除き、どこからも辿れない行を検出できるようにする
+fn assemble_task_yaml(
+    id: &str,
+    attributes_by_id: &mut HashMap<String, Yaml>,
+    children_by_parent_id: &HashMap<String, Vec<String>>,
+) -> Option<Yaml> {
+    let Yaml::Hash(mut task_hash) = attributes_by_id.remove(id)? else {
+        return None;
+    };
+    let children = children_by_parent_id
+        .get(id)
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .filter_map(|child_id| {
+            assemble_task_yaml(child_id, attributes_by_id, children_by_parent_id)
+        })
+        .collect::<Vec<_>>();
+    if !children.is_empty() {
+        task_hash.insert(
+            Yaml::String(String::from("children")),
+            Yaml::Array(children),
+        );
+    }
+    Some(Yaml::Hash(task_hash))
+}
+
+impl TaskRepositoryTrait for SqliteTaskRepository {
+    fn get_project_storage_dir_name(&self) -> &str {
+        &self.project_storage_dir_name
+    }
+
+    fn get_all_projects(&self) -> Vec<&TaskHandle> {
+        self.projects
+            .iter()
+            .map(|project| &project.root_task)
+            .collect()
+    }
+
+    fn load(&mut self) -> Result<(), TaskRepositoryError> {
+        self.load_projects(false)
+    }
+
+    fn reload_if_changed(
+        &mut self,
+        now: DateTime<Local>,
+    ) -> Result<RepositoryReloadOutcome, TaskRepositoryError> {
+        let storage_revision = self.read_storage_revision()?;
+        if self.has_loaded && storage_revision == self.storage_revision.get() {
+            self.sync_clock(now).map_err(|error| {
+                TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
+            })?;
+            return Ok(RepositoryReloadOutcome::Cached);
+        }
+
+        self.last_synced_time = now;
+        let reuses_unchanged_projects = self.has_loaded;
+        self.load_projects(reuses_unchanged_projects)?;
+        Ok(RepositoryReloadOutcome::Reloaded)
+    }
+
+    fn has_pending_changes(&self) -> Result<bool, TaskTreeError> {
+        if !self.removed_project_keys.borrow().is_empty() {
+            return Ok(true);
+        }
+        self.projects
+            .iter()
+            .map(SqliteProject::needs_save)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|needs_save| needs_save.into_iter().any(|needs_save| needs_save))
+    }
+
+    // 変更したprojectの行を1 transactionで置き換える。失敗した場合はdirtyのまま残す
+    fn save(&self) -> Result<(), TaskRepositoryError> {
+        let projects_to_save = self
+            .projects
+            .iter()
+            .map(|project| project.needs_save().map(|needs_save| (project, needs_save)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| TaskRepositoryError::new(ApplicationRepositoryOperation::Save, error))?
+            .into_iter()
+            .filter_map(|(project, needs_save)| needs_save.then_some(project))
+            .collect::<Vec<_>>();
+        let removed_project_keys = self.removed_project_keys.borrow().clone();
+        if projects_to_save.is_empty() && removed_project_keys.is_empty() {
+            return Ok(());
+        }
+
+        let prepared_rows = projects_to_save
+            .iter()
+            .map(|project| self.task_rows(project))
+            .collect::<Result<Vec<_>, _>>()?;
+        let new_storage_revision = Uuid::new_v4();
+        let project_revisions = projects_to_save
+            .iter()
+            .map(|_| Uuid::new_v4())
+            .collect::<Vec<_>>();
+
+        let write = || -> Result<(), Box<dyn Error + Send + Sync>> {
+            let transaction = self.connection.unchecked_transaction()?;
+            for project_key in &removed_project_keys {
+                transaction.execute(
+                    "DELETE FROM tasks WHERE project_key = ?1",
+                    params![project_key],
+                )?;
+                transaction.execute(
+                    "DELETE FROM projects WHERE project_key = ?1",
+                    params![project_key],
+                )?;
+            }
+            // projectをまたいで移したtaskのidが重ならないよう、挿入の前に保存するprojectの行をすべて消す
+            for project in &projects_to_save {
+                transaction.execute(
+                    "DELETE FROM tasks WHERE project_key = ?1",
+                    params![project.project_key],
+                )?;
+            }
+            for ((project, rows), revision) in projects_to_save
+                .iter()
+                .zip(&prepared_rows)
+                .zip(&project_revisions)
+            {
+                transaction.execute(
+                    "INSERT INTO projects (project_key, root_task_id, priority, revision)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT(project_key) DO UPDATE SET
+                         root_task_id = excluded.root_task_id,
+                         priority = excluded.priority,
+                         revision = excluded.revision",
+                    params![
+                        project.project_key,
+                        project.root_task.get_id()?.to_string(),
+                        project.root_task.get_priority()?,
+                        revision.to_string(),
+                    ],
+                )?;
+                let mut statement = transaction.prepare_cached(
+                    "INSERT INTO tasks (id, project_key, parent_id, position, name, status, deadline_time, end_time, attributes)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                )?;
+                for row in rows {
+                    statement.execute(params![
+                        row.id,
+                        project.project_key,
+                        row.parent_id,
+                        row.position,
+                        row.name,
+                        row.status,
+                        row.deadline_time,
+                        row.end_time,
+                        row.attributes,
+                    ])?;
+                }
+            }
+            transaction.execute(
+                "INSERT INTO storage_metadata (key, value) VALUES ('revision', ?1)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![new_storage_revision.to_string()],
+            )?;
+            transaction.commit()?;
+            Ok(())
+        };
+        write().map_err(|error| self.save_error(SqliteRepositoryOperation::Write, error))?;
+        self.removed_project_keys.borrow_mut().clear();
+
+        for (project, revision) in projects_to_save.into_iter().zip(project_revisions) {
+            project.revision.set(Some(revision));
+            project.mark_clean().map_err(|error| {
+                TaskRepositoryError::new(ApplicationRepositoryOperation::Save, error)
+            })?;
+        }
+        self.storage_revision.set(Some(new_storage_revision));
+        Ok(())
+    }
+
+    fn sync_clock(&mut self, now: DateTime<Local>) -> Result<(), TaskTreeError> {
+        self.last_synced_time = now;
+        for project in &self.projects {
+            Self::sync_task_and_descendants(&project.root_task, now)?;
+        }
+        Ok(())
+    }
+
+    fn get_last_synced_time(&self) -> DateTime<Local> {
+        self.last_synced_time
+    }
+
+    fn get_highest_priority_project(&mut self) -> Option<&TaskHandle> {
+        // YAMLの保存先と同じく、副作用としてprojectsを優先度の低い順に破壊的にソートする
+        self.projects.sort_by_key(|a| a.priority);
+
+        self.projects.last().map(|project| &project.root_task)
+    }
+
+    fn get_highest_priority_leaf_task_id(&mut self) -> Result<Option<Uuid>, TaskTreeError> {
+        self.projects.sort_by_key(|a| a.priority);
+
+        select_highest_priority_leaf_task_id(self.projects.iter().map(|project| &project.root_task))
+    }
+
+    fn get_defer_candidate_leaf_task_id(
+        &mut self,
+        recent_threshold: DateTime<Local>,
+    ) -> Result<Option<Uuid>, TaskTreeError> {
+        self.projects.sort_by_key(|a| a.priority);
+
+        select_defer_candidate_leaf_task_id(
+            self.projects.iter().map(|project| &project.root_task),
+            recent_threshold,
+        )
+    }
+
+    fn get_by_id(&self, id: Uuid) -> Result<Option<TaskHandle>, TaskTreeError> {
+        let cached_task = self.id_to_task_map.borrow().get(&id).cloned();
+        if let Some(task) = cached_task {
+            // YAMLの保存先と同じく、捨てたtaskがcacheに残っていないかを確かめる
+            let root = task.root()?;
+            if self
+                .projects
+                .iter()
+                .any(|project| project.root_task.ptr_eq(&root))
+            {
+                return Ok(Some(task));
+            }
+            self.id_to_task_map.borrow_mut().remove(&id);
+        }
+
+        for project in self.projects.iter() {
+            if let Some(task) = project.root_task.get_by_id(id)? {
+                self.id_to_task_map.borrow_mut().insert(id, task.clone());
+                return Ok(Some(task));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // 期限と完了時刻はindexで引く。未保存の変更があるときや検索に失敗したときはmemory上で絞り込ませる
+    fn find_task_ids_in_period(
+        &self,
+        field: TaskPeriodField,
+        from: DateTime<Local>,
+        until: DateTime<Local>,
+    ) -> Option<HashSet<Uuid>> {
+        let column = match field {
+            TaskPeriodField::Deadline => SqliteTaskTimeColumn::Deadline,
+            TaskPeriodField::CompletedAt => SqliteTaskTimeColumn::EndTime,
+            TaskPeriodField::ScheduledStart | TaskPeriodField::CreatedAt => return None,
+        };
+        if self.has_pending_changes().unwrap_or(true) {
+            return None;
+        }
+        self.find_saved_task_ids_between(column, from, until)
+            .ok()
+            .map(|task_ids| task_ids.into_iter().collect())
+    }
+
+    fn start_new_project(&mut self, root_task: TaskHandle) -> Result<(), TaskTreeError> {
+        let project_key = new_project_key(&root_task, self.last_synced_time)?;
+        self.import_project(&project_key, root_task)
+    }
+
+    fn remove_project(&mut self, root_task_id: Uuid) -> Result<Option<String>, TaskTreeError> {
+        let Some(index) = self
+            .projects
+            .iter()
+            .map(|project| project.root_task.get_id())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .position(|id| id == root_task_id)
+        else {
+            return Ok(None);
+        };
+        let project = self.projects.remove(index);
+        self.removed_project_keys
+            .borrow_mut()
+            .push(project.project_key.clone());
+        Ok(Some(project.project_key))
+    }
+
+    fn restore_project(
+        &mut self,
+        project_key: &str,
+        root_task: TaskHandle,
+    ) -> Result<String, TaskTreeError> {
+        let used_keys = self
+            .projects
+            .iter()
+            .map(|project| project.project_key.clone())
+            .collect::<Vec<_>>();
+        let project_key = available_project_key(project_key, &used_keys);
+        self.removed_project_keys
+            .borrow_mut()
+            .retain(|removed_key| *removed_key != project_key);
+        self.import_project(&project_key, root_task)?;
+        Ok(project_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::subtree::move_subtree;
+    use crate::application::task_use_case::{list_tasks, ListTasksFilter, TaskPeriodFilter};
+    use crate::entity::datetime::BusinessDateTimePolicy;
+    use crate::entity::task::{Status, TaskAttr};
+    use crate::test_support::{BusyIntervals, TestStorageDir};
+    use chrono::TimeZone;
+
+    fn open_repository(storage_dir: &TestStorageDir, now: DateTime<Local>) -> SqliteTaskRepository {
+        let mut repository = SqliteTaskRepository::open(storage_dir.path_str()).unwrap();
+        repository.sync_clock(now).unwrap();
+        repository
+    }
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap()
+    }
+
+    fn task(name: &str) -> TaskHandle {
+        TaskHandle::with_identity(name, Uuid::new_v4(), now()).unwrap()
+    }
+
+    fn attr(name: &str) -> TaskAttr {
+        TaskAttr::with_identity(name, Uuid::new_v4(), now())
+    }
+
+    fn project_with_children() -> TaskHandle {
+        let root = task("プロジェクト");
+        root.set_priority(3).unwrap();
+        let first = root.create_child(attr("最初")).unwrap();
+        first
+            .set_deadline_time_opt(Some(
+                Local.with_ymd_and_hms(2026, 10, 20, 12, 0, 0).unwrap(),
+            ))
+            .unwrap();
+        first.create_child(attr("孫")).unwrap();
+        let second = root.create_child(attr("次")).unwrap();
+        second.set_orig_status(Status::Done).unwrap();
+        second
+            .set_end_time_opt(Some(
+                Local.with_ymd_and_hms(2026, 10, 17, 18, 0, 0).unwrap(),
+            ))
+            .unwrap();
+        root
+    }
+
+    #[test]
+    fn sqliteへ保存したtask木を別connectionで同じyamlとして読み直せる() {
+        let storage_dir = TestStorageDir::new("sqlite-repository");
+        let root = project_with_children();
+        let expected = task_to_yaml(&root).unwrap();
+        let mut repository = open_repository(&storage_dir, now());
+        repository.start_new_project(root).unwrap();
+        repository.save().unwrap();
+
+        let mut reloaded = open_repository(&storage_dir, now());
+        reloaded.load().unwrap();
+
+        let projects = reloaded.get_all_projects();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(task_to_yaml(projects[0]).unwrap(), expected);
+        assert_eq!(
+            reloaded.project_entries()[0].0,
+            "20261018-プロジェクト".to_string()
+        );
+        assert!(!reloaded.has_pending_changes().unwrap());
+    }
+
+    #[test]
+    fn sqliteの期限と完了時刻の期間検索は保存済みtaskをindexから半開区間で返す() {
+        let storage_dir = TestStorageDir::new("sqlite-repository");
+        let root = project_with_children();
+        let children = root.get_children().unwrap();
+        let mut repository = open_repository(&storage_dir, now());
+        repository.start_new_project(root.clone()).unwrap();
+        repository.save().unwrap();
+
+        let deadline_ids = repository.find_task_ids_in_period(
+            TaskPeriodField::Deadline,
+            Local.with_ymd_and_hms(2026, 10, 20, 0, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2026, 10, 21, 0, 0, 0).unwrap(),
+        );
+        let end_time_ids = repository.find_task_ids_in_period(
+            TaskPeriodField::CompletedAt,
+            Local.with_ymd_and_hms(2026, 10, 17, 0, 0, 0).unwrap(),
+            Local.with_ymd_and_hms(2026, 10, 17, 18, 0, 0).unwrap(),
+        );
+        let listed = list_tasks(
+            &repository,
+            &mut BusyIntervals(vec![]),
+            &BusinessDateTimePolicy::new(30),
+            ListTasksFilter {
+                period: Some(TaskPeriodFilter {
+                    field: TaskPeriodField::Deadline,
+                    from: Local.with_ymd_and_hms(2026, 10, 20, 0, 0, 0).unwrap(),
+                    until: Local.with_ymd_and_hms(2026, 10, 21, 0, 0, 0).unwrap(),
+                }),
+                statuses: vec![],
+                categories: vec![],
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            deadline_ids,
+            Some(HashSet::from([children[0].get_id().unwrap()]))
+        );
+        assert_eq!(end_time_ids, Some(HashSet::new()));
+        assert_eq!(
+            listed.iter().map(|task| task.id).collect::<Vec<_>>(),
+            vec![children[0].get_id().unwrap()]
+        );
+    }
+
+    #[test]
+    fn sqliteの時刻列はutcで持ちlocal_timeの古い列は開くときに書き直す() {
+        let storage_dir = TestStorageDir::new("sqlite-repository");
+        let root = project_with_children();
+        let first_id = root.get_children().unwrap()[0].get_id().unwrap();
+        let deadline = Local.with_ymd_and_hms(2026, 10, 20, 12, 0, 0).unwrap();
+        let deadline_column = |repository: &SqliteTaskRepository| {
+            repository
+                .connection
+                .query_row(
+                    "SELECT deadline_time FROM tasks WHERE id = ?1",
+                    params![first_id.to_string()],
+                    |row| row.get::<_, String>(0),
+                )
+                .unwrap()
+        };
+        let mut repository = open_repository(&storage_dir, now());
+        repository.start_new_project(root).unwrap();
+        repository.save().unwrap();
+        assert_eq!(
+            deadline_column(&repository),
+            deadline
+                .with_timezone(&Utc)
+                .format("%Y-%m-%dT%H:%M:%SZ")
+                .to_string()
+        );
+
+        // UTCへ移す前に保存したdatabaseを再現する
+        repository
+            .connection
+            .execute_batch(
+                "DELETE FROM storage_metadata WHERE key = 'time_zone';
+                 UPDATE tasks SET deadline_time = '2026-10-20 12:00:00'
+                 WHERE deadline_time IS NOT NULL;
+                 UPDATE tasks SET end_time = '2026-10-17 18:00:00' WHERE end_time IS NOT NULL;",
+            )
+            .unwrap();
+        drop(repository);
+
+        let reopened = open_repository(&storage_dir, now());
+        assert_eq!(deadline_column(&reopened), format_time(deadline));
+        assert_eq!(
+            reopened.find_task_ids_in_period(
+                TaskPeriodField::Deadline,
+                Local.with_ymd_and_hms(2026, 10, 20, 12, 0, 0).unwrap(),
+                Local.with_ymd_and_hms(2026, 10, 20, 12, 0, 1).unwrap(),
+            ),
+            Some(HashSet::from([first_id]))
+        );
+        assert_eq!(
+            reopened
+                .find_task_ids_in_period(
+                    TaskPeriodField::CompletedAt,
+                    Local.with_ymd_and_hms(2026, 10, 17, 18, 0, 0).unwrap(),
+                    Local.with_ymd_and_hms(2026, 10, 17, 18, 0, 1).unwrap(),
+                )
+                .map(|task_ids| task_ids.len()),
+            Some(1)
+        );
+
+        // 移行済みの列は開き直しても変わらない
+        drop(reopened);
+        let reopened = open_repository(&storage_dir, now());
+        assert_eq!(deadline_column(&reopened), format_time(deadline));
+    }
+
+    #[test]
+    fn sqliteで未保存の変更があれば期間検索をmemory上の絞り込みに任せる() {
+        let storage_dir = TestStorageDir::new("sqlite-repository");
+        let root = project_with_children();
+        let mut repository = open_repository(&storage_dir, now());
+        repository.start_new_project(root.clone()).unwrap();
+        repository.save().unwrap();
+        root.set_deadline_time_opt(Some(
+            Local.with_ymd_and_hms(2026, 10, 25, 12, 0, 0).unwrap(),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            repository.find_task_ids_in_period(
+                TaskPeriodField::Deadline,
+                Local.with_ymd_and_hms(2026, 10, 25, 0, 0, 0).unwrap(),
+                Local.with_ymd_and_hms(2026, 10, 26, 0, 0, 0).unwrap(),
+            ),
+            None
+        );
+        assert_eq!(
+            repository.find_task_ids_in_period(
+                TaskPeriodField::CreatedAt,
+                now(),
+                now() + chrono::Duration::days(1),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn sqliteで取り除いたprojectは保存時に行ごと消え同じkeyへ戻せる() {
+        let storage_dir = TestStorageDir::new("sqlite-repository");
+        let root = project_with_children();
+        let root_id = root.get_id().unwrap();
+        let mut repository = open_repository(&storage_dir, now());
+        repository.start_new_project(root.clone()).unwrap();
+        repository.save().unwrap();
+        let project_key = repository.project_entries()[0].0.clone();
+
+        assert_eq!(
+            repository.remove_project(root_id).unwrap(),
+            Some(project_key.clone())
+        );
+        repository.save().unwrap();
+        let mut other = open_repository(&storage_dir, now());
+        other.load().unwrap();
+        assert!(other.get_all_projects().is_empty());
+
+        assert_eq!(
+            repository.restore_project(&project_key, root).unwrap(),
+            project_key
+        );
+        repository.save().unwrap();
+        other.load().unwrap();
+        assert!(other.get_by_id(root_id).unwrap().is_some());
+    }
+
+    #[test]
+    fn sqliteのreloadは他connectionが保存したprojectだけを読み直す() {
+        let storage_dir = TestStorageDir::new("sqlite-repository");
+        let mut repository = open_repository(&storage_dir, now());
+        repository.start_new_project(task("a")).unwrap();
+        repository.start_new_project(task("b")).unwrap();
+        repository.save().unwrap();
+        repository.load().unwrap();
+        let kept_root = repository.get_all_projects()[0].clone();
+        let kept_root_id = kept_root.get_id().unwrap();
+
+        let mut other = open_repository(&storage_dir, now());
+        other.load().unwrap();
+        other.get_all_projects()[1]
+            .create_child(attr("追加"))
+            .unwrap();
+        other.save().unwrap();
+
+        assert_eq!(
+            repository.reload_if_changed(now()).unwrap(),
+            RepositoryReloadOutcome::Reloaded
+        );
+
+        // 使い続けたtask treeは再読込前のhandleと同じnodeを共有する
+        repository
+            .get_by_id(kept_root_id)
+            .unwrap()
+            .unwrap()
+            .set_estimated_work_seconds(20 * 60)
+            .unwrap();
+        assert_eq!(kept_root.get_estimated_work_seconds().unwrap(), 20 * 60);
+        assert_eq!(
+            repository.get_all_projects()[1]
+                .get_children()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            repository.reload_if_changed(now()).unwrap(),
+            RepositoryReloadOutcome::Cached
+        );
+    }
+
+    #[test]
+    fn sqliteでprojectをまたいで移した部分木を保存して読み直せる() {
+        let storage_dir = TestStorageDir::new("sqlite-repository");
+        let destination = task("移動先");
+        let source = project_with_children();
+        let moved_id = source.get_children().unwrap()[0].get_id().unwrap();
+        let destination_id = destination.get_id().unwrap();
+        let mut repository = open_repository(&storage_dir, now());
+        repository.start_new_project(destination).unwrap();
+        repository.start_new_project(source).unwrap();
+        repository.save().unwrap();
+
+        move_subtree(&mut repository, moved_id, Some(destination_id)).unwrap();
+        repository.save().unwrap();
+
+        let mut reloaded = open_repository(&storage_dir, now());
+        reloaded.load().unwrap();
+        let moved = reloaded.get_by_id(moved_id).unwrap().unwrap();
+        assert_eq!(
+            moved.parent().unwrap().unwrap().get_id().unwrap(),
+            destination_id
+        );
+        assert_eq!(moved.get_children().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn sqliteの未変更projectは保存せずrevisionも進めない() {
+        let storage_dir = TestStorageDir::new("sqlite-repository");
+        let mut repository = open_repository(&storage_dir, now());
+        repository.start_new_project(task("a")).unwrap();
+        repository.save().unwrap();
+        let revision = repository.read_storage_revision().unwrap();
+
+        repository.save().unwrap();
+
+        assert!(revision.is_some());
+        assert_eq!(repository.read_storage_revision().unwrap(), revision);
+    }
+
+    #[test]
+    fn sqliteで親から辿れない行はparse_errorにする() {
+        let storage_dir = TestStorageDir::new("sqlite-repository");
+        let mut repository = open_repository(&storage_dir, now());
+        repository
+            .start_new_project(project_with_children())
+            .unwrap();
+        repository.save().unwrap();
+        repository
+            .connection
+            .execute(
+                "UPDATE tasks SET parent_id = ?1 WHERE name = '孫'",
+                params![Uuid::new_v4().to_string()],
+            )
+            .unwrap();
+
+        let error = open_repository(&storage_dir, now()).load().unwrap_err();
+
+        assert_eq!(error.operation(), ApplicationRepositoryOperation::Load);
+        assert!(error.to_string().contains("not reachable"));
+    }
+}
@@ -0,0 +1,312 @@
+use crate::adapter::gateway::task_repository::write_file_atomically;
+use crate::application::consistency::{ConsistencyCode, ConsistencyFinding};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+const STORAGE_REVISION_FILE_NAME: &str = ".revision";
+// 保存は.revisionを書いてからproject.yamlを書くため、この幅までの遅れは同じ保存とみなす
+const STORAGE_REVISION_TOLERANCE: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+pub struct StorageConsistencyError {
+    path: PathBuf,
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl StorageConsistencyError {
+    fn new(
+        path: impl Into<PathBuf>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            source: source.into(),
+        }
+    }
+}
+
+impl fmt::Display for StorageConsistencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to inspect storage at {}: {}",
+            self.path.display(),
+            self.source
+        )
+    }
+}
+
+impl std::error::Error for StorageConsistencyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+// YAML保存先のfileの食い違いを検査する。fixなら、fileを含まないorphan directoryを消し、
+// 古い.revisionを新しい版で置き換えて、常駐processに次の操作で読み直させる
+pub fn check_storage_consistency(
+    storage_directory: &Path,
+    fix: bool,
+) -> Result<Vec<ConsistencyFinding>, StorageConsistencyError> {
+    let mut findings = vec![];
+    let mut orphan_directories = vec![];
+    collect_orphan_directories(storage_directory, &mut orphan_directories)?;
+    orphan_directories.sort();
+    for directory in orphan_directories {
+        let fixable = !contains_file(&directory)?;
+        if fix && fixable {
+            fs::remove_dir_all(&directory)
+                .map_err(|error| StorageConsistencyError::new(&directory, error))?;
+        }
+        findings.push(storage_finding(
+            ConsistencyCode::OrphanDirectory,
+            storage_directory,
+            &directory,
+            fixable,
+            fix,
+        ));
+    }
+
+    let revision_path = storage_directory.join(STORAGE_REVISION_FILE_NAME);
+    if is_storage_revision_stale(storage_directory, &revision_path)? {
+        if fix {
+            write_file_atomically(&revision_path, format!("{}\n", Uuid::new_v4()).as_bytes())
+                .map_err(|error| StorageConsistencyError::new(&revision_path, error))?;
+        }
+        findings.push(storage_finding(
+            ConsistencyCode::StaleStorageRevision,
+            storage_directory,
+            &revision_path,
+            true,
+            fix,
+        ));
+    }
+    Ok(findings)
+}
+
+fn storage_finding(
+    code: ConsistencyCode,
+    storage_directory: &Path,
+    path: &Path,
+    fixable: bool,
+    fix: bool,
+) -> ConsistencyFinding {
+    let relative_path = path.strip_prefix(storage_directory).unwrap_or(path);
+    ConsistencyFinding {
+        code,
+        location: relative_path.to_string_lossy().into_owned(),
+        task_id: None,
+        fixable,
+        fixed: fixable && fix,
+    }
+}
+
+fn sorted_entries(directory: &Path) -> Result<Vec<fs::DirEntry>, StorageConsistencyError> {
+    let mut entries = fs::read_dir(directory)
+        .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+        .map_err(|error| StorageConsistencyError::new(directory, error))?;
+    entries.sort_by_key(|entry| entry.file_name());
+    Ok(entries)
+}
+
+// directoryより下にproject.yamlがあるかを返し、project.yamlを1つも含まないdirectoryのうち
+// 最も上のものをorphansへ足す。.historyなどの隠しdirectoryとproject内のmarkdownなどは見ない
+fn collect_orphan_directories(
+    directory: &Path,
+    orphans: &mut Vec<PathBuf>,
+) -> Result<bool, StorageConsistencyError> {
+    let mut subdirectories = vec![];
+    for entry in sorted_entries(directory)? {
+        let file_type = entry
+            .file_type()
+            .map_err(|error| StorageConsistencyError::new(entry.path(), error))?;
+        let file_name = entry.file_name();
+        if file_name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if file_type.is_file() && file_name == "project.yaml" {
+            return Ok(true);
+        }
+        if file_type.is_dir() {
+            subdirectories.push(entry.path());
+        }
+    }
+
+    let mut contains_project = false;
+    let mut empty_subdirectories = vec![];
+    for subdirectory in subdirectories {
+        if collect_orphan_directories(&subdirectory, orphans)? {
+            contains_project = true;
+        } else {
+            empty_subdirectories.push(subdirectory);
+        }
+    }
+    if contains_project {
+        orphans.extend(empty_subdirectories);
+    }
+    Ok(contains_project)
+}
+
+fn contains_file(directory: &Path) -> Result<bool, StorageConsistencyError> {
+    for entry in sorted_entries(directory)? {
+        let file_type = entry
+            .file_type()
+            .map_err(|error| StorageConsistencyError::new(entry.path(), error))?;
+        if !file_type.is_dir() || contains_file(&entry.path())? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// .revisionがないのにprojectがある、またはproject.yamlが.revisionより後に直接書き換えられている
+fn is_storage_revision_stale(
+    storage_directory: &Path,
+    revision_path: &Path,
+) -> Result<bool, StorageConsistencyError> {
+    let revision_modified = match fs::symlink_metadata(revision_path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => return Ok(false),
+        Ok(metadata) => Some(
+            metadata
+                .modified()
+                .map_err(|error| StorageConsistencyError::new(revision_path, error))?,
+        ),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+        Err(error) => return Err(StorageConsistencyError::new(revision_path, error)),
+    };
+    for entry in WalkDir::new(storage_directory) {
+        let entry = entry.map_err(|error| {
+            let path = error
+                .path()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| storage_directory.to_path_buf());
+            StorageConsistencyError::new(path, error)
+        })?;
+        if entry.file_name() != "project.yaml" || !entry.file_type().is_file() {
+            continue;
+        }
+        let Some(revision_modified) = revision_modified else {
+            return Ok(true);
+        };
+        let project_modified = fs::metadata(entry.path())
+            .and_then(|metadata| metadata.modified())
+            .map_err(|error| StorageConsistencyError::new(entry.path(), error))?;
+        if project_modified
+            .duration_since(revision_modified)
+            .is_ok_and(|delay| delay > STORAGE_REVISION_TOLERANCE)
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestStorageDir;
+    use std::fs::File;
+    use std::time::SystemTime;
+
+    fn write_storage_file(storage: &TestStorageDir, relative_path: &str, text: &str) {
+        let path = storage.path.join(relative_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    fn codes(findings: &[ConsistencyFinding]) -> Vec<(&'static str, &str, bool, bool)> {
+        findings
+            .iter()
+            .map(|finding| {
+                (
+                    finding.code.as_str(),
+                    finding.location.as_str(),
+                    finding.fixable,
+                    finding.fixed,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn projectを含まないdirectoryを報告しfileのないものだけを消す() {
+        let storage = TestStorageDir::new("consistency");
+        write_storage_file(
+            &storage,
+            ".revision",
+            "00000000-0000-0000-0000-000000000001\n",
+        );
+        write_storage_file(&storage, "20261019-旅行/project.yaml", "name: 旅行\n");
+        fs::create_dir_all(storage.path.join("20261019-旅行/markdown")).unwrap();
+        write_storage_file(&storage, "20261001-消した/markdown/memo.md", "memo");
+        fs::create_dir_all(storage.path.join("20261002-空/markdown")).unwrap();
+        fs::create_dir_all(storage.path.join(".history/blobs")).unwrap();
+
+        let findings = check_storage_consistency(&storage.path, false).unwrap();
+        assert_eq!(
+            codes(&findings),
+            [
+                ("orphan_directory", "20261001-消した", false, false),
+                ("orphan_directory", "20261002-空", true, false),
+            ]
+        );
+
+        let fixed = check_storage_consistency(&storage.path, true).unwrap();
+        assert_eq!(
+            codes(&fixed),
+            [
+                ("orphan_directory", "20261001-消した", false, false),
+                ("orphan_directory", "20261002-空", true, true),
+            ]
+        );
+        assert!(!storage.path.join("20261002-空").exists());
+        assert!(storage
+            .path
+            .join("20261001-消した/markdown/memo.md")
+            .exists());
+        assert!(storage.path.join("20261019-旅行/markdown").exists());
+    }
+
+    #[test]
+    fn revisionより後に直接書き換えたprojectがあればrevisionを新しくする() {
+        let storage = TestStorageDir::new("consistency");
+        write_storage_file(
+            &storage,
+            "nested/20261019-旅行/project.yaml",
+            "name: 旅行\n",
+        );
+        assert_eq!(
+            codes(&check_storage_consistency(&storage.path, false).unwrap()),
+            [("stale_storage_revision", ".revision", true, false)]
+        );
+
+        let old_revision = "00000000-0000-0000-0000-000000000001\n";
+        write_storage_file(&storage, ".revision", old_revision);
+        assert!(check_storage_consistency(&storage.path, false)
+            .unwrap()
+            .is_empty());
+        File::options()
+            .write(true)
+            .open(storage.path.join(".revision"))
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+
+        let fixed = check_storage_consistency(&storage.path, true).unwrap();
+        assert_eq!(
+            codes(&fixed),
+            [("stale_storage_revision", ".revision", true, true)]
+        );
+        let revision = fs::read_to_string(storage.path.join(".revision")).unwrap();
+        assert_ne!(revision, old_revision);
+        assert!(Uuid::parse_str(revision.trim()).is_ok());
+        assert!(check_storage_consistency(&storage.path, false)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,696 @@
+use crate::adapter::gateway::task_repository::write_file_atomically;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+// 保存先直下に置き、保存先が暗号化されていることと鍵の導出方法を示す
+pub const STORAGE_ENCRYPTION_FILE_NAME: &str = ".encryption";
+// 暗号化した保存先のpassphraseを渡す環境変数。設定fileには書かせない
+pub const STORAGE_PASSPHRASE_ENV: &str = "SCHRONU_STORAGE_PASSPHRASE";
+
+const ENCRYPTION_HEADER_VERSION: u32 = 1;
+// 暗号化したfileの先頭。続けてnonceと認証tag付きの暗号文を置く
+const ENCRYPTED_FILE_MAGIC: &[u8] = b"SCHRONU-ENC1\n";
+const NONCE_LENGTH: usize = 24;
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+// 鍵が正しいかをproject fileを読む前に確かめるため、.encryptionに暗号化して残す平文
+const KEY_CHECK_PLAINTEXT: &[u8] = b"schronu storage key check";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageKeyKind {
+    Passphrase,
+    KeyFile,
+}
+
+#[derive(Debug)]
+pub enum StorageEncryptionError {
+    // 必要な種類の鍵が設定されていない。Noneは暗号化を始めるのにどちらも設定されていない場合
+    MissingKey {
+        storage_directory: PathBuf,
+        expected: Option<StorageKeyKind>,
+    },
+    WrongKey {
+        storage_directory: PathBuf,
+    },
+    InvalidKeyFile {
+        path: PathBuf,
+        reason: String,
+    },
+    InvalidHeader {
+        path: PathBuf,
+        reason: String,
+    },
+    AlreadyEncrypted {
+        storage_directory: PathBuf,
+    },
+    NotEncrypted {
+        storage_directory: PathBuf,
+    },
+    // 保存先は暗号化されているのに、平文のfileが残っている
+    UnencryptedFile,
+    // 鍵を渡されていないのに、暗号化されたfileがある
+    EncryptedFileWithoutKey,
+    // 鍵は確かめ済みなので、復号できないのはfileが壊れている
+    CorruptedFile,
+    // SQLite保存先は暗号化できないのに、.encryptionか鍵が設定されている
+    SqliteBackendEncrypted {
+        storage_directory: PathBuf,
+    },
+    // 暗号化した保存先のtaskを、保存先の外へ平文で書き出そうとした
+    PlaintextOutsideStorage {
+        path: PathBuf,
+    },
+    KeyDerivation(String),
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl fmt::Display for StorageEncryptionError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingKey {
+                storage_directory,
+                expected: Some(StorageKeyKind::Passphrase),
+            } => write!(
+                formatter,
+                "storage {} is encrypted with a passphrase; set {STORAGE_PASSPHRASE_ENV}",
+                storage_directory.display()
+            ),
+            Self::MissingKey {
+                storage_directory,
+                expected: Some(StorageKeyKind::KeyFile),
+            } => write!(
+                formatter,
+                "storage {} is encrypted with a key file; set storage_encryption_key_file_path",
+                storage_directory.display()
+            ),
+            Self::MissingKey {
+                storage_directory,
+                expected: None,
+            } => write!(
+                formatter,
+                "no encryption key for storage {}; set storage_encryption_key_file_path or {STORAGE_PASSPHRASE_ENV}",
+                storage_directory.display()
+            ),
+            Self::WrongKey { storage_directory } => write!(
+                formatter,
+                "wrong encryption key for storage {}; the passphrase or key file does not match",
+                storage_directory.display()
+            ),
+            Self::InvalidKeyFile { path, reason } => {
+                write!(formatter, "invalid key file {}: {reason}", path.display())
+            }
+            Self::InvalidHeader { path, reason } => {
+                write!(
+                    formatter,
+                    "invalid encryption header {}: {reason}",
+                    path.display()
+                )
+            }
+            Self::AlreadyEncrypted { storage_directory } => write!(
+                formatter,
+                "storage {} is already encrypted",
+                storage_directory.display()
+            ),
+            Self::NotEncrypted { storage_directory } => write!(
+                formatter,
+                "storage {} is not encrypted",
+                storage_directory.display()
+            ),
+            Self::UnencryptedFile => write!(
+                formatter,
+                "file is not encrypted although the storage is; run schronu-migrate to-encrypted"
+            ),
+            Self::EncryptedFileWithoutKey => {
+                write!(formatter, "file is encrypted but no encryption key was given")
+            }
+            Self::CorruptedFile => write!(formatter, "encrypted file is corrupted"),
+            Self::SqliteBackendEncrypted { storage_directory } => write!(
+                formatter,
+                "storage {} is configured for encryption, but storage_backend: sqlite cannot be encrypted; use storage_backend: yaml, or remove {STORAGE_ENCRYPTION_FILE_NAME}, storage_encryption_key_file_path and {STORAGE_PASSPHRASE_ENV}",
+                storage_directory.display()
+            ),
+            Self::PlaintextOutsideStorage { path } => write!(
+                formatter,
+                "storage is encrypted; refusing to write tasks in plaintext to {}",
+                path.display()
+            ),
+            Self::KeyDerivation(reason) => write!(formatter, "key derivation failed: {reason}"),
+            Self::Io { path, source } => write!(formatter, "{}: {source}", path.display()),
+        }
+    }
+}
+
+impl Error for StorageEncryptionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// 設定のkey file pathと、環境変数から受け取ったpassphrase。どちらを使うかは保存先の.encryptionで決まる
+#[derive(Clone, Default)]
+pub struct StorageKeySource {
+    key_file_path: Option<PathBuf>,
+    passphrase: Option<String>,
+}
+
+impl StorageKeySource {
+    pub fn new(key_file_path: Option<PathBuf>, passphrase: Option<String>) -> Self {
+        Self {
+            key_file_path,
+            passphrase: passphrase.filter(|passphrase| !passphrase.is_empty()),
+        }
+    }
+
+    // 設定のkey file pathに、SCHRONU_STORAGE_PASSPHRASEのpassphraseを添える
+    pub fn from_env(key_file_path: Option<PathBuf>) -> Self {
+        Self::new(key_file_path, std::env::var(STORAGE_PASSPHRASE_ENV).ok())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "key", rename_all = "snake_case")]
+enum KeyDerivation {
+    Passphrase {
+        salt: String,
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+    },
+    KeyFile,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptionHeader {
+    version: u32,
+    #[serde(flatten)]
+    derivation: KeyDerivation,
+    key_check: String,
+}
+
+// 確かめ済みの鍵で、project fileと保存履歴をXChaCha20-Poly1305で暗号化・復号する
+#[derive(Clone)]
+pub struct StorageCipher {
+    key: Key,
+}
+
+impl fmt::Debug for StorageCipher {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("StorageCipher")
+            .finish_non_exhaustive()
+    }
+}
+
+impl StorageCipher {
+    fn new(key: [u8; KEY_LENGTH]) -> Self {
+        Self { key: key.into() }
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = XChaCha20Poly1305::new(&self.key)
+            .encrypt(&nonce, plaintext)
+            .expect("XChaCha20-Poly1305 encrypts any in-memory plaintext");
+        let mut bytes =
+            Vec::with_capacity(ENCRYPTED_FILE_MAGIC.len() + NONCE_LENGTH + ciphertext.len());
+        bytes.extend_from_slice(ENCRYPTED_FILE_MAGIC);
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+        bytes
+    }
+
+    pub fn decrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, StorageEncryptionError> {
+        let body = bytes
+            .strip_prefix(ENCRYPTED_FILE_MAGIC)
+            .ok_or(StorageEncryptionError::UnencryptedFile)?;
+        if body.len() < NONCE_LENGTH {
+            return Err(StorageEncryptionError::CorruptedFile);
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_LENGTH);
+        XChaCha20Poly1305::new(&self.key)
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| StorageEncryptionError::CorruptedFile)
+    }
+}
+
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(ENCRYPTED_FILE_MAGIC)
+}
+
+// 保存先の暗号化の有無に合わせてfileの内容を平文に戻す。両者が食い違えばerrorにする
+pub fn decode_storage_file(
+    cipher: Option<&StorageCipher>,
+    bytes: &[u8],
+) -> Result<Vec<u8>, StorageEncryptionError> {
+    match cipher {
+        Some(cipher) => cipher.decrypt(bytes),
+        None if is_encrypted(bytes) => Err(StorageEncryptionError::EncryptedFileWithoutKey),
+        None => Ok(bytes.to_vec()),
+    }
+}
+
+pub fn encode_storage_file(cipher: Option<&StorageCipher>, bytes: Vec<u8>) -> Vec<u8> {
+    match cipher {
+        Some(cipher) => cipher.encrypt(&bytes),
+        None => bytes,
+    }
+}
+
+// 書き出しやnoteのように保存先の外へtaskを平文で書く前に呼ぶ。暗号化した保存先では書かせない
+pub fn ensure_plaintext_output_allowed(
+    cipher: Option<&StorageCipher>,
+    path: &Path,
+) -> Result<(), StorageEncryptionError> {
+    match cipher {
+        Some(_) => Err(StorageEncryptionError::PlaintextOutsideStorage {
+            path: path.to_path_buf(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn header_path(storage_directory: &Path) -> PathBuf {
+    storage_directory.join(STORAGE_ENCRYPTION_FILE_NAME)
+}
+
+fn read_header(
+    storage_directory: &Path,
+) -> Result<Option<EncryptionHeader>, StorageEncryptionError> {
+    let path = header_path(storage_directory);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(StorageEncryptionError::Io {
+                path,
+                source: error,
+            })
+        }
+    };
+    let header: EncryptionHeader =
+        serde_json::from_str(&contents).map_err(|error| StorageEncryptionError::InvalidHeader {
+            path: path.clone(),
+            reason: error.to_string(),
+        })?;
+    if header.version != ENCRYPTION_HEADER_VERSION {
+        return Err(StorageEncryptionError::InvalidHeader {
+            path,
+            reason: format!("unsupported version {}", header.version),
+        });
+    }
+    Ok(Some(header))
+}
+
+fn write_header(
+    storage_directory: &Path,
+    header: &EncryptionHeader,
+) -> Result<(), StorageEncryptionError> {
+    let path = header_path(storage_directory);
+    let mut contents = serde_json::to_string_pretty(header).map_err(|error| {
+        StorageEncryptionError::InvalidHeader {
+            path: path.clone(),
+            reason: error.to_string(),
+        }
+    })?;
+    contents.push('\n');
+    write_file_atomically(&path, contents.as_bytes())
+        .map(|_| ())
+        .map_err(|error| StorageEncryptionError::Io {
+            path,
+            source: std::io::Error::other(error),
+        })
+}
+
+// key fileは32byteの鍵そのものか、それを16進で書いた64文字とする
+fn read_key_file(path: &Path) -> Result<[u8; KEY_LENGTH], StorageEncryptionError> {
+    let bytes = fs::read(path).map_err(|error| StorageEncryptionError::Io {
+        path: path.to_path_buf(),
+        source: error,
+    })?;
+    if let Ok(key) = <[u8; KEY_LENGTH]>::try_from(bytes.as_slice()) {
+        return Ok(key);
+    }
+    std::str::from_utf8(&bytes)
+        .ok()
+        .and_then(|text| decode_hex(text.trim()))
+        .and_then(|key| <[u8; KEY_LENGTH]>::try_from(key.as_slice()).ok())
+        .ok_or_else(|| StorageEncryptionError::InvalidKeyFile {
+            path: path.to_path_buf(),
+            reason: format!(
+                "expected {KEY_LENGTH} raw bytes or {} hex digits",
+                KEY_LENGTH * 2
+            ),
+        })
+}
+
+fn derive_passphrase_key(
+    passphrase: &str,
+    salt: &[u8],
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+) -> Result<[u8; KEY_LENGTH], StorageEncryptionError> {
+    let params = Params::new(memory_kib, iterations, parallelism, Some(KEY_LENGTH))
+        .map_err(|error| StorageEncryptionError::KeyDerivation(error.to_string()))?;
+    let mut key = [0; KEY_LENGTH];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|error| StorageEncryptionError::KeyDerivation(error.to_string()))?;
+    Ok(key)
+}
+
+fn derive_key(
+    storage_directory: &Path,
+    derivation: &KeyDerivation,
+    key_source: &StorageKeySource,
+) -> Result<[u8; KEY_LENGTH], StorageEncryptionError> {
+    match derivation {
+        KeyDerivation::Passphrase {
+            salt,
+            memory_kib,
+            iterations,
+            parallelism,
+        } => {
+            let passphrase = key_source.passphrase.as_deref().ok_or_else(|| {
+                StorageEncryptionError::MissingKey {
+                    storage_directory: storage_directory.to_path_buf(),
+                    expected: Some(StorageKeyKind::Passphrase),
+                }
+            })?;
+            let salt = decode_hex(salt).ok_or_else(|| StorageEncryptionError::InvalidHeader {
+                path: header_path(storage_directory),
+                reason: "salt must be hex".to_string(),
+            })?;
+            derive_passphrase_key(passphrase, &salt, *memory_kib, *iterations, *parallelism)
+        }
+        KeyDerivation::KeyFile => {
+            let path = key_source.key_file_path.as_deref().ok_or_else(|| {
+                StorageEncryptionError::MissingKey {
+                    storage_directory: storage_directory.to_path_buf(),
+                    expected: Some(StorageKeyKind::KeyFile),
+                }
+            })?;
+            read_key_file(path)
+        }
+    }
+}
+
+// 保存先が暗号化されていれば、鍵を導出して.encryptionの確認用暗号文で確かめる。平文ならNone
+pub fn open_storage_cipher(
+    storage_directory: &Path,
+    key_source: &StorageKeySource,
+) -> Result<Option<StorageCipher>, StorageEncryptionError> {
+    let Some(header) = read_header(storage_directory)? else {
+        return Ok(None);
+    };
+    let cipher = StorageCipher::new(derive_key(
+        storage_directory,
+        &header.derivation,
+        key_source,
+    )?);
+    let key_check =
+        decode_hex(&header.key_check).ok_or_else(|| StorageEncryptionError::InvalidHeader {
+            path: header_path(storage_directory),
+            reason: "key_check must be hex".to_string(),
+        })?;
+    match cipher.decrypt(&key_check) {
+        Ok(plaintext) if plaintext == KEY_CHECK_PLAINTEXT => Ok(Some(cipher)),
+        _ => Err(StorageEncryptionError::WrongKey {
+            storage_directory: storage_directory.to_path_buf(),
+        }),
+    }
+}
+
+// SQLite保存先を開く前に呼ぶ。暗号化を求める設定を黙って無視し、平文で書かないようにする
+pub fn ensure_unencrypted_storage(
+    storage_directory: &Path,
+    key_source: &StorageKeySource,
+) -> Result<(), StorageEncryptionError> {
+    if header_path(storage_directory).exists()
+        || key_source.key_file_path.is_some()
+        || key_source.passphrase.is_some()
+    {
+        return Err(StorageEncryptionError::SqliteBackendEncrypted {
+            storage_directory: storage_directory.to_path_buf(),
+        });
+    }
+    Ok(())
+}
+
+// 平文の保存先に.encryptionを作る。key fileが設定されていればそれを、なければpassphraseを使う
+pub fn initialize_storage_encryption(
+    storage_directory: &Path,
+    key_source: &StorageKeySource,
+) -> Result<StorageCipher, StorageEncryptionError> {
+    if read_header(storage_directory)?.is_some() {
+        return Err(StorageEncryptionError::AlreadyEncrypted {
+            storage_directory: storage_directory.to_path_buf(),
+        });
+    }
+    let derivation = match (&key_source.key_file_path, &key_source.passphrase) {
+        (Some(_), _) => KeyDerivation::KeyFile,
+        (None, Some(_)) => {
+            let mut salt = [0; SALT_LENGTH];
+            OsRng.fill_bytes(&mut salt);
+            KeyDerivation::Passphrase {
+                salt: encode_hex(&salt),
+                memory_kib: Params::DEFAULT_M_COST,
+                iterations: Params::DEFAULT_T_COST,
+                parallelism: Params::DEFAULT_P_COST,
+            }
+        }
+        (None, None) => {
+            return Err(StorageEncryptionError::MissingKey {
+                storage_directory: storage_directory.to_path_buf(),
+                expected: None,
+            })
+        }
+    };
+    let cipher = StorageCipher::new(derive_key(storage_directory, &derivation, key_source)?);
+    write_header(
+        storage_directory,
+        &EncryptionHeader {
+            version: ENCRYPTION_HEADER_VERSION,
+            derivation,
+            key_check: encode_hex(&cipher.encrypt(KEY_CHECK_PLAINTEXT)),
+        },
+    )?;
+    Ok(cipher)
+}
+
+// 全fileを平文に戻した後に呼び、保存先を平文に戻す
+pub fn remove_storage_encryption(storage_directory: &Path) -> Result<(), StorageEncryptionError> {
+    let path = header_path(storage_directory);
+    fs::remove_file(&path).map_err(|error| StorageEncryptionError::Io {
+        path,
+        source: error,
+    })
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&text[index..index + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestStorageDir;
+
+    fn write_key_file(storage_dir: &TestStorageDir, name: &str, contents: &[u8]) -> PathBuf {
+        let path = storage_dir.path.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn key_fileで暗号化した保存先は同じkey_fileで開けて平文に戻せる() {
+        let storage = TestStorageDir::new("storage-encryption");
+        let key_file = write_key_file(&storage, "key", &[7; KEY_LENGTH]);
+        let key_source = StorageKeySource::new(Some(key_file), None);
+
+        let cipher = initialize_storage_encryption(&storage.path, &key_source).unwrap();
+        let encrypted = cipher.encrypt(b"project:\n  name: secret\n");
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted
+            .windows(b"secret".len())
+            .any(|window| window == b"secret"));
+
+        let reopened = open_storage_cipher(&storage.path, &key_source)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            decode_storage_file(Some(&reopened), &encrypted).unwrap(),
+            b"project:\n  name: secret\n"
+        );
+    }
+
+    #[test]
+    fn 平文の保存先はcipherなしで開き暗号化されたfileを拒む() {
+        let storage = TestStorageDir::new("storage-encryption");
+
+        assert!(
+            open_storage_cipher(&storage.path, &StorageKeySource::default())
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            decode_storage_file(None, b"project: {}\n").unwrap(),
+            b"project: {}\n"
+        );
+        let cipher = StorageCipher::new([1; KEY_LENGTH]);
+        assert!(matches!(
+            decode_storage_file(None, &cipher.encrypt(b"project: {}\n")),
+            Err(StorageEncryptionError::EncryptedFileWithoutKey)
+        ));
+        assert!(matches!(
+            decode_storage_file(Some(&cipher), b"project: {}\n"),
+            Err(StorageEncryptionError::UnencryptedFile)
+        ));
+    }
+
+    #[test]
+    fn 鍵がないか違えば開く前に分かるerrorにする() {
+        let storage = TestStorageDir::new("storage-encryption");
+        let key_file = write_key_file(&storage, "key", encode_hex(&[3; KEY_LENGTH]).as_bytes());
+        let other_key_file = write_key_file(&storage, "other", &[4; KEY_LENGTH]);
+        initialize_storage_encryption(&storage.path, &StorageKeySource::new(Some(key_file), None))
+            .unwrap();
+
+        let missing = open_storage_cipher(
+            &storage.path,
+            &StorageKeySource::new(None, Some("passphrase".to_string())),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            missing,
+            StorageEncryptionError::MissingKey {
+                expected: Some(StorageKeyKind::KeyFile),
+                ..
+            }
+        ));
+        assert!(missing
+            .to_string()
+            .contains("set storage_encryption_key_file_path"));
+
+        let wrong = open_storage_cipher(
+            &storage.path,
+            &StorageKeySource::new(Some(other_key_file), None),
+        )
+        .unwrap_err();
+        assert!(matches!(wrong, StorageEncryptionError::WrongKey { .. }));
+    }
+
+    #[test]
+    fn passphraseで暗号化した保存先は違うpassphraseを拒む() {
+        let storage = TestStorageDir::new("storage-encryption");
+        initialize_storage_encryption(
+            &storage.path,
+            &StorageKeySource::new(None, Some("correct horse".to_string())),
+        )
+        .unwrap();
+
+        assert!(open_storage_cipher(
+            &storage.path,
+            &StorageKeySource::new(None, Some("correct horse".to_string())),
+        )
+        .unwrap()
+        .is_some());
+        assert!(matches!(
+            open_storage_cipher(
+                &storage.path,
+                &StorageKeySource::new(None, Some("battery staple".to_string())),
+            ),
+            Err(StorageEncryptionError::WrongKey { .. })
+        ));
+        assert!(matches!(
+            open_storage_cipher(&storage.path, &StorageKeySource::default()),
+            Err(StorageEncryptionError::MissingKey {
+                expected: Some(StorageKeyKind::Passphrase),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn key_fileは32byteか64桁の16進だけを受け付ける() {
+        let storage = TestStorageDir::new("storage-encryption");
+        let short_key_file = write_key_file(&storage, "short", b"too short\n");
+
+        assert!(matches!(
+            initialize_storage_encryption(
+                &storage.path,
+                &StorageKeySource::new(Some(short_key_file), None),
+            ),
+            Err(StorageEncryptionError::InvalidKeyFile { .. })
+        ));
+        assert!(!header_path(&storage.path).exists());
+    }
+
+    #[test]
+    fn sqlite保存先は鍵の設定か暗号化の印があれば開く前にerrorにする() {
+        let storage = TestStorageDir::new("storage-encryption");
+        let key_file = write_key_file(&storage, "key", &[5; KEY_LENGTH]);
+
+        assert!(ensure_unencrypted_storage(&storage.path, &StorageKeySource::default()).is_ok());
+        for key_source in [
+            StorageKeySource::new(Some(key_file.clone()), None),
+            StorageKeySource::new(None, Some("passphrase".to_string())),
+        ] {
+            assert!(matches!(
+                ensure_unencrypted_storage(&storage.path, &key_source),
+                Err(StorageEncryptionError::SqliteBackendEncrypted { .. })
+            ));
+        }
+
+        initialize_storage_encryption(&storage.path, &StorageKeySource::new(Some(key_file), None))
+            .unwrap();
+        let error =
+            ensure_unencrypted_storage(&storage.path, &StorageKeySource::default()).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("storage_backend: sqlite cannot be encrypted"));
+    }
+
+    #[test]
+    fn 暗号化した保存先では保存先の外へ平文を書かせない() {
+        let storage = TestStorageDir::new("storage-encryption");
+        let key_file = write_key_file(&storage, "key", &[3; KEY_LENGTH]);
+        let cipher = initialize_storage_encryption(
+            &storage.path,
+            &StorageKeySource::new(Some(key_file), None),
+        )
+        .unwrap();
+        let output_path = storage.path.join("export.md");
+
+        assert!(ensure_plaintext_output_allowed(None, &output_path).is_ok());
+        let error = ensure_plaintext_output_allowed(Some(&cipher), &output_path).unwrap_err();
+        assert!(matches!(
+            error,
+            StorageEncryptionError::PlaintextOutsideStorage { ref path } if *path == output_path
+        ));
+    }
+}
@@ -0,0 +1,855 @@
+use crate::adapter::gateway::storage_encryption::{
+    decode_storage_file, StorageCipher, StorageEncryptionError,
+};
+use crate::adapter::gateway::task_repository::write_file_atomically;
+use crate::application::interface::{StorageHistoryError, StorageHistoryTrait};
+use crate::application::storage_history::{StorageRevision, TaskRevisionNode};
+use crate::entity::task::{task_to_yaml, TaskHandle, TaskTreeError};
+use chrono::{DateTime, Local};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use uuid::Uuid;
+use walkdir::WalkDir;
+use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+
+pub const STORAGE_HISTORY_DIRECTORY_NAME: &str = ".history";
+pub const DEFAULT_STORAGE_HISTORY_RETENTION_REVISIONS: usize = 100;
+const REVISION_INDEX_FILE_NAME: &str = "revisions.jsonl";
+const BLOB_DIRECTORY_NAME: &str = "blobs";
+
+// 1版につき1行。projectsはその版の全projectを、保存先からの相対pathからblobへの対応で持つ。
+// 変更のないprojectは前の版のblobを参照するため、古い版を消してもblobは参照が残る間は残す
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RevisionRecord {
+    revision: Uuid,
+    saved_at: DateTime<Local>,
+    projects: BTreeMap<String, String>,
+    changed_projects: Vec<String>,
+}
+
+impl RevisionRecord {
+    fn to_storage_revision(&self) -> StorageRevision {
+        StorageRevision {
+            revision: self.revision,
+            saved_at: self.saved_at,
+            projects: self.projects.keys().cloned().collect(),
+            changed_projects: self.changed_projects.clone(),
+        }
+    }
+}
+
+// 保存先直下の.historyに、保存ごとのproject.yamlをgzip圧縮して保持する。
+// retention_revisionsを超えた古い版から削除し、0なら記録しない
+pub struct StorageHistory {
+    storage_directory: PathBuf,
+    retention_revisions: usize,
+    // 暗号化した保存先では、blobもproject.yamlと同じ暗号文のまま保持する
+    cipher: Option<StorageCipher>,
+}
+
+impl StorageHistory {
+    pub fn new(storage_directory: impl Into<PathBuf>, retention_revisions: usize) -> Self {
+        Self {
+            storage_directory: storage_directory.into(),
+            retention_revisions,
+            cipher: None,
+        }
+    }
+
+    pub fn with_cipher(mut self, cipher: Option<StorageCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    fn history_directory(&self) -> PathBuf {
+        self.storage_directory.join(STORAGE_HISTORY_DIRECTORY_NAME)
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.history_directory().join(REVISION_INDEX_FILE_NAME)
+    }
+
+    fn blob_path(&self, blob: &str) -> PathBuf {
+        self.history_directory()
+            .join(BLOB_DIRECTORY_NAME)
+            .join(blob)
+    }
+
+    // 保存先の外にあるprojectは履歴の対象外としてNoneを返す
+    pub fn relative_project_path(&self, project_yaml_file_path: &Path) -> Option<String> {
+        let relative = project_yaml_file_path
+            .strip_prefix(&self.storage_directory)
+            .ok()?;
+        let components = relative
+            .components()
+            .map(|component| match component {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        (!components.is_empty()).then(|| components.join("/"))
+    }
+
+    fn read_records(&self) -> Result<Vec<RevisionRecord>, StorageHistoryError> {
+        let index_path = self.index_path();
+        let contents = match fs::read_to_string(&index_path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(StorageHistoryError::new(index_path, error)),
+        };
+        contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line)
+                    .map_err(|error| StorageHistoryError::new(&index_path, error))
+            })
+            .collect()
+    }
+
+    fn write_records(&self, records: &[RevisionRecord]) -> Result<(), StorageHistoryError> {
+        let index_path = self.index_path();
+        let mut contents = String::new();
+        for record in records {
+            contents.push_str(
+                &serde_json::to_string(record)
+                    .map_err(|error| StorageHistoryError::new(&index_path, error))?,
+            );
+            contents.push('\n');
+        }
+        write_file_atomically(&index_path, contents.as_bytes())
+            .map(|_| ())
+            .map_err(|error| StorageHistoryError::new(&index_path, error))
+    }
+
+    fn write_blob(
+        &self,
+        revision: Uuid,
+        index: usize,
+        bytes: &[u8],
+    ) -> Result<String, StorageHistoryError> {
+        let blob = format!("{revision}/{index}.yaml.gz");
+        let blob_path = self.blob_path(&blob);
+        let result = (|| {
+            fs::create_dir_all(blob_path.parent().expect("blob path has a parent"))?;
+            let mut encoder = GzEncoder::new(File::create(&blob_path)?, Compression::default());
+            encoder.write_all(bytes)?;
+            encoder.finish()?.sync_all()
+        })();
+        result
+            .map(|()| blob)
+            .map_err(|error| StorageHistoryError::new(blob_path, error))
+    }
+
+    fn read_blob(&self, blob: &str) -> Result<Vec<u8>, StorageHistoryError> {
+        let blob_path = self.blob_path(blob);
+        let mut bytes = Vec::new();
+        File::open(&blob_path)
+            .and_then(|file| GzDecoder::new(file).read_to_end(&mut bytes))
+            .map_err(|error| StorageHistoryError::new(blob_path, error))?;
+        Ok(bytes)
+    }
+
+    // 保存先の暗号化・復号で、全blobの内容をrewriteの結果に置き換える。Noneを返したblobはそのまま残す
+    pub fn rewrite_blobs(
+        &self,
+        mut rewrite: impl FnMut(&[u8]) -> Result<Option<Vec<u8>>, StorageEncryptionError>,
+    ) -> Result<usize, StorageHistoryError> {
+        let blob_directory = self.history_directory().join(BLOB_DIRECTORY_NAME);
+        let mut rewritten = 0;
+        for entry in WalkDir::new(&blob_directory).sort_by_file_name() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error)
+                    if error.io_error().map(std::io::Error::kind) == Some(ErrorKind::NotFound) =>
+                {
+                    return Ok(0)
+                }
+                Err(error) => {
+                    let path = error
+                        .path()
+                        .map(Path::to_path_buf)
+                        .unwrap_or_else(|| blob_directory.clone());
+                    return Err(StorageHistoryError::new(path, error));
+                }
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let blob_path = entry.path();
+            let mut bytes = Vec::new();
+            File::open(blob_path)
+                .and_then(|file| GzDecoder::new(file).read_to_end(&mut bytes))
+                .map_err(|error| StorageHistoryError::new(blob_path, error))?;
+            let Some(new_bytes) =
+                rewrite(&bytes).map_err(|error| StorageHistoryError::new(blob_path, error))?
+            else {
+                continue;
+            };
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            let compressed = encoder
+                .write_all(&new_bytes)
+                .and_then(|()| encoder.finish())
+                .map_err(|error| StorageHistoryError::new(blob_path, error))?;
+            write_file_atomically(blob_path, &compressed)
+                .map_err(|error| StorageHistoryError::new(blob_path, error))?;
+            rewritten += 1;
+        }
+        Ok(rewritten)
+    }
+
+    // 保存の直前に呼び、changedを新しいblobとして、それ以外のprojectは前の版のblobを参照して記録する。
+    // 前の版にないprojectは、現在のfileの内容を新しいblobとして記録する
+    pub fn record_revision(
+        &self,
+        revision: Uuid,
+        saved_at: DateTime<Local>,
+        changed: &[(String, &[u8])],
+        all_projects: &[String],
+    ) -> Result<(), StorageHistoryError> {
+        if self.retention_revisions == 0 {
+            return Ok(());
+        }
+        let mut records = self.read_records()?;
+        let previous_projects = records
+            .last()
+            .map(|record| record.projects.clone())
+            .unwrap_or_default();
+
+        let mut projects = BTreeMap::new();
+        for (index, project) in all_projects.iter().enumerate() {
+            let blob = match changed.iter().find(|(path, _)| path == project) {
+                Some((_, bytes)) => self.write_blob(revision, index, bytes)?,
+                None => match previous_projects.get(project) {
+                    Some(blob) => blob.clone(),
+                    None => {
+                        let project_path = self.storage_directory.join(project);
+                        let bytes = match fs::read(&project_path) {
+                            Ok(bytes) => bytes,
+                            Err(error) if error.kind() == ErrorKind::NotFound => continue,
+                            Err(error) => {
+                                return Err(StorageHistoryError::new(project_path, error))
+                            }
+                        };
+                        self.write_blob(revision, index, &bytes)?
+                    }
+                },
+            };
+            projects.insert(project.clone(), blob);
+        }
+
+        records.push(RevisionRecord {
+            revision,
+            saved_at,
+            projects,
+            changed_projects: changed.iter().map(|(path, _)| path.clone()).collect(),
+        });
+        self.write_records_with_retention(records)
+    }
+
+    // 保存先の状態全体を記録する。前の版と同じ内容のprojectはblobを共有し、変更扱いにしない
+    fn record_state(
+        &self,
+        records: &mut Vec<RevisionRecord>,
+        revision: Uuid,
+        saved_at: DateTime<Local>,
+        state: &BTreeMap<String, Vec<u8>>,
+    ) -> Result<RevisionRecord, StorageHistoryError> {
+        let previous_projects = records
+            .last()
+            .map(|record| record.projects.clone())
+            .unwrap_or_default();
+
+        let mut projects = BTreeMap::new();
+        let mut changed_projects = Vec::new();
+        for (index, (project, bytes)) in state.iter().enumerate() {
+            let unchanged_blob = match previous_projects.get(project) {
+                Some(blob) => (self.read_blob(blob)? == *bytes).then(|| blob.clone()),
+                None => None,
+            };
+            let blob = match unchanged_blob {
+                Some(blob) => blob,
+                None => {
+                    changed_projects.push(project.clone());
+                    self.write_blob(revision, index, bytes)?
+                }
+            };
+            projects.insert(project.clone(), blob);
+        }
+        changed_projects.extend(
+            previous_projects
+                .keys()
+                .filter(|project| !state.contains_key(*project))
+                .cloned(),
+        );
+
+        let record = RevisionRecord {
+            revision,
+            saved_at,
+            projects,
+            changed_projects,
+        };
+        records.push(record.clone());
+        Ok(record)
+    }
+
+    fn write_records_with_retention(
+        &self,
+        mut records: Vec<RevisionRecord>,
+    ) -> Result<(), StorageHistoryError> {
+        if records.len() > self.retention_revisions {
+            records.drain(..records.len() - self.retention_revisions);
+        }
+        self.write_records(&records)?;
+        self.remove_unreferenced_blobs(&records)
+    }
+
+    fn remove_unreferenced_blobs(
+        &self,
+        records: &[RevisionRecord],
+    ) -> Result<(), StorageHistoryError> {
+        let blob_directory = self.history_directory().join(BLOB_DIRECTORY_NAME);
+        let referenced = records
+            .iter()
+            .flat_map(|record| record.projects.values())
+            .map(|blob| self.blob_path(blob))
+            .collect::<BTreeSet<_>>();
+        let revision_directories = match fs::read_dir(&blob_directory) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(StorageHistoryError::new(blob_directory, error)),
+        };
+        for revision_directory in revision_directories {
+            let revision_directory = revision_directory
+                .map_err(|error| StorageHistoryError::new(&blob_directory, error))?
+                .path();
+            let blobs = fs::read_dir(&revision_directory)
+                .map_err(|error| StorageHistoryError::new(&revision_directory, error))?;
+            let mut remaining = 0;
+            for blob in blobs {
+                let blob = blob
+                    .map_err(|error| StorageHistoryError::new(&revision_directory, error))?
+                    .path();
+                if referenced.contains(&blob) {
+                    remaining += 1;
+                } else {
+                    fs::remove_file(&blob)
+                        .map_err(|error| StorageHistoryError::new(blob, error))?;
+                }
+            }
+            if remaining == 0 {
+                fs::remove_dir(&revision_directory)
+                    .map_err(|error| StorageHistoryError::new(revision_directory, error))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_current_state(&self) -> Result<BTreeMap<String, Vec<u8>>, StorageHistoryError> {
+        let mut state = BTreeMap::new();
+        for entry in WalkDir::new(&self.storage_directory).sort_by_file_name() {
+            let entry = entry.map_err(|error| {
+                let path = error
+                    .path()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|| self.storage_directory.clone());
+                StorageHistoryError::new(path, error)
+            })?;
+            if entry.file_name() != "project.yaml" {
+                continue;
+            }
+            let Some(project) = self.relative_project_path(entry.path()) else {
+                continue;
+            };
+            let bytes = fs::read(entry.path())
+                .map_err(|error| StorageHistoryError::new(entry.path(), error))?;
+            state.insert(project, bytes);
+        }
+        Ok(state)
+    }
+
+    fn read_record_state(
+        &self,
+        record: &RevisionRecord,
+    ) -> Result<BTreeMap<String, Vec<u8>>, StorageHistoryError> {
+        record
+            .projects
+            .iter()
+            .map(|(project, blob)| Ok((project.clone(), self.read_blob(blob)?)))
+            .collect()
+    }
+
+    fn find_record(
+        &self,
+        records: &[RevisionRecord],
+        revision: Uuid,
+    ) -> Result<RevisionRecord, StorageHistoryError> {
+        records
+            .iter()
+            .find(|record| record.revision == revision)
+            .cloned()
+            .ok_or_else(|| {
+                StorageHistoryError::new(
+                    self.index_path(),
+                    std::io::Error::new(
+                        ErrorKind::NotFound,
+                        format!("storage revision {revision} is not recorded"),
+                    ),
+                )
+            })
+    }
+
+    fn read_storage_revision(&self) -> Option<Uuid> {
+        fs::read_to_string(self.storage_directory.join(".revision"))
+            .ok()
+            .and_then(|text| Uuid::parse_str(text.trim()).ok())
+    }
+}
+
+impl StorageHistoryTrait for StorageHistory {
+    fn list_revisions(&self) -> Result<Vec<StorageRevision>, StorageHistoryError> {
+        Ok(self
+            .read_records()?
+            .iter()
+            .map(RevisionRecord::to_storage_revision)
+            .collect())
+    }
+
+    fn read_revision_tasks(
+        &self,
+        revision: Option<Uuid>,
+    ) -> Result<BTreeMap<String, Vec<TaskRevisionNode>>, StorageHistoryError> {
+        let state = match revision {
+            Some(revision) => {
+                let record = self.find_record(&self.read_records()?, revision)?;
+                self.read_record_state(&record)?
+            }
+            None => self.read_current_state()?,
+        };
+        state
+            .into_iter()
+            .map(|(project, bytes)| {
+                let bytes = decode_storage_file(self.cipher.as_ref(), &bytes).map_err(|error| {
+                    StorageHistoryError::new(self.storage_directory.join(&project), error)
+                })?;
+                let nodes = project_yaml_to_nodes(&bytes).map_err(|error| {
+                    StorageHistoryError::new(self.storage_directory.join(&project), error)
+                })?;
+                Ok((project, nodes))
+            })
+            .collect()
+    }
+
+    fn restore_revision(
+        &mut self,
+        revision: Uuid,
+        project: Option<&str>,
+        now: DateTime<Local>,
+    ) -> Result<StorageRevision, StorageHistoryError> {
+        let mut records = self.read_records()?;
+        let target = self.find_record(&records, revision)?;
+        let target_state = self.read_record_state(&target)?;
+        let current_state = self.read_current_state()?;
+
+        // 戻す前の状態が最新の版として残っていなければ、先に記録して戻し操作も取り消せるようにする
+        let current_revision = self.read_storage_revision();
+        if self.retention_revisions > 0
+            && (current_revision.is_none()
+                || records.last().map(|record| record.revision) != current_revision)
+        {
+            self.record_state(
+                &mut records,
+                current_revision.unwrap_or_else(Uuid::new_v4),
+                now,
+                &current_state,
+            )?;
+        }
+
+        let restored_state = match project {
+            Some(project) => {
+                let mut restored_state = current_state.clone();
+                let bytes = target_state.get(project).cloned().ok_or_else(|| {
+                    StorageHistoryError::new(
+                        self.storage_directory.join(project),
+                        std::io::Error::new(
+                            ErrorKind::NotFound,
+                            format!("project is not recorded in storage revision {revision}"),
+                        ),
+                    )
+                })?;
+                restored_state.insert(project.to_string(), bytes);
+                restored_state
+            }
+            None => target_state,
+        };
+
+        let new_revision = Uuid::new_v4();
+        let record = self.record_state(&mut records, new_revision, now, &restored_state)?;
+        if self.retention_revisions > 0 {
+            self.write_records_with_retention(records)?;
+        }
+
+        let revision_path = self.storage_directory.join(".revision");
+        write_file_atomically(&revision_path, format!("{new_revision}\n").as_bytes())
+            .map_err(|error| StorageHistoryError::new(revision_path, error))?;
+        for (project, bytes) in &restored_state {
+            if current_state.get(project) == Some(bytes) {
+                continue;
+            }
+            let project_path = self.storage_directory.join(project);
+            let project_dir_path = project_path.parent().expect("project path has a parent");
+            fs::create_dir_all(project_dir_path.join("markdown"))
+                .map_err(|error| StorageHistoryError::new(project_dir_path, error))?;
+            write_file_atomically(&project_path, bytes)
+                .map_err(|error| StorageHistoryError::new(&project_path, error))?;
+        }
+        // 戻した版にないprojectはproject.yamlだけを取り除き、markdownなどは残す
+        for project in current_state.keys() {
+            if !restored_state.contains_key(project) {
+                let project_path = self.storage_directory.join(project);
+                fs::remove_file(&project_path)
+                    .map_err(|error| StorageHistoryError::new(project_path, error))?;
+            }
+        }
+        Ok(record.to_storage_revision())
+    }
+}
+
+fn project_yaml_to_nodes(bytes: &[u8]) -> Result<Vec<TaskRevisionNode>, std::io::Error> {
+    let text = std::str::from_utf8(bytes)
+        .map_err(|error| std::io::Error::new(ErrorKind::InvalidData, error))?;
+    let documents = YamlLoader::load_from_str(text)
+        .map_err(|error| std::io::Error::new(ErrorKind::InvalidData, error))?;
+    let project = documents
+        .first()
+        .map(|document| &document["project"])
+        .filter(|project| project.as_hash().is_some())
+        .ok_or_else(|| {
+            std::io::Error::new(
+                ErrorKind::InvalidData,
+                "project document must contain a project mapping",
+            )
+        })?;
+    let mut nodes = Vec::new();
+    push_task_nodes(project, None, &mut nodes);
+    Ok(nodes)
+}
+
+// memory上のtask木を、保存履歴の差分と同じ単位に平坦化する
+pub fn task_tree_revision_nodes(
+    root_task: &TaskHandle,
+) -> Result<Vec<TaskRevisionNode>, TaskTreeError> {
+    let mut nodes = Vec::new();
+    push_task_nodes(&task_to_yaml(root_task)?, None, &mut nodes);
+    Ok(nodes)
+}
+
+fn push_task_nodes(yaml: &Yaml, parent_id: Option<&str>, nodes: &mut Vec<TaskRevisionNode>) {
+    let Some(hash) = yaml.as_hash() else {
+        return;
+    };
+    let name = yaml["name"].as_str().unwrap_or("").to_string();
+    // idのない古いtaskは、親と名前で同じtaskとみなす
+    let id = yaml["id"]
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{}/{name}", parent_id.unwrap_or("")));
+    let fields = hash
+        .iter()
+        .filter_map(|(key, value)| {
+            let key = key.as_str()?;
+            (key != "id" && key != "children").then(|| (key.to_string(), yaml_value_text(value)))
+        })
+        .collect();
+    nodes.push(TaskRevisionNode {
+        id: id.clone(),
+        name,
+        parent_id: parent_id.map(str::to_string),
+        fields,
+    });
+    for child in yaml["children"].as_vec().into_iter().flatten() {
+        push_task_nodes(child, Some(&id), nodes);
+    }
+}
+
+fn yaml_value_text(value: &Yaml) -> String {
+    match value {
+        Yaml::String(text) | Yaml::Real(text) => text.clone(),
+        Yaml::Integer(number) => number.to_string(),
+        Yaml::Boolean(flag) => flag.to_string(),
+        Yaml::Null => "null".to_string(),
+        _ => {
+            let mut out = String::new();
+            match YamlEmitter::new(&mut out).dump(value) {
+                Ok(()) => out.trim_start_matches("---").trim().replace('\n', " "),
+                Err(_) => format!("{value:?}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestStorageDir;
+    use chrono::TimeZone;
+
+    fn write_project(storage_dir: &TestStorageDir, project: &str, contents: &str) {
+        let path = storage_dir.path.join(project);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn read_project(storage_dir: &TestStorageDir, project: &str) -> Option<String> {
+        fs::read_to_string(storage_dir.path.join(project)).ok()
+    }
+
+    const PROJECT_A: &str = "20261018-a/project.yaml";
+    const PROJECT_B: &str = "20261018-b/project.yaml";
+
+    fn project_yaml(status: &str) -> String {
+        format!(
+            "---\nproject:\n  name: A\n  id: root-a\n  status: {status}\n  children:\n    - name: child\n      id: child-a\n      status: todo\n"
+        )
+    }
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap()
+    }
+
+    fn save(
+        storage_dir: &TestStorageDir,
+        history: &StorageHistory,
+        changed: &[(&str, String)],
+        all_projects: &[&str],
+    ) -> Uuid {
+        let revision = Uuid::new_v4();
+        let changed_bytes = changed
+            .iter()
+            .map(|(project, contents)| (project.to_string(), contents.as_bytes()))
+            .collect::<Vec<_>>();
+        let all_projects = all_projects
+            .iter()
+            .map(|project| project.to_string())
+            .collect::<Vec<_>>();
+        history
+            .record_revision(revision, now(), &changed_bytes, &all_projects)
+            .unwrap();
+        for (project, contents) in changed {
+            write_project(storage_dir, project, contents);
+        }
+        fs::write(storage_dir.path.join(".revision"), format!("{revision}\n")).unwrap();
+        revision
+    }
+
+    #[test]
+    fn record_revisionは変更projectだけを圧縮blobにし未変更projectは前の版を参照する() {
+        let storage_dir = TestStorageDir::new("storage-history");
+        write_project(
+            &storage_dir,
+            PROJECT_B,
+            "---\nproject:\n  name: B\n  id: root-b\n",
+        );
+        let history = StorageHistory::new(&storage_dir.path, 10);
+
+        save(
+            &storage_dir,
+            &history,
+            &[(PROJECT_A, project_yaml("todo"))],
+            &[PROJECT_A, PROJECT_B],
+        );
+        save(
+            &storage_dir,
+            &history,
+            &[(PROJECT_A, project_yaml("done"))],
+            &[PROJECT_A, PROJECT_B],
+        );
+
+        let records = history.read_records().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].changed_projects, vec![PROJECT_A.to_string()]);
+        assert_ne!(
+            records[0].projects[PROJECT_A],
+            records[1].projects[PROJECT_A]
+        );
+        assert_eq!(
+            records[0].projects[PROJECT_B],
+            records[1].projects[PROJECT_B]
+        );
+        assert_eq!(
+            history.read_blob(&records[0].projects[PROJECT_A]).unwrap(),
+            project_yaml("todo").into_bytes()
+        );
+    }
+
+    #[test]
+    fn record_revisionは保持数を超えた版と参照されないblobを削除する() {
+        let storage_dir = TestStorageDir::new("storage-history");
+        let history = StorageHistory::new(&storage_dir.path, 2);
+
+        let first = save(
+            &storage_dir,
+            &history,
+            &[(PROJECT_A, project_yaml("todo"))],
+            &[PROJECT_A],
+        );
+        save(
+            &storage_dir,
+            &history,
+            &[(PROJECT_B, "---\nproject:\n  name: B\n".to_string())],
+            &[PROJECT_A, PROJECT_B],
+        );
+        save(
+            &storage_dir,
+            &history,
+            &[(PROJECT_B, "---\nproject:\n  name: B2\n".to_string())],
+            &[PROJECT_A, PROJECT_B],
+        );
+
+        let records = history.read_records().unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|record| record.revision != first));
+        // 最初の版のPROJECT_Aのblobは後続の版から参照されているので残る
+        assert_eq!(
+            history.read_blob(&records[0].projects[PROJECT_A]).unwrap(),
+            project_yaml("todo").into_bytes()
+        );
+        assert_eq!(
+            fs::read_dir(history.history_directory().join(BLOB_DIRECTORY_NAME))
+                .unwrap()
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn 保持数0では版を記録しない() {
+        let storage_dir = TestStorageDir::new("storage-history");
+        let history = StorageHistory::new(&storage_dir.path, 0);
+
+        save(
+            &storage_dir,
+            &history,
+            &[(PROJECT_A, project_yaml("todo"))],
+            &[PROJECT_A],
+        );
+
+        assert!(!history.history_directory().exists());
+        assert_eq!(history.list_revisions().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn read_revision_tasksは版と現在の保存先をtask単位に平坦化する() {
+        let storage_dir = TestStorageDir::new("storage-history");
+        let history = StorageHistory::new(&storage_dir.path, 10);
+        let revision = save(
+            &storage_dir,
+            &history,
+            &[(PROJECT_A, project_yaml("todo"))],
+            &[PROJECT_A],
+        );
+        write_project(&storage_dir, PROJECT_A, &project_yaml("done"));
+
+        let recorded = history.read_revision_tasks(Some(revision)).unwrap();
+        let current = history.read_revision_tasks(None).unwrap();
+
+        let recorded_nodes = &recorded[PROJECT_A];
+        assert_eq!(recorded_nodes.len(), 2);
+        assert_eq!(recorded_nodes[0].id, "root-a");
+        assert_eq!(recorded_nodes[0].fields["status"], "todo");
+        assert!(!recorded_nodes[0].fields.contains_key("children"));
+        assert_eq!(recorded_nodes[1].parent_id.as_deref(), Some("root-a"));
+        assert_eq!(current[PROJECT_A][0].fields["status"], "done");
+    }
+
+    #[test]
+    fn restore_revisionは1projectだけを戻し戻す前と戻した後を版として残す() {
+        let storage_dir = TestStorageDir::new("storage-history");
+        let mut history = StorageHistory::new(&storage_dir.path, 10);
+        let first = save(
+            &storage_dir,
+            &history,
+            &[(PROJECT_A, project_yaml("todo"))],
+            &[PROJECT_A],
+        );
+        save(
+            &storage_dir,
+            &history,
+            &[
+                (PROJECT_A, project_yaml("done")),
+                (PROJECT_B, "---\nproject:\n  name: B\n".to_string()),
+            ],
+            &[PROJECT_A, PROJECT_B],
+        );
+        // 版を経ずに変更された状態も、戻す前に記録される
+        write_project(&storage_dir, PROJECT_B, "---\nproject:\n  name: B edited\n");
+        fs::write(
+            storage_dir.path.join(".revision"),
+            format!("{}\n", Uuid::new_v4()),
+        )
+        .unwrap();
+
+        let restored = history
+            .restore_revision(first, Some(PROJECT_A), now())
+            .unwrap();
+
+        assert_eq!(
+            read_project(&storage_dir, PROJECT_A),
+            Some(project_yaml("todo"))
+        );
+        assert_eq!(
+            read_project(&storage_dir, PROJECT_B).as_deref(),
+            Some("---\nproject:\n  name: B edited\n")
+        );
+        assert_eq!(restored.changed_projects, vec![PROJECT_A.to_string()]);
+        let records = history.read_records().unwrap();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[2].changed_projects, vec![PROJECT_B.to_string()]);
+        assert_eq!(
+            fs::read_to_string(storage_dir.path.join(".revision")).unwrap(),
+            format!("{}\n", restored.revision)
+        );
+    }
+
+    #[test]
+    fn restore_revisionは保存先全体を戻し版にないprojectを取り除く() {
+        let storage_dir = TestStorageDir::new("storage-history");
+        let mut history = StorageHistory::new(&storage_dir.path, 10);
+        let first = save(
+            &storage_dir,
+            &history,
+            &[(PROJECT_A, project_yaml("todo"))],
+            &[PROJECT_A],
+        );
+        save(
+            &storage_dir,
+            &history,
+            &[
+                (PROJECT_A, project_yaml("done")),
+                (PROJECT_B, "---\nproject:\n  name: B\n".to_string()),
+            ],
+            &[PROJECT_A, PROJECT_B],
+        );
+
+        let restored = history.restore_revision(first, None, now()).unwrap();
+
+        assert_eq!(
+            read_project(&storage_dir, PROJECT_A),
+            Some(project_yaml("todo"))
+        );
+        assert_eq!(read_project(&storage_dir, PROJECT_B), None);
+        assert!(storage_dir.path.join("20261018-b").exists());
+        assert_eq!(restored.projects, vec![PROJECT_A.to_string()]);
+        assert_eq!(
+            restored.changed_projects,
+            vec![PROJECT_A.to_string(), PROJECT_B.to_string()]
+        );
+        assert_eq!(history.read_records().unwrap().len(), 3);
+    }
+}
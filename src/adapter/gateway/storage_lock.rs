@@ -1,13 +1,15 @@
-use chrono::{DateTime, Local};
+use chrono::{DateTime, FixedOffset, Local};
 use fs2::FileExt;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::fs::File;
-use std::io::{Seek, SeekFrom, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+const LOCK_FILE_NAME: &str = ".lock";
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum LockMode {
@@ -24,6 +26,75 @@ impl fmt::Display for LockMode {
     }
 }
 
+impl LockMode {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "cli" => Some(Self::Cli),
+            "mcp" => Some(Self::Mcp),
+            _ => None,
+        }
+    }
+}
+
+// .lockに記録した保持者。hostnameは以前の版が書いた.lockにはない
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub hostname: Option<String>,
+    pub started_at: DateTime<FixedOffset>,
+    pub mode: LockMode,
+}
+
+impl LockHolder {
+    fn current(mode: LockMode, started_at: DateTime<Local>) -> Self {
+        Self {
+            pid: std::process::id(),
+            hostname: current_hostname(),
+            started_at: started_at.fixed_offset(),
+            mode,
+        }
+    }
+
+    pub fn parse(metadata: &str) -> Option<Self> {
+        let fields = parse_metadata_fields(metadata);
+        Some(Self {
+            pid: fields.get("pid")?.parse().ok()?,
+            hostname: fields.get("hostname").map(|hostname| hostname.to_string()),
+            started_at: DateTime::parse_from_rfc3339(fields.get("started_at")?).ok()?,
+            mode: LockMode::parse(fields.get("mode")?)?,
+        })
+    }
+
+    fn to_metadata(&self) -> String {
+        let mut metadata = format!("pid={}\n", self.pid);
+        if let Some(hostname) = &self.hostname {
+            metadata.push_str(&format!("hostname={hostname}\n"));
+        }
+        metadata.push_str(&format!(
+            "started_at={}\nmode={}\n",
+            self.started_at.to_rfc3339(),
+            self.mode
+        ));
+        metadata
+    }
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{} pid {}", self.mode, self.pid)?;
+        if let Some(hostname) = &self.hostname {
+            write!(formatter, "@{hostname}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StorageLockStatus {
+    Free { last_holder: Option<LockHolder> },
+    Held { holder: Option<LockHolder> },
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum StorageLockErrorKind {
     Contended,
@@ -68,6 +139,10 @@ impl StorageLockError {
     pub fn holder_metadata(&self) -> Option<&str> {
         self.holder_metadata.as_deref()
     }
+
+    pub fn holder(&self) -> Option<LockHolder> {
+        self.holder_metadata.as_deref().and_then(LockHolder::parse)
+    }
 }
 
 impl fmt::Display for StorageLockError {
@@ -104,22 +179,28 @@ impl Error for StorageLockError {
 pub struct StorageLock {
     _file: File,
     path: PathBuf,
+    holder: LockHolder,
 }
 
 impl StorageLock {
     pub fn acquire(storage_directory: &Path, mode: LockMode) -> Result<Self, StorageLockError> {
-        let path = storage_directory.join(".lock");
+        let path = storage_directory.join(LOCK_FILE_NAME);
         let mut file = open_lock_file(&path).map_err(|error| StorageLockError::io(&path, error))?;
 
         file.try_lock_exclusive()
             .map_err(|error| classify_lock_attempt_error(&path, error))?;
 
-        write_metadata(&mut file, mode, Local::now()).map_err(|error| {
+        let holder = LockHolder::current(mode, Local::now());
+        write_metadata(&mut file, &holder).map_err(|error| {
             let _ = FileExt::unlock(&file);
             StorageLockError::io(&path, error)
         })?;
 
-        Ok(Self { _file: file, path })
+        Ok(Self {
+            _file: file,
+            path,
+            holder,
+        })
     }
 
     pub fn acquire_with_timeout(
@@ -133,6 +214,73 @@ impl StorageLock {
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    pub fn holder(&self) -> &LockHolder {
+        &self.holder
+    }
+}
+
+// lockを取らずに保持者を調べる。共有lockを一瞬だけ試すので、metadataは書き換えない
+pub fn inspect_storage_lock(
+    storage_directory: &Path,
+) -> Result<StorageLockStatus, StorageLockError> {
+    let path = storage_directory.join(LOCK_FILE_NAME);
+    match std::fs::symlink_metadata(&path) {
+        Ok(_) => {}
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(StorageLockStatus::Free { last_holder: None })
+        }
+        Err(error) => return Err(StorageLockError::io(&path, error)),
+    }
+    let mut file = open_lock_file(&path).map_err(|error| StorageLockError::io(&path, error))?;
+    let held = match FileExt::try_lock_shared(&file) {
+        Ok(()) => {
+            let _ = FileExt::unlock(&file);
+            false
+        }
+        Err(error) => match classify_lock_attempt_error(&path, error) {
+            error if error.kind() == StorageLockErrorKind::Contended => true,
+            error => return Err(error),
+        },
+    };
+    let mut metadata = String::new();
+    file.read_to_string(&mut metadata)
+        .map_err(|error| StorageLockError::io(&path, error))?;
+    let holder = LockHolder::parse(&metadata);
+    if !held {
+        return Ok(StorageLockStatus::Free {
+            last_holder: holder,
+        });
+    }
+    Ok(StorageLockStatus::Held { holder })
+}
+
+pub(crate) fn parse_metadata_fields(metadata: &str) -> HashMap<&str, &str> {
+    metadata
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .collect()
+}
+
+#[cfg(unix)]
+pub(crate) fn current_hostname() -> Option<String> {
+    let mut buffer = [0u8; 256];
+    // SAFETY: bufferの長さを渡しており、gethostnameはその範囲にだけ書き込む
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) };
+    if result != 0 {
+        return None;
+    }
+    let length = buffer
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(buffer.len());
+    let hostname = String::from_utf8_lossy(&buffer[..length]).into_owned();
+    (!hostname.is_empty()).then_some(hostname)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn current_hostname() -> Option<String> {
+    None
 }
 
 fn retry_contended_with_timeout<T>(
@@ -199,26 +347,19 @@ fn classify_lock_attempt_error(path: &Path, source: std::io::Error) -> StorageLo
     }
 }
 
-fn write_metadata(
-    file: &mut File,
-    mode: LockMode,
-    started_at: DateTime<Local>,
-) -> std::io::Result<()> {
+fn write_metadata(file: &mut File, holder: &LockHolder) -> std::io::Result<()> {
     file.set_len(0)?;
     file.seek(SeekFrom::Start(0))?;
-    write!(
-        file,
-        "pid={}\nstarted_at={}\nmode={}\n",
-        std::process::id(),
-        started_at.to_rfc3339(),
-        mode
-    )?;
+    file.write_all(holder.to_metadata().as_bytes())?;
     file.sync_data()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{LockMode, StorageLock, StorageLockErrorKind};
+    use super::{
+        inspect_storage_lock, LockHolder, LockMode, StorageLock, StorageLockErrorKind,
+        StorageLockStatus,
+    };
     use std::fs;
     use std::io::ErrorKind;
     use std::path::{Path, PathBuf};
@@ -261,6 +402,48 @@ mod tests {
         assert!(metadata.contains(&format!("pid={}", std::process::id())));
         assert!(metadata.contains("mode=cli"));
         assert!(metadata.contains("started_at="));
+        assert!(metadata.contains("hostname="));
+    }
+
+    #[test]
+    fn lock_holderはhostnameのない以前のmetadataも読める() {
+        let holder =
+            LockHolder::parse("pid=42\nstarted_at=2026-10-19T12:00:00+09:00\nmode=mcp\n").unwrap();
+
+        assert_eq!(holder.pid, 42);
+        assert_eq!(holder.hostname, None);
+        assert_eq!(holder.mode, LockMode::Mcp);
+        assert_eq!(holder.to_string(), "mcp pid 42");
+        assert_eq!(LockHolder::parse("pid=42\nmode=cli\n"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn inspect_storage_lockは取得せずに保持者と空きを見分ける() {
+        let directory = TestDir::new();
+        assert_eq!(
+            inspect_storage_lock(directory.path()).unwrap(),
+            StorageLockStatus::Free { last_holder: None }
+        );
+        assert!(!directory.path().join(".lock").exists());
+
+        let lock = StorageLock::acquire(directory.path(), LockMode::Cli).unwrap();
+        let holder = lock.holder().clone();
+        assert_eq!(holder.pid, std::process::id());
+        assert_eq!(
+            inspect_storage_lock(directory.path()).unwrap(),
+            StorageLockStatus::Held {
+                holder: Some(holder.clone()),
+            }
+        );
+
+        drop(lock);
+        assert_eq!(
+            inspect_storage_lock(directory.path()).unwrap(),
+            StorageLockStatus::Free {
+                last_holder: Some(holder)
+            }
+        );
     }
 
     #[cfg(unix)]
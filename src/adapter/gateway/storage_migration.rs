@@ -0,0 +1,583 @@
+use crate::adapter::gateway::busy_block::BUSY_BLOCKS_FILE_NAME;
+use crate::adapter::gateway::load_history::LOAD_HISTORY_FILE_NAME;
+use crate::adapter::gateway::sqlite_task_repository::SqliteTaskRepository;
+use crate::adapter::gateway::storage_encryption::{
+    initialize_storage_encryption, is_encrypted, open_storage_cipher, remove_storage_encryption,
+    StorageCipher, StorageEncryptionError, StorageKeySource,
+};
+use crate::adapter::gateway::storage_history::StorageHistory;
+use crate::adapter::gateway::task_repository::{write_file_atomically, TaskRepository};
+use crate::adapter::gateway::trash::TRASH_FILE_NAME;
+use crate::application::interface::{
+    StorageHistoryError, TaskRepositoryError, TaskRepositoryOperation, TaskRepositoryTrait,
+};
+use crate::entity::task::{task_to_yaml, TaskHandle, TaskTreeError};
+use chrono::{DateTime, Local};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use walkdir::WalkDir;
+use yaml_rust::Yaml;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StorageMigrationReport {
+    pub projects: usize,
+    pub tasks: usize,
+}
+
+#[derive(Debug)]
+pub enum StorageMigrationError {
+    Repository(TaskRepositoryError),
+    TaskTree(TaskTreeError),
+    Encryption(StorageEncryptionError),
+    History(StorageHistoryError),
+    MissingDatabase(PathBuf),
+    DestinationNotEmpty { projects: usize },
+    VerificationFailed { project: String, reason: String },
+}
+
+impl fmt::Display for StorageMigrationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Repository(error) => error.fmt(formatter),
+            Self::TaskTree(error) => error.fmt(formatter),
+            Self::Encryption(error) => error.fmt(formatter),
+            Self::History(error) => error.fmt(formatter),
+            Self::MissingDatabase(path) => {
+                write!(formatter, "sqlite database not found: {}", path.display())
+            }
+            Self::DestinationNotEmpty { projects } => write!(
+                formatter,
+                "migration destination already contains {projects} project(s)"
+            ),
+            Self::VerificationFailed { project, reason } => {
+                write!(
+                    formatter,
+                    "migration verification failed for {project}: {reason}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for StorageMigrationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Repository(error) => Some(error),
+            Self::TaskTree(error) => Some(error),
+            Self::Encryption(error) => Some(error),
+            Self::History(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<TaskRepositoryError> for StorageMigrationError {
+    fn from(error: TaskRepositoryError) -> Self {
+        Self::Repository(error)
+    }
+}
+
+impl From<TaskTreeError> for StorageMigrationError {
+    fn from(error: TaskTreeError) -> Self {
+        Self::TaskTree(error)
+    }
+}
+
+impl From<StorageEncryptionError> for StorageMigrationError {
+    fn from(error: StorageEncryptionError) -> Self {
+        Self::Encryption(error)
+    }
+}
+
+impl From<StorageHistoryError> for StorageMigrationError {
+    fn from(error: StorageHistoryError) -> Self {
+        Self::History(error)
+    }
+}
+
+// YAML directoryの全projectを空のdatabaseへ移し、読み直した内容が元と一致することを確かめる。
+// YAML側は変更しないので、失敗した場合もそのまま使い続けられる
+pub fn migrate_yaml_to_sqlite(
+    storage_directory: &str,
+    database_path: &Path,
+    now: DateTime<Local>,
+) -> Result<StorageMigrationReport, StorageMigrationError> {
+    let mut source = TaskRepository::new(storage_directory);
+    source.reload_if_changed(now)?;
+    let mut destination = SqliteTaskRepository::open_database(storage_directory, database_path)?;
+    destination.reload_if_changed(now)?;
+    ensure_empty(&destination)?;
+
+    for (project_key, root_task) in source.project_entries() {
+        destination.import_project(&project_key, root_task.clone())?;
+    }
+    destination.save()?;
+
+    verify_storage_backends(storage_directory, database_path, now)
+}
+
+// databaseの全projectを空のYAML directoryへ書き出し、読み直した内容が元と一致することを確かめる
+pub fn migrate_sqlite_to_yaml(
+    storage_directory: &str,
+    database_path: &Path,
+    history_retention_revisions: usize,
+    now: DateTime<Local>,
+) -> Result<StorageMigrationReport, StorageMigrationError> {
+    let source = open_existing_database(storage_directory, database_path, now)?;
+    let mut destination = TaskRepository::new(storage_directory)
+        .with_history_retention_revisions(history_retention_revisions);
+    destination.reload_if_changed(now)?;
+    ensure_empty(&destination)?;
+
+    for (project_key, root_task) in source.project_entries() {
+        destination.import_project(&project_key, root_task.clone())?;
+    }
+    destination.save()?;
+
+    verify_storage_backends(storage_directory, database_path, now)
+}
+
+// 両方の保存先を読み直し、projectの集合と各task木のYAML表現が一致するかを比べる
+pub fn verify_storage_backends(
+    storage_directory: &str,
+    database_path: &Path,
+    now: DateTime<Local>,
+) -> Result<StorageMigrationReport, StorageMigrationError> {
+    let mut yaml_repository = TaskRepository::new(storage_directory);
+    yaml_repository.reload_if_changed(now)?;
+    let sqlite_repository = open_existing_database(storage_directory, database_path, now)?;
+
+    let yaml_projects = project_yaml_by_key(yaml_repository.project_entries())?;
+    let sqlite_projects = project_yaml_by_key(sqlite_repository.project_entries())?;
+    for project in yaml_projects.keys() {
+        if !sqlite_projects.contains_key(project) {
+            return Err(StorageMigrationError::VerificationFailed {
+                project: project.clone(),
+                reason: "missing in sqlite database".to_string(),
+            });
+        }
+    }
+
+    let mut tasks = 0;
+    for (project, sqlite_yaml) in &sqlite_projects {
+        let Some(yaml) = yaml_projects.get(project) else {
+            return Err(StorageMigrationError::VerificationFailed {
+                project: project.clone(),
+                reason: "missing in YAML directory".to_string(),
+            });
+        };
+        if yaml != sqlite_yaml {
+            return Err(StorageMigrationError::VerificationFailed {
+                project: project.clone(),
+                reason: "task tree differs".to_string(),
+            });
+        }
+        tasks += count_tasks(yaml);
+    }
+
+    Ok(StorageMigrationReport {
+        projects: sqlite_projects.len(),
+        tasks,
+    })
+}
+
+// 平文のYAML保存先を暗号化する。project fileと保存履歴のblobを1つずつ置き換えるため、
+// 途中で止まっても同じ鍵で再実行すれば残りだけを暗号化する
+pub fn encrypt_yaml_storage(
+    storage_directory: &str,
+    key_source: &StorageKeySource,
+    now: DateTime<Local>,
+) -> Result<StorageMigrationReport, StorageMigrationError> {
+    let directory = Path::new(storage_directory);
+    let cipher = match open_storage_cipher(directory, key_source)? {
+        Some(cipher) => cipher,
+        None => {
+            // 鍵を作る前に、今の平文がすべて読めることを確かめる
+            TaskRepository::new(storage_directory).reload_if_changed(now)?;
+            initialize_storage_encryption(directory, key_source)?
+        }
+    };
+    rewrite_storage_files(storage_directory, |bytes| {
+        Ok((!is_encrypted(bytes)).then(|| cipher.encrypt(bytes)))
+    })?;
+    verify_yaml_storage(storage_directory, Some(cipher), now)
+}
+
+// 暗号化したYAML保存先を平文に戻し、最後に.encryptionを消す
+pub fn decrypt_yaml_storage(
+    storage_directory: &str,
+    key_source: &StorageKeySource,
+    now: DateTime<Local>,
+) -> Result<StorageMigrationReport, StorageMigrationError> {
+    let directory = Path::new(storage_directory);
+    let cipher = open_storage_cipher(directory, key_source)?.ok_or_else(|| {
+        StorageEncryptionError::NotEncrypted {
+            storage_directory: directory.to_path_buf(),
+        }
+    })?;
+    rewrite_storage_files(storage_directory, |bytes| {
+        is_encrypted(bytes)
+            .then(|| cipher.decrypt(bytes))
+            .transpose()
+    })?;
+    remove_storage_encryption(directory)?;
+    verify_yaml_storage(storage_directory, None, now)
+}
+
+// project.yaml、捨てた記録、負荷履歴、埋まっている時間と保存履歴のblobを書き換え、他processが読み直すよう保存先の版を進める
+fn rewrite_storage_files(
+    storage_directory: &str,
+    mut rewrite: impl FnMut(&[u8]) -> Result<Option<Vec<u8>>, StorageEncryptionError>,
+) -> Result<(), StorageMigrationError> {
+    for entry in WalkDir::new(storage_directory).sort_by_file_name() {
+        let entry = entry.map_err(|error| StorageEncryptionError::Io {
+            path: error
+                .path()
+                .map_or_else(|| PathBuf::from(storage_directory), Path::to_path_buf),
+            source: std::io::Error::other(error),
+        })?;
+        if entry.file_name() != "project.yaml" {
+            continue;
+        }
+        rewrite_storage_file(entry.path(), &mut rewrite)?;
+    }
+    // 捨てた記録と負荷履歴、埋まっている時間もtaskや予定の内容を含むので、file全体を同じ鍵で置き換える
+    for file_name in [
+        TRASH_FILE_NAME,
+        LOAD_HISTORY_FILE_NAME,
+        BUSY_BLOCKS_FILE_NAME,
+    ] {
+        let path = Path::new(storage_directory).join(file_name);
+        if path.is_file() {
+            rewrite_storage_file(&path, &mut rewrite)?;
+        }
+    }
+    // blobを書き換えるだけなので、保持数は使わない
+    StorageHistory::new(storage_directory, 0).rewrite_blobs(rewrite)?;
+
+    let revision_path = Path::new(storage_directory).join(".revision");
+    write_file_atomically(&revision_path, format!("{}\n", Uuid::new_v4()).as_bytes())
+        .map_err(|error| TaskRepositoryError::new(TaskRepositoryOperation::Save, error))?;
+    Ok(())
+}
+
+fn rewrite_storage_file(
+    path: &Path,
+    rewrite: &mut impl FnMut(&[u8]) -> Result<Option<Vec<u8>>, StorageEncryptionError>,
+) -> Result<(), StorageMigrationError> {
+    let bytes = fs::read(path).map_err(|error| StorageEncryptionError::Io {
+        path: path.to_path_buf(),
+        source: error,
+    })?;
+    let new_bytes = rewrite(&bytes).map_err(|error| StorageEncryptionError::Io {
+        path: path.to_path_buf(),
+        source: std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+    })?;
+    if let Some(new_bytes) = new_bytes {
+        write_file_atomically(path, &new_bytes)
+            .map_err(|error| TaskRepositoryError::new(TaskRepositoryOperation::Save, error))?;
+    }
+    Ok(())
+}
+
+// 移行後の保存先を移行後の鍵で読み直し、全projectが読めることを確かめる
+fn verify_yaml_storage(
+    storage_directory: &str,
+    cipher: Option<StorageCipher>,
+    now: DateTime<Local>,
+) -> Result<StorageMigrationReport, StorageMigrationError> {
+    let mut repository = TaskRepository::new(storage_directory).with_storage_cipher(cipher);
+    repository.reload_if_changed(now)?;
+    let projects = project_yaml_by_key(repository.project_entries())?;
+    Ok(StorageMigrationReport {
+        projects: projects.len(),
+        tasks: projects.values().map(count_tasks).sum(),
+    })
+}
+
+fn open_existing_database(
+    storage_directory: &str,
+    database_path: &Path,
+    now: DateTime<Local>,
+) -> Result<SqliteTaskRepository, StorageMigrationError> {
+    if !database_path.is_file() {
+        return Err(StorageMigrationError::MissingDatabase(
+            database_path.to_path_buf(),
+        ));
+    }
+    let mut repository = SqliteTaskRepository::open_database(storage_directory, database_path)?;
+    repository.reload_if_changed(now)?;
+    Ok(repository)
+}
+
+// 移行先に既存のprojectがあると、同じkeyのprojectを上書きしてしまうため移行しない
+fn ensure_empty(destination: &dyn TaskRepositoryTrait) -> Result<(), StorageMigrationError> {
+    match destination.get_all_projects().len() {
+        0 => Ok(()),
+        projects => Err(StorageMigrationError::DestinationNotEmpty { projects }),
+    }
+}
+
+fn project_yaml_by_key(
+    entries: Vec<(String, &TaskHandle)>,
+) -> Result<BTreeMap<String, Yaml>, TaskTreeError> {
+    entries
+        .into_iter()
+        .map(|(project_key, root_task)| Ok((project_key, task_to_yaml(root_task)?)))
+        .collect()
+}
+
+fn count_tasks(task_yaml: &Yaml) -> usize {
+    1 + task_yaml["children"]
+        .as_vec()
+        .map_or(0, |children| children.iter().map(count_tasks).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::gateway::sqlite_task_repository::SQLITE_DATABASE_FILE_NAME;
+    use crate::entity::task::TaskAttr;
+    use crate::test_support::TestStorageDir;
+    use chrono::TimeZone;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn database_path(storage_dir: &TestStorageDir) -> PathBuf {
+        storage_dir.path.join(SQLITE_DATABASE_FILE_NAME)
+    }
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap()
+    }
+
+    fn save_yaml_projects(storage_dir: &TestStorageDir, names: &[&str]) {
+        let mut repository = TaskRepository::new(storage_dir.path_str());
+        repository.sync_clock(now()).unwrap();
+        for name in names {
+            let root = TaskHandle::with_identity(name, Uuid::new_v4(), now()).unwrap();
+            root.create_child(TaskAttr::with_identity("子", Uuid::new_v4(), now()))
+                .unwrap();
+            repository.start_new_project(root).unwrap();
+        }
+        repository.save().unwrap();
+    }
+
+    #[test]
+    fn yamlからsqliteへ移行し検証で全projectとtask数を返す() {
+        let storage_dir = TestStorageDir::new("storage-migration");
+        save_yaml_projects(&storage_dir, &["a", "b"]);
+
+        let report =
+            migrate_yaml_to_sqlite(storage_dir.path_str(), &database_path(&storage_dir), now())
+                .unwrap();
+
+        assert_eq!(
+            report,
+            StorageMigrationReport {
+                projects: 2,
+                tasks: 4
+            }
+        );
+    }
+
+    #[test]
+    fn sqliteからyamlへ戻すと元のdirectory名とtask木を復元する() {
+        let source_dir = TestStorageDir::new("storage-migration");
+        save_yaml_projects(&source_dir, &["a", "b"]);
+        migrate_yaml_to_sqlite(source_dir.path_str(), &database_path(&source_dir), now()).unwrap();
+        let destination_dir = TestStorageDir::new("storage-migration");
+        fs::copy(database_path(&source_dir), database_path(&destination_dir)).unwrap();
+
+        let report = migrate_sqlite_to_yaml(
+            destination_dir.path_str(),
+            &database_path(&destination_dir),
+            0,
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(report.projects, 2);
+        assert!(destination_dir
+            .path
+            .join("20261018-a")
+            .join("project.yaml")
+            .is_file());
+        assert_eq!(
+            fs::read(destination_dir.path.join("20261018-b/project.yaml")).unwrap(),
+            fs::read(source_dir.path.join("20261018-b/project.yaml")).unwrap()
+        );
+    }
+
+    #[test]
+    fn 移行先にprojectがあれば上書きせずerrorにする() {
+        let storage_dir = TestStorageDir::new("storage-migration");
+        save_yaml_projects(&storage_dir, &["a"]);
+        migrate_yaml_to_sqlite(storage_dir.path_str(), &database_path(&storage_dir), now())
+            .unwrap();
+
+        let error =
+            migrate_yaml_to_sqlite(storage_dir.path_str(), &database_path(&storage_dir), now())
+                .unwrap_err();
+
+        assert!(matches!(
+            error,
+            StorageMigrationError::DestinationNotEmpty { projects: 1 }
+        ));
+    }
+
+    #[test]
+    fn 検証は片方にだけ変更があるprojectを報告する() {
+        let storage_dir = TestStorageDir::new("storage-migration");
+        save_yaml_projects(&storage_dir, &["a"]);
+        migrate_yaml_to_sqlite(storage_dir.path_str(), &database_path(&storage_dir), now())
+            .unwrap();
+        let mut repository = TaskRepository::new(storage_dir.path_str());
+        repository.reload_if_changed(now()).unwrap();
+        repository.get_all_projects()[0]
+            .set_estimated_work_seconds(30 * 60)
+            .unwrap();
+        repository.save().unwrap();
+
+        let error =
+            verify_storage_backends(storage_dir.path_str(), &database_path(&storage_dir), now())
+                .unwrap_err();
+
+        assert!(matches!(
+            error,
+            StorageMigrationError::VerificationFailed { ref project, .. } if project == "20261018-a"
+        ));
+    }
+
+    #[test]
+    fn database未作成ならsqliteからの移行をerrorにする() {
+        let storage_dir = TestStorageDir::new("storage-migration");
+
+        let error = migrate_sqlite_to_yaml(
+            storage_dir.path_str(),
+            &database_path(&storage_dir),
+            0,
+            now(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(error, StorageMigrationError::MissingDatabase(_)));
+        assert!(!database_path(&storage_dir).exists());
+    }
+
+    fn write_key_file(storage_dir: &TestStorageDir, name: &str, key: u8) -> StorageKeySource {
+        let path = storage_dir.path.join(name);
+        fs::write(&path, [key; 32]).unwrap();
+        StorageKeySource::new(Some(path), None)
+    }
+
+    #[test]
+    fn 暗号化した保存先を同じ鍵で平文に戻すとproject_fileと履歴が元に戻る() {
+        use crate::adapter::gateway::storage_encryption::STORAGE_ENCRYPTION_FILE_NAME;
+        use crate::application::interface::StorageHistoryTrait;
+
+        let storage_dir = TestStorageDir::new("storage-migration");
+        save_yaml_projects(&storage_dir, &["a", "b"]);
+        let project_path = storage_dir.path.join("20261018-a/project.yaml");
+        let plain_bytes = fs::read(&project_path).unwrap();
+        let key_source = write_key_file(&storage_dir, "schronu.key", 5);
+        let history = StorageHistory::new(storage_dir.path_str(), 10);
+        let revision = history.list_revisions().unwrap()[0].revision;
+
+        let report = encrypt_yaml_storage(storage_dir.path_str(), &key_source, now()).unwrap();
+
+        assert_eq!(
+            report,
+            StorageMigrationReport {
+                projects: 2,
+                tasks: 4
+            }
+        );
+        assert!(is_encrypted(&fs::read(&project_path).unwrap()));
+        assert!(history.read_revision_tasks(Some(revision)).is_err());
+        // 途中で止まった場合と同じく、暗号化済みの保存先へ再実行しても同じ結果になる
+        assert_eq!(
+            encrypt_yaml_storage(storage_dir.path_str(), &key_source, now()).unwrap(),
+            report
+        );
+
+        let report = decrypt_yaml_storage(storage_dir.path_str(), &key_source, now()).unwrap();
+
+        assert_eq!(report.projects, 2);
+        assert_eq!(fs::read(&project_path).unwrap(), plain_bytes);
+        assert!(!storage_dir.path.join(STORAGE_ENCRYPTION_FILE_NAME).exists());
+        assert_eq!(
+            history.read_revision_tasks(Some(revision)).unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn 捨てた記録と負荷履歴と埋まっている時間も暗号化し平文に戻せる() {
+        let storage_dir = TestStorageDir::new("storage-migration");
+        save_yaml_projects(&storage_dir, &["a"]);
+        let trash_path = storage_dir.path.join(TRASH_FILE_NAME);
+        let load_history_path = storage_dir.path.join(LOAD_HISTORY_FILE_NAME);
+        let busy_blocks_path = storage_dir.path.join(BUSY_BLOCKS_FILE_NAME);
+        fs::write(&trash_path, "{\"name\":\"秘密\"}\n").unwrap();
+        fs::write(&load_history_path, "{\"date\":\"2026-10-18\"}\n").unwrap();
+        fs::write(&busy_blocks_path, "busy_blocks: []\n").unwrap();
+        let paths = [&trash_path, &load_history_path, &busy_blocks_path];
+        let plain_bytes = paths.map(|path| fs::read(path).unwrap());
+        let key_source = write_key_file(&storage_dir, "schronu.key", 5);
+
+        encrypt_yaml_storage(storage_dir.path_str(), &key_source, now()).unwrap();
+
+        for path in paths {
+            assert!(is_encrypted(&fs::read(path).unwrap()));
+        }
+
+        decrypt_yaml_storage(storage_dir.path_str(), &key_source, now()).unwrap();
+
+        for (path, bytes) in paths.iter().zip(plain_bytes) {
+            assert_eq!(fs::read(path).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn 違う鍵や平文の保存先は平文に戻さずerrorにする() {
+        let storage_dir = TestStorageDir::new("storage-migration");
+        save_yaml_projects(&storage_dir, &["a"]);
+        let project_path = storage_dir.path.join("20261018-a/project.yaml");
+
+        let error = decrypt_yaml_storage(
+            storage_dir.path_str(),
+            &write_key_file(&storage_dir, "schronu.key", 5),
+            now(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            StorageMigrationError::Encryption(StorageEncryptionError::NotEncrypted { .. })
+        ));
+
+        encrypt_yaml_storage(
+            storage_dir.path_str(),
+            &write_key_file(&storage_dir, "schronu.key", 5),
+            now(),
+        )
+        .unwrap();
+        let encrypted_bytes = fs::read(&project_path).unwrap();
+        let error = decrypt_yaml_storage(
+            storage_dir.path_str(),
+            &write_key_file(&storage_dir, "other.key", 6),
+            now(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            StorageMigrationError::Encryption(StorageEncryptionError::WrongKey { .. })
+        ));
+        assert_eq!(fs::read(&project_path).unwrap(), encrypted_bytes);
+    }
+}
@@ -0,0 +1,326 @@
+use crate::application::task_tree::TaskTreeNodeView;
+use crate::application::task_use_case::ApplicationError;
+use crate::entity::task::Status;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Opml,
+}
+
+impl ExportFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "opml" => Some(Self::Opml),
+            _ => None,
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "markdown" => Some(Self::Markdown),
+            "opml" => Some(Self::Opml),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Opml => "opml",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TaskExportError {
+    Application(ApplicationError),
+    Write { path: PathBuf, message: String },
+}
+
+impl fmt::Display for TaskExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Application(error) => write!(f, "{error}"),
+            Self::Write { path, message } => {
+                write!(f, "failed to write {}: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for TaskExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Application(error) => Some(error),
+            Self::Write { .. } => None,
+        }
+    }
+}
+
+impl From<ApplicationError> for TaskExportError {
+    fn from(error: ApplicationError) -> Self {
+        Self::Application(error)
+    }
+}
+
+pub fn render_task_tree(tree: &TaskTreeNodeView, format: ExportFormat) -> String {
+    let mut text = String::new();
+    match format {
+        ExportFormat::Markdown => write_markdown_node(&mut text, tree, 0),
+        ExportFormat::Opml => {
+            text.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+            text.push_str("<opml version=\"2.0\">\n");
+            text.push_str("  <head>\n");
+            text.push_str(&format!(
+                "    <title>{}</title>\n",
+                escape_xml(&tree.task.name)
+            ));
+            text.push_str("  </head>\n");
+            text.push_str("  <body>\n");
+            write_opml_node(&mut text, tree, 2);
+            text.push_str("  </body>\n");
+            text.push_str("</opml>\n");
+        }
+    }
+    text
+}
+
+pub fn write_task_export(path: &Path, text: &str) -> Result<(), TaskExportError> {
+    fs::write(path, text).map_err(|error| TaskExportError::Write {
+        path: path.into(),
+        message: error.to_string(),
+    })
+}
+
+// 取り込みと同じ「(30m)」「@2026-10-23 19:00」で書き、実績と保留は行末の<!-- -->に添える
+fn write_markdown_node(text: &mut String, node: &TaskTreeNodeView, depth: usize) {
+    let task = &node.task;
+    let mark = if task.original_status == Status::Done {
+        'x'
+    } else {
+        ' '
+    };
+    text.push_str(&format!("{}- [{mark}] {}", "  ".repeat(depth), task.name));
+    if let Some(estimate) = duration_label(task.estimated_work_seconds) {
+        text.push_str(&format!(" ({estimate})"));
+    }
+    if let Some(deadline_time) = task.deadline_time {
+        text.push_str(&format!(" @{}", deadline_time.format("%Y-%m-%d %H:%M")));
+    }
+    let mut notes = vec![];
+    if task.original_status == Status::Pending {
+        notes.push("status: pending".to_string());
+    }
+    if let Some(pending_until) = task.pending_until {
+        notes.push(format!(
+            "pending_until: {}",
+            pending_until.format("%Y-%m-%d %H:%M")
+        ));
+    }
+    if let Some(actual) = duration_label(task.actual_work_seconds) {
+        notes.push(format!("actual: {actual}"));
+    }
+    if !notes.is_empty() {
+        text.push_str(&format!(" <!-- {} -->", notes.join(", ")));
+    }
+    text.push('\n');
+    for child in &node.children {
+        write_markdown_node(text, child, depth + 1);
+    }
+}
+
+fn write_opml_node(text: &mut String, node: &TaskTreeNodeView, depth: usize) {
+    let task = &node.task;
+    let mut attributes = vec![
+        ("text", task.name.clone()),
+        ("status", task.original_status.to_string()),
+    ];
+    if task.estimated_work_seconds > 0 {
+        attributes.push((
+            "estimatedMinutes",
+            rounded_minutes(task.estimated_work_seconds).to_string(),
+        ));
+    }
+    if task.actual_work_seconds > 0 {
+        attributes.push((
+            "actualMinutes",
+            rounded_minutes(task.actual_work_seconds).to_string(),
+        ));
+    }
+    if let Some(deadline_time) = task.deadline_time {
+        attributes.push(("deadline", deadline_time.to_rfc3339()));
+    }
+    if let Some(pending_until) = task.pending_until {
+        attributes.push(("pendingUntil", pending_until.to_rfc3339()));
+    }
+    attributes.push(("taskId", task.id.to_string()));
+    let attributes = attributes
+        .iter()
+        .map(|(name, value)| format!(" {name}=\"{}\"", escape_xml(value)))
+        .collect::<String>();
+    let indent = "  ".repeat(depth);
+    if node.children.is_empty() {
+        text.push_str(&format!("{indent}<outline{attributes}/>\n"));
+        return;
+    }
+    text.push_str(&format!("{indent}<outline{attributes}>\n"));
+    for child in &node.children {
+        write_opml_node(text, child, depth + 1);
+    }
+    text.push_str(&format!("{indent}</outline>\n"));
+}
+
+fn rounded_minutes(seconds: i64) -> i64 {
+    (seconds + 30) / 60
+}
+
+fn duration_label(seconds: i64) -> Option<String> {
+    let minutes = rounded_minutes(seconds);
+    match (minutes / 60, minutes % 60) {
+        (0, 0) => None,
+        (0, minutes) => Some(format!("{minutes}m")),
+        (hours, 0) => Some(format!("{hours}h")),
+        (hours, minutes) => Some(format!("{hours}h{minutes}m")),
+    }
+}
+
+// 属性値に改行が入っても1行のoutlineに収まるよう、改行も文字参照にする
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::gateway::task_import::{parse_task_outline, ImportClock, ImportFormat};
+    use crate::application::interface::TaskRepositoryTrait;
+    use crate::application::task_import::import_task_outline;
+    use crate::application::task_tree::get_task_tree_without_schedule;
+    use crate::application::task_use_case::TaskFactory;
+    use crate::entity::datetime::temporal::ClockTime;
+    use crate::entity::datetime::BusinessDateTimePolicy;
+    use crate::entity::task::TaskHandle;
+    use crate::test_support::{new_task_attr_at, new_task_handle_at, TestTaskRepository};
+    use chrono::{DateTime, Local, TimeZone};
+    use uuid::Uuid;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap()
+    }
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap()
+    }
+
+    fn sample_tree() -> TaskHandle {
+        let root = new_task_handle_at("旅行 & 準備", now()).unwrap();
+        root.set_estimated_work_seconds(90 * 60).unwrap();
+        root.set_deadline_time_opt(Some(at(23, 19))).unwrap();
+        let ticket = root
+            .create_child(new_task_attr_at("切符を買う", now()))
+            .unwrap();
+        ticket.set_estimated_work_seconds(30 * 60).unwrap();
+        ticket.set_actual_work_seconds(40 * 60).unwrap();
+        ticket.set_deadline_time_opt(Some(at(22, 12))).unwrap();
+        ticket.set_orig_status(Status::Pending).unwrap();
+        ticket.set_pending_until(at(21, 6)).unwrap();
+        let done = root
+            .create_child(new_task_attr_at("宿を取る", now()))
+            .unwrap();
+        done.set_estimated_work_seconds(0).unwrap();
+        done.set_orig_status(Status::Done).unwrap();
+        root
+    }
+
+    fn tree_view(root: TaskHandle) -> TaskTreeNodeView {
+        let root_id = root.get_id().unwrap();
+        let repository = TestTaskRepository::new(vec![root], now());
+        get_task_tree_without_schedule(&repository, root_id, None).unwrap()
+    }
+
+    #[test]
+    fn markdownは取り込みで同じ木と見積と実績と〆切と状態に戻る() {
+        let text = render_task_tree(&tree_view(sample_tree()), ExportFormat::Markdown);
+
+        assert_eq!(
+            text,
+            "- [ ] 旅行 & 準備 (1h30m) @2026-10-23 19:00\n\
+             \x20 - [ ] 切符を買う (30m) @2026-10-22 12:00 <!-- status: pending, pending_until: 2026-10-21 06:00, actual: 40m -->\n\
+             \x20 - [x] 宿を取る\n"
+        );
+        let policy = BusinessDateTimePolicy::new(30);
+        let clock = ImportClock {
+            now: now(),
+            policy: &policy,
+            default_deadline_time: ClockTime::new(19, 0, 0).unwrap(),
+        };
+        let outline = parse_task_outline(&text, ImportFormat::Markdown, &clock).unwrap();
+        assert_eq!(outline.skipped_done_count, 0);
+        let root = &outline.tasks[0];
+        assert_eq!(root.name, "旅行 & 準備");
+        assert_eq!(root.status, Status::Todo);
+        assert_eq!(root.estimated_work_minutes, Some(90));
+        assert_eq!(root.actual_work_minutes, None);
+        assert_eq!(root.deadline_time, Some(at(23, 19)));
+        let ticket = &root.children[0];
+        assert_eq!(ticket.name, "切符を買う");
+        assert_eq!(ticket.status, Status::Pending);
+        assert_eq!(ticket.estimated_work_minutes, Some(30));
+        assert_eq!(ticket.actual_work_minutes, Some(40));
+        assert_eq!(ticket.deadline_time, Some(at(22, 12)));
+        assert_eq!(ticket.pending_until, Some(at(21, 6)));
+        let done = &root.children[1];
+        assert_eq!(done.name, "宿を取る");
+        assert_eq!(done.status, Status::Done);
+
+        let mut repository = TestTaskRepository::new(vec![], now());
+        let mut next_id = Uuid::new_v4;
+        let mut factory = TaskFactory::new(now(), &mut next_id);
+        let task_ids = import_task_outline(&mut repository, &outline, None, &mut factory).unwrap();
+        let imported = repository.get_by_id(task_ids[0]).unwrap().unwrap();
+        let children = imported.get_children().unwrap();
+        assert_eq!(children[0].get_orig_status().unwrap(), Status::Pending);
+        assert_eq!(children[0].get_pending_until().unwrap(), at(21, 6));
+        assert_eq!(children[0].get_actual_work_seconds().unwrap(), 40 * 60);
+        assert_eq!(children[1].get_orig_status().unwrap(), Status::Done);
+    }
+
+    #[test]
+    fn opmlは属性に見積と実績と〆切と状態を持ち文字をescapeする() {
+        let root = sample_tree();
+        let root_id = root.get_id().unwrap();
+        let text = render_task_tree(&tree_view(root), ExportFormat::Opml);
+
+        assert!(text
+            .starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n"));
+        assert!(text.contains("    <title>旅行 &amp; 準備</title>\n"));
+        assert!(text.contains(&format!(
+            "    <outline text=\"旅行 &amp; 準備\" status=\"todo\" estimatedMinutes=\"90\" deadline=\"{}\" taskId=\"{root_id}\">\n",
+            at(23, 19).to_rfc3339()
+        )));
+        assert!(text.contains(&format!(
+            "status=\"pending\" estimatedMinutes=\"30\" actualMinutes=\"40\" deadline=\"{}\" pendingUntil=\"{}\"",
+            at(22, 12).to_rfc3339(),
+            at(21, 6).to_rfc3339()
+        )));
+        assert!(text.contains("<outline text=\"宿を取る\" status=\"done\""));
+        assert!(text.ends_with("    </outline>\n  </body>\n</opml>\n"));
+    }
+}
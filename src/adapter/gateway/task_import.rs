@@ -0,0 +1,671 @@
+use crate::application::task_import::{ImportedTask, TaskOutline, TaskOutlineError};
+use crate::application::task_use_case::resolve_local_datetime;
+use crate::entity::datetime::temporal::{ClockTime, TemporalExpression};
+use crate::entity::datetime::BusinessDateTimePolicy;
+use crate::entity::task::Status;
+use chrono::{DateTime, Local};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    Markdown,
+    Org,
+    TodoTxt,
+}
+
+impl ImportFormat {
+    // 拡張子で形式を決める。.txtはtodo.txtとして読む
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "org" => Some(Self::Org),
+            "txt" => Some(Self::TodoTxt),
+            _ => None,
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "markdown" => Some(Self::Markdown),
+            "org" => Some(Self::Org),
+            "todo_txt" => Some(Self::TodoTxt),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Org => "org",
+            Self::TodoTxt => "todo_txt",
+        }
+    }
+}
+
+// 日時を解決する基準。時刻のない〆切はdefault_deadline_time、時刻のない保留は業務日の始まりまでにする
+pub struct ImportClock<'a> {
+    pub now: DateTime<Local>,
+    pub policy: &'a BusinessDateTimePolicy,
+    pub default_deadline_time: ClockTime,
+}
+
+pub fn read_task_outline(
+    path: &Path,
+    clock: &ImportClock<'_>,
+) -> Result<TaskOutline, TaskOutlineError> {
+    let format = ImportFormat::from_path(path)
+        .ok_or_else(|| TaskOutlineError::UnknownFormat(path.into()))?;
+    let text = fs::read_to_string(path).map_err(|error| TaskOutlineError::Read {
+        path: path.into(),
+        message: error.to_string(),
+    })?;
+    parse_task_outline(&text, format, clock)
+}
+
+// OrgとTodo.txtの完了済みの項目は子孫ごと読み飛ばし、その数をskipped_done_countに数える。
+// Markdownは書き出した木と往復できるよう、完了済みの項目も完了のtaskとして読む
+pub fn parse_task_outline(
+    text: &str,
+    format: ImportFormat,
+    clock: &ImportClock<'_>,
+) -> Result<TaskOutline, TaskOutlineError> {
+    match format {
+        ImportFormat::Markdown => parse_markdown(text, clock).map(build_outline),
+        ImportFormat::Org => parse_org(text, clock).map(build_outline),
+        ImportFormat::TodoTxt => parse_todo_txt(text, clock),
+    }
+}
+
+// skippedは完了済みとして子孫ごと読み飛ばす項目
+struct OutlineEntry {
+    depth: usize,
+    task: ImportedTask,
+    skipped: bool,
+}
+
+fn build_outline(entries: Vec<OutlineEntry>) -> TaskOutline {
+    let mut outline = TaskOutline::default();
+    let mut ancestors: Vec<(usize, ImportedTask)> = vec![];
+    let mut skipped_depth = None;
+    for entry in entries {
+        if let Some(depth) = skipped_depth {
+            if entry.depth > depth {
+                outline.skipped_done_count += 1;
+                continue;
+            }
+            skipped_depth = None;
+        }
+        close_ancestors(&mut ancestors, entry.depth, &mut outline);
+        if entry.skipped {
+            outline.skipped_done_count += 1;
+            skipped_depth = Some(entry.depth);
+            continue;
+        }
+        ancestors.push((entry.depth, entry.task));
+    }
+    close_ancestors(&mut ancestors, 0, &mut outline);
+    outline
+}
+
+// depth以上の深さの項目を閉じ、親の子か木の根に加える
+fn close_ancestors(
+    ancestors: &mut Vec<(usize, ImportedTask)>,
+    depth: usize,
+    outline: &mut TaskOutline,
+) {
+    while ancestors
+        .last()
+        .is_some_and(|(ancestor_depth, _)| *ancestor_depth >= depth)
+    {
+        let (_, task) = ancestors.pop().expect("last ancestor exists");
+        match ancestors.last_mut() {
+            Some((_, parent)) => parent.children.push(task),
+            None => outline.tasks.push(task),
+        }
+    }
+}
+
+// 見出しは段落の深さで、箇条書きは字下げで入れ子にする。箇条書きはどの見出しよりも深い
+const MARKDOWN_LIST_DEPTH: usize = 7;
+const TAB_WIDTH: usize = 4;
+
+fn parse_markdown(
+    text: &str,
+    clock: &ImportClock<'_>,
+) -> Result<Vec<OutlineEntry>, TaskOutlineError> {
+    let heading = Regex::new(r"^(#{1,6})\s+(.*?)(?:\s+#+)?\s*$").unwrap();
+    let item = Regex::new(r"^([ \t]*)(?:[-*+]|\d+[.)])\s+(?:\[([ xX])\]\s*)?(.*)$").unwrap();
+    let mut entries = vec![];
+    let mut in_code_block = false;
+    for (index, line) in text.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        let (depth, done, title) = if let Some(captures) = heading.captures(line) {
+            (captures[1].len(), false, captures.get(2).unwrap().as_str())
+        } else if let Some(captures) = item.captures(line) {
+            let indent = captures[1]
+                .chars()
+                .map(|character| if character == '\t' { TAB_WIDTH } else { 1 })
+                .sum::<usize>();
+            let done = captures
+                .get(2)
+                .is_some_and(|mark| mark.as_str().eq_ignore_ascii_case("x"));
+            (
+                MARKDOWN_LIST_DEPTH + indent,
+                done,
+                captures.get(3).unwrap().as_str(),
+            )
+        } else {
+            continue;
+        };
+        let mut task =
+            parse_markdown_title(title, clock).map_err(|reason| TaskOutlineError::Line {
+                line: index + 1,
+                reason,
+            })?;
+        if done {
+            task.status = Status::Done;
+        }
+        entries.push(OutlineEntry {
+            depth,
+            task,
+            skipped: false,
+        });
+    }
+    Ok(entries)
+}
+
+// 名前の後ろの「(30m)」を見積、「@金 18:00」を〆切として読む。日時として読めない@はそのまま名前に残す
+// 行末の<!-- ... -->は書き出した注記で、状態と保留と実績を読む
+fn parse_markdown_title(title: &str, clock: &ImportClock<'_>) -> Result<ImportedTask, String> {
+    let mut task = ImportedTask::new("");
+    let title = match title
+        .trim_end()
+        .strip_suffix("-->")
+        .and_then(|rest| rest.rsplit_once("<!--"))
+    {
+        Some((title, comment)) => {
+            parse_markdown_comment(comment, clock, &mut task)?;
+            title
+        }
+        None => title,
+    };
+    let tokens = title.split_whitespace().collect::<Vec<_>>();
+    let mut words = vec![];
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = tokens[index];
+        index += 1;
+        if let Some(minutes) = token
+            .strip_prefix('(')
+            .and_then(|token| token.strip_suffix(')'))
+            .and_then(parse_duration_minutes)
+        {
+            if task.estimated_work_minutes.replace(minutes).is_some() {
+                return Err(format!("duplicate estimate: {token}"));
+            }
+            continue;
+        }
+        let Some(date) = token.strip_prefix('@').filter(|date| !date.is_empty()) else {
+            words.push(token);
+            continue;
+        };
+        let time = tokens
+            .get(index)
+            .filter(|time| is_clock_time(time))
+            .copied();
+        let expression = match time {
+            Some(time) => TemporalExpression::parse(&[date, time]),
+            None => TemporalExpression::parse_str(date),
+        };
+        let Ok(expression) = expression else {
+            words.push(token);
+            continue;
+        };
+        if time.is_some() {
+            index += 1;
+        }
+        let deadline_time = resolve_datetime(&expression, clock, clock.default_deadline_time)?;
+        if task.deadline_time.replace(deadline_time).is_some() {
+            return Err(format!("duplicate deadline: {token}"));
+        }
+    }
+    task.name = non_empty_name(words)?;
+    Ok(task)
+}
+
+// 知らないfieldは他の道具が添えた注記として読み飛ばす
+fn parse_markdown_comment(
+    comment: &str,
+    clock: &ImportClock<'_>,
+    task: &mut ImportedTask,
+) -> Result<(), String> {
+    for field in comment.split(',') {
+        let Some((key, value)) = field.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "status" => {
+                task.status = match value {
+                    "todo" => Status::Todo,
+                    "pending" => Status::Pending,
+                    "done" => Status::Done,
+                    _ => return Err(format!("invalid status: {value}")),
+                };
+            }
+            "pending_until" => {
+                let tokens = value.split_whitespace().collect::<Vec<_>>();
+                let expression = TemporalExpression::parse(&tokens)
+                    .map_err(|_| format!("invalid pending date: {value}"))?;
+                task.pending_until = Some(resolve_datetime(
+                    &expression,
+                    clock,
+                    ClockTime::business_day_start(),
+                )?);
+            }
+            "actual" => {
+                task.actual_work_minutes = Some(
+                    parse_duration_minutes(value)
+                        .ok_or_else(|| format!("invalid actual: {value}"))?,
+                );
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+const ORG_OPEN_KEYWORDS: &[&str] = &["TODO", "NEXT", "WAIT", "WAITING", "HOLD", "SOMEDAY"];
+const ORG_DONE_KEYWORDS: &[&str] = &["DONE", "CANCELED", "CANCELLED"];
+
+fn parse_org(text: &str, clock: &ImportClock<'_>) -> Result<Vec<OutlineEntry>, TaskOutlineError> {
+    let heading = Regex::new(r"^(\*+)\s+(.*)$").unwrap();
+    let priority = Regex::new(r"^\[#[A-Za-z0-9]\]\s*").unwrap();
+    let cookie = Regex::new(r"\s*\[\d*(?:/\d*|%)\]").unwrap();
+    let tags = Regex::new(r"\s+:(?:[^\s:]+:)+\s*$").unwrap();
+    let timestamp = Regex::new(r"(SCHEDULED|DEADLINE):\s*<([^>]*)>").unwrap();
+    let effort = Regex::new(r"(?i)^\s*:effort:\s*(\S+)\s*$").unwrap();
+    let mut entries: Vec<OutlineEntry> = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line_error = |reason| TaskOutlineError::Line {
+            line: index + 1,
+            reason,
+        };
+        if let Some(captures) = heading.captures(line) {
+            let mut title = captures.get(2).unwrap().as_str().trim();
+            let mut done = false;
+            if let Some((keyword, rest)) = title.split_once(' ').or(Some((title, ""))) {
+                if ORG_DONE_KEYWORDS.contains(&keyword) {
+                    done = true;
+                    title = rest.trim_start();
+                } else if ORG_OPEN_KEYWORDS.contains(&keyword) {
+                    title = rest.trim_start();
+                }
+            }
+            let title = priority.replace(title, "");
+            let title = tags.replace(&title, "");
+            let title = cookie.replace_all(&title, "");
+            let name = non_empty_name(title.split_whitespace().collect()).map_err(line_error)?;
+            entries.push(OutlineEntry {
+                depth: captures[1].len(),
+                task: ImportedTask::new(name),
+                skipped: done,
+            });
+            continue;
+        }
+        // 最初の見出しより前の行と、見出しの本文は読み飛ばす
+        let Some(entry) = entries.last_mut() else {
+            continue;
+        };
+        for captures in timestamp.captures_iter(line) {
+            let (default_time, target) = if &captures[1] == "DEADLINE" {
+                (clock.default_deadline_time, &mut entry.task.deadline_time)
+            } else {
+                (
+                    ClockTime::business_day_start(),
+                    &mut entry.task.pending_until,
+                )
+            };
+            let datetime =
+                parse_org_timestamp(&captures[2], clock, default_time).map_err(line_error)?;
+            *target = Some(datetime);
+        }
+        if let Some(captures) = effort.captures(line) {
+            let minutes = parse_effort_minutes(&captures[1])
+                .ok_or_else(|| line_error(format!("invalid effort: {}", &captures[1])))?;
+            entry.task.estimated_work_minutes = Some(minutes);
+        }
+    }
+    Ok(entries)
+}
+
+// <2026-10-20 Tue 10:00-11:00 +1w> の日付と開始時刻だけを使う
+fn parse_org_timestamp(
+    value: &str,
+    clock: &ImportClock<'_>,
+    default_time: ClockTime,
+) -> Result<DateTime<Local>, String> {
+    let mut tokens = value.split_whitespace();
+    let date = tokens
+        .next()
+        .ok_or_else(|| format!("invalid timestamp: <{value}>"))?;
+    let time = tokens
+        .map(|token| token.split('-').next().unwrap_or(token))
+        .find(|token| is_clock_time(token));
+    let expression = match time {
+        Some(time) => TemporalExpression::parse(&[date, time]),
+        None => TemporalExpression::parse_str(date),
+    }
+    .map_err(|_| format!("invalid timestamp: <{value}>"))?;
+    resolve_datetime(&expression, clock, default_time)
+}
+
+// 1行1task。最初の+projectが同じtaskは、そのproject名のtaskの下にまとめる
+fn parse_todo_txt(text: &str, clock: &ImportClock<'_>) -> Result<TaskOutline, TaskOutlineError> {
+    let priority = Regex::new(r"^\([A-Z]\)$").unwrap();
+    let date = Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap();
+    let key_value = Regex::new(r"^([A-Za-z][\w-]*):(\S+)$").unwrap();
+    let mut outline = TaskOutline::default();
+    let mut project_indices = HashMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_error = |reason| TaskOutlineError::Line {
+            line: index + 1,
+            reason,
+        };
+        let mut tokens = line.split_whitespace().peekable();
+        if tokens.peek().is_none() {
+            continue;
+        }
+        if tokens.next_if_eq(&"x").is_some() {
+            outline.skipped_done_count += 1;
+            continue;
+        }
+        tokens.next_if(|token| priority.is_match(token));
+        while tokens.next_if(|token| date.is_match(token)).is_some() {}
+
+        let mut task = ImportedTask::new("");
+        let mut project = None;
+        let mut words = vec![];
+        for token in tokens {
+            if let Some(name) = token.strip_prefix('+').filter(|name| !name.is_empty()) {
+                project.get_or_insert(name);
+                continue;
+            }
+            if token.len() > 1 && token.starts_with('@') {
+                continue;
+            }
+            let Some(captures) = key_value
+                .captures(token)
+                .filter(|captures| !captures[2].starts_with("//"))
+            else {
+                words.push(token);
+                continue;
+            };
+            let value = &captures[2];
+            match &captures[1] {
+                "due" => {
+                    task.deadline_time = Some(
+                        parse_date_value(value, clock, clock.default_deadline_time)
+                            .map_err(line_error)?,
+                    );
+                }
+                "t" => {
+                    task.pending_until = Some(
+                        parse_date_value(value, clock, ClockTime::business_day_start())
+                            .map_err(line_error)?,
+                    );
+                }
+                "est" => {
+                    task.estimated_work_minutes = Some(
+                        parse_effort_minutes(value)
+                            .ok_or_else(|| line_error(format!("invalid estimate: {token}")))?,
+                    );
+                }
+                _ => {}
+            }
+        }
+        task.name = non_empty_name(words).map_err(line_error)?;
+        match project {
+            Some(project) => {
+                let project_index = *project_indices.entry(project).or_insert_with(|| {
+                    outline.tasks.push(ImportedTask::new(project));
+                    outline.tasks.len() - 1
+                });
+                outline.tasks[project_index].children.push(task);
+            }
+            None => outline.tasks.push(task),
+        }
+    }
+    Ok(outline)
+}
+
+fn parse_date_value(
+    value: &str,
+    clock: &ImportClock<'_>,
+    default_time: ClockTime,
+) -> Result<DateTime<Local>, String> {
+    let expression =
+        TemporalExpression::parse_str(value).map_err(|_| format!("invalid date: {value}"))?;
+    resolve_datetime(&expression, clock, default_time)
+}
+
+fn resolve_datetime(
+    expression: &TemporalExpression,
+    clock: &ImportClock<'_>,
+    default_time: ClockTime,
+) -> Result<DateTime<Local>, String> {
+    let local_datetime = expression
+        .resolve(clock.now, clock.policy, default_time)
+        .map_err(|error| error.to_string())?;
+    resolve_local_datetime(local_datetime, local_datetime.and_local_timezone(Local))
+        .map_err(|error| error.to_string())
+}
+
+fn is_clock_time(token: &str) -> bool {
+    matches!(
+        TemporalExpression::parse_str(token),
+        Ok(TemporalExpression {
+            date: None,
+            time: Some(_),
+        })
+    )
+}
+
+fn non_empty_name(words: Vec<&str>) -> Result<String, String> {
+    if words.is_empty() {
+        return Err("task name must not be empty".to_string());
+    }
+    Ok(words.join(" "))
+}
+
+// 1h30m・90m・1時間30分のような単位つきの長さ
+fn parse_duration_minutes(value: &str) -> Option<i64> {
+    let duration = Regex::new(r"^(?:(\d+)(?:h|時間))?(?:(\d+)(?:m|min|分))?$").unwrap();
+    let captures = duration.captures(value)?;
+    let number = |index| {
+        captures
+            .get(index)
+            .map_or(Some(0), |digits| digits.as_str().parse::<i64>().ok())
+    };
+    if captures.get(1).is_none() && captures.get(2).is_none() {
+        return None;
+    }
+    number(1)?.checked_mul(60)?.checked_add(number(2)?)
+}
+
+// Orgの見積はH:MMか分の数。単位つきの長さも受け付ける
+fn parse_effort_minutes(value: &str) -> Option<i64> {
+    if let Some((hours, minutes)) = value.split_once(':') {
+        let hours = hours.parse::<i64>().ok()?;
+        let minutes = minutes
+            .parse::<i64>()
+            .ok()
+            .filter(|minutes| *minutes < 60)?;
+        return hours.checked_mul(60)?.checked_add(minutes);
+    }
+    value
+        .parse::<i64>()
+        .ok()
+        .or_else(|| parse_duration_minutes(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Weekday};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap()
+    }
+
+    fn policy() -> BusinessDateTimePolicy {
+        BusinessDateTimePolicy::new(30).with_non_business_weekdays(&[Weekday::Sat, Weekday::Sun])
+    }
+
+    fn parse(text: &str, format: ImportFormat) -> Result<TaskOutline, TaskOutlineError> {
+        let policy = policy();
+        let clock = ImportClock {
+            now: now(),
+            policy: &policy,
+            default_deadline_time: ClockTime::new(19, 0, 0).unwrap(),
+        };
+        parse_task_outline(text, format, &clock)
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> Option<DateTime<Local>> {
+        Some(
+            Local
+                .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+                .unwrap(),
+        )
+    }
+
+    fn names(tasks: &[ImportedTask]) -> Vec<&str> {
+        tasks.iter().map(|task| task.name.as_str()).collect()
+    }
+
+    #[test]
+    fn markdownの見出しと入れ子の箇条書きを木にし見積と〆切を読む() {
+        let outline = parse(
+            "# 旅行の準備\n\
+             メモは読み飛ばす\n\
+             - [ ] 切符を買う (30m) @2026-10-21\n\
+             \x20 - [ ] 座席を選ぶ (1h30m) @10/20 18:00\n\
+             - [x] 宿を取る\n\
+             \x20 - [ ] 完了した親の子\n\
+             - 持ち物を書き出す @未定\n\
+             ## 当日\n\
+             * [ ] 出発\n",
+            ImportFormat::Markdown,
+        )
+        .unwrap();
+
+        assert_eq!(outline.skipped_done_count, 0);
+        assert_eq!(names(&outline.tasks), vec!["旅行の準備"]);
+        let root = &outline.tasks[0];
+        assert_eq!(
+            names(&root.children),
+            vec!["切符を買う", "宿を取る", "持ち物を書き出す @未定", "当日"]
+        );
+        let ticket = &root.children[0];
+        assert_eq!(ticket.status, Status::Todo);
+        assert_eq!(ticket.estimated_work_minutes, Some(30));
+        assert_eq!(ticket.deadline_time, at(21, 19, 0));
+        assert_eq!(ticket.children[0].estimated_work_minutes, Some(90));
+        assert_eq!(ticket.children[0].deadline_time, at(20, 18, 0));
+        let done = &root.children[1];
+        assert_eq!(done.status, Status::Done);
+        assert_eq!(names(&done.children), vec!["完了した親の子"]);
+        assert_eq!(done.children[0].status, Status::Todo);
+        assert_eq!(names(&root.children[3].children), vec!["出発"]);
+    }
+
+    #[test]
+    fn orgの見出しからキーワードと優先度とタグを除き予定と〆切と見積を読む() {
+        let outline = parse(
+            "#+TITLE: 計画\n\
+             * TODO [#A] 引っ越し [1/2] :home:\n\
+             DEADLINE: <2026-10-30 Fri>\n\
+             ** NEXT 業者を選ぶ\n\
+             SCHEDULED: <2026-10-20 Tue 10:00-11:00> DEADLINE: <2026-10-22 Thu 17:00>\n\
+             :PROPERTIES:\n\
+             :Effort:   1:30\n\
+             :END:\n\
+             ** DONE 見積を取る\n\
+             *** 完了した親の子\n\
+             * 別の計画\n",
+            ImportFormat::Org,
+        )
+        .unwrap();
+
+        assert_eq!(outline.skipped_done_count, 2);
+        assert_eq!(names(&outline.tasks), vec!["引っ越し", "別の計画"]);
+        let root = &outline.tasks[0];
+        assert_eq!(root.deadline_time, at(30, 19, 0));
+        assert_eq!(names(&root.children), vec!["業者を選ぶ"]);
+        let child = &root.children[0];
+        assert_eq!(child.pending_until, at(20, 10, 0));
+        assert_eq!(child.deadline_time, at(22, 17, 0));
+        assert_eq!(child.estimated_work_minutes, Some(90));
+    }
+
+    #[test]
+    fn todo_txtの行を最初のprojectごとにまとめ完了した行を読み飛ばす() {
+        let outline = parse(
+            "(A) 2026-10-18 請求書を送る +経理 @office due:2026-10-23 est:45m\n\
+             x 2026-10-17 2026-10-10 領収書を整理 +経理\n\
+             \n\
+             散歩 t:2026-10-20 url:https://example.com\n\
+             経費を申請 +経理 +月末\n",
+            ImportFormat::TodoTxt,
+        )
+        .unwrap();
+
+        assert_eq!(outline.skipped_done_count, 1);
+        assert_eq!(names(&outline.tasks), vec!["経理", "散歩"]);
+        let project = &outline.tasks[0];
+        assert_eq!(names(&project.children), vec!["請求書を送る", "経費を申請"]);
+        assert_eq!(project.children[0].deadline_time, at(23, 19, 0));
+        assert_eq!(project.children[0].estimated_work_minutes, Some(45));
+        assert_eq!(outline.tasks[1].pending_until, at(20, 6, 0));
+    }
+
+    #[test]
+    fn 名前のない行や読めない日付は行番号つきで拒否する() {
+        assert_eq!(
+            parse("- [ ] 書類\n- [ ] (30m)\n", ImportFormat::Markdown),
+            Err(TaskOutlineError::Line {
+                line: 2,
+                reason: "task name must not be empty".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("* 書類\nDEADLINE: <someday>\n", ImportFormat::Org),
+            Err(TaskOutlineError::Line {
+                line: 2,
+                reason: "invalid timestamp: <someday>".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("書類 est:長め\n", ImportFormat::TodoTxt),
+            Err(TaskOutlineError::Line {
+                line: 1,
+                reason: "invalid estimate: est:長め".to_string(),
+            })
+        );
+        assert_eq!(
+            ImportFormat::from_path(Path::new("plan.markdown")),
+            Some(ImportFormat::Markdown)
+        );
+        assert_eq!(ImportFormat::from_path(Path::new("plan.json")), None);
+    }
+}
@@ -1,8 +1,14 @@
+use crate::adapter::gateway::storage_encryption::{
+    decode_storage_file, encode_storage_file, StorageCipher,
+};
+use crate::adapter::gateway::storage_history::{
+    StorageHistory, DEFAULT_STORAGE_HISTORY_RETENTION_REVISIONS,
+};
 use crate::adapter::gateway::yaml::yaml_to_task;
 #[cfg(test)]
 use crate::adapter::gateway::yaml::YamlConversionError;
 use crate::application::interface::{
-    RepositoryReloadOutcome, TaskRepositoryError,
+    RepositoryReloadOutcome, StorageHistoryError, TaskRepositoryError,
     TaskRepositoryOperation as ApplicationRepositoryOperation, TaskRepositoryTrait,
 };
 use crate::entity::task::extract_leaf_tasks_from_project;
@@ -14,13 +20,16 @@ use chrono::{DateTime, Local};
 use linked_hash_map::LinkedHashMap;
 use regex::Regex;
 use std::cell::{Cell, RefCell};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::fs;
 use std::fs::File;
+use std::hash::{Hash, Hasher};
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use uuid::Uuid;
 use walkdir::WalkDir;
 use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
@@ -32,6 +41,11 @@ pub struct TaskRepository {
     id_to_task_map: RefCell<HashMap<Uuid, TaskHandle>>,
     storage_revision: Cell<Option<Uuid>>,
     has_loaded: bool,
+    history_retention_revisions: usize,
+    // remove_projectで取り除き、次のsaveで消すproject.yaml
+    removed_project_yaml_file_paths: RefCell<Vec<PathBuf>>,
+    // 暗号化した保存先では、project.yamlと保存履歴をこの鍵で読み書きする
+    cipher: Option<StorageCipher>,
 }
 
 struct Project {
@@ -40,6 +54,40 @@ struct Project {
     project_yaml_file_path: PathBuf,
     priority: i64,
     persisted_mutation_revision: Cell<Option<u64>>,
+    fingerprint: Cell<Option<ProjectFileFingerprint>>,
+}
+
+// reload時に再解析が必要かを判定するproject.yamlの指紋。
+// mtimeとsizeが一致すれば読み直さず、異なっても内容のhashが一致すれば既存のtask treeを使い続ける
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct ProjectFileFingerprint {
+    modified: Option<SystemTime>,
+    len: u64,
+    content_hash: u64,
+}
+
+impl ProjectFileFingerprint {
+    fn new(metadata: &fs::Metadata, bytes: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+            content_hash: hasher.finish(),
+        }
+    }
+
+    fn has_same_metadata(&self, metadata: &fs::Metadata) -> bool {
+        self.modified.is_some()
+            && self.modified == metadata.modified().ok()
+            && self.len == metadata.len()
+    }
+}
+
+// 差分reloadで1つのproject.yamlをどう扱ったか
+enum LoadedProject {
+    Kept(usize),
+    Parsed(Project),
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -51,16 +99,18 @@ enum FileRepositoryOperation {
     ParseProject,
     ParseRevision,
     SerializeProject,
+    DecryptFile,
     CreateDirectory,
     CreateFile,
     WriteFile,
     SyncFile,
     SetPermissions,
     RenameFile,
+    RemoveFile,
 }
 
 #[derive(Debug)]
-struct FileRepositoryError {
+pub(super) struct FileRepositoryError {
     operation: FileRepositoryOperation,
     path: PathBuf,
     source: std::io::Error,
@@ -207,7 +257,7 @@ fn write_file_atomically_if_changed_with_temporary_path(
     Ok(true)
 }
 
-fn write_file_atomically(
+pub(super) fn write_file_atomically(
     target_file_path: &Path,
     bytes: &[u8],
 ) -> Result<bool, FileRepositoryError> {
@@ -237,9 +287,19 @@ impl Project {
             project_yaml_file_path: project_yaml_file_path.into(),
             priority,
             persisted_mutation_revision: Cell::new(None),
+            fingerprint: Cell::new(None),
         }
     }
 
+    // 保存・読込した内容とdisk上のmetadataから指紋を更新する。metadataが読めなければ次回は読み直す
+    fn record_fingerprint(&self, bytes: &[u8]) {
+        self.fingerprint.set(
+            fs::metadata(&self.project_yaml_file_path)
+                .ok()
+                .map(|metadata| ProjectFileFingerprint::new(&metadata, bytes)),
+        );
+    }
+
     fn mark_clean(&self) -> Result<(), TaskTreeError> {
         self.persisted_mutation_revision
             .set(Some(self.root_task.get_persistent_mutation_revision()?));
@@ -261,9 +321,64 @@ impl TaskRepository {
             id_to_task_map: RefCell::new(HashMap::new()),
             storage_revision: Cell::new(None),
             has_loaded: false,
+            history_retention_revisions: DEFAULT_STORAGE_HISTORY_RETENTION_REVISIONS,
+            removed_project_yaml_file_paths: RefCell::new(vec![]),
+            cipher: None,
         }
     }
 
+    // 保存ごとに.historyへ残す版の数。0なら履歴を記録しない
+    pub fn with_history_retention_revisions(mut self, revisions: usize) -> Self {
+        self.history_retention_revisions = revisions;
+        self
+    }
+
+    // open_storage_cipherで開いた鍵。Noneなら平文のまま読み書きする
+    pub fn with_storage_cipher(mut self, cipher: Option<StorageCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    // 保存先からの相対directory名をkeyとして、読み込んだprojectを返す。保存形式の移行で使う
+    pub(super) fn project_entries(&self) -> Vec<(String, &TaskHandle)> {
+        self.projects
+            .iter()
+            .map(|project| (self.project_key(project), &project.root_task))
+            .collect()
+    }
+
+    fn project_key(&self, project: &Project) -> String {
+        let relative_path = project
+            .project_dir_path
+            .strip_prefix(&self.project_storage_dir_name)
+            .unwrap_or(&project.project_dir_path);
+        relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    // 他の保存形式から移したprojectを、元のkeyをdirectory名として未保存のまま登録する
+    pub(super) fn import_project(
+        &mut self,
+        project_key: &str,
+        root_task: TaskHandle,
+    ) -> Result<(), TaskTreeError> {
+        let project_dir_path = Path::new(&self.project_storage_dir_name).join(project_key);
+        let project_yaml_file_path = project_dir_path.join("project.yaml");
+        let priority = root_task.get_priority()?;
+        let project = Project::new(
+            root_task,
+            project_dir_path,
+            project_yaml_file_path,
+            priority,
+        );
+        self.cache_task_and_descendants(&project.root_task)?;
+        self.projects.push(project);
+        Ok(())
+    }
+
     fn cache_task_and_descendants(&self, task: &TaskHandle) -> Result<(), TaskTreeError> {
         self.id_to_task_map
             .borrow_mut()
@@ -286,6 +401,32 @@ impl TaskRepository {
         Ok(())
     }
 
+    // 書き込む前の版の内容を失わないよう、project fileより先に履歴を記録する
+    fn record_storage_history(
+        &self,
+        revision: Uuid,
+        prepared_writes: &[(&Project, Vec<u8>)],
+    ) -> Result<(), StorageHistoryError> {
+        let history = StorageHistory::new(
+            &self.project_storage_dir_name,
+            self.history_retention_revisions,
+        );
+        let changed = prepared_writes
+            .iter()
+            .filter_map(|(project, bytes)| {
+                history
+                    .relative_project_path(&project.project_yaml_file_path)
+                    .map(|path| (path, bytes.as_slice()))
+            })
+            .collect::<Vec<_>>();
+        let all_projects = self
+            .projects
+            .iter()
+            .filter_map(|project| history.relative_project_path(&project.project_yaml_file_path))
+            .collect::<Vec<_>>();
+        history.record_revision(revision, Local::now(), &changed, &all_projects)
+    }
+
     fn storage_revision_path(&self) -> PathBuf {
         Path::new(&self.project_storage_dir_name).join(".revision")
     }
@@ -328,45 +469,27 @@ impl TaskRepository {
             })
     }
 
-    fn serialize_project(project: &Project) -> Result<Vec<u8>, TaskRepositoryError> {
-        let task_yaml = task_to_yaml(&project.root_task).map_err(|error| {
-            TaskRepositoryError::new(ApplicationRepositoryOperation::Save, error)
-        })?;
-        let mut project_hash = LinkedHashMap::new();
-        project_hash.insert(Yaml::String(String::from("project")), task_yaml);
-        let doc = Yaml::Hash(project_hash);
-        let mut out = String::new();
-        YamlEmitter::new(&mut out).dump(&doc).map_err(|error| {
-            TaskRepositoryError::new(
-                ApplicationRepositoryOperation::Save,
-                FileRepositoryError::new(
-                    FileRepositoryOperation::SerializeProject,
-                    &project.project_yaml_file_path,
-                    std::io::Error::new(std::io::ErrorKind::InvalidData, error),
-                ),
-            )
-        })?;
-        out.push('\n');
-        Ok(out.into_bytes())
-    }
-}
-
-impl TaskRepositoryTrait for TaskRepository {
-    fn get_project_storage_dir_name(&self) -> &str {
-        &self.project_storage_dir_name
-    }
-
-    fn get_all_projects(&self) -> Vec<&TaskHandle> {
-        self.projects
-            .iter()
-            .map(|project| &project.root_task)
-            .collect()
-    }
-
-    fn load(&mut self) -> Result<(), TaskRepositoryError> {
+    // reuses_unchanged_projectsなら、指紋が一致し未保存の変更もないprojectのtask treeをそのまま使い、
+    // 追加・変更されたproject.yamlだけを解析する。途中で失敗した場合はmemoryを変更しない
+    fn load_projects(
+        &mut self,
+        reuses_unchanged_projects: bool,
+    ) -> Result<(), TaskRepositoryError> {
         let storage_revision = self.read_storage_revision().map_err(|error| {
             TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
         })?;
+        let mut reusable_projects = HashMap::new();
+        if reuses_unchanged_projects {
+            for (index, project) in self.projects.iter().enumerate() {
+                let needs_save = project.needs_save().map_err(|error| {
+                    TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
+                })?;
+                if !needs_save {
+                    reusable_projects.insert(project.project_yaml_file_path.clone(), index);
+                }
+            }
+        }
+
         let mut loaded_projects = Vec::new();
         for entry_result in WalkDir::new(self.project_storage_dir_name.as_str()).sort_by_file_name()
         {
@@ -388,102 +511,62 @@ impl TaskRepositoryTrait for TaskRepository {
                     ),
                 )
             })?;
-            if entry.file_name() == "project.yaml" {
-                let project_yaml_file_path = entry.path().to_path_buf();
-                let project_dir_path = entry
-                    .path()
-                    .parent()
-                    .ok_or_else(|| {
-                        TaskRepositoryError::new(
-                            ApplicationRepositoryOperation::Load,
-                            FileRepositoryError::new(
-                                FileRepositoryOperation::ParseProject,
-                                &project_yaml_file_path,
-                                std::io::Error::new(
-                                    std::io::ErrorKind::InvalidData,
-                                    "project.yaml must have a parent directory",
-                                ),
-                            ),
-                        )
-                    })?
-                    .to_path_buf();
-                let mut file = File::open(entry.path()).map_err(|error| {
-                    TaskRepositoryError::new(
-                        ApplicationRepositoryOperation::Load,
-                        FileRepositoryError::new(
-                            FileRepositoryOperation::OpenFile,
-                            &project_yaml_file_path,
-                            error,
-                        ),
-                    )
-                })?;
-                let mut text = String::new();
-                file.read_to_string(&mut text).map_err(|error| {
-                    TaskRepositoryError::new(
-                        ApplicationRepositoryOperation::Load,
-                        FileRepositoryError::new(
-                            FileRepositoryOperation::ReadFile,
-                            &project_yaml_file_path,
-                            error,
-                        ),
-                    )
-                })?;
+            if entry.file_name() != "project.yaml" {
+                continue;
+            }
+            let reusable_index = reusable_projects.remove(entry.path());
+            if let Some(index) = reusable_index {
+                let fingerprint = self.projects[index].fingerprint.get();
+                if fs::metadata(entry.path()).is_ok_and(|metadata| {
+                    fingerprint.is_some_and(|fingerprint| fingerprint.has_same_metadata(&metadata))
+                }) {
+                    loaded_projects.push(LoadedProject::Kept(index));
+                    continue;
+                }
+            }
 
-                let docs = YamlLoader::load_from_str(&text).map_err(|error| {
-                    TaskRepositoryError::new(
-                        ApplicationRepositoryOperation::Load,
-                        FileRepositoryError::new(
-                            FileRepositoryOperation::ParseProject,
-                            &project_yaml_file_path,
-                            std::io::Error::new(std::io::ErrorKind::InvalidData, error),
-                        ),
-                    )
-                })?;
-                let project_yaml = docs
-                    .first()
-                    .map(|doc| &doc["project"])
-                    .filter(|yaml| yaml.as_hash().is_some())
-                    .ok_or_else(|| {
-                        TaskRepositoryError::new(
-                            ApplicationRepositoryOperation::Load,
-                            FileRepositoryError::new(
-                                FileRepositoryOperation::ParseProject,
-                                &project_yaml_file_path,
-                                std::io::Error::new(
-                                    std::io::ErrorKind::InvalidData,
-                                    "project document must contain a project mapping",
-                                ),
-                            ),
-                        )
-                    })?;
-                let root_task =
-                    yaml_to_task(project_yaml, self.last_synced_time).map_err(|error| {
-                        TaskRepositoryError::new(
-                            ApplicationRepositoryOperation::Load,
-                            FileRepositoryError::new(
-                                FileRepositoryOperation::ParseProject,
-                                &project_yaml_file_path,
-                                std::io::Error::new(std::io::ErrorKind::InvalidData, error),
-                            ),
-                        )
-                    })?;
-                let priority = root_task.get_priority().map_err(|error| {
-                    TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
-                })?;
-                let project = Project::new(
-                    root_task,
-                    project_dir_path,
-                    project_yaml_file_path,
-                    priority,
-                );
-                project.mark_clean().map_err(|error| {
-                    TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
-                })?;
-                loaded_projects.push(project);
+            let (bytes, fingerprint) = Self::read_project_file(entry.path())?;
+            if let Some(index) = reusable_index {
+                let project = &self.projects[index];
+                if project
+                    .fingerprint
+                    .get()
+                    .is_some_and(|previous| previous.content_hash == fingerprint.content_hash)
+                {
+                    project.fingerprint.set(Some(fingerprint));
+                    loaded_projects.push(LoadedProject::Kept(index));
+                    continue;
+                }
             }
+            let bytes = self.decode_project_file(entry.path(), &bytes)?;
+            let project = self.parse_project_file(entry.path(), &bytes)?;
+            project.fingerprint.set(Some(fingerprint));
+            loaded_projects.push(LoadedProject::Parsed(project));
         }
 
-        self.projects = loaded_projects;
+        let mut previous_projects = std::mem::take(&mut self.projects)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        let mut kept_projects = Vec::new();
+        for loaded_project in loaded_projects {
+            match loaded_project {
+                LoadedProject::Kept(index) => {
+                    let project = previous_projects[index]
+                        .take()
+                        .expect("each project is kept at most once");
+                    kept_projects.push(self.projects.len());
+                    self.projects.push(project);
+                }
+                LoadedProject::Parsed(project) => self.projects.push(project),
+            }
+        }
+        for index in kept_projects {
+            Self::sync_task_and_descendants(&self.projects[index].root_task, self.last_synced_time)
+                .map_err(|error| {
+                    TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
+                })?;
+        }
         self.id_to_task_map.borrow_mut().clear();
         for project in &self.projects {
             self.cache_task_and_descendants(&project.root_task)
@@ -492,10 +575,202 @@ impl TaskRepositoryTrait for TaskRepository {
                 })?;
         }
         self.storage_revision.set(storage_revision);
+        self.removed_project_yaml_file_paths.borrow_mut().clear();
         self.has_loaded = true;
         Ok(())
     }
 
+    // 取り除いたprojectのproject.yamlを消す。markdownなどのfileが残るためdirectoryは消さない
+    fn remove_project_files(&self) -> Result<(), TaskRepositoryError> {
+        let removed_paths = self.removed_project_yaml_file_paths.take();
+        for (index, path) in removed_paths.iter().enumerate() {
+            match fs::remove_file(path) {
+                Ok(()) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => {
+                    *self.removed_project_yaml_file_paths.borrow_mut() =
+                        removed_paths[index..].to_vec();
+                    return Err(TaskRepositoryError::new(
+                        ApplicationRepositoryOperation::Save,
+                        FileRepositoryError::new(FileRepositoryOperation::RemoveFile, path, error),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_project_file(
+        project_yaml_file_path: &Path,
+    ) -> Result<(Vec<u8>, ProjectFileFingerprint), TaskRepositoryError> {
+        let mut file = File::open(project_yaml_file_path).map_err(|error| {
+            TaskRepositoryError::new(
+                ApplicationRepositoryOperation::Load,
+                FileRepositoryError::new(
+                    FileRepositoryOperation::OpenFile,
+                    project_yaml_file_path,
+                    error,
+                ),
+            )
+        })?;
+        let mut bytes = Vec::new();
+        let metadata = file
+            .read_to_end(&mut bytes)
+            .and_then(|_| file.metadata())
+            .map_err(|error| {
+                TaskRepositoryError::new(
+                    ApplicationRepositoryOperation::Load,
+                    FileRepositoryError::new(
+                        FileRepositoryOperation::ReadFile,
+                        project_yaml_file_path,
+                        error,
+                    ),
+                )
+            })?;
+        let fingerprint = ProjectFileFingerprint::new(&metadata, &bytes);
+        Ok((bytes, fingerprint))
+    }
+
+    fn decode_project_file(
+        &self,
+        project_yaml_file_path: &Path,
+        bytes: &[u8],
+    ) -> Result<Vec<u8>, TaskRepositoryError> {
+        decode_storage_file(self.cipher.as_ref(), bytes).map_err(|error| {
+            TaskRepositoryError::new(
+                ApplicationRepositoryOperation::Load,
+                FileRepositoryError::new(
+                    FileRepositoryOperation::DecryptFile,
+                    project_yaml_file_path,
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+                ),
+            )
+        })
+    }
+
+    fn parse_project_file(
+        &self,
+        project_yaml_file_path: &Path,
+        bytes: &[u8],
+    ) -> Result<Project, TaskRepositoryError> {
+        let project_dir_path = project_yaml_file_path
+            .parent()
+            .ok_or_else(|| {
+                TaskRepositoryError::new(
+                    ApplicationRepositoryOperation::Load,
+                    FileRepositoryError::new(
+                        FileRepositoryOperation::ParseProject,
+                        project_yaml_file_path,
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "project.yaml must have a parent directory",
+                        ),
+                    ),
+                )
+            })?
+            .to_path_buf();
+        let text = std::str::from_utf8(bytes).map_err(|error| {
+            TaskRepositoryError::new(
+                ApplicationRepositoryOperation::Load,
+                FileRepositoryError::new(
+                    FileRepositoryOperation::ReadFile,
+                    project_yaml_file_path,
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+                ),
+            )
+        })?;
+
+        let docs = YamlLoader::load_from_str(text).map_err(|error| {
+            TaskRepositoryError::new(
+                ApplicationRepositoryOperation::Load,
+                FileRepositoryError::new(
+                    FileRepositoryOperation::ParseProject,
+                    project_yaml_file_path,
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+                ),
+            )
+        })?;
+        let project_yaml = docs
+            .first()
+            .map(|doc| &doc["project"])
+            .filter(|yaml| yaml.as_hash().is_some())
+            .ok_or_else(|| {
+                TaskRepositoryError::new(
+                    ApplicationRepositoryOperation::Load,
+                    FileRepositoryError::new(
+                        FileRepositoryOperation::ParseProject,
+                        project_yaml_file_path,
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "project document must contain a project mapping",
+                        ),
+                    ),
+                )
+            })?;
+        let root_task = yaml_to_task(project_yaml, self.last_synced_time).map_err(|error| {
+            TaskRepositoryError::new(
+                ApplicationRepositoryOperation::Load,
+                FileRepositoryError::new(
+                    FileRepositoryOperation::ParseProject,
+                    project_yaml_file_path,
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+                ),
+            )
+        })?;
+        let priority = root_task.get_priority().map_err(|error| {
+            TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
+        })?;
+        let project = Project::new(
+            root_task,
+            project_dir_path,
+            project_yaml_file_path.to_path_buf(),
+            priority,
+        );
+        project.mark_clean().map_err(|error| {
+            TaskRepositoryError::new(ApplicationRepositoryOperation::Load, error)
+        })?;
+        Ok(project)
+    }
+
+    fn serialize_project(project: &Project) -> Result<Vec<u8>, TaskRepositoryError> {
+        let task_yaml = task_to_yaml(&project.root_task).map_err(|error| {
+            TaskRepositoryError::new(ApplicationRepositoryOperation::Save, error)
+        })?;
+        let mut project_hash = LinkedHashMap::new();
+        project_hash.insert(Yaml::String(String::from("project")), task_yaml);
+        let doc = Yaml::Hash(project_hash);
+        let mut out = String::new();
+        YamlEmitter::new(&mut out).dump(&doc).map_err(|error| {
+            TaskRepositoryError::new(
+                ApplicationRepositoryOperation::Save,
+                FileRepositoryError::new(
+                    FileRepositoryOperation::SerializeProject,
+                    &project.project_yaml_file_path,
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+                ),
+            )
+        })?;
+        out.push('\n');
+        Ok(out.into_bytes())
+    }
+}
+
+impl TaskRepositoryTrait for TaskRepository {
+    fn get_project_storage_dir_name(&self) -> &str {
+        &self.project_storage_dir_name
+    }
+
+    fn get_all_projects(&self) -> Vec<&TaskHandle> {
+        self.projects
+            .iter()
+            .map(|project| &project.root_task)
+            .collect()
+    }
+
+    fn load(&mut self) -> Result<(), TaskRepositoryError> {
+        self.load_projects(false)
+    }
+
     fn reload_if_changed(
         &mut self,
         now: DateTime<Local>,
@@ -511,11 +786,15 @@ impl TaskRepositoryTrait for TaskRepository {
         }
 
         self.last_synced_time = now;
-        self.load()?;
+        let reuses_unchanged_projects = self.has_loaded;
+        self.load_projects(reuses_unchanged_projects)?;
         Ok(RepositoryReloadOutcome::Reloaded)
     }
 
     fn has_pending_changes(&self) -> Result<bool, TaskTreeError> {
+        if !self.removed_project_yaml_file_paths.borrow().is_empty() {
+            return Ok(true);
+        }
         self.projects
             .iter()
             .map(Project::needs_save)
@@ -537,14 +816,23 @@ impl TaskRepositoryTrait for TaskRepository {
         let mut prepared_writes = Vec::new();
         for project in &projects_to_save {
             let bytes = Self::serialize_project(project)?;
-            let unchanged = fs::read(&project.project_yaml_file_path)
-                .is_ok_and(|existing_bytes| existing_bytes == bytes);
-            if !unchanged {
-                prepared_writes.push((*project, bytes));
+            // 暗号文はnonceが毎回変わるため、平文に戻して比べる
+            let unchanged_bytes =
+                fs::read(&project.project_yaml_file_path)
+                    .ok()
+                    .filter(|existing_bytes| {
+                        decode_storage_file(self.cipher.as_ref(), existing_bytes)
+                            .is_ok_and(|existing_plaintext| existing_plaintext == bytes)
+                    });
+            match unchanged_bytes {
+                Some(existing_bytes) => project.record_fingerprint(&existing_bytes),
+                None => prepared_writes
+                    .push((*project, encode_storage_file(self.cipher.as_ref(), bytes))),
             }
         }
 
-        if prepared_writes.is_empty() {
+        let has_removed_projects = !self.removed_project_yaml_file_paths.borrow().is_empty();
+        if prepared_writes.is_empty() && !has_removed_projects {
             for project in projects_to_save {
                 project.mark_clean().map_err(|error| {
                     TaskRepositoryError::new(ApplicationRepositoryOperation::Save, error)
@@ -593,6 +881,10 @@ impl TaskRepositoryTrait for TaskRepository {
             ));
         }
         let new_storage_revision = Uuid::new_v4();
+        self.record_storage_history(new_storage_revision, &prepared_writes)
+            .map_err(|error| {
+                TaskRepositoryError::new(ApplicationRepositoryOperation::Save, error)
+            })?;
         let revision_text = format!("{new_storage_revision}\n");
         write_file_atomically(&revision_path, revision_text.as_bytes()).map_err(|error| {
             TaskRepositoryError::new(ApplicationRepositoryOperation::Save, error)
@@ -602,7 +894,9 @@ impl TaskRepositoryTrait for TaskRepository {
             write_file_atomically(&project.project_yaml_file_path, &bytes).map_err(|error| {
                 TaskRepositoryError::new(ApplicationRepositoryOperation::Save, error)
             })?;
+            project.record_fingerprint(&bytes);
         }
+        self.remove_project_files()?;
 
         for project in projects_to_save {
             project.mark_clean().map_err(|error| {
@@ -640,33 +934,7 @@ impl TaskRepositoryTrait for TaskRepository {
         // 最も優先度が高いprojectsが必要な場合はlast()で取得する
         self.projects.sort_by_key(|a| a.priority);
 
-        // 優先度が低いPJ順に見て、返すべき葉タスクのid値を更新していく
-        let mut ans = None;
-
-        for project in &self.projects {
-            let root_task = &project.root_task;
-
-            let leaf_tasks = extract_leaf_tasks_from_project(root_task)?;
-
-            for leaf_task in leaf_tasks.iter() {
-                let deadline_time_opt = leaf_task.get_deadline_time_opt()?;
-                let neg_priority = !leaf_task.get_priority()?;
-                let id = leaf_task.get_id()?;
-
-                let tpl = (
-                    deadline_time_opt.is_none(),
-                    deadline_time_opt,
-                    neg_priority,
-                    id,
-                );
-
-                if ans.is_none() || tpl < ans.unwrap() {
-                    ans = Some(tpl);
-                }
-            }
-        }
-
-        Ok(ans.map(|tpl| tpl.3))
+        select_highest_priority_leaf_task_id(self.projects.iter().map(|project| &project.root_task))
     }
 
     // 優先度の低いタスクを未来に飛ばすための先送り候補選択用
@@ -677,49 +945,25 @@ impl TaskRepositoryTrait for TaskRepository {
         // 副作用として、projectsを優先度の低い順に破壊的にソートする
         self.projects.sort_by_key(|a| a.priority);
 
-        // 優先度が低いPJ順に見て、返すべき葉タスクのid値を更新していく
-        let mut ans = None;
-        for project in &self.projects {
-            let root_task = &project.root_task;
-
-            let leaf_tasks = extract_leaf_tasks_from_project_with_pending(root_task)?;
-
-            for leaf_task in leaf_tasks.iter() {
-                if leaf_task.get_start_time()? >= recent_threshold
-                    || (leaf_task.get_orig_status()? == Status::Pending
-                        && leaf_task.get_pending_until()? >= recent_threshold)
-                {
-                    continue;
-                }
-
-                let deadline_time_opt = leaf_task.get_deadline_time_opt()?;
-                let first_available_time = leaf_task.first_available_time()?;
-                let is_recent = first_available_time < recent_threshold;
-                let neg_priority = !leaf_task.get_priority()?;
-                let id = leaf_task.get_id()?;
-
-                // 優先度が低いほど大さい値になる
-                let tpl = (
-                    deadline_time_opt.is_none(),
-                    is_recent,
-                    neg_priority,
-                    deadline_time_opt,
-                    first_available_time,
-                    id,
-                );
-
-                if ans.is_none() || tpl > ans.unwrap() {
-                    ans = Some(tpl);
-                }
-            }
-        }
-
-        Ok(ans.map(|tpl| tpl.5))
+        select_defer_candidate_leaf_task_id(
+            self.projects.iter().map(|project| &project.root_task),
+            recent_threshold,
+        )
     }
 
     fn get_by_id(&self, id: Uuid) -> Result<Option<TaskHandle>, TaskTreeError> {
-        if let Some(task) = self.id_to_task_map.borrow().get(&id).cloned() {
-            return Ok(Some(task));
+        let cached_task = self.id_to_task_map.borrow().get(&id).cloned();
+        if let Some(task) = cached_task {
+            // 捨てた部分木や取り除いたprojectのtaskはcacheに残るため、今のprojectに属するかを確かめる
+            let root = task.root()?;
+            if self
+                .projects
+                .iter()
+                .any(|project| project.root_task.ptr_eq(&root))
+            {
+                return Ok(Some(task));
+            }
+            self.id_to_task_map.borrow_mut().remove(&id);
         }
 
         for project in self.projects.iter() {
@@ -734,15 +978,7 @@ impl TaskRepositoryTrait for TaskRepository {
     }
 
     fn start_new_project(&mut self, root_task: TaskHandle) -> Result<(), TaskTreeError> {
-        let project_name = root_task.get_name()?;
-
-        let yyyymmdd = self.last_synced_time.format("%Y%m%d").to_string();
-
-        // ディレクトリ名からはURLを除く (ディレクトリの区切りに使われうる "/" が入らないようにするため)
-        let http_pattern = Regex::new(r"http.*").unwrap();
-        let project_name_for_dir = http_pattern.replace(&project_name, "").replace("/", "-");
-
-        let dir_name = format!("{}-{}", yyyymmdd, project_name_for_dir);
+        let dir_name = new_project_key(&root_task, self.last_synced_time)?;
         let project_dir_path = Path::new(&self.project_storage_dir_name).join(dir_name);
 
         let project_yaml_file_path = project_dir_path.join("project.yaml");
@@ -755,10 +991,150 @@ impl TaskRepositoryTrait for TaskRepository {
             priority,
         );
 
-        self.cache_task_and_descendants(&project.root_task)?;
-        self.projects.push(project);
-        Ok(())
+        self.cache_task_and_descendants(&project.root_task)?;
+        self.projects.push(project);
+        Ok(())
+    }
+
+    fn remove_project(&mut self, root_task_id: Uuid) -> Result<Option<String>, TaskTreeError> {
+        let Some(index) = self
+            .projects
+            .iter()
+            .map(|project| project.root_task.get_id())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .position(|id| id == root_task_id)
+        else {
+            return Ok(None);
+        };
+        let project = self.projects.remove(index);
+        self.removed_project_yaml_file_paths
+            .borrow_mut()
+            .push(project.project_yaml_file_path.clone());
+        Ok(Some(self.project_key(&project)))
+    }
+
+    fn restore_project(
+        &mut self,
+        project_key: &str,
+        root_task: TaskHandle,
+    ) -> Result<String, TaskTreeError> {
+        let used_keys = self
+            .projects
+            .iter()
+            .map(|project| self.project_key(project))
+            .collect::<Vec<_>>();
+        let project_key = available_project_key(project_key, &used_keys);
+        let project_yaml_file_path = Path::new(&self.project_storage_dir_name)
+            .join(&project_key)
+            .join("project.yaml");
+        self.removed_project_yaml_file_paths
+            .borrow_mut()
+            .retain(|path| *path != project_yaml_file_path);
+        self.import_project(&project_key, root_task)?;
+        Ok(project_key)
+    }
+}
+
+// 戻すprojectのkeyが他のprojectで使われていれば、"-2"から順に番号を付けて空いているkeyにする
+pub(super) fn available_project_key(project_key: &str, used_keys: &[String]) -> String {
+    let is_used = |key: &str| used_keys.iter().any(|used_key| used_key == key);
+    if !is_used(project_key) {
+        return project_key.to_string();
+    }
+    (2..)
+        .map(|suffix| format!("{project_key}-{suffix}"))
+        .find(|key| !is_used(key))
+        .expect("some numbered project key is unused")
+}
+
+// 新規projectを保存先で識別する名前。YAMLではproject directory名として使う
+pub(super) fn new_project_key(
+    root_task: &TaskHandle,
+    now: DateTime<Local>,
+) -> Result<String, TaskTreeError> {
+    let project_name = root_task.get_name()?;
+
+    let yyyymmdd = now.format("%Y%m%d").to_string();
+
+    // ディレクトリ名からはURLを除く (ディレクトリの区切りに使われうる "/" が入らないようにするため)
+    let http_pattern = Regex::new(r"http.*").unwrap();
+    let project_name_for_dir = http_pattern.replace(&project_name, "").replace("/", "-");
+
+    Ok(format!("{}-{}", yyyymmdd, project_name_for_dir))
+}
+
+// 保存形式によらず、優先度の低い順に並んだprojectのrootから最優先の葉タスクを選ぶ
+pub(super) fn select_highest_priority_leaf_task_id<'a>(
+    root_tasks: impl IntoIterator<Item = &'a TaskHandle>,
+) -> Result<Option<Uuid>, TaskTreeError> {
+    // 優先度が低いPJ順に見て、返すべき葉タスクのid値を更新していく
+    let mut ans = None;
+
+    for root_task in root_tasks {
+        let leaf_tasks = extract_leaf_tasks_from_project(root_task)?;
+
+        for leaf_task in leaf_tasks.iter() {
+            let deadline_time_opt = leaf_task.get_deadline_time_opt()?;
+            let neg_priority = !leaf_task.get_priority()?;
+            let id = leaf_task.get_id()?;
+
+            let tpl = (
+                deadline_time_opt.is_none(),
+                deadline_time_opt,
+                neg_priority,
+                id,
+            );
+
+            if ans.is_none() || tpl < ans.unwrap() {
+                ans = Some(tpl);
+            }
+        }
+    }
+
+    Ok(ans.map(|tpl| tpl.3))
+}
+
+pub(super) fn select_defer_candidate_leaf_task_id<'a>(
+    root_tasks: impl IntoIterator<Item = &'a TaskHandle>,
+    recent_threshold: DateTime<Local>,
+) -> Result<Option<Uuid>, TaskTreeError> {
+    // 優先度が低いPJ順に見て、返すべき葉タスクのid値を更新していく
+    let mut ans = None;
+    for root_task in root_tasks {
+        let leaf_tasks = extract_leaf_tasks_from_project_with_pending(root_task)?;
+
+        for leaf_task in leaf_tasks.iter() {
+            if leaf_task.get_start_time()? >= recent_threshold
+                || (leaf_task.get_orig_status()? == Status::Pending
+                    && leaf_task.get_pending_until()? >= recent_threshold)
+            {
+                continue;
+            }
+
+            let deadline_time_opt = leaf_task.get_deadline_time_opt()?;
+            let first_available_time = leaf_task.first_available_time()?;
+            let is_recent = first_available_time < recent_threshold;
+            let neg_priority = !leaf_task.get_priority()?;
+            let id = leaf_task.get_id()?;
+
+            // 優先度が低いほど大さい値になる
+            let tpl = (
+                deadline_time_opt.is_none(),
+                is_recent,
+                neg_priority,
+                deadline_time_opt,
+                first_available_time,
+                id,
+            );
+
+            if ans.is_none() || tpl > ans.unwrap() {
+                ans = Some(tpl);
+            }
+        }
     }
+
+    Ok(ans.map(|tpl| tpl.5))
 }
 
 #[cfg(test)]
@@ -961,6 +1337,82 @@ mod tests {
         assert_eq!(loaded_task.get_name().unwrap(), "保存対象");
     }
 
+    #[test]
+    fn test_remove_projectは保存時にproject_yamlだけを消しrestore_projectで同じkeyへ戻す() {
+        let storage_dir = TestStorageDir::new();
+        let now = Local.with_ymd_and_hms(2026, 8, 11, 12, 0, 0).unwrap();
+        let mut task_repository = TaskRepository::new(storage_dir.path_str());
+        task_repository.sync_clock(now).unwrap();
+        let root_task = crate::test_support::new_task_handle("捨てる").unwrap();
+        let root_task_id = root_task.get_id().unwrap();
+        task_repository
+            .start_new_project(root_task.clone())
+            .unwrap();
+        task_repository.save().unwrap();
+        let project_dir_path = storage_dir.project_dir_path("20260811", "捨てる");
+
+        assert_eq!(
+            task_repository.remove_project(root_task_id).unwrap(),
+            Some("20260811-捨てる".to_string())
+        );
+        assert_eq!(task_repository.remove_project(root_task_id).unwrap(), None);
+        assert_eq!(task_repository.get_by_id(root_task_id).unwrap(), None);
+        assert!(task_repository.has_pending_changes().unwrap());
+        task_repository.save().unwrap();
+
+        assert!(!project_dir_path.join("project.yaml").exists());
+        assert!(project_dir_path.join("markdown").is_dir());
+        assert!(!task_repository.has_pending_changes().unwrap());
+
+        assert_eq!(
+            task_repository
+                .restore_project("20260811-捨てる", root_task)
+                .unwrap(),
+            "20260811-捨てる"
+        );
+        task_repository.save().unwrap();
+        let mut loaded_repository = TaskRepository::new(storage_dir.path_str());
+        loaded_repository.load().unwrap();
+        assert!(loaded_repository.get_by_id(root_task_id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_restore_projectは使われているkeyに番号を付ける() {
+        let storage_dir = TestStorageDir::new();
+        let now = Local.with_ymd_and_hms(2026, 8, 11, 12, 0, 0).unwrap();
+        let mut task_repository = TaskRepository::new(storage_dir.path_str());
+        task_repository.sync_clock(now).unwrap();
+        task_repository
+            .start_new_project(crate::test_support::new_task_handle("重複").unwrap())
+            .unwrap();
+
+        let restored_key = task_repository
+            .restore_project(
+                "20260811-重複",
+                crate::test_support::new_task_handle("重複").unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(restored_key, "20260811-重複-2");
+    }
+
+    #[test]
+    fn test_get_by_idは親から切り離したtaskをcacheから返さない() {
+        let storage_dir = TestStorageDir::new();
+        let mut task_repository = TaskRepository::new(storage_dir.path_str());
+        let root_task = crate::test_support::new_task_handle("親").unwrap();
+        let mut child = root_task
+            .create_child(crate::test_support::new_task_attr("子"))
+            .unwrap();
+        let child_id = child.get_id().unwrap();
+        task_repository.start_new_project(root_task).unwrap();
+        assert!(task_repository.get_by_id(child_id).unwrap().is_some());
+
+        child.detach_from_parent().unwrap();
+
+        assert_eq!(task_repository.get_by_id(child_id).unwrap(), None);
+    }
+
     #[test]
     fn test_save_directory作成失敗を型付きerrorで返す() {
         let storage_dir = TestStorageDir::new();
@@ -1506,6 +1958,112 @@ mod tests {
         assert_eq!(repository.storage_revision.get(), Some(first_revision));
     }
 
+    #[test]
+    fn test_save_書き込んだrevisionごとに保存履歴を残す() {
+        use crate::application::interface::StorageHistoryTrait;
+
+        let storage_dir = TestStorageDir::new();
+        let now = Local.with_ymd_and_hms(2026, 8, 13, 12, 0, 0).unwrap();
+        let mut repository = TaskRepository::new(storage_dir.path_str());
+        repository.sync_clock(now).unwrap();
+        let task = crate::test_support::new_task_handle("履歴対象").unwrap();
+        repository.start_new_project(task.clone()).unwrap();
+        repository.save().unwrap();
+        task.set_estimated_work_seconds(30 * 60).unwrap();
+        repository.save().unwrap();
+        repository.save().unwrap();
+
+        let revisions = StorageHistory::new(storage_dir.path_str(), 10)
+            .list_revisions()
+            .unwrap();
+
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(
+            Some(revisions[1].revision),
+            repository.storage_revision.get()
+        );
+        assert_eq!(
+            revisions[1].changed_projects,
+            vec!["20260813-履歴対象/project.yaml".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_save_暗号化した保存先ではproject_fileと履歴を暗号文で書き鍵がなければ読まない() {
+        use crate::adapter::gateway::storage_encryption::{
+            initialize_storage_encryption, is_encrypted, StorageKeySource,
+        };
+        use crate::application::interface::StorageHistoryTrait;
+
+        let storage_dir = TestStorageDir::new();
+        fs::create_dir_all(&storage_dir.path).unwrap();
+        let key_file_path = storage_dir.path.join("schronu.key");
+        fs::write(&key_file_path, [9; 32]).unwrap();
+        let cipher = initialize_storage_encryption(
+            &storage_dir.path,
+            &StorageKeySource::new(Some(key_file_path), None),
+        )
+        .unwrap();
+        let now = Local.with_ymd_and_hms(2026, 8, 13, 12, 0, 0).unwrap();
+        let mut repository =
+            TaskRepository::new(storage_dir.path_str()).with_storage_cipher(Some(cipher.clone()));
+        repository.sync_clock(now).unwrap();
+        let task = crate::test_support::new_task_handle("秘密のproject").unwrap();
+        repository.start_new_project(task.clone()).unwrap();
+        task.set_estimated_work_seconds(30 * 60).unwrap();
+        repository.save().unwrap();
+        let project_yaml_file_path = storage_dir
+            .project_dir_path("20260813", "秘密のproject")
+            .join("project.yaml");
+        let saved_bytes = fs::read(&project_yaml_file_path).unwrap();
+
+        // 平文が同じなら、nonceが変わるだけの書き直しはしない
+        task.set_estimated_work_seconds(60 * 60).unwrap();
+        task.set_estimated_work_seconds(30 * 60).unwrap();
+        repository.save().unwrap();
+
+        assert!(is_encrypted(&saved_bytes));
+        assert_eq!(fs::read(&project_yaml_file_path).unwrap(), saved_bytes);
+        let mut reloaded =
+            TaskRepository::new(storage_dir.path_str()).with_storage_cipher(Some(cipher.clone()));
+        reloaded.load().unwrap();
+        assert_eq!(
+            reloaded.get_all_projects()[0].get_name().unwrap(),
+            "秘密のproject"
+        );
+        let history_tasks = StorageHistory::new(storage_dir.path_str(), 10)
+            .with_cipher(Some(cipher))
+            .read_revision_tasks(repository.storage_revision.get())
+            .unwrap();
+        assert_eq!(history_tasks.len(), 1);
+
+        let error = TaskRepository::new(storage_dir.path_str())
+            .load()
+            .unwrap_err();
+        assert_eq!(
+            file_repository_error(&error).operation,
+            FileRepositoryOperation::DecryptFile
+        );
+        assert!(error.to_string().contains("no encryption key"));
+    }
+
+    #[test]
+    fn test_save_保存履歴の保持数0では履歴directoryを作らない() {
+        let storage_dir = TestStorageDir::new();
+        let now = Local.with_ymd_and_hms(2026, 8, 13, 12, 0, 0).unwrap();
+        let mut repository =
+            TaskRepository::new(storage_dir.path_str()).with_history_retention_revisions(0);
+        repository.sync_clock(now).unwrap();
+        repository
+            .start_new_project(crate::test_support::new_task_handle("履歴なし").unwrap())
+            .unwrap();
+
+        repository.save().unwrap();
+
+        assert!(storage_dir.path.join(".revision").is_file());
+        assert!(!storage_dir.path.join(".history").exists());
+    }
+
     #[test]
     fn test_save_project失敗時はdisk_revisionだけを先に進める() {
         let storage_dir = TestStorageDir::new();
@@ -1768,6 +2326,227 @@ mod tests {
         );
     }
 
+    fn save_projects(
+        storage_dir: &TestStorageDir,
+        now: DateTime<Local>,
+        names: &[&str],
+    ) -> Vec<Uuid> {
+        let mut source = TaskRepository::new(storage_dir.path_str());
+        source.sync_clock(now).unwrap();
+        let task_ids = names
+            .iter()
+            .map(|name| {
+                let task = crate::test_support::new_task_handle(name).unwrap();
+                let task_id = task.get_id().unwrap();
+                source.start_new_project(task).unwrap();
+                task_id
+            })
+            .collect();
+        source.save().unwrap();
+        task_ids
+    }
+
+    fn estimated_work_seconds(repository: &TaskRepository, task_id: Uuid) -> i64 {
+        repository
+            .get_by_id(task_id)
+            .unwrap()
+            .unwrap()
+            .get_estimated_work_seconds()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reload_if_changed_変更されたprojectだけを読み直し他のtask_treeを使い続ける() {
+        let storage_dir = TestStorageDir::new();
+        let now = Local.with_ymd_and_hms(2026, 8, 13, 12, 0, 0).unwrap();
+        let task_ids = save_projects(&storage_dir, now, &["未変更project", "変更project"]);
+        let mut cached = TaskRepository::new(storage_dir.path_str());
+        cached.reload_if_changed(now).unwrap();
+        let unchanged_before_reload = cached.get_by_id(task_ids[0]).unwrap().unwrap();
+        let changed_before_reload = cached.get_by_id(task_ids[1]).unwrap().unwrap();
+        let mut external = TaskRepository::new(storage_dir.path_str());
+        external.reload_if_changed(now).unwrap();
+        external
+            .get_by_id(task_ids[1])
+            .unwrap()
+            .unwrap()
+            .set_estimated_work_seconds(45 * 60)
+            .unwrap();
+        external.save().unwrap();
+
+        assert_eq!(
+            cached.reload_if_changed(now).unwrap(),
+            RepositoryReloadOutcome::Reloaded
+        );
+
+        // 使い続けたtask treeは再読込前のhandleと同じnodeを共有する
+        cached
+            .get_by_id(task_ids[0])
+            .unwrap()
+            .unwrap()
+            .set_estimated_work_seconds(20 * 60)
+            .unwrap();
+        assert_eq!(
+            unchanged_before_reload
+                .get_estimated_work_seconds()
+                .unwrap(),
+            20 * 60
+        );
+        assert_eq!(estimated_work_seconds(&cached, task_ids[1]), 45 * 60);
+        assert_ne!(
+            changed_before_reload.get_estimated_work_seconds().unwrap(),
+            45 * 60
+        );
+        assert_eq!(
+            cached
+                .get_all_projects()
+                .iter()
+                .map(|project| project.get_name().unwrap())
+                .collect::<Vec<_>>(),
+            ["変更project", "未変更project"]
+        );
+    }
+
+    #[test]
+    fn test_reload_if_changed_追加と削除されたprojectを反映する() {
+        let storage_dir = TestStorageDir::new();
+        let now = Local.with_ymd_and_hms(2026, 8, 13, 12, 0, 0).unwrap();
+        let task_ids = save_projects(&storage_dir, now, &["残るproject", "消えるproject"]);
+        let mut cached = TaskRepository::new(storage_dir.path_str());
+        cached.reload_if_changed(now).unwrap();
+        fs::remove_file(
+            storage_dir
+                .project_dir_path("20260813", "消えるproject")
+                .join("project.yaml"),
+        )
+        .unwrap();
+        let added_task_ids = save_projects(&storage_dir, now, &["追加project"]);
+
+        cached.reload_if_changed(now).unwrap();
+
+        assert!(cached.get_by_id(task_ids[0]).unwrap().is_some());
+        assert!(cached.get_by_id(task_ids[1]).unwrap().is_none());
+        assert!(cached.get_by_id(added_task_ids[0]).unwrap().is_some());
+        assert_eq!(cached.get_all_projects().len(), 2);
+    }
+
+    #[test]
+    fn test_reload_if_changed_未保存の変更があるprojectは全loadと同じくdiskから読み直す() {
+        let storage_dir = TestStorageDir::new();
+        let now = Local.with_ymd_and_hms(2026, 8, 13, 12, 0, 0).unwrap();
+        let task_ids = save_projects(&storage_dir, now, &["未保存project", "外部変更project"]);
+        let mut cached = TaskRepository::new(storage_dir.path_str());
+        cached.reload_if_changed(now).unwrap();
+        let original_seconds = estimated_work_seconds(&cached, task_ids[0]);
+        cached
+            .get_by_id(task_ids[0])
+            .unwrap()
+            .unwrap()
+            .set_estimated_work_seconds(original_seconds + 60)
+            .unwrap();
+        let mut external = TaskRepository::new(storage_dir.path_str());
+        external.reload_if_changed(now).unwrap();
+        external
+            .get_by_id(task_ids[1])
+            .unwrap()
+            .unwrap()
+            .set_estimated_work_seconds(45 * 60)
+            .unwrap();
+        external.save().unwrap();
+
+        cached.reload_if_changed(now).unwrap();
+
+        assert_eq!(
+            estimated_work_seconds(&cached, task_ids[0]),
+            original_seconds
+        );
+        assert!(!cached.has_pending_changes().unwrap());
+    }
+
+    #[test]
+    fn test_reload_if_changed_mtimeだけ変わった同一内容のprojectは解析し直さない() {
+        let storage_dir = TestStorageDir::new();
+        let now = Local.with_ymd_and_hms(2026, 8, 13, 12, 0, 0).unwrap();
+        let task_ids = save_projects(&storage_dir, now, &["同一内容project"]);
+        let mut cached = TaskRepository::new(storage_dir.path_str());
+        cached.reload_if_changed(now).unwrap();
+        let before_reload = cached.get_by_id(task_ids[0]).unwrap().unwrap();
+        let project_yaml_path = storage_dir
+            .project_dir_path("20260813", "同一内容project")
+            .join("project.yaml");
+        let bytes = fs::read(&project_yaml_path).unwrap();
+        fs::write(&project_yaml_path, bytes).unwrap();
+        File::options()
+            .write(true)
+            .open(&project_yaml_path)
+            .unwrap()
+            .set_modified(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap();
+        fs::write(
+            storage_dir.path.join(".revision"),
+            format!("{}\n", Uuid::new_v4()),
+        )
+        .unwrap();
+
+        cached.reload_if_changed(now).unwrap();
+
+        cached
+            .get_by_id(task_ids[0])
+            .unwrap()
+            .unwrap()
+            .set_estimated_work_seconds(20 * 60)
+            .unwrap();
+        assert_eq!(before_reload.get_estimated_work_seconds().unwrap(), 20 * 60);
+    }
+
+    #[test]
+    #[ignore = "manual reload performance measurement"]
+    fn benchmark_reload_2172project中1件変更を全load時間の1割未満で処理する() {
+        use std::time::Instant;
+
+        let source_storage_dir = std::env::var("SCHRONU_BENCHMARK_STORAGE")
+            .expect("SCHRONU_BENCHMARK_STORAGE must point to a task storage copy source");
+        let storage_dir = TestStorageDir::new();
+        let source_storage_path = Path::new(&source_storage_dir);
+        for entry in WalkDir::new(source_storage_path) {
+            let entry = entry.unwrap();
+            if entry.file_name() != "project.yaml" {
+                continue;
+            }
+            let relative_path = entry.path().strip_prefix(source_storage_path).unwrap();
+            let copied_path = storage_dir.path.join(relative_path);
+            fs::create_dir_all(copied_path.parent().unwrap()).unwrap();
+            fs::copy(entry.path(), copied_path).unwrap();
+        }
+
+        let now = Local::now();
+        let mut repository = TaskRepository::new(storage_dir.path_str());
+        let started_at = Instant::now();
+        repository.reload_if_changed(now).unwrap();
+        let full_load_elapsed = started_at.elapsed();
+        assert_eq!(repository.get_all_projects().len(), 2_172);
+
+        let mut external =
+            TaskRepository::new(storage_dir.path_str()).with_history_retention_revisions(0);
+        external.reload_if_changed(now).unwrap();
+        let changed_task = (*external
+            .get_all_projects()
+            .first()
+            .expect("benchmark storage must contain a project"))
+        .clone();
+        changed_task
+            .set_priority(changed_task.get_priority().unwrap() + 1)
+            .unwrap();
+        external.save().unwrap();
+
+        let started_at = Instant::now();
+        repository.reload_if_changed(now).unwrap();
+        let reload_elapsed = started_at.elapsed();
+
+        eprintln!("full load elapsed: {full_load_elapsed:?}, reload elapsed: {reload_elapsed:?}");
+        assert!(reload_elapsed * 10 < full_load_elapsed);
+    }
+
     #[test]
     #[ignore = "manual save performance measurement"]
     fn benchmark_save_2172project中1件変更を2秒未満で処理する() {
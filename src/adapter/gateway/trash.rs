@@ -0,0 +1,328 @@
+use crate::adapter::gateway::storage_encryption::{
+    decode_storage_file, encode_storage_file, StorageCipher,
+};
+use crate::adapter::gateway::task_repository::write_file_atomically;
+use crate::adapter::gateway::work_session::{list_open_work_sessions, WORK_SESSION_DIRECTORY_NAME};
+use crate::adapter::gateway::yaml::yaml_to_task;
+use crate::application::interface::{TrashStoreError, TrashStoreTrait};
+use crate::application::trash::TrashEntry;
+use crate::entity::task::{task_to_yaml, TaskHandle};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use walkdir::WalkDir;
+use yaml_rust::{YamlEmitter, YamlLoader};
+
+pub const TRASH_FILE_NAME: &str = ".trash.jsonl";
+
+// 1行に1件、捨てた記録と部分木のYAMLをJSONで持つ。project.yamlとは独立しているため、
+// repositoryのloadやrevisionの対象にはならない
+pub struct TrashFile {
+    storage_directory: PathBuf,
+    path: PathBuf,
+    // 暗号化した保存先では、捨てたtaskもproject.yamlと同じくfile全体を暗号文で持つ
+    cipher: Option<StorageCipher>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TrashRecord {
+    #[serde(flatten)]
+    entry: TrashEntry,
+    subtree: String,
+}
+
+impl TrashFile {
+    pub fn in_storage_directory(storage_directory: impl AsRef<Path>) -> Self {
+        let storage_directory = storage_directory.as_ref().to_path_buf();
+        let path = storage_directory.join(TRASH_FILE_NAME);
+        Self {
+            storage_directory,
+            path,
+            cipher: None,
+        }
+    }
+
+    pub fn with_cipher(mut self, cipher: Option<StorageCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_records(&self) -> Result<Vec<TrashRecord>, TrashStoreError> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(error) => return Err(TrashStoreError::new(&self.path, None, error)),
+        };
+        let contents = decode_storage_file(self.cipher.as_ref(), &bytes)
+            .map_err(|error| TrashStoreError::new(&self.path, None, error))
+            .and_then(|bytes| {
+                String::from_utf8(bytes)
+                    .map_err(|error| TrashStoreError::new(&self.path, None, error))
+            })?;
+
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line)
+                    .map_err(|error| TrashStoreError::new(&self.path, Some(index + 1), error))
+            })
+            .collect()
+    }
+
+    fn write_records(&self, records: &[TrashRecord]) -> Result<(), TrashStoreError> {
+        let mut contents = String::new();
+        for record in records {
+            contents.push_str(
+                &serde_json::to_string(record)
+                    .map_err(|error| TrashStoreError::new(&self.path, None, error))?,
+            );
+            contents.push('\n');
+        }
+        write_file_atomically(
+            &self.path,
+            &encode_storage_file(self.cipher.as_ref(), contents.into_bytes()),
+        )
+        .map(|_| ())
+        .map_err(|error| TrashStoreError::new(&self.path, None, error))
+    }
+}
+
+impl TrashStoreTrait for TrashFile {
+    fn list_trash_entries(&self) -> Result<Vec<TrashEntry>, TrashStoreError> {
+        Ok(self
+            .read_records()?
+            .into_iter()
+            .map(|record| record.entry)
+            .collect())
+    }
+
+    fn append_trash_entry(
+        &mut self,
+        entry: &TrashEntry,
+        subtree: &TaskHandle,
+    ) -> Result<(), TrashStoreError> {
+        let yaml =
+            task_to_yaml(subtree).map_err(|error| TrashStoreError::new(&self.path, None, error))?;
+        let mut subtree = String::new();
+        YamlEmitter::new(&mut subtree)
+            .dump(&yaml)
+            .map_err(|error| {
+                TrashStoreError::new(
+                    &self.path,
+                    None,
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+                )
+            })?;
+
+        let mut records = self.read_records()?;
+        records.push(TrashRecord {
+            entry: entry.clone(),
+            subtree,
+        });
+        self.write_records(&records)
+    }
+
+    fn read_trash_subtree(
+        &self,
+        trash_id: Uuid,
+        now: DateTime<Local>,
+    ) -> Result<Option<TaskHandle>, TrashStoreError> {
+        let Some(record) = self
+            .read_records()?
+            .into_iter()
+            .find(|record| record.entry.trash_id == trash_id)
+        else {
+            return Ok(None);
+        };
+        let docs = YamlLoader::load_from_str(&record.subtree).map_err(|error| {
+            TrashStoreError::new(
+                &self.path,
+                None,
+                std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+            )
+        })?;
+        let yaml = docs.first().ok_or_else(|| {
+            TrashStoreError::new(
+                &self.path,
+                None,
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "trash entry must contain a task subtree",
+                ),
+            )
+        })?;
+        yaml_to_task(yaml, now)
+            .map(Some)
+            .map_err(|error| TrashStoreError::new(&self.path, None, error))
+    }
+
+    fn remove_trash_entry(&mut self, trash_id: Uuid) -> Result<(), TrashStoreError> {
+        let mut records = self.read_records()?;
+        records.retain(|record| record.entry.trash_id != trash_id);
+        self.write_records(&records)
+    }
+
+    // 保存時に作るmarkdown/のような空のdirectoryしかなければ消す。fileが1つでも残っていれば残す
+    fn remove_empty_project_directory(
+        &mut self,
+        project_key: &str,
+    ) -> Result<bool, TrashStoreError> {
+        let project_dir_path = self.storage_directory.join(project_key);
+        if !project_dir_path.is_dir() {
+            return Ok(false);
+        }
+        for entry in WalkDir::new(&project_dir_path) {
+            let entry = entry.map_err(|error| {
+                let reason = error.to_string();
+                TrashStoreError::new(
+                    &project_dir_path,
+                    None,
+                    error
+                        .into_io_error()
+                        .unwrap_or_else(|| std::io::Error::other(reason)),
+                )
+            })?;
+            if !entry.file_type().is_dir() {
+                return Ok(false);
+            }
+        }
+        fs::remove_dir_all(&project_dir_path)
+            .map(|()| true)
+            .map_err(|error| TrashStoreError::new(&project_dir_path, None, error))
+    }
+
+    // 捨てる側のprocessが自分で計時しているtaskは、利用者が選んで捨てるものなので除く
+    fn open_work_task_ids(&self) -> Result<Vec<Uuid>, TrashStoreError> {
+        let sessions = list_open_work_sessions(&self.storage_directory).map_err(|error| {
+            TrashStoreError::new(
+                self.storage_directory.join(WORK_SESSION_DIRECTORY_NAME),
+                None,
+                error,
+            )
+        })?;
+        Ok(sessions
+            .into_iter()
+            .filter(|session| !session.is_current_process())
+            .map(|session| session.task_id)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestStorageDir;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap()
+    }
+
+    fn entry(task: &TaskHandle, project_key: Option<&str>) -> TrashEntry {
+        TrashEntry {
+            trash_id: Uuid::new_v4(),
+            deleted_at: now(),
+            task_id: task.get_id().unwrap(),
+            name: task.get_name().unwrap(),
+            task_count: 2,
+            original_parent_id: None,
+            position: 0,
+            project_key: project_key.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn 捨てた部分木を記録ごとに読み直し消せる() {
+        let storage_dir = TestStorageDir::new("trash");
+        let mut trash = TrashFile::in_storage_directory(&storage_dir.path);
+        let task = TaskHandle::with_identity("捨てる", Uuid::new_v4(), now()).unwrap();
+        let child = task
+            .create_child(crate::entity::task::TaskAttr::with_identity(
+                "子",
+                Uuid::new_v4(),
+                now(),
+            ))
+            .unwrap();
+        let first = entry(&task, None);
+        let second = entry(&task, Some("20261018-捨てる"));
+
+        trash.append_trash_entry(&first, &task).unwrap();
+        trash.append_trash_entry(&second, &task).unwrap();
+
+        assert_eq!(
+            trash.list_trash_entries().unwrap(),
+            vec![first.clone(), second.clone()]
+        );
+        let subtree = trash
+            .read_trash_subtree(first.trash_id, now())
+            .unwrap()
+            .unwrap();
+        assert_eq!(subtree.get_id(), task.get_id());
+        assert_eq!(subtree.get_children().unwrap()[0].get_id(), child.get_id());
+
+        trash.remove_trash_entry(first.trash_id).unwrap();
+
+        assert_eq!(trash.list_trash_entries().unwrap(), vec![second]);
+        assert_eq!(
+            trash.read_trash_subtree(first.trash_id, now()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn 暗号化した保存先では捨てた部分木を暗号文で書き平文のfileは読まない() {
+        use crate::adapter::gateway::storage_encryption::{
+            initialize_storage_encryption, is_encrypted, StorageKeySource,
+        };
+
+        let storage_dir = TestStorageDir::new("trash");
+        let key_file_path = storage_dir.path.join("schronu.key");
+        fs::write(&key_file_path, [9; 32]).unwrap();
+        let cipher = initialize_storage_encryption(
+            &storage_dir.path,
+            &StorageKeySource::new(Some(key_file_path), None),
+        )
+        .unwrap();
+        let mut trash =
+            TrashFile::in_storage_directory(&storage_dir.path).with_cipher(Some(cipher.clone()));
+        let task = TaskHandle::with_identity("秘密の部分木", Uuid::new_v4(), now()).unwrap();
+        let trashed = entry(&task, None);
+
+        trash.append_trash_entry(&trashed, &task).unwrap();
+
+        let bytes = fs::read(trash.path()).unwrap();
+        assert!(is_encrypted(&bytes));
+        assert!(!String::from_utf8_lossy(&bytes).contains("秘密の部分木"));
+        assert_eq!(trash.list_trash_entries().unwrap(), vec![trashed]);
+        assert!(TrashFile::in_storage_directory(&storage_dir.path)
+            .list_trash_entries()
+            .is_err());
+
+        fs::write(trash.path(), "{}\n").unwrap();
+        assert!(trash.list_trash_entries().is_err());
+    }
+
+    #[test]
+    fn fileの残るproject_directoryは消さない() {
+        let storage_dir = TestStorageDir::new("trash");
+        let mut trash = TrashFile::in_storage_directory(&storage_dir.path);
+        fs::create_dir_all(storage_dir.path.join("empty/markdown")).unwrap();
+        fs::create_dir_all(storage_dir.path.join("kept/markdown")).unwrap();
+        fs::write(storage_dir.path.join("kept/markdown/note.md"), "memo").unwrap();
+
+        assert!(trash.remove_empty_project_directory("empty").unwrap());
+        assert!(!trash.remove_empty_project_directory("kept").unwrap());
+        assert!(!trash.remove_empty_project_directory("missing").unwrap());
+
+        assert!(!storage_dir.path.join("empty").exists());
+        assert!(storage_dir.path.join("kept/markdown/note.md").exists());
+    }
+}
@@ -0,0 +1,227 @@
+use crate::adapter::gateway::storage_lock::{current_hostname, parse_metadata_fields};
+use chrono::{DateTime, FixedOffset, Local};
+use fs2::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+pub const WORK_SESSION_DIRECTORY_NAME: &str = ".work_sessions";
+const WORK_SESSION_EXTENSION: &str = "session";
+
+// 他processが計時中のtask。対話CLIがfocusを当てている間だけ記録される
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OpenWorkSession {
+    pub pid: u32,
+    pub hostname: Option<String>,
+    pub task_id: Uuid,
+    pub started_at: DateTime<FixedOffset>,
+}
+
+impl OpenWorkSession {
+    fn parse(metadata: &str) -> Option<Self> {
+        let fields = parse_metadata_fields(metadata);
+        Some(Self {
+            pid: fields.get("pid")?.parse().ok()?,
+            hostname: fields.get("hostname").map(|hostname| hostname.to_string()),
+            task_id: Uuid::parse_str(fields.get("task_id")?).ok()?,
+            started_at: DateTime::parse_from_rfc3339(fields.get("started_at")?).ok()?,
+        })
+    }
+
+    fn to_metadata(&self) -> String {
+        let mut metadata = format!("pid={}\n", self.pid);
+        if let Some(hostname) = &self.hostname {
+            metadata.push_str(&format!("hostname={hostname}\n"));
+        }
+        metadata.push_str(&format!(
+            "task_id={}\nstarted_at={}\n",
+            self.task_id,
+            self.started_at.to_rfc3339()
+        ));
+        metadata
+    }
+
+    pub fn is_current_process(&self) -> bool {
+        self.pid == std::process::id() && self.hostname == current_hostname()
+    }
+}
+
+// 対話CLI 1つ分の作業記録。fileの排他lockを持つ間だけ作業中とみなすので、
+// 落ちたprocessが残したfileは読む側で古いものとして消す
+#[derive(Debug)]
+pub struct WorkSession {
+    file: File,
+    path: PathBuf,
+    recorded: Option<(Uuid, DateTime<Local>)>,
+}
+
+impl WorkSession {
+    pub fn start(storage_directory: &Path) -> std::io::Result<Self> {
+        let directory = storage_directory.join(WORK_SESSION_DIRECTORY_NAME);
+        fs::create_dir_all(&directory)?;
+        let name = format!("{}-{}", std::process::id(), Uuid::new_v4().hyphenated());
+        // lockを取る前のfileを読む側が古いものと誤って消さないよう、lockしてから名前を付ける
+        let temporary_path = directory.join(format!("{name}.tmp"));
+        let file = OpenOptions::new()
+            .create_new(true)
+            .read(true)
+            .write(true)
+            .open(&temporary_path)?;
+        let path = directory.join(format!("{name}.{WORK_SESSION_EXTENSION}"));
+        if let Err(error) = file
+            .try_lock_exclusive()
+            .and_then(|()| fs::rename(&temporary_path, &path))
+        {
+            let _ = fs::remove_file(&temporary_path);
+            return Err(error);
+        }
+        Ok(Self {
+            file,
+            path,
+            recorded: None,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // focusが変わったときだけ書き直す。focusがなければ空にして作業中のtaskをなくす
+    pub fn record_focus(
+        &mut self,
+        task_id_opt: Option<Uuid>,
+        started_at: DateTime<Local>,
+    ) -> std::io::Result<()> {
+        let recorded = task_id_opt.map(|task_id| (task_id, started_at));
+        if recorded == self.recorded {
+            return Ok(());
+        }
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        if let Some((task_id, started_at)) = recorded {
+            let session = OpenWorkSession {
+                pid: std::process::id(),
+                hostname: current_hostname(),
+                task_id,
+                started_at: started_at.fixed_offset(),
+            };
+            self.file.write_all(session.to_metadata().as_bytes())?;
+        }
+        self.file.sync_data()?;
+        self.recorded = recorded;
+        Ok(())
+    }
+}
+
+impl Drop for WorkSession {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// 保存先で作業中の記録を列挙する。lockの外れた記録は古いものとして消す
+pub fn list_open_work_sessions(storage_directory: &Path) -> std::io::Result<Vec<OpenWorkSession>> {
+    let directory = storage_directory.join(WORK_SESSION_DIRECTORY_NAME);
+    let entries = match fs::read_dir(&directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(error) => return Err(error),
+    };
+    let mut sessions = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some(WORK_SESSION_EXTENSION)
+            || !entry.file_type()?.is_file()
+        {
+            continue;
+        }
+        let mut file = match File::open(&path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error),
+        };
+        match FileExt::try_lock_shared(&file) {
+            Ok(()) => {
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(error) => return Err(error),
+        }
+        let mut metadata = String::new();
+        file.read_to_string(&mut metadata)?;
+        sessions.extend(OpenWorkSession::parse(&metadata));
+    }
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list_open_work_sessions, OpenWorkSession, WorkSession};
+    use crate::test_support::TestStorageDir;
+    use chrono::{Local, TimeZone};
+    use uuid::Uuid;
+
+    #[cfg(unix)]
+    #[test]
+    fn focusを記録したsessionだけが作業中として見える() {
+        let storage_dir = TestStorageDir::new("work-session");
+        let started_at = Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap();
+        let task_id = Uuid::new_v4();
+
+        let mut session = WorkSession::start(&storage_dir.path).unwrap();
+        assert!(list_open_work_sessions(&storage_dir.path)
+            .unwrap()
+            .is_empty());
+
+        session.record_focus(Some(task_id), started_at).unwrap();
+        let sessions = list_open_work_sessions(&storage_dir.path).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].task_id, task_id);
+        assert_eq!(sessions[0].started_at, started_at.fixed_offset());
+        assert!(sessions[0].is_current_process());
+
+        session.record_focus(None, started_at).unwrap();
+        assert!(list_open_work_sessions(&storage_dir.path)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lockの外れた記録は古いものとして消す() {
+        let storage_dir = TestStorageDir::new("work-session-stale");
+        let mut session = WorkSession::start(&storage_dir.path).unwrap();
+        session
+            .record_focus(Some(Uuid::new_v4()), Local::now())
+            .unwrap();
+        let path = session.path().to_path_buf();
+        let stale_path = path.with_file_name("1-stale.session");
+        std::fs::copy(&path, &stale_path).unwrap();
+
+        assert_eq!(list_open_work_sessions(&storage_dir.path).unwrap().len(), 1);
+        assert!(!stale_path.exists());
+
+        drop(session);
+        assert!(!path.exists());
+        assert!(list_open_work_sessions(&storage_dir.path)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn 記録はhostnameのないmetadataも読める() {
+        let task_id = Uuid::new_v4();
+        let session = OpenWorkSession::parse(&format!(
+            "pid=42\ntask_id={task_id}\nstarted_at=2026-10-19T09:00:00+09:00\n"
+        ))
+        .unwrap();
+
+        assert_eq!(session.pid, 42);
+        assert_eq!(session.task_id, task_id);
+        assert!(!session.is_current_process());
+        assert_eq!(OpenWorkSession::parse("pid=42\n"), None);
+    }
+}
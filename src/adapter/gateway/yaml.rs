@@ -1,11 +1,13 @@
+use crate::entity::allowed_window::AllowedWindow;
 use crate::entity::datetime::parse_local_datetime;
 use crate::entity::task::read_project_category;
 use crate::entity::task::read_status;
 use crate::entity::task::Status;
 use crate::entity::task::{ImmutableTask, RepetitionAnchor, TaskHandle, TaskTreeError};
+use crate::entity::waiting::WaitingRecord;
 use chrono::LocalResult;
 use chrono::TimeZone;
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, NaiveDate};
 use std::error::Error;
 use std::fmt;
 use uuid::Uuid;
@@ -375,6 +377,76 @@ fn strict_datetime(
     ))
 }
 
+fn strict_waiting_record(value: &Yaml, path: &str) -> Result<WaitingRecord, YamlConversionError> {
+    if value.as_hash().is_none() {
+        return Err(strict_error(path, "waiting", "must be a mapping or null"));
+    }
+    let counterpart = yaml_field(value, "counterpart")
+        .ok_or_else(|| strict_error(path, "waiting.counterpart", "is required"))?
+        .as_str()
+        .ok_or_else(|| strict_error(path, "waiting.counterpart", "must be a string"))?;
+    if counterpart.trim().is_empty() {
+        return Err(strict_error(
+            path,
+            "waiting.counterpart",
+            "must not be blank",
+        ));
+    }
+    let since = strict_datetime(
+        yaml_field(value, "since")
+            .ok_or_else(|| strict_error(path, "waiting.since", "is required"))?,
+        path,
+        "waiting.since",
+        &["%Y/%m/%d %H:%M:%S"],
+    )?;
+    let expected_reply_date = match yaml_field(value, "expected_reply_date") {
+        None | Some(Yaml::Null) => None,
+        Some(value) => Some(
+            value
+                .as_str()
+                .and_then(|text| NaiveDate::parse_from_str(text, "%Y/%m/%d").ok())
+                .ok_or_else(|| {
+                    strict_error(
+                        path,
+                        "waiting.expected_reply_date",
+                        "must be a date in YYYY/MM/DD format",
+                    )
+                })?,
+        ),
+    };
+    let follow_up_interval_days = match yaml_field(value, "follow_up_interval_days") {
+        None | Some(Yaml::Null) => None,
+        Some(value) => match value.as_i64() {
+            Some(value) if value > 0 => Some(value),
+            _ => {
+                return Err(strict_error(
+                    path,
+                    "waiting.follow_up_interval_days",
+                    "must be a positive integer",
+                ))
+            }
+        },
+    };
+    let last_followed_up_at = match yaml_field(value, "last_followed_up_at") {
+        None | Some(Yaml::Null) => None,
+        Some(value) => Some(strict_datetime(
+            value,
+            path,
+            "waiting.last_followed_up_at",
+            &["%Y/%m/%d %H:%M:%S"],
+        )?),
+    };
+
+    let mut waiting = WaitingRecord::new(
+        counterpart,
+        since,
+        expected_reply_date,
+        follow_up_interval_days,
+    );
+    waiting.set_last_followed_up_at_opt(last_followed_up_at);
+    Ok(waiting)
+}
+
 fn yaml_to_task_strict(
     yaml: &Yaml,
     now: DateTime<Local>,
@@ -475,6 +547,20 @@ fn yaml_to_task_strict(
             }
         },
     };
+    let waiting = match yaml_field(yaml, "waiting") {
+        None | Some(Yaml::Null) => None,
+        Some(value) => Some(strict_waiting_record(value, path)?),
+    };
+    let allowed_window =
+        match yaml_field(yaml, "allowed_window") {
+            None | Some(Yaml::Null) => None,
+            Some(value) => Some(
+                AllowedWindow::parse(value.as_str().ok_or_else(|| {
+                    strict_error(path, "allowed_window", "must be a string or null")
+                })?)
+                .map_err(|reason| strict_error(path, "allowed_window", reason))?,
+            ),
+        };
     let children = match yaml_field(yaml, "children") {
         None | Some(Yaml::Null) => &[][..],
         Some(Yaml::Array(children)) => children.as_slice(),
@@ -488,6 +574,9 @@ fn yaml_to_task_strict(
     task.set_orig_status(status).map_err(map_task_tree_error)?;
     task.set_is_on_other_side(boolean("is_on_other_side")?)
         .map_err(map_task_tree_error)?;
+    task.set_waiting_opt(waiting).map_err(map_task_tree_error)?;
+    task.set_allowed_window_opt(allowed_window)
+        .map_err(map_task_tree_error)?;
     task.set_atomic(boolean("atomic")?)
         .map_err(map_task_tree_error)?;
     task.set_pending_until(pending)
@@ -670,6 +759,30 @@ fn test_yaml_to_task_存在する型違いと不正enumはerrorを返す() {
             "name: task\nrepetition_interval_days: 0",
             "project.repetition_interval_days: must be a positive integer",
         ),
+        (
+            "name: task\nwaiting: 田中",
+            "project.waiting: must be a mapping or null",
+        ),
+        (
+            "name: task\nwaiting:\n  since: '2026/08/19 10:00:00'",
+            "project.waiting.counterpart: is required",
+        ),
+        (
+            "name: task\nwaiting:\n  counterpart: 田中\n  since: '2026/08/19 10:00:00'\n  follow_up_interval_days: 0",
+            "project.waiting.follow_up_interval_days: must be a positive integer",
+        ),
+        (
+            "name: task\nwaiting:\n  counterpart: 田中\n  since: '2026/08/19 10:00:00'\n  expected_reply_date: 2026-08-21",
+            "project.waiting.expected_reply_date: must be a date in YYYY/MM/DD format",
+        ),
+        (
+            "name: task\nallowed_window: 3",
+            "project.allowed_window: must be a string or null",
+        ),
+        (
+            "name: task\nallowed_window: '18:00-09:00'",
+            "project.allowed_window: time ranges must be HH:MM-HH:MM with the start before the end",
+        ),
         (
             "name: task\ncreate_time: invalid",
             "project.create_time: must be a valid local datetime in YYYY/MM/DD HH:MM:SS format",
@@ -907,6 +1020,50 @@ status: 'todo'
     assert_eq!(&actual.get_id().unwrap(), &expected.get_id().unwrap());
 }
 
+#[test]
+fn test_yaml_to_task_waitingは相手と催促の予定を読み込む() {
+    let s = "
+name: 'タスク1'
+is_on_other_side: true
+waiting:
+  counterpart: '田中'
+  since: '2026/08/19 10:00:00'
+  expected_reply_date: '2026/08/21'
+  follow_up_interval_days: 3
+  last_followed_up_at: '2026/08/22 09:00:00'
+";
+
+    let docs = YamlLoader::load_from_str(s).unwrap();
+    let actual = yaml_to_task(&docs[0], Local::now()).unwrap();
+
+    let mut expected = WaitingRecord::new(
+        "田中",
+        Local.with_ymd_and_hms(2026, 8, 19, 10, 0, 0).unwrap(),
+        NaiveDate::from_ymd_opt(2026, 8, 21),
+        Some(3),
+    );
+    expected
+        .set_last_followed_up_at_opt(Some(Local.with_ymd_and_hms(2026, 8, 22, 9, 0, 0).unwrap()));
+    assert_eq!(actual.get_waiting_opt().unwrap(), Some(expected));
+    assert!(actual.get_is_on_other_side().unwrap());
+}
+
+#[test]
+fn test_yaml_to_task_allowed_windowは曜日と時間帯を読み込む() {
+    let s = "
+name: 'タスク1'
+allowed_window: '平日 09:00-12:00'
+";
+
+    let docs = YamlLoader::load_from_str(s).unwrap();
+    let actual = yaml_to_task(&docs[0], Local::now()).unwrap();
+
+    assert_eq!(
+        actual.get_allowed_window_opt().unwrap(),
+        Some(AllowedWindow::parse("月火水木金 09:00-12:00").unwrap())
+    );
+}
+
 #[test]
 fn test_yaml_to_task_is_on_other_side_正常系() {
     let s = "
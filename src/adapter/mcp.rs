@@ -1,3 +1,4 @@
+use crate::adapter::gateway::storage_encryption::StorageCipher;
 use crate::adapter::gateway::storage_lock::{
     LockMode, StorageLock, StorageLockError, StorageLockErrorKind,
 };
@@ -6,9 +7,12 @@ use crate::application::repository_transaction::{
     run_repository_transaction, RepositoryTransactionError,
 };
 use crate::application::task_use_case::{ApplicationError, TaskFactory};
-use chrono::{DateTime, Local};
+use crate::entity::datetime::temporal::ClockTime;
+use crate::entity::datetime::{BusinessDateTimePolicy, DEFAULT_END_OF_DAY_OFFSET_MINUTES};
+use chrono::{DateTime, Local, NaiveTime, Weekday};
 use serde_json::{json, Value};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use uuid::Uuid;
 
 mod error;
@@ -27,8 +31,35 @@ use protocol::{
 pub struct McpServer<R> {
     repository: R,
     storage_directory: Option<PathBuf>,
-    lifecycle_state: LifecycleState,
+    session: McpSession,
     repository_state_uncertain: bool,
+    datetime_policy: BusinessDateTimePolicy,
+    default_deadline_time: ClockTime,
+    busy_time_slots_path: Option<PathBuf>,
+    storage_cipher: Option<StorageCipher>,
+    lock_wait: Duration,
+}
+
+// 初期化の進み具合は接続ごとに持つ。schronudでは1つのserverが複数の接続に応える
+pub struct McpSession {
+    lifecycle_state: LifecycleState,
+}
+
+impl Default for McpSession {
+    fn default() -> Self {
+        Self {
+            lifecycle_state: LifecycleState::Uninitialized,
+        }
+    }
+}
+
+fn default_datetime_policy() -> BusinessDateTimePolicy {
+    BusinessDateTimePolicy::new(DEFAULT_END_OF_DAY_OFFSET_MINUTES)
+        .with_non_business_weekdays(&[Weekday::Sat, Weekday::Sun])
+}
+
+fn default_deadline_time() -> ClockTime {
+    ClockTime::new(23, 59, 59).expect("23:59:59 must be a valid clock time")
 }
 
 impl<R: TaskRepositoryTrait> McpServer<R> {
@@ -36,50 +67,104 @@ impl<R: TaskRepositoryTrait> McpServer<R> {
         Self {
             repository,
             storage_directory: Some(storage_directory.into()),
-            lifecycle_state: LifecycleState::Uninitialized,
+            session: McpSession::default(),
             repository_state_uncertain: false,
+            datetime_policy: default_datetime_policy(),
+            default_deadline_time: default_deadline_time(),
+            busy_time_slots_path: None,
+            storage_cipher: None,
+            lock_wait: Duration::ZERO,
         }
     }
 
+    // 日時表現の営業日計算と、日付だけの締切に使う時刻を設定する
+    pub fn with_datetime_policy(
+        mut self,
+        datetime_policy: BusinessDateTimePolicy,
+        default_deadline_time: NaiveTime,
+    ) -> Self {
+        self.datetime_policy = datetime_policy;
+        self.default_deadline_time = ClockTime::from_naive_time(default_deadline_time);
+        self
+    }
+
+    // 臨界経路の空き時間を数えるための予定表。設定しなければすべての時間を空きとして扱う
+    pub fn with_busy_time_slots_path(mut self, busy_time_slots_path: impl Into<PathBuf>) -> Self {
+        self.busy_time_slots_path = Some(busy_time_slots_path.into());
+        self
+    }
+
+    // 暗号化した保存先で、捨てた記録と負荷履歴を読み書きする鍵。repositoryに渡したものと同じにする
+    pub fn with_storage_cipher(mut self, storage_cipher: Option<StorageCipher>) -> Self {
+        self.storage_cipher = storage_cipher;
+        self
+    }
+
+    // 書き込みのtool callがlockの競合で待つ時間。0なら待たずに競合を返す
+    pub fn with_lock_wait(mut self, lock_wait: Duration) -> Self {
+        self.lock_wait = lock_wait;
+        self
+    }
+
     #[cfg(test)]
     fn new(repository: R) -> Self {
         Self {
             repository,
             storage_directory: None,
-            lifecycle_state: LifecycleState::Uninitialized,
+            session: McpSession::default(),
             repository_state_uncertain: false,
+            datetime_policy: default_datetime_policy(),
+            default_deadline_time: default_deadline_time(),
+            busy_time_slots_path: None,
+            storage_cipher: None,
+            lock_wait: Duration::ZERO,
         }
     }
 
+    pub fn repository_mut(&mut self) -> &mut R {
+        &mut self.repository
+    }
+
     pub fn handle_request(&mut self, request: Value) -> Option<Value> {
+        let mut session = std::mem::take(&mut self.session);
+        let response = self.handle_session_request(&mut session, request);
+        self.session = session;
+        response
+    }
+
+    pub fn handle_session_request(
+        &mut self,
+        session: &mut McpSession,
+        request: Value,
+    ) -> Option<Value> {
         let (method, id) = match validate_request_envelope(&request) {
             Ok(envelope) => envelope,
             Err(id) => return Some(error_response(id, -32600, "Invalid Request")),
         };
         let Some(id) = id else {
             if method == "notifications/initialized"
-                && self.lifecycle_state == LifecycleState::InitializeResponded
+                && session.lifecycle_state == LifecycleState::InitializeResponded
                 && initialized_notification_params_are_valid(&request)
             {
-                self.lifecycle_state = LifecycleState::Initialized;
+                session.lifecycle_state = LifecycleState::Initialized;
             }
             return None;
         };
 
         match method.as_str() {
-            "initialize" if self.lifecycle_state == LifecycleState::Uninitialized => {
+            "initialize" if session.lifecycle_state == LifecycleState::Uninitialized => {
                 if let Err(error) = validate_initialize_params(&request) {
                     return Some(invalid_params_response(id, error));
                 }
-                self.lifecycle_state = LifecycleState::InitializeResponded;
+                session.lifecycle_state = LifecycleState::InitializeResponded;
                 Some(initialize_response(id))
             }
             "initialize" => Some(error_response(id, -32600, "Invalid Request")),
-            "tools/list" if self.lifecycle_state != LifecycleState::Initialized => {
+            "tools/list" if session.lifecycle_state != LifecycleState::Initialized => {
                 Some(error_response(id, -32002, "Server not initialized"))
             }
             "tools/list" => Some(tools_list_response(id, registry::tool_definitions())),
-            "tools/call" if self.lifecycle_state != LifecycleState::Initialized => {
+            "tools/call" if session.lifecycle_state != LifecycleState::Initialized => {
                 Some(error_response(id, -32002, "Server not initialized"))
             }
             "tools/call" if self.repository_state_uncertain => {
@@ -101,12 +186,21 @@ impl<R: TaskRepositoryTrait> McpServer<R> {
         operation_now: DateTime<Local>,
     ) -> Value {
         let storage_directory = self.storage_directory.clone();
+        let datetime_policy = self.datetime_policy;
+        let default_deadline_time = self.default_deadline_time;
+        let busy_time_slots_path = self.busy_time_slots_path.clone();
+        let storage_cipher = self.storage_cipher.clone();
+        let lock_wait = if handler::is_mutating_tool_call(request) {
+            self.lock_wait
+        } else {
+            Duration::ZERO
+        };
         match run_repository_transaction(
             &mut self.repository,
             operation_now,
             || match storage_directory {
                 Some(storage_directory) => {
-                    StorageLock::acquire(&storage_directory, LockMode::Mcp).map(Some)
+                    acquire_mcp_lock(&storage_directory, lock_wait).map(Some)
                 }
                 None => Ok(None),
             },
@@ -117,7 +211,13 @@ impl<R: TaskRepositoryTrait> McpServer<R> {
                     repository,
                     id.clone(),
                     request,
-                    operation_now,
+                    handler::ToolClock {
+                        operation_now,
+                        datetime_policy: &datetime_policy,
+                        default_deadline_time,
+                        busy_time_slots_path: busy_time_slots_path.as_deref(),
+                        storage_cipher: storage_cipher.as_ref(),
+                    },
                     &mut factory,
                 );
                 let should_save = handler::tool_call_succeeded_with_mutation(request, &response)
@@ -145,6 +245,18 @@ impl<R: TaskRepositoryTrait> McpServer<R> {
     }
 }
 
+// 競合したら、lock_waitまで保持者の操作が終わるのを待つ
+fn acquire_mcp_lock(
+    storage_directory: &Path,
+    lock_wait: Duration,
+) -> Result<StorageLock, StorageLockError> {
+    if lock_wait.is_zero() {
+        StorageLock::acquire(storage_directory, LockMode::Mcp)
+    } else {
+        StorageLock::acquire_with_timeout(storage_directory, LockMode::Mcp, lock_wait)
+    }
+}
+
 fn repository_save_error_response(id: Value, message: &str) -> Value {
     tool_result_response(
         id,
@@ -1,28 +1,72 @@
 use super::input::{
-    decode_input, BreakdownTaskInput, CompleteTaskInput, CreateTaskInput, DeferTaskInput,
-    GetFocusInput, GetScheduleInput, GetTaskInput, ListTasksInput, ToolInputError, UpdateTaskInput,
+    decode_input, AddBusyBlockInput, BreakdownTaskInput, CompleteTaskInput, CreateTaskInput,
+    DeferTaskInput, DeleteTaskInput, ExportTasksInput, GetCriticalPathInput, GetFlowMetricsInput,
+    GetFocusInput, GetLoadHistoryInput, GetScheduleInput, GetTaskInput, GetTaskTreeInput,
+    ImportTasksInput, ListAtRiskTasksInput, ListBusyBlocksInput, ListTasksInput, ListTrashInput,
+    ListWaitingTasksInput, MergeTasksInput, RemoveBusyBlockInput, SubtreeDestinationInput,
+    ToolInputError, TrashEntryInput, UpdateTaskInput,
 };
 use super::internal_error_response;
-use super::output::{scheduled_task_view_json, task_view_json};
+use super::output::{scheduled_task_view_json, task_view_json, unscheduled_task_view_json};
 use super::protocol::{error_response, invalid_params_response, tool_result_response};
-use crate::application::interface::TaskRepositoryTrait;
-use crate::application::schedule_use_case::get_schedule;
+use crate::adapter::gateway::busy_block::BusyBlockFile;
+use crate::adapter::gateway::free_time_manager::FreeTimeManager;
+use crate::adapter::gateway::load_history::LoadHistoryFile;
+use crate::adapter::gateway::storage_encryption::StorageCipher;
+use crate::adapter::gateway::task_export::render_task_tree;
+use crate::adapter::gateway::task_import::{parse_task_outline, ImportClock};
+use crate::adapter::gateway::trash::TrashFile;
+use crate::application::at_risk::list_at_risk_tasks;
+use crate::application::busy_block::{
+    add_busy_block, busy_block_interval, list_busy_blocks, load_busy_blocks, remove_busy_block,
+    BusyBlockError,
+};
+use crate::application::critical_path::compute_critical_path;
+use crate::application::daily_capacity::try_subjective_date;
+use crate::application::flow_metrics::calculate_flow_metrics;
+use crate::application::interface::{FreeTimeManagerTrait, TaskRepositoryTrait};
+use crate::application::load_history::list_load_history;
+use crate::application::schedule_use_case::get_schedule_with_unscheduled;
+use crate::application::subtree::{copy_subtree, merge_sibling_tasks, move_subtree};
+use crate::application::task_import::{import_task_outline, ImportedTask};
+use crate::application::task_tree::{get_task_tree, get_task_tree_without_schedule};
 use crate::application::task_use_case::{
     breakdown_task as breakdown_task_use_case, complete_task as complete_task_use_case,
     create_task as create_task_use_case, defer_task as defer_task_use_case, get_focus, get_task,
-    list_tasks, set_category, set_deadline, set_estimate, ApplicationError, TaskFactory,
+    list_tasks, set_allowed_window, set_category, set_deadline, set_estimate, ApplicationError,
+    TaskFactory,
+};
+use crate::application::trash::{
+    delete_task as delete_task_use_case, list_trash, purge_trash_entry, restore_trash_entry,
+    TrashError,
 };
+use crate::application::waiting::list_waiting_tasks;
+use crate::entity::busy_block::{BusyBlock, BusyBlockSpan};
+use crate::entity::datetime::temporal::ClockTime;
+use crate::entity::datetime::BusinessDateTimePolicy;
 use chrono::{DateTime, Local};
 use serde_json::{json, Value};
+use std::path::Path;
 use uuid::Uuid;
 
+// 操作時刻と、日時表現や空き時間を解決し、保存先のfileを読み書きするための設定
+#[derive(Clone, Copy)]
+pub(super) struct ToolClock<'a> {
+    pub(super) operation_now: DateTime<Local>,
+    pub(super) datetime_policy: &'a BusinessDateTimePolicy,
+    pub(super) default_deadline_time: ClockTime,
+    pub(super) busy_time_slots_path: Option<&'a Path>,
+    pub(super) storage_cipher: Option<&'a StorageCipher>,
+}
+
 pub(super) fn call_tool<R: TaskRepositoryTrait>(
     repository: &mut R,
     id: Value,
     request: &Value,
-    operation_now: DateTime<Local>,
+    clock: ToolClock<'_>,
     factory: &mut TaskFactory<'_>,
 ) -> Value {
+    let operation_now = clock.operation_now;
     let params = &request["params"];
     match params["name"].as_str() {
         Some("get_focus") => {
@@ -50,7 +94,7 @@ pub(super) fn call_tool<R: TaskRepositoryTrait>(
                 Ok(input) => input,
                 Err(error) => return tool_input_error_response(id, error),
             };
-            call_list_tasks(repository, id, input)
+            call_list_tasks(repository, id, input, clock)
         }
         Some("get_schedule") => {
             let empty_arguments = json!({});
@@ -60,21 +104,41 @@ pub(super) fn call_tool<R: TaskRepositoryTrait>(
                 Ok(input) => input,
                 Err(error) => return tool_input_error_response(id, error),
             };
-            call_get_schedule(repository, id, input)
+            call_get_schedule(repository, id, input, clock)
+        }
+        Some("get_load_history") => {
+            let empty_arguments = json!({});
+            let input = match decode_input::<GetLoadHistoryInput>(
+                params.get("arguments").unwrap_or(&empty_arguments),
+            ) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_get_load_history(repository, id, input, clock)
+        }
+        Some("get_flow_metrics") => {
+            let empty_arguments = json!({});
+            let input = match decode_input::<GetFlowMetricsInput>(
+                params.get("arguments").unwrap_or(&empty_arguments),
+            ) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_get_flow_metrics(repository, id, input)
         }
         Some("create_task") => {
             let input = match decode_input::<CreateTaskInput>(&params["arguments"]) {
                 Ok(input) => input,
                 Err(error) => return tool_input_error_response(id, error),
             };
-            call_create_task(repository, id, input, factory)
+            call_create_task(repository, id, input, clock, factory)
         }
         Some("breakdown_task") => {
             let input = match decode_input::<BreakdownTaskInput>(&params["arguments"]) {
                 Ok(input) => input,
                 Err(error) => return tool_input_error_response(id, error),
             };
-            call_breakdown_task(repository, id, input, factory)
+            call_breakdown_task(repository, id, input, clock, factory)
         }
         Some("defer_task") => {
             let input = match decode_input::<DeferTaskInput>(&params["arguments"]) {
@@ -88,14 +152,138 @@ pub(super) fn call_tool<R: TaskRepositoryTrait>(
                 Ok(input) => input,
                 Err(error) => return tool_input_error_response(id, error),
             };
-            call_complete_task(repository, id, input, operation_now, factory)
+            call_complete_task(repository, id, input, clock, factory)
         }
         Some("update_task") => {
             let input = match decode_input::<UpdateTaskInput>(&params["arguments"]) {
                 Ok(input) => input,
                 Err(error) => return tool_input_error_response(id, error),
             };
-            call_update_task(repository, id, input)
+            call_update_task(repository, id, input, clock)
+        }
+        Some("delete_task") => {
+            let input = match decode_input::<DeleteTaskInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_delete_task(repository, id, input, clock)
+        }
+        Some("list_trash") => {
+            let empty_arguments = json!({});
+            let input = match decode_input::<ListTrashInput>(
+                params.get("arguments").unwrap_or(&empty_arguments),
+            ) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_list_trash(repository, id, input, clock)
+        }
+        Some("restore_task") => {
+            let input = match decode_input::<TrashEntryInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_restore_task(repository, id, input, clock)
+        }
+        Some("purge_trash") => {
+            let input = match decode_input::<TrashEntryInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_purge_trash(repository, id, input, clock)
+        }
+        Some("add_busy_block") => {
+            let input = match decode_input::<AddBusyBlockInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_add_busy_block(repository, id, input, clock)
+        }
+        Some("list_busy_blocks") => {
+            let empty_arguments = json!({});
+            let input = match decode_input::<ListBusyBlocksInput>(
+                params.get("arguments").unwrap_or(&empty_arguments),
+            ) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_list_busy_blocks(repository, id, input, clock)
+        }
+        Some("remove_busy_block") => {
+            let input = match decode_input::<RemoveBusyBlockInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_remove_busy_block(repository, id, input, clock)
+        }
+        Some("list_waiting_tasks") => {
+            let empty_arguments = json!({});
+            let input = match decode_input::<ListWaitingTasksInput>(
+                params.get("arguments").unwrap_or(&empty_arguments),
+            ) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_list_waiting_tasks(repository, id, input, operation_now)
+        }
+        Some("list_at_risk_tasks") => {
+            let empty_arguments = json!({});
+            let input = match decode_input::<ListAtRiskTasksInput>(
+                params.get("arguments").unwrap_or(&empty_arguments),
+            ) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_list_at_risk_tasks(repository, id, input, clock)
+        }
+        Some("get_critical_path") => {
+            let input = match decode_input::<GetCriticalPathInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_get_critical_path(repository, id, input, clock)
+        }
+        Some("get_task_tree") => {
+            let input = match decode_input::<GetTaskTreeInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_get_task_tree(repository, id, input, clock)
+        }
+        Some("move_task") => {
+            let input = match decode_input::<SubtreeDestinationInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_move_task(repository, id, input)
+        }
+        Some("copy_task") => {
+            let input = match decode_input::<SubtreeDestinationInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_copy_task(repository, id, input, factory)
+        }
+        Some("merge_tasks") => {
+            let input = match decode_input::<MergeTasksInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_merge_tasks(repository, id, input)
+        }
+        Some("import_tasks") => {
+            let input = match decode_input::<ImportTasksInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_import_tasks(repository, id, input, clock, factory)
+        }
+        Some("export_tasks") => {
+            let input = match decode_input::<ExportTasksInput>(&params["arguments"]) {
+                Ok(input) => input,
+                Err(error) => return tool_input_error_response(id, error),
+            };
+            call_export_tasks(repository, id, input)
         }
         _ => error_response(id, -32602, "Unknown tool"),
     }
@@ -129,8 +317,19 @@ fn call_list_tasks<R: TaskRepositoryTrait>(
     repository: &R,
     id: Value,
     input: ListTasksInput,
+    clock: ToolClock<'_>,
 ) -> Value {
-    match list_tasks(repository, input.into_filter()) {
+    let mut free_time_manager =
+        match load_free_time_manager(clock, repository.get_project_storage_dir_name()) {
+            Ok(free_time_manager) => free_time_manager,
+            Err(error) => return internal_error_response(id, &error.to_string()),
+        };
+    match list_tasks(
+        repository,
+        &mut free_time_manager,
+        clock.datetime_policy,
+        input.into_filter(),
+    ) {
         Ok(tasks) => tool_result_response(
             id,
             json!({
@@ -145,10 +344,12 @@ fn call_list_tasks<R: TaskRepositoryTrait>(
     }
 }
 
+// 空き時間はCLIと同じ予定表から読み、埋まっている時間と夜を避けた予定を返す
 pub(super) fn call_get_schedule<R: TaskRepositoryTrait>(
     repository: &R,
     id: Value,
     input: GetScheduleInput,
+    clock: ToolClock<'_>,
 ) -> Value {
     let (from, until) = match input.into_period(repository.get_last_synced_time()) {
         Ok(period) => period,
@@ -161,14 +362,26 @@ pub(super) fn call_get_schedule<R: TaskRepositoryTrait>(
         }
     };
 
-    match get_schedule(repository) {
+    let mut free_time_manager =
+        match load_free_time_manager(clock, repository.get_project_storage_dir_name()) {
+            Ok(free_time_manager) => free_time_manager,
+            Err(error) => return internal_error_response(id, &error.to_string()),
+        };
+    match get_schedule_with_unscheduled(repository, &mut free_time_manager, clock.datetime_policy) {
+        // 置けなかったtaskには時刻がないので、範囲で絞らずにすべて返す
         Ok(schedule) => tool_result_response(
             id,
             json!({
                 "schedule": schedule
+                    .scheduled_tasks
                     .iter()
                     .filter(|scheduled| scheduled.scheduled_start < until && scheduled.scheduled_end > from)
                     .map(scheduled_task_view_json)
+                    .collect::<Vec<_>>(),
+                "unscheduled": schedule
+                    .unscheduled_tasks
+                    .iter()
+                    .map(unscheduled_task_view_json)
                     .collect::<Vec<_>>()
             }),
             false,
@@ -177,13 +390,72 @@ pub(super) fn call_get_schedule<R: TaskRepositoryTrait>(
     }
 }
 
+fn call_get_load_history<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    input: GetLoadHistoryInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let today = match try_subjective_date(repository.get_last_synced_time()) {
+        Ok(today) => today,
+        Err(error) => return internal_error_response(id, &error.to_string()),
+    };
+    let (from, until) = match input.into_date_range(today) {
+        Ok(range) => range,
+        Err(ToolInputError::Schema(error)) => return invalid_params_response(id, error),
+        Err(ToolInputError::Semantic { field, message }) => {
+            return invalid_input_response(id, &field, message)
+        }
+        Err(ToolInputError::Application(error)) => {
+            return internal_error_response(id, &error.to_string())
+        }
+    };
+
+    let store = LoadHistoryFile::in_storage_directory(repository.get_project_storage_dir_name())
+        .with_cipher(clock.storage_cipher.cloned());
+    match list_load_history(&store, from, until) {
+        Ok(snapshots) => tool_result_response(id, json!({"snapshots": snapshots}), false),
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+fn call_get_flow_metrics<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    input: GetFlowMetricsInput,
+) -> Value {
+    let today = match try_subjective_date(repository.get_last_synced_time()) {
+        Ok(today) => today,
+        Err(error) => return internal_error_response(id, &error.to_string()),
+    };
+    let (from, until) = match input.into_date_range(today) {
+        Ok(range) => range,
+        Err(ToolInputError::Schema(error)) => return invalid_params_response(id, error),
+        Err(ToolInputError::Semantic { field, message }) => {
+            return invalid_input_response(id, &field, message)
+        }
+        Err(ToolInputError::Application(error)) => {
+            return internal_error_response(id, &error.to_string())
+        }
+    };
+
+    match calculate_flow_metrics(repository, from, until) {
+        Ok(metrics) => tool_result_response(id, json!({"metrics": metrics}), false),
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
 fn call_create_task<R: TaskRepositoryTrait>(
     repository: &mut R,
     id: Value,
     input: CreateTaskInput,
+    clock: ToolClock<'_>,
     factory: &mut TaskFactory<'_>,
 ) -> Value {
-    let input = input.into_application();
+    let input = match input.into_application(clock.operation_now, clock.datetime_policy) {
+        Ok(input) => input,
+        Err(error) => return datetime_input_error_response(id, error),
+    };
 
     let task_id = match create_task_use_case(repository, input, factory) {
         Ok(task_id) => task_id,
@@ -200,9 +472,13 @@ fn call_breakdown_task<R: TaskRepositoryTrait>(
     repository: &mut R,
     id: Value,
     input: BreakdownTaskInput,
+    clock: ToolClock<'_>,
     factory: &mut TaskFactory<'_>,
 ) -> Value {
-    let input = input.into_application();
+    let input = match input.into_application(clock.operation_now, clock.datetime_policy) {
+        Ok(input) => input,
+        Err(error) => return datetime_input_error_response(id, error),
+    };
     let child_ids = match breakdown_task_use_case(repository, input, factory) {
         Ok(child_ids) => child_ids,
         Err(ApplicationError::TaskNotFound(task_id)) => {
@@ -248,10 +524,13 @@ fn call_complete_task<R: TaskRepositoryTrait>(
     repository: &mut R,
     id: Value,
     input: CompleteTaskInput,
-    operation_now: DateTime<Local>,
+    clock: ToolClock<'_>,
     factory: &mut TaskFactory<'_>,
 ) -> Value {
-    let input = input.into_application(operation_now);
+    let input = match input.into_application(clock.operation_now, clock.datetime_policy) {
+        Ok(input) => input,
+        Err(error) => return datetime_input_error_response(id, error),
+    };
     let task_id = input.task_id;
     let output = match complete_task_use_case(repository, input, factory) {
         Ok(output) => output,
@@ -281,38 +560,481 @@ fn call_complete_task<R: TaskRepositoryTrait>(
     )
 }
 
-fn call_update_task<R: TaskRepositoryTrait>(
-    repository: &mut R,
+fn call_update_task<R: TaskRepositoryTrait>(
+    repository: &mut R,
+    id: Value,
+    input: UpdateTaskInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let input = match input.into_changes(
+        clock.operation_now,
+        clock.datetime_policy,
+        clock.default_deadline_time,
+    ) {
+        Ok(input) => input,
+        Err(error) => return datetime_input_error_response(id, error),
+    };
+
+    if let Some(estimated_work_minutes) = input.estimated_work_minutes {
+        if let Err(error) = set_estimate(repository, input.task_id, estimated_work_minutes) {
+            return update_task_application_error_response(id, error);
+        }
+    }
+    if let Some(deadline_time) = input.deadline_time {
+        if let Err(error) = set_deadline(repository, input.task_id, deadline_time) {
+            return update_task_application_error_response(id, error);
+        }
+    }
+    if let Some(category) = input.category {
+        if let Err(error) = set_category(repository, input.task_id, category) {
+            return update_task_application_error_response(id, error);
+        }
+    }
+    if let Some(allowed_window) = input.allowed_window {
+        if let Err(error) = set_allowed_window(repository, input.task_id, allowed_window) {
+            return update_task_application_error_response(id, error);
+        }
+    }
+
+    tool_result_response(id, json!({"task_id": input.task_id.to_string()}), false)
+}
+
+// 暗号化した保存先では、捨てた記録もproject fileと同じ鍵で読み書きする
+fn trash_file<R: TaskRepositoryTrait>(repository: &R, clock: ToolClock<'_>) -> TrashFile {
+    TrashFile::in_storage_directory(repository.get_project_storage_dir_name())
+        .with_cipher(clock.storage_cipher.cloned())
+}
+
+fn call_delete_task<R: TaskRepositoryTrait>(
+    repository: &mut R,
+    id: Value,
+    input: DeleteTaskInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let mut store = trash_file(repository, clock);
+    match delete_task_use_case(repository, &mut store, input.task_id.0, clock.operation_now) {
+        Ok(entry) => tool_result_response(id, json!({"entry": entry}), false),
+        Err(error) => trash_error_response(id, error, "task_id"),
+    }
+}
+
+fn call_list_trash<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    _input: ListTrashInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let store = trash_file(repository, clock);
+    match list_trash(&store) {
+        Ok(entries) => tool_result_response(id, json!({"entries": entries}), false),
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+fn call_list_waiting_tasks<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    _input: ListWaitingTasksInput,
+    operation_now: DateTime<Local>,
+) -> Value {
+    match list_waiting_tasks(repository, operation_now) {
+        Ok(groups) => tool_result_response(id, json!({"groups": groups}), false),
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+fn call_list_at_risk_tasks<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    input: ListAtRiskTasksInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let mut free_time_manager =
+        match load_free_time_manager(clock, repository.get_project_storage_dir_name()) {
+            Ok(free_time_manager) => free_time_manager,
+            Err(error) => return internal_error_response(id, &error.to_string()),
+        };
+    match list_at_risk_tasks(
+        repository,
+        &mut free_time_manager,
+        clock.datetime_policy,
+        input.max_slack_seconds(),
+    ) {
+        Ok(tasks) => tool_result_response(id, json!({"tasks": tasks}), false),
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+// 空き時間はCLIと同じ予定表から読み、設定がなければすべての時間を空きとして数える
+fn call_get_critical_path<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    input: GetCriticalPathInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let task_id = input.task_id.0;
+    let mut free_time_manager =
+        match load_free_time_manager(clock, repository.get_project_storage_dir_name()) {
+            Ok(free_time_manager) => free_time_manager,
+            Err(error) => return internal_error_response(id, &error.to_string()),
+        };
+    match compute_critical_path(repository, &mut free_time_manager, task_id) {
+        Ok(critical_path) => {
+            tool_result_response(id, json!({"critical_path": critical_path}), false)
+        }
+        Err(ApplicationError::TaskNotFound(task_id)) => {
+            task_not_found_response(id, task_id, Some("task_id"))
+        }
+        Err(ApplicationError::InvalidInput { field, reason }) => {
+            invalid_input_response(id, field, reason)
+        }
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+// 予定表の設定がないか、予定表のfileがなければ、すべての時間を空きとして数える。
+// 日付を決めた埋まっている時間は保存先から重ねて読む
+fn load_free_time_manager(
+    clock: ToolClock<'_>,
+    storage_directory: &str,
+) -> Result<FreeTimeManager, BusyBlockError> {
+    let mut free_time_manager =
+        FreeTimeManager::new().with_holiday_weekday(clock.datetime_policy.holiday_weekday());
+    if let Some(path) = clock.busy_time_slots_path.filter(|path| path.exists()) {
+        free_time_manager.load_busy_time_slots_from_file(&path.to_string_lossy())?;
+    }
+    load_busy_blocks(
+        &mut free_time_manager,
+        &BusyBlockFile::in_storage_directory(storage_directory)
+            .with_cipher(clock.storage_cipher.cloned()),
+    )?;
+    Ok(free_time_manager)
+}
+
+// 暗号化した保存先では、埋まっている時間もproject fileと同じ鍵で読み書きする
+fn busy_block_file<R: TaskRepositoryTrait>(repository: &R, clock: ToolClock<'_>) -> BusyBlockFile {
+    BusyBlockFile::in_storage_directory(repository.get_project_storage_dir_name())
+        .with_cipher(clock.storage_cipher.cloned())
+}
+
+fn call_add_busy_block<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    input: AddBusyBlockInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let name = input.name.0.clone();
+    let span = match input.into_span(clock.operation_now, clock.datetime_policy) {
+        Ok(span) => span,
+        Err(error) => return datetime_input_error_response(id, error),
+    };
+    let mut store = busy_block_file(repository, clock);
+    match add_busy_block(&mut store, &name, span) {
+        Ok(block) => busy_block_result_response(id, &block),
+        Err(BusyBlockError::Application(error)) => datetime_input_error_response(id, error),
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+fn call_list_busy_blocks<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    _input: ListBusyBlocksInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let store = busy_block_file(repository, clock);
+    let blocks = match list_busy_blocks(&store) {
+        Ok(blocks) => blocks,
+        Err(error) => return internal_error_response(id, &error.to_string()),
+    };
+    match blocks
+        .iter()
+        .map(busy_block_json)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(blocks) => tool_result_response(id, json!({"busy_blocks": blocks}), false),
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+fn call_remove_busy_block<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    input: RemoveBusyBlockInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let mut store = busy_block_file(repository, clock);
+    match remove_busy_block(&mut store, input.busy_block_id.0) {
+        Ok(block) => busy_block_result_response(id, &block),
+        Err(error @ BusyBlockError::BlockNotFound(_)) => tool_result_response(
+            id,
+            json!({
+                "error": {
+                    "code": "busy_block_not_found",
+                    "message": error.to_string(),
+                    "field": "busy_block_id"
+                }
+            }),
+            true,
+        ),
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+fn busy_block_result_response(id: Value, block: &BusyBlock) -> Value {
+    match busy_block_json(block) {
+        Ok(block) => tool_result_response(id, json!({"busy_block": block}), false),
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+// start/endは実際に塞ぐ区間。終日なら日付も並べて返す
+fn busy_block_json(block: &BusyBlock) -> Result<Value, ApplicationError> {
+    let (start, end) = busy_block_interval(block)?;
+    let mut value = json!({
+        "busy_block_id": block.busy_block_id.to_string(),
+        "name": block.name,
+        "all_day": matches!(block.span, BusyBlockSpan::AllDay { .. }),
+        "start": start.to_rfc3339(),
+        "end": end.to_rfc3339()
+    });
+    if let BusyBlockSpan::AllDay {
+        start_date,
+        end_date,
+    } = block.span
+    {
+        value["start_date"] = Value::String(start_date.format("%Y-%m-%d").to_string());
+        value["end_date"] = Value::String(end_date.format("%Y-%m-%d").to_string());
+    }
+    Ok(value)
+}
+
+fn call_get_task_tree<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    input: GetTaskTreeInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let mut free_time_manager =
+        match load_free_time_manager(clock, repository.get_project_storage_dir_name()) {
+            Ok(free_time_manager) => free_time_manager,
+            Err(error) => return internal_error_response(id, &error.to_string()),
+        };
+    match get_task_tree(
+        repository,
+        &mut free_time_manager,
+        clock.datetime_policy,
+        input.task_id.0,
+        input.max_depth(),
+    ) {
+        Ok(tree) => tool_result_response(id, json!({"tree": tree}), false),
+        Err(ApplicationError::TaskNotFound(task_id)) => {
+            task_not_found_response(id, task_id, Some("task_id"))
+        }
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+fn call_export_tasks<R: TaskRepositoryTrait>(
+    repository: &R,
+    id: Value,
+    input: ExportTasksInput,
+) -> Value {
+    match get_task_tree_without_schedule(repository, input.task_id.0, None) {
+        Ok(tree) => tool_result_response(
+            id,
+            json!({
+                "format": input.format.0.as_str(),
+                "text": render_task_tree(&tree, input.format.0)
+            }),
+            false,
+        ),
+        Err(ApplicationError::TaskNotFound(task_id)) => {
+            task_not_found_response(id, task_id, Some("task_id"))
+        }
+        Err(error) => internal_error_response(id, &error.to_string()),
+    }
+}
+
+fn call_restore_task<R: TaskRepositoryTrait>(
+    repository: &mut R,
+    id: Value,
+    input: TrashEntryInput,
+    clock: ToolClock<'_>,
+) -> Value {
+    let mut store = trash_file(repository, clock);
+    match restore_trash_entry(
+        repository,
+        &mut store,
+        input.trash_id.0,
+        clock.operation_now,
+    ) {
+        Ok(entry) => tool_result_response(id, json!({"entry": entry}), false),
+        Err(error) => trash_error_response(id, error, "trash_id"),
+    }
+}
+
+fn call_move_task<R: TaskRepositoryTrait>(
+    repository: &mut R,
+    id: Value,
+    input: SubtreeDestinationInput,
+) -> Value {
+    let task_id = input.task_id.0;
+    match move_subtree(repository, task_id, input.parent_id()) {
+        Ok(()) => tool_result_response(id, json!({"task_id": task_id.to_string()}), false),
+        Err(error) => subtree_error_response(id, error, task_id, "parent_id"),
+    }
+}
+
+fn call_copy_task<R: TaskRepositoryTrait>(
+    repository: &mut R,
+    id: Value,
+    input: SubtreeDestinationInput,
+    factory: &mut TaskFactory<'_>,
+) -> Value {
+    let task_id = input.task_id.0;
+    match copy_subtree(repository, task_id, input.parent_id(), factory) {
+        Ok(copy_id) => tool_result_response(id, json!({"task_id": copy_id.to_string()}), false),
+        Err(error) => subtree_error_response(id, error, task_id, "parent_id"),
+    }
+}
+
+fn call_merge_tasks<R: TaskRepositoryTrait>(
+    repository: &mut R,
+    id: Value,
+    input: MergeTasksInput,
+) -> Value {
+    let task_id = input.task_id.0;
+    match merge_sibling_tasks(repository, task_id, input.source_task_id.0) {
+        Ok(()) => tool_result_response(id, json!({"task_id": task_id.to_string()}), false),
+        Err(error) => subtree_error_response(id, error, task_id, "source_task_id"),
+    }
+}
+
+fn call_import_tasks<R: TaskRepositoryTrait>(
+    repository: &mut R,
+    id: Value,
+    input: ImportTasksInput,
+    clock: ToolClock<'_>,
+    factory: &mut TaskFactory<'_>,
+) -> Value {
+    let import_clock = ImportClock {
+        now: clock.operation_now,
+        policy: clock.datetime_policy,
+        default_deadline_time: clock.default_deadline_time,
+    };
+    let outline = match parse_task_outline(&input.text.0, input.format.0, &import_clock) {
+        Ok(outline) => outline,
+        Err(error) => return invalid_input_response(id, "text", &error.to_string()),
+    };
+    let task_ids = if input.dry_run() {
+        vec![]
+    } else {
+        match import_task_outline(repository, &outline, input.parent_id(), factory) {
+            Ok(task_ids) => task_ids,
+            Err(ApplicationError::TaskNotFound(parent_id)) => {
+                return task_not_found_response(id, parent_id, Some("parent_id"))
+            }
+            Err(ApplicationError::InvalidInput { field, reason }) => {
+                return invalid_input_response(id, field, reason)
+            }
+            Err(error) => return internal_error_response(id, &error.to_string()),
+        }
+    };
+    tool_result_response(
+        id,
+        json!({
+            "dry_run": input.dry_run(),
+            "tasks": outline.tasks.iter().map(imported_task_json).collect::<Vec<_>>(),
+            "task_count": outline.task_count(),
+            "skipped_done_count": outline.skipped_done_count,
+            "task_ids": task_ids.iter().map(Uuid::to_string).collect::<Vec<_>>()
+        }),
+        false,
+    )
+}
+
+fn imported_task_json(task: &ImportedTask) -> Value {
+    json!({
+        "name": task.name,
+        "status": task.status.to_string(),
+        "estimated_work_minutes": task.estimated_work_minutes,
+        "actual_work_minutes": task.actual_work_minutes,
+        "deadline_time": task.deadline_time.map(|deadline_time| deadline_time.to_rfc3339()),
+        "pending_until": task.pending_until.map(|pending_until| pending_until.to_rfc3339()),
+        "children": task.children.iter().map(imported_task_json).collect::<Vec<_>>()
+    })
+}
+
+// 見つからないidがtask_idでなければ、もう一方の指定先のfieldを返す
+fn subtree_error_response(
+    id: Value,
+    error: ApplicationError,
+    task_id: Uuid,
+    other_field: &str,
+) -> Value {
+    match error {
+        ApplicationError::TaskNotFound(missing_id) => task_not_found_response(
+            id,
+            missing_id,
+            Some(if missing_id == task_id {
+                "task_id"
+            } else {
+                other_field
+            }),
+        ),
+        ApplicationError::InvalidInput { field, reason } => {
+            invalid_input_response(id, field, reason)
+        }
+        error => internal_error_response(id, &error.to_string()),
+    }
+}
+
+fn call_purge_trash<R: TaskRepositoryTrait>(
+    repository: &R,
     id: Value,
-    input: UpdateTaskInput,
+    input: TrashEntryInput,
+    clock: ToolClock<'_>,
 ) -> Value {
-    let input = input.into_changes();
-
-    if let Some(estimated_work_minutes) = input.estimated_work_minutes {
-        if let Err(error) = set_estimate(repository, input.task_id, estimated_work_minutes) {
-            return update_task_application_error_response(id, error);
-        }
-    }
-    if let Some(deadline_time) = input.deadline_time {
-        if let Err(error) = set_deadline(repository, input.task_id, deadline_time) {
-            return update_task_application_error_response(id, error);
-        }
-    }
-    if let Some(category) = input.category {
-        if let Err(error) = set_category(repository, input.task_id, category) {
-            return update_task_application_error_response(id, error);
-        }
+    let mut store = trash_file(repository, clock);
+    match purge_trash_entry(&mut store, input.trash_id.0) {
+        Ok(purged) => tool_result_response(
+            id,
+            json!({
+                "entry": purged.entry,
+                "removed_project_directory": purged.removed_project_directory
+            }),
+            false,
+        ),
+        Err(error) => trash_error_response(id, error, "trash_id"),
     }
-
-    tool_result_response(id, json!({"task_id": input.task_id.to_string()}), false)
 }
 
 pub(super) fn tool_call_succeeded_with_mutation(request: &Value, response: &Value) -> bool {
+    is_mutating_tool_call(request)
+        && response.get("error").is_none()
+        && response["result"]["isError"] != Value::Bool(true)
+}
+
+pub(super) fn is_mutating_tool_call(request: &Value) -> bool {
     matches!(
         request["params"]["name"].as_str(),
-        Some("create_task" | "breakdown_task" | "defer_task" | "complete_task" | "update_task")
-    ) && response.get("error").is_none()
-        && response["result"]["isError"] != Value::Bool(true)
+        Some(
+            "create_task"
+                | "breakdown_task"
+                | "defer_task"
+                | "complete_task"
+                | "update_task"
+                | "delete_task"
+                | "restore_task"
+                | "move_task"
+                | "copy_task"
+                | "merge_tasks"
+                | "import_tasks"
+        )
+    )
+        // 確認だけの取り込みは何も変えない
+        && request["params"]["arguments"]["dry_run"] != Value::Bool(true)
 }
 
 fn invalid_input_response(id: Value, field: &str, message: &str) -> Value {
@@ -329,6 +1051,15 @@ fn invalid_input_response(id: Value, field: &str, message: &str) -> Value {
     )
 }
 
+fn datetime_input_error_response(id: Value, error: ApplicationError) -> Value {
+    match error {
+        ApplicationError::InvalidInput { field, reason } => {
+            invalid_input_response(id, field, reason)
+        }
+        error => internal_error_response(id, &error.to_string()),
+    }
+}
+
 fn tool_input_error_response(id: Value, error: ToolInputError) -> Value {
     match error {
         ToolInputError::Schema(error) => invalid_params_response(id, error),
@@ -364,6 +1095,30 @@ fn has_undone_children_response(id: Value, task_id: Uuid) -> Value {
     )
 }
 
+fn trash_error_response(id: Value, error: TrashError, field: &str) -> Value {
+    let (code, task_id) = match &error {
+        TrashError::Application(ApplicationError::TaskNotFound(task_id)) => {
+            return task_not_found_response(id, *task_id, Some(field))
+        }
+        TrashError::EntryNotFound(_) => ("trash_entry_not_found", None),
+        TrashError::ProjectHasOpenWork { task_id, .. } => ("project_has_open_work", Some(*task_id)),
+        TrashError::TaskAlreadyExists(task_id) => ("task_already_exists", Some(*task_id)),
+        TrashError::ParentNotFound(task_id) => ("parent_not_found", Some(*task_id)),
+        TrashError::Store(_) | TrashError::Application(_) | TrashError::AmbiguousEntry(_) => {
+            return internal_error_response(id, &error.to_string())
+        }
+    };
+    let mut body = json!({
+        "code": code,
+        "message": error.to_string(),
+        "field": field
+    });
+    if let Some(task_id) = task_id {
+        body["task_id"] = Value::String(task_id.to_string());
+    }
+    tool_result_response(id, json!({"error": body}), true)
+}
+
 fn update_task_application_error_response(id: Value, error: ApplicationError) -> Value {
     match error {
         ApplicationError::TaskNotFound(task_id) => {
@@ -381,16 +1136,24 @@ mod typed_handler_contract_tests {
     use super::{
         call_breakdown_task as call_breakdown_task_with_factory,
         call_complete_task as call_complete_task_with_factory,
-        call_create_task as call_create_task_with_factory, call_defer_task, call_get_focus,
-        call_get_schedule, call_get_task, call_list_tasks, call_update_task,
-        tool_input_error_response,
+        call_copy_task as call_copy_task_with_factory,
+        call_create_task as call_create_task_with_factory, call_defer_task, call_export_tasks,
+        call_get_critical_path, call_get_focus, call_get_schedule, call_get_task,
+        call_get_task_tree, call_import_tasks as call_import_tasks_with_factory,
+        call_list_at_risk_tasks, call_list_tasks, call_list_waiting_tasks, call_merge_tasks,
+        call_move_task, call_update_task as call_update_task_with_clock, tool_input_error_response,
+        ToolClock,
     };
+    use crate::adapter::gateway::task_export::ExportFormat;
+    use crate::adapter::gateway::task_import::ImportFormat;
     use crate::adapter::mcp::input::{
-        BreakdownTaskInput, CompleteTaskInput, CreateTaskInput, DeferTaskInput, GetFocusInput,
-        GetScheduleInput, GetTaskInput, IsoDate, ListTasksInput, NonEmptyString, NonEmptyVec,
-        NonNegativeI64, NullablePatch, OptionalValue, ProjectCategoryValue, Rfc3339DateTime,
-        StatusValue, TaskPeriodFieldValue, TaskPeriodInput, ToolInputError, UpdateTaskInput,
-        UuidValue,
+        AllowedWindowValue, BreakdownTaskInput, CompleteTaskInput, CreateTaskInput, DateTimeValue,
+        DeferTaskInput, ExportFormatValue, ExportTasksInput, GetCriticalPathInput, GetFocusInput,
+        GetScheduleInput, GetTaskInput, GetTaskTreeInput, ImportFormatValue, ImportTasksInput,
+        IsoDate, ListAtRiskTasksInput, ListTasksInput, ListWaitingTasksInput, MergeTasksInput,
+        NonEmptyString, NonEmptyVec, NonNegativeI64, NullablePatch, OptionalValue,
+        ProjectCategoryValue, Rfc3339DateTime, StatusValue, SubtreeDestinationInput,
+        TaskPeriodFieldValue, TaskPeriodInput, ToolInputError, UpdateTaskInput, UuidValue,
     };
     use crate::adapter::mcp::test_support::{
         assert_tool_result_content_matches_structured, fixed_now, new_task_handle, task_for_list,
@@ -398,7 +1161,13 @@ mod typed_handler_contract_tests {
         TimeZone,
     };
     use crate::application::task_use_case::{ApplicationError, TaskFactory};
-    use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+    use crate::application::waiting::start_waiting;
+    use crate::entity::allowed_window::AllowedWindow;
+    use crate::entity::datetime::temporal::ClockTime;
+    use crate::entity::datetime::BusinessDateTimePolicy;
+    use crate::entity::waiting::WaitingRecord;
+    use crate::test_support::new_task_attr_at;
+    use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
     use serde_json::{json, Value};
     use std::rc::Rc;
     use uuid::Uuid;
@@ -410,7 +1179,7 @@ mod typed_handler_contract_tests {
     ) -> serde_json::Value {
         let mut next_id = Uuid::new_v4;
         let mut factory = TaskFactory::new(fixed_now(), &mut next_id);
-        call_create_task_with_factory(repository, id, input, &mut factory)
+        call_create_task_with_factory(repository, id, input, test_clock(&policy()), &mut factory)
     }
 
     fn call_breakdown_task(
@@ -420,7 +1189,7 @@ mod typed_handler_contract_tests {
     ) -> serde_json::Value {
         let mut next_id = Uuid::new_v4;
         let mut factory = TaskFactory::new(fixed_now(), &mut next_id);
-        call_breakdown_task_with_factory(repository, id, input, &mut factory)
+        call_breakdown_task_with_factory(repository, id, input, test_clock(&policy()), &mut factory)
     }
 
     fn call_complete_task(
@@ -428,10 +1197,51 @@ mod typed_handler_contract_tests {
         id: serde_json::Value,
         input: CompleteTaskInput,
     ) -> serde_json::Value {
-        let operation_now = fixed_now();
         let mut next_id = Uuid::new_v4;
-        let mut factory = TaskFactory::new(operation_now, &mut next_id);
-        call_complete_task_with_factory(repository, id, input, operation_now, &mut factory)
+        let mut factory = TaskFactory::new(fixed_now(), &mut next_id);
+        call_complete_task_with_factory(repository, id, input, test_clock(&policy()), &mut factory)
+    }
+
+    fn call_copy_task(
+        repository: &mut RecordingRepository,
+        id: serde_json::Value,
+        input: SubtreeDestinationInput,
+    ) -> serde_json::Value {
+        let mut next_id = Uuid::new_v4;
+        let mut factory = TaskFactory::new(fixed_now(), &mut next_id);
+        call_copy_task_with_factory(repository, id, input, &mut factory)
+    }
+
+    fn call_import_tasks(
+        repository: &mut RecordingRepository,
+        id: serde_json::Value,
+        input: ImportTasksInput,
+    ) -> serde_json::Value {
+        let mut next_id = Uuid::new_v4;
+        let mut factory = TaskFactory::new(fixed_now(), &mut next_id);
+        call_import_tasks_with_factory(repository, id, input, test_clock(&policy()), &mut factory)
+    }
+
+    fn call_update_task(
+        repository: &mut RecordingRepository,
+        id: serde_json::Value,
+        input: UpdateTaskInput,
+    ) -> serde_json::Value {
+        call_update_task_with_clock(repository, id, input, test_clock(&policy()))
+    }
+
+    fn policy() -> BusinessDateTimePolicy {
+        BusinessDateTimePolicy::new(30).with_non_business_weekdays(&[Weekday::Sat, Weekday::Sun])
+    }
+
+    fn test_clock(policy: &BusinessDateTimePolicy) -> ToolClock<'_> {
+        ToolClock {
+            operation_now: fixed_now(),
+            datetime_policy: policy,
+            default_deadline_time: ClockTime::new(19, 0, 0).unwrap(),
+            busy_time_slots_path: None,
+            storage_cipher: None,
+        }
     }
 
     fn assert_application_datetime_error_metadata(expected: ApplicationError) {
@@ -490,7 +1300,7 @@ mod typed_handler_contract_tests {
         id: &str,
         input: ListTasksInput,
     ) -> serde_json::Value {
-        call_list_tasks(repository, json!(id), input)
+        call_list_tasks(repository, json!(id), input, test_clock(&policy()))
     }
 
     fn response_task_ids(response: &serde_json::Value) -> Vec<Uuid> {
@@ -547,6 +1357,421 @@ mod typed_handler_contract_tests {
         assert_eq!(mutation_count.get(), 0);
     }
 
+    #[test]
+    fn list_waiting_tasks_handlerは相手ごとの待ちと催促の要否を返しrepositoryを変更しない() {
+        let task = new_task_handle("返事待ち").unwrap();
+        let task_id = task.get_id().unwrap();
+        start_waiting(
+            &task,
+            WaitingRecord::new("reviewer", fixed_now() - Duration::days(4), None, Some(3)),
+        )
+        .unwrap();
+        let repository = RecordingRepository::new(vec![task]);
+        let save_count = Rc::clone(&repository.save_count);
+        let mutation_count = Rc::clone(&repository.mutation_count);
+
+        let response = call_list_waiting_tasks(
+            &repository,
+            json!("typed-waiting"),
+            ListWaitingTasksInput {},
+            fixed_now(),
+        );
+
+        assert_eq!(response["result"]["isError"], false);
+        let group = &response["result"]["structuredContent"]["groups"][0];
+        assert_eq!(group["counterpart"], "reviewer");
+        assert_eq!(group["entries"][0]["task"]["id"], task_id.to_string());
+        assert_eq!(
+            group["entries"][0]["task"]["waiting"]["counterpart"],
+            "reviewer"
+        );
+        assert_eq!(group["entries"][0]["age_days"], 4);
+        assert_eq!(group["entries"][0]["follow_up_due"], true);
+        assert_tool_result_content_matches_structured(&response);
+        assert_eq!(save_count.get(), 0);
+        assert_eq!(mutation_count.get(), 0);
+    }
+
+    #[test]
+    fn list_at_risk_tasks_handlerは余裕の上限を秒に直して遅れるtaskを返す() {
+        let late = new_task_handle("遅れる作業").unwrap();
+        late.sync_clock(fixed_now()).unwrap();
+        late.set_start_time(fixed_now()).unwrap();
+        late.set_estimated_work_seconds(2 * 60 * 60).unwrap();
+        late.set_deadline_time_opt(Some(fixed_now() + Duration::hours(1)))
+            .unwrap();
+        let relaxed = new_task_handle("余裕のある作業").unwrap();
+        relaxed.sync_clock(fixed_now()).unwrap();
+        relaxed.set_start_time(fixed_now()).unwrap();
+        relaxed.set_estimated_work_seconds(30 * 60).unwrap();
+        relaxed
+            .set_deadline_time_opt(Some(fixed_now() + Duration::days(3)))
+            .unwrap();
+        let late_id = late.get_id().unwrap();
+        let repository = RecordingRepository::new(vec![late, relaxed]);
+        let save_count = Rc::clone(&repository.save_count);
+
+        let response = call_list_at_risk_tasks(
+            &repository,
+            json!("typed-at-risk"),
+            ListAtRiskTasksInput {
+                max_slack_minutes: OptionalValue::Value(NonNegativeI64(60)),
+            },
+            test_clock(&policy()),
+        );
+
+        assert_eq!(response["result"]["isError"], false);
+        let tasks = response["result"]["structuredContent"]["tasks"]
+            .as_array()
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0]["task"]["id"], late_id.to_string());
+        assert_eq!(tasks[0]["deadline_task_id"], late_id.to_string());
+        assert_eq!(tasks[0]["slack_seconds"], -60 * 60);
+        assert_eq!(tasks[0]["recoverable_by_deferring"], false);
+        assert_tool_result_content_matches_structured(&response);
+        assert_eq!(save_count.get(), 0);
+    }
+
+    #[test]
+    fn get_critical_path_handlerは根のprojectの臨界経路を返し存在しないtaskを区別する() {
+        let project = new_task_handle("project").unwrap();
+        project.sync_clock(fixed_now()).unwrap();
+        project.set_start_time(fixed_now()).unwrap();
+        project.set_estimated_work_seconds(0).unwrap();
+        let long = project
+            .create_child(new_task_attr_at("長い作業", fixed_now()))
+            .unwrap();
+        long.set_estimated_work_seconds(2 * 60 * 60).unwrap();
+        let project_id = project.get_id().unwrap();
+        let long_id = long.get_id().unwrap();
+        let repository = RecordingRepository::new(vec![project]);
+        let save_count = Rc::clone(&repository.save_count);
+
+        let response = call_get_critical_path(
+            &repository,
+            json!("typed-critical-path"),
+            GetCriticalPathInput {
+                task_id: UuidValue(long_id),
+            },
+            test_clock(&policy()),
+        );
+
+        assert_eq!(response["result"]["isError"], false);
+        let critical_path = &response["result"]["structuredContent"]["critical_path"];
+        assert_eq!(critical_path["root_id"], project_id.to_string());
+        assert_eq!(
+            critical_path["critical_chain"],
+            json!([long_id.to_string(), project_id.to_string()])
+        );
+        assert_eq!(critical_path["tasks"][1]["float_seconds"], 0);
+        assert_eq!(critical_path["tasks"][1]["critical"], true);
+        assert_tool_result_content_matches_structured(&response);
+        assert_eq!(save_count.get(), 0);
+
+        let missing_id = uuid::Uuid::from_u128(0x35);
+        let response = call_get_critical_path(
+            &repository,
+            json!("typed-critical-path-missing"),
+            GetCriticalPathInput {
+                task_id: UuidValue(missing_id),
+            },
+            test_clock(&policy()),
+        );
+        assert_eq!(response["result"]["isError"], true);
+        assert_eq!(
+            response["result"]["structuredContent"]["error"]["code"],
+            "task_not_found"
+        );
+    }
+
+    #[test]
+    fn export_tasks_handlerは部分木をmarkdownとopmlの文字列で返しrepositoryを変更しない() {
+        let project = new_task_handle("旅行").unwrap();
+        project.sync_clock(fixed_now()).unwrap();
+        project.set_estimated_work_seconds(0).unwrap();
+        project
+            .create_child(new_task_attr_at("切符 <往復>", fixed_now()))
+            .unwrap()
+            .set_estimated_work_seconds(90 * 60)
+            .unwrap();
+        let project_id = project.get_id().unwrap();
+        let repository = RecordingRepository::new(vec![project]);
+        let save_count = Rc::clone(&repository.save_count);
+        let export = |format| ExportTasksInput {
+            task_id: UuidValue(project_id),
+            format: ExportFormatValue(format),
+        };
+
+        let response = call_export_tasks(
+            &repository,
+            json!("typed-export-markdown"),
+            export(ExportFormat::Markdown),
+        );
+        assert_eq!(response["result"]["isError"], false);
+        assert_eq!(
+            response["result"]["structuredContent"],
+            json!({
+                "format": "markdown",
+                "text": "- [ ] 旅行\n  - [ ] 切符 <往復> (1h30m)\n"
+            })
+        );
+        assert_tool_result_content_matches_structured(&response);
+
+        let response = call_export_tasks(
+            &repository,
+            json!("typed-export-opml"),
+            export(ExportFormat::Opml),
+        );
+        let text = response["result"]["structuredContent"]["text"]
+            .as_str()
+            .unwrap();
+        assert!(text.contains(
+            "<outline text=\"切符 &lt;往復&gt;\" status=\"todo\" estimatedMinutes=\"90\""
+        ));
+        assert_eq!(save_count.get(), 0);
+
+        let response = call_export_tasks(
+            &repository,
+            json!("typed-export-missing"),
+            ExportTasksInput {
+                task_id: UuidValue(uuid::Uuid::from_u128(0x45)),
+                format: ExportFormatValue(ExportFormat::Markdown),
+            },
+        );
+        assert_eq!(
+            response["result"]["structuredContent"]["error"]["code"],
+            "task_not_found"
+        );
+    }
+
+    #[test]
+    fn get_task_tree_handlerは入れ子の部分木と集計を返しrepositoryを変更しない() {
+        let project = new_task_handle("project").unwrap();
+        project.sync_clock(fixed_now()).unwrap();
+        project.set_estimated_work_seconds(0).unwrap();
+        let draft = project
+            .create_child(new_task_attr_at("下書き", fixed_now()))
+            .unwrap();
+        draft.set_estimated_work_seconds(30 * 60).unwrap();
+        draft
+            .create_child(new_task_attr_at("構成", fixed_now()))
+            .unwrap()
+            .set_estimated_work_seconds(20 * 60)
+            .unwrap();
+        let project_id = project.get_id().unwrap();
+        let repository = RecordingRepository::new(vec![project]);
+        let save_count = Rc::clone(&repository.save_count);
+
+        let response = call_get_task_tree(
+            &repository,
+            json!("typed-task-tree"),
+            GetTaskTreeInput {
+                task_id: UuidValue(project_id),
+                max_depth: OptionalValue::Value(NonNegativeI64(1)),
+            },
+            test_clock(&policy()),
+        );
+
+        assert_eq!(response["result"]["isError"], false);
+        let tree = &response["result"]["structuredContent"]["tree"];
+        assert_eq!(tree["task"]["id"], project_id.to_string());
+        assert_eq!(tree["rollup"]["remaining_estimated_work_seconds"], 50 * 60);
+        assert_eq!(tree["rollup"]["leaf_count"], 1);
+        assert_eq!(tree["children"][0]["task"]["name"], "下書き");
+        assert_eq!(tree["children"][0]["children_truncated"], true);
+        assert_eq!(tree["children"][0]["children"], json!([]));
+        assert_tool_result_content_matches_structured(&response);
+        assert_eq!(save_count.get(), 0);
+
+        let response = call_get_task_tree(
+            &repository,
+            json!("typed-task-tree-missing"),
+            GetTaskTreeInput {
+                task_id: UuidValue(uuid::Uuid::from_u128(0x38)),
+                max_depth: OptionalValue::Missing,
+            },
+            test_clock(&policy()),
+        );
+        assert_eq!(
+            response["result"]["structuredContent"]["error"]["code"],
+            "task_not_found"
+        );
+    }
+
+    #[test]
+    fn move_copy_merge_handlerは部分木を組み替え見つからないidのfieldを返す() {
+        let project = new_task_handle("project").unwrap();
+        project.sync_clock(fixed_now()).unwrap();
+        let first = project
+            .create_child(new_task_attr_at("first", fixed_now()))
+            .unwrap();
+        let second = project
+            .create_child(new_task_attr_at("second", fixed_now()))
+            .unwrap();
+        second.set_estimated_work_seconds(10 * 60).unwrap();
+        let (project_id, first_id, second_id) = (
+            project.get_id().unwrap(),
+            first.get_id().unwrap(),
+            second.get_id().unwrap(),
+        );
+        let mut repository = RecordingRepository::new(vec![project.clone()]);
+
+        let copied = call_copy_task(
+            &mut repository,
+            json!("typed-copy"),
+            SubtreeDestinationInput {
+                task_id: UuidValue(second_id),
+                parent_id: OptionalValue::Missing,
+            },
+        );
+        assert_eq!(copied["result"]["isError"], false);
+        let copy_id = copied["result"]["structuredContent"]["task_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert_ne!(copy_id, second_id.to_string());
+
+        let moved = call_move_task(
+            &mut repository,
+            json!("typed-move"),
+            SubtreeDestinationInput {
+                task_id: UuidValue(second_id),
+                parent_id: OptionalValue::Value(UuidValue(first_id)),
+            },
+        );
+        assert_eq!(moved["result"]["isError"], false);
+        assert_tool_result_content_matches_structured(&moved);
+        assert_eq!(
+            second.parent().unwrap().unwrap().get_id().unwrap(),
+            first_id
+        );
+
+        let cycle = call_move_task(
+            &mut repository,
+            json!("typed-move-cycle"),
+            SubtreeDestinationInput {
+                task_id: UuidValue(project_id),
+                parent_id: OptionalValue::Value(UuidValue(second_id)),
+            },
+        );
+        assert_eq!(
+            cycle["result"]["structuredContent"]["error"]["field"],
+            "parent_id"
+        );
+
+        let merged = call_merge_tasks(
+            &mut repository,
+            json!("typed-merge-missing"),
+            MergeTasksInput {
+                task_id: UuidValue(first_id),
+                source_task_id: UuidValue(Uuid::from_u128(0x39)),
+            },
+        );
+        assert_eq!(
+            merged["result"]["structuredContent"]["error"],
+            json!({
+                "code": "task_not_found",
+                "message": "task not found: 00000000-0000-0000-0000-000000000039",
+                "task_id": "00000000-0000-0000-0000-000000000039",
+                "field": "source_task_id"
+            })
+        );
+        assert_eq!(project.get_children().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn import_tasks_handlerはdry_runでは木だけを返し指定した親の下に取り込む() {
+        let parent = new_task_handle("parent").unwrap();
+        let parent_id = parent.get_id().unwrap();
+        let mut repository = RecordingRepository::new(vec![parent.clone()]);
+        let input = |text: &str, dry_run| ImportTasksInput {
+            text: NonEmptyString(text.to_string()),
+            format: ImportFormatValue(ImportFormat::Markdown),
+            parent_id: OptionalValue::Value(UuidValue(parent_id)),
+            dry_run: OptionalValue::Value(dry_run),
+        };
+        let deadline = Local.with_ymd_and_hms(2026, 8, 14, 19, 0, 0).unwrap();
+        let text =
+            "- [ ] 下書き (45m) @2026-08-14\n  - [ ] 構成\n- [x] 済み <!-- actual: 20m -->\n";
+
+        let preview = call_import_tasks(
+            &mut repository,
+            json!("typed-import-dry"),
+            input(text, true),
+        );
+        assert_tool_result_content_matches_structured(&preview);
+        assert_eq!(
+            preview["result"]["structuredContent"],
+            json!({
+                "dry_run": true,
+                "tasks": [{
+                    "name": "下書き",
+                    "status": "todo",
+                    "estimated_work_minutes": 45,
+                    "actual_work_minutes": null,
+                    "deadline_time": deadline.to_rfc3339(),
+                    "pending_until": null,
+                    "children": [{
+                        "name": "構成",
+                        "status": "todo",
+                        "estimated_work_minutes": null,
+                        "actual_work_minutes": null,
+                        "deadline_time": null,
+                        "pending_until": null,
+                        "children": []
+                    }]
+                }, {
+                    "name": "済み",
+                    "status": "done",
+                    "estimated_work_minutes": null,
+                    "actual_work_minutes": 20,
+                    "deadline_time": null,
+                    "pending_until": null,
+                    "children": []
+                }],
+                "task_count": 3,
+                "skipped_done_count": 0,
+                "task_ids": []
+            })
+        );
+        assert!(parent.get_children().unwrap().is_empty());
+
+        let imported =
+            call_import_tasks(&mut repository, json!("typed-import"), input(text, false));
+        assert_eq!(imported["result"]["isError"], false);
+        let children = parent.get_children().unwrap();
+        assert_eq!(
+            imported["result"]["structuredContent"]["task_ids"],
+            json!([
+                children[0].get_id().unwrap().to_string(),
+                children[1].get_id().unwrap().to_string()
+            ])
+        );
+        assert_eq!(children[1].get_orig_status().unwrap(), Status::Done);
+        assert_eq!(children[1].get_actual_work_seconds().unwrap(), 20 * 60);
+        assert_eq!(
+            children[0].get_children().unwrap()[0]
+                .get_deadline_time_opt()
+                .unwrap(),
+            Some(deadline)
+        );
+
+        let invalid = call_import_tasks(
+            &mut repository,
+            json!("typed-import-invalid"),
+            input("- [ ] (30m)", false),
+        );
+        assert_eq!(
+            invalid["result"]["structuredContent"]["error"],
+            json!({
+                "code": "invalid_input",
+                "message": "line 1: task name must not be empty",
+                "field": "text"
+            })
+        );
+    }
+
     #[test]
     fn list_tasks_handlerはtyped_filterをapplication入力へ変換しrepositoryを変更しない() {
         let matching = task_for_list(
@@ -806,6 +2031,7 @@ mod typed_handler_contract_tests {
                 from: OptionalValue::Value(IsoDate(from.date_naive())),
                 until: OptionalValue::Value(IsoDate(until.date_naive())),
             },
+            test_clock(&policy()),
         );
 
         assert_eq!(response["result"]["isError"], false);
@@ -844,6 +2070,7 @@ mod typed_handler_contract_tests {
                 from: OptionalValue::Missing,
                 until: OptionalValue::Missing,
             },
+            test_clock(&policy()),
         );
 
         assert_eq!(response["result"]["isError"], false);
@@ -859,6 +2086,34 @@ mod typed_handler_contract_tests {
         assert_eq!(mutation_count.get(), 0);
     }
 
+    #[test]
+    fn get_schedule_handlerはどの空きにも収まらないatomic_taskを分けずにunscheduledで返す() {
+        let long = new_task_handle("long atomic").unwrap();
+        long.set_start_time(fixed_now()).unwrap();
+        long.set_estimated_work_seconds(24 * 60 * 60).unwrap();
+        long.set_atomic(true).unwrap();
+        let repository = RecordingRepository::new(vec![long]);
+
+        let response = call_get_schedule(
+            &repository,
+            json!("typed-schedule-unscheduled"),
+            GetScheduleInput {
+                from: OptionalValue::Missing,
+                until: OptionalValue::Missing,
+            },
+            test_clock(&policy()),
+        );
+
+        assert_eq!(response["result"]["isError"], false);
+        let content = &response["result"]["structuredContent"];
+        assert_eq!(content["schedule"], json!([]));
+        let unscheduled = content["unscheduled"].as_array().unwrap();
+        assert_eq!(unscheduled.len(), 1);
+        assert_eq!(unscheduled[0]["task"]["name"], "long atomic");
+        assert_eq!(unscheduled[0]["total_work_seconds"], 24 * 60 * 60);
+        assert_eq!(unscheduled[0]["reason"], "longer_than_free_time");
+    }
+
     #[test]
     fn create_task_handlerはtyped_inputをapplication入力へ変換する() {
         let pending_until = fixed_now() + Duration::hours(18);
@@ -873,7 +2128,7 @@ mod typed_handler_contract_tests {
             CreateTaskInput {
                 name: NonEmptyString("created by typed input".to_string()),
                 estimated_work_minutes: OptionalValue::Value(NonNegativeI64(30)),
-                pending_until: OptionalValue::Value(Rfc3339DateTime(pending_until)),
+                pending_until: OptionalValue::Value(DateTimeValue::Absolute(pending_until)),
             },
         );
 
@@ -919,7 +2174,7 @@ mod typed_handler_contract_tests {
                     NonEmptyString("first typed child".to_string()),
                     NonEmptyString("second typed child".to_string()),
                 ]),
-                pending_until: OptionalValue::Value(Rfc3339DateTime(pending_until)),
+                pending_until: OptionalValue::Value(DateTimeValue::Absolute(pending_until)),
             },
         );
 
@@ -1081,7 +2336,7 @@ mod typed_handler_contract_tests {
             json!("typed-complete"),
             CompleteTaskInput {
                 task_id: UuidValue(task_id),
-                finished_at: OptionalValue::Value(Rfc3339DateTime(finished_at)),
+                finished_at: OptionalValue::Value(DateTimeValue::Absolute(finished_at)),
                 additional_actual_work_seconds: NonNegativeI64(120),
             },
         );
@@ -1144,8 +2399,11 @@ mod typed_handler_contract_tests {
             UpdateTaskInput {
                 task_id: UuidValue(task_id),
                 estimated_work_minutes: OptionalValue::Value(NonNegativeI64(45)),
-                deadline_time: NullablePatch::Value(Rfc3339DateTime(deadline)),
+                deadline_time: NullablePatch::Value(DateTimeValue::Absolute(deadline)),
                 category: NullablePatch::Value(ProjectCategoryValue::Recovery),
+                allowed_window: NullablePatch::Value(AllowedWindowValue(
+                    AllowedWindow::parse("平日 09:00-18:00").unwrap(),
+                )),
             },
         );
 
@@ -1165,6 +2423,13 @@ mod typed_handler_contract_tests {
             task_observer.get_project_category_opt().unwrap(),
             Some(ProjectCategory::Recovery)
         );
+        assert_eq!(
+            task_observer
+                .get_allowed_window_opt()
+                .unwrap()
+                .map(|allowed_window| allowed_window.to_string()),
+            Some("月火水木金 09:00-18:00".to_string())
+        );
         assert_eq!(save_count.get(), 0);
     }
 
@@ -1190,6 +2455,7 @@ mod typed_handler_contract_tests {
                 estimated_work_minutes: OptionalValue::Value(NonNegativeI64(45)),
                 deadline_time: NullablePatch::Missing,
                 category: NullablePatch::Missing,
+                allowed_window: NullablePatch::Missing,
             },
         );
 
@@ -1229,6 +2495,7 @@ mod typed_handler_contract_tests {
                 estimated_work_minutes: OptionalValue::Missing,
                 deadline_time: NullablePatch::Null,
                 category: NullablePatch::Null,
+                allowed_window: NullablePatch::Null,
             },
         );
 
@@ -1261,8 +2528,9 @@ mod typed_handler_contract_tests {
             UpdateTaskInput {
                 task_id: UuidValue(task_id),
                 estimated_work_minutes: OptionalValue::Value(NonNegativeI64(i64::MAX)),
-                deadline_time: NullablePatch::Value(Rfc3339DateTime(requested_deadline)),
+                deadline_time: NullablePatch::Value(DateTimeValue::Absolute(requested_deadline)),
                 category: NullablePatch::Value(ProjectCategoryValue::Investment),
+                allowed_window: NullablePatch::Missing,
             },
         );
 
@@ -1,13 +1,21 @@
 use super::error::InvalidParams;
-use crate::application::daily_capacity::{try_next_business_day_start, try_subjective_date_start};
+use crate::adapter::gateway::task_export::ExportFormat;
+use crate::adapter::gateway::task_import::ImportFormat;
+use crate::application::daily_capacity::{
+    try_local_date_and_time, try_next_business_day_start, try_subjective_date_start,
+};
 use crate::application::task_use_case::{
     ApplicationError, BreakdownTaskInput as ApplicationBreakdownTaskInput,
     CompleteTaskInput as ApplicationCompleteTaskInput,
     CreateTaskInput as ApplicationCreateTaskInput, ListTasksFilter, TaskPeriodField,
     TaskPeriodFilter,
 };
+use crate::entity::allowed_window::AllowedWindow;
+use crate::entity::busy_block::BusyBlockSpan;
+use crate::entity::datetime::temporal::{ClockTime, TemporalExpression, TemporalResolveError};
+use crate::entity::datetime::BusinessDateTimePolicy;
 use crate::entity::task::{ProjectCategory, Status};
-use chrono::{DateTime, Local, NaiveDate};
+use chrono::{DateTime, Days, Local, NaiveDate};
 use schemars::{generate::SchemaSettings, json_schema, JsonSchema, Schema, SchemaGenerator};
 use serde::{de::DeserializeOwned, de::IntoDeserializer, Deserialize, Deserializer};
 use serde_json::{Map, Value};
@@ -84,6 +92,106 @@ impl JsonSchema for Rfc3339DateTime {
     }
 }
 
+// 任意の日時fieldはRFC 3339に加えて、CLIと同じ日時表現(明 9:00、+3d、金、末など)を受け付ける
+pub(super) enum DateTimeValue {
+    Absolute(DateTime<Local>),
+    Expression(TemporalExpression),
+}
+
+impl DateTimeValue {
+    pub(super) fn resolve(
+        self,
+        field: &'static str,
+        now: DateTime<Local>,
+        policy: &BusinessDateTimePolicy,
+        default_time: ClockTime,
+    ) -> Result<DateTime<Local>, ApplicationError> {
+        let expression = match self {
+            Self::Absolute(datetime) => return Ok(datetime),
+            Self::Expression(expression) => expression,
+        };
+        let naive_datetime = match expression.resolve(now, policy, default_time) {
+            Ok(naive_datetime) => naive_datetime,
+            Err(TemporalResolveError::InvalidMonthDay { .. }) => {
+                return Err(ApplicationError::InvalidInput {
+                    field,
+                    reason: "must resolve to an existing date",
+                })
+            }
+            Err(TemporalResolveError::OutOfRange { operation }) => {
+                return Err(ApplicationError::SubjectiveDateOutOfRange {
+                    operation,
+                    datetime: now,
+                })
+            }
+        };
+        try_local_date_and_time(naive_datetime.date(), naive_datetime.time())
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTimeValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        if let Ok(datetime) = parse_local_datetime(&value) {
+            return Ok(Self::Absolute(datetime));
+        }
+        TemporalExpression::parse_str(&value)
+            .map(Self::Expression)
+            .map_err(|_| {
+                serde::de::Error::custom(format!(
+                    "{SEMANTIC_ERROR_PREFIX}must be a valid RFC 3339 date-time or date-time expression"
+                ))
+            })
+    }
+}
+
+impl JsonSchema for DateTimeValue {
+    fn schema_name() -> Cow<'static, str> {
+        "DateTimeValue".into()
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({"type": "string"})
+    }
+}
+
+pub(super) struct AllowedWindowValue(pub(super) AllowedWindow);
+
+impl<'de> Deserialize<'de> for AllowedWindowValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        AllowedWindow::parse(&value).map(Self).map_err(|_| {
+            serde::de::Error::custom(format!(
+                "{SEMANTIC_ERROR_PREFIX}must be weekdays followed by HH:MM-HH:MM time ranges"
+            ))
+        })
+    }
+}
+
+impl JsonSchema for AllowedWindowValue {
+    fn schema_name() -> Cow<'static, str> {
+        "AllowedWindowValue".into()
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({"type": "string"})
+    }
+}
+
 pub(super) struct IsoDate(pub(super) NaiveDate);
 
 impl<'de> Deserialize<'de> for IsoDate {
@@ -309,6 +417,14 @@ impl NullableValue for Rfc3339DateTime {
     const WRONG_TYPE_REASON: &'static str = "must be a string or null";
 }
 
+impl NullableValue for DateTimeValue {
+    const WRONG_TYPE_REASON: &'static str = "must be a string or null";
+}
+
+impl NullableValue for AllowedWindowValue {
+    const WRONG_TYPE_REASON: &'static str = "must be a string or null";
+}
+
 impl NullableValue for ProjectCategoryValue {
     const WRONG_TYPE_REASON: &'static str = "must be a supported category or null";
 }
@@ -372,6 +488,255 @@ pub(super) struct GetTaskInput {
     pub(super) task_id: UuidValue,
 }
 
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct DeleteTaskInput {
+    pub(super) task_id: UuidValue,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ListTrashInput {}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ListWaitingTasksInput {}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ListAtRiskTasksInput {
+    #[serde(default)]
+    pub(super) max_slack_minutes: OptionalValue<NonNegativeI64>,
+}
+
+impl ListAtRiskTasksInput {
+    // 秒に直せないほど大きい上限は、上限なしと同じ結果になる
+    pub(super) fn max_slack_seconds(&self) -> Option<i64> {
+        match &self.max_slack_minutes {
+            OptionalValue::Missing => None,
+            OptionalValue::Value(minutes) => Some(minutes.0.saturating_mul(60)),
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct GetCriticalPathInput {
+    pub(super) task_id: UuidValue,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct GetTaskTreeInput {
+    pub(super) task_id: UuidValue,
+    #[serde(default)]
+    pub(super) max_depth: OptionalValue<NonNegativeI64>,
+}
+
+impl GetTaskTreeInput {
+    pub(super) fn max_depth(&self) -> Option<usize> {
+        match &self.max_depth {
+            OptionalValue::Missing => None,
+            OptionalValue::Value(depth) => Some(usize::try_from(depth.0).unwrap_or(usize::MAX)),
+        }
+    }
+}
+
+// parent_idを省くと、部分木を新しいprojectにする
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct SubtreeDestinationInput {
+    pub(super) task_id: UuidValue,
+    #[serde(default)]
+    pub(super) parent_id: OptionalValue<UuidValue>,
+}
+
+impl SubtreeDestinationInput {
+    pub(super) fn parent_id(&self) -> Option<Uuid> {
+        match &self.parent_id {
+            OptionalValue::Missing => None,
+            OptionalValue::Value(parent_id) => Some(parent_id.0),
+        }
+    }
+}
+
+// textを木に解析し、parent_idの下か新しいprojectとして作る。dry_runなら作らずに木だけを返す
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ImportTasksInput {
+    pub(super) text: NonEmptyString,
+    pub(super) format: ImportFormatValue,
+    #[serde(default)]
+    pub(super) parent_id: OptionalValue<UuidValue>,
+    #[serde(default)]
+    pub(super) dry_run: OptionalValue<bool>,
+}
+
+impl ImportTasksInput {
+    pub(super) fn parent_id(&self) -> Option<Uuid> {
+        match &self.parent_id {
+            OptionalValue::Missing => None,
+            OptionalValue::Value(parent_id) => Some(parent_id.0),
+        }
+    }
+
+    pub(super) fn dry_run(&self) -> bool {
+        matches!(self.dry_run, OptionalValue::Value(true))
+    }
+}
+
+pub(super) struct ImportFormatValue(pub(super) ImportFormat);
+
+impl<'de> Deserialize<'de> for ImportFormatValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        ImportFormat::parse(&value).map(Self).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "{SCHEMA_ERROR_PREFIX}must be markdown, org, or todo_txt"
+            ))
+        })
+    }
+}
+
+impl JsonSchema for ImportFormatValue {
+    fn schema_name() -> Cow<'static, str> {
+        "ImportFormatValue".into()
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "enum": ["markdown", "org", "todo_txt"]
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ExportTasksInput {
+    pub(super) task_id: UuidValue,
+    pub(super) format: ExportFormatValue,
+}
+
+pub(super) struct ExportFormatValue(pub(super) ExportFormat);
+
+impl<'de> Deserialize<'de> for ExportFormatValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        ExportFormat::parse(&value).map(Self).ok_or_else(|| {
+            serde::de::Error::custom(format!("{SCHEMA_ERROR_PREFIX}must be markdown or opml"))
+        })
+    }
+}
+
+impl JsonSchema for ExportFormatValue {
+    fn schema_name() -> Cow<'static, str> {
+        "ExportFormatValue".into()
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "enum": ["markdown", "opml"]
+        })
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct MergeTasksInput {
+    pub(super) task_id: UuidValue,
+    pub(super) source_task_id: UuidValue,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct TrashEntryInput {
+    pub(super) trash_id: UuidValue,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ListBusyBlocksInput {}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct RemoveBusyBlockInput {
+    pub(super) busy_block_id: UuidValue,
+}
+
+// startとendなら時刻を決めた予定、start_dateなら終日の予定。end_dateを省くと1日だけ塞ぐ
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct AddBusyBlockInput {
+    pub(super) name: NonEmptyString,
+    #[serde(default)]
+    pub(super) start: OptionalValue<DateTimeValue>,
+    #[serde(default)]
+    pub(super) end: OptionalValue<DateTimeValue>,
+    #[serde(default)]
+    pub(super) start_date: OptionalValue<IsoDate>,
+    #[serde(default)]
+    pub(super) end_date: OptionalValue<IsoDate>,
+}
+
+impl AddBusyBlockInput {
+    pub(super) fn into_span(
+        self,
+        now: DateTime<Local>,
+        policy: &BusinessDateTimePolicy,
+    ) -> Result<BusyBlockSpan, ApplicationError> {
+        let invalid = |field, reason| Err(ApplicationError::InvalidInput { field, reason });
+        match (self.start, self.end, self.start_date, self.end_date) {
+            (
+                OptionalValue::Value(start),
+                OptionalValue::Value(end),
+                OptionalValue::Missing,
+                OptionalValue::Missing,
+            ) => {
+                let time = ClockTime::business_day_start();
+                Ok(BusyBlockSpan::Timed {
+                    start: start.resolve("start", now, policy, time)?,
+                    end: end.resolve("end", now, policy, time)?,
+                })
+            }
+            (OptionalValue::Value(_), OptionalValue::Missing, _, _) => {
+                invalid("end", "is required with start")
+            }
+            (
+                OptionalValue::Missing,
+                OptionalValue::Missing,
+                OptionalValue::Value(start_date),
+                end_date,
+            ) => {
+                let start_date = start_date.0;
+                Ok(BusyBlockSpan::AllDay {
+                    start_date,
+                    end_date: match end_date {
+                        OptionalValue::Missing => start_date,
+                        OptionalValue::Value(end_date) => end_date.0,
+                    },
+                })
+            }
+            _ => invalid("start", "must specify either start and end or start_date"),
+        }
+    }
+}
+
 #[derive(Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub(super) struct CreateTaskInput {
@@ -379,24 +744,43 @@ pub(super) struct CreateTaskInput {
     #[serde(default)]
     pub(super) estimated_work_minutes: OptionalValue<NonNegativeI64>,
     #[serde(default)]
-    pub(super) pending_until: OptionalValue<Rfc3339DateTime>,
+    pub(super) pending_until: OptionalValue<DateTimeValue>,
 }
 
 impl CreateTaskInput {
-    pub(super) fn into_application(self) -> ApplicationCreateTaskInput {
+    pub(super) fn into_application(
+        self,
+        now: DateTime<Local>,
+        policy: &BusinessDateTimePolicy,
+    ) -> Result<ApplicationCreateTaskInput, ApplicationError> {
         let estimated_work_minutes = match self.estimated_work_minutes {
             OptionalValue::Missing => None,
             OptionalValue::Value(minutes) => Some(minutes.0),
         };
 
-        ApplicationCreateTaskInput {
+        Ok(ApplicationCreateTaskInput {
             name: self.name.0,
             estimated_work_minutes,
-            pending_until: match self.pending_until {
-                OptionalValue::Missing => None,
-                OptionalValue::Value(pending_until) => Some(pending_until.0),
-            },
-        }
+            pending_until: resolve_optional_pending_until(self.pending_until, now, policy)?,
+        })
+    }
+}
+
+fn resolve_optional_pending_until(
+    pending_until: OptionalValue<DateTimeValue>,
+    now: DateTime<Local>,
+    policy: &BusinessDateTimePolicy,
+) -> Result<Option<DateTime<Local>>, ApplicationError> {
+    match pending_until {
+        OptionalValue::Missing => Ok(None),
+        OptionalValue::Value(pending_until) => pending_until
+            .resolve(
+                "pending_until",
+                now,
+                policy,
+                ClockTime::business_day_start(),
+            )
+            .map(Some),
     }
 }
 
@@ -406,19 +790,20 @@ pub(super) struct BreakdownTaskInput {
     pub(super) parent_id: UuidValue,
     pub(super) names: NonEmptyVec<NonEmptyString>,
     #[serde(default)]
-    pub(super) pending_until: OptionalValue<Rfc3339DateTime>,
+    pub(super) pending_until: OptionalValue<DateTimeValue>,
 }
 
 impl BreakdownTaskInput {
-    pub(super) fn into_application(self) -> ApplicationBreakdownTaskInput {
-        ApplicationBreakdownTaskInput {
+    pub(super) fn into_application(
+        self,
+        now: DateTime<Local>,
+        policy: &BusinessDateTimePolicy,
+    ) -> Result<ApplicationBreakdownTaskInput, ApplicationError> {
+        Ok(ApplicationBreakdownTaskInput {
             parent_id: self.parent_id.0,
             names: self.names.0.into_iter().map(|name| name.0).collect(),
-            pending_until: match self.pending_until {
-                OptionalValue::Missing => None,
-                OptionalValue::Value(pending_until) => Some(pending_until.0),
-            },
-        }
+            pending_until: resolve_optional_pending_until(self.pending_until, now, policy)?,
+        })
     }
 }
 
@@ -440,7 +825,7 @@ impl DeferTaskInput {
 pub(super) struct CompleteTaskInput {
     pub(super) task_id: UuidValue,
     #[serde(default)]
-    pub(super) finished_at: OptionalValue<Rfc3339DateTime>,
+    pub(super) finished_at: OptionalValue<DateTimeValue>,
     #[serde(default = "zero_non_negative")]
     #[schemars(schema_with = "additional_work_seconds_schema")]
     pub(super) additional_actual_work_seconds: NonNegativeI64,
@@ -450,15 +835,21 @@ impl CompleteTaskInput {
     pub(super) fn into_application(
         self,
         operation_now: DateTime<Local>,
-    ) -> ApplicationCompleteTaskInput {
-        ApplicationCompleteTaskInput {
+        policy: &BusinessDateTimePolicy,
+    ) -> Result<ApplicationCompleteTaskInput, ApplicationError> {
+        Ok(ApplicationCompleteTaskInput {
             task_id: self.task_id.0,
             finished_at: match self.finished_at {
                 OptionalValue::Missing => operation_now,
-                OptionalValue::Value(finished_at) => finished_at.0,
+                OptionalValue::Value(finished_at) => finished_at.resolve(
+                    "finished_at",
+                    operation_now,
+                    policy,
+                    ClockTime::business_day_start(),
+                )?,
             },
             additional_actual_work_seconds: self.additional_actual_work_seconds.0,
-        }
+        })
     }
 }
 
@@ -475,6 +866,7 @@ enum UpdateTaskField {
     EstimatedWorkMinutes,
     DeadlineTime,
     Category,
+    AllowedWindow,
 }
 
 impl UpdateTaskField {
@@ -483,6 +875,7 @@ impl UpdateTaskField {
             Self::EstimatedWorkMinutes => "estimated_work_minutes",
             Self::DeadlineTime => "deadline_time",
             Self::Category => "category",
+            Self::AllowedWindow => "allowed_window",
         }
     }
 
@@ -493,14 +886,16 @@ impl UpdateTaskField {
             }
             Self::DeadlineTime => !matches!(fields.deadline_time, NullablePatch::Missing),
             Self::Category => !matches!(fields.category, NullablePatch::Missing),
+            Self::AllowedWindow => !matches!(fields.allowed_window, NullablePatch::Missing),
         }
     }
 }
 
-const UPDATE_TASK_FIELDS: [UpdateTaskField; 3] = [
+const UPDATE_TASK_FIELDS: [UpdateTaskField; 4] = [
     UpdateTaskField::EstimatedWorkMinutes,
     UpdateTaskField::DeadlineTime,
     UpdateTaskField::Category,
+    UpdateTaskField::AllowedWindow,
 ];
 const UPDATE_TASK_FIELD_REQUIRED_REASON: &str = "must include at least one field to update";
 
@@ -509,8 +904,9 @@ const UPDATE_TASK_FIELD_REQUIRED_REASON: &str = "must include at least one field
 pub(super) struct UpdateTaskInput {
     pub(super) task_id: UuidValue,
     pub(super) estimated_work_minutes: OptionalValue<NonNegativeI64>,
-    pub(super) deadline_time: NullablePatch<Rfc3339DateTime>,
+    pub(super) deadline_time: NullablePatch<DateTimeValue>,
     pub(super) category: NullablePatch<ProjectCategoryValue>,
+    pub(super) allowed_window: NullablePatch<AllowedWindowValue>,
 }
 
 pub(super) struct UpdateTaskChanges {
@@ -518,10 +914,17 @@ pub(super) struct UpdateTaskChanges {
     pub(super) estimated_work_minutes: Option<i64>,
     pub(super) deadline_time: Option<Option<DateTime<Local>>>,
     pub(super) category: Option<Option<ProjectCategory>>,
+    pub(super) allowed_window: Option<Option<AllowedWindow>>,
 }
 
 impl UpdateTaskInput {
-    pub(super) fn into_changes(self) -> UpdateTaskChanges {
+    // 日付だけの締切表現には、CLIの〆と同じく既定の締切時刻を使う
+    pub(super) fn into_changes(
+        self,
+        now: DateTime<Local>,
+        policy: &BusinessDateTimePolicy,
+        default_deadline_time: ClockTime,
+    ) -> Result<UpdateTaskChanges, ApplicationError> {
         let estimated_work_minutes = match self.estimated_work_minutes {
             OptionalValue::Missing => None,
             OptionalValue::Value(minutes) => Some(minutes.0),
@@ -529,20 +932,31 @@ impl UpdateTaskInput {
         let deadline_time = match self.deadline_time {
             NullablePatch::Missing => None,
             NullablePatch::Null => Some(None),
-            NullablePatch::Value(deadline_time) => Some(Some(deadline_time.0)),
+            NullablePatch::Value(deadline_time) => Some(Some(deadline_time.resolve(
+                "deadline_time",
+                now,
+                policy,
+                default_deadline_time,
+            )?)),
         };
         let category = match self.category {
             NullablePatch::Missing => None,
             NullablePatch::Null => Some(None),
             NullablePatch::Value(category) => Some(Some(category.into_category())),
         };
+        let allowed_window = match self.allowed_window {
+            NullablePatch::Missing => None,
+            NullablePatch::Null => Some(None),
+            NullablePatch::Value(allowed_window) => Some(Some(allowed_window.0)),
+        };
 
-        UpdateTaskChanges {
+        Ok(UpdateTaskChanges {
             task_id: self.task_id.0,
             estimated_work_minutes,
             deadline_time,
             category,
-        }
+            allowed_window,
+        })
     }
 }
 
@@ -554,9 +968,11 @@ struct UpdateTaskInputFields {
     #[serde(default)]
     estimated_work_minutes: OptionalValue<NonNegativeI64>,
     #[serde(default)]
-    deadline_time: NullablePatch<Rfc3339DateTime>,
+    deadline_time: NullablePatch<DateTimeValue>,
     #[serde(default)]
     category: NullablePatch<ProjectCategoryValue>,
+    #[serde(default)]
+    allowed_window: NullablePatch<AllowedWindowValue>,
 }
 
 impl TryFrom<UpdateTaskInputFields> for UpdateTaskInput {
@@ -577,6 +993,7 @@ impl TryFrom<UpdateTaskInputFields> for UpdateTaskInput {
             estimated_work_minutes: fields.estimated_work_minutes,
             deadline_time: fields.deadline_time,
             category: fields.category,
+            allowed_window: fields.allowed_window,
         })
     }
 }
@@ -863,6 +1280,71 @@ impl GetScheduleInput {
     }
 }
 
+// CLIの「履」と同じく、既定では今日を含む直近28日を返す
+const LOAD_HISTORY_DEFAULT_DAYS: u64 = 28;
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct GetLoadHistoryInput {
+    #[serde(default)]
+    pub(super) from: OptionalValue<IsoDate>,
+    #[serde(default)]
+    pub(super) until: OptionalValue<IsoDate>,
+}
+
+impl GetLoadHistoryInput {
+    pub(super) fn into_date_range(
+        self,
+        today: NaiveDate,
+    ) -> Result<(NaiveDate, NaiveDate), ToolInputError> {
+        decide_report_date_range(self.from, self.until, today)
+    }
+}
+
+// CLIの「流」と同じく、既定の範囲は「履」と揃える
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(super) struct GetFlowMetricsInput {
+    #[serde(default)]
+    pub(super) from: OptionalValue<IsoDate>,
+    #[serde(default)]
+    pub(super) until: OptionalValue<IsoDate>,
+}
+
+impl GetFlowMetricsInput {
+    pub(super) fn into_date_range(
+        self,
+        today: NaiveDate,
+    ) -> Result<(NaiveDate, NaiveDate), ToolInputError> {
+        decide_report_date_range(self.from, self.until, today)
+    }
+}
+
+fn decide_report_date_range(
+    from: OptionalValue<IsoDate>,
+    until: OptionalValue<IsoDate>,
+    today: NaiveDate,
+) -> Result<(NaiveDate, NaiveDate), ToolInputError> {
+    let until = match until {
+        OptionalValue::Missing => today,
+        OptionalValue::Value(date) => date.0,
+    };
+    let from = match from {
+        OptionalValue::Missing => until
+            .checked_sub_days(Days::new(LOAD_HISTORY_DEFAULT_DAYS - 1))
+            .unwrap_or(NaiveDate::MIN),
+        OptionalValue::Value(date) => date.0,
+    };
+    if from > until {
+        return Err(ToolInputError::Semantic {
+            field: "until".to_string(),
+            message: "must not be earlier than from",
+        });
+    }
+
+    Ok((from, until))
+}
+
 fn schedule_day_start(
     date: IsoDate,
     _field: &'static str,
@@ -1003,7 +1485,13 @@ fn semantic_reason(reason: &str) -> &'static str {
     match reason {
         "must be a valid UUID" => "must be a valid UUID",
         "must be a valid RFC 3339 date-time" => "must be a valid RFC 3339 date-time",
+        "must be a valid RFC 3339 date-time or date-time expression" => {
+            "must be a valid RFC 3339 date-time or date-time expression"
+        }
         "must be a valid ISO 8601 date" => "must be a valid ISO 8601 date",
+        "must be weekdays followed by HH:MM-HH:MM time ranges" => {
+            "must be weekdays followed by HH:MM-HH:MM time ranges"
+        }
         "is outside the supported integer range" => "is outside the supported integer range",
         _ => "contains an invalid value",
     }
@@ -1129,12 +1617,15 @@ fn common_input_contract() -> CommonInputContract {
 mod tests {
     use super::{
         common_input_contract, decode_input, generated_input_schema, BreakdownTaskInput,
-        CompleteTaskInput, CreateTaskInput, DeferTaskInput, GetFocusInput, GetScheduleInput,
-        GetTaskInput, ListTasksInput, NonNegativeI64, NullablePatch, OptionalValue,
-        ProjectCategoryValue, Rfc3339DateTime, ToolInputError, UpdateTaskInput,
+        CompleteTaskInput, CreateTaskInput, DateTimeValue, DeferTaskInput, GetFlowMetricsInput,
+        GetFocusInput, GetLoadHistoryInput, GetScheduleInput, GetTaskInput, IsoDate,
+        ListTasksInput, NonNegativeI64, NullablePatch, OptionalValue, ProjectCategoryValue,
+        Rfc3339DateTime, ToolInputError, UpdateTaskInput,
     };
     use crate::application::task_use_case::ApplicationError;
-    use chrono::{DateTime, FixedOffset, Local, NaiveDate};
+    use crate::entity::datetime::temporal::ClockTime;
+    use crate::entity::datetime::BusinessDateTimePolicy;
+    use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeZone, Weekday};
     use schemars::JsonSchema;
     use serde::Deserialize;
     use serde_json::{json, Value};
@@ -1288,6 +1779,16 @@ mod tests {
             public_tool_schema("get_schedule"),
             get_schedule_input_cases(),
         );
+        assert_reference_input_contract::<GetLoadHistoryInput>(
+            "get_load_history",
+            public_tool_schema("get_load_history"),
+            get_schedule_input_cases(),
+        );
+        assert_reference_input_contract::<GetFlowMetricsInput>(
+            "get_flow_metrics",
+            public_tool_schema("get_flow_metrics"),
+            get_schedule_input_cases(),
+        );
     }
 
     #[test]
@@ -1357,7 +1858,7 @@ mod tests {
         .unwrap_or_else(|_| panic!("complete optional values must decode"));
         assert!(matches!(
             complete.finished_at,
-            OptionalValue::Value(value) if value.0 == expected_finished_at
+            OptionalValue::Value(DateTimeValue::Absolute(value)) if value == expected_finished_at
         ));
         assert_eq!(complete.additional_actual_work_seconds.0, 15);
 
@@ -1395,7 +1896,7 @@ mod tests {
         ));
         assert!(matches!(
             update.deadline_time,
-            NullablePatch::Value(value) if value.0 == expected_deadline
+            NullablePatch::Value(DateTimeValue::Absolute(value)) if value == expected_deadline
         ));
         assert!(matches!(
             update.category,
@@ -1403,6 +1904,70 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn date_time_expressions_resolve_against_operation_now_and_policy() {
+        let task_id = "80d7db87-324e-4e8d-a5b7-ff78cd5bf39a";
+        let now = Local
+            .from_local_datetime(
+                &NaiveDate::from_ymd_opt(2026, 8, 19)
+                    .unwrap()
+                    .and_hms_opt(10, 0, 0)
+                    .unwrap(),
+            )
+            .unwrap();
+        let policy = BusinessDateTimePolicy::new(0)
+            .with_non_business_weekdays(&[Weekday::Sat, Weekday::Sun]);
+        let default_deadline_time = ClockTime::new(19, 0, 0).unwrap();
+        let expected = |day, hour| {
+            NaiveDate::from_ymd_opt(2026, 8, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+        };
+
+        let create = decode_input::<CreateTaskInput>(&json!({
+            "name": "write contract test",
+            "pending_until": "明 9:00"
+        }))
+        .unwrap_or_else(|_| panic!("pending_until expression must decode"));
+        let create = create
+            .into_application(now, &policy)
+            .unwrap_or_else(|error| panic!("pending_until expression must resolve: {error:?}"));
+        assert_eq!(
+            create.pending_until.map(|time| time.naive_local()),
+            Some(expected(20, 9))
+        );
+
+        let update = decode_input::<UpdateTaskInput>(&json!({
+            "task_id": task_id,
+            "deadline_time": "+3bd"
+        }))
+        .unwrap_or_else(|_| panic!("deadline expression must decode"));
+        let changes = update
+            .into_changes(now, &policy, default_deadline_time)
+            .unwrap_or_else(|error| panic!("deadline expression must resolve: {error:?}"));
+        assert_eq!(
+            changes
+                .deadline_time
+                .flatten()
+                .map(|time| time.naive_local()),
+            Some(expected(24, 19))
+        );
+
+        let update = decode_input::<UpdateTaskInput>(&json!({
+            "task_id": task_id,
+            "deadline_time": "2/30"
+        }))
+        .unwrap_or_else(|_| panic!("calendar-shaped expression must decode"));
+        assert!(matches!(
+            update.into_changes(now, &policy, default_deadline_time),
+            Err(ApplicationError::InvalidInput {
+                field: "deadline_time",
+                reason: "must resolve to an existing date",
+            })
+        ));
+    }
+
     fn defer_task_input_cases() -> Vec<ContractCase> {
         let task_id = "80d7db87-324e-4e8d-a5b7-ff78cd5bf39a";
         let pending_until = "2026-08-19T10:00:00+09:00";
@@ -1504,11 +2069,12 @@ mod tests {
                 "finished_at",
                 "must be a string",
             ),
-            semantic_case(
+            semantic_case_with_schema_acceptance(
                 "finished at is invalid",
                 json!({"task_id": task_id, "finished_at": "not-a-date"}),
+                true,
                 "finished_at",
-                "must be a valid RFC 3339 date-time",
+                "must be a valid RFC 3339 date-time or date-time expression",
             ),
             schema_case(
                 "additional work cannot be null",
@@ -1652,11 +2218,12 @@ mod tests {
                 "deadline_time",
                 "must be a string or null",
             ),
-            semantic_case(
+            semantic_case_with_schema_acceptance(
                 "deadline is invalid",
                 json!({"task_id": task_id, "deadline_time": "not-a-date"}),
+                true,
                 "deadline_time",
-                "must be a valid RFC 3339 date-time",
+                "must be a valid RFC 3339 date-time or date-time expression",
             ),
             valid_case(
                 "clear update category",
@@ -1890,10 +2457,10 @@ mod tests {
             ContractCase {
                 name: "pending until is invalid",
                 input: json!({"name": "write contract test", "pending_until": "not-a-date"}),
-                schema_accepts: false,
+                schema_accepts: true,
                 decode: ExpectedDecode::Semantic {
                     field: "pending_until",
-                    reason: "must be a valid RFC 3339 date-time",
+                    reason: "must be a valid RFC 3339 date-time or date-time expression",
                 },
             },
             ContractCase {
@@ -2040,10 +2607,10 @@ mod tests {
                     "names": ["first child"],
                     "pending_until": "not-a-date"
                 }),
-                schema_accepts: false,
+                schema_accepts: true,
                 decode: ExpectedDecode::Semantic {
                     field: "pending_until",
-                    reason: "must be a valid RFC 3339 date-time",
+                    reason: "must be a valid RFC 3339 date-time or date-time expression",
                 },
             },
             ContractCase {
@@ -2892,4 +3459,65 @@ mod tests {
             _ => panic!("expected an application datetime error"),
         }
     }
+
+    #[test]
+    fn load_history_rangeは省略時に今日までの28日を両端を含む範囲にする() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2026, month, day).unwrap();
+        let decide = |from, until| {
+            GetLoadHistoryInput { from, until }
+                .into_date_range(date(10, 18))
+                .map_err(|error| match error {
+                    ToolInputError::Semantic { field, .. } => field,
+                    _ => panic!("expected a semantic range error"),
+                })
+        };
+
+        assert_eq!(
+            decide(OptionalValue::Missing, OptionalValue::Missing),
+            Ok((date(9, 21), date(10, 18)))
+        );
+        assert_eq!(
+            decide(
+                OptionalValue::Missing,
+                OptionalValue::Value(IsoDate(date(10, 1)))
+            ),
+            Ok((date(9, 4), date(10, 1)))
+        );
+        assert_eq!(
+            decide(
+                OptionalValue::Value(IsoDate(date(10, 18))),
+                OptionalValue::Value(IsoDate(date(10, 18)))
+            ),
+            Ok((date(10, 18), date(10, 18)))
+        );
+        assert_eq!(
+            decide(
+                OptionalValue::Value(IsoDate(date(10, 19))),
+                OptionalValue::Missing
+            ),
+            Err("until".to_string())
+        );
+    }
+
+    #[test]
+    fn flow_metrics_rangeは負荷履歴と同じ既定範囲と範囲errorを使う() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2026, month, day).unwrap();
+
+        assert!(matches!(
+            GetFlowMetricsInput {
+                from: OptionalValue::Missing,
+                until: OptionalValue::Missing,
+            }
+            .into_date_range(date(10, 18)),
+            Ok(range) if range == (date(9, 21), date(10, 18))
+        ));
+        assert!(matches!(
+            GetFlowMetricsInput {
+                from: OptionalValue::Value(IsoDate(date(10, 19))),
+                until: OptionalValue::Missing,
+            }
+            .into_date_range(date(10, 18)),
+            Err(ToolInputError::Semantic { field, .. }) if field == "until"
+        ));
+    }
 }
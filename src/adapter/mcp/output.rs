@@ -1,4 +1,4 @@
-use crate::application::schedule_use_case::ScheduledTaskView;
+use crate::application::schedule_use_case::{ScheduledTaskView, UnscheduledTaskView};
 use crate::application::task_use_case::TaskView;
 use serde_json::Value;
 
@@ -9,3 +9,7 @@ pub(super) fn task_view_json(task: &TaskView) -> Value {
 pub(super) fn scheduled_task_view_json(scheduled: &ScheduledTaskView) -> Value {
     serde_json::to_value(scheduled).expect("ScheduledTaskView serialization is infallible")
 }
+
+pub(super) fn unscheduled_task_view_json(unscheduled: &UnscheduledTaskView) -> Value {
+    serde_json::to_value(unscheduled).expect("UnscheduledTaskView serialization is infallible")
+}
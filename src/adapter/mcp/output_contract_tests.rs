@@ -1,9 +1,9 @@
 use super::output::{scheduled_task_view_json, task_view_json};
 use super::test_support::json_fixture;
 use crate::application::schedule_use_case::ScheduledTaskView;
-use crate::application::task_use_case::TaskView;
+use crate::application::task_use_case::{TaskView, WaitingView};
 use crate::entity::task::{ProjectCategory, RepetitionAnchor, Status};
-use chrono::{Local, TimeZone};
+use chrono::{Local, NaiveDate, TimeZone};
 use serde_json::json;
 use uuid::Uuid;
 
@@ -38,6 +38,15 @@ fn task_viewのserde表現は既存mcp_json契約と一致する() {
         repetition_anchor: RepetitionAnchor::Completion,
         days_in_advance: 2,
         project_category: Some(ProjectCategory::Recovery),
+        waiting: Some(WaitingView {
+            counterpart: "reviewer".to_string(),
+            since: create_time,
+            expected_reply_date: NaiveDate::from_ymd_opt(2026, 8, 11),
+            follow_up_interval_days: Some(14),
+            last_followed_up_at: None,
+            next_follow_up_at: Some(pending_until),
+        }),
+        allowed_window: Some("月火水木金 09:00-18:00".to_string()),
     };
     let mut expected = json_fixture(
         include_str!("../../../tests/fixtures/mcp/task-view.json"),
@@ -91,6 +100,8 @@ fn scheduled_task_viewのserde表現はnested_taskを含む既存mcp_json契約
             repetition_anchor: RepetitionAnchor::Deadline,
             days_in_advance: 0,
             project_category: None,
+            waiting: None,
+            allowed_window: None,
         },
         first_available_time,
         scheduled_start,
@@ -1,5 +1,5 @@
 use super::test_support::*;
-use super::McpServer;
+use super::{McpServer, McpSession};
 
 #[test]
 fn initializeはserver情報とtools能力を返す() {
@@ -75,6 +75,38 @@ fn initializeとtools_listではrepository_clockを同期もloadもしない() {
     assert_eq!(load_count.get(), 0);
 }
 
+#[test]
+fn 初期化の状態は接続ごとのsessionで別々に進む() {
+    let mut server = McpServer::new(TaskRepository::new(""));
+    let mut initialized = McpSession::default();
+    let mut fresh = McpSession::default();
+    let tools_list = json!({
+        "jsonrpc": "2.0",
+        "id": "tools-list",
+        "method": "tools/list"
+    });
+
+    server
+        .handle_session_request(&mut initialized, initialize_request())
+        .unwrap();
+    server.handle_session_request(
+        &mut initialized,
+        json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/initialized"
+        }),
+    );
+    let initialized_response = server
+        .handle_session_request(&mut initialized, tools_list.clone())
+        .unwrap();
+    let fresh_response = server
+        .handle_session_request(&mut fresh, tools_list)
+        .unwrap();
+
+    assert!(initialized_response["result"]["tools"].is_array());
+    assert_eq!(fresh_response["error"]["code"], -32002);
+}
+
 #[test]
 #[allow(non_snake_case)]
 fn 初期化完了前のtools_callはUninitializedとInitializeRespondedの両方で拒否しrepository_clockを同期もloadもしない(
@@ -925,11 +957,29 @@ fn tools_list_initialized通知後に9つのtoolのschemaを返す() {
         "get_task",
         "list_tasks",
         "get_schedule",
+        "get_load_history",
+        "get_flow_metrics",
         "create_task",
         "breakdown_task",
         "defer_task",
         "complete_task",
         "update_task",
+        "delete_task",
+        "list_trash",
+        "restore_task",
+        "purge_trash",
+        "list_waiting_tasks",
+        "list_at_risk_tasks",
+        "get_critical_path",
+        "get_task_tree",
+        "move_task",
+        "copy_task",
+        "merge_tasks",
+        "import_tasks",
+        "export_tasks",
+        "add_busy_block",
+        "list_busy_blocks",
+        "remove_busy_block",
     ];
     expected_names.sort_unstable();
     assert_eq!(names, expected_names);
@@ -950,6 +1000,14 @@ fn tools_list_initialized通知後に9つのtoolのschemaを返す() {
         vec!["categories", "period", "statuses"]
     );
     assert_eq!(property_names(tools, "get_schedule"), vec!["from", "until"]);
+    assert_eq!(
+        property_names(tools, "get_load_history"),
+        vec!["from", "until"]
+    );
+    assert_eq!(
+        property_names(tools, "get_flow_metrics"),
+        vec!["from", "until"]
+    );
     assert_eq!(
         property_names(tools, "create_task"),
         vec!["estimated_work_minutes", "name", "pending_until"]
@@ -969,6 +1027,7 @@ fn tools_list_initialized通知後に9つのtoolのschemaを返す() {
     assert_eq!(
         property_names(tools, "update_task"),
         vec![
+            "allowed_window",
             "category",
             "deadline_time",
             "estimated_work_minutes",
@@ -980,6 +1039,14 @@ fn tools_list_initialized通知後に9つのtoolのschemaを返す() {
     assert_eq!(required_fields(tools, "get_task"), vec!["task_id"]);
     assert_eq!(required_fields(tools, "list_tasks"), Vec::<&str>::new());
     assert_eq!(required_fields(tools, "get_schedule"), Vec::<&str>::new());
+    assert_eq!(
+        required_fields(tools, "get_load_history"),
+        Vec::<&str>::new()
+    );
+    assert_eq!(
+        required_fields(tools, "get_flow_metrics"),
+        Vec::<&str>::new()
+    );
     assert_eq!(required_fields(tools, "create_task"), vec!["name"]);
     assert_eq!(
         required_fields(tools, "breakdown_task"),
@@ -991,30 +1058,117 @@ fn tools_list_initialized通知後に9つのtoolのschemaを返す() {
     );
     assert_eq!(required_fields(tools, "complete_task"), vec!["task_id"]);
     assert_eq!(required_fields(tools, "update_task"), vec!["task_id"]);
+    assert_eq!(required_fields(tools, "delete_task"), vec!["task_id"]);
+    assert_eq!(required_fields(tools, "list_trash"), Vec::<&str>::new());
+    assert_eq!(required_fields(tools, "restore_task"), vec!["trash_id"]);
+    assert_eq!(required_fields(tools, "purge_trash"), vec!["trash_id"]);
+    assert_eq!(
+        required_fields(tools, "list_waiting_tasks"),
+        Vec::<&str>::new()
+    );
+    assert_eq!(
+        required_fields(tools, "list_at_risk_tasks"),
+        Vec::<&str>::new()
+    );
+    assert_eq!(required_fields(tools, "get_critical_path"), vec!["task_id"]);
+    assert_eq!(required_fields(tools, "get_task_tree"), vec!["task_id"]);
+    assert_eq!(required_fields(tools, "move_task"), vec!["task_id"]);
+    assert_eq!(required_fields(tools, "copy_task"), vec!["task_id"]);
+    assert_eq!(
+        required_fields(tools, "merge_tasks"),
+        vec!["source_task_id", "task_id"]
+    );
+    assert_eq!(
+        required_fields(tools, "import_tasks"),
+        vec!["format", "text"]
+    );
+    assert_eq!(
+        required_fields(tools, "export_tasks"),
+        vec!["format", "task_id"]
+    );
+    assert_eq!(required_fields(tools, "add_busy_block"), vec!["name"]);
+    assert_eq!(
+        required_fields(tools, "list_busy_blocks"),
+        Vec::<&str>::new()
+    );
+    assert_eq!(
+        required_fields(tools, "remove_busy_block"),
+        vec!["busy_block_id"]
+    );
 
     assert_string_property(tools, "get_task", "task_id", Some("uuid"));
     assert_string_property(tools, "get_schedule", "from", Some("date"));
     assert_string_property(tools, "get_schedule", "until", Some("date"));
+    assert_string_property(tools, "get_load_history", "from", Some("date"));
+    assert_string_property(tools, "get_load_history", "until", Some("date"));
+    assert_string_property(tools, "get_flow_metrics", "from", Some("date"));
+    assert_string_property(tools, "get_flow_metrics", "until", Some("date"));
     assert_string_property(tools, "create_task", "name", None);
     assert_eq!(property(tools, "create_task", "name")["minLength"], 1);
     assert_non_negative_integer_property(tools, "create_task", "estimated_work_minutes");
-    assert_string_property(tools, "create_task", "pending_until", Some("date-time"));
+    assert_string_property(tools, "create_task", "pending_until", None);
     assert_string_property(tools, "breakdown_task", "parent_id", Some("uuid"));
     let names_schema = property(tools, "breakdown_task", "names");
     assert_eq!(names_schema["type"], "array");
     assert_eq!(names_schema["items"]["type"], "string");
     assert_eq!(names_schema["items"]["minLength"], 1);
     assert_eq!(names_schema["minItems"], 1);
-    assert_string_property(tools, "breakdown_task", "pending_until", Some("date-time"));
+    assert_string_property(tools, "breakdown_task", "pending_until", None);
     assert_string_property(tools, "defer_task", "task_id", Some("uuid"));
     assert_string_property(tools, "defer_task", "pending_until", Some("date-time"));
     assert_string_property(tools, "complete_task", "task_id", Some("uuid"));
-    assert_string_property(tools, "complete_task", "finished_at", Some("date-time"));
+    assert_string_property(tools, "complete_task", "finished_at", None);
     assert_non_negative_integer_property(tools, "complete_task", "additional_actual_work_seconds");
     assert_string_property(tools, "update_task", "task_id", Some("uuid"));
     assert_non_negative_integer_property(tools, "update_task", "estimated_work_minutes");
-    assert_nullable_string_property(tools, "update_task", "deadline_time", Some("date-time"));
+    assert_nullable_string_property(tools, "update_task", "deadline_time", None);
     assert_nullable_string_property(tools, "update_task", "category", None);
+    assert_nullable_string_property(tools, "update_task", "allowed_window", None);
+    assert_string_property(tools, "delete_task", "task_id", Some("uuid"));
+    assert_string_property(tools, "restore_task", "trash_id", Some("uuid"));
+    assert_string_property(tools, "purge_trash", "trash_id", Some("uuid"));
+    assert_non_negative_integer_property(tools, "list_at_risk_tasks", "max_slack_minutes");
+    assert_string_property(tools, "get_critical_path", "task_id", Some("uuid"));
+    assert_string_property(tools, "get_task_tree", "task_id", Some("uuid"));
+    assert_non_negative_integer_property(tools, "get_task_tree", "max_depth");
+    for tool in ["move_task", "copy_task"] {
+        assert_string_property(tools, tool, "task_id", Some("uuid"));
+        assert_string_property(tools, tool, "parent_id", Some("uuid"));
+    }
+    assert_string_property(tools, "merge_tasks", "task_id", Some("uuid"));
+    assert_string_property(tools, "merge_tasks", "source_task_id", Some("uuid"));
+    assert_eq!(
+        property_names(tools, "import_tasks"),
+        vec!["dry_run", "format", "parent_id", "text"]
+    );
+    assert_string_property(tools, "import_tasks", "text", None);
+    assert_string_property(tools, "import_tasks", "parent_id", Some("uuid"));
+    assert_eq!(
+        sorted_strings(&property(tools, "import_tasks", "format")["enum"]),
+        vec!["markdown", "org", "todo_txt"]
+    );
+    assert_eq!(
+        property(tools, "import_tasks", "dry_run")["type"],
+        "boolean"
+    );
+    assert_eq!(
+        property_names(tools, "export_tasks"),
+        vec!["format", "task_id"]
+    );
+    assert_string_property(tools, "export_tasks", "task_id", Some("uuid"));
+    assert_eq!(
+        sorted_strings(&property(tools, "export_tasks", "format")["enum"]),
+        vec!["markdown", "opml"]
+    );
+    assert_eq!(
+        property_names(tools, "add_busy_block"),
+        vec!["end", "end_date", "name", "start", "start_date"]
+    );
+    assert_string_property(tools, "add_busy_block", "start", None);
+    assert_string_property(tools, "add_busy_block", "end", None);
+    assert_string_property(tools, "add_busy_block", "start_date", Some("date"));
+    assert_string_property(tools, "add_busy_block", "end_date", Some("date"));
+    assert_string_property(tools, "remove_busy_block", "busy_block_id", Some("uuid"));
 
     let period = property(tools, "list_tasks", "period");
     assert_eq!(period["type"], "object");
@@ -1055,6 +1209,7 @@ fn tools_list_initialized通知後に9つのtoolのschemaを返す() {
     assert_eq!(
         update_fields,
         vec![
+            vec!["allowed_window"],
             vec!["category"],
             vec!["deadline_time"],
             vec!["estimated_work_minutes"]
@@ -1,6 +1,10 @@
 use super::input::{
-    generated_input_schema, BreakdownTaskInput, CompleteTaskInput, CreateTaskInput, DeferTaskInput,
-    GetFocusInput, GetScheduleInput, GetTaskInput, ListTasksInput, UpdateTaskInput,
+    generated_input_schema, AddBusyBlockInput, BreakdownTaskInput, CompleteTaskInput,
+    CreateTaskInput, DeferTaskInput, DeleteTaskInput, ExportTasksInput, GetCriticalPathInput,
+    GetFlowMetricsInput, GetFocusInput, GetLoadHistoryInput, GetScheduleInput, GetTaskInput,
+    GetTaskTreeInput, ImportTasksInput, ListAtRiskTasksInput, ListBusyBlocksInput, ListTasksInput,
+    ListTrashInput, ListWaitingTasksInput, MergeTasksInput, RemoveBusyBlockInput,
+    SubtreeDestinationInput, TrashEntryInput, UpdateTaskInput,
 };
 use serde_json::{json, Value};
 
@@ -23,9 +27,19 @@ pub(super) fn tool_definitions() -> Vec<Value> {
         }),
         json!({
             "name": "get_schedule",
-            "description": "Get Schronu's calculated task schedule for a date range.",
+            "description": "Get Schronu's calculated task schedule for a date range. Atomic tasks that fit in no free interval are listed under unscheduled instead of being split.",
             "inputSchema": generated_input_schema::<GetScheduleInput>()
         }),
+        json!({
+            "name": "get_load_history",
+            "description": "Get the daily load snapshots recorded by Schronu for a date range.",
+            "inputSchema": generated_input_schema::<GetLoadHistoryInput>()
+        }),
+        json!({
+            "name": "get_flow_metrics",
+            "description": "Get lead time, cycle time, weekly throughput, and work in progress for tasks completed in a date range.",
+            "inputSchema": generated_input_schema::<GetFlowMetricsInput>()
+        }),
         json!({
             "name": "create_task",
             "description": "Create a new root project task.",
@@ -48,8 +62,88 @@ pub(super) fn tool_definitions() -> Vec<Value> {
         }),
         json!({
             "name": "update_task",
-            "description": "Update a task's estimate, deadline, or category.",
+            "description": "Update a task's estimate, deadline, category, or allowed time window.",
             "inputSchema": generated_input_schema::<UpdateTaskInput>()
         }),
+        json!({
+            "name": "delete_task",
+            "description": "Move a task and its subtree to the trash without completing it.",
+            "inputSchema": generated_input_schema::<DeleteTaskInput>()
+        }),
+        json!({
+            "name": "list_trash",
+            "description": "List trashed subtrees, newest first.",
+            "inputSchema": generated_input_schema::<ListTrashInput>()
+        }),
+        json!({
+            "name": "restore_task",
+            "description": "Restore a trashed subtree to its original parent and position.",
+            "inputSchema": generated_input_schema::<TrashEntryInput>()
+        }),
+        json!({
+            "name": "purge_trash",
+            "description": "Permanently remove a trash entry and any empty project directory it left.",
+            "inputSchema": generated_input_schema::<TrashEntryInput>()
+        }),
+        json!({
+            "name": "list_waiting_tasks",
+            "description": "List open tasks waiting on someone else, grouped by counterpart and oldest first, with follow-up due flags.",
+            "inputSchema": generated_input_schema::<ListWaitingTasksInput>()
+        }),
+        json!({
+            "name": "list_at_risk_tasks",
+            "description": "List open leaf tasks with a deadline (own or inherited) by slack between scheduled end and deadline, smallest first, flagging late tasks that deferring lower-priority work would recover.",
+            "inputSchema": generated_input_schema::<ListAtRiskTasksInput>()
+        }),
+        json!({
+            "name": "get_critical_path",
+            "description": "Compute earliest and latest start/finish and float for every open task in the project containing task_id, treating children as prerequisites of their parent and counting only free time, and return the critical chain that sets the project finish.",
+            "inputSchema": generated_input_schema::<GetCriticalPathInput>()
+        }),
+        json!({
+            "name": "get_task_tree",
+            "description": "Get the subtree rooted at task_id as nested nodes down to max_depth levels of children (all levels when omitted). Each node has the task and rollups over its whole subtree: remaining estimate of open tasks, actual work, done and total leaf counts, earliest open deadline and latest scheduled finish.",
+            "inputSchema": generated_input_schema::<GetTaskTreeInput>()
+        }),
+        json!({
+            "name": "move_task",
+            "description": "Move a task and its subtree under parent_id as its last child, or make it a new project when parent_id is omitted. Moving a project root merges that project into the destination. Fails when parent_id is inside the subtree or when an open task in the subtree has a deadline later than the parent's.",
+            "inputSchema": generated_input_schema::<SubtreeDestinationInput>()
+        }),
+        json!({
+            "name": "copy_task",
+            "description": "Duplicate a task and its subtree under parent_id, or as a new project when parent_id is omitted. Copies get fresh ids, Todo status and zero actual work, and keep estimates, deadlines and repetition settings. Returns the id of the copied root.",
+            "inputSchema": generated_input_schema::<SubtreeDestinationInput>()
+        }),
+        json!({
+            "name": "merge_tasks",
+            "description": "Merge source_task_id into its sibling task_id: estimates and actual work are summed, the source's children are appended after task_id's children, the earlier open deadline is kept and the source task is removed. Fails when a descendant's deadline is later than the merged deadline.",
+            "inputSchema": generated_input_schema::<MergeTasksInput>()
+        }),
+        json!({
+            "name": "import_tasks",
+            "description": "Import task trees from text: a Markdown checklist (- [ ] name (30m) @deadline, nested by indentation under headings), Org-mode headings with SCHEDULED, DEADLINE and Effort, or todo.txt lines grouped under their first +project. Checked Markdown items become done tasks, and a trailing <!-- status: pending, pending_until: ..., actual: 40m --> comment restores the status, pending date and actual work written by export_tasks. Org-mode DONE headings and todo.txt x lines are skipped with their subtrees. Trees become new projects, or children of parent_id when given; children without a deadline inherit their parent's. With dry_run true, only returns the parsed trees.",
+            "inputSchema": generated_input_schema::<ImportTasksInput>()
+        }),
+        json!({
+            "name": "export_tasks",
+            "description": "Export the subtree rooted at task_id as text. markdown writes a checklist in the import_tasks syntax (- [ ] name (30m) @YYYY-MM-DD HH:MM, done items checked, actual work and pending dates in a trailing <!-- --> comment) so it can be imported again; opml writes outline elements with status, estimatedMinutes, actualMinutes, deadline, pendingUntil and taskId attributes.",
+            "inputSchema": generated_input_schema::<ExportTasksInput>()
+        }),
+        json!({
+            "name": "add_busy_block",
+            "description": "Block a dated period so scheduling treats it as busy: either a timed block with start and end, or an all-day block from start_date through end_date (one day when end_date is omitted) that covers each business day and its night.",
+            "inputSchema": generated_input_schema::<AddBusyBlockInput>()
+        }),
+        json!({
+            "name": "list_busy_blocks",
+            "description": "List dated busy blocks in start order with the interval each one blocks.",
+            "inputSchema": generated_input_schema::<ListBusyBlocksInput>()
+        }),
+        json!({
+            "name": "remove_busy_block",
+            "description": "Remove a dated busy block so its time counts as free again.",
+            "inputSchema": generated_input_schema::<RemoveBusyBlockInput>()
+        }),
     ]
 }
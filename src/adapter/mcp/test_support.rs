@@ -208,6 +208,36 @@ impl TaskRepositoryTrait for RecordingRepository {
         self.project_count.set(self.projects.len());
         Ok(())
     }
+
+    fn remove_project(
+        &mut self,
+        root_task_id: Uuid,
+    ) -> Result<Option<String>, crate::entity::task::TaskTreeError> {
+        let Some(index) = self
+            .projects
+            .iter()
+            .position(|project| project.get_id() == Ok(root_task_id))
+        else {
+            return Ok(None);
+        };
+        self.mutation_count.set(self.mutation_count.get() + 1);
+        self.operation_order.borrow_mut().push("mutation");
+        let removed = self.projects.remove(index);
+        self.project_count.set(self.projects.len());
+        removed.get_name().map(Some)
+    }
+
+    fn restore_project(
+        &mut self,
+        project_key: &str,
+        root_task: TaskHandle,
+    ) -> Result<String, crate::entity::task::TaskTreeError> {
+        self.mutation_count.set(self.mutation_count.get() + 1);
+        self.operation_order.borrow_mut().push("mutation");
+        self.projects.push(root_task);
+        self.project_count.set(self.projects.len());
+        Ok(project_key.to_string())
+    }
 }
 
 pub(super) fn initialize_request() -> serde_json::Value {
@@ -224,7 +254,10 @@ pub(super) fn initialize_request() -> serde_json::Value {
 }
 
 pub(super) fn initialized_server<R: TaskRepositoryTrait>(repository: R) -> McpServer<R> {
-    let mut server = McpServer::new(repository);
+    initialize_server(McpServer::new(repository))
+}
+
+pub(super) fn initialize_server<R: TaskRepositoryTrait>(mut server: McpServer<R>) -> McpServer<R> {
     server.handle_request(initialize_request()).unwrap();
     server.handle_request(json!({
         "jsonrpc": "2.0",
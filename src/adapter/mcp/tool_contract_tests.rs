@@ -1,5 +1,10 @@
 use super::test_support::*;
 use super::McpServer;
+use crate::entity::allowed_window::AllowedWindow;
+use crate::entity::datetime::temporal::ClockTime;
+use crate::entity::datetime::BusinessDateTimePolicy;
+use crate::entity::waiting::WaitingRecord;
+use chrono::{NaiveDate, NaiveTime};
 
 #[test]
 fn 初期化済みtools_callは検証結果によらずdispatch直前にrepository_clockを同期してloadする() {
@@ -69,6 +74,13 @@ fn get_scheduleは借用競合を既存internal_error形式で返す() {
                 from: super::input::OptionalValue::Missing,
                 until: super::input::OptionalValue::Missing,
             },
+            super::handler::ToolClock {
+                operation_now: Local::now(),
+                datetime_policy: &BusinessDateTimePolicy::new(30),
+                default_deadline_time: ClockTime::new(19, 0, 0).unwrap(),
+                busy_time_slots_path: None,
+                storage_cipher: None,
+            },
         )
     });
 
@@ -123,6 +135,220 @@ fn 同一mcp_processの2回目のread_toolは実repositoryのcacheを使う() {
     );
 }
 
+#[test]
+fn lock_waitを設定すると書き込みtoolだけが保持者の操作が終わるまで待つ() {
+    use crate::adapter::gateway::storage_lock::{LockMode, StorageLock};
+    use std::time::Duration;
+
+    let storage = McpCacheTestStorage::new();
+    let repository = TaskRepository::new(storage.path.to_str().unwrap());
+    let mut server = McpServer::with_storage_directory(repository, &storage.path)
+        .with_lock_wait(Duration::from_secs(5));
+    server.handle_request(initialize_request()).unwrap();
+    server.handle_request(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    }));
+    let cli_lock = StorageLock::acquire(&storage.path, LockMode::Cli).unwrap();
+
+    let read = server
+        .handle_request(tool_call_request("read", "list_tasks", json!({})))
+        .unwrap();
+    assert_eq!(
+        read["result"]["structuredContent"]["error"]["code"],
+        "repository_lock_contended"
+    );
+
+    // 保持者の操作が少し続いてから解放される
+    let holder = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(200));
+        drop(cli_lock);
+    });
+    let created = server
+        .handle_request(tool_call_request(
+            "create",
+            "create_task",
+            json!({"name": "空いてから作る"}),
+        ))
+        .unwrap();
+
+    holder.join().unwrap();
+    assert_eq!(created["result"]["isError"], false);
+}
+
+#[test]
+fn 暗号化した保存先ではdelete_taskが捨てた記録を暗号文で書きlist_trashで読み直せる() {
+    use crate::adapter::gateway::storage_encryption::{
+        initialize_storage_encryption, is_encrypted, StorageKeySource,
+    };
+    use crate::adapter::gateway::trash::TRASH_FILE_NAME;
+
+    let storage = McpCacheTestStorage::new();
+    let key_file_path = storage.path.join("schronu.key");
+    fs::write(&key_file_path, [7; 32]).unwrap();
+    let cipher = initialize_storage_encryption(
+        &storage.path,
+        &StorageKeySource::new(Some(key_file_path), None),
+    )
+    .unwrap();
+    let repository = TaskRepository::new(storage.path.to_str().unwrap())
+        .with_storage_cipher(Some(cipher.clone()));
+    let mut server = McpServer::with_storage_directory(repository, &storage.path)
+        .with_storage_cipher(Some(cipher));
+    server.handle_request(initialize_request()).unwrap();
+    server.handle_request(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    }));
+    let created = server
+        .handle_request(tool_call_request(
+            "create",
+            "create_task",
+            json!({"name": "秘密のproject"}),
+        ))
+        .unwrap();
+    let task_id = created["result"]["structuredContent"]["task_id"].clone();
+
+    let deleted = server
+        .handle_request(tool_call_request(
+            "delete",
+            "delete_task",
+            json!({"task_id": task_id}),
+        ))
+        .unwrap();
+    let listed = server
+        .handle_request(tool_call_request("list", "list_trash", json!({})))
+        .unwrap();
+
+    assert_eq!(deleted["result"]["isError"], false);
+    let bytes = fs::read(storage.path.join(TRASH_FILE_NAME)).unwrap();
+    assert!(is_encrypted(&bytes));
+    assert!(!String::from_utf8_lossy(&bytes).contains("秘密のproject"));
+    assert_eq!(
+        listed["result"]["structuredContent"]["entries"][0]["name"],
+        "秘密のproject"
+    );
+}
+
+#[test]
+fn get_load_historyはstorageの負荷履歴を両端を含む日付範囲で返しrepositoryを変更しない() {
+    use crate::adapter::gateway::load_history::LoadHistoryFile;
+    use crate::application::interface::LoadHistoryStoreTrait;
+    use crate::application::load_history::{build_load_snapshot, StatusCounts};
+
+    let storage = McpCacheTestStorage::new();
+    let mut history = LoadHistoryFile::in_storage_directory(&storage.path);
+    for day in [9, 10, 11, 12] {
+        history
+            .append_load_snapshot(&build_load_snapshot(
+                chrono::NaiveDate::from_ymd_opt(2026, 8, day).unwrap(),
+                Local.with_ymd_and_hms(2026, 8, day, 9, 0, 0).unwrap(),
+                8 * 60,
+                4 * 3600,
+                3600,
+                StatusCounts {
+                    todo: 3,
+                    pending: 1,
+                    done: 2,
+                },
+            ))
+            .unwrap();
+    }
+    let repository = TaskRepository::new(storage.path.to_str().unwrap());
+    let mut server = McpServer::with_storage_directory(repository, &storage.path);
+    server.handle_request(initialize_request()).unwrap();
+    server.handle_request(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    }));
+
+    let response = server
+        .handle_request(tool_call_request(
+            "load-history",
+            "get_load_history",
+            json!({"from": "2026-08-10", "until": "2026-08-11"}),
+        ))
+        .unwrap();
+
+    assert_eq!(response["result"]["isError"], false);
+    assert_tool_result_content_matches_structured(&response);
+    let snapshots = response["result"]["structuredContent"]["snapshots"]
+        .as_array()
+        .unwrap();
+    assert_eq!(
+        snapshots
+            .iter()
+            .map(|snapshot| snapshot["date"].as_str().unwrap())
+            .collect::<Vec<_>>(),
+        vec!["2026-08-10", "2026-08-11"]
+    );
+    assert_eq!(snapshots[0]["rho"], 0.5);
+    assert_eq!(
+        snapshots[0]["status_counts"],
+        json!({"todo": 3, "pending": 1, "done": 2})
+    );
+
+    let reversed = server
+        .handle_request(tool_call_request(
+            "reversed-load-history",
+            "get_load_history",
+            json!({"from": "2026-08-12", "until": "2026-08-11"}),
+        ))
+        .unwrap();
+    assert_eq!(
+        reversed["result"]["structuredContent"]["error"]["field"],
+        "until"
+    );
+    assert_eq!(history.list_load_snapshots().unwrap().len(), 4);
+}
+
+#[test]
+fn get_flow_metricsは完了taskの分布と週ごとの完了と日ごとの仕掛りを返しrepositoryを変更しない() {
+    let task = task_for_list(
+        "done",
+        Status::Done,
+        ProjectCategory::Recovery,
+        Local.with_ymd_and_hms(2026, 8, 10, 9, 0, 0).unwrap(),
+    );
+    task.set_start_time(Local.with_ymd_and_hms(2026, 8, 10, 12, 0, 0).unwrap())
+        .unwrap();
+    task.set_end_time_opt(Some(Local.with_ymd_and_hms(2026, 8, 11, 9, 0, 0).unwrap()))
+        .unwrap();
+    let repository = RecordingRepository::new(vec![task]);
+    let save_count = Rc::clone(&repository.save_count);
+    let mutation_count = Rc::clone(&repository.mutation_count);
+    let mut server = initialized_server(repository);
+
+    let response = server
+        .handle_request(tool_call_request(
+            "flow-metrics",
+            "get_flow_metrics",
+            json!({"from": "2026-08-10", "until": "2026-08-16"}),
+        ))
+        .unwrap();
+
+    assert_eq!(response["result"]["isError"], false);
+    assert_tool_result_content_matches_structured(&response);
+    let metrics = &response["result"]["structuredContent"]["metrics"];
+    assert_eq!(metrics["overall"]["completed"], 1);
+    assert_eq!(metrics["overall"]["lead_time"]["p50_seconds"], 24 * 3600);
+    assert_eq!(metrics["overall"]["cycle_time"]["max_seconds"], 21 * 3600);
+    assert_eq!(metrics["by_level"][1]["level"], "leaf");
+    assert_eq!(metrics["by_level"][1]["completed"], 1);
+    assert_eq!(metrics["by_category"][0]["category"], "recovery");
+    assert_eq!(
+        metrics["weekly_throughput"],
+        json!([{"week_start": "2026-08-10", "completed": 1, "completed_leaves": 1}])
+    );
+    assert_eq!(
+        metrics["work_in_progress"][0],
+        json!({"date": "2026-08-10", "leaves": 1, "projects": 1})
+    );
+    assert_eq!(metrics["work_in_progress"][1]["leaves"], 0);
+    assert_eq!(save_count.get(), 0);
+    assert_eq!(mutation_count.get(), 0);
+}
+
 #[test]
 fn repository_load失敗はtaskを作成せずstructured_errorを返し同一sessionの次回callで再試行する() {
     let repository = RecordingRepository::new(vec![]).with_load_failure_once();
@@ -211,6 +437,15 @@ fn get_taskはTaskViewをstructured_contentで返してsaveしない() {
     root.set_days_in_advance(2).unwrap();
     root.set_project_category_opt(Some(ProjectCategory::Recovery))
         .unwrap();
+    root.set_waiting_opt(Some(WaitingRecord::new(
+        "reviewer",
+        create_time,
+        NaiveDate::from_ymd_opt(2026, 8, 11),
+        Some(14),
+    )))
+    .unwrap();
+    root.set_allowed_window_opt(Some(AllowedWindow::parse("平日 09:00-18:00").unwrap()))
+        .unwrap();
     root.sync_clock(fixed_now()).unwrap();
     let child = root.create_as_last_child(new_task_attr("child"));
     let task_id = root.get_id().unwrap();
@@ -237,6 +472,7 @@ fn get_taskはTaskViewをstructured_contentで返してsaveしない() {
         sorted_object_keys(task),
         vec![
             "actual_work_seconds",
+            "allowed_window",
             "atomic",
             "child_ids",
             "create_time",
@@ -256,7 +492,8 @@ fn get_taskはTaskViewをstructured_contentで返してsaveしない() {
             "repetition_interval_days",
             "root_id",
             "start_time",
-            "status"
+            "status",
+            "waiting"
         ]
     );
     assert_eq!(
@@ -896,7 +1133,11 @@ fn get_scheduleは日付範囲と片方の日付指定で重なる予定を返
     later_task.set_estimated_work_seconds(15 * 60).unwrap();
 
     let repository = RecordingRepository::new(vec![crossing_task, inside_task, later_task]);
-    let mut server = initialized_server(repository);
+    // 業務日の境界をまたぐ予定を作るため、業務日の終わりを次の業務日の始まりに合わせる
+    let mut server = initialize_server(McpServer::new(repository).with_datetime_policy(
+        BusinessDateTimePolicy::new(6 * 60),
+        NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+    ));
     let from = from_boundary.format("%F").to_string();
     let until = until_boundary.format("%F").to_string();
 
@@ -927,6 +1168,71 @@ fn get_scheduleは日付範囲と片方の日付指定で重なる予定を返
     }
 }
 
+#[test]
+fn get_scheduleは夜と埋まっている時間を避けて予定を置く() {
+    let storage = McpCacheTestStorage::new();
+    let busy_time_slots_path = storage.path.join("busy_time_slots.yaml");
+    let mut yaml = String::from("days_of_week:\n");
+    for day_of_week in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"] {
+        yaml.push_str(&format!(
+            "  - day_of_week: {day_of_week}\n    busy_time_slots:\n      - start_time: '13:00'\n        duration_minutes: 60\n        name: lunch\n"
+        ));
+    }
+    fs::write(&busy_time_slots_path, yaml).unwrap();
+    let day_start = try_next_business_day_start(Local::now()).unwrap();
+
+    let early_task = new_task_handle("early task").unwrap();
+    early_task
+        .set_start_time(day_start - Duration::hours(1))
+        .unwrap();
+    early_task.set_estimated_work_seconds(30 * 60).unwrap();
+    let lunch_crossing_task = new_task_handle("lunch crossing task").unwrap();
+    lunch_crossing_task
+        .set_start_time(day_start + Duration::hours(6))
+        .unwrap();
+    lunch_crossing_task
+        .set_estimated_work_seconds(2 * 60 * 60)
+        .unwrap();
+
+    let repository = RecordingRepository::new(vec![early_task, lunch_crossing_task]);
+    let mut server = initialize_server(
+        McpServer::new(repository).with_busy_time_slots_path(&busy_time_slots_path),
+    );
+    let response = server
+        .handle_request(tool_call_request(
+            "busy",
+            "get_schedule",
+            json!({"from": day_start.format("%F").to_string()}),
+        ))
+        .unwrap();
+    let segments = response["result"]["structuredContent"]["schedule"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|scheduled| {
+            (
+                scheduled["task"]["name"].as_str().unwrap().to_string(),
+                scheduled["scheduled_start"].as_str().unwrap().to_string(),
+                scheduled["scheduled_end"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let at = |hour: i64| (day_start + Duration::hours(hour - 6)).to_rfc3339();
+    assert_eq!(
+        segments,
+        vec![
+            (
+                "early task".to_string(),
+                at(6),
+                (day_start + Duration::minutes(30)).to_rfc3339()
+            ),
+            ("lunch crossing task".to_string(), at(12), at(13)),
+            ("lunch crossing task".to_string(), at(14), at(15)),
+        ]
+    );
+}
+
 #[test]
 fn get_schedule_予定なしを空配列で返す() {
     let repository = RecordingRepository::new(vec![]);
@@ -2574,3 +2880,307 @@ fn get_scheduleは日時application_errorを情報付きinternal_errorとして
         expected.to_string()
     );
 }
+
+#[test]
+fn delete_taskは部分木をゴミ箱へ移しrestore_taskで元の位置へ戻してから保存する() {
+    let storage = McpCacheTestStorage::new();
+    let storage_path = storage.path.to_str().unwrap();
+    let now = fixed_now();
+    let mut source = TaskRepository::new(storage_path);
+    source.sync_clock(now).unwrap();
+    let root = new_task_handle("ゴミ箱").unwrap();
+    let first = root.create_child(new_task_attr("一")).unwrap();
+    let second = root.create_child(new_task_attr("二")).unwrap();
+    let first_id = first.get_id().unwrap();
+    let second_id = second.get_id().unwrap();
+    source.start_new_project(root).unwrap();
+    source.save().unwrap();
+    let repository = TaskRepository::new(storage_path);
+    let mut server = McpServer::with_storage_directory(repository, &storage.path);
+    server.handle_request(initialize_request()).unwrap();
+    server.handle_request(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    }));
+
+    let deleted = server
+        .handle_request(tool_call_request(
+            "delete",
+            "delete_task",
+            json!({"task_id": first_id.to_string()}),
+        ))
+        .unwrap();
+    assert_eq!(deleted["result"]["isError"], false);
+    let entry = &deleted["result"]["structuredContent"]["entry"];
+    assert_eq!(entry["task_id"], first_id.to_string());
+    assert_eq!(entry["position"], 0);
+    let trash_id = entry["trash_id"].as_str().unwrap().to_string();
+
+    let mut saved = TaskRepository::new(storage_path);
+    saved.load().unwrap();
+    assert_eq!(saved.get_by_id(first_id).unwrap(), None);
+
+    let listed = server
+        .handle_request(tool_call_request("list", "list_trash", json!({})))
+        .unwrap();
+    assert_eq!(
+        listed["result"]["structuredContent"]["entries"][0]["trash_id"],
+        trash_id
+    );
+
+    let restored = server
+        .handle_request(tool_call_request(
+            "restore",
+            "restore_task",
+            json!({"trash_id": trash_id}),
+        ))
+        .unwrap();
+    assert_eq!(restored["result"]["isError"], false);
+
+    saved.load().unwrap();
+    let restored_first = saved.get_by_id(first_id).unwrap().unwrap();
+    let children = restored_first
+        .parent()
+        .unwrap()
+        .unwrap()
+        .get_children()
+        .unwrap();
+    assert_eq!(
+        children
+            .iter()
+            .map(|child| child.get_id().unwrap())
+            .collect::<Vec<_>>(),
+        [first_id, second_id]
+    );
+
+    let purged = server
+        .handle_request(tool_call_request(
+            "purge",
+            "purge_trash",
+            json!({"trash_id": trash_id}),
+        ))
+        .unwrap();
+    assert_eq!(purged["result"]["isError"], true);
+    assert_eq!(
+        purged["result"]["structuredContent"]["error"]["code"],
+        "trash_entry_not_found"
+    );
+}
+
+#[test]
+fn move_taskで別projectの根を取り込むと保存後も1つのprojectとして読み直せる() {
+    let storage = McpCacheTestStorage::new();
+    let storage_path = storage.path.to_str().unwrap();
+    let now = fixed_now();
+    let mut source = TaskRepository::new(storage_path);
+    source.sync_clock(now).unwrap();
+    let destination = new_task_handle("移動先").unwrap();
+    let moved = new_task_handle("移す").unwrap();
+    let child = moved.create_child(new_task_attr("子")).unwrap();
+    let destination_id = destination.get_id().unwrap();
+    let moved_id = moved.get_id().unwrap();
+    let child_id = child.get_id().unwrap();
+    source.start_new_project(destination).unwrap();
+    source.start_new_project(moved).unwrap();
+    source.save().unwrap();
+    let repository = TaskRepository::new(storage_path);
+    let mut server = McpServer::with_storage_directory(repository, &storage.path);
+    server.handle_request(initialize_request()).unwrap();
+    server.handle_request(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    }));
+
+    let response = server
+        .handle_request(tool_call_request(
+            "move",
+            "move_task",
+            json!({"task_id": moved_id.to_string(), "parent_id": destination_id.to_string()}),
+        ))
+        .unwrap();
+    assert_eq!(response["result"]["isError"], false);
+
+    let mut saved = TaskRepository::new(storage_path);
+    saved.load().unwrap();
+    assert_eq!(saved.get_all_projects().len(), 1);
+    let saved_child = saved.get_by_id(child_id).unwrap().unwrap();
+    assert_eq!(
+        saved_child.root().unwrap().get_id().unwrap(),
+        destination_id
+    );
+
+    let response = server
+        .handle_request(tool_call_request(
+            "split-off",
+            "move_task",
+            json!({"task_id": moved_id.to_string()}),
+        ))
+        .unwrap();
+    assert_eq!(response["result"]["isError"], false);
+    saved.load().unwrap();
+    assert_eq!(saved.get_all_projects().len(), 2);
+    assert!(saved
+        .get_by_id(moved_id)
+        .unwrap()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn delete_taskは他processが計時中のtaskを含むprojectと部分木を捨てない() {
+    use fs2::FileExt;
+
+    let storage = McpCacheTestStorage::new();
+    let storage_path = storage.path.to_str().unwrap();
+    let now = fixed_now();
+    let mut source = TaskRepository::new(storage_path);
+    source.sync_clock(now).unwrap();
+    let root = new_task_handle("作業中").unwrap();
+    let parent = root.create_child(new_task_attr("親")).unwrap();
+    let child = parent.create_child(new_task_attr("計時中")).unwrap();
+    let root_id = root.get_id().unwrap();
+    source.start_new_project(root).unwrap();
+    source.save().unwrap();
+    // 対話CLIが別processでfocusしている状態を、lockを持った作業記録で作る
+    let session_directory = storage.path.join(".work_sessions");
+    std::fs::create_dir_all(&session_directory).unwrap();
+    let session_path = session_directory.join("1-other.session");
+    std::fs::write(
+        &session_path,
+        format!(
+            "pid=1\ntask_id={}\nstarted_at=2026-08-11T09:00:00+09:00\n",
+            child.get_id().unwrap()
+        ),
+    )
+    .unwrap();
+    let session_file = std::fs::File::open(&session_path).unwrap();
+    session_file.try_lock_exclusive().unwrap();
+    let repository = TaskRepository::new(storage_path);
+    let mut server = McpServer::with_storage_directory(repository, &storage.path);
+    server.handle_request(initialize_request()).unwrap();
+    server.handle_request(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    }));
+
+    for target_id in [root_id, parent.get_id().unwrap()] {
+        let response = server
+            .handle_request(tool_call_request(
+                "delete",
+                "delete_task",
+                json!({"task_id": target_id.to_string()}),
+            ))
+            .unwrap();
+
+        assert_eq!(response["result"]["isError"], true);
+        let error = &response["result"]["structuredContent"]["error"];
+        assert_eq!(error["code"], "project_has_open_work");
+        assert_eq!(error["task_id"], child.get_id().unwrap().to_string());
+    }
+    assert!(storage
+        .path
+        .join("20260811-作業中")
+        .join("project.yaml")
+        .exists());
+
+    drop(session_file);
+    let response = server
+        .handle_request(tool_call_request(
+            "delete",
+            "delete_task",
+            json!({"task_id": parent.get_id().unwrap().to_string()}),
+        ))
+        .unwrap();
+    assert_eq!(response["result"]["isError"], false);
+    assert!(!session_path.exists());
+}
+
+#[test]
+fn add_busy_blockで入れた終日の予定を一覧しremove_busy_blockで消せる() {
+    let storage = McpCacheTestStorage::new();
+    let storage_path = storage.path.to_str().unwrap();
+    let mut source = TaskRepository::new(storage_path);
+    source.sync_clock(fixed_now()).unwrap();
+    source.save().unwrap();
+    let repository = TaskRepository::new(storage_path);
+    let mut server = McpServer::with_storage_directory(repository, &storage.path);
+    server.handle_request(initialize_request()).unwrap();
+    server.handle_request(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    }));
+
+    let invalid = server
+        .handle_request(tool_call_request(
+            "invalid",
+            "add_busy_block",
+            json!({"name": "休暇", "start": "2026-08-13T10:00:00+09:00"}),
+        ))
+        .unwrap();
+    assert_eq!(invalid["result"]["isError"], true);
+    let error = &invalid["result"]["structuredContent"]["error"];
+    assert_eq!(error["code"], "invalid_input");
+    assert_eq!(error["field"], "end");
+
+    let added = server
+        .handle_request(tool_call_request(
+            "add",
+            "add_busy_block",
+            json!({"name": "夏休み", "start_date": "2026-08-13", "end_date": "2026-08-14"}),
+        ))
+        .unwrap();
+    assert_eq!(added["result"]["isError"], false);
+    let block = &added["result"]["structuredContent"]["busy_block"];
+    assert_eq!(block["name"], "夏休み");
+    assert_eq!(block["all_day"], true);
+    assert_eq!(block["start_date"], "2026-08-13");
+    assert_eq!(block["end_date"], "2026-08-14");
+    assert_eq!(
+        block["start"],
+        Local
+            .with_ymd_and_hms(2026, 8, 13, 6, 0, 0)
+            .unwrap()
+            .to_rfc3339()
+    );
+    assert_eq!(
+        block["end"],
+        Local
+            .with_ymd_and_hms(2026, 8, 15, 6, 0, 0)
+            .unwrap()
+            .to_rfc3339()
+    );
+    let busy_block_id = block["busy_block_id"].as_str().unwrap().to_string();
+    assert!(storage.path.join(".busy_blocks.yaml").exists());
+
+    let listed = server
+        .handle_request(tool_call_request("list", "list_busy_blocks", json!({})))
+        .unwrap();
+    assert_eq!(
+        listed["result"]["structuredContent"]["busy_blocks"][0]["busy_block_id"],
+        busy_block_id
+    );
+
+    let removed = server
+        .handle_request(tool_call_request(
+            "remove",
+            "remove_busy_block",
+            json!({"busy_block_id": busy_block_id}),
+        ))
+        .unwrap();
+    assert_eq!(removed["result"]["isError"], false);
+
+    let removed_again = server
+        .handle_request(tool_call_request(
+            "remove-again",
+            "remove_busy_block",
+            json!({"busy_block_id": busy_block_id}),
+        ))
+        .unwrap();
+    assert_eq!(removed_again["result"]["isError"], true);
+    let error = &removed_again["result"]["structuredContent"]["error"];
+    assert_eq!(error["code"], "busy_block_not_found");
+    assert_eq!(error["field"], "busy_block_id");
+}
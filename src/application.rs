@@ -1,11 +1,25 @@
+pub mod at_risk;
+pub mod busy_block;
+pub mod consistency;
+pub mod critical_path;
 pub mod daily_capacity;
 pub mod flatten_use_case;
+pub mod flow_metrics;
+pub mod gantt;
 pub mod interface;
+pub mod load_history;
 pub mod pack_use_case;
+pub mod project_note;
 pub mod repository_transaction;
 pub mod schedule_use_case;
+pub mod storage_history;
+pub mod subtree;
+pub mod task_import;
+pub mod task_tree;
 pub mod task_use_case;
 mod task_view;
+pub mod trash;
+pub mod waiting;
 
 #[cfg(test)]
 mod list_tasks_contract_tests;
@@ -0,0 +1,269 @@
+use crate::application::interface::{FreeTimeManagerTrait, TaskRepositoryTrait};
+use crate::application::schedule_use_case::{
+    get_schedule, get_schedule_in_free_time_with_first_available_time_overrides, ScheduledTaskView,
+};
+use crate::application::task_use_case::ApplicationError;
+use crate::application::task_view::TaskView;
+use crate::entity::datetime::BusinessDateTimePolicy;
+use crate::entity::task::{Status, TaskHandle, TaskTreeError};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+// 〆切に対する余裕(slack)付きの未完了の葉。deadline_task_idは〆切を与えたtaskで、祖先から引き継いだ場合は祖先のid
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AtRiskTaskView {
+    pub task: TaskView,
+    pub deadline_time: DateTime<Local>,
+    pub deadline_task_id: Uuid,
+    pub scheduled_end: DateTime<Local>,
+    pub slack_seconds: i64,
+    pub recoverable_by_deferring: bool,
+}
+
+// 〆切のある未完了の葉を、余裕の少ない順に返す。max_slack_secondsを指定すると、余裕がそれ以下のtaskだけを返す。
+// 予定終了が〆切を過ぎているtaskには、優先度の低いtaskを〆切の後へ送れば間に合うかを添える
+pub fn list_at_risk_tasks(
+    repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
+    max_slack_seconds: Option<i64>,
+) -> Result<Vec<AtRiskTaskView>, ApplicationError> {
+    let schedule = get_schedule(repository, free_time_manager, policy)?;
+    let scheduled_end_by_id = collect_scheduled_end_by_id(&schedule);
+
+    let mut rows = Vec::new();
+    let mut seen_ids = HashSet::new();
+    for scheduled in &schedule {
+        let task_id = scheduled.task.id;
+        if scheduled.rank != 0 || scheduled.task.status == Status::Done || !seen_ids.insert(task_id)
+        {
+            continue;
+        }
+        let Some(task) = repository
+            .get_by_id(task_id)
+            .map_err(ApplicationError::TaskTree)?
+        else {
+            continue;
+        };
+        let Some((deadline_time, deadline_task_id)) =
+            inherited_deadline(&task).map_err(ApplicationError::TaskTree)?
+        else {
+            continue;
+        };
+        let scheduled_end = scheduled_end_by_id[&task_id];
+        let slack_seconds = (deadline_time - scheduled_end).num_seconds();
+        if max_slack_seconds.is_some_and(|max_slack_seconds| slack_seconds > max_slack_seconds) {
+            continue;
+        }
+        let recoverable_by_deferring = slack_seconds < 0
+            && can_recover_by_deferring(
+                repository,
+                free_time_manager,
+                policy,
+                &schedule,
+                &task,
+                deadline_time,
+            )?;
+        rows.push(AtRiskTaskView {
+            task: scheduled.task.clone(),
+            deadline_time,
+            deadline_task_id,
+            scheduled_end,
+            slack_seconds,
+            recoverable_by_deferring,
+        });
+    }
+    rows.sort_by_key(|row| (row.slack_seconds, row.scheduled_end, row.task.id));
+    Ok(rows)
+}
+
+// 分割されたtaskは複数の枠に現れるため、最後の枠の終了を予定終了とする
+fn collect_scheduled_end_by_id(schedule: &[ScheduledTaskView]) -> HashMap<Uuid, DateTime<Local>> {
+    let mut scheduled_end_by_id: HashMap<Uuid, DateTime<Local>> = HashMap::new();
+    for scheduled in schedule {
+        scheduled_end_by_id
+            .entry(scheduled.task.id)
+            .and_modify(|end| *end = (*end).max(scheduled.scheduled_end))
+            .or_insert(scheduled.scheduled_end);
+    }
+    scheduled_end_by_id
+}
+
+// set_deadline_time_optの伝搬と同じく、未完了の祖先の〆切のうち最も早いものを引き継ぐ
+fn inherited_deadline(task: &TaskHandle) -> Result<Option<(DateTime<Local>, Uuid)>, TaskTreeError> {
+    let mut deadline_opt: Option<(DateTime<Local>, Uuid)> = None;
+    let mut current_opt = Some(task.clone());
+    while let Some(current) = current_opt {
+        if current.get_status()? != Status::Done {
+            if let Some(deadline_time) = current.get_deadline_time_opt()? {
+                if deadline_opt.is_none_or(|(earliest, _)| deadline_time < earliest) {
+                    deadline_opt = Some((deadline_time, current.get_id()?));
+                }
+            }
+        }
+        current_opt = current.parent()?;
+    }
+    Ok(deadline_opt)
+}
+
+// 自分と祖先以外で優先度の低いtaskを〆切まで着手できないものとして予定を組み直し、〆切に間に合うかを調べる
+fn can_recover_by_deferring(
+    repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
+    schedule: &[ScheduledTaskView],
+    task: &TaskHandle,
+    deadline_time: DateTime<Local>,
+) -> Result<bool, ApplicationError> {
+    let task_id = task.get_id().map_err(ApplicationError::TaskTree)?;
+    let priority = task.get_priority().map_err(ApplicationError::TaskTree)?;
+    let mut protected_ids = HashSet::from([task_id]);
+    let mut parent_opt = task.parent().map_err(ApplicationError::TaskTree)?;
+    while let Some(parent) = parent_opt {
+        protected_ids.insert(parent.get_id().map_err(ApplicationError::TaskTree)?);
+        parent_opt = parent.parent().map_err(ApplicationError::TaskTree)?;
+    }
+
+    let overrides = schedule
+        .iter()
+        .filter(|scheduled| {
+            scheduled.task.priority < priority && !protected_ids.contains(&scheduled.task.id)
+        })
+        .map(|scheduled| (scheduled.task.id, deadline_time))
+        .collect::<HashMap<_, _>>();
+    if overrides.is_empty() {
+        return Ok(false);
+    }
+
+    let rescheduled = get_schedule_in_free_time_with_first_available_time_overrides(
+        repository,
+        free_time_manager,
+        policy,
+        &overrides,
+    )?;
+    Ok(collect_scheduled_end_by_id(&rescheduled)
+        .get(&task_id)
+        .is_some_and(|scheduled_end| *scheduled_end <= deadline_time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        new_task_attr_at, new_task_handle, BusyIntervals, TestTaskRepository,
+    };
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap()
+    }
+
+    fn policy() -> BusinessDateTimePolicy {
+        BusinessDateTimePolicy::new(30)
+    }
+
+    fn task_with_schedule(name: &str, work_seconds: i64, priority: i64) -> TaskHandle {
+        let task = new_task_handle(name).unwrap();
+        task.sync_clock(now()).unwrap();
+        task.set_start_time(now()).unwrap();
+        task.set_estimated_work_seconds(work_seconds).unwrap();
+        task.set_priority(priority).unwrap();
+        task
+    }
+
+    #[test]
+    fn 祖先の〆切を引き継いで余裕の少ない順に並べ後回しで間に合うかを示す() {
+        let low = task_with_schedule("低優先", 3 * 60 * 60, 1);
+        low.set_deadline_time_opt(Some(now() + Duration::hours(2)))
+            .unwrap();
+        let project = task_with_schedule("高優先project", 0, 5);
+        let child = project
+            .create_child(new_task_attr_at("高優先の作業", now()))
+            .unwrap();
+        child.set_estimated_work_seconds(60 * 60).unwrap();
+        child.set_priority(5).unwrap();
+        project
+            .set_deadline_time_opt(Some(now() + Duration::minutes(150)))
+            .unwrap();
+        child.unset_deadline_time_opt().unwrap();
+        let no_deadline = task_with_schedule("〆切なし", 30 * 60, 9);
+        let repository =
+            TestTaskRepository::new(vec![low.clone(), project.clone(), no_deadline], now());
+
+        let rows =
+            list_at_risk_tasks(&repository, &mut BusyIntervals(vec![]), &policy(), None).unwrap();
+
+        assert_eq!(
+            rows.iter()
+                .map(|row| (
+                    row.task.name.as_str(),
+                    row.slack_seconds,
+                    row.recoverable_by_deferring
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("高優先の作業", -2 * 60 * 60, true),
+                ("低優先", -60 * 60, false),
+            ]
+        );
+        assert_eq!(rows[0].deadline_time, now() + Duration::minutes(150));
+        assert_eq!(rows[0].deadline_task_id, project.get_id().unwrap());
+        assert_eq!(rows[0].scheduled_end, now() + Duration::minutes(270));
+        assert_eq!(rows[1].deadline_task_id, low.get_id().unwrap());
+    }
+
+    #[test]
+    fn 余裕の上限を指定するとそれ以下のtaskだけを返す() {
+        let tight = task_with_schedule("余裕1時間", 60 * 60, 5);
+        tight
+            .set_deadline_time_opt(Some(now() + Duration::hours(2)))
+            .unwrap();
+        let loose = task_with_schedule("余裕5時間", 60 * 60, 5);
+        loose
+            .set_deadline_time_opt(Some(now() + Duration::hours(7)))
+            .unwrap();
+        let repository = TestTaskRepository::new(vec![tight, loose], now());
+
+        let all =
+            list_at_risk_tasks(&repository, &mut BusyIntervals(vec![]), &policy(), None).unwrap();
+        let filtered = list_at_risk_tasks(
+            &repository,
+            &mut BusyIntervals(vec![]),
+            &policy(),
+            Some(2 * 60 * 60),
+        )
+        .unwrap();
+
+        assert_eq!(
+            all.iter()
+                .map(|row| (row.task.name.as_str(), row.slack_seconds))
+                .collect::<Vec<_>>(),
+            vec![("余裕1時間", 60 * 60), ("余裕5時間", 5 * 60 * 60)]
+        );
+        assert!(all.iter().all(|row| !row.recoverable_by_deferring));
+        assert_eq!(
+            filtered
+                .iter()
+                .map(|row| row.task.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["余裕1時間"]
+        );
+    }
+
+    #[test]
+    fn 埋まっている時間の後ろへずれた予定終了で余裕を数える() {
+        let task = task_with_schedule("会議の後の作業", 60 * 60, 5);
+        task.set_deadline_time_opt(Some(now() + Duration::hours(2)))
+            .unwrap();
+        let repository = TestTaskRepository::new(vec![task], now());
+        let mut free_time_manager = BusyIntervals(vec![(now(), now() + Duration::minutes(90))]);
+
+        let rows =
+            list_at_risk_tasks(&repository, &mut free_time_manager, &policy(), None).unwrap();
+
+        assert_eq!(rows[0].scheduled_end, now() + Duration::minutes(150));
+        assert_eq!(rows[0].slack_seconds, -30 * 60);
+    }
+}
@@ -0,0 +1,332 @@
+use super::daily_capacity::{try_local_date_and_time, try_subjective_date_start};
+use super::interface::{
+    BusyBlockStoreError, BusyBlockStoreTrait, BusyTimeSlotLoadError, BusyTimeSlotRegistrationError,
+    FreeTimeManagerTrait,
+};
+use super::storage_history::MIN_REVISION_PREFIX_LENGTH;
+use super::task_use_case::ApplicationError;
+use crate::entity::busy_block::{BusyBlock, BusyBlockSpan, EMPTY_NAME_REASON};
+use chrono::{DateTime, Local, NaiveTime};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub enum BusyBlockError {
+    Load(BusyTimeSlotLoadError),
+    Store(BusyBlockStoreError),
+    Application(ApplicationError),
+    Registration(BusyTimeSlotRegistrationError),
+    BlockNotFound(String),
+    AmbiguousBlock(String),
+}
+
+impl fmt::Display for BusyBlockError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Load(error) => error.fmt(formatter),
+            Self::Store(error) => error.fmt(formatter),
+            Self::Application(error) => error.fmt(formatter),
+            Self::Registration(error) => error.fmt(formatter),
+            Self::BlockNotFound(reference) => {
+                write!(formatter, "busy block not found: {reference}")
+            }
+            Self::AmbiguousBlock(reference) => {
+                write!(formatter, "busy block is ambiguous: {reference}")
+            }
+        }
+    }
+}
+
+impl Error for BusyBlockError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Load(error) => Some(error),
+            Self::Store(error) => Some(error),
+            Self::Application(error) => Some(error),
+            Self::Registration(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<BusyTimeSlotLoadError> for BusyBlockError {
+    fn from(error: BusyTimeSlotLoadError) -> Self {
+        Self::Load(error)
+    }
+}
+
+impl From<BusyBlockStoreError> for BusyBlockError {
+    fn from(error: BusyBlockStoreError) -> Self {
+        Self::Store(error)
+    }
+}
+
+impl From<ApplicationError> for BusyBlockError {
+    fn from(error: ApplicationError) -> Self {
+        Self::Application(error)
+    }
+}
+
+impl From<BusyTimeSlotRegistrationError> for BusyBlockError {
+    fn from(error: BusyTimeSlotRegistrationError) -> Self {
+        Self::Registration(error)
+    }
+}
+
+pub fn add_busy_block(
+    store: &mut dyn BusyBlockStoreTrait,
+    name: &str,
+    span: BusyBlockSpan,
+) -> Result<BusyBlock, BusyBlockError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ApplicationError::InvalidInput {
+            field: "name",
+            reason: EMPTY_NAME_REASON,
+        }
+        .into());
+    }
+    span.validate()
+        .map_err(|(field, reason)| ApplicationError::InvalidInput { field, reason })?;
+
+    let block = BusyBlock {
+        busy_block_id: Uuid::new_v4(),
+        name: name.to_string(),
+        span,
+    };
+    let mut blocks = store.list_busy_blocks()?;
+    blocks.push(block.clone());
+    store.save_busy_blocks(&blocks)?;
+    Ok(block)
+}
+
+// 始まる順に返す
+pub fn list_busy_blocks(
+    store: &dyn BusyBlockStoreTrait,
+) -> Result<Vec<BusyBlock>, BusyTimeSlotLoadError> {
+    let mut blocks = store.list_busy_blocks()?;
+    blocks.sort_by_key(|block| block.span.sort_key());
+    Ok(blocks)
+}
+
+// 捨てた記録と同じく、UUID全体または一意に決まる先頭部分で指定する
+pub fn resolve_busy_block(
+    blocks: &[BusyBlock],
+    reference: &str,
+) -> Result<BusyBlock, BusyBlockError> {
+    let normalized = reference.trim().to_ascii_lowercase();
+    if normalized.len() < MIN_REVISION_PREFIX_LENGTH {
+        return Err(BusyBlockError::BlockNotFound(reference.to_string()));
+    }
+    let matched_ids = blocks
+        .iter()
+        .map(|block| block.busy_block_id)
+        .filter(|block_id| block_id.hyphenated().to_string().starts_with(&normalized))
+        .collect::<BTreeSet<_>>();
+    if matched_ids.len() > 1 {
+        return Err(BusyBlockError::AmbiguousBlock(reference.to_string()));
+    }
+    blocks
+        .iter()
+        .find(|block| matched_ids.contains(&block.busy_block_id))
+        .cloned()
+        .ok_or_else(|| BusyBlockError::BlockNotFound(reference.to_string()))
+}
+
+pub fn remove_busy_block(
+    store: &mut dyn BusyBlockStoreTrait,
+    busy_block_id: Uuid,
+) -> Result<BusyBlock, BusyBlockError> {
+    let mut blocks = store.list_busy_blocks()?;
+    let index = blocks
+        .iter()
+        .position(|block| block.busy_block_id == busy_block_id)
+        .ok_or_else(|| BusyBlockError::BlockNotFound(busy_block_id.to_string()))?;
+    let removed = blocks.remove(index);
+    store.save_busy_blocks(&blocks)?;
+    Ok(removed)
+}
+
+// 埋まっている区間[start, end)。終日は初日の業務日開始から最終日の翌業務日開始まで
+pub fn busy_block_interval(
+    block: &BusyBlock,
+) -> Result<(DateTime<Local>, DateTime<Local>), ApplicationError> {
+    match block.span {
+        BusyBlockSpan::Timed { start, end } => Ok((start, end)),
+        BusyBlockSpan::AllDay {
+            start_date,
+            end_date,
+        } => {
+            let day_after_end = end_date
+                .succ_opt()
+                .ok_or(ApplicationError::SubjectiveDateStartOutOfRange { date: end_date })?;
+            Ok((
+                try_subjective_date_start(start_date)?,
+                try_subjective_date_start(day_after_end)?,
+            ))
+        }
+    }
+}
+
+// 空き時間の登録は暦の1日ごとなので、日を跨ぐ区間は0時で分けて登録する
+pub fn register_busy_blocks(
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    blocks: &[BusyBlock],
+) -> Result<(), BusyBlockError> {
+    for block in blocks {
+        let (mut current, end) = busy_block_interval(block)?;
+        while current < end {
+            let next_date = current.date_naive().succ_opt().ok_or(
+                ApplicationError::SubjectiveDateOutOfRange {
+                    operation: "next_calendar_date",
+                    datetime: current,
+                },
+            )?;
+            let piece_end = end.min(try_local_date_and_time(next_date, NaiveTime::MIN)?);
+            free_time_manager.register_busy_time_slot(&current, &piece_end)?;
+            current = piece_end;
+        }
+    }
+    Ok(())
+}
+
+// 週ごとの予定表と並べて読み込む。fileがなければ何も登録しない
+pub fn load_busy_blocks(
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    store: &dyn BusyBlockStoreTrait,
+) -> Result<(), BusyBlockError> {
+    register_busy_blocks(free_time_manager, &store.list_busy_blocks()?)
+}
+
+// 消した予定や他processが入れた予定を反映するため、登録済みの区間を捨てて読み直す
+pub fn reload_busy_blocks(
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    store: &dyn BusyBlockStoreTrait,
+) -> Result<(), BusyBlockError> {
+    let blocks = store.list_busy_blocks()?;
+    free_time_manager.clear_registered_busy_time_slots();
+    register_busy_blocks(free_time_manager, &blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::BusyIntervals;
+    use chrono::{NaiveDate, TimeZone};
+
+    #[derive(Default)]
+    struct TestBusyBlockStore {
+        blocks: Vec<BusyBlock>,
+    }
+
+    impl BusyBlockStoreTrait for TestBusyBlockStore {
+        fn list_busy_blocks(&self) -> Result<Vec<BusyBlock>, BusyTimeSlotLoadError> {
+            Ok(self.blocks.clone())
+        }
+
+        fn save_busy_blocks(&mut self, blocks: &[BusyBlock]) -> Result<(), BusyBlockStoreError> {
+            self.blocks = blocks.to_vec();
+            Ok(())
+        }
+    }
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn 追加した埋まっている時間を始まる順に返し先頭部分で消せる() {
+        let mut store = TestBusyBlockStore::default();
+        let dentist = add_busy_block(
+            &mut store,
+            " 歯医者 ",
+            BusyBlockSpan::Timed {
+                start: local(22, 14, 0),
+                end: local(22, 15, 0),
+            },
+        )
+        .unwrap();
+        let vacation = add_busy_block(
+            &mut store,
+            "休暇",
+            BusyBlockSpan::AllDay {
+                start_date: date(20),
+                end_date: date(21),
+            },
+        )
+        .unwrap();
+
+        let blocks = list_busy_blocks(&store).unwrap();
+        assert_eq!(blocks, vec![vacation.clone(), dentist.clone()]);
+        assert_eq!(dentist.name, "歯医者");
+
+        let prefix = &dentist.busy_block_id.to_string()[..8];
+        let resolved = resolve_busy_block(&blocks, prefix).unwrap();
+        let removed = remove_busy_block(&mut store, resolved.busy_block_id).unwrap();
+
+        assert_eq!(removed, dentist);
+        assert_eq!(list_busy_blocks(&store).unwrap(), vec![vacation]);
+    }
+
+    #[test]
+    fn 名前が空か範囲が逆向きなら追加しない() {
+        let mut store = TestBusyBlockStore::default();
+        let span = BusyBlockSpan::Timed {
+            start: local(22, 14, 0),
+            end: local(22, 15, 0),
+        };
+
+        let empty_name = add_busy_block(&mut store, " ", span).unwrap_err();
+        let reversed = add_busy_block(
+            &mut store,
+            "歯医者",
+            BusyBlockSpan::Timed {
+                start: local(22, 15, 0),
+                end: local(22, 14, 0),
+            },
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            empty_name,
+            BusyBlockError::Application(ApplicationError::InvalidInput { field: "name", .. })
+        ));
+        assert!(matches!(
+            reversed,
+            BusyBlockError::Application(ApplicationError::InvalidInput { field: "end", .. })
+        ));
+        assert!(store.blocks.is_empty());
+    }
+
+    #[test]
+    fn 終日は業務日開始から翌業務日開始までを0時で分けて登録する() {
+        let block = BusyBlock {
+            busy_block_id: Uuid::new_v4(),
+            name: "休暇".to_string(),
+            span: BusyBlockSpan::AllDay {
+                start_date: date(20),
+                end_date: date(21),
+            },
+        };
+        let mut free_time_manager = BusyIntervals(vec![]);
+
+        register_busy_blocks(&mut free_time_manager, &[block]).unwrap();
+
+        assert_eq!(
+            free_time_manager.0,
+            vec![
+                (local(20, 6, 0), local(21, 0, 0)),
+                (local(21, 0, 0), local(22, 0, 0)),
+                (local(22, 0, 0), local(22, 6, 0)),
+            ]
+        );
+    }
+}
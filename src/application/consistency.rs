@@ -0,0 +1,288 @@
+use crate::application::interface::TaskRepositoryTrait;
+use crate::entity::task::{Status, TaskHandle, TaskTreeError};
+use chrono::{DateTime, Duration, Local};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+// pending_untilを過ぎてからこの日数が経ってもpendingのままなら、戻し忘れとみなす
+pub const STALE_PENDING_DAYS: i64 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsistencyCode {
+    DuplicateTaskId,
+    DoneParentWithUndoneChildren,
+    ChildDeadlineAfterAncestor,
+    RepetitionWithoutTodo,
+    StalePendingUntil,
+    OrphanDirectory,
+    StaleStorageRevision,
+}
+
+impl ConsistencyCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DuplicateTaskId => "duplicate_task_id",
+            Self::DoneParentWithUndoneChildren => "done_parent_with_undone_children",
+            Self::ChildDeadlineAfterAncestor => "child_deadline_after_ancestor",
+            Self::RepetitionWithoutTodo => "repetition_without_todo",
+            Self::StalePendingUntil => "stale_pending_until",
+            Self::OrphanDirectory => "orphan_directory",
+            Self::StaleStorageRevision => "stale_storage_revision",
+        }
+    }
+}
+
+// locationはtask由来ならprojectからのtask名を/でつないだもの、保存先由来なら保存先からの相対path
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsistencyFinding {
+    pub code: ConsistencyCode,
+    pub location: String,
+    pub task_id: Option<Uuid>,
+    pub fixable: bool,
+    pub fixed: bool,
+}
+
+struct TaskConsistencyCheck {
+    now: DateTime<Local>,
+    fix: bool,
+    seen_task_ids: HashSet<Uuid>,
+    findings: Vec<ConsistencyFinding>,
+}
+
+// 全projectの木を検査する。fixなら、doneの親を開き直すことと古いpendingをtodoへ戻すことだけを行う。
+// 子から先に直すので、開き直した子を持つdoneの祖先も同じ回で開き直す
+pub fn check_task_consistency(
+    repository: &dyn TaskRepositoryTrait,
+    now: DateTime<Local>,
+    fix: bool,
+) -> Result<Vec<ConsistencyFinding>, TaskTreeError> {
+    let mut check = TaskConsistencyCheck {
+        now,
+        fix,
+        seen_task_ids: HashSet::new(),
+        findings: vec![],
+    };
+    for project in repository.get_all_projects() {
+        check.visit(project, &[], None)?;
+    }
+    Ok(check.findings)
+}
+
+impl TaskConsistencyCheck {
+    fn visit(
+        &mut self,
+        task: &TaskHandle,
+        parent_path: &[String],
+        ancestor_deadline: Option<DateTime<Local>>,
+    ) -> Result<(), TaskTreeError> {
+        let mut path = parent_path.to_vec();
+        path.push(task.get_name()?);
+        let task_id = task.get_id()?;
+        let status = task.get_orig_status()?;
+        if !self.seen_task_ids.insert(task_id) {
+            self.push(ConsistencyCode::DuplicateTaskId, &path, task_id, false);
+        }
+
+        let deadline = task.get_deadline_time_opt()?;
+        if status != Status::Done
+            && deadline
+                .zip(ancestor_deadline)
+                .is_some_and(|(deadline, ancestor_deadline)| deadline > ancestor_deadline)
+        {
+            self.push(
+                ConsistencyCode::ChildDeadlineAfterAncestor,
+                &path,
+                task_id,
+                false,
+            );
+        }
+        if status == Status::Pending
+            && task.get_pending_until()? + Duration::days(STALE_PENDING_DAYS) < self.now
+        {
+            if self.fix {
+                task.set_orig_status(Status::Todo)?;
+            }
+            self.push(ConsistencyCode::StalePendingUntil, &path, task_id, true);
+        }
+
+        // 繰り返しの親の〆切は各回の時刻の雛形なので、子の〆切とは比べない
+        let repeats = task.get_repetition_interval_days_opt()?.is_some();
+        let children_deadline = if repeats {
+            None
+        } else {
+            match (deadline, ancestor_deadline) {
+                (Some(deadline), Some(ancestor_deadline)) => Some(deadline.min(ancestor_deadline)),
+                (deadline, ancestor_deadline) => deadline.or(ancestor_deadline),
+            }
+        };
+        let children = task.get_children()?;
+        for child in &children {
+            self.visit(child, &path, children_deadline)?;
+        }
+
+        if repeats && status != Status::Done {
+            let mut has_todo_instance = false;
+            for child in &children {
+                has_todo_instance |= child.get_orig_status()? != Status::Done;
+            }
+            if !has_todo_instance {
+                self.push(
+                    ConsistencyCode::RepetitionWithoutTodo,
+                    &path,
+                    task_id,
+                    false,
+                );
+            }
+        }
+        if status == Status::Done && task.has_undone_children()? {
+            if self.fix {
+                task.set_orig_status(Status::Todo)?;
+            }
+            self.push(
+                ConsistencyCode::DoneParentWithUndoneChildren,
+                &path,
+                task_id,
+                true,
+            );
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, code: ConsistencyCode, path: &[String], task_id: Uuid, fixable: bool) {
+        self.findings.push(ConsistencyFinding {
+            code,
+            location: path.join("/"),
+            task_id: Some(task_id),
+            fixable,
+            fixed: fixable && self.fix,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{new_task_attr_at, new_task_handle_at, TestTaskRepository};
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap()
+    }
+
+    fn at(month: u32, day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, month, day, 19, 0, 0).unwrap()
+    }
+
+    fn codes(findings: &[ConsistencyFinding]) -> Vec<(&'static str, &str, bool)> {
+        findings
+            .iter()
+            .map(|finding| {
+                (
+                    finding.code.as_str(),
+                    finding.location.as_str(),
+                    finding.fixed,
+                )
+            })
+            .collect()
+    }
+
+    fn sample_project() -> TaskHandle {
+        let root = new_task_handle_at("旅行", now()).unwrap();
+        root.set_deadline_time_opt(Some(at(10, 25))).unwrap();
+        let ticket = root.create_child(new_task_attr_at("切符", now())).unwrap();
+        ticket.set_deadline_time_opt(Some(at(10, 30))).unwrap();
+        let hotel = root.create_child(new_task_attr_at("宿", now())).unwrap();
+        let room = hotel.create_child(new_task_attr_at("部屋", now())).unwrap();
+        room.create_child(new_task_attr_at("眺め", now())).unwrap();
+        room.set_orig_status(Status::Done).unwrap();
+        hotel.set_orig_status(Status::Done).unwrap();
+        let reply = root.create_child(new_task_attr_at("返事", now())).unwrap();
+        reply.set_pending_until(at(9, 1)).unwrap();
+        reply.set_orig_status(Status::Pending).unwrap();
+        let recent = root
+            .create_child(new_task_attr_at("最近の返事", now()))
+            .unwrap();
+        recent.set_pending_until(at(10, 1)).unwrap();
+        recent.set_orig_status(Status::Pending).unwrap();
+        let routine = root.create_child(new_task_attr_at("掃除", now())).unwrap();
+        routine.set_repetition_interval_days_opt(Some(7)).unwrap();
+        routine.set_deadline_time_opt(Some(at(10, 20))).unwrap();
+        let instance = routine
+            .create_child(new_task_attr_at("掃除(10/26)", now()))
+            .unwrap();
+        instance.set_deadline_time_opt(Some(at(10, 27))).unwrap();
+        instance.set_orig_status(Status::Done).unwrap();
+        root
+    }
+
+    #[test]
+    fn 木の食い違いを子から順にcode付きで報告し直さない() {
+        let root = sample_project();
+        let hotel_id = root.get_children().unwrap()[1].get_id().unwrap();
+        let repository = TestTaskRepository::new(vec![root], now());
+
+        let findings = check_task_consistency(&repository, now(), false).unwrap();
+
+        assert_eq!(
+            codes(&findings),
+            [
+                ("child_deadline_after_ancestor", "旅行/切符", false),
+                ("done_parent_with_undone_children", "旅行/宿/部屋", false),
+                ("stale_pending_until", "旅行/返事", false),
+                ("repetition_without_todo", "旅行/掃除", false),
+            ]
+        );
+        assert!(findings.iter().all(|finding| finding.fixable
+            == (finding.code != ConsistencyCode::ChildDeadlineAfterAncestor
+                && finding.code != ConsistencyCode::RepetitionWithoutTodo)));
+        let hotel = repository.get_by_id(hotel_id).unwrap().unwrap();
+        assert_eq!(hotel.get_orig_status().unwrap(), Status::Done);
+    }
+
+    #[test]
+    fn fixはdoneの祖先まで開き直し古いpendingだけをtodoへ戻す() {
+        let root = sample_project();
+        let children = root.get_children().unwrap();
+        let (hotel, reply, recent) = (
+            children[1].clone(),
+            children[2].clone(),
+            children[3].clone(),
+        );
+        let repository = TestTaskRepository::new(vec![root], now());
+
+        let findings = check_task_consistency(&repository, now(), true).unwrap();
+
+        assert_eq!(
+            codes(&findings),
+            [
+                ("child_deadline_after_ancestor", "旅行/切符", false),
+                ("done_parent_with_undone_children", "旅行/宿/部屋", true),
+                ("done_parent_with_undone_children", "旅行/宿", true),
+                ("stale_pending_until", "旅行/返事", true),
+                ("repetition_without_todo", "旅行/掃除", false),
+            ]
+        );
+        assert_eq!(hotel.get_orig_status().unwrap(), Status::Todo);
+        assert_eq!(reply.get_orig_status().unwrap(), Status::Todo);
+        assert_eq!(recent.get_orig_status().unwrap(), Status::Pending);
+        assert!(check_task_consistency(&repository, now(), false)
+            .unwrap()
+            .iter()
+            .all(|finding| !finding.fixable));
+    }
+
+    #[test]
+    fn 複数のprojectに同じidのtaskがあれば後に出た方を報告する() {
+        let first = new_task_handle_at("一", now()).unwrap();
+        let second = new_task_handle_at("二", now()).unwrap();
+        let mut duplicated = new_task_attr_at("複製", now());
+        duplicated.set_id(first.get_id().unwrap());
+        let child = second.create_child(duplicated).unwrap();
+        let repository = TestTaskRepository::new(vec![first, second], now());
+
+        let findings = check_task_consistency(&repository, now(), true).unwrap();
+
+        assert_eq!(codes(&findings), [("duplicate_task_id", "二/複製", false)]);
+        assert_eq!(findings[0].task_id, Some(child.get_id().unwrap()));
+    }
+}
@@ -0,0 +1,374 @@
+use crate::application::interface::{FreeTimeManagerTrait, TaskRepositoryTrait};
+use crate::application::schedule_use_case::calculate_remaining_work_seconds;
+use crate::application::task_use_case::ApplicationError;
+use crate::application::task_view::TaskView;
+use crate::entity::task::{Status, TaskHandle, TaskTreeError};
+use chrono::{DateTime, Duration, Local};
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// 空き時間を探す範囲の上限。これを超えても空きが足りない場合は、連続した時間として数える
+const MAX_SEARCH_DAYS: i64 = 366;
+
+// 未完了のtaskごとの最早・最遅の開始と終了。float_secondsは全体の終了を遅らせずに後ろへずらせる時間
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CriticalPathTaskView {
+    pub task: TaskView,
+    pub depth: usize,
+    pub remaining_work_seconds: i64,
+    pub earliest_start: DateTime<Local>,
+    pub earliest_finish: DateTime<Local>,
+    pub latest_start: DateTime<Local>,
+    pub latest_finish: DateTime<Local>,
+    pub float_seconds: i64,
+    pub critical: bool,
+}
+
+// tasksは根からの行きがけ順。critical_chainはprojectの終了を決めるtaskの列で、着手する順(葉から根)に並べる
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CriticalPathView {
+    pub root_id: Uuid,
+    pub project_finish: DateTime<Local>,
+    pub tasks: Vec<CriticalPathTaskView>,
+    pub critical_chain: Vec<Uuid>,
+}
+
+struct Timing {
+    remaining_minutes: i64,
+    earliest_start: DateTime<Local>,
+    earliest_finish: DateTime<Local>,
+    latest_start: DateTime<Local>,
+    latest_finish: DateTime<Local>,
+}
+
+// task_idを含むprojectの根から臨界経路を求める。
+// 親は未完了の子がすべて終わってから着手できるものとし(連の順序も入れ子で表される)、兄弟は並行して進められるものとする。
+// 所要時間は残りの見積で、空き時間の分だけ作業が進むものとして数える
+pub fn compute_critical_path(
+    repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    task_id: Uuid,
+) -> Result<CriticalPathView, ApplicationError> {
+    let task = repository
+        .get_by_id(task_id)
+        .map_err(ApplicationError::TaskTree)?
+        .ok_or(ApplicationError::TaskNotFound(task_id))?;
+    let root = task.root().map_err(ApplicationError::TaskTree)?;
+    if root.get_status().map_err(ApplicationError::TaskTree)? == Status::Done {
+        return Err(ApplicationError::InvalidInput {
+            field: "task_id",
+            reason: "project is already done",
+        });
+    }
+
+    let now = repository.get_last_synced_time();
+    let mut timings = HashMap::new();
+    forward_pass(&root, now, free_time_manager, &mut timings)
+        .map_err(ApplicationError::TaskTree)?;
+    let root_id = root.get_id().map_err(ApplicationError::TaskTree)?;
+    let project_finish = timings[&root_id].earliest_finish;
+    backward_pass(&root, project_finish, free_time_manager, &mut timings)
+        .map_err(ApplicationError::TaskTree)?;
+
+    let mut tasks = Vec::new();
+    collect_rows(&root, 0, &timings, &mut tasks).map_err(ApplicationError::TaskTree)?;
+    let critical_chain =
+        collect_critical_chain(&root, &timings).map_err(ApplicationError::TaskTree)?;
+    Ok(CriticalPathView {
+        root_id,
+        project_finish,
+        tasks,
+        critical_chain,
+    })
+}
+
+fn undone_children(task: &TaskHandle) -> Result<Vec<TaskHandle>, TaskTreeError> {
+    let mut children = Vec::new();
+    for child in task.get_children()? {
+        if child.get_status()? != Status::Done {
+            children.push(child);
+        }
+    }
+    Ok(children)
+}
+
+// 子の最早終了を待ってから着手し、空き時間で残りの見積を消化した時刻を最早終了とする
+fn forward_pass(
+    task: &TaskHandle,
+    now: DateTime<Local>,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    timings: &mut HashMap<Uuid, Timing>,
+) -> Result<DateTime<Local>, TaskTreeError> {
+    let mut ready_time = now.max(task.first_available_time()?);
+    for child in undone_children(task)? {
+        ready_time = ready_time.max(forward_pass(&child, now, free_time_manager, timings)?);
+    }
+
+    let remaining_minutes = (calculate_remaining_work_seconds(task)? + 59) / 60;
+    let earliest_finish = advance_by_free_minutes(free_time_manager, ready_time, remaining_minutes);
+    // 着手できる時刻が空いていなければ、実際に作業を始めるのは次の空き時間から
+    let earliest_start =
+        retreat_by_free_minutes(free_time_manager, earliest_finish, remaining_minutes)
+            .max(ready_time);
+    timings.insert(
+        task.get_id()?,
+        Timing {
+            remaining_minutes,
+            earliest_start,
+            earliest_finish,
+            latest_start: earliest_start,
+            latest_finish: earliest_finish,
+        },
+    );
+    Ok(earliest_finish)
+}
+
+// 親の最遅開始までに終わればよいものとして、根から順に最遅の開始と終了を決める
+fn backward_pass(
+    task: &TaskHandle,
+    latest_finish: DateTime<Local>,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    timings: &mut HashMap<Uuid, Timing>,
+) -> Result<(), TaskTreeError> {
+    let timing = timings
+        .get_mut(&task.get_id()?)
+        .expect("forward pass visits every undone task");
+    timing.latest_finish = latest_finish;
+    timing.latest_start =
+        retreat_by_free_minutes(free_time_manager, latest_finish, timing.remaining_minutes)
+            .max(timing.earliest_start);
+    let latest_start = timing.latest_start;
+    for child in undone_children(task)? {
+        backward_pass(&child, latest_start, free_time_manager, timings)?;
+    }
+    Ok(())
+}
+
+fn collect_rows(
+    task: &TaskHandle,
+    depth: usize,
+    timings: &HashMap<Uuid, Timing>,
+    rows: &mut Vec<CriticalPathTaskView>,
+) -> Result<(), TaskTreeError> {
+    let timing = &timings[&task.get_id()?];
+    let float_seconds = (timing.latest_start - timing.earliest_start).num_seconds();
+    rows.push(CriticalPathTaskView {
+        task: TaskView::try_from(task)?,
+        depth,
+        remaining_work_seconds: calculate_remaining_work_seconds(task)?,
+        earliest_start: timing.earliest_start,
+        earliest_finish: timing.earliest_finish,
+        latest_start: timing.latest_start,
+        latest_finish: timing.latest_finish,
+        float_seconds,
+        critical: float_seconds == 0,
+    });
+    for child in undone_children(task)? {
+        collect_rows(&child, depth + 1, timings, rows)?;
+    }
+    Ok(())
+}
+
+// 根から、余裕のない子のうち最も遅く終わるものをたどって葉まで下りる
+fn collect_critical_chain(
+    root: &TaskHandle,
+    timings: &HashMap<Uuid, Timing>,
+) -> Result<Vec<Uuid>, TaskTreeError> {
+    let mut chain = vec![root.get_id()?];
+    let mut current = root.clone();
+    loop {
+        let mut next_opt: Option<(DateTime<Local>, TaskHandle)> = None;
+        for child in undone_children(&current)? {
+            let timing = &timings[&child.get_id()?];
+            if timing.latest_start != timing.earliest_start {
+                continue;
+            }
+            if next_opt
+                .as_ref()
+                .is_none_or(|(finish, _)| timing.earliest_finish > *finish)
+            {
+                next_opt = Some((timing.earliest_finish, child));
+            }
+        }
+        let Some((_, next)) = next_opt else {
+            break;
+        };
+        chain.push(next.get_id()?);
+        current = next;
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+// startから空き時間をminutes分使い切る最も早い時刻
+fn advance_by_free_minutes(
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    start: DateTime<Local>,
+    minutes: i64,
+) -> DateTime<Local> {
+    if minutes <= 0 {
+        return start;
+    }
+    let mut window_start = start;
+    let mut remaining_minutes = minutes;
+    for _ in 0..MAX_SEARCH_DAYS {
+        let window_end = window_start + Duration::days(1);
+        let free_minutes = free_time_manager.get_free_minutes(&window_start, &window_end);
+        if free_minutes >= remaining_minutes {
+            let offset = smallest_window_minutes(remaining_minutes, |offset| {
+                free_time_manager
+                    .get_free_minutes(&window_start, &(window_start + Duration::minutes(offset)))
+            });
+            return window_start + Duration::minutes(offset);
+        }
+        remaining_minutes -= free_minutes;
+        window_start = window_end;
+    }
+    start + Duration::minutes(minutes)
+}
+
+// endまでに空き時間をminutes分使える最も遅い開始時刻
+fn retreat_by_free_minutes(
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    end: DateTime<Local>,
+    minutes: i64,
+) -> DateTime<Local> {
+    if minutes <= 0 {
+        return end;
+    }
+    let mut window_end = end;
+    let mut remaining_minutes = minutes;
+    for _ in 0..MAX_SEARCH_DAYS {
+        let window_start = window_end - Duration::days(1);
+        let free_minutes = free_time_manager.get_free_minutes(&window_start, &window_end);
+        if free_minutes >= remaining_minutes {
+            let offset = smallest_window_minutes(remaining_minutes, |offset| {
+                free_time_manager
+                    .get_free_minutes(&(window_end - Duration::minutes(offset)), &window_end)
+            });
+            return window_end - Duration::minutes(offset);
+        }
+        remaining_minutes -= free_minutes;
+        window_end = window_start;
+    }
+    end - Duration::minutes(minutes)
+}
+
+// 1日の枠の中で、空き時間がminutes以上になる最短の幅を二分探索する
+fn smallest_window_minutes(minutes: i64, mut free_minutes_in: impl FnMut(i64) -> i64) -> i64 {
+    let mut low = minutes;
+    let mut high = 24 * 60;
+    while low < high {
+        let middle = (low + high) / 2;
+        if free_minutes_in(middle) >= minutes {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    low
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        new_task_attr_at, new_task_handle, BusyIntervals, TestTaskRepository,
+    };
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap()
+    }
+
+    fn child(parent: &TaskHandle, name: &str, minutes: i64) -> TaskHandle {
+        let task = parent.create_child(new_task_attr_at(name, now())).unwrap();
+        task.set_estimated_work_seconds(minutes * 60).unwrap();
+        task
+    }
+
+    fn project() -> TaskHandle {
+        let project = new_task_handle("project").unwrap();
+        project.sync_clock(now()).unwrap();
+        project.set_start_time(now()).unwrap();
+        project.set_estimated_work_seconds(0).unwrap();
+        project
+    }
+
+    #[test]
+    fn 連の入れ子をたどって臨界経路と余裕を求める() {
+        let project = project();
+        // 連と同じく、後の手順が前の手順を子に持つ
+        let review = child(&project, "レビュー", 30);
+        let draft = child(&review, "下書き", 120);
+        let slides = child(&project, "スライド", 60);
+        let done = child(&project, "完了済み", 600);
+        done.set_orig_status(Status::Done).unwrap();
+        let repository = TestTaskRepository::new(vec![project.clone()], now());
+        let mut free_time_manager = BusyIntervals(vec![]);
+
+        let view =
+            compute_critical_path(&repository, &mut free_time_manager, draft.get_id().unwrap())
+                .unwrap();
+
+        assert_eq!(view.root_id, project.get_id().unwrap());
+        assert_eq!(view.project_finish, now() + Duration::minutes(150));
+        assert_eq!(
+            view.tasks
+                .iter()
+                .map(|row| (row.task.name.as_str(), row.depth, row.float_seconds))
+                .collect::<Vec<_>>(),
+            vec![
+                ("project", 0, 0),
+                ("レビュー", 1, 0),
+                ("下書き", 2, 0),
+                ("スライド", 1, 90 * 60),
+            ]
+        );
+        assert_eq!(view.tasks[1].earliest_start, now() + Duration::minutes(120));
+        assert_eq!(view.tasks[3].task.id, slides.get_id().unwrap());
+        assert_eq!(view.tasks[3].latest_start, now() + Duration::minutes(90));
+        assert_eq!(view.tasks[3].latest_finish, now() + Duration::minutes(150));
+        assert_eq!(
+            view.critical_chain,
+            vec![
+                draft.get_id().unwrap(),
+                review.get_id().unwrap(),
+                project.get_id().unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn 埋まっている時間を飛ばして最早終了と余裕を数える() {
+        let project = project();
+        let long = child(&project, "長い作業", 120);
+        let short = child(&project, "短い作業", 30);
+        let repository = TestTaskRepository::new(vec![project], now());
+        let mut free_time_manager = BusyIntervals(vec![(
+            now() + Duration::hours(1),
+            now() + Duration::hours(3),
+        )]);
+
+        let view =
+            compute_critical_path(&repository, &mut free_time_manager, long.get_id().unwrap())
+                .unwrap();
+
+        assert_eq!(view.project_finish, now() + Duration::hours(4));
+        let short_row = view
+            .tasks
+            .iter()
+            .find(|row| row.task.id == short.get_id().unwrap())
+            .unwrap();
+        assert_eq!(short_row.earliest_finish, now() + Duration::minutes(30));
+        // 埋まっている2時間は余裕に含めるが、最遅開始は空き時間の中に置く
+        assert_eq!(short_row.latest_start, now() + Duration::minutes(210));
+        assert_eq!(short_row.float_seconds, 210 * 60);
+        assert!(!short_row.critical);
+        assert_eq!(
+            view.critical_chain,
+            vec![long.get_id().unwrap(), view.root_id]
+        );
+    }
+}
@@ -182,6 +182,8 @@ mod tests {
         ) -> Result<(), super::super::interface::BusyTimeSlotLoadError> {
             Ok(())
         }
+
+        fn clear_registered_busy_time_slots(&mut self) {}
     }
 
     #[test]
@@ -5,9 +5,10 @@ use super::daily_capacity::{
 };
 use super::interface::{FreeTimeManagerTrait, TaskRepositoryTrait};
 use super::schedule_use_case::{
-    get_schedule, get_schedule_with_first_available_time_overrides, ScheduledTaskView,
+    get_back_to_back_schedule, get_schedule_with_first_available_time_overrides, ScheduledTaskView,
 };
 use super::task_use_case::ApplicationError;
+use crate::entity::allowed_window::AllowedWindow;
 use crate::entity::datetime::BusinessDateTimePolicy;
 use crate::entity::task::Status;
 use chrono::{DateTime, Duration, Local, NaiveDate};
@@ -34,6 +35,7 @@ pub enum UnresolvedReason {
     ExceedsDailyCapacity,
     OwnDeadline,
     RelatedDeadline,
+    OutsideAllowedWindow,
     Other,
 }
 
@@ -73,6 +75,7 @@ struct FlattenCandidate {
     total_work_seconds: i64,
     is_on_other_side: bool,
     all_work_is_on_overload_date: bool,
+    allowed_window: Option<AllowedWindow>,
 }
 
 pub fn flatten_tasks(
@@ -121,7 +124,7 @@ pub fn flatten_tasks_with_end_of_day_offset_minutes(
         })
         .collect::<Result<HashMap<_, _>, ApplicationError>>()?;
     let maximum_daily_capacity = capacities.values().copied().max().unwrap_or(0);
-    let initial_schedule = get_schedule(repository)?;
+    let initial_schedule = get_back_to_back_schedule(repository)?;
     let original_task_details = collect_original_task_details(&initial_schedule)?;
     let mut schedule = initial_schedule;
     let mut overrides = HashMap::<Uuid, DateTime<Local>>::new();
@@ -153,8 +156,12 @@ pub fn flatten_tasks_with_end_of_day_offset_minutes(
                 },
             )?
         };
-        let mut candidates =
-            collect_candidates(&schedule, overload_date, end_of_day_offset_minutes)?;
+        let mut candidates = collect_candidates(
+            repository,
+            &schedule,
+            overload_date,
+            end_of_day_offset_minutes,
+        )?;
         sort_candidates_for_deferral(&mut candidates);
 
         let mut accepted = None;
@@ -165,6 +172,17 @@ pub fn flatten_tasks_with_end_of_day_offset_minutes(
                 continue;
             }
             let target_datetime = try_subjective_date_start(target_date)?;
+            // 移す先の日に許可された時間帯が足りなければ、延期しても収まらない
+            if let Some(allowed_window) = &candidate.allowed_window {
+                let target_date_end =
+                    try_subjective_date_end(target_date, end_of_day_offset_minutes)?;
+                if allowed_window.allowed_seconds_between(target_datetime, target_date_end)
+                    < candidate.total_work_seconds
+                {
+                    rejected.push((candidate, UnresolvedReason::OutsideAllowedWindow));
+                    continue;
+                }
+            }
             if effective_pending_until(
                 target_datetime,
                 candidate.deadline_time,
@@ -274,6 +292,7 @@ fn collect_original_task_details(
 }
 
 fn collect_candidates(
+    repository: &dyn TaskRepositoryTrait,
     schedule: &[ScheduledTaskView],
     overload_date: NaiveDate,
     end_of_day_offset_minutes: i64,
@@ -316,6 +335,13 @@ fn collect_candidates(
             segments.iter().zip(segment_dates).all(|(segment, date)| {
                 date == overload_date && segment.scheduled_end <= overload_date_end
             });
+        let allowed_window = repository
+            .get_by_id(first.task.id)
+            .map_err(ApplicationError::TaskTree)?
+            .map(|task| task.get_effective_allowed_window_opt())
+            .transpose()
+            .map_err(ApplicationError::TaskTree)?
+            .flatten();
         candidates.push(FlattenCandidate {
             task_id: first.task.id,
             name: first.task.name.clone(),
@@ -327,6 +353,7 @@ fn collect_candidates(
             total_work_seconds: first.total_work_seconds,
             is_on_other_side: first.task.is_on_other_side,
             all_work_is_on_overload_date,
+            allowed_window,
         });
     }
     Ok(candidates)
@@ -422,6 +449,7 @@ fn summarize_unresolved_overload(
         UnresolvedReason::ExceedsDailyCapacity,
         UnresolvedReason::OwnDeadline,
         UnresolvedReason::RelatedDeadline,
+        UnresolvedReason::OutsideAllowedWindow,
         UnresolvedReason::Other,
     ] {
         let matching = rejected
@@ -485,8 +513,10 @@ fn add_scheduled_work_seconds_by_date(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_support::{new_task_handle, TestFreeTimeManager, TestTaskRepository};
-    use chrono::FixedOffset;
+    use crate::test_support::{
+        new_task_handle, new_task_handle_at, TestFreeTimeManager, TestTaskRepository,
+    };
+    use chrono::{FixedOffset, TimeZone};
 
     #[test]
     fn flatten_tasksはoperation時刻のsubjective_date計算不能を伝搬しtaskを変更しない() {
@@ -518,4 +548,39 @@ mod tests {
             original_revision
         );
     }
+
+    #[test]
+    fn flatten_tasksは移す先の日に許可された時間帯がないtaskを枠外として報告する() {
+        // 2026/10/19は月曜日
+        let now = Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap();
+        let projects = ["月曜の電話1", "月曜の電話2"]
+            .into_iter()
+            .map(|name| {
+                let task = new_task_handle_at(name, now).unwrap();
+                task.set_estimated_work_seconds(40 * 60).unwrap();
+                task.set_allowed_window_opt(Some(AllowedWindow::parse("月").unwrap()))
+                    .unwrap();
+                task
+            })
+            .collect::<Vec<_>>();
+        let repository = TestTaskRepository::new(projects, now);
+        let mut free_time_manager = TestFreeTimeManager::new(60);
+
+        let actual = flatten_tasks(&repository, &mut free_time_manager).unwrap();
+
+        assert!(actual.flattened_tasks.is_empty());
+        assert_eq!(actual.unresolved_overloads.len(), 1);
+        assert_eq!(
+            actual.unresolved_overloads[0].date,
+            NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()
+        );
+        assert_eq!(
+            actual.unresolved_overloads[0]
+                .reasons
+                .iter()
+                .map(|summary| (summary.reason, summary.task_count))
+                .collect::<Vec<_>>(),
+            vec![(UnresolvedReason::OutsideAllowedWindow, 2)]
+        );
+    }
 }
@@ -0,0 +1,412 @@
+use crate::application::daily_capacity::{
+    try_subjective_date, try_subjective_date_end, END_OF_DAY_OFFSET_MINUTES,
+};
+use crate::application::interface::TaskRepositoryTrait;
+use crate::application::task_use_case::ApplicationError;
+use crate::entity::task::{ProjectCategory, TaskHandle, TaskTreeError};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate};
+use serde::Serialize;
+
+// 分布を求める時間(秒)。percentileは最近傍順位法で求める
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FlowDurationStats {
+    pub min_seconds: i64,
+    pub p50_seconds: i64,
+    pub p85_seconds: i64,
+    pub max_seconds: i64,
+    pub mean_seconds: i64,
+}
+
+// 完了数と、lead time(作成→完了)・cycle time(着手可能→完了)の分布。対象がなければNone
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct FlowDistributionView {
+    pub completed: usize,
+    pub lead_time: Option<FlowDurationStats>,
+    pub cycle_time: Option<FlowDurationStats>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum FlowTaskLevel {
+    #[serde(rename = "root")]
+    Root,
+    #[serde(rename = "leaf")]
+    Leaf,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FlowLevelDistributionView {
+    pub level: FlowTaskLevel,
+    #[serde(flatten)]
+    pub distribution: FlowDistributionView,
+}
+
+// categoryはprojectのrootのもの。Noneは未分類
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FlowCategoryDistributionView {
+    pub category: Option<ProjectCategory>,
+    #[serde(flatten)]
+    pub distribution: FlowDistributionView,
+}
+
+// 月曜始まりの週ごとの完了数。範囲の端の週は範囲内の完了だけを数える
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WeeklyThroughputView {
+    pub week_start: NaiveDate,
+    pub completed: usize,
+    pub completed_leaves: usize,
+}
+
+// その日の終わりの時点で着手可能になっていて未完了の葉とproject(root)の数
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WorkInProgressView {
+    pub date: NaiveDate,
+    pub leaves: usize,
+    pub projects: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FlowMetricsView {
+    pub from: NaiveDate,
+    pub until: NaiveDate,
+    pub overall: FlowDistributionView,
+    pub by_level: Vec<FlowLevelDistributionView>,
+    pub by_category: Vec<FlowCategoryDistributionView>,
+    pub weekly_throughput: Vec<WeeklyThroughputView>,
+    pub work_in_progress: Vec<WorkInProgressView>,
+}
+
+const CATEGORY_ORDER: [Option<ProjectCategory>; 6] = [
+    Some(ProjectCategory::Earning),
+    Some(ProjectCategory::Sustaining),
+    Some(ProjectCategory::Recovery),
+    Some(ProjectCategory::Investment),
+    Some(ProjectCategory::Consumption),
+    None,
+];
+
+struct FlowTask {
+    is_root: bool,
+    is_leaf: bool,
+    category: Option<ProjectCategory>,
+    create_time: DateTime<Local>,
+    start_time: DateTime<Local>,
+    end_time_opt: Option<DateTime<Local>>,
+}
+
+#[derive(Default)]
+struct DurationSamples {
+    completed: usize,
+    lead_seconds: Vec<i64>,
+    cycle_seconds: Vec<i64>,
+}
+
+// 主観日付でfrom〜untilに完了したtaskの流れを集計する。完了はend_timeの有無で判定し、
+// rootかつ葉のtaskは両方の区分に数える。時刻の前後が逆転したtaskは分布から除く
+pub fn calculate_flow_metrics(
+    repository: &dyn TaskRepositoryTrait,
+    from: NaiveDate,
+    until: NaiveDate,
+) -> Result<FlowMetricsView, ApplicationError> {
+    let mut tasks = Vec::new();
+    for project in repository.get_all_projects() {
+        collect_flow_tasks(project, &mut tasks).map_err(ApplicationError::TaskTree)?;
+    }
+
+    let mut overall = DurationSamples::default();
+    let mut roots = DurationSamples::default();
+    let mut leaves = DurationSamples::default();
+    let mut categories: Vec<DurationSamples> = CATEGORY_ORDER
+        .iter()
+        .map(|_| DurationSamples::default())
+        .collect();
+    let mut weekly_throughput = Vec::new();
+    let mut week_start = monday_of(from);
+    while week_start <= until {
+        weekly_throughput.push(WeeklyThroughputView {
+            week_start,
+            completed: 0,
+            completed_leaves: 0,
+        });
+        week_start = next_date(week_start, 7)?;
+    }
+
+    for task in &tasks {
+        let Some(end_time) = task.end_time_opt else {
+            continue;
+        };
+        let end_date = try_subjective_date(end_time)?;
+        if end_date < from || until < end_date {
+            continue;
+        }
+        let lead_seconds = (end_time - task.create_time).num_seconds();
+        let cycle_seconds = (end_time - task.start_time).num_seconds();
+        overall.push(lead_seconds, cycle_seconds);
+        if task.is_root {
+            roots.push(lead_seconds, cycle_seconds);
+        }
+        if task.is_leaf {
+            leaves.push(lead_seconds, cycle_seconds);
+        }
+        let category_index = CATEGORY_ORDER
+            .iter()
+            .position(|category| *category == task.category)
+            .expect("CATEGORY_ORDER covers every category");
+        categories[category_index].push(lead_seconds, cycle_seconds);
+
+        let week_index = ((monday_of(end_date) - monday_of(from)).num_days() / 7) as usize;
+        let week = &mut weekly_throughput[week_index];
+        week.completed += 1;
+        if task.is_leaf {
+            week.completed_leaves += 1;
+        }
+    }
+
+    let mut work_in_progress = Vec::new();
+    let mut date = from;
+    loop {
+        let day_end = try_subjective_date_end(date, END_OF_DAY_OFFSET_MINUTES)?;
+        let in_progress = |task: &&FlowTask| {
+            task.create_time < day_end
+                && task.start_time < day_end
+                && task.end_time_opt.is_none_or(|end_time| day_end <= end_time)
+        };
+        work_in_progress.push(WorkInProgressView {
+            date,
+            leaves: tasks
+                .iter()
+                .filter(in_progress)
+                .filter(|task| task.is_leaf)
+                .count(),
+            projects: tasks
+                .iter()
+                .filter(in_progress)
+                .filter(|task| task.is_root)
+                .count(),
+        });
+        if date == until {
+            break;
+        }
+        date = next_date(date, 1)?;
+    }
+
+    Ok(FlowMetricsView {
+        from,
+        until,
+        overall: overall.into_view(),
+        by_level: vec![
+            FlowLevelDistributionView {
+                level: FlowTaskLevel::Root,
+                distribution: roots.into_view(),
+            },
+            FlowLevelDistributionView {
+                level: FlowTaskLevel::Leaf,
+                distribution: leaves.into_view(),
+            },
+        ],
+        by_category: CATEGORY_ORDER
+            .into_iter()
+            .zip(categories)
+            .filter(|(_, samples)| samples.completed > 0)
+            .map(|(category, samples)| FlowCategoryDistributionView {
+                category,
+                distribution: samples.into_view(),
+            })
+            .collect(),
+        weekly_throughput,
+        work_in_progress,
+    })
+}
+
+fn collect_flow_tasks(task: &TaskHandle, tasks: &mut Vec<FlowTask>) -> Result<(), TaskTreeError> {
+    let children = task.get_children()?;
+    tasks.push(FlowTask {
+        is_root: task.parent()?.is_none(),
+        is_leaf: children.is_empty(),
+        category: task.get_project_category_opt()?,
+        create_time: task.get_create_time()?,
+        start_time: task.get_start_time()?,
+        end_time_opt: task.get_end_time_opt()?,
+    });
+    for child in &children {
+        collect_flow_tasks(child, tasks)?;
+    }
+    Ok(())
+}
+
+fn monday_of(date: NaiveDate) -> NaiveDate {
+    date - Days::new(u64::from(date.weekday().num_days_from_monday()))
+}
+
+fn next_date(date: NaiveDate, days: u64) -> Result<NaiveDate, ApplicationError> {
+    date.checked_add_days(Days::new(days))
+        .ok_or(ApplicationError::SubjectiveDateStartOutOfRange { date })
+}
+
+impl DurationSamples {
+    fn push(&mut self, lead_seconds: i64, cycle_seconds: i64) {
+        self.completed += 1;
+        if lead_seconds >= 0 {
+            self.lead_seconds.push(lead_seconds);
+        }
+        if cycle_seconds >= 0 {
+            self.cycle_seconds.push(cycle_seconds);
+        }
+    }
+
+    fn into_view(self) -> FlowDistributionView {
+        FlowDistributionView {
+            completed: self.completed,
+            lead_time: duration_stats(self.lead_seconds),
+            cycle_time: duration_stats(self.cycle_seconds),
+        }
+    }
+}
+
+fn duration_stats(mut seconds: Vec<i64>) -> Option<FlowDurationStats> {
+    if seconds.is_empty() {
+        return None;
+    }
+    seconds.sort_unstable();
+    let percentile = |percent: usize| seconds[(seconds.len() * percent).div_ceil(100) - 1];
+    let total: i128 = seconds.iter().map(|value| i128::from(*value)).sum();
+    Some(FlowDurationStats {
+        min_seconds: seconds[0],
+        p50_seconds: percentile(50),
+        p85_seconds: percentile(85),
+        max_seconds: seconds[seconds.len() - 1],
+        mean_seconds: (total / seconds.len() as i128) as i64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{new_task_attr_at, new_task_handle_at, TestTaskRepository};
+    use chrono::{Duration, TimeZone};
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn finish(task: &TaskHandle, start_time: DateTime<Local>, end_time: DateTime<Local>) {
+        task.set_start_time(start_time).unwrap();
+        task.set_end_time_opt(Some(end_time)).unwrap();
+    }
+
+    #[test]
+    fn 期間内に完了したtaskのlead_timeとcycle_timeを区分ごとに集計する() {
+        let project = new_task_handle_at("稼ぐproject", at(1, 9)).unwrap();
+        project
+            .set_project_category_opt(Some(ProjectCategory::Earning))
+            .unwrap();
+        project.set_start_time(at(1, 9)).unwrap();
+        let first = project
+            .create_child(new_task_attr_at("作業1", at(5, 9)))
+            .unwrap();
+        finish(&first, at(6, 9), at(6, 12));
+        let second = project
+            .create_child(new_task_attr_at("作業2", at(5, 9)))
+            .unwrap();
+        finish(&second, at(7, 9), at(8, 9));
+        let outside = project
+            .create_child(new_task_attr_at("範囲外", at(5, 9)))
+            .unwrap();
+        finish(&outside, at(5, 9), at(20, 9));
+        let single = new_task_handle_at("単発", at(12, 9)).unwrap();
+        finish(&single, at(12, 9), at(12, 10));
+        let repository = TestTaskRepository::new(vec![project, single], at(19, 9));
+
+        let metrics = calculate_flow_metrics(&repository, date(5), date(18)).unwrap();
+
+        assert_eq!(metrics.overall.completed, 3);
+        assert_eq!(
+            metrics.overall.lead_time,
+            Some(FlowDurationStats {
+                min_seconds: 60 * 60,
+                p50_seconds: 27 * 60 * 60,
+                p85_seconds: 72 * 60 * 60,
+                max_seconds: 72 * 60 * 60,
+                mean_seconds: 100 * 60 * 60 / 3,
+            })
+        );
+        assert_eq!(
+            metrics
+                .by_level
+                .iter()
+                .map(|row| (row.level, row.distribution.completed))
+                .collect::<Vec<_>>(),
+            vec![(FlowTaskLevel::Root, 1), (FlowTaskLevel::Leaf, 3)]
+        );
+        assert_eq!(
+            metrics
+                .by_category
+                .iter()
+                .map(|row| (
+                    row.category,
+                    row.distribution.completed,
+                    row.distribution.cycle_time.as_ref().unwrap().max_seconds
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some(ProjectCategory::Earning), 2, 24 * 60 * 60),
+                (None, 1, 60 * 60),
+            ]
+        );
+    }
+
+    #[test]
+    fn 週ごとの完了数と日ごとの仕掛り数を空の週や日も含めて返す() {
+        let project = new_task_handle_at("project", at(5, 9)).unwrap();
+        project.set_start_time(at(5, 9)).unwrap();
+        let done = project
+            .create_child(new_task_attr_at("完了", at(5, 9)))
+            .unwrap();
+        finish(&done, at(5, 9), at(7, 12));
+        let waiting = project
+            .create_child(new_task_attr_at("着手待ち", at(5, 9)))
+            .unwrap();
+        waiting.set_start_time(at(8, 9)).unwrap();
+        let repository = TestTaskRepository::new(vec![project], at(19, 9));
+
+        let metrics = calculate_flow_metrics(&repository, date(6), date(13)).unwrap();
+
+        assert_eq!(
+            metrics
+                .weekly_throughput
+                .iter()
+                .map(|week| (week.week_start, week.completed, week.completed_leaves))
+                .collect::<Vec<_>>(),
+            vec![(date(5), 1, 1), (date(12), 0, 0)]
+        );
+        assert_eq!(
+            metrics
+                .work_in_progress
+                .iter()
+                .take(3)
+                .map(|day| (day.date, day.leaves, day.projects))
+                .collect::<Vec<_>>(),
+            vec![(date(6), 1, 1), (date(7), 0, 1), (date(8), 1, 1)]
+        );
+        assert_eq!(metrics.work_in_progress.len(), 8);
+    }
+
+    #[test]
+    fn 完了が着手可能より前のtaskはcycle_timeの分布から除く() {
+        let task = new_task_handle_at("前倒し", at(5, 9)).unwrap();
+        finish(&task, at(9, 9), at(6, 9));
+        let repository = TestTaskRepository::new(vec![task], at(19, 9));
+
+        let metrics = calculate_flow_metrics(&repository, date(5), date(11)).unwrap();
+
+        assert_eq!(metrics.overall.completed, 1);
+        assert_eq!(
+            metrics.overall.lead_time.map(|stats| stats.max_seconds),
+            Some(Duration::days(1).num_seconds())
+        );
+        assert_eq!(metrics.overall.cycle_time, None);
+    }
+}
@@ -0,0 +1,312 @@
+use crate::application::daily_capacity::try_subjective_date_start;
+use crate::application::interface::FreeTimeManagerTrait;
+use crate::application::schedule_use_case::ScheduledTaskView;
+use crate::application::task_use_case::ApplicationError;
+use crate::application::task_view::TaskView;
+use chrono::{DateTime, Days, Duration, Local, NaiveDate};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+// 予定の区間を1本のlaneに収まるように切った片。同じtaskの片には通し番号を振る
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GanttSegmentView {
+    pub task: TaskView,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub segment_index: usize,
+    pub segment_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GanttBusySlotView {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GanttDeadlineView {
+    pub task_id: Uuid,
+    pub name: String,
+    pub deadline_time: DateTime<Local>,
+}
+
+// 主観日の開始から翌日の開始までを1本のlaneとする
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GanttLaneView {
+    pub date: NaiveDate,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub segments: Vec<GanttSegmentView>,
+    pub busy_slots: Vec<GanttBusySlotView>,
+    pub deadlines: Vec<GanttDeadlineView>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GanttChartView {
+    pub generated_at: DateTime<Local>,
+    pub lanes: Vec<GanttLaneView>,
+}
+
+// get_scheduleの予定を、fromからdays日分の主観日ごとのlaneに並べる。
+// 作業時間のない予定は描かず、〆切は予定に現れたtaskのものだけを印として置く。
+// 埋まっている時間は空き時間の管理から1分単位で読み取る
+pub fn build_gantt_chart(
+    schedule: &[ScheduledTaskView],
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    from: NaiveDate,
+    days: u64,
+    generated_at: DateTime<Local>,
+) -> Result<GanttChartView, ApplicationError> {
+    let mut lanes = Vec::new();
+    for offset in 0..days {
+        let date =
+            from.checked_add_days(Days::new(offset))
+                .ok_or(ApplicationError::InvalidInput {
+                    field: "days",
+                    reason: "date out of range",
+                })?;
+        let next_date = date.succ_opt().ok_or(ApplicationError::InvalidInput {
+            field: "days",
+            reason: "date out of range",
+        })?;
+        let start = try_subjective_date_start(date)?;
+        let end = try_subjective_date_start(next_date)?;
+        lanes.push(GanttLaneView {
+            date,
+            start,
+            end,
+            segments: collect_lane_segments(schedule, start, end),
+            busy_slots: collect_busy_slots(free_time_manager, start, end),
+            deadlines: collect_lane_deadlines(schedule, start, end),
+        });
+    }
+
+    number_segments(&mut lanes);
+    Ok(GanttChartView {
+        generated_at,
+        lanes,
+    })
+}
+
+fn collect_lane_segments(
+    schedule: &[ScheduledTaskView],
+    lane_start: DateTime<Local>,
+    lane_end: DateTime<Local>,
+) -> Vec<GanttSegmentView> {
+    schedule
+        .iter()
+        .filter(|scheduled| scheduled.scheduled_work_seconds > 0)
+        .filter(|scheduled| {
+            scheduled.scheduled_start < lane_end && lane_start < scheduled.scheduled_end
+        })
+        .map(|scheduled| GanttSegmentView {
+            task: scheduled.task.clone(),
+            start: scheduled.scheduled_start.max(lane_start),
+            end: scheduled.scheduled_end.min(lane_end),
+            segment_index: 0,
+            segment_count: 0,
+        })
+        .collect()
+}
+
+fn collect_lane_deadlines(
+    schedule: &[ScheduledTaskView],
+    lane_start: DateTime<Local>,
+    lane_end: DateTime<Local>,
+) -> Vec<GanttDeadlineView> {
+    let mut seen = HashSet::new();
+    let mut deadlines = schedule
+        .iter()
+        .filter_map(|scheduled| {
+            let deadline_time = scheduled.task.deadline_time?;
+            (lane_start <= deadline_time
+                && deadline_time < lane_end
+                && seen.insert(scheduled.task.id))
+            .then(|| GanttDeadlineView {
+                task_id: scheduled.task.id,
+                name: scheduled.task.name.clone(),
+                deadline_time,
+            })
+        })
+        .collect::<Vec<_>>();
+    deadlines.sort_by_key(|deadline| (deadline.deadline_time, deadline.task_id));
+    deadlines
+}
+
+// 1分ずつ空きを調べ、続いて埋まっている分をまとめて1つの区間にする
+fn collect_busy_slots(
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    lane_start: DateTime<Local>,
+    lane_end: DateTime<Local>,
+) -> Vec<GanttBusySlotView> {
+    let mut busy_slots: Vec<GanttBusySlotView> = Vec::new();
+    let mut minute_start = lane_start;
+    while minute_start < lane_end {
+        let minute_end = (minute_start + Duration::minutes(1)).min(lane_end);
+        if free_time_manager.get_free_minutes(&minute_start, &minute_end) == 0 {
+            match busy_slots.last_mut() {
+                Some(slot) if slot.end == minute_start => slot.end = minute_end,
+                _ => busy_slots.push(GanttBusySlotView {
+                    start: minute_start,
+                    end: minute_end,
+                }),
+            }
+        }
+        minute_start = minute_end;
+    }
+    busy_slots
+}
+
+// 分割された予定もlaneをまたいだ予定も、同じtaskの片として開始順に番号を振る
+fn number_segments(lanes: &mut [GanttLaneView]) {
+    let mut segment_counts: HashMap<Uuid, usize> = HashMap::new();
+    for segment in lanes.iter().flat_map(|lane| &lane.segments) {
+        *segment_counts.entry(segment.task.id).or_default() += 1;
+    }
+
+    let mut next_indexes: HashMap<Uuid, usize> = HashMap::new();
+    for segment in lanes.iter_mut().flat_map(|lane| &mut lane.segments) {
+        let next_index = next_indexes.entry(segment.task.id).or_default();
+        *next_index += 1;
+        segment.segment_index = *next_index;
+        segment.segment_count = segment_counts[&segment.task.id];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::daily_capacity::try_subjective_date;
+    use crate::application::schedule_use_case::get_back_to_back_schedule;
+    use crate::test_support::{
+        new_task_attr_at, new_task_handle_at, TestFreeTimeManager, TestTaskRepository,
+    };
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap()
+    }
+
+    fn scheduled(
+        task: &TaskView,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+    ) -> ScheduledTaskView {
+        ScheduledTaskView {
+            task: task.clone(),
+            first_available_time: start,
+            scheduled_start: start,
+            scheduled_end: end,
+            scheduled_work_seconds: (end - start).num_seconds(),
+            total_work_seconds: (end - start).num_seconds(),
+            rank: 0,
+        }
+    }
+
+    #[test]
+    fn 予定を主観日のlaneに切り分けて同じtaskの片に番号を振る() {
+        let task = new_task_handle_at("長い作業", now()).unwrap();
+        task.set_deadline_time_opt(Some(now() + Duration::hours(20)))
+            .unwrap();
+        let view = TaskView::try_from(&task).unwrap();
+        let today = try_subjective_date(now()).unwrap();
+        let tomorrow_start = try_subjective_date_start(today.succ_opt().unwrap()).unwrap();
+        let schedule = vec![
+            scheduled(&view, now(), now() + Duration::hours(1)),
+            scheduled(
+                &view,
+                tomorrow_start - Duration::minutes(30),
+                tomorrow_start + Duration::minutes(30),
+            ),
+            scheduled(&view, now(), now()),
+        ];
+        let mut free_time_manager = TestFreeTimeManager::new(24 * 60);
+
+        let chart = build_gantt_chart(&schedule, &mut free_time_manager, today, 2, now()).unwrap();
+
+        assert_eq!(chart.lanes.len(), 2);
+        assert_eq!(chart.lanes[0].end, chart.lanes[1].start);
+        assert_eq!(
+            chart
+                .lanes
+                .iter()
+                .map(|lane| lane
+                    .segments
+                    .iter()
+                    .map(|segment| (segment.start, segment.end, segment.segment_index))
+                    .collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![
+                vec![
+                    (now(), now() + Duration::hours(1), 1),
+                    (tomorrow_start - Duration::minutes(30), tomorrow_start, 2),
+                ],
+                vec![(tomorrow_start, tomorrow_start + Duration::minutes(30), 3)],
+            ]
+        );
+        assert!(chart
+            .lanes
+            .iter()
+            .flat_map(|lane| &lane.segments)
+            .all(|segment| segment.segment_count == 3));
+        let deadlines = chart
+            .lanes
+            .iter()
+            .map(|lane| lane.deadlines.len())
+            .collect::<Vec<_>>();
+        assert_eq!(deadlines.iter().sum::<usize>(), 1);
+    }
+
+    #[test]
+    fn 埋まっている時間を連続した区間にまとめる() {
+        let today = try_subjective_date(now()).unwrap();
+        let mut free_time_manager = TestFreeTimeManager::with_blocked_interval(
+            24 * 60,
+            now() + Duration::hours(3),
+            now() + Duration::minutes(210),
+        );
+
+        let chart = build_gantt_chart(&[], &mut free_time_manager, today, 1, now()).unwrap();
+
+        assert_eq!(
+            chart.lanes[0].busy_slots,
+            vec![GanttBusySlotView {
+                start: now() + Duration::hours(3),
+                end: now() + Duration::minutes(210),
+            }]
+        );
+        assert!(chart.lanes[0].segments.is_empty());
+    }
+
+    #[test]
+    fn get_scheduleの予定から作業時間のある片だけを描く() {
+        let project = new_task_handle_at("project", now()).unwrap();
+        project.set_estimated_work_seconds(0).unwrap();
+        let child = project
+            .create_child(new_task_attr_at("下書き", now()))
+            .unwrap();
+        child.set_estimated_work_seconds(30 * 60).unwrap();
+        let repository = TestTaskRepository::new(vec![project], now());
+        let schedule = get_back_to_back_schedule(&repository).unwrap();
+        let mut free_time_manager = TestFreeTimeManager::new(24 * 60);
+
+        let chart = build_gantt_chart(
+            &schedule,
+            &mut free_time_manager,
+            try_subjective_date(now()).unwrap(),
+            1,
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            chart.lanes[0]
+                .segments
+                .iter()
+                .map(|segment| segment.task.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["下書き"]
+        );
+    }
+}
@@ -1,5 +1,12 @@
+use crate::application::load_history::LoadSnapshot;
+use crate::application::project_note::ProjectNote;
+use crate::application::storage_history::{StorageRevision, TaskRevisionNode};
+use crate::application::task_use_case::TaskPeriodField;
+use crate::application::trash::TrashEntry;
+use crate::entity::busy_block::BusyBlock;
 use crate::entity::task::{TaskHandle, TaskTreeError};
 use chrono::{DateTime, Local};
+use std::collections::{BTreeMap, HashSet};
 use std::error::Error;
 use std::fmt;
 use std::path::{Path, PathBuf};
@@ -81,7 +88,26 @@ pub trait TaskRepositoryTrait {
         recent_threshold: DateTime<Local>,
     ) -> Result<Option<Uuid>, TaskTreeError>;
     fn get_by_id(&self, id: Uuid) -> Result<Option<TaskHandle>, TaskTreeError>;
+    // 時刻がfrom以上until未満のtaskのidを索引で引く。索引を持たない保存先や、未保存の変更があって
+    // 索引が古い場合はNoneを返し、呼び出し側がmemory上のtaskで絞り込む
+    fn find_task_ids_in_period(
+        &self,
+        _field: TaskPeriodField,
+        _from: DateTime<Local>,
+        _until: DateTime<Local>,
+    ) -> Option<HashSet<Uuid>> {
+        None
+    }
     fn start_new_project(&mut self, root_task: TaskHandle) -> Result<(), TaskTreeError>;
+    // projectを取り除き、次のsaveで保存先からも消す。保存先でのprojectのkeyを返し、
+    // root_task_idがprojectのrootでなければNoneを返す
+    fn remove_project(&mut self, root_task_id: Uuid) -> Result<Option<String>, TaskTreeError>;
+    // 取り除いたprojectを元のkeyで戻す。keyが他のprojectで使われていれば別のkeyにして、使ったkeyを返す
+    fn restore_project(
+        &mut self,
+        project_key: &str,
+        root_task: TaskHandle,
+    ) -> Result<String, TaskTreeError>;
 }
 
 pub trait FreeTimeManagerTrait {
@@ -96,6 +122,8 @@ pub trait FreeTimeManagerTrait {
         &mut self,
         busy_time_slots_file_path: &str,
     ) -> Result<(), BusyTimeSlotLoadError>;
+    // register_busy_time_slotで入れた区間だけを捨てる。毎週の予定表は残す
+    fn clear_registered_busy_time_slots(&mut self);
 }
 
 #[derive(Debug)]
@@ -165,3 +193,260 @@ impl fmt::Display for BusyTimeSlotRegistrationError {
     }
 }
 impl Error for BusyTimeSlotRegistrationError {}
+
+// 日付を決めた埋まっている時間を保持する。読み込みの誤りは週ごとの予定表と同じ形で返す
+pub trait BusyBlockStoreTrait {
+    fn list_busy_blocks(&self) -> Result<Vec<BusyBlock>, BusyTimeSlotLoadError>;
+    fn save_busy_blocks(&mut self, blocks: &[BusyBlock]) -> Result<(), BusyBlockStoreError>;
+}
+
+#[derive(Debug)]
+pub struct BusyBlockStoreError {
+    path: PathBuf,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl BusyBlockStoreError {
+    pub fn new<E>(path: impl Into<PathBuf>, source: E) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Self {
+            path: path.into(),
+            source: Box::new(source),
+        }
+    }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Display for BusyBlockStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to save busy blocks at {}: {}",
+            self.path.display(),
+            self.source
+        )
+    }
+}
+impl Error for BusyBlockStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+// project noteはfile名で区別する。書き込みは同じ名前のnoteを置き換える
+pub trait ProjectNoteStoreTrait {
+    fn list_project_notes(&self) -> Result<Vec<ProjectNote>, ProjectNoteStoreError>;
+    fn write_project_note(&mut self, note: &ProjectNote) -> Result<(), ProjectNoteStoreError>;
+}
+
+#[derive(Debug)]
+pub struct ProjectNoteStoreError {
+    path: PathBuf,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl ProjectNoteStoreError {
+    pub fn new<E>(path: impl Into<PathBuf>, source: E) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Self {
+            path: path.into(),
+            source: Box::new(source),
+        }
+    }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Display for ProjectNoteStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to access project note at {}: {}",
+            self.path.display(),
+            self.source
+        )
+    }
+}
+impl Error for ProjectNoteStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+pub trait LoadHistoryStoreTrait {
+    fn list_load_snapshots(&self) -> Result<Vec<LoadSnapshot>, LoadHistoryStoreError>;
+    fn append_load_snapshot(
+        &mut self,
+        snapshot: &LoadSnapshot,
+    ) -> Result<(), LoadHistoryStoreError>;
+}
+
+#[derive(Debug)]
+pub struct LoadHistoryStoreError {
+    path: PathBuf,
+    line_number: Option<usize>,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl LoadHistoryStoreError {
+    pub fn new<E>(path: impl Into<PathBuf>, line_number: Option<usize>, source: E) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Self {
+            path: path.into(),
+            line_number,
+            source: Box::new(source),
+        }
+    }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    pub fn line_number(&self) -> Option<usize> {
+        self.line_number
+    }
+}
+
+impl fmt::Display for LoadHistoryStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to access load history at {}",
+            self.path.display()
+        )?;
+        if let Some(line_number) = self.line_number {
+            write!(f, ":{line_number}")?;
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+impl Error for LoadHistoryStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+// 捨てたtaskの部分木を、元の親と位置とともに保持する。一覧は捨てた順に返す
+pub trait TrashStoreTrait {
+    fn list_trash_entries(&self) -> Result<Vec<TrashEntry>, TrashStoreError>;
+    fn append_trash_entry(
+        &mut self,
+        entry: &TrashEntry,
+        subtree: &TaskHandle,
+    ) -> Result<(), TrashStoreError>;
+    fn read_trash_subtree(
+        &self,
+        trash_id: Uuid,
+        now: DateTime<Local>,
+    ) -> Result<Option<TaskHandle>, TrashStoreError>;
+    fn remove_trash_entry(&mut self, trash_id: Uuid) -> Result<(), TrashStoreError>;
+    // 捨てたprojectの保存先に空のdirectoryしか残っていなければ消し、消したかを返す
+    fn remove_empty_project_directory(
+        &mut self,
+        project_key: &str,
+    ) -> Result<bool, TrashStoreError>;
+    // 他processで計時中のtask。作業中の部分木を捨てないために使う
+    fn open_work_task_ids(&self) -> Result<Vec<Uuid>, TrashStoreError>;
+}
+
+#[derive(Debug)]
+pub struct TrashStoreError {
+    path: PathBuf,
+    line_number: Option<usize>,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl TrashStoreError {
+    pub fn new<E>(path: impl Into<PathBuf>, line_number: Option<usize>, source: E) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Self {
+            path: path.into(),
+            line_number,
+            source: Box::new(source),
+        }
+    }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    pub fn line_number(&self) -> Option<usize> {
+        self.line_number
+    }
+}
+
+impl fmt::Display for TrashStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to access trash at {}", self.path.display())?;
+        if let Some(line_number) = self.line_number {
+            write!(f, ":{line_number}")?;
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+impl Error for TrashStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+// 保存ごとのproject.yamlの版を保持する。projectは保存先からの相対path("<dir>/project.yaml")で表す
+pub trait StorageHistoryTrait {
+    fn list_revisions(&self) -> Result<Vec<StorageRevision>, StorageHistoryError>;
+    // revisionがNoneなら現在の保存先の内容を返す
+    fn read_revision_tasks(
+        &self,
+        revision: Option<Uuid>,
+    ) -> Result<BTreeMap<String, Vec<TaskRevisionNode>>, StorageHistoryError>;
+    // projectがNoneなら保存先全体を戻す。戻した結果も新しい版として記録する
+    fn restore_revision(
+        &mut self,
+        revision: Uuid,
+        project: Option<&str>,
+        now: DateTime<Local>,
+    ) -> Result<StorageRevision, StorageHistoryError>;
+}
+
+#[derive(Debug)]
+pub struct StorageHistoryError {
+    path: PathBuf,
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl StorageHistoryError {
+    pub fn new<E>(path: impl Into<PathBuf>, source: E) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Self {
+            path: path.into(),
+            source: Box::new(source),
+        }
+    }
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Display for StorageHistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to access storage history at {}: {}",
+            self.path.display(),
+            self.source
+        )
+    }
+}
+impl Error for StorageHistoryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
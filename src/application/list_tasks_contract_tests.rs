@@ -2,7 +2,9 @@ use super::interface::{TaskRepositoryError, TaskRepositoryTrait};
 use super::task_use_case::{
     list_tasks, ApplicationError, ListTasksFilter, TaskPeriodField, TaskPeriodFilter,
 };
+use crate::entity::datetime::BusinessDateTimePolicy;
 use crate::entity::task::{ProjectCategory, Status, TaskHandle};
+use crate::test_support::BusyIntervals;
 use chrono::{DateTime, Duration, Local, TimeZone};
 use uuid::Uuid;
 
@@ -82,6 +84,33 @@ impl TaskRepositoryTrait for TestTaskRepository {
         self.projects.push(root_task);
         Ok(())
     }
+
+    fn remove_project(
+        &mut self,
+        root_task_id: Uuid,
+    ) -> Result<Option<String>, crate::entity::task::TaskTreeError> {
+        let Some(index) = self
+            .projects
+            .iter()
+            .position(|project| project.get_id() == Ok(root_task_id))
+        else {
+            return Ok(None);
+        };
+        self.projects.remove(index).get_name().map(Some)
+    }
+
+    fn restore_project(
+        &mut self,
+        project_key: &str,
+        root_task: TaskHandle,
+    ) -> Result<String, crate::entity::task::TaskTreeError> {
+        self.projects.push(root_task);
+        Ok(project_key.to_string())
+    }
+}
+
+fn policy() -> BusinessDateTimePolicy {
+    BusinessDateTimePolicy::new(30)
 }
 
 fn fixed_now() -> DateTime<Local> {
@@ -112,7 +141,13 @@ fn list_tasks_filterなしならdoneを含む全taskをpre_orderで返す() {
     second_root.sync_clock(now).unwrap();
     let repository = TestTaskRepository::new(vec![first_root.clone(), second_root.clone()], now);
 
-    let actual = list_tasks(&repository, no_filter()).unwrap();
+    let actual = list_tasks(
+        &repository,
+        &mut BusyIntervals(vec![]),
+        &policy(),
+        no_filter(),
+    )
+    .unwrap();
 
     assert_eq!(
         actual.iter().map(|task| task.id).collect::<Vec<_>>(),
@@ -162,7 +197,7 @@ fn list_tasks_statusは期限経過を反映した実効状態をorで絞る() {
     let mut filter = no_filter();
     filter.statuses = vec![Status::Todo, Status::Done];
 
-    let actual = list_tasks(&repository, filter).unwrap();
+    let actual = list_tasks(&repository, &mut BusyIntervals(vec![]), &policy(), filter).unwrap();
 
     assert_eq!(
         actual.iter().map(|task| task.id).collect::<Vec<_>>(),
@@ -202,7 +237,7 @@ fn list_tasks_category内はorでstatusとはandで絞る() {
     filter.statuses = vec![Status::Todo];
     filter.categories = vec![Some(ProjectCategory::Investment), None];
 
-    let actual = list_tasks(&repository, filter).unwrap();
+    let actual = list_tasks(&repository, &mut BusyIntervals(vec![]), &policy(), filter).unwrap();
 
     assert_eq!(
         actual.iter().map(|task| task.id).collect::<Vec<_>>(),
@@ -247,6 +282,8 @@ fn list_tasks_period_status_categoryの全filterをandで絞る() {
 
     let actual = list_tasks(
         &repository,
+        &mut BusyIntervals(vec![]),
+        &policy(),
         ListTasksFilter {
             period: Some(TaskPeriodFilter {
                 field: TaskPeriodField::CreatedAt,
@@ -309,6 +346,8 @@ fn list_tasks_created_deadline_completedの期間を半開区間で絞る() {
     ] {
         let actual = list_tasks(
             &repository,
+            &mut BusyIntervals(vec![]),
+            &policy(),
             ListTasksFilter {
                 period: Some(TaskPeriodFilter {
                     field,
@@ -357,6 +396,8 @@ fn list_tasks_periodは指定された時刻fieldだけを参照する() {
     ] {
         let actual = list_tasks(
             &repository,
+            &mut BusyIntervals(vec![]),
+            &policy(),
             ListTasksFilter {
                 period: Some(TaskPeriodFilter {
                     field,
@@ -398,6 +439,8 @@ fn list_tasks_scheduled_start期間は分割taskを重複させない() {
 
     let actual = list_tasks(
         &repository,
+        &mut BusyIntervals(vec![]),
+        &policy(),
         ListTasksFilter {
             period: Some(TaskPeriodFilter {
                 field: TaskPeriodField::ScheduledStart,
@@ -420,6 +463,8 @@ fn list_tasks_scheduled_start期間は分割taskを重複させない() {
 
     let second_segment_only = list_tasks(
         &repository,
+        &mut BusyIntervals(vec![]),
+        &policy(),
         ListTasksFilter {
             period: Some(TaskPeriodFilter {
                 field: TaskPeriodField::ScheduledStart,
@@ -441,6 +486,8 @@ fn list_tasks_scheduled_start期間は分割taskを重複させない() {
 
     let until_exclusive = list_tasks(
         &repository,
+        &mut BusyIntervals(vec![]),
+        &policy(),
         ListTasksFilter {
             period: Some(TaskPeriodFilter {
                 field: TaskPeriodField::ScheduledStart,
@@ -466,6 +513,8 @@ fn list_tasks_fromがuntil以上ならinvalid_inputを返す() {
     for from in [now, now + Duration::seconds(1)] {
         let actual = list_tasks(
             &repository,
+            &mut BusyIntervals(vec![]),
+            &policy(),
             ListTasksFilter {
                 period: Some(TaskPeriodFilter {
                     field: TaskPeriodField::CreatedAt,
@@ -0,0 +1,305 @@
+use super::daily_capacity::{
+    calculate_daily_leeway_seconds,
+    calculate_free_time_minutes_for_subjective_date_with_end_of_day_offset_minutes,
+    try_subjective_date,
+};
+use super::interface::{
+    FreeTimeManagerTrait, LoadHistoryStoreError, LoadHistoryStoreTrait, TaskRepositoryTrait,
+};
+use super::schedule_use_case::get_schedule;
+use super::task_use_case::ApplicationError;
+use crate::entity::datetime::BusinessDateTimePolicy;
+use crate::entity::task::{Status, TaskHandle};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StatusCounts {
+    pub todo: usize,
+    pub pending: usize,
+    pub done: usize,
+}
+
+// 1主観日につき1件だけ記録する負荷の記録。
+// rhoなどは空き時間が0の日に発散するため、計算できない値はNoneとして保存する
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LoadSnapshot {
+    pub date: NaiveDate,
+    pub recorded_at: DateTime<Local>,
+    pub free_minutes: i64,
+    pub scheduled_work_seconds: i64,
+    pub repetitive_work_seconds: i64,
+    pub rho: Option<f64>,
+    pub non_repetitive_rho: Option<f64>,
+    pub lq: Option<f64>,
+    pub leeway_seconds: i64,
+    pub overflow_seconds: i64,
+    pub status_counts: StatusCounts,
+}
+
+#[derive(Debug)]
+pub enum LoadHistoryError {
+    Store(LoadHistoryStoreError),
+    Application(ApplicationError),
+}
+
+impl fmt::Display for LoadHistoryError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Store(error) => error.fmt(formatter),
+            Self::Application(error) => error.fmt(formatter),
+        }
+    }
+}
+
+impl Error for LoadHistoryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Store(error) => Some(error),
+            Self::Application(error) => Some(error),
+        }
+    }
+}
+
+impl From<LoadHistoryStoreError> for LoadHistoryError {
+    fn from(error: LoadHistoryStoreError) -> Self {
+        Self::Store(error)
+    }
+}
+
+impl From<ApplicationError> for LoadHistoryError {
+    fn from(error: ApplicationError) -> Self {
+        Self::Application(error)
+    }
+}
+
+pub fn build_load_snapshot(
+    date: NaiveDate,
+    recorded_at: DateTime<Local>,
+    free_minutes: i64,
+    scheduled_work_seconds: i64,
+    repetitive_work_seconds: i64,
+    status_counts: StatusCounts,
+) -> LoadSnapshot {
+    let free_seconds = free_minutes * 60;
+    let rho = (free_seconds > 0).then(|| scheduled_work_seconds as f64 / free_seconds as f64);
+    let non_repetitive_free_seconds = free_seconds - repetitive_work_seconds;
+    let non_repetitive_rho = (non_repetitive_free_seconds > 0).then(|| {
+        (scheduled_work_seconds - repetitive_work_seconds) as f64
+            / non_repetitive_free_seconds as f64
+    });
+
+    LoadSnapshot {
+        date,
+        recorded_at,
+        free_minutes,
+        scheduled_work_seconds,
+        repetitive_work_seconds,
+        rho,
+        non_repetitive_rho,
+        lq: rho.filter(|rho| *rho < 1.0).map(|rho| rho / (1.0 - rho)),
+        leeway_seconds: calculate_daily_leeway_seconds(
+            free_minutes,
+            repetitive_work_seconds,
+            scheduled_work_seconds,
+        ),
+        overflow_seconds: (scheduled_work_seconds - free_seconds).max(0),
+        status_counts,
+    }
+}
+
+// 予定作業時間は、get_scheduleと同じく埋まっている時間と夜を避けた予定のうち今日始まる分を数える
+pub fn capture_load_snapshot(
+    repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
+) -> Result<LoadSnapshot, ApplicationError> {
+    let now = repository.get_last_synced_time();
+    let date = try_subjective_date(now)?;
+
+    let mut scheduled_work_seconds = 0;
+    let mut repetitive_work_seconds = 0;
+    for scheduled in get_schedule(repository, free_time_manager, policy)? {
+        if try_subjective_date(scheduled.scheduled_start)? != date {
+            continue;
+        }
+        scheduled_work_seconds += scheduled.scheduled_work_seconds;
+
+        let task = repository
+            .get_by_id(scheduled.task.id)
+            .map_err(ApplicationError::TaskTree)?
+            .ok_or(ApplicationError::TaskNotFound(scheduled.task.id))?;
+        if task
+            .get_inherited_repetition_interval_days_opt()
+            .map_err(ApplicationError::TaskTree)?
+            .is_some()
+        {
+            repetitive_work_seconds += scheduled.scheduled_work_seconds;
+        }
+    }
+
+    let free_minutes =
+        calculate_free_time_minutes_for_subjective_date_with_end_of_day_offset_minutes(
+            &date,
+            now,
+            free_time_manager,
+            policy.end_of_day_offset_minutes(),
+        )?;
+
+    let mut status_counts = StatusCounts::default();
+    for project_root in repository.get_all_projects() {
+        count_statuses(project_root, &mut status_counts)?;
+    }
+
+    Ok(build_load_snapshot(
+        date,
+        now,
+        free_minutes,
+        scheduled_work_seconds,
+        repetitive_work_seconds,
+        status_counts,
+    ))
+}
+
+fn count_statuses(task: &TaskHandle, counts: &mut StatusCounts) -> Result<(), ApplicationError> {
+    match task.get_status().map_err(ApplicationError::TaskTree)? {
+        Status::Todo => counts.todo += 1,
+        Status::Pending => counts.pending += 1,
+        Status::Done => counts.done += 1,
+    }
+    for child in task.get_children().map_err(ApplicationError::TaskTree)? {
+        count_statuses(&child, counts)?;
+    }
+    Ok(())
+}
+
+// その主観日の最初の実行でだけ記録し、記録済みならcaptureを呼ばずにNoneを返す
+pub fn record_load_snapshot_if_absent(
+    store: &mut dyn LoadHistoryStoreTrait,
+    date: NaiveDate,
+    capture: impl FnOnce() -> Result<LoadSnapshot, ApplicationError>,
+) -> Result<Option<LoadSnapshot>, LoadHistoryError> {
+    if store
+        .list_load_snapshots()?
+        .iter()
+        .any(|snapshot| snapshot.date == date)
+    {
+        return Ok(None);
+    }
+
+    let snapshot = capture()?;
+    store.append_load_snapshot(&snapshot)?;
+    Ok(Some(snapshot))
+}
+
+// from以上until以下の記録を日付順に返す。同じ日付が複数あれば最初の記録を採用する
+pub fn list_load_history(
+    store: &dyn LoadHistoryStoreTrait,
+    from: NaiveDate,
+    until: NaiveDate,
+) -> Result<Vec<LoadSnapshot>, LoadHistoryStoreError> {
+    let mut snapshots = store
+        .list_load_snapshots()?
+        .into_iter()
+        .filter(|snapshot| from <= snapshot.date && snapshot.date <= until)
+        .collect::<Vec<_>>();
+    snapshots.sort_by_key(|snapshot| snapshot.date);
+    snapshots.dedup_by_key(|snapshot| snapshot.date);
+    Ok(snapshots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[derive(Default)]
+    struct MemoryLoadHistoryStore {
+        snapshots: Vec<LoadSnapshot>,
+    }
+
+    impl LoadHistoryStoreTrait for MemoryLoadHistoryStore {
+        fn list_load_snapshots(&self) -> Result<Vec<LoadSnapshot>, LoadHistoryStoreError> {
+            Ok(self.snapshots.clone())
+        }
+
+        fn append_load_snapshot(
+            &mut self,
+            snapshot: &LoadSnapshot,
+        ) -> Result<(), LoadHistoryStoreError> {
+            self.snapshots.push(snapshot.clone());
+            Ok(())
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn snapshot(day: u32) -> LoadSnapshot {
+        build_load_snapshot(
+            date(day),
+            Local.with_ymd_and_hms(2026, 10, day, 9, 0, 0).unwrap(),
+            10 * 60,
+            6 * 3600,
+            2 * 3600,
+            StatusCounts::default(),
+        )
+    }
+
+    #[test]
+    fn build_load_snapshotはrhoと余差と超過を日次容量と同じ定義で計算する() {
+        let actual = snapshot(18);
+
+        assert_eq!(actual.rho, Some(0.6));
+        assert_eq!(actual.non_repetitive_rho, Some(0.5));
+        assert_eq!(actual.lq, Some(0.6 / 0.4));
+        assert_eq!(actual.leeway_seconds, 96 * 60);
+        assert_eq!(actual.overflow_seconds, 0);
+    }
+
+    #[test]
+    fn build_load_snapshotは空き時間がなければrhoを記録せず全量を超過にする() {
+        let actual = build_load_snapshot(
+            date(18),
+            Local.with_ymd_and_hms(2026, 10, 18, 23, 0, 0).unwrap(),
+            0,
+            3600,
+            0,
+            StatusCounts::default(),
+        );
+
+        assert_eq!(actual.rho, None);
+        assert_eq!(actual.non_repetitive_rho, None);
+        assert_eq!(actual.lq, None);
+        assert_eq!(actual.leeway_seconds, 0);
+        assert_eq!(actual.overflow_seconds, 3600);
+    }
+
+    #[test]
+    fn record_load_snapshot_if_absentは同じ主観日の2回目を記録しない() {
+        let mut store = MemoryLoadHistoryStore::default();
+
+        let first = record_load_snapshot_if_absent(&mut store, date(18), || Ok(snapshot(18)));
+        let second = record_load_snapshot_if_absent(&mut store, date(18), || {
+            panic!("記録済みの日はcaptureしない")
+        });
+
+        assert_eq!(first.unwrap(), Some(snapshot(18)));
+        assert_eq!(second.unwrap(), None);
+        assert_eq!(store.snapshots, vec![snapshot(18)]);
+    }
+
+    #[test]
+    fn list_load_historyは範囲内の記録を日付順に重複なく返す() {
+        let store = MemoryLoadHistoryStore {
+            snapshots: vec![snapshot(18), snapshot(16), snapshot(10), snapshot(16)],
+        };
+
+        let actual = list_load_history(&store, date(11), date(18)).unwrap();
+
+        assert_eq!(actual, vec![snapshot(16), snapshot(18)]);
+    }
+}
@@ -6,7 +6,7 @@ use super::daily_capacity::{
 };
 use super::interface::{FreeTimeManagerTrait, TaskRepositoryTrait};
 use super::schedule_use_case::{
-    get_schedule, get_schedule_with_task_first_available_time, ScheduledTaskView,
+    get_back_to_back_schedule, get_schedule_with_task_first_available_time, ScheduledTaskView,
 };
 use super::task_use_case::ApplicationError;
 use crate::entity::task::Status;
@@ -96,7 +96,7 @@ pub fn pack_tasks_with_end_of_day_offset_minutes(
     let mut result = PackResult::default();
     for candidate in candidates {
         let mut packed_task_opt = None;
-        let current_planned_start_opt = get_schedule(repository)?
+        let current_planned_start_opt = get_back_to_back_schedule(repository)?
             .into_iter()
             .find(|scheduled| scheduled.task.id == candidate.task_id)
             .map(|scheduled| scheduled.scheduled_start);
@@ -259,7 +259,7 @@ fn collect_candidates(
     repository: &dyn TaskRepositoryTrait,
     target_dates: &[NaiveDate],
 ) -> Result<Vec<PackCandidate>, ApplicationError> {
-    let schedule = get_schedule(repository)?;
+    let schedule = get_back_to_back_schedule(repository)?;
     let mut seen_ids = HashSet::new();
     let mut candidates = Vec::new();
     for scheduled in schedule {
@@ -300,7 +300,7 @@ fn calculate_daily_leeway(
     let mut total_work_seconds = HashMap::<NaiveDate, i64>::new();
     let mut repetitive_work_seconds = HashMap::<NaiveDate, i64>::new();
 
-    for scheduled in get_schedule(repository)? {
+    for scheduled in get_back_to_back_schedule(repository)? {
         let date = try_subjective_date(scheduled.scheduled_start)?;
         if !target_dates.contains(&date) {
             continue;
@@ -382,6 +382,7 @@ fn placement_fits_target_day(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::entity::allowed_window::AllowedWindow;
     use crate::entity::task::{Status, TaskHandle};
     use crate::test_support::{TestFreeTimeManager, TestTaskRepository};
     use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate, TimeZone};
@@ -465,6 +466,29 @@ mod tests {
         assert!(high.get_pending_until().unwrap() < now + Duration::days(10));
     }
 
+    #[test]
+    fn pack_tasks_許可された時間帯のある日の枠の始まりに詰める() {
+        // 2026/08/11は火曜日
+        let now = fixed_now();
+        let task = pending_task("木曜の打合せ", now, now + Duration::days(10), 30, 5);
+        task.set_allowed_window_opt(Some(AllowedWindow::parse("木 10:00-11:00").unwrap()))
+            .unwrap();
+        let repository = TestTaskRepository::new(vec![task.clone()], now);
+        let mut free_time_manager = TestFreeTimeManager::new(120);
+
+        let actual = pack_tasks(&repository, &mut free_time_manager).unwrap();
+
+        assert_eq!(actual.packed_tasks.len(), 1);
+        assert_eq!(
+            actual.packed_tasks[0].target_date,
+            NaiveDate::from_ymd_opt(2026, 8, 13).unwrap()
+        );
+        assert_eq!(
+            task.get_pending_until().unwrap(),
+            Local.with_ymd_and_hms(2026, 8, 13, 10, 0, 0).unwrap()
+        );
+    }
+
     #[test]
     fn pack_tasks_同じ優先度では現在の予定日時が早い順に詰める() {
         let now = fixed_now();
@@ -0,0 +1,656 @@
+use crate::application::interface::{
+    ProjectNoteStoreError, ProjectNoteStoreTrait, TaskRepositoryTrait,
+};
+use crate::application::task_tree::{get_task_tree_without_schedule, TaskTreeNodeView};
+use crate::application::task_use_case::{
+    complete_task, ApplicationError, CompleteTaskInput, TaskFactory,
+};
+use crate::entity::task::{Status, TaskTreeError};
+use chrono::{DateTime, Local};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+pub const TASK_SECTION_START: &str = "<!-- schronu:tasks -->";
+pub const TASK_SECTION_END: &str = "<!-- /schronu:tasks -->";
+
+// 生成する一覧の外とfrontmatterのほかの項目は、Obsidianで書いたまま残す
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectNote {
+    pub file_name: String,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectNoteConflictKind {
+    // 同じschronu_idのnoteが複数ある
+    DuplicateProject,
+    // 一覧に手で足した行など、task idの印がない
+    MissingTaskId,
+    // 印のtaskがもうない
+    UnknownTask,
+    // Schronuで完了したtaskのcheckが外された
+    Reopened,
+    // 未完了の子があるtaskにcheckが付いた
+    HasUndoneChildren,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectNoteConflict {
+    pub file_name: String,
+    pub line: Option<usize>,
+    pub task_name: Option<String>,
+    pub kind: ProjectNoteConflictKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NoteCompletion {
+    pub task_id: Uuid,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProjectNoteSyncReport {
+    pub completed: Vec<NoteCompletion>,
+    pub written_file_names: Vec<String>,
+    pub conflicts: Vec<ProjectNoteConflict>,
+}
+
+#[derive(Debug)]
+pub enum ProjectNoteSyncError {
+    Store(ProjectNoteStoreError),
+    Application(ApplicationError),
+}
+
+impl fmt::Display for ProjectNoteSyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Store(error) => write!(f, "{error}"),
+            Self::Application(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for ProjectNoteSyncError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Store(error) => Some(error),
+            Self::Application(error) => Some(error),
+        }
+    }
+}
+
+impl From<ProjectNoteStoreError> for ProjectNoteSyncError {
+    fn from(error: ProjectNoteStoreError) -> Self {
+        Self::Store(error)
+    }
+}
+
+impl From<ApplicationError> for ProjectNoteSyncError {
+    fn from(error: ApplicationError) -> Self {
+        Self::Application(error)
+    }
+}
+
+// noteで付いたcheckを完了として取り込んでから、未完了のprojectごとにnoteを書き直す。
+// 食い違いのあったnoteは上書きせず、報告だけする
+pub fn sync_project_notes(
+    repository: &mut dyn TaskRepositoryTrait,
+    store: &mut dyn ProjectNoteStoreTrait,
+    finished_at: DateTime<Local>,
+    factory: &mut TaskFactory<'_>,
+) -> Result<ProjectNoteSyncReport, ProjectNoteSyncError> {
+    let notes = store.list_project_notes()?;
+    let parsed_notes = notes
+        .iter()
+        .map(|note| parse_note(&note.text))
+        .collect::<Vec<_>>();
+    let mut note_counts: HashMap<Uuid, usize> = HashMap::new();
+    for project_id in parsed_notes.iter().filter_map(|parsed| parsed.project_id) {
+        *note_counts.entry(project_id).or_default() += 1;
+    }
+
+    let mut report = ProjectNoteSyncReport::default();
+    let mut notes_by_project: HashMap<Uuid, &ProjectNote> = HashMap::new();
+    let mut conflicted_projects = HashSet::new();
+    for (note, parsed) in notes.iter().zip(&parsed_notes) {
+        let Some(project_id) = parsed.project_id else {
+            continue;
+        };
+        if note_counts[&project_id] > 1 {
+            conflicted_projects.insert(project_id);
+            report.conflicts.push(ProjectNoteConflict {
+                file_name: note.file_name.clone(),
+                line: None,
+                task_name: None,
+                kind: ProjectNoteConflictKind::DuplicateProject,
+            });
+            continue;
+        }
+        notes_by_project.insert(project_id, note);
+        let conflict_count = report.conflicts.len();
+        read_back_completions(repository, note, parsed, finished_at, factory, &mut report)?;
+        if report.conflicts.len() > conflict_count {
+            conflicted_projects.insert(project_id);
+        }
+    }
+
+    let projects = repository
+        .get_all_projects()
+        .iter()
+        .map(|project| Ok((project.get_id()?, project.get_orig_status()?)))
+        .collect::<Result<Vec<_>, TaskTreeError>>()
+        .map_err(ApplicationError::TaskTree)?;
+    let mut used_file_names = notes
+        .iter()
+        .map(|note| note.file_name.clone())
+        .collect::<HashSet<_>>();
+    for (project_id, status) in projects {
+        if status == Status::Done || conflicted_projects.contains(&project_id) {
+            continue;
+        }
+        let tree = get_task_tree_without_schedule(repository, project_id, None)?;
+        let existing = notes_by_project.get(&project_id);
+        let text = render_note(existing.map(|note| note.text.as_str()), &tree);
+        let file_name = match existing {
+            Some(note) if note.text == text => continue,
+            Some(note) => note.file_name.clone(),
+            None => new_note_file_name(&tree, &mut used_file_names),
+        };
+        store.write_project_note(&ProjectNote {
+            file_name: file_name.clone(),
+            text,
+        })?;
+        report.written_file_names.push(file_name);
+    }
+    Ok(report)
+}
+
+struct ParsedNote {
+    project_id: Option<Uuid>,
+    items: Vec<NoteItem>,
+}
+
+// recordedは前回書き出した時のtask idと完了していたかどうか
+struct NoteItem {
+    line: usize,
+    name: String,
+    checked: bool,
+    recorded: Option<(Uuid, bool)>,
+}
+
+fn parse_note(text: &str) -> ParsedNote {
+    let lines = text.lines().collect::<Vec<_>>();
+    let (frontmatter, body_start) = split_frontmatter(&lines);
+    let project_id = frontmatter
+        .iter()
+        .find_map(|line| frontmatter_value(line, "schronu_id"))
+        .and_then(|value| Uuid::parse_str(value).ok());
+    let Some((start, end)) = task_section(&lines, body_start) else {
+        return ParsedNote {
+            project_id,
+            items: vec![],
+        };
+    };
+    let item = Regex::new(r"^\s*[-*+]\s+\[([ xX])\]\s*(.*?)\s*$").unwrap();
+    let marker =
+        Regex::new(r"^(.*?)\s*<!--\s*schronu:([0-9a-fA-F-]{36})\s+(todo|done)\s*-->$").unwrap();
+    let items = (start + 1..end)
+        .filter_map(|index| {
+            let captures = item.captures(lines[index])?;
+            let checked = captures[1].eq_ignore_ascii_case("x");
+            let title = captures.get(2).unwrap().as_str();
+            let (name, recorded) = match marker.captures(title) {
+                Some(marker) => (
+                    marker.get(1).unwrap().as_str(),
+                    Uuid::parse_str(&marker[2])
+                        .ok()
+                        .map(|task_id| (task_id, &marker[3] == "done")),
+                ),
+                None => (title, None),
+            };
+            Some(NoteItem {
+                line: index + 1,
+                name: name.to_string(),
+                checked,
+                recorded,
+            })
+        })
+        .collect();
+    ParsedNote { project_id, items }
+}
+
+// 子は親より後ろに並ぶので、後ろから完了させれば子を先に閉じられる
+fn read_back_completions(
+    repository: &mut dyn TaskRepositoryTrait,
+    note: &ProjectNote,
+    parsed: &ParsedNote,
+    finished_at: DateTime<Local>,
+    factory: &mut TaskFactory<'_>,
+    report: &mut ProjectNoteSyncReport,
+) -> Result<(), ProjectNoteSyncError> {
+    let conflict = |item: &NoteItem, kind| ProjectNoteConflict {
+        file_name: note.file_name.clone(),
+        line: Some(item.line),
+        task_name: Some(item.name.clone()),
+        kind,
+    };
+    let mut completions = vec![];
+    for item in &parsed.items {
+        let Some((task_id, recorded_done)) = item.recorded else {
+            report
+                .conflicts
+                .push(conflict(item, ProjectNoteConflictKind::MissingTaskId));
+            continue;
+        };
+        if item.checked == recorded_done {
+            continue;
+        }
+        let Some(task) = repository
+            .get_by_id(task_id)
+            .map_err(ApplicationError::TaskTree)?
+        else {
+            report
+                .conflicts
+                .push(conflict(item, ProjectNoteConflictKind::UnknownTask));
+            continue;
+        };
+        let done = task.get_orig_status().map_err(ApplicationError::TaskTree)? == Status::Done;
+        match (item.checked, done) {
+            (true, false) => completions.push((task_id, item)),
+            (false, true) => report
+                .conflicts
+                .push(conflict(item, ProjectNoteConflictKind::Reopened)),
+            _ => {}
+        }
+    }
+    for (task_id, item) in completions.into_iter().rev() {
+        let input = CompleteTaskInput {
+            task_id,
+            finished_at,
+            additional_actual_work_seconds: 0,
+        };
+        match complete_task(repository, input, factory) {
+            Ok(_) => report.completed.push(NoteCompletion {
+                task_id,
+                name: item.name.clone(),
+            }),
+            Err(ApplicationError::HasUndoneChildren(_)) => report
+                .conflicts
+                .push(conflict(item, ProjectNoteConflictKind::HasUndoneChildren)),
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Ok(())
+}
+
+fn render_note(existing: Option<&str>, tree: &TaskTreeNodeView) -> String {
+    let fields = note_fields(tree);
+    let mut section = vec![TASK_SECTION_START.to_string()];
+    write_checklist(&mut section, tree, 0);
+    section.push(TASK_SECTION_END.to_string());
+
+    let mut output = vec!["---".to_string()];
+    let Some(existing) = existing else {
+        output.extend(
+            fields
+                .iter()
+                .filter_map(|(key, value)| value.as_ref().map(|value| format!("{key}: {value}"))),
+        );
+        output.push("---".to_string());
+        output.push(format!("# {}", tree.task.name));
+        output.push(String::new());
+        output.extend(section);
+        return output.join("\n") + "\n";
+    };
+
+    let lines = existing.lines().collect::<Vec<_>>();
+    let (frontmatter, body_start) = split_frontmatter(&lines);
+    let mut written_keys = HashSet::new();
+    for line in frontmatter {
+        match fields
+            .iter()
+            .find(|(key, _)| frontmatter_value(line, key).is_some())
+        {
+            Some((key, value)) => {
+                if let Some(value) = value.as_ref().filter(|_| written_keys.insert(*key)) {
+                    output.push(format!("{key}: {value}"));
+                }
+            }
+            None => output.push(line.to_string()),
+        }
+    }
+    for (key, value) in &fields {
+        if let Some(value) = value.as_ref().filter(|_| !written_keys.contains(key)) {
+            output.push(format!("{key}: {value}"));
+        }
+    }
+    output.push("---".to_string());
+    match task_section(&lines, body_start) {
+        Some((start, end)) => {
+            output.extend(lines[body_start..start].iter().map(|line| line.to_string()));
+            output.extend(section);
+            output.extend(
+                lines[(end + 1).min(lines.len())..]
+                    .iter()
+                    .map(|line| line.to_string()),
+            );
+        }
+        None => {
+            let body = &lines[body_start..];
+            output.extend(body.iter().map(|line| line.to_string()));
+            if body.last().is_some_and(|line| !line.trim().is_empty()) {
+                output.push(String::new());
+            }
+            output.extend(section);
+        }
+    }
+    output.join("\n") + "\n"
+}
+
+// 日時はObsidianのpropertyとして読めるISO形式で書く
+fn note_fields(tree: &TaskTreeNodeView) -> [(&'static str, Option<String>); 4] {
+    [
+        ("schronu_id", Some(tree.task.id.to_string())),
+        (
+            "deadline",
+            tree.task
+                .deadline_time
+                .map(|deadline_time| deadline_time.format("%Y-%m-%dT%H:%M").to_string()),
+        ),
+        (
+            "category",
+            tree.task
+                .project_category
+                .map(|category| category.to_string()),
+        ),
+        (
+            "progress",
+            Some(format!(
+                "{}/{}",
+                tree.rollup.done_leaf_count, tree.rollup.leaf_count
+            )),
+        ),
+    ]
+}
+
+fn write_checklist(lines: &mut Vec<String>, node: &TaskTreeNodeView, depth: usize) {
+    let done = node.task.original_status == Status::Done;
+    lines.push(format!(
+        "{}- [{}] {} <!-- schronu:{} {} -->",
+        "  ".repeat(depth),
+        if done { 'x' } else { ' ' },
+        node.task.name,
+        node.task.id,
+        if done { "done" } else { "todo" }
+    ));
+    for child in &node.children {
+        write_checklist(lines, child, depth + 1);
+    }
+}
+
+// frontmatterの行と、本文が始まる行の位置を返す
+fn split_frontmatter<'a, 'b>(lines: &'b [&'a str]) -> (&'b [&'a str], usize) {
+    if lines.first() != Some(&"---") {
+        return (&[], 0);
+    }
+    match lines.iter().skip(1).position(|line| *line == "---") {
+        Some(length) => (&lines[1..length + 1], length + 2),
+        None => (&[], 0),
+    }
+}
+
+fn frontmatter_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let value = line.strip_prefix(key)?.strip_prefix(':')?.trim();
+    Some(
+        value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value),
+    )
+}
+
+// 終わりの印が消されていれば、noteの最後までを一覧とみなす
+fn task_section(lines: &[&str], body_start: usize) -> Option<(usize, usize)> {
+    let start = body_start
+        + lines[body_start..]
+            .iter()
+            .position(|line| line.trim() == TASK_SECTION_START)?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| line.trim() == TASK_SECTION_END)
+        .map_or(lines.len(), |length| start + 1 + length);
+    Some((start, end))
+}
+
+// Obsidianのlinkで使えない文字を除いたproject名にする。名前が重なればidの先頭を添える
+fn new_note_file_name(tree: &TaskTreeNodeView, used_file_names: &mut HashSet<String>) -> String {
+    let name = tree
+        .task
+        .name
+        .chars()
+        .map(|character| {
+            if "\\/:*?\"<>|#^[]".contains(character) || character.is_control() {
+                '_'
+            } else {
+                character
+            }
+        })
+        .collect::<String>();
+    let name = name.trim().trim_start_matches('.');
+    let id = tree.task.id.to_string();
+    let mut file_name = if name.is_empty() {
+        format!("{id}.md")
+    } else {
+        format!("{name}.md")
+    };
+    if used_file_names.contains(&file_name) {
+        file_name = format!("{name} {}.md", &id[..8]);
+    }
+    used_file_names.insert(file_name.clone());
+    file_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::task::TaskHandle;
+    use crate::test_support::{new_task_attr_at, new_task_handle_at, TestTaskRepository};
+    use chrono::TimeZone;
+
+    #[derive(Default)]
+    struct MemoryNoteStore {
+        notes: Vec<ProjectNote>,
+        writes: Vec<String>,
+    }
+
+    impl ProjectNoteStoreTrait for MemoryNoteStore {
+        fn list_project_notes(&self) -> Result<Vec<ProjectNote>, ProjectNoteStoreError> {
+            Ok(self.notes.clone())
+        }
+
+        fn write_project_note(&mut self, note: &ProjectNote) -> Result<(), ProjectNoteStoreError> {
+            self.writes.push(note.file_name.clone());
+            match self
+                .notes
+                .iter_mut()
+                .find(|existing| existing.file_name == note.file_name)
+            {
+                Some(existing) => existing.text = note.text.clone(),
+                None => self.notes.push(note.clone()),
+            }
+            Ok(())
+        }
+    }
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap()
+    }
+
+    fn project() -> (TaskHandle, TaskHandle, TaskHandle) {
+        let project = new_task_handle_at("旅行/準備", now()).unwrap();
+        project
+            .set_deadline_time_opt(Some(
+                Local.with_ymd_and_hms(2026, 10, 23, 19, 0, 0).unwrap(),
+            ))
+            .unwrap();
+        let ticket = project
+            .create_child(new_task_attr_at("切符", now()))
+            .unwrap();
+        let seat = ticket
+            .create_child(new_task_attr_at("座席", now()))
+            .unwrap();
+        (project, ticket, seat)
+    }
+
+    fn sync(
+        repository: &mut TestTaskRepository,
+        store: &mut MemoryNoteStore,
+    ) -> ProjectNoteSyncReport {
+        let mut next_id = Uuid::new_v4;
+        let mut factory = TaskFactory::new(now(), &mut next_id);
+        sync_project_notes(repository, store, now(), &mut factory).unwrap()
+    }
+
+    fn checked(text: &str, task_id: Uuid) -> String {
+        text.replace(
+            &format!("[ ] 切符 <!-- schronu:{task_id} todo -->"),
+            &format!("[x] 切符 <!-- schronu:{task_id} todo -->"),
+        )
+        .replace(
+            &format!("[ ] 座席 <!-- schronu:{task_id} todo -->"),
+            &format!("[x] 座席 <!-- schronu:{task_id} todo -->"),
+        )
+    }
+
+    #[test]
+    fn 未完了のprojectごとにfrontmatterと一覧を持つnoteを作り変化がなければ書かない() {
+        let (project, ticket, seat) = project();
+        let ids = [&project, &ticket, &seat].map(|task| task.get_id().unwrap());
+        let mut repository = TestTaskRepository::new(vec![project], now());
+        let mut store = MemoryNoteStore::default();
+
+        let report = sync(&mut repository, &mut store);
+
+        assert_eq!(report.written_file_names, ["旅行_準備.md"]);
+        assert_eq!(
+            store.notes[0].text,
+            format!(
+                "---\nschronu_id: {}\ndeadline: 2026-10-23T19:00\nprogress: 0/1\n---\n# 旅行/準備\n\n\
+                 {TASK_SECTION_START}\n\
+                 - [ ] 旅行/準備 <!-- schronu:{} todo -->\n\
+                 \x20 - [ ] 切符 <!-- schronu:{} todo -->\n\
+                 \x20   - [ ] 座席 <!-- schronu:{} todo -->\n\
+                 {TASK_SECTION_END}\n",
+                ids[0], ids[0], ids[1], ids[2]
+            )
+        );
+
+        let report = sync(&mut repository, &mut store);
+        assert_eq!(report, ProjectNoteSyncReport::default());
+        assert_eq!(store.writes.len(), 1);
+    }
+
+    #[test]
+    fn noteで付いたcheckは子から完了させ一覧の外とほかのpropertyを残して書き直す() {
+        let (project, ticket, seat) = project();
+        let ticket_id = ticket.get_id().unwrap();
+        let seat_id = seat.get_id().unwrap();
+        let mut repository = TestTaskRepository::new(vec![project], now());
+        let mut store = MemoryNoteStore::default();
+        sync(&mut repository, &mut store);
+        let edited = checked(&checked(&store.notes[0].text, ticket_id), seat_id)
+            .replace("progress: 0/1\n", "progress: 0/1\ntags: [trip]\n")
+            + "\nメモ\n";
+        store.notes[0].text = edited;
+
+        let report = sync(&mut repository, &mut store);
+
+        assert_eq!(
+            report.completed,
+            [
+                NoteCompletion {
+                    task_id: seat_id,
+                    name: "座席".to_string()
+                },
+                NoteCompletion {
+                    task_id: ticket_id,
+                    name: "切符".to_string()
+                },
+            ]
+        );
+        assert!(report.conflicts.is_empty());
+        let text = &store.notes[0].text;
+        assert!(text.contains("progress: 1/1\ntags: [trip]\n---\n"));
+        assert!(text.contains(&format!("- [x] 座席 <!-- schronu:{seat_id} done -->")));
+        assert!(text.ends_with(&format!("{TASK_SECTION_END}\n\nメモ\n")));
+        assert_eq!(
+            repository
+                .get_by_id(ticket_id)
+                .unwrap()
+                .unwrap()
+                .get_orig_status()
+                .unwrap(),
+            Status::Done
+        );
+    }
+
+    #[test]
+    fn 食い違いは報告しそのnoteを上書きしない() {
+        let (project, ticket, seat) = project();
+        let ticket_id = ticket.get_id().unwrap();
+        let seat_id = seat.get_id().unwrap();
+        let mut repository = TestTaskRepository::new(vec![project], now());
+        let mut store = MemoryNoteStore::default();
+        sync(&mut repository, &mut store);
+        let generated = store.notes[0].text.clone();
+        let edited = checked(&generated, ticket_id).replace(
+            TASK_SECTION_END,
+            &format!("- [ ] 手で足した行\n{TASK_SECTION_END}"),
+        );
+        store.notes[0].text = edited.clone();
+
+        let report = sync(&mut repository, &mut store);
+
+        let conflicts = report
+            .conflicts
+            .iter()
+            .map(|conflict| (conflict.line, conflict.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            conflicts,
+            [
+                (Some(12), ProjectNoteConflictKind::MissingTaskId),
+                (Some(10), ProjectNoteConflictKind::HasUndoneChildren),
+            ]
+        );
+        assert!(report.written_file_names.is_empty());
+        assert_eq!(store.notes[0].text, edited);
+
+        store.notes[0].text = generated;
+        seat.set_orig_status(Status::Done).unwrap();
+        sync(&mut repository, &mut store);
+        store.notes[0].text = store.notes[0].text.replace(
+            &format!("[x] 座席 <!-- schronu:{seat_id} done -->"),
+            &format!("[ ] 座席 <!-- schronu:{seat_id} done -->"),
+        );
+        let report = sync(&mut repository, &mut store);
+        assert_eq!(report.conflicts[0].kind, ProjectNoteConflictKind::Reopened);
+        assert_eq!(report.conflicts[0].task_name.as_deref(), Some("座席"));
+
+        let mut duplicate = store.notes[0].clone();
+        duplicate.file_name = "copy.md".to_string();
+        store.notes.push(duplicate);
+        let report = sync(&mut repository, &mut store);
+        assert_eq!(
+            report
+                .conflicts
+                .iter()
+                .map(|conflict| conflict.kind)
+                .collect::<Vec<_>>(),
+            [ProjectNoteConflictKind::DuplicateProject; 2]
+        );
+        assert!(report.written_file_names.is_empty());
+    }
+}
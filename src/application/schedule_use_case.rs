@@ -1,17 +1,26 @@
-use crate::application::daily_capacity::try_next_business_day_start;
-use crate::application::interface::TaskRepositoryTrait;
+use crate::application::daily_capacity::{
+    try_next_business_day_start, try_subjective_date_end, try_subjective_date_start,
+};
+use crate::application::interface::{FreeTimeManagerTrait, TaskRepositoryTrait};
 use crate::application::task_use_case::ApplicationError;
 use crate::application::task_view::TaskView;
+use crate::entity::allowed_window::AllowedWindow;
+use crate::entity::datetime::BusinessDateTimePolicy;
 use crate::entity::task::{
     extract_leaf_tasks_from_project_with_pending, TaskHandle, TaskTreeError,
 };
 use chrono::{DateTime, Duration, Local};
 use serde::Serialize;
 use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 const MIN_SPLIT_SEGMENT_SECONDS: i64 = 5 * 60;
+// 空き時間を調べる日数。予定がその先へはみ出したら倍に広げて計算し直す
+const FREE_TIME_HORIZON_DAYS: i64 = 14;
+const MAX_FREE_TIME_HORIZON_DAYS: i64 = 448;
+
+type TimeSlot = (DateTime<Local>, DateTime<Local>);
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct ScheduledTaskView {
@@ -24,6 +33,32 @@ pub struct ScheduledTaskView {
     pub rank: usize,
 }
 
+// 予定に置けなかった理由
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnscheduledReason {
+    // 分けられないtaskが、途切れずに使えるどの空きよりも長い
+    LongerThanFreeTime,
+    // 分けられないtaskが、許可された時間帯のどの枠よりも長い
+    LongerThanAllowedWindow,
+    // 先に終える子taskを置けていない
+    DependencyUnscheduled,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct UnscheduledTaskView {
+    pub task: TaskView,
+    pub total_work_seconds: i64,
+    pub reason: UnscheduledReason,
+}
+
+// 置けた予定と、置けずに残ったtask
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Schedule {
+    pub scheduled_tasks: Vec<ScheduledTaskView>,
+    pub unscheduled_tasks: Vec<UnscheduledTaskView>,
+}
+
 #[derive(Clone)]
 struct TaskScheduleCandidate {
     id: Uuid,
@@ -35,6 +70,7 @@ struct TaskScheduleCandidate {
     remaining_seconds: i64,
     dependency_ids: Vec<Uuid>,
     atomic: bool,
+    allowed_window: Option<AllowedWindow>,
 }
 
 struct TaskScheduleAttributes {
@@ -58,8 +94,97 @@ struct ScheduledTask {
     deadline_time: Option<DateTime<Local>>,
 }
 
+#[derive(Clone)]
+struct UnscheduledTask {
+    id: Uuid,
+    task: TaskHandle,
+    total_work_seconds: i64,
+    reason: UnscheduledReason,
+}
+
+struct TaskSchedule {
+    scheduled: Vec<ScheduledTask>,
+    unscheduled: Vec<UnscheduledTask>,
+}
+
+// 空き時間管理で埋まっている時間と、業務日の終わりから次の業務日の始まりまでを避けて予定を置く
 pub fn get_schedule(
     repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
+) -> Result<Vec<ScheduledTaskView>, ApplicationError> {
+    get_schedule_in_free_time_with_first_available_time_overrides(
+        repository,
+        free_time_manager,
+        policy,
+        &HashMap::new(),
+    )
+}
+
+// get_scheduleと同じ予定に、どの空きにも置けずに残ったtaskを添えて返す
+pub fn get_schedule_with_unscheduled(
+    repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
+) -> Result<Schedule, ApplicationError> {
+    let candidates = prepare_schedule_candidates(repository, &HashMap::new())?;
+    let schedule = schedule_in_free_time(repository, free_time_manager, policy, &candidates)?;
+    Ok(Schedule {
+        scheduled_tasks: schedule
+            .scheduled
+            .iter()
+            .map(to_scheduled_task_view)
+            .collect::<Result<_, _>>()?,
+        unscheduled_tasks: schedule
+            .unscheduled
+            .iter()
+            .map(to_unscheduled_task_view)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+pub(crate) fn get_schedule_in_free_time_with_first_available_time_overrides(
+    repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
+    first_available_time_overrides: &HashMap<Uuid, DateTime<Local>>,
+) -> Result<Vec<ScheduledTaskView>, ApplicationError> {
+    let candidates = prepare_schedule_candidates(repository, first_available_time_overrides)?;
+    schedule_in_free_time(repository, free_time_manager, policy, &candidates)?
+        .scheduled
+        .iter()
+        .map(to_scheduled_task_view)
+        .collect()
+}
+
+fn schedule_in_free_time(
+    repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
+    candidates: &[TaskScheduleCandidate],
+) -> Result<TaskSchedule, ApplicationError> {
+    let last_synced_time = repository.get_last_synced_time();
+    let mut horizon_days = FREE_TIME_HORIZON_DAYS;
+    loop {
+        let (unavailable_slots, horizon_end) =
+            collect_unavailable_slots(free_time_manager, policy, last_synced_time, horizon_days)?;
+        let schedule = schedule_tasks_by_priority(candidates, last_synced_time, &unavailable_slots)
+            .map_err(ApplicationError::TaskTree)?;
+        if horizon_days >= MAX_FREE_TIME_HORIZON_DAYS
+            || schedule
+                .scheduled
+                .iter()
+                .all(|scheduled| scheduled.scheduled_end <= horizon_end)
+        {
+            return Ok(schedule);
+        }
+        horizon_days *= 2;
+    }
+}
+
+// 空き時間を考えずに今から隙間なく並べる。日ごとの容量は平坦化や前倒しの側で数える
+pub fn get_back_to_back_schedule(
+    repository: &dyn TaskRepositoryTrait,
 ) -> Result<Vec<ScheduledTaskView>, ApplicationError> {
     get_schedule_with_first_available_time_overrides(repository, &HashMap::new())
 }
@@ -79,6 +204,19 @@ pub(crate) fn get_schedule_with_first_available_time_overrides(
     repository: &dyn TaskRepositoryTrait,
     first_available_time_overrides: &HashMap<Uuid, DateTime<Local>>,
 ) -> Result<Vec<ScheduledTaskView>, ApplicationError> {
+    let candidates = prepare_schedule_candidates(repository, first_available_time_overrides)?;
+    schedule_tasks_by_priority(&candidates, repository.get_last_synced_time(), &[])
+        .map_err(ApplicationError::TaskTree)?
+        .scheduled
+        .iter()
+        .map(to_scheduled_task_view)
+        .collect()
+}
+
+fn prepare_schedule_candidates(
+    repository: &dyn TaskRepositoryTrait,
+    first_available_time_overrides: &HashMap<Uuid, DateTime<Local>>,
+) -> Result<Vec<TaskScheduleCandidate>, ApplicationError> {
     for project_root in repository.get_all_projects() {
         project_root
             .snapshot()
@@ -97,21 +235,112 @@ pub(crate) fn get_schedule_with_first_available_time_overrides(
                 max(*first_available_time, repository.get_last_synced_time());
         }
     }
-    schedule_tasks_by_priority(&candidates, repository.get_last_synced_time())
-        .map_err(ApplicationError::TaskTree)?
-        .into_iter()
-        .map(|scheduled| {
-            Ok(ScheduledTaskView {
-                task: TaskView::try_from(&scheduled.task).map_err(ApplicationError::TaskTree)?,
-                first_available_time: scheduled.first_available_time,
-                scheduled_start: scheduled.scheduled_start,
-                scheduled_end: scheduled.scheduled_end,
-                scheduled_work_seconds: scheduled.scheduled_work_seconds,
-                total_work_seconds: scheduled.total_work_seconds,
-                rank: scheduled.rank,
-            })
-        })
-        .collect()
+    Ok(candidates)
+}
+
+fn to_scheduled_task_view(
+    scheduled: &ScheduledTask,
+) -> Result<ScheduledTaskView, ApplicationError> {
+    Ok(ScheduledTaskView {
+        task: TaskView::try_from(&scheduled.task).map_err(ApplicationError::TaskTree)?,
+        first_available_time: scheduled.first_available_time,
+        scheduled_start: scheduled.scheduled_start,
+        scheduled_end: scheduled.scheduled_end,
+        scheduled_work_seconds: scheduled.scheduled_work_seconds,
+        total_work_seconds: scheduled.total_work_seconds,
+        rank: scheduled.rank,
+    })
+}
+
+fn to_unscheduled_task_view(
+    unscheduled: &UnscheduledTask,
+) -> Result<UnscheduledTaskView, ApplicationError> {
+    Ok(UnscheduledTaskView {
+        task: TaskView::try_from(&unscheduled.task).map_err(ApplicationError::TaskTree)?,
+        total_work_seconds: unscheduled.total_work_seconds,
+        reason: unscheduled.reason,
+    })
+}
+
+// fromを含む主観日からdays日分について、予定を置けない区間と調べ終えた時刻を返す
+fn collect_unavailable_slots(
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
+    from: DateTime<Local>,
+    days: i64,
+) -> Result<(Vec<TimeSlot>, DateTime<Local>), ApplicationError> {
+    let first_date =
+        policy
+            .subjective_date(from)
+            .ok_or(ApplicationError::SubjectiveDateOutOfRange {
+                operation: "subjective_date",
+                datetime: from,
+            })?;
+    let mut slots = Vec::new();
+    let mut horizon_end = from;
+    for offset in 0..days {
+        let date = first_date
+            .checked_add_signed(Duration::days(offset))
+            .ok_or(ApplicationError::SubjectiveDateOutOfRange {
+                operation: "subjective_date",
+                datetime: from,
+            })?;
+        let day_start = try_subjective_date_start(date)?;
+        let day_end = try_subjective_date_end(date, policy.end_of_day_offset_minutes())?;
+        collect_busy_slots(free_time_manager, day_start, day_end, &mut slots);
+        horizon_end = try_next_business_day_start(day_start)?;
+        if day_end < horizon_end {
+            push_slot(&mut slots, (day_end, horizon_end));
+        }
+    }
+    Ok((slots, horizon_end))
+}
+
+// 空き時間管理は分単位の合計しか返さないので、区間を半分ずつに分けて埋まっている区間を探す
+fn collect_busy_slots(
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+    slots: &mut Vec<TimeSlot>,
+) {
+    let minutes = (end - start).num_minutes();
+    if minutes <= 0 {
+        return;
+    }
+    let busy_minutes = free_time_manager.get_busy_minutes(&start, &end);
+    if busy_minutes <= 0 {
+        return;
+    }
+    if busy_minutes >= minutes {
+        push_slot(slots, (start, end));
+        return;
+    }
+    let middle = start + Duration::minutes(minutes / 2);
+    collect_busy_slots(free_time_manager, start, middle, slots);
+    collect_busy_slots(free_time_manager, middle, end, slots);
+}
+
+fn push_slot(slots: &mut Vec<TimeSlot>, (start, end): TimeSlot) {
+    match slots.last_mut() {
+        Some((_, last_end)) if *last_end >= start => *last_end = (*last_end).max(end),
+        _ => slots.push((start, end)),
+    }
+}
+
+// 置けない区間の間にある最も長い空きの秒数。最後の区間より後は調べていないので数えない
+fn longest_free_seconds(unavailable_slots: &[TimeSlot], from: DateTime<Local>) -> i64 {
+    if unavailable_slots.is_empty() {
+        return i64::MAX;
+    }
+    let mut longest = 0;
+    let mut cursor = from;
+    for (start, end) in unavailable_slots {
+        if *start > cursor {
+            longest = longest.max((*start - cursor).num_seconds());
+        }
+        cursor = cursor.max(*end);
+    }
+    longest
 }
 
 fn build_schedule_candidates(
@@ -199,6 +428,9 @@ fn build_schedule_candidates(
                 .map_err(ApplicationError::TaskTree)?,
             dependency_ids: child_ids_by_parent_id.remove(&id).unwrap_or_default(),
             atomic: task.get_atomic().map_err(ApplicationError::TaskTree)?,
+            allowed_window: task
+                .get_effective_allowed_window_opt()
+                .map_err(ApplicationError::TaskTree)?,
             task,
             first_available_time: attributes.first_available_time,
             neg_priority: attributes.neg_priority,
@@ -209,7 +441,7 @@ fn build_schedule_candidates(
     Ok(candidates)
 }
 
-fn calculate_remaining_work_seconds(task: &TaskHandle) -> Result<i64, TaskTreeError> {
+pub(crate) fn calculate_remaining_work_seconds(task: &TaskHandle) -> Result<i64, TaskTreeError> {
     let estimated_work_seconds = task.get_estimated_work_seconds()?;
     let actual_work_seconds = task.get_actual_work_seconds()?;
     if estimated_work_seconds >= actual_work_seconds {
@@ -242,6 +474,48 @@ fn find_earliest_non_overlapping_start(
     }
 }
 
+// 他のtaskと重ならず、許可された枠の中にある最初の時刻
+fn find_earliest_allowed_start(
+    first_available_time: DateTime<Local>,
+    allowed_window: Option<&AllowedWindow>,
+    occupied_slots: &[(DateTime<Local>, DateTime<Local>)],
+) -> DateTime<Local> {
+    let mut start = first_available_time;
+    loop {
+        start = find_earliest_non_overlapping_start(start, 0, occupied_slots);
+        match allowed_window.and_then(|window| window.next_allowed_interval(start)) {
+            Some((allowed_start, _)) if allowed_start > start => start = allowed_start,
+            _ => return start,
+        }
+    }
+}
+
+// atomicなtaskは1つの枠の中に途切れず収める
+fn find_earliest_atomic_start(
+    first_available_time: DateTime<Local>,
+    remaining_seconds: i64,
+    allowed_window: Option<&AllowedWindow>,
+    occupied_slots: &[(DateTime<Local>, DateTime<Local>)],
+) -> DateTime<Local> {
+    let duration = Duration::seconds(remaining_seconds);
+    let mut start = first_available_time;
+    loop {
+        start = find_earliest_non_overlapping_start(start, remaining_seconds, occupied_slots);
+        let Some((allowed_start, allowed_end)) =
+            allowed_window.and_then(|window| window.next_allowed_interval(start))
+        else {
+            return start;
+        };
+        if allowed_start > start {
+            start = allowed_start;
+        } else if start + duration > allowed_end {
+            start = allowed_end;
+        } else {
+            return start;
+        }
+    }
+}
+
 fn find_next_occupied_slot(
     start: DateTime<Local>,
     occupied_slots: &[(DateTime<Local>, DateTime<Local>)],
@@ -255,7 +529,8 @@ fn find_next_occupied_slot(
 fn schedule_tasks_by_priority(
     candidates: &[TaskScheduleCandidate],
     last_synced_time: DateTime<Local>,
-) -> Result<Vec<ScheduledTask>, TaskTreeError> {
+    unavailable_slots: &[TimeSlot],
+) -> Result<TaskSchedule, TaskTreeError> {
     let mut pending_candidates = candidates.to_vec();
     pending_candidates.sort_by(|a, b| {
         (
@@ -276,9 +551,12 @@ fn schedule_tasks_by_priority(
             ))
     });
 
-    let mut occupied_slots = Vec::new();
+    let longest_free_seconds = longest_free_seconds(unavailable_slots, last_synced_time);
+    let mut occupied_slots = unavailable_slots.to_vec();
     let mut scheduled_tasks = Vec::new();
+    let mut unscheduled_tasks = Vec::new();
     let mut scheduled_end_by_id = HashMap::new();
+    let mut unscheduled_ids = HashSet::new();
 
     while !pending_candidates.is_empty() {
         let index = pending_candidates
@@ -287,10 +565,11 @@ fn schedule_tasks_by_priority(
                 candidate
                     .dependency_ids
                     .iter()
-                    .all(|id| scheduled_end_by_id.contains_key(id))
+                    .all(|id| scheduled_end_by_id.contains_key(id) || unscheduled_ids.contains(id))
             })
             .unwrap_or(0);
         let candidate = pending_candidates.remove(index);
+        let allowed_window = candidate.allowed_window.as_ref();
         let dependency_end = candidate
             .dependency_ids
             .iter()
@@ -298,14 +577,42 @@ fn schedule_tasks_by_priority(
             .max()
             .copied()
             .unwrap_or(last_synced_time);
-        let mut segment_start = find_earliest_non_overlapping_start(
+        let mut segment_start = find_earliest_allowed_start(
             max(
                 max(candidate.first_available_time, last_synced_time),
                 dependency_end,
             ),
-            0,
+            allowed_window,
             &occupied_slots,
         );
+        // 分けられないtaskは、収まる空きがなければ分けて置かずに残す
+        let unscheduled_reason = if candidate
+            .dependency_ids
+            .iter()
+            .any(|id| unscheduled_ids.contains(id))
+        {
+            Some(UnscheduledReason::DependencyUnscheduled)
+        } else if candidate.atomic && candidate.remaining_seconds > longest_free_seconds {
+            Some(UnscheduledReason::LongerThanFreeTime)
+        } else if candidate.atomic
+            && allowed_window.is_some_and(|window| {
+                window.longest_interval_seconds(segment_start) < candidate.remaining_seconds
+            })
+        {
+            Some(UnscheduledReason::LongerThanAllowedWindow)
+        } else {
+            None
+        };
+        if let Some(reason) = unscheduled_reason {
+            unscheduled_ids.insert(candidate.id);
+            unscheduled_tasks.push(UnscheduledTask {
+                id: candidate.id,
+                task: candidate.task.clone(),
+                total_work_seconds: candidate.remaining_seconds,
+                reason,
+            });
+            continue;
+        }
         let mut remaining_seconds = candidate.remaining_seconds;
         let total_work_seconds = remaining_seconds;
         let mut candidate_scheduled_end = segment_start;
@@ -319,9 +626,10 @@ fn schedule_tasks_by_priority(
                 total_work_seconds,
             ));
         } else if candidate.atomic {
-            let start = find_earliest_non_overlapping_start(
+            let start = find_earliest_atomic_start(
                 segment_start,
                 remaining_seconds,
+                allowed_window,
                 &occupied_slots,
             );
             let end = start + Duration::seconds(remaining_seconds);
@@ -338,13 +646,16 @@ fn schedule_tasks_by_priority(
         } else {
             while remaining_seconds > 0 {
                 segment_start =
-                    find_earliest_non_overlapping_start(segment_start, 0, &occupied_slots);
+                    find_earliest_allowed_start(segment_start, allowed_window, &occupied_slots);
                 let uninterrupted_end = segment_start + Duration::seconds(remaining_seconds);
+                let window_end = allowed_window
+                    .and_then(|window| window.next_allowed_interval(segment_start))
+                    .map_or(uninterrupted_end, |(_, allowed_end)| {
+                        allowed_end.min(uninterrupted_end)
+                    });
                 let segment_end = match find_next_occupied_slot(segment_start, &occupied_slots) {
-                    Some((occupied_start, _)) if occupied_start < uninterrupted_end => {
-                        occupied_start
-                    }
-                    _ => uninterrupted_end,
+                    Some((occupied_start, _)) if occupied_start < window_end => occupied_start,
+                    _ => window_end,
                 };
                 let work_seconds = (segment_end - segment_start).num_seconds();
                 if work_seconds <= 0 {
@@ -356,7 +667,8 @@ fn schedule_tasks_by_priority(
                     continue;
                 }
                 let after_split = remaining_seconds - work_seconds;
-                if segment_end < uninterrupted_end
+                // 枠の終わりで切れる場合は、短くてもそのまま置く
+                if segment_end < window_end
                     && (work_seconds <= MIN_SPLIT_SEGMENT_SECONDS
                         || after_split <= MIN_SPLIT_SEGMENT_SECONDS)
                 {
@@ -400,7 +712,11 @@ fn schedule_tasks_by_priority(
                 b.id,
             ))
     });
-    Ok(scheduled_tasks)
+    unscheduled_tasks.sort_by_key(|unscheduled| unscheduled.id);
+    Ok(TaskSchedule {
+        scheduled: scheduled_tasks,
+        unscheduled: unscheduled_tasks,
+    })
 }
 
 fn to_scheduled_task(
@@ -446,6 +762,7 @@ mod tests {
             remaining_seconds,
             dependency_ids: vec![],
             atomic: false,
+            allowed_window: None,
         }
     }
 
@@ -466,7 +783,9 @@ mod tests {
             60 * 60,
         );
 
-        let actual = schedule_tasks_by_priority(&[low, high], now).unwrap();
+        let actual = schedule_tasks_by_priority(&[low, high], now, &[])
+            .unwrap()
+            .scheduled;
         let low_segments = actual
             .iter()
             .filter(|scheduled| scheduled.task.get_id().unwrap() == low_id)
@@ -497,7 +816,9 @@ mod tests {
             60 * 60,
         );
 
-        let actual = schedule_tasks_by_priority(&[low, high], now).unwrap();
+        let actual = schedule_tasks_by_priority(&[low, high], now, &[])
+            .unwrap()
+            .scheduled;
         let low_segments = actual
             .iter()
             .filter(|scheduled| scheduled.task.get_id().unwrap() == low_id)
@@ -533,7 +854,9 @@ mod tests {
             60 * 60,
         );
 
-        let actual = schedule_tasks_by_priority(&[low, high], now).unwrap();
+        let actual = schedule_tasks_by_priority(&[low, high], now, &[])
+            .unwrap()
+            .scheduled;
         let low_segments = actual
             .iter()
             .filter(|scheduled| scheduled.task.get_id().unwrap() == low_id)
@@ -559,7 +882,9 @@ mod tests {
         );
         let task_id = task.task.get_id().unwrap();
 
-        let actual = schedule_tasks_by_priority(&[blocker, task], now).unwrap();
+        let actual = schedule_tasks_by_priority(&[blocker, task], now, &[])
+            .unwrap()
+            .scheduled;
         let scheduled = actual
             .iter()
             .find(|scheduled| scheduled.task.get_id().unwrap() == task_id)
@@ -589,7 +914,9 @@ mod tests {
         parent.dependency_ids = vec![child_id];
         let parent_id = parent.task.get_id().unwrap();
 
-        let actual = schedule_tasks_by_priority(&[parent, blocker, child], now).unwrap();
+        let actual = schedule_tasks_by_priority(&[parent, blocker, child], now, &[])
+            .unwrap()
+            .scheduled;
         let scheduled = actual
             .iter()
             .find(|scheduled| scheduled.task.get_id().unwrap() == parent_id)
@@ -633,7 +960,9 @@ mod tests {
         let dinner_id = dinner.task.get_id().unwrap();
 
         let actual =
-            schedule_tasks_by_priority(&[priority_87, dinner, priority_88, lunch], now).unwrap();
+            schedule_tasks_by_priority(&[priority_87, dinner, priority_88, lunch], now, &[])
+                .unwrap()
+                .scheduled;
         let start = |id| {
             actual
                 .iter()
@@ -668,7 +997,9 @@ mod tests {
         parent.dependency_ids = vec![child_id];
         let parent_id = parent.task.get_id().unwrap();
 
-        let actual = schedule_tasks_by_priority(&[parent, blocker, child], now).unwrap();
+        let actual = schedule_tasks_by_priority(&[parent, blocker, child], now, &[])
+            .unwrap()
+            .scheduled;
         let start = |id| {
             actual
                 .iter()
@@ -686,4 +1017,196 @@ mod tests {
             Local.with_ymd_and_hms(2026, 5, 10, 15, 1, 0).unwrap()
         );
     }
+
+    #[test]
+    fn schedule_tasks_by_priority_許可された時間帯の中だけに分けて配置する() {
+        // 2026/05/10は日曜日
+        let now = Local.with_ymd_and_hms(2026, 5, 10, 12, 0, 0).unwrap();
+        let mut task = candidate("電話", now, -1, 60 * 60);
+        task.allowed_window = Some(AllowedWindow::parse("13:00-13:30,14:00-15:00").unwrap());
+
+        let actual = schedule_tasks_by_priority(&[task], now, &[])
+            .unwrap()
+            .scheduled;
+
+        assert_eq!(
+            actual
+                .iter()
+                .map(|scheduled| (scheduled.scheduled_start, scheduled.scheduled_end))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Local.with_ymd_and_hms(2026, 5, 10, 13, 0, 0).unwrap(),
+                    Local.with_ymd_and_hms(2026, 5, 10, 13, 30, 0).unwrap()
+                ),
+                (
+                    Local.with_ymd_and_hms(2026, 5, 10, 14, 0, 0).unwrap(),
+                    Local.with_ymd_and_hms(2026, 5, 10, 14, 30, 0).unwrap()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn schedule_tasks_by_priority_atomic_taskは収まる枠まで待つ() {
+        let now = Local.with_ymd_and_hms(2026, 5, 10, 12, 0, 0).unwrap();
+        let mut task = candidate("外出", now, -1, 60 * 60);
+        task.atomic = true;
+        task.allowed_window = Some(AllowedWindow::parse("12:00-12:30,14:00-16:00").unwrap());
+
+        let actual = schedule_tasks_by_priority(&[task], now, &[])
+            .unwrap()
+            .scheduled;
+
+        assert_eq!(actual.len(), 1);
+        assert_eq!(
+            actual[0].scheduled_start,
+            Local.with_ymd_and_hms(2026, 5, 10, 14, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn schedule_tasks_by_priority_許可されていない曜日には配置しない() {
+        let now = Local.with_ymd_and_hms(2026, 5, 10, 12, 0, 0).unwrap();
+        let mut task = candidate("銀行", now, -1, 30 * 60);
+        task.allowed_window = Some(AllowedWindow::parse("平日 09:00-15:00").unwrap());
+
+        let actual = schedule_tasks_by_priority(&[task], now, &[])
+            .unwrap()
+            .scheduled;
+
+        assert_eq!(
+            actual[0].scheduled_start,
+            Local.with_ymd_and_hms(2026, 5, 11, 9, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn collect_unavailable_slotsは埋まっている時間と夜を返す() {
+        let now = Local.with_ymd_and_hms(2026, 5, 10, 12, 0, 0).unwrap();
+        let lunch_start = Local.with_ymd_and_hms(2026, 5, 10, 13, 0, 0).unwrap();
+        let lunch_end = Local.with_ymd_and_hms(2026, 5, 10, 14, 0, 0).unwrap();
+        let mut free_time_manager = crate::test_support::TestFreeTimeManager::with_blocked_interval(
+            18 * 60 + 30 - 60,
+            lunch_start,
+            lunch_end,
+        );
+
+        let (slots, horizon_end) = collect_unavailable_slots(
+            &mut free_time_manager,
+            &BusinessDateTimePolicy::new(30),
+            now,
+            1,
+        )
+        .unwrap();
+
+        let next_day_start = Local.with_ymd_and_hms(2026, 5, 11, 6, 0, 0).unwrap();
+        assert_eq!(
+            slots,
+            vec![
+                (lunch_start, lunch_end),
+                (
+                    Local.with_ymd_and_hms(2026, 5, 11, 0, 30, 0).unwrap(),
+                    next_day_start
+                ),
+            ]
+        );
+        assert_eq!(horizon_end, next_day_start);
+    }
+
+    #[test]
+    fn schedule_tasks_by_priority_atomic_taskは置けない時間の後の空きに連続して置く() {
+        let now = Local.with_ymd_and_hms(2026, 5, 10, 12, 0, 0).unwrap();
+        let mut task = candidate("会議準備", now, -1, 2 * 60 * 60);
+        task.atomic = true;
+        let unavailable_slots = [
+            (
+                Local.with_ymd_and_hms(2026, 5, 10, 13, 0, 0).unwrap(),
+                Local.with_ymd_and_hms(2026, 5, 10, 14, 0, 0).unwrap(),
+            ),
+            (
+                Local.with_ymd_and_hms(2026, 5, 11, 0, 30, 0).unwrap(),
+                Local.with_ymd_and_hms(2026, 5, 11, 6, 0, 0).unwrap(),
+            ),
+        ];
+
+        let actual = schedule_tasks_by_priority(&[task], now, &unavailable_slots)
+            .unwrap()
+            .scheduled;
+
+        assert_eq!(actual.len(), 1);
+        assert_eq!(
+            actual[0].scheduled_start,
+            Local.with_ymd_and_hms(2026, 5, 10, 14, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn schedule_tasks_by_priority_どの空きにも収まらないatomic_taskは分けずに置けないまま残す() {
+        let now = Local.with_ymd_and_hms(2026, 5, 10, 12, 0, 0).unwrap();
+        let mut task = candidate("長い作業", now, -1, 3 * 60 * 60);
+        task.atomic = true;
+        let task_id = task.id;
+        let mut parent = candidate("親", now, -1, 30 * 60);
+        parent.dependency_ids = vec![task_id];
+        let parent_id = parent.id;
+        let other = candidate("短い作業", now, -2, 30 * 60);
+        let unavailable_slots = [
+            (
+                Local.with_ymd_and_hms(2026, 5, 10, 13, 0, 0).unwrap(),
+                Local.with_ymd_and_hms(2026, 5, 10, 14, 0, 0).unwrap(),
+            ),
+            (
+                Local.with_ymd_and_hms(2026, 5, 10, 15, 0, 0).unwrap(),
+                Local.with_ymd_and_hms(2026, 5, 11, 6, 0, 0).unwrap(),
+            ),
+        ];
+
+        let actual =
+            schedule_tasks_by_priority(&[parent, task, other], now, &unavailable_slots).unwrap();
+
+        assert_eq!(
+            actual
+                .scheduled
+                .iter()
+                .map(|scheduled| (
+                    scheduled.task.get_name().unwrap(),
+                    scheduled.scheduled_start
+                ))
+                .collect::<Vec<_>>(),
+            vec![(
+                "短い作業".to_string(),
+                Local.with_ymd_and_hms(2026, 5, 10, 12, 0, 0).unwrap()
+            )]
+        );
+        let mut unscheduled = actual
+            .unscheduled
+            .iter()
+            .map(|unscheduled| (unscheduled.id, unscheduled.reason))
+            .collect::<Vec<_>>();
+        unscheduled.sort();
+        let mut expected = vec![
+            (task_id, UnscheduledReason::LongerThanFreeTime),
+            (parent_id, UnscheduledReason::DependencyUnscheduled),
+        ];
+        expected.sort();
+        assert_eq!(unscheduled, expected);
+    }
+
+    #[test]
+    fn schedule_tasks_by_priority_許可された枠より長いatomic_taskは置けないまま残す() {
+        let now = Local.with_ymd_and_hms(2026, 5, 10, 12, 0, 0).unwrap();
+        let mut task = candidate("外出", now, -1, 2 * 60 * 60);
+        task.atomic = true;
+        task.allowed_window = Some(AllowedWindow::parse("12:00-13:00").unwrap());
+
+        let actual = schedule_tasks_by_priority(&[task], now, &[]).unwrap();
+
+        assert!(actual.scheduled.is_empty());
+        assert_eq!(actual.unscheduled[0].total_work_seconds, 2 * 60 * 60);
+        assert_eq!(
+            actual.unscheduled[0].reason,
+            UnscheduledReason::LongerThanAllowedWindow
+        );
+    }
 }
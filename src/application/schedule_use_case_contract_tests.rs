@@ -1,7 +1,9 @@
 use super::interface::{TaskRepositoryError, TaskRepositoryTrait};
-use super::schedule_use_case::get_schedule;
+use super::schedule_use_case::{get_back_to_back_schedule, get_schedule, ScheduledTaskView};
 use super::task_use_case::get_task;
+use crate::entity::datetime::BusinessDateTimePolicy;
 use crate::entity::task::{Status, TaskHandle};
+use crate::test_support::BusyIntervals;
 use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate, TimeZone};
 use std::cell::Cell;
 use uuid::Uuid;
@@ -88,6 +90,29 @@ impl TaskRepositoryTrait for TestTaskRepository {
         self.projects.push(root_task);
         Ok(())
     }
+
+    fn remove_project(
+        &mut self,
+        root_task_id: Uuid,
+    ) -> Result<Option<String>, crate::entity::task::TaskTreeError> {
+        let Some(index) = self
+            .projects
+            .iter()
+            .position(|project| project.get_id() == Ok(root_task_id))
+        else {
+            return Ok(None);
+        };
+        self.projects.remove(index).get_name().map(Some)
+    }
+
+    fn restore_project(
+        &mut self,
+        project_key: &str,
+        root_task: TaskHandle,
+    ) -> Result<String, crate::entity::task::TaskTreeError> {
+        self.projects.push(root_task);
+        Ok(project_key.to_string())
+    }
 }
 
 fn fixed_now() -> DateTime<Local> {
@@ -99,7 +124,8 @@ fn get_scheduleは借用競合をtask_tree_errorとして返す() {
     let task = crate::test_support::new_task_handle("借用競合").unwrap();
     let repository = TestTaskRepository::new(vec![task.clone()], fixed_now());
 
-    let actual = task.with_exclusive_data_borrow_for_test(|| get_schedule(&repository));
+    let actual =
+        task.with_exclusive_data_borrow_for_test(|| get_back_to_back_schedule(&repository));
 
     assert_eq!(
         actual,
@@ -151,7 +177,7 @@ fn get_schedule_締切を優先しdoneを除外してtask_viewを返す() {
         })
         .collect::<Vec<_>>();
 
-    let actual = get_schedule(&repository).unwrap();
+    let actual = get_back_to_back_schedule(&repository).unwrap();
 
     assert_eq!(actual.len(), 2);
     assert_eq!(actual[0].task.id, deadline_task.get_id().unwrap());
@@ -186,7 +212,7 @@ fn get_schedule_i64最小値付近でも優先度の高いtaskを先に配置す
     let next = task_with_schedule("次", now, 15 * 60, i64::MIN + 1);
     let repository = TestTaskRepository::new(vec![lowest.clone(), next.clone()], now);
 
-    let actual = get_schedule(&repository).unwrap();
+    let actual = get_back_to_back_schedule(&repository).unwrap();
 
     assert_eq!(actual[0].task.id, next.get_id().unwrap());
     assert_eq!(actual[1].task.id, lowest.get_id().unwrap());
@@ -217,7 +243,7 @@ fn get_scheduleは候補の次業務日開始計算不能を伝搬しtaskを変
         second.get_persistent_mutation_revision().unwrap(),
     ];
 
-    let actual = get_schedule(&repository);
+    let actual = get_back_to_back_schedule(&repository);
 
     assert_eq!(
         actual,
@@ -277,7 +303,7 @@ fn get_scheduleは複数の日時errorからuuid順先頭候補を決定的に
     );
 
     for _ in 0..64 {
-        assert_eq!(get_schedule(&repository), expected);
+        assert_eq!(get_back_to_back_schedule(&repository), expected);
     }
 }
 
@@ -294,7 +320,7 @@ fn get_schedule_pending解除後に子を配置し親をその後へ置く() {
     child.set_orig_status(Status::Pending).unwrap();
     let repository = TestTaskRepository::new(vec![parent.clone()], now);
 
-    let actual = get_schedule(&repository).unwrap();
+    let actual = get_back_to_back_schedule(&repository).unwrap();
     let child_schedule = actual
         .iter()
         .find(|entry| entry.task.id == child.get_id().unwrap())
@@ -324,7 +350,7 @@ fn get_schedule_非atomic_taskを未来の高優先度taskの前後へ分割す
     let repository =
         TestTaskRepository::new(vec![low_priority.clone(), high_priority.clone()], now);
 
-    let actual = get_schedule(&repository).unwrap();
+    let actual = get_back_to_back_schedule(&repository).unwrap();
     let low_segments = actual
         .iter()
         .filter(|entry| entry.task.id == low_priority.get_id().unwrap())
@@ -352,7 +378,7 @@ fn get_schedule_atomic_taskを分割せず連続枠へ配置する() {
     let high_priority = task_with_schedule("高優先度", now + Duration::hours(6), 3600, 89);
     let repository = TestTaskRepository::new(vec![atomic_task.clone(), high_priority], now);
 
-    let actual = get_schedule(&repository).unwrap();
+    let actual = get_back_to_back_schedule(&repository).unwrap();
     let atomic_segments = actual
         .iter()
         .filter(|entry| entry.task.id == atomic_task.get_id().unwrap())
@@ -362,3 +388,92 @@ fn get_schedule_atomic_taskを分割せず連続枠へ配置する() {
     assert_eq!(atomic_segments[0].scheduled_start, now + Duration::hours(7));
     assert_eq!(atomic_segments[0].scheduled_end, now + Duration::hours(17));
 }
+
+fn segments_of(
+    schedule: &[ScheduledTaskView],
+    task: &TaskHandle,
+) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+    let id = task.get_id().unwrap();
+    schedule
+        .iter()
+        .filter(|entry| entry.task.id == id)
+        .map(|entry| (entry.scheduled_start, entry.scheduled_end))
+        .collect()
+}
+
+#[test]
+fn get_schedule_埋まっている時間を飛ばして空きへ置く() {
+    let now = fixed_now();
+    let task = task_with_schedule("会議後", now, 3600, 1);
+    let repository = TestTaskRepository::new(vec![task.clone()], now);
+    let mut busy = BusyIntervals(vec![(now, now + Duration::hours(1))]);
+
+    let actual = get_schedule(&repository, &mut busy, &BusinessDateTimePolicy::new(30)).unwrap();
+
+    assert_eq!(
+        segments_of(&actual, &task),
+        vec![(now + Duration::hours(1), now + Duration::hours(2))]
+    );
+}
+
+#[test]
+fn get_schedule_非atomic_taskを埋まっている時間の前後へ分割する() {
+    let now = fixed_now();
+    let task = task_with_schedule("分割", now, 3 * 3600, 1);
+    let repository = TestTaskRepository::new(vec![task.clone()], now);
+    let mut busy = BusyIntervals(vec![(now + Duration::hours(1), now + Duration::hours(2))]);
+
+    let actual = get_schedule(&repository, &mut busy, &BusinessDateTimePolicy::new(30)).unwrap();
+
+    assert_eq!(
+        segments_of(&actual, &task),
+        vec![
+            (now, now + Duration::hours(1)),
+            (now + Duration::hours(2), now + Duration::hours(4)),
+        ]
+    );
+}
+
+#[test]
+fn get_schedule_atomic_taskは埋まっている時間の後の連続した空きへ置く() {
+    let now = fixed_now();
+    let task = task_with_schedule("atomic", now, 3 * 3600, 1);
+    task.set_atomic(true).unwrap();
+    let repository = TestTaskRepository::new(vec![task.clone()], now);
+    let mut busy = BusyIntervals(vec![(now + Duration::hours(1), now + Duration::hours(2))]);
+
+    let actual = get_schedule(&repository, &mut busy, &BusinessDateTimePolicy::new(30)).unwrap();
+
+    assert_eq!(
+        segments_of(&actual, &task),
+        vec![(now + Duration::hours(2), now + Duration::hours(5))]
+    );
+}
+
+#[test]
+fn get_schedule_業務日の終わりをまたぐtaskを翌業務日の始まりへ続ける() {
+    let now = fixed_now();
+    let task = task_with_schedule("夜更け", now + Duration::hours(10), 4 * 3600, 1);
+    let repository = TestTaskRepository::new(vec![task.clone()], now);
+
+    let actual = get_schedule(
+        &repository,
+        &mut BusyIntervals(vec![]),
+        &BusinessDateTimePolicy::new(30),
+    )
+    .unwrap();
+
+    assert_eq!(
+        segments_of(&actual, &task),
+        vec![
+            (
+                Local.with_ymd_and_hms(2026, 8, 11, 22, 0, 0).unwrap(),
+                Local.with_ymd_and_hms(2026, 8, 12, 0, 30, 0).unwrap(),
+            ),
+            (
+                Local.with_ymd_and_hms(2026, 8, 12, 6, 0, 0).unwrap(),
+                Local.with_ymd_and_hms(2026, 8, 12, 7, 30, 0).unwrap(),
+            ),
+        ]
+    );
+}
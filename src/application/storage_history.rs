@@ -0,0 +1,367 @@
+use super::interface::{StorageHistoryError, StorageHistoryTrait};
+use chrono::{DateTime, Local};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+// 版を指定するUUIDの先頭部分は、この文字数以上を要求する
+pub const MIN_REVISION_PREFIX_LENGTH: usize = 4;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageRevision {
+    pub revision: Uuid,
+    pub saved_at: DateTime<Local>,
+    pub projects: Vec<String>,
+    pub changed_projects: Vec<String>,
+}
+
+// 差分計算用にtask木を平坦化した1 task分の値。
+// fieldsはchildrenを除く属性を、保存形式の表記のまま文字列で持つ
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TaskRevisionNode {
+    pub id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldRevisionChange {
+    pub field: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TaskRevisionChange {
+    Added {
+        id: String,
+        name: String,
+    },
+    Removed {
+        id: String,
+        name: String,
+    },
+    Changed {
+        id: String,
+        name: String,
+        fields: Vec<FieldRevisionChange>,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProjectRevisionDiff {
+    pub project: String,
+    pub changes: Vec<TaskRevisionChange>,
+}
+
+#[derive(Debug)]
+pub enum StorageHistoryUseCaseError {
+    Store(StorageHistoryError),
+    RevisionNotFound(String),
+    AmbiguousRevision(String),
+    ProjectNotFound { revision: Uuid, project: String },
+}
+
+impl fmt::Display for StorageHistoryUseCaseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Store(error) => error.fmt(formatter),
+            Self::RevisionNotFound(reference) => {
+                write!(formatter, "storage revision not found: {reference}")
+            }
+            Self::AmbiguousRevision(reference) => {
+                write!(formatter, "storage revision is ambiguous: {reference}")
+            }
+            Self::ProjectNotFound { revision, project } => write!(
+                formatter,
+                "project {project} is not recorded in storage revision {revision}"
+            ),
+        }
+    }
+}
+
+impl Error for StorageHistoryUseCaseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Store(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<StorageHistoryError> for StorageHistoryUseCaseError {
+    fn from(error: StorageHistoryError) -> Self {
+        Self::Store(error)
+    }
+}
+
+// 新しい版から順に返す
+pub fn list_storage_revisions(
+    history: &dyn StorageHistoryTrait,
+) -> Result<Vec<StorageRevision>, StorageHistoryError> {
+    let mut revisions = history.list_revisions()?;
+    revisions.reverse();
+    Ok(revisions)
+}
+
+// UUID全体、または一意に決まるMIN_REVISION_PREFIX_LENGTH文字以上の先頭部分で版を指定する
+pub fn resolve_storage_revision(
+    revisions: &[StorageRevision],
+    reference: &str,
+) -> Result<Uuid, StorageHistoryUseCaseError> {
+    let normalized = reference.trim().to_ascii_lowercase();
+    if normalized.len() < MIN_REVISION_PREFIX_LENGTH {
+        return Err(StorageHistoryUseCaseError::RevisionNotFound(
+            reference.to_string(),
+        ));
+    }
+    let mut candidates = revisions
+        .iter()
+        .map(|revision| revision.revision)
+        .filter(|revision| revision.hyphenated().to_string().starts_with(&normalized))
+        .collect::<BTreeSet<_>>()
+        .into_iter();
+    match (candidates.next(), candidates.next()) {
+        (Some(revision), None) => Ok(revision),
+        (Some(_), Some(_)) => Err(StorageHistoryUseCaseError::AmbiguousRevision(
+            reference.to_string(),
+        )),
+        (None, _) => Err(StorageHistoryUseCaseError::RevisionNotFound(
+            reference.to_string(),
+        )),
+    }
+}
+
+// fromの版からuntilの版(Noneなら現在の保存先)へのtask単位の差分を、project順に返す
+pub fn diff_storage_revisions(
+    history: &dyn StorageHistoryTrait,
+    from: Uuid,
+    until: Option<Uuid>,
+) -> Result<Vec<ProjectRevisionDiff>, StorageHistoryError> {
+    let before = history.read_revision_tasks(Some(from))?;
+    let after = history.read_revision_tasks(until)?;
+    let projects = before.keys().chain(after.keys()).collect::<BTreeSet<_>>();
+
+    Ok(projects
+        .into_iter()
+        .filter_map(|project| {
+            let changes = diff_task_nodes(
+                before.get(project).map_or(&[][..], Vec::as_slice),
+                after.get(project).map_or(&[][..], Vec::as_slice),
+            );
+            (!changes.is_empty()).then(|| ProjectRevisionDiff {
+                project: project.clone(),
+                changes,
+            })
+        })
+        .collect())
+}
+
+// 追加・変更はafterの順、削除はbeforeの順で、削除を最後に並べる
+pub fn diff_task_nodes(
+    before: &[TaskRevisionNode],
+    after: &[TaskRevisionNode],
+) -> Vec<TaskRevisionChange> {
+    let before_by_id = before
+        .iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect::<BTreeMap<_, _>>();
+    let after_ids = after
+        .iter()
+        .map(|node| node.id.as_str())
+        .collect::<BTreeSet<_>>();
+
+    let mut changes = Vec::new();
+    for node in after {
+        let Some(previous) = before_by_id.get(node.id.as_str()) else {
+            changes.push(TaskRevisionChange::Added {
+                id: node.id.clone(),
+                name: node.name.clone(),
+            });
+            continue;
+        };
+        let fields = diff_task_fields(previous, node);
+        if !fields.is_empty() {
+            changes.push(TaskRevisionChange::Changed {
+                id: node.id.clone(),
+                name: node.name.clone(),
+                fields,
+            });
+        }
+    }
+    for node in before {
+        if !after_ids.contains(node.id.as_str()) {
+            changes.push(TaskRevisionChange::Removed {
+                id: node.id.clone(),
+                name: node.name.clone(),
+            });
+        }
+    }
+    changes
+}
+
+fn diff_task_fields(
+    before: &TaskRevisionNode,
+    after: &TaskRevisionNode,
+) -> Vec<FieldRevisionChange> {
+    let mut changes = Vec::new();
+    if before.parent_id != after.parent_id {
+        changes.push(FieldRevisionChange {
+            field: "parent".to_string(),
+            before: before.parent_id.clone(),
+            after: after.parent_id.clone(),
+        });
+    }
+    let fields = before
+        .fields
+        .keys()
+        .chain(after.fields.keys())
+        .collect::<BTreeSet<_>>();
+    for field in fields {
+        let previous = before.fields.get(field);
+        let current = after.fields.get(field);
+        if previous != current {
+            changes.push(FieldRevisionChange {
+                field: field.clone(),
+                before: previous.cloned(),
+                after: current.cloned(),
+            });
+        }
+    }
+    changes
+}
+
+pub fn restore_storage_revision(
+    history: &mut dyn StorageHistoryTrait,
+    revision: Uuid,
+    project: Option<&str>,
+    now: DateTime<Local>,
+) -> Result<StorageRevision, StorageHistoryUseCaseError> {
+    let target = history
+        .list_revisions()?
+        .into_iter()
+        .find(|candidate| candidate.revision == revision)
+        .ok_or_else(|| StorageHistoryUseCaseError::RevisionNotFound(revision.to_string()))?;
+    if let Some(project) = project {
+        if !target.projects.iter().any(|recorded| recorded == project) {
+            return Err(StorageHistoryUseCaseError::ProjectNotFound {
+                revision,
+                project: project.to_string(),
+            });
+        }
+    }
+    Ok(history.restore_revision(revision, project, now)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn node(id: &str, parent_id: Option<&str>, fields: &[(&str, &str)]) -> TaskRevisionNode {
+        TaskRevisionNode {
+            id: id.to_string(),
+            name: format!("task-{id}"),
+            parent_id: parent_id.map(str::to_string),
+            fields: fields
+                .iter()
+                .map(|(field, value)| (field.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    fn revision(text: &str) -> StorageRevision {
+        StorageRevision {
+            revision: Uuid::parse_str(text).unwrap(),
+            saved_at: Local.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap(),
+            projects: vec!["20261018-a/project.yaml".to_string()],
+            changed_projects: vec![],
+        }
+    }
+
+    #[test]
+    fn diff_task_nodesは追加と削除と変更fieldと親の付け替えを返す() {
+        let before = [
+            node("root", None, &[("status", "todo")]),
+            node("moved", Some("root"), &[("priority", "5")]),
+            node("removed", Some("root"), &[]),
+        ];
+        let after = [
+            node("root", None, &[("status", "done")]),
+            node("added", Some("root"), &[]),
+            node(
+                "moved",
+                Some("added"),
+                &[("priority", "5"), ("deadline", "x")],
+            ),
+        ];
+
+        let actual = diff_task_nodes(&before, &after);
+
+        assert_eq!(
+            actual,
+            vec![
+                TaskRevisionChange::Changed {
+                    id: "root".to_string(),
+                    name: "task-root".to_string(),
+                    fields: vec![FieldRevisionChange {
+                        field: "status".to_string(),
+                        before: Some("todo".to_string()),
+                        after: Some("done".to_string()),
+                    }],
+                },
+                TaskRevisionChange::Added {
+                    id: "added".to_string(),
+                    name: "task-added".to_string(),
+                },
+                TaskRevisionChange::Changed {
+                    id: "moved".to_string(),
+                    name: "task-moved".to_string(),
+                    fields: vec![
+                        FieldRevisionChange {
+                            field: "parent".to_string(),
+                            before: Some("root".to_string()),
+                            after: Some("added".to_string()),
+                        },
+                        FieldRevisionChange {
+                            field: "deadline".to_string(),
+                            before: None,
+                            after: Some("x".to_string()),
+                        },
+                    ],
+                },
+                TaskRevisionChange::Removed {
+                    id: "removed".to_string(),
+                    name: "task-removed".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_storage_revisionは一意な先頭部分だけを受け付ける() {
+        let revisions = [
+            revision("abcd1111-0000-4000-8000-000000000000"),
+            revision("abcd2222-0000-4000-8000-000000000000"),
+        ];
+
+        assert_eq!(
+            resolve_storage_revision(&revisions, "ABCD1").unwrap(),
+            revisions[0].revision
+        );
+        assert!(matches!(
+            resolve_storage_revision(&revisions, "abcd"),
+            Err(StorageHistoryUseCaseError::AmbiguousRevision(_))
+        ));
+        assert!(matches!(
+            resolve_storage_revision(&revisions, "abc"),
+            Err(StorageHistoryUseCaseError::RevisionNotFound(_))
+        ));
+        assert!(matches!(
+            resolve_storage_revision(&revisions, "ffff"),
+            Err(StorageHistoryUseCaseError::RevisionNotFound(_))
+        ));
+    }
+}
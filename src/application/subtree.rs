@@ -0,0 +1,516 @@
+use crate::application::interface::TaskRepositoryTrait;
+use crate::application::task_use_case::{ApplicationError, TaskFactory};
+use crate::entity::task::{Status, TaskAttr, TaskHandle, TaskTreeError};
+use chrono::{DateTime, Local};
+use uuid::Uuid;
+
+// task_idの部分木をparent_idの最後の子へ移す。parent_idがNoneなら新しいprojectにする。
+// 別のprojectの根を移すと、そのprojectは移動先のprojectに取り込まれる
+pub fn move_subtree(
+    repository: &mut dyn TaskRepositoryTrait,
+    task_id: Uuid,
+    parent_id: Option<Uuid>,
+) -> Result<(), ApplicationError> {
+    let mut task = find_task(repository, task_id)?;
+    let is_project = task.parent().map_err(ApplicationError::TaskTree)?.is_none();
+    let Some(parent_id) = parent_id else {
+        if is_project {
+            return Err(ApplicationError::InvalidInput {
+                field: "parent_id",
+                reason: "task is already a project",
+            });
+        }
+        task.detach_from_parent()
+            .map_err(ApplicationError::TaskTree)?;
+        return repository
+            .start_new_project(task)
+            .map_err(ApplicationError::TaskTree);
+    };
+
+    let parent = find_task(repository, parent_id)?;
+    if contains_task(&task, &parent).map_err(ApplicationError::TaskTree)? {
+        return Err(ApplicationError::InvalidInput {
+            field: "parent_id",
+            reason: "must not be inside the moved subtree",
+        });
+    }
+    let parent_deadline = validate_deadlines_nest(&task, &parent)?;
+
+    if is_project {
+        repository
+            .remove_project(task_id)
+            .map_err(ApplicationError::TaskTree)?;
+    }
+    task.reparent_to(&parent)
+        .map_err(ApplicationError::TaskTree)?;
+    inherit_deadline(&task, parent_deadline)
+}
+
+// 部分木を新しいUUIDで複製し、状態は未着手に、実績は0に戻す。見積や〆切、繰り返しの設定は引き継ぐ。
+// 複製した部分木の根のidを返す
+pub fn copy_subtree(
+    repository: &mut dyn TaskRepositoryTrait,
+    task_id: Uuid,
+    parent_id: Option<Uuid>,
+    factory: &mut TaskFactory<'_>,
+) -> Result<Uuid, ApplicationError> {
+    let task = find_task(repository, task_id)?;
+    let Some(parent_id) = parent_id else {
+        let name = task.get_name().map_err(ApplicationError::TaskTree)?;
+        let copy = factory
+            .create_root_task(&name)
+            .map_err(ApplicationError::TaskTree)?;
+        copy_settings(&task, &copy).map_err(ApplicationError::TaskTree)?;
+        copy_children(&task, &copy, factory).map_err(ApplicationError::TaskTree)?;
+        let copy_id = copy.get_id().map_err(ApplicationError::TaskTree)?;
+        repository
+            .start_new_project(copy)
+            .map_err(ApplicationError::TaskTree)?;
+        return Ok(copy_id);
+    };
+
+    let parent = find_task(repository, parent_id)?;
+    let parent_deadline = validate_deadlines_nest(&task, &parent)?;
+    let copy = copy_task(&task, &parent, factory).map_err(ApplicationError::TaskTree)?;
+    inherit_deadline(&copy, parent_deadline)?;
+    copy.get_id().map_err(ApplicationError::TaskTree)
+}
+
+// source_task_idを兄弟のtask_idへ併合する。見積と実績は足し合わせ、sourceの子はtask_idの子の後ろに並べる。
+// 〆切は早いほうに揃え、子孫の〆切がそれより遅ければ併合しない
+pub fn merge_sibling_tasks(
+    repository: &mut dyn TaskRepositoryTrait,
+    task_id: Uuid,
+    source_task_id: Uuid,
+) -> Result<(), ApplicationError> {
+    if task_id == source_task_id {
+        return Err(ApplicationError::InvalidInput {
+            field: "source_task_id",
+            reason: "must differ from task_id",
+        });
+    }
+    let task = find_task(repository, task_id)?;
+    let mut source = find_task(repository, source_task_id)?;
+    let parent = task.parent().map_err(ApplicationError::TaskTree)?;
+    let source_parent = source.parent().map_err(ApplicationError::TaskTree)?;
+    let is_sibling = match (&parent, &source_parent) {
+        (Some(parent), Some(source_parent)) => parent.ptr_eq(source_parent),
+        _ => false,
+    };
+    if !is_sibling {
+        return Err(ApplicationError::InvalidInput {
+            field: "source_task_id",
+            reason: "must be a sibling of task_id",
+        });
+    }
+
+    let estimated_work_seconds = checked_sum(
+        task.get_estimated_work_seconds(),
+        source.get_estimated_work_seconds(),
+        "estimated work seconds overflow",
+    )?;
+    let actual_work_seconds = checked_sum(
+        task.get_actual_work_seconds(),
+        source.get_actual_work_seconds(),
+        "actual work seconds overflow",
+    )?;
+    let deadline_time = [&task, &source]
+        .into_iter()
+        .map(open_deadline)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ApplicationError::TaskTree)?
+        .into_iter()
+        .flatten()
+        .min();
+    let descendants = [&task, &source]
+        .into_iter()
+        .map(TaskHandle::get_children)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ApplicationError::TaskTree)?
+        .concat();
+    if let (Some(deadline_time), Some(latest)) = (
+        deadline_time,
+        latest_open_deadline(&descendants).map_err(ApplicationError::TaskTree)?,
+    ) {
+        if latest > deadline_time {
+            return Err(ApplicationError::InvalidInput {
+                field: "source_task_id",
+                reason:
+                    "deadlines in the merged subtree must not be later than the merged deadline",
+            });
+        }
+    }
+
+    for mut child in source.get_children().map_err(ApplicationError::TaskTree)? {
+        child
+            .reparent_to(&task)
+            .map_err(ApplicationError::TaskTree)?;
+    }
+    task.set_estimated_work_seconds(estimated_work_seconds)
+        .map_err(ApplicationError::TaskTree)?;
+    task.set_actual_work_seconds(actual_work_seconds)
+        .map_err(ApplicationError::TaskTree)?;
+    // どちらかが未完了なら、併合したtaskも未完了のまま残す
+    if task.get_status().map_err(ApplicationError::TaskTree)? == Status::Done
+        && source.get_status().map_err(ApplicationError::TaskTree)? != Status::Done
+    {
+        task.set_orig_status(
+            source
+                .get_orig_status()
+                .map_err(ApplicationError::TaskTree)?,
+        )
+        .map_err(ApplicationError::TaskTree)?;
+        task.set_end_time_opt(None)
+            .map_err(ApplicationError::TaskTree)?;
+    }
+    source
+        .detach_from_parent()
+        .map_err(ApplicationError::TaskTree)?;
+    inherit_deadline(&task, deadline_time)
+}
+
+fn find_task(
+    repository: &dyn TaskRepositoryTrait,
+    task_id: Uuid,
+) -> Result<TaskHandle, ApplicationError> {
+    repository
+        .get_by_id(task_id)
+        .map_err(ApplicationError::TaskTree)?
+        .ok_or(ApplicationError::TaskNotFound(task_id))
+}
+
+fn contains_task(subtree: &TaskHandle, task: &TaskHandle) -> Result<bool, TaskTreeError> {
+    let mut ancestor = Some(task.clone());
+    while let Some(current) = ancestor {
+        if current.ptr_eq(subtree) {
+            return Ok(true);
+        }
+        ancestor = current.parent()?;
+    }
+    Ok(false)
+}
+
+// 親が〆切を持つなら、移す部分木の未完了taskの〆切はそれより遅くてはならない。親の〆切を返す
+fn validate_deadlines_nest(
+    task: &TaskHandle,
+    parent: &TaskHandle,
+) -> Result<Option<DateTime<Local>>, ApplicationError> {
+    let parent_deadline = open_deadline(parent).map_err(ApplicationError::TaskTree)?;
+    if let (Some(parent_deadline), Some(latest)) = (
+        parent_deadline,
+        latest_open_deadline(std::slice::from_ref(task)).map_err(ApplicationError::TaskTree)?,
+    ) {
+        if latest > parent_deadline {
+            return Err(ApplicationError::InvalidInput {
+                field: "parent_id",
+                reason: "deadlines in the subtree must not be later than the parent deadline",
+            });
+        }
+    }
+    Ok(parent_deadline)
+}
+
+fn open_deadline(task: &TaskHandle) -> Result<Option<DateTime<Local>>, TaskTreeError> {
+    if task.get_status()? == Status::Done {
+        return Ok(None);
+    }
+    task.get_deadline_time_opt()
+}
+
+fn latest_open_deadline(tasks: &[TaskHandle]) -> Result<Option<DateTime<Local>>, TaskTreeError> {
+    let mut latest = None;
+    for task in tasks {
+        latest = latest.max(open_deadline(task)?);
+        latest = latest.max(latest_open_deadline(&task.get_children()?)?);
+    }
+    Ok(latest)
+}
+
+// 〆切のない子孫にも親の〆切を伝える
+fn inherit_deadline(
+    task: &TaskHandle,
+    deadline_time: Option<DateTime<Local>>,
+) -> Result<(), ApplicationError> {
+    if deadline_time.is_some() {
+        task.set_deadline_time_opt(deadline_time)
+            .map_err(ApplicationError::TaskTree)?;
+    }
+    Ok(())
+}
+
+fn checked_sum(
+    left: Result<i64, TaskTreeError>,
+    right: Result<i64, TaskTreeError>,
+    reason: &'static str,
+) -> Result<i64, ApplicationError> {
+    left.map_err(ApplicationError::TaskTree)?
+        .checked_add(right.map_err(ApplicationError::TaskTree)?)
+        .ok_or(ApplicationError::InvalidInput {
+            field: "source_task_id",
+            reason,
+        })
+}
+
+fn copy_task(
+    task: &TaskHandle,
+    parent: &TaskHandle,
+    factory: &mut TaskFactory<'_>,
+) -> Result<TaskHandle, TaskTreeError> {
+    let copy = parent.create_child(copied_attr(&task.get_attr()?, factory))?;
+    copy_children(task, &copy, factory)?;
+    Ok(copy)
+}
+
+fn copy_children(
+    task: &TaskHandle,
+    copy: &TaskHandle,
+    factory: &mut TaskFactory<'_>,
+) -> Result<(), TaskTreeError> {
+    for child in task.get_children()? {
+        copy_task(&child, copy, factory)?;
+    }
+    Ok(())
+}
+
+fn copied_attr(attr: &TaskAttr, factory: &mut TaskFactory<'_>) -> TaskAttr {
+    let mut copy = factory.create_task_attr(attr.get_name());
+    copy.set_priority(attr.get_priority());
+    copy.set_atomic(attr.get_atomic());
+    copy.set_deadline_time_opt(*attr.get_deadline_time_opt());
+    copy.set_estimated_work_seconds(attr.get_estimated_work_seconds());
+    copy.set_repetition_interval_days_opt(attr.get_repetition_interval_days_opt());
+    copy.set_repetition_anchor(attr.get_repetition_anchor());
+    copy.set_days_in_advance(attr.get_days_in_advance());
+    copy.set_project_category_opt(attr.get_project_category_opt());
+    copy.set_allowed_window_opt(attr.get_allowed_window_opt().clone());
+    copy
+}
+
+// projectの根として複製する場合は、作ったtaskに設定を書き写す
+fn copy_settings(task: &TaskHandle, copy: &TaskHandle) -> Result<(), TaskTreeError> {
+    let attr = task.get_attr()?;
+    copy.set_priority(attr.get_priority())?;
+    copy.set_atomic(attr.get_atomic())?;
+    copy.set_estimated_work_seconds(attr.get_estimated_work_seconds())?;
+    copy.set_repetition_interval_days_opt(attr.get_repetition_interval_days_opt())?;
+    copy.set_repetition_anchor(attr.get_repetition_anchor())?;
+    copy.set_days_in_advance(attr.get_days_in_advance())?;
+    copy.set_project_category_opt(attr.get_project_category_opt())?;
+    copy.set_allowed_window_opt(attr.get_allowed_window_opt().clone())?;
+    if let Some(deadline_time) = *attr.get_deadline_time_opt() {
+        copy.set_deadline_time_opt(Some(deadline_time))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{new_task_attr_at, new_task_handle_at, TestTaskRepository};
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap()
+    }
+
+    fn child_names(task: &TaskHandle) -> Vec<String> {
+        task.get_children()
+            .unwrap()
+            .iter()
+            .map(|child| child.get_name().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn 部分木を別のprojectのtaskの下へ移し親の〆切を伝える() {
+        let source = new_task_handle_at("source", now()).unwrap();
+        let moved = source
+            .create_child(new_task_attr_at("移す", now()))
+            .unwrap();
+        let grandchild = moved.create_child(new_task_attr_at("孫", now())).unwrap();
+        let destination = new_task_handle_at("destination", now()).unwrap();
+        destination
+            .set_deadline_time_opt(Some(now() + Duration::days(3)))
+            .unwrap();
+        let (moved_id, destination_id) = (moved.get_id().unwrap(), destination.get_id().unwrap());
+        let mut repository =
+            TestTaskRepository::new(vec![source.clone(), destination.clone()], now());
+
+        move_subtree(&mut repository, moved_id, Some(destination_id)).unwrap();
+
+        assert!(child_names(&source).is_empty());
+        assert_eq!(child_names(&destination), vec!["移す"]);
+        assert_eq!(
+            grandchild.get_deadline_time_opt().unwrap(),
+            Some(now() + Duration::days(3))
+        );
+    }
+
+    #[test]
+    fn 部分木を新しいprojectにし根のprojectは移動先に取り込む() {
+        let project = new_task_handle_at("project", now()).unwrap();
+        let moved = project
+            .create_child(new_task_attr_at("独立", now()))
+            .unwrap();
+        let other = new_task_handle_at("other", now()).unwrap();
+        let (moved_id, project_id, other_id) = (
+            moved.get_id().unwrap(),
+            project.get_id().unwrap(),
+            other.get_id().unwrap(),
+        );
+        let mut repository = TestTaskRepository::new(vec![project.clone(), other], now());
+
+        move_subtree(&mut repository, moved_id, None).unwrap();
+        assert!(repository
+            .get_by_id(moved_id)
+            .unwrap()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .is_none());
+        assert_eq!(repository.get_all_projects().len(), 3);
+
+        move_subtree(&mut repository, project_id, Some(other_id)).unwrap();
+        assert_eq!(repository.get_all_projects().len(), 2);
+        assert_eq!(
+            project.parent().unwrap().unwrap().get_id().unwrap(),
+            other_id
+        );
+    }
+
+    #[test]
+    fn 自分の子孫の下へは移さず遅い〆切も受け付けない() {
+        let project = new_task_handle_at("project", now()).unwrap();
+        let moved = project
+            .create_child(new_task_attr_at("移す", now()))
+            .unwrap();
+        moved
+            .set_deadline_time_opt(Some(now() + Duration::days(5)))
+            .unwrap();
+        let descendant = moved.create_child(new_task_attr_at("孫", now())).unwrap();
+        let strict = project
+            .create_child(new_task_attr_at("急ぎ", now()))
+            .unwrap();
+        strict
+            .set_deadline_time_opt(Some(now() + Duration::days(1)))
+            .unwrap();
+        let moved_id = moved.get_id().unwrap();
+        let mut repository = TestTaskRepository::new(vec![project.clone()], now());
+
+        let cycle = move_subtree(
+            &mut repository,
+            moved_id,
+            Some(descendant.get_id().unwrap()),
+        )
+        .unwrap_err();
+        let deadline =
+            move_subtree(&mut repository, moved_id, Some(strict.get_id().unwrap())).unwrap_err();
+
+        assert!(matches!(
+            cycle,
+            ApplicationError::InvalidInput { field: "parent_id", reason } if reason.contains("inside")
+        ));
+        assert!(matches!(
+            deadline,
+            ApplicationError::InvalidInput { field: "parent_id", reason } if reason.contains("deadline")
+        ));
+        assert_eq!(child_names(&project), vec!["移す", "急ぎ"]);
+    }
+
+    #[test]
+    fn 部分木を新しいidで複製し状態と実績を戻す() {
+        let project = new_task_handle_at("project", now()).unwrap();
+        let template = project
+            .create_child(new_task_attr_at("手順", now()))
+            .unwrap();
+        template.set_estimated_work_seconds(30 * 60).unwrap();
+        let step = template
+            .create_child(new_task_attr_at("段階", now()))
+            .unwrap();
+        step.set_actual_work_seconds(10 * 60).unwrap();
+        step.set_orig_status(Status::Done).unwrap();
+        let template_id = template.get_id().unwrap();
+        let project_id = project.get_id().unwrap();
+        let mut repository = TestTaskRepository::new(vec![project.clone()], now());
+        let mut sequence = 0x3900;
+        let mut next_id = || {
+            sequence += 1;
+            Uuid::from_u128(sequence)
+        };
+        let mut factory = TaskFactory::new(now(), &mut next_id);
+
+        let copy_id =
+            copy_subtree(&mut repository, template_id, Some(project_id), &mut factory).unwrap();
+        let project_copy_id =
+            copy_subtree(&mut repository, template_id, None, &mut factory).unwrap();
+
+        assert_eq!(copy_id, Uuid::from_u128(0x3901));
+        assert_eq!(child_names(&project), vec!["手順", "手順"]);
+        let copy = repository.get_by_id(copy_id).unwrap().unwrap();
+        assert_eq!(copy.get_estimated_work_seconds().unwrap(), 30 * 60);
+        let copied_step = &copy.get_children().unwrap()[0];
+        assert_ne!(copied_step.get_id().unwrap(), step.get_id().unwrap());
+        assert_eq!(copied_step.get_status().unwrap(), Status::Todo);
+        assert_eq!(copied_step.get_actual_work_seconds().unwrap(), 0);
+        assert_eq!(step.get_status().unwrap(), Status::Done);
+        let project_copy = repository.get_by_id(project_copy_id).unwrap().unwrap();
+        assert!(project_copy.parent().unwrap().is_none());
+        assert_eq!(child_names(&project_copy), vec!["段階"]);
+    }
+
+    #[test]
+    fn 兄弟のtaskを併合し見積と実績を足して子を並べる() {
+        let project = new_task_handle_at("project", now()).unwrap();
+        let task = project
+            .create_child(new_task_attr_at("残す", now()))
+            .unwrap();
+        task.set_estimated_work_seconds(20 * 60).unwrap();
+        task.set_actual_work_seconds(5 * 60).unwrap();
+        task.create_child(new_task_attr_at("a", now())).unwrap();
+        let source = project
+            .create_child(new_task_attr_at("併合", now()))
+            .unwrap();
+        source.set_estimated_work_seconds(10 * 60).unwrap();
+        source.set_actual_work_seconds(3 * 60).unwrap();
+        source
+            .set_deadline_time_opt(Some(now() + Duration::days(2)))
+            .unwrap();
+        source.create_child(new_task_attr_at("b", now())).unwrap();
+        let (task_id, source_id) = (task.get_id().unwrap(), source.get_id().unwrap());
+        let mut repository = TestTaskRepository::new(vec![project.clone()], now());
+
+        merge_sibling_tasks(&mut repository, task_id, source_id).unwrap();
+
+        assert_eq!(child_names(&project), vec!["残す"]);
+        assert_eq!(child_names(&task), vec!["a", "b"]);
+        assert_eq!(task.get_estimated_work_seconds().unwrap(), 30 * 60);
+        assert_eq!(task.get_actual_work_seconds().unwrap(), 8 * 60);
+        assert_eq!(
+            task.get_children().unwrap()[0]
+                .get_deadline_time_opt()
+                .unwrap(),
+            Some(now() + Duration::days(2))
+        );
+        assert!(repository.get_by_id(source_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn 兄弟でないtaskは併合しない() {
+        let project = new_task_handle_at("project", now()).unwrap();
+        let task = project.create_child(new_task_attr_at("親", now())).unwrap();
+        let nested = task.create_child(new_task_attr_at("子", now())).unwrap();
+        let (task_id, nested_id) = (task.get_id().unwrap(), nested.get_id().unwrap());
+        let mut repository = TestTaskRepository::new(vec![project], now());
+
+        let error = merge_sibling_tasks(&mut repository, task_id, nested_id).unwrap_err();
+
+        assert!(matches!(
+            error,
+            ApplicationError::InvalidInput {
+                field: "source_task_id",
+                reason: "must be a sibling of task_id"
+            }
+        ));
+        assert_eq!(child_names(&task), vec!["子"]);
+    }
+}
@@ -0,0 +1,424 @@
+use crate::application::interface::TaskRepositoryTrait;
+use crate::application::task_use_case::{
+    estimated_work_seconds_from_minutes, validate_task_name, ApplicationError, TaskFactory,
+};
+use crate::entity::task::{Status, TaskHandle};
+use chrono::{DateTime, Local};
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+// 外部の形式から読み込んだtaskの木。〆切のない子は、作る時に親の〆切を引き継ぐ。
+// statusはpending_untilがあれば保留になるので、書き出した木のように状態だけを持つ場合に使う
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedTask {
+    pub name: String,
+    pub status: Status,
+    pub estimated_work_minutes: Option<i64>,
+    pub actual_work_minutes: Option<i64>,
+    pub deadline_time: Option<DateTime<Local>>,
+    pub pending_until: Option<DateTime<Local>>,
+    pub children: Vec<ImportedTask>,
+}
+
+impl ImportedTask {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: Status::Todo,
+            estimated_work_minutes: None,
+            actual_work_minutes: None,
+            deadline_time: None,
+            pending_until: None,
+            children: vec![],
+        }
+    }
+
+    pub fn task_count(&self) -> usize {
+        1 + self
+            .children
+            .iter()
+            .map(ImportedTask::task_count)
+            .sum::<usize>()
+    }
+}
+
+// 取り込む前に見せる木。完了済みとして読み飛ばしたtaskの数も持つ
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TaskOutline {
+    pub tasks: Vec<ImportedTask>,
+    pub skipped_done_count: usize,
+}
+
+impl TaskOutline {
+    pub fn task_count(&self) -> usize {
+        self.tasks.iter().map(ImportedTask::task_count).sum()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TaskOutlineError {
+    UnknownFormat(PathBuf),
+    Read { path: PathBuf, message: String },
+    Line { line: usize, reason: String },
+}
+
+impl fmt::Display for TaskOutlineError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat(path) => write!(
+                formatter,
+                "cannot determine the import format of {}: use .md, .org or .txt",
+                path.display()
+            ),
+            Self::Read { path, message } => {
+                write!(formatter, "failed to read {}: {message}", path.display())
+            }
+            Self::Line { line, reason } => write!(formatter, "line {line}: {reason}"),
+        }
+    }
+}
+
+impl Error for TaskOutlineError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TaskImportError {
+    Outline(TaskOutlineError),
+    Application(ApplicationError),
+}
+
+impl fmt::Display for TaskImportError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Outline(error) => error.fmt(formatter),
+            Self::Application(error) => error.fmt(formatter),
+        }
+    }
+}
+
+impl Error for TaskImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Outline(error) => Some(error),
+            Self::Application(error) => Some(error),
+        }
+    }
+}
+
+impl From<TaskOutlineError> for TaskImportError {
+    fn from(error: TaskOutlineError) -> Self {
+        Self::Outline(error)
+    }
+}
+
+impl From<ApplicationError> for TaskImportError {
+    fn from(error: ApplicationError) -> Self {
+        Self::Application(error)
+    }
+}
+
+// 木をparent_idの最後の子として、parent_idがNoneなら新しいprojectとして作り、作った根のidを返す。
+// 先に木全体を検証し、途中で失敗して一部だけ作られることはない
+pub fn import_task_outline(
+    repository: &mut dyn TaskRepositoryTrait,
+    outline: &TaskOutline,
+    parent_id: Option<Uuid>,
+    factory: &mut TaskFactory<'_>,
+) -> Result<Vec<Uuid>, ApplicationError> {
+    if outline.tasks.is_empty() {
+        return Err(ApplicationError::InvalidInput {
+            field: "tasks",
+            reason: "must not be empty",
+        });
+    }
+    let parent = parent_id
+        .map(|parent_id| {
+            repository
+                .get_by_id(parent_id)
+                .map_err(ApplicationError::TaskTree)?
+                .ok_or(ApplicationError::TaskNotFound(parent_id))
+        })
+        .transpose()?;
+    let parent_deadline = match &parent {
+        Some(parent)
+            if parent.get_status().map_err(ApplicationError::TaskTree)? != Status::Done =>
+        {
+            parent
+                .get_deadline_time_opt()
+                .map_err(ApplicationError::TaskTree)?
+        }
+        _ => None,
+    };
+    for task in &outline.tasks {
+        validate_imported_task(task, parent_deadline, "parent_id")?;
+    }
+
+    let mut task_ids = vec![];
+    for imported in &outline.tasks {
+        let task = match &parent {
+            Some(parent) => parent
+                .create_child(factory.create_task_attr(&imported.name))
+                .map_err(ApplicationError::TaskTree)?,
+            None => {
+                let task = factory
+                    .create_root_task(&imported.name)
+                    .map_err(ApplicationError::TaskTree)?;
+                task.set_priority(5).map_err(ApplicationError::TaskTree)?;
+                task
+            }
+        };
+        let deadline_time = apply_imported_settings(&task, imported, parent_deadline)?;
+        create_imported_children(&task, imported, deadline_time, factory)?;
+        task_ids.push(task.get_id().map_err(ApplicationError::TaskTree)?);
+        if parent.is_none() {
+            repository
+                .start_new_project(task)
+                .map_err(ApplicationError::TaskTree)?;
+        }
+    }
+    Ok(task_ids)
+}
+
+fn validate_imported_task(
+    task: &ImportedTask,
+    parent_deadline: Option<DateTime<Local>>,
+    field: &'static str,
+) -> Result<(), ApplicationError> {
+    validate_task_name(&task.name, "name")?;
+    if let Some(estimated_work_minutes) = task.estimated_work_minutes {
+        estimated_work_seconds_from_minutes(estimated_work_minutes)?;
+    }
+    if let Some(actual_work_minutes) = task.actual_work_minutes {
+        actual_work_seconds_from_minutes(actual_work_minutes)?;
+    }
+    if let (Some(parent_deadline), Some(deadline_time)) = (parent_deadline, task.deadline_time) {
+        if deadline_time > parent_deadline {
+            return Err(ApplicationError::InvalidInput {
+                field,
+                reason: "deadlines in the imported tree must not be later than the parent deadline",
+            });
+        }
+    }
+    let deadline_time = task.deadline_time.or(parent_deadline);
+    for child in &task.children {
+        validate_imported_task(child, deadline_time, "tasks")?;
+    }
+    Ok(())
+}
+
+fn actual_work_seconds_from_minutes(minutes: i64) -> Result<i64, ApplicationError> {
+    if minutes < 0 {
+        return Err(ApplicationError::InvalidInput {
+            field: "actual_work_minutes",
+            reason: "must not be negative",
+        });
+    }
+
+    minutes
+        .checked_mul(60)
+        .ok_or(ApplicationError::InvalidInput {
+            field: "actual_work_minutes",
+            reason: "seconds conversion overflow",
+        })
+}
+
+// 見積・実績・〆切・状態を設定し、子へ伝える〆切を返す。
+// 完了日時は書き出した木にもないので、取り込んだ完了taskには付けない
+fn apply_imported_settings(
+    task: &TaskHandle,
+    imported: &ImportedTask,
+    parent_deadline: Option<DateTime<Local>>,
+) -> Result<Option<DateTime<Local>>, ApplicationError> {
+    if let Some(estimated_work_minutes) = imported.estimated_work_minutes {
+        task.set_estimated_work_seconds(estimated_work_seconds_from_minutes(
+            estimated_work_minutes,
+        )?)
+        .map_err(ApplicationError::TaskTree)?;
+    }
+    if let Some(actual_work_minutes) = imported.actual_work_minutes {
+        task.set_actual_work_seconds(actual_work_seconds_from_minutes(actual_work_minutes)?)
+            .map_err(ApplicationError::TaskTree)?;
+    }
+    let deadline_time = imported.deadline_time.or(parent_deadline);
+    if deadline_time.is_some() {
+        task.set_deadline_time_opt(deadline_time)
+            .map_err(ApplicationError::TaskTree)?;
+    }
+    if let Some(pending_until) = imported.pending_until {
+        task.set_pending_until(pending_until)
+            .map_err(ApplicationError::TaskTree)?;
+    }
+    let status = match imported.status {
+        Status::Todo if imported.pending_until.is_some() => Status::Pending,
+        status => status,
+    };
+    if status != Status::Todo {
+        task.set_orig_status(status)
+            .map_err(ApplicationError::TaskTree)?;
+    }
+    Ok(deadline_time)
+}
+
+fn create_imported_children(
+    task: &TaskHandle,
+    imported: &ImportedTask,
+    deadline_time: Option<DateTime<Local>>,
+    factory: &mut TaskFactory<'_>,
+) -> Result<(), ApplicationError> {
+    for imported_child in &imported.children {
+        let child = task
+            .create_child(factory.create_task_attr(&imported_child.name))
+            .map_err(ApplicationError::TaskTree)?;
+        let child_deadline = apply_imported_settings(&child, imported_child, deadline_time)?;
+        create_imported_children(&child, imported_child, child_deadline, factory)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{new_task_handle_at, TestTaskRepository};
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap()
+    }
+
+    fn child_names(task: &TaskHandle) -> Vec<String> {
+        task.get_children()
+            .unwrap()
+            .iter()
+            .map(|child| child.get_name().unwrap())
+            .collect()
+    }
+
+    fn outline(tasks: Vec<ImportedTask>) -> TaskOutline {
+        TaskOutline {
+            tasks,
+            skipped_done_count: 0,
+        }
+    }
+
+    #[test]
+    fn 読み込んだ木を新しいprojectとして作り見積と保留と親の〆切を設定する() {
+        let deadline = now() + Duration::days(5);
+        let mut project = ImportedTask::new("旅行の準備");
+        project.deadline_time = Some(deadline);
+        let mut ticket = ImportedTask::new("切符を買う");
+        ticket.estimated_work_minutes = Some(30);
+        ticket.pending_until = Some(now() + Duration::days(1));
+        project.children = vec![ticket, ImportedTask::new("荷造り")];
+        let mut repository = TestTaskRepository::new(vec![], now());
+        let mut next_id = Uuid::new_v4;
+        let mut factory = TaskFactory::new(now(), &mut next_id);
+
+        let task_ids = import_task_outline(
+            &mut repository,
+            &outline(vec![project, ImportedTask::new("別の用事")]),
+            None,
+            &mut factory,
+        )
+        .unwrap();
+
+        assert_eq!(task_ids.len(), 2);
+        let root = repository.get_by_id(task_ids[0]).unwrap().unwrap();
+        assert_eq!(root.get_priority().unwrap(), 5);
+        assert_eq!(child_names(&root), vec!["切符を買う", "荷造り"]);
+        let children = root.get_children().unwrap();
+        assert_eq!(children[0].get_estimated_work_seconds().unwrap(), 30 * 60);
+        assert_eq!(children[0].get_orig_status().unwrap(), Status::Pending);
+        assert_eq!(children[1].get_deadline_time_opt().unwrap(), Some(deadline));
+        assert!(repository.get_by_id(task_ids[1]).unwrap().is_some());
+    }
+
+    #[test]
+    fn 書き出した状態と実績を取り込んだtaskに戻す() {
+        let mut project = ImportedTask::new("引っ越し");
+        let mut done = ImportedTask::new("業者を選ぶ");
+        done.status = Status::Done;
+        done.actual_work_minutes = Some(40);
+        let mut waiting = ImportedTask::new("見積の返事");
+        waiting.status = Status::Pending;
+        project.children = vec![done, waiting];
+        let mut repository = TestTaskRepository::new(vec![], now());
+        let mut next_id = Uuid::new_v4;
+        let mut factory = TaskFactory::new(now(), &mut next_id);
+
+        let task_ids =
+            import_task_outline(&mut repository, &outline(vec![project]), None, &mut factory)
+                .unwrap();
+
+        let root = repository.get_by_id(task_ids[0]).unwrap().unwrap();
+        assert_eq!(root.get_orig_status().unwrap(), Status::Todo);
+        let children = root.get_children().unwrap();
+        assert_eq!(children[0].get_orig_status().unwrap(), Status::Done);
+        assert_eq!(children[0].get_actual_work_seconds().unwrap(), 40 * 60);
+        assert_eq!(children[0].get_end_time_opt().unwrap(), None);
+        assert_eq!(children[1].get_orig_status().unwrap(), Status::Pending);
+        assert_eq!(children[1].get_actual_work_seconds().unwrap(), 0);
+    }
+
+    #[test]
+    fn 親の〆切より遅い〆切を含む木は何も作らずに拒否する() {
+        let parent = new_task_handle_at("親", now()).unwrap();
+        parent
+            .set_deadline_time_opt(Some(now() + Duration::days(1)))
+            .unwrap();
+        let parent_id = parent.get_id().unwrap();
+        let mut late = ImportedTask::new("遅い");
+        late.deadline_time = Some(now() + Duration::days(2));
+        let mut wrapper = ImportedTask::new("まとめ");
+        wrapper.children = vec![late];
+        let mut repository = TestTaskRepository::new(vec![parent.clone()], now());
+        let mut next_id = Uuid::new_v4;
+        let mut factory = TaskFactory::new(now(), &mut next_id);
+
+        let result = import_task_outline(
+            &mut repository,
+            &outline(vec![ImportedTask::new("先に作られない"), wrapper]),
+            Some(parent_id),
+            &mut factory,
+        );
+
+        assert_eq!(
+            result,
+            Err(ApplicationError::InvalidInput {
+                field: "tasks",
+                reason: "deadlines in the imported tree must not be later than the parent deadline",
+            })
+        );
+        assert!(child_names(&parent).is_empty());
+    }
+
+    #[test]
+    fn 指定したtaskの下に作ると親の〆切を引き継ぐ() {
+        let parent = new_task_handle_at("親", now()).unwrap();
+        let deadline = now() + Duration::days(3);
+        parent.set_deadline_time_opt(Some(deadline)).unwrap();
+        let parent_id = parent.get_id().unwrap();
+        let mut repository = TestTaskRepository::new(vec![parent.clone()], now());
+        let mut next_id = Uuid::new_v4;
+        let mut factory = TaskFactory::new(now(), &mut next_id);
+
+        let task_ids = import_task_outline(
+            &mut repository,
+            &outline(vec![ImportedTask::new("子")]),
+            Some(parent_id),
+            &mut factory,
+        )
+        .unwrap();
+
+        assert_eq!(child_names(&parent), vec!["子"]);
+        let child = repository.get_by_id(task_ids[0]).unwrap().unwrap();
+        assert_eq!(child.get_deadline_time_opt().unwrap(), Some(deadline));
+        assert_eq!(
+            import_task_outline(&mut repository, &outline(vec![]), None, &mut factory),
+            Err(ApplicationError::InvalidInput {
+                field: "tasks",
+                reason: "must not be empty",
+            })
+        );
+    }
+}
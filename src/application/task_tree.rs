@@ -0,0 +1,292 @@
+use crate::application::interface::{FreeTimeManagerTrait, TaskRepositoryTrait};
+use crate::application::schedule_use_case::get_schedule;
+use crate::application::task_use_case::ApplicationError;
+use crate::application::task_view::TaskView;
+use crate::entity::datetime::BusinessDateTimePolicy;
+use crate::entity::task::{Status, TaskHandle, TaskSnapshot, TaskTreeError};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// 部分木全体の集計。残りの見積と〆切は未完了のtaskだけから数え、実績は完了したtaskも含める
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct TaskTreeRollupView {
+    pub remaining_estimated_work_seconds: i64,
+    pub actual_work_seconds: i64,
+    pub done_leaf_count: usize,
+    pub leaf_count: usize,
+    pub earliest_deadline_time: Option<DateTime<Local>>,
+    pub scheduled_finish: Option<DateTime<Local>>,
+}
+
+// 深さの上限で子を省いたnodeはchildren_truncatedを立てる。集計は省いた子孫も含む
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TaskTreeNodeView {
+    pub task: TaskView,
+    pub rollup: TaskTreeRollupView,
+    pub children_truncated: bool,
+    pub children: Vec<TaskTreeNodeView>,
+}
+
+// task_idを根とする部分木を、max_depth段下の子まで入れ子で返す。max_depthがなければすべて返す。
+// scheduled_finishはget_scheduleと同じく埋まっている時間と夜を避けた予定から求める
+pub fn get_task_tree(
+    repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
+    task_id: Uuid,
+    max_depth: Option<usize>,
+) -> Result<TaskTreeNodeView, ApplicationError> {
+    let mut scheduled_finishes: HashMap<Uuid, DateTime<Local>> = HashMap::new();
+    for scheduled in get_schedule(repository, free_time_manager, policy)? {
+        let finish = scheduled_finishes
+            .entry(scheduled.task.id)
+            .or_insert(scheduled.scheduled_end);
+        *finish = (*finish).max(scheduled.scheduled_end);
+    }
+    build_tree(repository, task_id, max_depth, &scheduled_finishes)
+}
+
+// 予定を組まずに部分木を返す。scheduled_finishは常にNoneになる
+pub fn get_task_tree_without_schedule(
+    repository: &dyn TaskRepositoryTrait,
+    task_id: Uuid,
+    max_depth: Option<usize>,
+) -> Result<TaskTreeNodeView, ApplicationError> {
+    build_tree(repository, task_id, max_depth, &HashMap::new())
+}
+
+fn build_tree(
+    repository: &dyn TaskRepositoryTrait,
+    task_id: Uuid,
+    max_depth: Option<usize>,
+    scheduled_finishes: &HashMap<Uuid, DateTime<Local>>,
+) -> Result<TaskTreeNodeView, ApplicationError> {
+    let task = repository
+        .get_by_id(task_id)
+        .map_err(ApplicationError::TaskTree)?
+        .ok_or(ApplicationError::TaskNotFound(task_id))?;
+    let snapshot = task.snapshot().map_err(ApplicationError::TaskTree)?;
+    build_node(&task, &snapshot, max_depth, scheduled_finishes).map_err(ApplicationError::TaskTree)
+}
+
+fn build_node(
+    task: &TaskHandle,
+    snapshot: &TaskSnapshot,
+    remaining_depth: Option<usize>,
+    scheduled_finishes: &HashMap<Uuid, DateTime<Local>>,
+) -> Result<TaskTreeNodeView, TaskTreeError> {
+    let children = match remaining_depth {
+        Some(0) => Vec::new(),
+        _ => task
+            .get_children()?
+            .iter()
+            .zip(snapshot.children())
+            .map(|(child, child_snapshot)| {
+                build_node(
+                    child,
+                    child_snapshot,
+                    remaining_depth.map(|depth| depth - 1),
+                    scheduled_finishes,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    Ok(TaskTreeNodeView {
+        task: TaskView::try_from(task)?,
+        rollup: rollup(snapshot, scheduled_finishes),
+        children_truncated: children.len() < snapshot.children().len(),
+        children,
+    })
+}
+
+fn rollup(
+    snapshot: &TaskSnapshot,
+    scheduled_finishes: &HashMap<Uuid, DateTime<Local>>,
+) -> TaskTreeRollupView {
+    let attr = snapshot.attr();
+    let open = *attr.get_status() != Status::Done;
+    let is_leaf = snapshot.children().is_empty();
+    let mut total = TaskTreeRollupView {
+        remaining_estimated_work_seconds: if open {
+            snapshot.estimated_work_seconds()
+        } else {
+            0
+        },
+        actual_work_seconds: attr.get_actual_work_seconds(),
+        done_leaf_count: usize::from(is_leaf && !open),
+        leaf_count: usize::from(is_leaf),
+        earliest_deadline_time: attr.get_deadline_time_opt().filter(|_| open),
+        scheduled_finish: scheduled_finishes.get(attr.get_id()).copied(),
+    };
+    for child in snapshot.children() {
+        let child_total = rollup(child, scheduled_finishes);
+        total.remaining_estimated_work_seconds += child_total.remaining_estimated_work_seconds;
+        total.actual_work_seconds += child_total.actual_work_seconds;
+        total.done_leaf_count += child_total.done_leaf_count;
+        total.leaf_count += child_total.leaf_count;
+        total.earliest_deadline_time = total
+            .earliest_deadline_time
+            .into_iter()
+            .chain(child_total.earliest_deadline_time)
+            .min();
+        total.scheduled_finish = total
+            .scheduled_finish
+            .into_iter()
+            .chain(child_total.scheduled_finish)
+            .max();
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        new_task_attr_at, new_task_handle_at, BusyIntervals, TestTaskRepository,
+    };
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap()
+    }
+
+    fn policy() -> BusinessDateTimePolicy {
+        BusinessDateTimePolicy::new(30)
+    }
+
+    fn no_busy() -> BusyIntervals {
+        BusyIntervals(vec![])
+    }
+
+    #[test]
+    fn 部分木の見積と実績と葉の完了数と〆切を集計する() {
+        let project = new_task_handle_at("project", now()).unwrap();
+        project.set_estimated_work_seconds(0).unwrap();
+        let draft = project
+            .create_child(new_task_attr_at("下書き", now()))
+            .unwrap();
+        draft.set_estimated_work_seconds(0).unwrap();
+        let outline = draft.create_child(new_task_attr_at("構成", now())).unwrap();
+        outline.set_estimated_work_seconds(30 * 60).unwrap();
+        outline.set_actual_work_seconds(10 * 60).unwrap();
+        outline
+            .set_deadline_time_opt(Some(now() + Duration::days(2)))
+            .unwrap();
+        let done = draft.create_child(new_task_attr_at("調査", now())).unwrap();
+        done.set_estimated_work_seconds(60 * 60).unwrap();
+        done.set_actual_work_seconds(45 * 60).unwrap();
+        done.set_deadline_time_opt(Some(now() + Duration::days(1)))
+            .unwrap();
+        done.set_orig_status(Status::Done).unwrap();
+        let review = project
+            .create_child(new_task_attr_at("レビュー", now()))
+            .unwrap();
+        review.set_estimated_work_seconds(15 * 60).unwrap();
+        let project_id = project.get_id().unwrap();
+        let repository = TestTaskRepository::new(vec![project], now());
+
+        let tree = get_task_tree(&repository, &mut no_busy(), &policy(), project_id, None).unwrap();
+
+        assert_eq!(tree.rollup.remaining_estimated_work_seconds, 45 * 60);
+        assert_eq!(tree.rollup.actual_work_seconds, 55 * 60);
+        assert_eq!(
+            (tree.rollup.done_leaf_count, tree.rollup.leaf_count),
+            (1, 3)
+        );
+        assert_eq!(
+            tree.rollup.earliest_deadline_time,
+            Some(now() + Duration::days(2))
+        );
+        assert_eq!(
+            tree.rollup.scheduled_finish,
+            tree.children
+                .iter()
+                .filter_map(|child| child.rollup.scheduled_finish)
+                .max()
+        );
+        assert!(tree.rollup.scheduled_finish.is_some());
+        assert_eq!(
+            tree.children
+                .iter()
+                .map(|child| (child.task.name.as_str(), child.rollup.leaf_count))
+                .collect::<Vec<_>>(),
+            vec![("下書き", 2), ("レビュー", 1)]
+        );
+    }
+
+    #[test]
+    fn 予定の終わりは埋まっている時間を避けた予定から求め予定なしの木では空にする() {
+        let project = new_task_handle_at("project", now()).unwrap();
+        project.set_estimated_work_seconds(0).unwrap();
+        let child = project
+            .create_child(new_task_attr_at("作業", now()))
+            .unwrap();
+        child.set_estimated_work_seconds(60 * 60).unwrap();
+        let project_id = project.get_id().unwrap();
+        let repository = TestTaskRepository::new(vec![project], now());
+
+        let tree = get_task_tree(
+            &repository,
+            &mut BusyIntervals(vec![(now(), now() + Duration::hours(2))]),
+            &policy(),
+            project_id,
+            None,
+        )
+        .unwrap();
+        let unscheduled = get_task_tree_without_schedule(&repository, project_id, None).unwrap();
+
+        assert_eq!(
+            tree.rollup.scheduled_finish,
+            Some(now() + Duration::hours(3))
+        );
+        assert_eq!(unscheduled.rollup.scheduled_finish, None);
+        assert_eq!(unscheduled.rollup.leaf_count, 1);
+    }
+
+    #[test]
+    fn 深さの上限で子を省いても集計は子孫全体から数える() {
+        let project = new_task_handle_at("project", now()).unwrap();
+        let child = project.create_child(new_task_attr_at("子", now())).unwrap();
+        child
+            .create_child(new_task_attr_at("孫", now()))
+            .unwrap()
+            .set_estimated_work_seconds(20 * 60)
+            .unwrap();
+        let project_id = project.get_id().unwrap();
+        let repository = TestTaskRepository::new(vec![project], now());
+
+        let tree =
+            get_task_tree(&repository, &mut no_busy(), &policy(), project_id, Some(1)).unwrap();
+
+        assert!(!tree.children_truncated);
+        assert!(tree.children[0].children_truncated);
+        assert!(tree.children[0].children.is_empty());
+        assert_eq!(tree.children[0].rollup.leaf_count, 1);
+        assert_eq!(
+            tree.children[0].rollup.remaining_estimated_work_seconds,
+            child.get_estimated_work_seconds().unwrap() + 20 * 60
+        );
+    }
+
+    #[test]
+    fn 存在しないtaskはtask_not_foundを返す() {
+        let repository =
+            TestTaskRepository::new(vec![new_task_handle_at("project", now()).unwrap()], now());
+
+        let error = get_task_tree(
+            &repository,
+            &mut no_busy(),
+            &policy(),
+            Uuid::from_u128(0x38),
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            ApplicationError::TaskNotFound(task_id) if task_id == Uuid::from_u128(0x38)
+        ));
+    }
+}
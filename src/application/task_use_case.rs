@@ -1,7 +1,9 @@
 use crate::application::daily_capacity::try_next_business_day_start;
-use crate::application::interface::TaskRepositoryTrait;
+use crate::application::interface::{FreeTimeManagerTrait, TaskRepositoryTrait};
 use crate::application::schedule_use_case::get_schedule;
-pub use crate::application::task_view::TaskView;
+pub use crate::application::task_view::{TaskView, WaitingView};
+use crate::entity::allowed_window::AllowedWindow;
+use crate::entity::datetime::BusinessDateTimePolicy;
 use crate::entity::task::{
     ProjectCategory, RepetitionAnchor, Status, TaskAttr, TaskHandle, TaskTreeError,
 };
@@ -194,8 +196,11 @@ pub fn get_task(
         .transpose()
 }
 
+// scheduled_startは、get_scheduleと同じく埋まっている時間と夜を避けた予定の開始で絞り込む
 pub fn list_tasks(
     repository: &dyn TaskRepositoryTrait,
+    free_time_manager: &mut dyn FreeTimeManagerTrait,
+    policy: &BusinessDateTimePolicy,
     filter: ListTasksFilter,
 ) -> Result<Vec<TaskView>, ApplicationError> {
     if filter
@@ -214,7 +219,7 @@ pub fn list_tasks(
         .as_ref()
         .filter(|period| period.field == TaskPeriodField::ScheduledStart)
         .map(|period| {
-            Ok(get_schedule(repository)?
+            Ok(get_schedule(repository, free_time_manager, policy)?
                 .into_iter()
                 .filter(|entry| {
                     period.from <= entry.scheduled_start && entry.scheduled_start < period.until
@@ -224,10 +229,20 @@ pub fn list_tasks(
         })
         .transpose()?;
 
+    let indexed_task_ids = filter.period.as_ref().and_then(|period| {
+        repository.find_task_ids_in_period(period.field, period.from, period.until)
+    });
+
     let mut tasks = Vec::new();
     for root in repository.get_all_projects() {
         collect_tasks_pre_order(root, &mut tasks).map_err(ApplicationError::TaskTree)?;
     }
+    if let Some(task_ids) = &indexed_task_ids {
+        tasks.retain(|task| {
+            task.get_id()
+                .is_ok_and(|task_id| task_ids.contains(&task_id))
+        });
+    }
 
     Ok(tasks
         .into_iter()
@@ -260,7 +275,7 @@ pub fn list_tasks(
         .collect::<Vec<_>>())
 }
 
-fn collect_tasks_pre_order(
+pub(crate) fn collect_tasks_pre_order(
     task: &TaskHandle,
     tasks: &mut Vec<TaskHandle>,
 ) -> Result<(), TaskTreeError> {
@@ -475,6 +490,17 @@ pub fn set_category(
     Ok(())
 }
 
+pub fn set_allowed_window(
+    repository: &mut dyn TaskRepositoryTrait,
+    task_id: Uuid,
+    allowed_window: Option<AllowedWindow>,
+) -> Result<(), ApplicationError> {
+    let task = find_task(repository, task_id)?;
+    task.set_allowed_window_opt(allowed_window)
+        .map_err(ApplicationError::TaskTree)?;
+    Ok(())
+}
+
 fn find_task(
     repository: &dyn TaskRepositoryTrait,
     task_id: Uuid,
@@ -837,6 +863,29 @@ mod tests {
             self.projects.push(root_task);
             Ok(())
         }
+
+        fn remove_project(
+            &mut self,
+            root_task_id: Uuid,
+        ) -> Result<Option<String>, crate::entity::task::TaskTreeError> {
+            let Some(index) = self
+                .projects
+                .iter()
+                .position(|project| project.get_id() == Ok(root_task_id))
+            else {
+                return Ok(None);
+            };
+            self.projects.remove(index).get_name().map(Some)
+        }
+
+        fn restore_project(
+            &mut self,
+            project_key: &str,
+            root_task: TaskHandle,
+        ) -> Result<String, crate::entity::task::TaskTreeError> {
+            self.projects.push(root_task);
+            Ok(project_key.to_string())
+        }
     }
 
     fn fixed_now() -> DateTime<Local> {
@@ -1,5 +1,6 @@
 use crate::entity::task::{ProjectCategory, RepetitionAnchor, Status, TaskHandle, TaskTreeError};
-use chrono::{DateTime, Local};
+use crate::entity::waiting::WaitingRecord;
+use chrono::{DateTime, Local, NaiveDate};
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -26,6 +27,31 @@ pub struct TaskView {
     pub repetition_anchor: RepetitionAnchor,
     pub days_in_advance: i64,
     pub project_category: Option<ProjectCategory>,
+    pub waiting: Option<WaitingView>,
+    pub allowed_window: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WaitingView {
+    pub counterpart: String,
+    pub since: DateTime<Local>,
+    pub expected_reply_date: Option<NaiveDate>,
+    pub follow_up_interval_days: Option<i64>,
+    pub last_followed_up_at: Option<DateTime<Local>>,
+    pub next_follow_up_at: Option<DateTime<Local>>,
+}
+
+impl From<&WaitingRecord> for WaitingView {
+    fn from(waiting: &WaitingRecord) -> Self {
+        Self {
+            counterpart: waiting.get_counterpart().to_string(),
+            since: waiting.get_since(),
+            expected_reply_date: waiting.get_expected_reply_date_opt(),
+            follow_up_interval_days: waiting.get_follow_up_interval_days_opt(),
+            last_followed_up_at: waiting.get_last_followed_up_at_opt(),
+            next_follow_up_at: waiting.next_follow_up_time(),
+        }
+    }
 }
 
 impl TryFrom<&TaskHandle> for TaskView {
@@ -68,6 +94,11 @@ impl TryFrom<&TaskHandle> for TaskView {
             repetition_anchor: attr.get_repetition_anchor(),
             days_in_advance: attr.get_days_in_advance(),
             project_category: root_attr.get_project_category_opt(),
+            waiting: attr.get_waiting_opt().as_ref().map(WaitingView::from),
+            allowed_window: attr
+                .get_allowed_window_opt()
+                .as_ref()
+                .map(ToString::to_string),
         })
     }
 }
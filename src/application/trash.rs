@@ -0,0 +1,489 @@
+use super::interface::{TaskRepositoryTrait, TrashStoreError, TrashStoreTrait};
+use super::storage_history::MIN_REVISION_PREFIX_LENGTH;
+use super::task_use_case::ApplicationError;
+use crate::entity::task::{TaskHandle, TaskTreeError};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+// 捨てた部分木1つ分の記録。projectを捨てた場合はoriginal_parent_idがNoneで、
+// 保存先でのkeyをproject_keyに持つ
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub trash_id: Uuid,
+    pub deleted_at: DateTime<Local>,
+    pub task_id: Uuid,
+    pub name: String,
+    pub task_count: usize,
+    pub original_parent_id: Option<Uuid>,
+    pub position: usize,
+    pub project_key: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PurgedTrashEntry {
+    pub entry: TrashEntry,
+    pub removed_project_directory: bool,
+}
+
+#[derive(Debug)]
+pub enum TrashError {
+    Store(TrashStoreError),
+    Application(ApplicationError),
+    EntryNotFound(String),
+    AmbiguousEntry(String),
+    // 他processが計時中のtaskを含む部分木は、作業中として捨てない
+    ProjectHasOpenWork { project_id: Uuid, task_id: Uuid },
+    TaskAlreadyExists(Uuid),
+    ParentNotFound(Uuid),
+}
+
+impl fmt::Display for TrashError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Store(error) => error.fmt(formatter),
+            Self::Application(error) => error.fmt(formatter),
+            Self::EntryNotFound(reference) => {
+                write!(formatter, "trash entry not found: {reference}")
+            }
+            Self::AmbiguousEntry(reference) => {
+                write!(formatter, "trash entry is ambiguous: {reference}")
+            }
+            Self::ProjectHasOpenWork {
+                project_id,
+                task_id,
+            } => write!(
+                formatter,
+                "project {project_id} has an open work session on task {task_id}"
+            ),
+            Self::TaskAlreadyExists(task_id) => {
+                write!(formatter, "task already exists: {task_id}")
+            }
+            Self::ParentNotFound(parent_id) => {
+                write!(formatter, "original parent task not found: {parent_id}")
+            }
+        }
+    }
+}
+
+impl Error for TrashError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Store(error) => Some(error),
+            Self::Application(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<TrashStoreError> for TrashError {
+    fn from(error: TrashStoreError) -> Self {
+        Self::Store(error)
+    }
+}
+
+impl From<ApplicationError> for TrashError {
+    fn from(error: ApplicationError) -> Self {
+        Self::Application(error)
+    }
+}
+
+impl From<TaskTreeError> for TrashError {
+    fn from(error: TaskTreeError) -> Self {
+        Self::Application(ApplicationError::TaskTree(error))
+    }
+}
+
+fn subtree_tasks(task: &TaskHandle) -> Result<Vec<TaskHandle>, TaskTreeError> {
+    let mut tasks = vec![task.clone()];
+    for child in task.get_children()? {
+        tasks.extend(subtree_tasks(&child)?);
+    }
+    Ok(tasks)
+}
+
+// 捨てたtaskは完了扱いにしないため、実績やperiodの集計に残らない
+pub fn delete_task(
+    repository: &mut dyn TaskRepositoryTrait,
+    store: &mut dyn TrashStoreTrait,
+    task_id: Uuid,
+    now: DateTime<Local>,
+) -> Result<TrashEntry, TrashError> {
+    let mut task = repository
+        .get_by_id(task_id)?
+        .ok_or(ApplicationError::TaskNotFound(task_id))?;
+    let tasks = subtree_tasks(&task)?;
+    let open_work_task_ids = store.open_work_task_ids()?;
+    for task in &tasks {
+        let id = task.get_id()?;
+        if open_work_task_ids.contains(&id) {
+            return Err(TrashError::ProjectHasOpenWork {
+                project_id: task_id,
+                task_id: id,
+            });
+        }
+    }
+    let mut entry = TrashEntry {
+        trash_id: Uuid::new_v4(),
+        deleted_at: now,
+        task_id,
+        name: task.get_name()?,
+        task_count: tasks.len(),
+        original_parent_id: None,
+        position: 0,
+        project_key: None,
+    };
+
+    let Some(parent) = task.parent()? else {
+        let project_key = repository
+            .remove_project(task_id)?
+            .ok_or(ApplicationError::TaskNotFound(task_id))?;
+        entry.project_key = Some(project_key.clone());
+        if let Err(error) = store.append_trash_entry(&entry, &task) {
+            repository.restore_project(&project_key, task)?;
+            return Err(error.into());
+        }
+        return Ok(entry);
+    };
+
+    entry.original_parent_id = Some(parent.get_id()?);
+    entry.position = parent
+        .get_children()?
+        .iter()
+        .position(|child| child.ptr_eq(&task))
+        .unwrap_or_default();
+    store.append_trash_entry(&entry, &task)?;
+    if let Err(error) = task.detach_from_parent() {
+        store.remove_trash_entry(entry.trash_id)?;
+        return Err(error.into());
+    }
+    Ok(entry)
+}
+
+// 新しく捨てたものから順に返す
+pub fn list_trash(store: &dyn TrashStoreTrait) -> Result<Vec<TrashEntry>, TrashStoreError> {
+    let mut entries = store.list_trash_entries()?;
+    entries.reverse();
+    Ok(entries)
+}
+
+// 版と同じく、UUID全体または一意に決まる先頭部分で捨てた記録を指定する
+pub fn resolve_trash_entry(
+    entries: &[TrashEntry],
+    reference: &str,
+) -> Result<TrashEntry, TrashError> {
+    let normalized = reference.trim().to_ascii_lowercase();
+    if normalized.len() < MIN_REVISION_PREFIX_LENGTH {
+        return Err(TrashError::EntryNotFound(reference.to_string()));
+    }
+    let matched_ids = entries
+        .iter()
+        .map(|entry| entry.trash_id)
+        .filter(|trash_id| trash_id.hyphenated().to_string().starts_with(&normalized))
+        .collect::<BTreeSet<_>>();
+    if matched_ids.len() > 1 {
+        return Err(TrashError::AmbiguousEntry(reference.to_string()));
+    }
+    entries
+        .iter()
+        .find(|entry| matched_ids.contains(&entry.trash_id))
+        .cloned()
+        .ok_or_else(|| TrashError::EntryNotFound(reference.to_string()))
+}
+
+// 元の親の元の位置へ戻す。projectは元のkeyで戻し、使われていれば別のkeyにしてentryに記録する
+pub fn restore_trash_entry(
+    repository: &mut dyn TaskRepositoryTrait,
+    store: &mut dyn TrashStoreTrait,
+    trash_id: Uuid,
+    now: DateTime<Local>,
+) -> Result<TrashEntry, TrashError> {
+    let mut entry = store
+        .list_trash_entries()?
+        .into_iter()
+        .find(|entry| entry.trash_id == trash_id)
+        .ok_or_else(|| TrashError::EntryNotFound(trash_id.to_string()))?;
+    let mut subtree = store
+        .read_trash_subtree(trash_id, now)?
+        .ok_or_else(|| TrashError::EntryNotFound(trash_id.to_string()))?;
+    for task in subtree_tasks(&subtree)? {
+        let task_id = task.get_id()?;
+        if repository.get_by_id(task_id)?.is_some() {
+            return Err(TrashError::TaskAlreadyExists(task_id));
+        }
+    }
+    let parent = entry
+        .original_parent_id
+        .map(|parent_id| {
+            repository
+                .get_by_id(parent_id)?
+                .ok_or(TrashError::ParentNotFound(parent_id))
+        })
+        .transpose()?;
+
+    store.remove_trash_entry(trash_id)?;
+    let restored = match (&parent, &entry.project_key) {
+        (Some(parent), _) => subtree
+            .reparent_to_position(parent, Some(entry.position))
+            .map(|()| None),
+        (None, project_key) => repository
+            .restore_project(
+                project_key.as_deref().unwrap_or(&entry.name),
+                subtree.clone(),
+            )
+            .map(Some),
+    };
+    match restored {
+        Ok(Some(project_key)) => entry.project_key = Some(project_key),
+        Ok(None) => {}
+        Err(error) => {
+            store.append_trash_entry(&entry, &subtree)?;
+            return Err(error.into());
+        }
+    }
+    Ok(entry)
+}
+
+// 記録を消す。projectなら、保存先に空のdirectoryだけが残っていればそれも消す
+pub fn purge_trash_entry(
+    store: &mut dyn TrashStoreTrait,
+    trash_id: Uuid,
+) -> Result<PurgedTrashEntry, TrashError> {
+    let entry = store
+        .list_trash_entries()?
+        .into_iter()
+        .find(|entry| entry.trash_id == trash_id)
+        .ok_or_else(|| TrashError::EntryNotFound(trash_id.to_string()))?;
+    store.remove_trash_entry(trash_id)?;
+    let removed_project_directory = match &entry.project_key {
+        Some(project_key) => store.remove_empty_project_directory(project_key)?,
+        None => false,
+    };
+    Ok(PurgedTrashEntry {
+        entry,
+        removed_project_directory,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{new_task_attr, new_task_handle, TestTaskRepository};
+    use chrono::TimeZone;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct TestTrashStore {
+        entries: Vec<(TrashEntry, TaskHandle)>,
+        removed_project_directories: RefCell<Vec<String>>,
+        open_work_task_ids: Vec<Uuid>,
+    }
+
+    impl TrashStoreTrait for TestTrashStore {
+        fn list_trash_entries(&self) -> Result<Vec<TrashEntry>, TrashStoreError> {
+            Ok(self
+                .entries
+                .iter()
+                .map(|(entry, _)| entry.clone())
+                .collect())
+        }
+
+        fn append_trash_entry(
+            &mut self,
+            entry: &TrashEntry,
+            subtree: &TaskHandle,
+        ) -> Result<(), TrashStoreError> {
+            self.entries.push((entry.clone(), subtree.clone()));
+            Ok(())
+        }
+
+        fn read_trash_subtree(
+            &self,
+            trash_id: Uuid,
+            _now: DateTime<Local>,
+        ) -> Result<Option<TaskHandle>, TrashStoreError> {
+            Ok(self
+                .entries
+                .iter()
+                .find(|(entry, _)| entry.trash_id == trash_id)
+                .map(|(_, subtree)| subtree.clone()))
+        }
+
+        fn remove_trash_entry(&mut self, trash_id: Uuid) -> Result<(), TrashStoreError> {
+            self.entries.retain(|(entry, _)| entry.trash_id != trash_id);
+            Ok(())
+        }
+
+        fn remove_empty_project_directory(
+            &mut self,
+            project_key: &str,
+        ) -> Result<bool, TrashStoreError> {
+            self.removed_project_directories
+                .borrow_mut()
+                .push(project_key.to_string());
+            Ok(true)
+        }
+
+        fn open_work_task_ids(&self) -> Result<Vec<Uuid>, TrashStoreError> {
+            Ok(self.open_work_task_ids.clone())
+        }
+    }
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap()
+    }
+
+    fn child_names(task: &TaskHandle) -> Vec<String> {
+        task.get_children()
+            .unwrap()
+            .iter()
+            .map(|child| child.get_name().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn 子taskを捨てると元の親と位置を記録し元の位置へ戻せる() {
+        let project = new_task_handle("project").unwrap();
+        project.create_child(new_task_attr("一")).unwrap();
+        let deleted = project.create_child(new_task_attr("二")).unwrap();
+        deleted.create_child(new_task_attr("孫")).unwrap();
+        project.create_child(new_task_attr("三")).unwrap();
+        let deleted_id = deleted.get_id().unwrap();
+        let mut repository = TestTaskRepository::new(vec![project.clone()], now());
+        let mut store = TestTrashStore::default();
+
+        let entry = delete_task(&mut repository, &mut store, deleted_id, now()).unwrap();
+
+        assert_eq!(entry.original_parent_id, Some(project.get_id().unwrap()));
+        assert_eq!(entry.position, 1);
+        assert_eq!(entry.task_count, 2);
+        assert_eq!(child_names(&project), vec!["一", "三"]);
+        assert_eq!(repository.get_by_id(deleted_id).unwrap(), None);
+
+        let restored =
+            restore_trash_entry(&mut repository, &mut store, entry.trash_id, now()).unwrap();
+
+        assert_eq!(restored.task_id, deleted_id);
+        assert_eq!(child_names(&project), vec!["一", "二", "三"]);
+        assert!(list_trash(&store).unwrap().is_empty());
+    }
+
+    #[test]
+    fn projectを捨てると保存先のkeyを記録しpurgeで空のdirectoryを消す() {
+        let project = new_task_handle("捨てるproject").unwrap();
+        let project_id = project.get_id().unwrap();
+        let mut repository = TestTaskRepository::new(vec![project], now());
+        let mut store = TestTrashStore::default();
+
+        let entry = delete_task(&mut repository, &mut store, project_id, now()).unwrap();
+
+        assert_eq!(entry.original_parent_id, None);
+        assert_eq!(entry.project_key.as_deref(), Some("捨てるproject"));
+        assert!(repository.get_all_projects().is_empty());
+
+        let purged = purge_trash_entry(&mut store, entry.trash_id).unwrap();
+
+        assert!(purged.removed_project_directory);
+        assert_eq!(
+            *store.removed_project_directories.borrow(),
+            vec!["捨てるproject".to_string()]
+        );
+        assert!(list_trash(&store).unwrap().is_empty());
+    }
+
+    #[test]
+    fn 他processが計時中のtaskを含むprojectと部分木は捨てない() {
+        let project = new_task_handle("作業中").unwrap();
+        let parent = project.create_child(new_task_attr("親")).unwrap();
+        let working = parent.create_child(new_task_attr("途中")).unwrap();
+        let project_id = project.get_id().unwrap();
+        let parent_id = parent.get_id().unwrap();
+        let mut repository = TestTaskRepository::new(vec![project], now());
+        let mut store = TestTrashStore {
+            open_work_task_ids: vec![working.get_id().unwrap()],
+            ..TestTrashStore::default()
+        };
+
+        for target_id in [project_id, parent_id] {
+            let error = delete_task(&mut repository, &mut store, target_id, now()).unwrap_err();
+
+            assert!(matches!(
+                error,
+                TrashError::ProjectHasOpenWork { project_id, task_id }
+                    if project_id == target_id && task_id == working.get_id().unwrap()
+            ));
+        }
+        assert_eq!(repository.get_all_projects().len(), 1);
+        assert!(parent.parent().unwrap().is_some());
+        assert!(store.entries.is_empty());
+    }
+
+    #[test]
+    fn 計時中でなければ未完了で実績のあるprojectも捨てられる() {
+        let project = new_task_handle("放置").unwrap();
+        let abandoned = project.create_child(new_task_attr("途中で放置")).unwrap();
+        abandoned.set_actual_work_seconds(600).unwrap();
+        let project_id = project.get_id().unwrap();
+        let mut repository = TestTaskRepository::new(vec![project], now());
+        let mut store = TestTrashStore::default();
+
+        delete_task(&mut repository, &mut store, project_id, now()).unwrap();
+
+        assert!(repository.get_all_projects().is_empty());
+        assert_eq!(store.entries.len(), 1);
+    }
+
+    #[test]
+    fn 元の親がなければ戻さず記録を残す() {
+        let project = new_task_handle("project").unwrap();
+        let parent = project.create_child(new_task_attr("親")).unwrap();
+        let child = parent.create_child(new_task_attr("子")).unwrap();
+        let mut repository = TestTaskRepository::new(vec![project], now());
+        let mut store = TestTrashStore::default();
+        let child_entry =
+            delete_task(&mut repository, &mut store, child.get_id().unwrap(), now()).unwrap();
+        delete_task(&mut repository, &mut store, parent.get_id().unwrap(), now()).unwrap();
+
+        let error = restore_trash_entry(&mut repository, &mut store, child_entry.trash_id, now())
+            .unwrap_err();
+
+        assert!(
+            matches!(error, TrashError::ParentNotFound(parent_id) if parent_id == parent.get_id().unwrap())
+        );
+        assert_eq!(list_trash(&store).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn 捨てた記録は先頭4文字以上で一意に指定する() {
+        let entry = |trash_id: &str| TrashEntry {
+            trash_id: Uuid::parse_str(trash_id).unwrap(),
+            deleted_at: now(),
+            task_id: Uuid::nil(),
+            name: "task".to_string(),
+            task_count: 1,
+            original_parent_id: None,
+            position: 0,
+            project_key: None,
+        };
+        let entries = vec![
+            entry("abcd1111-0000-0000-0000-000000000000"),
+            entry("abcd2222-0000-0000-0000-000000000000"),
+        ];
+
+        assert_eq!(
+            resolve_trash_entry(&entries, "ABCD1").unwrap().trash_id,
+            entries[0].trash_id
+        );
+        assert!(matches!(
+            resolve_trash_entry(&entries, "abcd"),
+            Err(TrashError::AmbiguousEntry(_))
+        ));
+        assert!(matches!(
+            resolve_trash_entry(&entries, "abc"),
+            Err(TrashError::EntryNotFound(_))
+        ));
+    }
+}
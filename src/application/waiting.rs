@@ -0,0 +1,256 @@
+use super::interface::TaskRepositoryTrait;
+use super::task_use_case::{collect_tasks_pre_order, ApplicationError, TaskView};
+use crate::entity::task::{Status, TaskHandle, TaskTreeError};
+use crate::entity::waiting::WaitingRecord;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+// 相手ボールの一覧の1行。相手を記録せずに待ちにしたtaskは、待ち始めが分からないためage_daysがNone
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WaitingEntry {
+    pub task: TaskView,
+    pub age_days: Option<i64>,
+    pub follow_up_due: bool,
+}
+
+// 同じ相手を待っているtaskのまとまり。相手を記録していないtaskはcounterpartがNone
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WaitingGroup {
+    pub counterpart: Option<String>,
+    pub entries: Vec<WaitingEntry>,
+}
+
+// 相手を記録して待ちにする。催促の予定があればその時刻までPendingにし、過ぎたら再びfocusの候補に戻す
+pub fn start_waiting(
+    task: &TaskHandle,
+    waiting: WaitingRecord,
+) -> Result<Option<DateTime<Local>>, ApplicationError> {
+    task.set_is_on_other_side(true)
+        .map_err(ApplicationError::TaskTree)?;
+    let next_follow_up_time_opt =
+        defer_until_next_follow_up(task, &waiting).map_err(ApplicationError::TaskTree)?;
+    task.set_waiting_opt(Some(waiting))
+        .map_err(ApplicationError::TaskTree)?;
+    Ok(next_follow_up_time_opt)
+}
+
+// 返事が来たなどで待ちを終える。催促のためのPendingが残っていれば解除する
+pub fn clear_waiting(task: &TaskHandle) -> Result<(), ApplicationError> {
+    let waiting_opt = task.get_waiting_opt().map_err(ApplicationError::TaskTree)?;
+    let next_follow_up_time_opt = waiting_opt
+        .as_ref()
+        .and_then(WaitingRecord::next_follow_up_time);
+    if next_follow_up_time_opt.is_some()
+        && task.get_orig_status().map_err(ApplicationError::TaskTree)? == Status::Pending
+        && Some(
+            task.get_pending_until()
+                .map_err(ApplicationError::TaskTree)?,
+        ) == next_follow_up_time_opt
+    {
+        task.set_orig_status(Status::Todo)
+            .map_err(ApplicationError::TaskTree)?;
+    }
+    task.set_waiting_opt(None)
+        .map_err(ApplicationError::TaskTree)?;
+    task.set_is_on_other_side(false)
+        .map_err(ApplicationError::TaskTree)
+}
+
+// 催促したことを記録し、次の催促の予定までPendingにする
+pub fn record_follow_up(
+    task: &TaskHandle,
+    now: DateTime<Local>,
+) -> Result<Option<DateTime<Local>>, ApplicationError> {
+    let mut waiting = task
+        .get_waiting_opt()
+        .map_err(ApplicationError::TaskTree)?
+        .ok_or(ApplicationError::InvalidInput {
+            field: "waiting",
+            reason: "task is not waiting on a counterpart",
+        })?;
+    waiting.set_last_followed_up_at_opt(Some(now));
+    let next_follow_up_time_opt =
+        defer_until_next_follow_up(task, &waiting).map_err(ApplicationError::TaskTree)?;
+    task.set_waiting_opt(Some(waiting))
+        .map_err(ApplicationError::TaskTree)?;
+    Ok(next_follow_up_time_opt)
+}
+
+fn defer_until_next_follow_up(
+    task: &TaskHandle,
+    waiting: &WaitingRecord,
+) -> Result<Option<DateTime<Local>>, TaskTreeError> {
+    let next_follow_up_time_opt = waiting.next_follow_up_time();
+    if let Some(next_follow_up_time) = next_follow_up_time_opt {
+        task.set_pending_until(next_follow_up_time)?;
+        task.set_orig_status(Status::Pending)?;
+    }
+    Ok(next_follow_up_time_opt)
+}
+
+// 未完了の相手ボールを相手ごとにまとめ、長く待っている順に並べる。
+// 相手のまとまりも最も長く待っているtaskの順で、相手を記録していないまとまりは最後に置く
+pub fn list_waiting_tasks(
+    repository: &dyn TaskRepositoryTrait,
+    now: DateTime<Local>,
+) -> Result<Vec<WaitingGroup>, ApplicationError> {
+    let mut tasks = Vec::new();
+    for root in repository.get_all_projects() {
+        collect_tasks_pre_order(root, &mut tasks).map_err(ApplicationError::TaskTree)?;
+    }
+
+    let mut entries = Vec::new();
+    for task in tasks {
+        let waiting_opt = task.get_waiting_opt().map_err(ApplicationError::TaskTree)?;
+        let is_waiting = waiting_opt.is_some()
+            || task
+                .get_is_on_other_side()
+                .map_err(ApplicationError::TaskTree)?;
+        if !is_waiting || task.get_status().map_err(ApplicationError::TaskTree)? == Status::Done {
+            continue;
+        }
+        let view = TaskView::try_from(&task).map_err(ApplicationError::TaskTree)?;
+        entries.push((
+            waiting_opt.as_ref().map(WaitingRecord::get_since),
+            WaitingEntry {
+                task: view,
+                age_days: waiting_opt.as_ref().map(|waiting| waiting.age_days(now)),
+                follow_up_due: waiting_opt
+                    .as_ref()
+                    .is_some_and(|waiting| waiting.is_follow_up_due(now)),
+            },
+        ));
+    }
+    entries.sort_by_key(|(since_opt, entry)| (since_opt.is_none(), *since_opt, entry.task.id));
+
+    let mut groups: Vec<WaitingGroup> = Vec::new();
+    for (_, entry) in entries {
+        let counterpart = entry
+            .task
+            .waiting
+            .as_ref()
+            .map(|waiting| waiting.counterpart.clone());
+        match groups
+            .iter_mut()
+            .find(|group| group.counterpart == counterpart)
+        {
+            Some(group) => group.entries.push(entry),
+            None => groups.push(WaitingGroup {
+                counterpart,
+                entries: vec![entry],
+            }),
+        }
+    }
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{new_task_attr, new_task_handle, TestTaskRepository};
+    use chrono::{NaiveDate, TimeZone};
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 8, day, hour, 0, 0).unwrap()
+    }
+
+    fn record(counterpart: &str, since: DateTime<Local>) -> WaitingRecord {
+        WaitingRecord::new(
+            counterpart,
+            since,
+            NaiveDate::from_ymd_opt(2026, 8, 21),
+            Some(3),
+        )
+    }
+
+    #[test]
+    fn 待ちを記録すると次の催促までpendingにし消すとtodoに戻す() {
+        let task = new_task_handle("見積もりの返事").unwrap();
+
+        let next = start_waiting(&task, record("田中", at(19, 10))).unwrap();
+
+        assert_eq!(next, Some(at(22, 6)));
+        assert!(task.get_is_on_other_side().unwrap());
+        assert_eq!(task.get_orig_status().unwrap(), Status::Pending);
+        assert_eq!(task.get_pending_until().unwrap(), at(22, 6));
+
+        let next = record_follow_up(&task, at(22, 9)).unwrap();
+
+        assert_eq!(next, Some(at(25, 9)));
+        assert_eq!(task.get_pending_until().unwrap(), at(25, 9));
+        assert_eq!(
+            task.get_waiting_opt()
+                .unwrap()
+                .unwrap()
+                .get_last_followed_up_at_opt(),
+            Some(at(22, 9))
+        );
+
+        clear_waiting(&task).unwrap();
+
+        assert_eq!(task.get_waiting_opt().unwrap(), None);
+        assert!(!task.get_is_on_other_side().unwrap());
+        assert_eq!(task.get_orig_status().unwrap(), Status::Todo);
+    }
+
+    #[test]
+    fn 待ちでないtaskの催促は記録しない() {
+        let task = new_task_handle("返事待ちではない").unwrap();
+
+        let error = record_follow_up(&task, at(22, 9)).unwrap_err();
+
+        assert!(matches!(
+            error,
+            ApplicationError::InvalidInput {
+                field: "waiting",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn 一覧は相手ごとにまとめ長く待っている順に並べる() {
+        let project = new_task_handle("project").unwrap();
+        let newer = project
+            .create_child(new_task_attr("田中の新しい件"))
+            .unwrap();
+        let other = project.create_child(new_task_attr("鈴木の件")).unwrap();
+        let older = project.create_child(new_task_attr("田中の古い件")).unwrap();
+        let unrecorded = project.create_child(new_task_attr("相手未記録")).unwrap();
+        let done = project.create_child(new_task_attr("終わった件")).unwrap();
+        start_waiting(&newer, record("田中", at(18, 10))).unwrap();
+        start_waiting(&other, record("鈴木", at(17, 10))).unwrap();
+        start_waiting(&older, record("田中", at(10, 10))).unwrap();
+        unrecorded.set_is_on_other_side(true).unwrap();
+        start_waiting(&done, record("鈴木", at(1, 10))).unwrap();
+        done.set_orig_status(Status::Done).unwrap();
+        let repository = TestTaskRepository::new(vec![project], at(22, 7));
+
+        let groups = list_waiting_tasks(&repository, at(22, 7)).unwrap();
+
+        assert_eq!(
+            groups
+                .iter()
+                .map(|group| group.counterpart.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("田中"), Some("鈴木"), None]
+        );
+        assert_eq!(
+            groups
+                .iter()
+                .flat_map(|group| &group.entries)
+                .map(|entry| (
+                    entry.task.name.as_str(),
+                    entry.age_days,
+                    entry.follow_up_due
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("田中の古い件", Some(11), true),
+                ("田中の新しい件", Some(3), true),
+                ("鈴木の件", Some(4), true),
+                ("相手未記録", None, false),
+            ]
+        );
+    }
+}
@@ -1,3 +1,6 @@
+pub mod allowed_window;
+pub mod busy_block;
 pub mod busy_time_slot;
 pub mod datetime;
 pub mod task;
+pub mod waiting;
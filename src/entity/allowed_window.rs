@@ -0,0 +1,352 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+use std::fmt;
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const ALL_WEEKDAYS_MASK: u8 = 0b111_1111;
+const WEEKDAY_CHARS: [&str; 7] = ["月", "火", "水", "木", "金", "土", "日"];
+const WEEKDAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+// 枠を探す先の日数。週単位の繰り返しなので2週分見れば必ず見つかる
+const LOOKAHEAD_DAYS: i64 = 14;
+
+pub const INVALID_WEEKDAYS_REASON: &str = "weekdays must be 月..日, mon..sun, 平日, 週末 or 毎日";
+pub const INVALID_TIME_RANGES_REASON: &str =
+    "time ranges must be HH:MM-HH:MM with the start before the end";
+pub const EMPTY_ALLOWED_WINDOW_REASON: &str = "must include weekdays or time ranges";
+
+// 作業してよい曜日と時間帯。曜日を省けば毎日、時間帯を省けば終日とする。
+// 時刻は暦の日付で数え、主観日の境界(06:00)とは関係しない
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AllowedWindow {
+    weekdays: u8,
+    // 0時からの分。重なりや隣接はまとめ、開始順に並べておく
+    ranges: Vec<(u32, u32)>,
+}
+
+impl AllowedWindow {
+    // "平日 09:00-12:00,13:00-18:00" や "月水金" のように、空白区切りで曜日と時間帯を並べる
+    pub fn parse(text: &str) -> Result<Self, &'static str> {
+        let mut weekdays = 0;
+        let mut ranges = Vec::new();
+        let mut has_token = false;
+        for token in text.split_whitespace() {
+            has_token = true;
+            if token.contains(':') {
+                for range in token.split(',').filter(|range| !range.is_empty()) {
+                    ranges.push(parse_time_range(range).ok_or(INVALID_TIME_RANGES_REASON)?);
+                }
+            } else {
+                weekdays |= parse_weekdays(token).ok_or(INVALID_WEEKDAYS_REASON)?;
+            }
+        }
+        if !has_token {
+            return Err(EMPTY_ALLOWED_WINDOW_REASON);
+        }
+        Ok(Self {
+            weekdays: if weekdays == 0 {
+                ALL_WEEKDAYS_MASK
+            } else {
+                weekdays
+            },
+            ranges: merge_ranges(ranges),
+        })
+    }
+
+    pub fn allows(&self, time: DateTime<Local>) -> bool {
+        self.next_allowed_interval(time)
+            .is_some_and(|(start, _)| start == time)
+    }
+
+    // time以降で最初に作業できる区間。timeが区間の中なら、timeからその区間の終わりまでを返す
+    pub fn next_allowed_interval(
+        &self,
+        time: DateTime<Local>,
+    ) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        let last_date = time
+            .date_naive()
+            .checked_add_signed(Duration::days(LOOKAHEAD_DAYS))?;
+        self.intervals(time.date_naive(), last_date)
+            .into_iter()
+            .find(|(_, end)| time < *end)
+            .map(|(start, end)| (start.max(time), end))
+    }
+
+    // time以降に途切れず作業できる最長の秒数。atomicなtaskが収まるかの判定に使う
+    pub fn longest_interval_seconds(&self, time: DateTime<Local>) -> i64 {
+        let Some(last_date) = time
+            .date_naive()
+            .checked_add_signed(Duration::days(LOOKAHEAD_DAYS))
+        else {
+            return 0;
+        };
+        self.intervals(time.date_naive(), last_date)
+            .into_iter()
+            .filter(|(_, end)| time < *end)
+            .map(|(start, end)| (end - start.max(time)).num_seconds())
+            .max()
+            .unwrap_or(0)
+    }
+
+    // [start, end)のうち作業できる秒数
+    pub fn allowed_seconds_between(&self, start: DateTime<Local>, end: DateTime<Local>) -> i64 {
+        if end <= start {
+            return 0;
+        }
+        self.intervals(start.date_naive(), end.date_naive())
+            .into_iter()
+            .map(|(interval_start, interval_end)| {
+                (interval_end.min(end) - interval_start.max(start))
+                    .num_seconds()
+                    .max(0)
+            })
+            .sum()
+    }
+
+    // first_dateからlast_dateまでの区間を、日を跨いで続くものはつないで返す
+    fn intervals(
+        &self,
+        first_date: NaiveDate,
+        last_date: NaiveDate,
+    ) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+        let mut intervals: Vec<(DateTime<Local>, DateTime<Local>)> = Vec::new();
+        for date in first_date.iter_days().take_while(|date| *date <= last_date) {
+            if self.weekdays & weekday_bit(date.weekday()) == 0 {
+                continue;
+            }
+            for (start_minute, end_minute) in self.effective_ranges() {
+                let (Some(start), Some(end)) =
+                    (local_time(date, start_minute), local_time(date, end_minute))
+                else {
+                    continue;
+                };
+                match intervals.last_mut() {
+                    Some((_, last_end)) if *last_end == start => *last_end = end,
+                    _ => intervals.push((start, end)),
+                }
+            }
+        }
+        intervals
+    }
+
+    fn effective_ranges(&self) -> Vec<(u32, u32)> {
+        if self.ranges.is_empty() {
+            vec![(0, MINUTES_PER_DAY)]
+        } else {
+            self.ranges.clone()
+        }
+    }
+}
+
+// parseで読み戻せる形に書く。毎日・終日の部分は省く
+impl fmt::Display for AllowedWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let weekdays = if self.weekdays == ALL_WEEKDAYS_MASK {
+            None
+        } else {
+            Some(
+                WEEKDAY_CHARS
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| self.weekdays & (1 << index) != 0)
+                    .map(|(_, weekday)| *weekday)
+                    .collect::<String>(),
+            )
+        };
+        let ranges = (!self.ranges.is_empty()).then(|| {
+            self.ranges
+                .iter()
+                .map(|(start, end)| format!("{}-{}", format_minute(*start), format_minute(*end)))
+                .collect::<Vec<_>>()
+                .join(",")
+        });
+        match (weekdays, ranges) {
+            (Some(weekdays), Some(ranges)) => write!(f, "{weekdays} {ranges}"),
+            (Some(weekdays), None) => write!(f, "{weekdays}"),
+            (None, Some(ranges)) => write!(f, "{ranges}"),
+            (None, None) => write!(f, "毎日"),
+        }
+    }
+}
+
+fn weekday_bit(weekday: Weekday) -> u8 {
+    1 << weekday.num_days_from_monday()
+}
+
+fn parse_weekdays(token: &str) -> Option<u8> {
+    match token {
+        "平日" | "weekdays" => return Some(0b001_1111),
+        "週末" | "weekends" => return Some(0b110_0000),
+        "毎日" | "daily" => return Some(ALL_WEEKDAYS_MASK),
+        _ => {}
+    }
+    token.split(',').try_fold(0, |mask, part| {
+        let part_mask = match part.split_once('-') {
+            Some((first, last)) => {
+                let first = parse_weekday(first)?;
+                let last = parse_weekday(last)?;
+                // 金-月のように週を跨ぐ範囲も受け付ける
+                let mut range_mask = 0;
+                for offset in 0..7 {
+                    let index = (first + offset) % 7;
+                    range_mask |= 1 << index;
+                    if index == last {
+                        break;
+                    }
+                }
+                range_mask
+            }
+            None => match parse_weekday(part) {
+                Some(index) => 1 << index,
+                // "月水金"のように漢字を続けて書いた曜日
+                None => part.chars().try_fold(0, |mask, character| {
+                    parse_weekday(character.encode_utf8(&mut [0; 4]))
+                        .map(|index| mask | (1 << index))
+                })?,
+            },
+        };
+        (part_mask != 0).then_some(mask | part_mask)
+    })
+}
+
+fn parse_weekday(text: &str) -> Option<u8> {
+    let lowercase = text.to_ascii_lowercase();
+    WEEKDAY_CHARS
+        .iter()
+        .position(|weekday| *weekday == text)
+        .or_else(|| WEEKDAY_NAMES.iter().position(|name| *name == lowercase))
+        .map(|index| index as u8)
+}
+
+fn parse_time_range(text: &str) -> Option<(u32, u32)> {
+    let (start, end) = text.split_once('-')?;
+    let start = parse_minute(start)?;
+    let end = parse_minute(end)?;
+    (start < end).then_some((start, end))
+}
+
+fn parse_minute(text: &str) -> Option<u32> {
+    let (hour, minute) = text.split_once(':')?;
+    if hour.is_empty() || hour.len() > 2 || minute.len() != 2 {
+        return None;
+    }
+    let hour = hour.parse::<u32>().ok()?;
+    let minute = minute.parse::<u32>().ok()?;
+    let total = hour * 60 + minute;
+    (minute < 60 && total <= MINUTES_PER_DAY).then_some(total)
+}
+
+fn merge_ranges(mut ranges: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+    ranges.sort();
+    let mut merged: Vec<(u32, u32)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = (*last_end).max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    // 終日は時間帯の指定がないのと同じ
+    if merged == [(0, MINUTES_PER_DAY)] {
+        merged.clear();
+    }
+    merged
+}
+
+fn format_minute(minute: u32) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+fn local_time(date: NaiveDate, minute: u32) -> Option<DateTime<Local>> {
+    let (date, minute) = if minute == MINUTES_PER_DAY {
+        (date.succ_opt()?, 0)
+    } else {
+        (date, minute)
+    };
+    let time = NaiveTime::from_hms_opt(minute / 60, minute % 60, 0)?;
+    Local.from_local_datetime(&date.and_time(time)).earliest()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        // 2026/10/19は月曜日
+        Local
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn 曜日と時間帯を読んで同じ形に書き戻す() {
+        for (text, expected) in [
+            (
+                "平日 09:00-12:00,13:00-18:00",
+                "月火水木金 09:00-12:00,13:00-18:00",
+            ),
+            (
+                "mon-wed 13:00-14:00 10:00-12:00",
+                "月火水 10:00-12:00,13:00-14:00",
+            ),
+            ("月水金", "月水金"),
+            ("金-月", "月金土日"),
+            ("tue-mon", "毎日"),
+            ("09:00-12:00,11:00-13:00", "09:00-13:00"),
+            ("週末 00:00-24:00", "土日"),
+            ("毎日", "毎日"),
+        ] {
+            let window = AllowedWindow::parse(text).unwrap();
+            assert_eq!(window.to_string(), expected, "{text}");
+            assert_eq!(AllowedWindow::parse(expected).unwrap(), window);
+        }
+    }
+
+    #[test]
+    fn 読めない曜日や時間帯はerrorを返す() {
+        for (text, expected) in [
+            ("", EMPTY_ALLOWED_WINDOW_REASON),
+            ("朝", INVALID_WEEKDAYS_REASON),
+            ("mon-xyz", INVALID_WEEKDAYS_REASON),
+            ("12:00-09:00", INVALID_TIME_RANGES_REASON),
+            ("09:00-24:30", INVALID_TIME_RANGES_REASON),
+            ("9-12:00", INVALID_TIME_RANGES_REASON),
+        ] {
+            assert_eq!(AllowedWindow::parse(text), Err(expected), "{text}");
+        }
+    }
+
+    #[test]
+    fn 枠の外なら次の曜日と時間帯の始まりを返す() {
+        let window = AllowedWindow::parse("平日 09:00-12:00,13:00-18:00").unwrap();
+
+        assert_eq!(
+            window.next_allowed_interval(at(19, 10, 0)),
+            Some((at(19, 10, 0), at(19, 12, 0)))
+        );
+        assert_eq!(
+            window.next_allowed_interval(at(19, 12, 0)),
+            Some((at(19, 13, 0), at(19, 18, 0)))
+        );
+        // 金曜の夕方からは翌週の月曜まで進む
+        assert_eq!(
+            window.next_allowed_interval(at(23, 18, 0)),
+            Some((at(26, 9, 0), at(26, 12, 0)))
+        );
+        assert!(window.allows(at(19, 9, 0)));
+        assert!(!window.allows(at(24, 10, 0)));
+    }
+
+    #[test]
+    fn 日を跨いで続く区間はつないで数える() {
+        let window = AllowedWindow::parse("週末").unwrap();
+
+        assert_eq!(
+            window.next_allowed_interval(at(23, 9, 0)),
+            Some((at(24, 0, 0), at(26, 0, 0)))
+        );
+        // 日曜の昼からでは残り12時間だが、翌週末には48時間続けて作業できる
+        assert_eq!(window.longest_interval_seconds(at(25, 12, 0)), 48 * 60 * 60);
+        assert_eq!(
+            window.allowed_seconds_between(at(23, 12, 0), at(24, 6, 0)),
+            6 * 60 * 60
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use super::datetime::BUSINESS_DAY_START_HOUR;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use uuid::Uuid;
+
+pub const EMPTY_NAME_REASON: &str = "must not be empty";
+pub const END_BEFORE_START_REASON: &str = "must be after start";
+pub const END_DATE_BEFORE_START_DATE_REASON: &str = "must not be before start_date";
+
+// 週ごとの予定表とは別に、日付を決めて入れる埋まっている時間
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BusyBlock {
+    pub busy_block_id: Uuid,
+    pub name: String,
+    pub span: BusyBlockSpan,
+}
+
+// 終日は休暇のように日付で持ち、初日の業務日開始から最終日の翌業務日開始までを塞ぐ
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BusyBlockSpan {
+    Timed {
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+    },
+    AllDay {
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    },
+}
+
+impl BusyBlockSpan {
+    // 空や逆向きの範囲なら、誤っているfieldと理由を返す
+    pub fn validate(&self) -> Result<(), (&'static str, &'static str)> {
+        match *self {
+            Self::Timed { start, end } if end <= start => Err(("end", END_BEFORE_START_REASON)),
+            Self::AllDay {
+                start_date,
+                end_date,
+            } if end_date < start_date => Err(("end_date", END_DATE_BEFORE_START_DATE_REASON)),
+            _ => Ok(()),
+        }
+    }
+
+    // 一覧を並べるための開始。終日は初日の業務日開始とする
+    pub fn sort_key(&self) -> NaiveDateTime {
+        match *self {
+            Self::Timed { start, .. } => start.naive_local(),
+            Self::AllDay { start_date, .. } => start_date
+                .and_hms_opt(BUSINESS_DAY_START_HOUR, 0, 0)
+                .expect("business day start must be a valid time"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn 逆向きの範囲は終わりのfieldを誤りとする() {
+        let start = Local.with_ymd_and_hms(2026, 10, 20, 14, 0, 0).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 14).unwrap();
+
+        assert_eq!(
+            BusyBlockSpan::Timed { start, end: start }.validate(),
+            Err(("end", END_BEFORE_START_REASON))
+        );
+        assert_eq!(
+            BusyBlockSpan::AllDay {
+                start_date: date,
+                end_date: date.pred_opt().unwrap(),
+            }
+            .validate(),
+            Err(("end_date", END_DATE_BEFORE_START_DATE_REASON))
+        );
+        assert_eq!(
+            BusyBlockSpan::AllDay {
+                start_date: date,
+                end_date: date,
+            }
+            .validate(),
+            Ok(())
+        );
+    }
+}
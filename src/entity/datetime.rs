@@ -1,9 +1,12 @@
 use chrono::{
-    DateTime, Duration, Local, LocalResult, NaiveDate, NaiveDateTime, ParseError, TimeZone,
-    Timelike,
+    DateTime, Datelike, Duration, Local, LocalResult, NaiveDate, NaiveDateTime, ParseError,
+    TimeZone, Timelike, Weekday,
 };
 
-const BUSINESS_DAY_START_HOUR: u32 = 6;
+pub mod holiday;
+pub mod temporal;
+
+pub(crate) const BUSINESS_DAY_START_HOUR: u32 = 6;
 const DEADLINE_PENDING_BUFFER_MINUTES: i64 = 5;
 const DEADLINE_FORCE_TODO_AFTER_START_BUFFER_MINUTES: i64 = 60;
 pub const DEFAULT_END_OF_DAY_OFFSET_MINUTES: i64 = 30;
@@ -11,15 +14,69 @@ pub const DEFAULT_END_OF_DAY_OFFSET_MINUTES: i64 = 30;
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct BusinessDateTimePolicy {
     end_of_day_offset_minutes: i64,
+    // 曜日ごとのbit。営業日の加減算でだけ使い、主観日の境界には影響しない
+    non_business_weekdays: u8,
+    // 設定されていれば祝日を休みとし、空き時間はこの曜日として数える
+    holiday_weekday: Option<Weekday>,
 }
 
 impl BusinessDateTimePolicy {
     pub fn new(end_of_day_offset_minutes: i64) -> Self {
         Self {
             end_of_day_offset_minutes,
+            non_business_weekdays: 0,
+            holiday_weekday: None,
         }
     }
 
+    pub fn with_non_business_weekdays(mut self, weekdays: &[Weekday]) -> Self {
+        self.non_business_weekdays = weekdays
+            .iter()
+            .fold(0, |mask, weekday| mask | weekday_bit(*weekday));
+        self
+    }
+
+    pub fn with_holiday_weekday(mut self, holiday_weekday: Option<Weekday>) -> Self {
+        self.holiday_weekday = holiday_weekday;
+        self
+    }
+
+    pub fn holiday_weekday(&self) -> Option<Weekday> {
+        self.holiday_weekday
+    }
+
+    pub fn end_of_day_offset_minutes(&self) -> i64 {
+        self.end_of_day_offset_minutes
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        self.non_business_weekdays & weekday_bit(date.weekday()) == 0
+            && !(self.holiday_weekday.is_some() && holiday::is_japanese_holiday(date))
+    }
+
+    // 0営業日はdateが休みなら次の営業日、正負の日数は休みの曜日を飛ばして数える
+    pub fn add_business_days(&self, date: NaiveDate, days: i64) -> Option<NaiveDate> {
+        if self.non_business_weekdays == ALL_WEEKDAYS_MASK {
+            return None;
+        }
+        let step = if days < 0 { -1 } else { 1 };
+        let mut current = date;
+        if days == 0 {
+            while !self.is_business_day(current) {
+                current = current.checked_add_signed(Duration::days(1))?;
+            }
+            return Some(current);
+        }
+        let mut remaining = days.unsigned_abs();
+        while remaining > 0 {
+            current = current.checked_add_signed(Duration::days(step))?;
+            if self.is_business_day(current) {
+                remaining -= 1;
+            }
+        }
+        Some(current)
+    }
+
     pub fn subjective_date(&self, datetime: DateTime<Local>) -> Option<NaiveDate> {
         self.subjective_date_from_local_date_and_hour(datetime.date_naive(), datetime.hour())
     }
@@ -96,6 +153,12 @@ impl BusinessDateTimePolicy {
     }
 }
 
+const ALL_WEEKDAYS_MASK: u8 = 0b111_1111;
+
+fn weekday_bit(weekday: Weekday) -> u8 {
+    1 << weekday.num_days_from_monday()
+}
+
 fn local_datetime(naive: Option<NaiveDateTime>) -> LocalResult<DateTime<Local>> {
     match naive {
         Some(naive) => Local.from_local_datetime(&naive),
@@ -236,6 +299,65 @@ mod business_datetime_policy_contract_tests {
         );
     }
 
+    #[test]
+    fn add_business_daysは祝日の曜日を設定したときだけ祝日を飛ばす() {
+        // 2026/5/1(金)の次は、5/2-5/6の土日と祝日を飛ばして5/7
+        let friday = chrono::NaiveDate::from_ymd_opt(2026, 5, 1).unwrap();
+        let policy = BusinessDateTimePolicy::new(30)
+            .with_non_business_weekdays(&[Weekday::Sat, Weekday::Sun]);
+
+        assert_eq!(
+            policy
+                .with_holiday_weekday(Some(Weekday::Sun))
+                .add_business_days(friday, 1),
+            chrono::NaiveDate::from_ymd_opt(2026, 5, 7)
+        );
+        assert_eq!(
+            policy.add_business_days(friday, 1),
+            chrono::NaiveDate::from_ymd_opt(2026, 5, 4)
+        );
+    }
+
+    #[test]
+    fn add_business_daysは休みの曜日を飛ばして前後に数える() {
+        let policy = BusinessDateTimePolicy::new(30)
+            .with_non_business_weekdays(&[Weekday::Sat, Weekday::Sun]);
+        let friday = chrono::NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let saturday = chrono::NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+
+        assert_eq!(
+            policy.add_business_days(friday, 1),
+            chrono::NaiveDate::from_ymd_opt(2026, 10, 19)
+        );
+        assert_eq!(
+            policy.add_business_days(friday, 3),
+            chrono::NaiveDate::from_ymd_opt(2026, 10, 21)
+        );
+        assert_eq!(policy.add_business_days(saturday, -1), Some(friday));
+        assert_eq!(
+            policy.add_business_days(saturday, 0),
+            chrono::NaiveDate::from_ymd_opt(2026, 10, 19)
+        );
+        assert_eq!(
+            BusinessDateTimePolicy::new(30).add_business_days(friday, 1),
+            Some(saturday)
+        );
+        assert_eq!(
+            BusinessDateTimePolicy::new(30)
+                .with_non_business_weekdays(&[
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                    Weekday::Sat,
+                    Weekday::Sun,
+                ])
+                .add_business_days(friday, 1),
+            None
+        );
+    }
+
     #[test]
     fn deadline_pending_limitはdeadlineから見積時間と5分を引く() {
         let policy = BusinessDateTimePolicy::new(30);
@@ -0,0 +1,255 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+
+// 表で持つのは2000年以降の祝日法。春分・秋分の近似式が使えるのは2099年まで
+pub const SUPPORTED_YEARS: std::ops::RangeInclusive<i32> = 2000..=LAST_YEAR;
+const LAST_YEAR: i32 = 2099;
+
+const SUBSTITUTE_HOLIDAY_NAME: &str = "振替休日";
+const CITIZENS_HOLIDAY_NAME: &str = "国民の休日";
+// この年から、日曜の祝日の後に続く祝日を飛ばして振り替える
+const EXTENDED_SUBSTITUTE_FROM_YEAR: i32 = 2007;
+
+#[derive(Clone, Copy)]
+enum HolidayDate {
+    Fixed { month: u32, day: u32 },
+    // month月の第week月曜日
+    HappyMonday { month: u32, week: u32 },
+    VernalEquinox,
+    AutumnalEquinox,
+}
+
+struct HolidayRule {
+    name: &'static str,
+    years: std::ops::RangeInclusive<i32>,
+    date: HolidayDate,
+}
+
+const fn rule(name: &'static str, from: i32, until: i32, date: HolidayDate) -> HolidayRule {
+    HolidayRule {
+        name,
+        years: from..=until,
+        date,
+    }
+}
+
+const fn fixed(month: u32, day: u32) -> HolidayDate {
+    HolidayDate::Fixed { month, day }
+}
+
+const fn happy_monday(month: u32, week: u32) -> HolidayDate {
+    HolidayDate::HappyMonday { month, week }
+}
+
+const HOLIDAY_RULES: &[HolidayRule] = &[
+    rule("元日", 2000, LAST_YEAR, fixed(1, 1)),
+    rule("成人の日", 2000, LAST_YEAR, happy_monday(1, 2)),
+    rule("建国記念の日", 2000, LAST_YEAR, fixed(2, 11)),
+    rule("天皇誕生日", 2020, LAST_YEAR, fixed(2, 23)),
+    rule("春分の日", 2000, LAST_YEAR, HolidayDate::VernalEquinox),
+    rule("みどりの日", 2000, 2006, fixed(4, 29)),
+    rule("昭和の日", 2007, LAST_YEAR, fixed(4, 29)),
+    rule("憲法記念日", 2000, LAST_YEAR, fixed(5, 3)),
+    rule("みどりの日", 2007, LAST_YEAR, fixed(5, 4)),
+    rule("こどもの日", 2000, LAST_YEAR, fixed(5, 5)),
+    rule("海の日", 2000, 2002, fixed(7, 20)),
+    rule("海の日", 2003, 2019, happy_monday(7, 3)),
+    rule("海の日", 2022, LAST_YEAR, happy_monday(7, 3)),
+    rule("山の日", 2016, 2019, fixed(8, 11)),
+    rule("山の日", 2022, LAST_YEAR, fixed(8, 11)),
+    rule("敬老の日", 2000, 2002, fixed(9, 15)),
+    rule("敬老の日", 2003, LAST_YEAR, happy_monday(9, 3)),
+    rule("秋分の日", 2000, LAST_YEAR, HolidayDate::AutumnalEquinox),
+    rule("体育の日", 2000, 2019, happy_monday(10, 2)),
+    rule("スポーツの日", 2022, LAST_YEAR, happy_monday(10, 2)),
+    rule("文化の日", 2000, LAST_YEAR, fixed(11, 3)),
+    rule("勤労感謝の日", 2000, LAST_YEAR, fixed(11, 23)),
+    rule("天皇誕生日", 2000, 2018, fixed(12, 23)),
+];
+
+// 即位の年と東京五輪の年だけの特例
+const SPECIAL_HOLIDAYS: &[(i32, u32, u32, &str)] = &[
+    (2019, 5, 1, "天皇の即位の日"),
+    (2019, 10, 22, "即位礼正殿の儀の行われる日"),
+    (2020, 7, 23, "海の日"),
+    (2020, 7, 24, "スポーツの日"),
+    (2020, 8, 10, "山の日"),
+    (2021, 7, 22, "海の日"),
+    (2021, 7, 23, "スポーツの日"),
+    (2021, 8, 8, "山の日"),
+];
+
+// 祝日なら名前を返す。振替休日と国民の休日も含み、対応範囲外の年はNone
+pub fn japanese_holiday_name(date: NaiveDate) -> Option<&'static str> {
+    if !SUPPORTED_YEARS.contains(&date.year()) {
+        return None;
+    }
+    if let Some(name) = national_holiday_name(date) {
+        return Some(name);
+    }
+    if is_substitute_holiday(date) {
+        return Some(SUBSTITUTE_HOLIDAY_NAME);
+    }
+    if is_citizens_holiday(date) {
+        return Some(CITIZENS_HOLIDAY_NAME);
+    }
+    None
+}
+
+pub fn is_japanese_holiday(date: NaiveDate) -> bool {
+    japanese_holiday_name(date).is_some()
+}
+
+// 空き時間を数える曜日。祝日の曜日が設定されていれば、祝日はその曜日として扱う
+pub fn weekday_profile(date: NaiveDate, holiday_weekday: Option<Weekday>) -> Weekday {
+    match holiday_weekday {
+        Some(weekday) if is_japanese_holiday(date) => weekday,
+        _ => date.weekday(),
+    }
+}
+
+fn national_holiday_name(date: NaiveDate) -> Option<&'static str> {
+    let year = date.year();
+    SPECIAL_HOLIDAYS
+        .iter()
+        .find(|(y, m, d, _)| (*y, *m, *d) == (year, date.month(), date.day()))
+        .map(|(_, _, _, name)| *name)
+        .or_else(|| {
+            HOLIDAY_RULES
+                .iter()
+                .find(|rule| rule.years.contains(&year) && rule_date(rule, year) == Some(date))
+                .map(|rule| rule.name)
+        })
+}
+
+fn rule_date(rule: &HolidayRule, year: i32) -> Option<NaiveDate> {
+    match rule.date {
+        HolidayDate::Fixed { month, day } => NaiveDate::from_ymd_opt(year, month, day),
+        HolidayDate::HappyMonday { month, week } => {
+            NaiveDate::from_weekday_of_month_opt(year, month, Weekday::Mon, week as u8)
+        }
+        HolidayDate::VernalEquinox => NaiveDate::from_ymd_opt(year, 3, equinox_day(year, 20.8431)),
+        HolidayDate::AutumnalEquinox => {
+            NaiveDate::from_ymd_opt(year, 9, equinox_day(year, 23.2488))
+        }
+    }
+}
+
+// 1980年から2099年まで使える近似式。官報での公示とは前年に確定するまで一致を保証しない
+fn equinox_day(year: i32, base: f64) -> u32 {
+    let elapsed = year - 1980;
+    (base + 0.242194 * f64::from(elapsed) - f64::from(elapsed.div_euclid(4))).floor() as u32
+}
+
+// 日曜の祝日から祝日が続く間は振り替えを先送りし、最初の平日を休みにする
+fn is_substitute_holiday(date: NaiveDate) -> bool {
+    let mut previous = date.pred_opt();
+    while let Some(day) = previous {
+        if national_holiday_name(day).is_none() {
+            return false;
+        }
+        if day.weekday() == Weekday::Sun {
+            return true;
+        }
+        if date.year() < EXTENDED_SUBSTITUTE_FROM_YEAR {
+            return false;
+        }
+        previous = day.pred_opt();
+    }
+    false
+}
+
+// 前日と翌日が祝日に挟まれた平日
+fn is_citizens_holiday(date: NaiveDate) -> bool {
+    date.weekday() != Weekday::Sun
+        && date
+            .pred_opt()
+            .is_some_and(|day| national_holiday_name(day).is_some())
+        && date
+            .succ_opt()
+            .is_some_and(|day| national_holiday_name(day).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holidays_in(year: i32) -> Vec<(u32, u32, &'static str)> {
+        let mut date = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        let mut holidays = vec![];
+        while date.year() == year {
+            if let Some(name) = japanese_holiday_name(date) {
+                holidays.push((date.month(), date.day(), name));
+            }
+            date = date.succ_opt().unwrap();
+        }
+        holidays
+    }
+
+    #[test]
+    fn 二〇二六年の祝日は振替休日と国民の休日を含めて内閣府の一覧と一致する() {
+        assert_eq!(
+            holidays_in(2026),
+            vec![
+                (1, 1, "元日"),
+                (1, 12, "成人の日"),
+                (2, 11, "建国記念の日"),
+                (2, 23, "天皇誕生日"),
+                (3, 20, "春分の日"),
+                (4, 29, "昭和の日"),
+                (5, 3, "憲法記念日"),
+                (5, 4, "みどりの日"),
+                (5, 5, "こどもの日"),
+                (5, 6, "振替休日"),
+                (7, 20, "海の日"),
+                (8, 11, "山の日"),
+                (9, 21, "敬老の日"),
+                (9, 22, "国民の休日"),
+                (9, 23, "秋分の日"),
+                (10, 12, "スポーツの日"),
+                (11, 3, "文化の日"),
+                (11, 23, "勤労感謝の日"),
+            ]
+        );
+    }
+
+    #[test]
+    fn 即位と五輪の特例の年は移動した祝日と挟まれた休日を数える() {
+        let holidays_2019 = holidays_in(2019);
+        assert!(holidays_2019.contains(&(4, 30, "国民の休日")));
+        assert!(holidays_2019.contains(&(5, 1, "天皇の即位の日")));
+        assert!(holidays_2019.contains(&(5, 2, "国民の休日")));
+        assert!(holidays_2019.contains(&(5, 6, "振替休日")));
+        assert!(holidays_2019.contains(&(10, 22, "即位礼正殿の儀の行われる日")));
+        assert!(!holidays_2019
+            .iter()
+            .any(|(_, _, name)| *name == "天皇誕生日"));
+
+        let holidays_2021 = holidays_in(2021);
+        assert!(holidays_2021.contains(&(7, 22, "海の日")));
+        assert!(holidays_2021.contains(&(7, 23, "スポーツの日")));
+        assert!(holidays_2021.contains(&(8, 9, "振替休日")));
+        assert!(!holidays_2021.contains(&(10, 11, "スポーツの日")));
+    }
+
+    #[test]
+    fn 二〇〇七年より前は日曜の翌日だけを振り替える() {
+        // 2008年は5/4(日)の振り替えが5/3と5/5を越えて5/6になる
+        assert_eq!(
+            japanese_holiday_name(NaiveDate::from_ymd_opt(2008, 5, 6).unwrap()),
+            Some("振替休日")
+        );
+        // 2003年は5/4が国民の休日で、日曜の5/4は振り替えない
+        assert_eq!(
+            japanese_holiday_name(NaiveDate::from_ymd_opt(2003, 5, 5).unwrap()),
+            Some("こどもの日")
+        );
+        assert_eq!(
+            japanese_holiday_name(NaiveDate::from_ymd_opt(2003, 5, 6).unwrap()),
+            None
+        );
+        assert_eq!(
+            japanese_holiday_name(NaiveDate::from_ymd_opt(1999, 1, 1).unwrap()),
+            None
+        );
+    }
+}
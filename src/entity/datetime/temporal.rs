@@ -0,0 +1,586 @@
+use super::{BusinessDateTimePolicy, BUSINESS_DAY_START_HOUR};
+use chrono::{
+    DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike,
+    Weekday,
+};
+use std::error::Error;
+use std::fmt;
+
+// CLIとMCPで共通の日時表現。日付の指定と時刻の指定をそれぞれ高々1つ持ち、順序は問わない
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TemporalExpression {
+    pub date: Option<DateTerm>,
+    pub time: Option<ClockTime>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateTerm {
+    Today,
+    Tomorrow,
+    Weekday(Weekday),
+    // 年を省いた月日は、解決した日時が現在以降になる最初の年を選ぶ
+    MonthDay { month: u32, day: u32 },
+    Date(NaiveDate),
+    MonthEnd,
+    Offset { amount: i64, unit: OffsetUnit },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OffsetUnit {
+    Day,
+    Week,
+    Month,
+    BusinessDay,
+}
+
+// 24時以降も書ける時刻。25:30は指定した日の翌calendar日の1:30を表す
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockTime {
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TemporalParseError {
+    Empty,
+    UnknownToken(String),
+    DuplicateDate(String),
+    DuplicateTime(String),
+    InvalidDate(String),
+    InvalidTime(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TemporalResolveError {
+    OutOfRange { operation: &'static str },
+    InvalidMonthDay { month: u32, day: u32 },
+}
+
+impl fmt::Display for TemporalParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(formatter, "temporal expression is empty"),
+            Self::UnknownToken(token) => write!(formatter, "unknown temporal token: {token}"),
+            Self::DuplicateDate(token) => {
+                write!(formatter, "date is specified more than once: {token}")
+            }
+            Self::DuplicateTime(token) => {
+                write!(formatter, "time is specified more than once: {token}")
+            }
+            Self::InvalidDate(token) => write!(formatter, "invalid date: {token}"),
+            Self::InvalidTime(token) => write!(formatter, "invalid time: {token}"),
+        }
+    }
+}
+
+impl Error for TemporalParseError {}
+
+impl fmt::Display for TemporalResolveError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfRange { operation } => {
+                write!(
+                    formatter,
+                    "temporal expression is out of range: {operation}"
+                )
+            }
+            Self::InvalidMonthDay { month, day } => {
+                write!(formatter, "invalid month and day: {month}/{day}")
+            }
+        }
+    }
+}
+
+impl Error for TemporalResolveError {}
+
+impl ClockTime {
+    pub fn new(hour: u32, minute: u32, second: u32) -> Option<Self> {
+        (minute < 60 && second < 60).then_some(Self {
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn business_day_start() -> Self {
+        Self {
+            hour: BUSINESS_DAY_START_HOUR,
+            minute: 0,
+            second: 0,
+        }
+    }
+
+    pub fn from_naive_time(time: NaiveTime) -> Self {
+        Self {
+            hour: time.hour(),
+            minute: time.minute(),
+            second: time.second(),
+        }
+    }
+
+    fn on(&self, date: NaiveDate) -> Option<NaiveDateTime> {
+        let calendar_time = NaiveTime::from_hms_opt(self.hour % 24, self.minute, self.second)?;
+        date.checked_add_signed(Duration::try_days(i64::from(self.hour / 24))?)
+            .map(|date| date.and_time(calendar_time))
+    }
+}
+
+impl TemporalExpression {
+    pub fn parse(tokens: &[&str]) -> Result<Self, TemporalParseError> {
+        let mut expression = Self {
+            date: None,
+            time: None,
+        };
+        let tokens = tokens
+            .iter()
+            .flat_map(|token| token.split_whitespace())
+            .collect::<Vec<_>>();
+        if tokens.is_empty() {
+            return Err(TemporalParseError::Empty);
+        }
+        for token in tokens {
+            if let Some(time) = parse_clock_time(token)? {
+                if expression.time.replace(time).is_some() {
+                    return Err(TemporalParseError::DuplicateTime(token.to_string()));
+                }
+            } else if let Some(date) = parse_date_term(token)? {
+                if expression.date.replace(date).is_some() {
+                    return Err(TemporalParseError::DuplicateDate(token.to_string()));
+                }
+            } else {
+                return Err(TemporalParseError::UnknownToken(token.to_string()));
+            }
+        }
+        Ok(expression)
+    }
+
+    pub fn parse_str(value: &str) -> Result<Self, TemporalParseError> {
+        Self::parse(&[value])
+    }
+
+    // 日付がなければ現在のcalendar日の時刻、時刻がなければdefault_timeを使う
+    pub fn resolve(
+        &self,
+        now: DateTime<Local>,
+        policy: &BusinessDateTimePolicy,
+        default_time: ClockTime,
+    ) -> Result<NaiveDateTime, TemporalResolveError> {
+        let time = self.time.unwrap_or(default_time);
+        let date = match self.date {
+            None => now.date_naive(),
+            Some(DateTerm::MonthDay { month, day }) => {
+                return resolve_upcoming_month_day(month, day, time, now)
+            }
+            Some(term) => term.resolve_date(now, policy)?,
+        };
+        time.on(date).ok_or(TemporalResolveError::OutOfRange {
+            operation: "time_of_day",
+        })
+    }
+}
+
+impl DateTerm {
+    // 今日は主観日で数える。月日は時刻と合わせて年を決めるため、ここでは現在年で解決する
+    pub fn resolve_date(
+        &self,
+        now: DateTime<Local>,
+        policy: &BusinessDateTimePolicy,
+    ) -> Result<NaiveDate, TemporalResolveError> {
+        let out_of_range = |operation| TemporalResolveError::OutOfRange { operation };
+        let today = policy
+            .subjective_date(now)
+            .ok_or(out_of_range("subjective_date"))?;
+        match *self {
+            Self::Today => Ok(today),
+            Self::Tomorrow => today
+                .succ_opt()
+                .ok_or(out_of_range("next_business_day_start")),
+            Self::Weekday(weekday) => {
+                // 曜日は翌日を起点に数えるため、翌日の計算不能と曜日の計算不能を区別する
+                let tomorrow = today
+                    .succ_opt()
+                    .ok_or(out_of_range("next_business_day_start"))?;
+                let difference = (7 + weekday.num_days_from_monday()
+                    - today.weekday().num_days_from_monday())
+                    % 7;
+                let days = if difference == 0 { 7 } else { difference };
+                tomorrow
+                    .checked_add_signed(Duration::days(i64::from(days - 1)))
+                    .ok_or(out_of_range("weekday_date"))
+            }
+            Self::MonthDay { month, day } => NaiveDate::from_ymd_opt(now.year(), month, day)
+                .ok_or(TemporalResolveError::InvalidMonthDay { month, day }),
+            Self::Date(date) => Ok(date),
+            Self::MonthEnd => today
+                .with_day(1)
+                .and_then(|first| first.checked_add_months(Months::new(1)))
+                .and_then(|next_first| next_first.pred_opt())
+                .ok_or(out_of_range("month_end_date")),
+            Self::Offset { amount, unit } => {
+                let date = match unit {
+                    OffsetUnit::Day => Duration::try_days(amount)
+                        .and_then(|duration| today.checked_add_signed(duration)),
+                    OffsetUnit::Week => amount
+                        .checked_mul(7)
+                        .and_then(Duration::try_days)
+                        .and_then(|duration| today.checked_add_signed(duration)),
+                    OffsetUnit::Month => {
+                        u32::try_from(amount.unsigned_abs())
+                            .ok()
+                            .and_then(|months| {
+                                if amount < 0 {
+                                    today.checked_sub_months(Months::new(months))
+                                } else {
+                                    today.checked_add_months(Months::new(months))
+                                }
+                            })
+                    }
+                    OffsetUnit::BusinessDay => policy.add_business_days(today, amount),
+                };
+                date.ok_or(out_of_range("relative_date"))
+            }
+        }
+    }
+}
+
+fn resolve_upcoming_month_day(
+    month: u32,
+    day: u32,
+    time: ClockTime,
+    now: DateTime<Local>,
+) -> Result<NaiveDateTime, TemporalResolveError> {
+    // 2/29のように年で存在が変わる日は、400年周期の同じ位置の年で妥当性だけを先に確かめる
+    let is_valid_in = |year: i32| NaiveDate::from_ymd_opt(2000 + year.rem_euclid(400), month, day);
+    if is_valid_in(now.year()).is_none() {
+        return Err(TemporalResolveError::InvalidMonthDay { month, day });
+    }
+    let out_of_range = TemporalResolveError::OutOfRange {
+        operation: "upcoming_calendar_date",
+    };
+    let this_year = NaiveDate::from_ymd_opt(now.year(), month, day)
+        .and_then(|date| time.on(date))
+        .ok_or(out_of_range)?;
+    if this_year >= now.naive_local() {
+        return Ok(this_year);
+    }
+    let next_year = now.year().checked_add(1).ok_or(out_of_range)?;
+    if is_valid_in(next_year).is_none() {
+        return Err(TemporalResolveError::InvalidMonthDay { month, day });
+    }
+    NaiveDate::from_ymd_opt(next_year, month, day)
+        .and_then(|date| time.on(date))
+        .ok_or(out_of_range)
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Option<T> {
+    (!value.is_empty() && value.chars().all(|character| character.is_ascii_digit()))
+        .then(|| value.parse().ok())
+        .flatten()
+}
+
+fn parse_clock_time(token: &str) -> Result<Option<ClockTime>, TemporalParseError> {
+    if !token.contains(':') {
+        return Ok(None);
+    }
+    let invalid = || TemporalParseError::InvalidTime(token.to_string());
+    let parts = token.split(':').collect::<Vec<_>>();
+    let (hour, minute, second) = match parts.as_slice() {
+        [hour, minute] => (*hour, *minute, "0"),
+        [hour, minute, second] => (*hour, *minute, *second),
+        _ => return Err(invalid()),
+    };
+    if hour.len() > 2 || minute.len() > 2 || second.len() > 2 {
+        return Err(invalid());
+    }
+    let (Some(hour), Some(minute), Some(second)) = (
+        parse_number::<u32>(hour),
+        parse_number::<u32>(minute),
+        parse_number::<u32>(second),
+    ) else {
+        return Err(invalid());
+    };
+    ClockTime::new(hour, minute, second)
+        .map(Some)
+        .ok_or_else(invalid)
+}
+
+fn parse_weekday(token: &str) -> Option<Weekday> {
+    let token = token.to_ascii_lowercase();
+    let token = token
+        .strip_suffix("曜日")
+        .or_else(|| token.strip_suffix('曜'))
+        .unwrap_or(&token);
+    Some(match token {
+        "月" | "mon" | "monday" => Weekday::Mon,
+        "火" | "tue" | "tuesday" => Weekday::Tue,
+        "水" | "wed" | "wednesday" => Weekday::Wed,
+        "木" | "thu" | "thursday" => Weekday::Thu,
+        "金" | "fri" | "friday" => Weekday::Fri,
+        "土" | "sat" | "saturday" => Weekday::Sat,
+        "日" | "sun" | "sunday" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn parse_offset(token: &str) -> Option<DateTerm> {
+    let signed = |sign: &str, amount: i64| if sign == "-" { -amount } else { amount };
+    if let Some(rest) = token.strip_prefix(['+', '-']) {
+        let sign = &token[..1];
+        let split = rest.find(|character: char| !character.is_ascii_digit())?;
+        let amount = parse_number::<i64>(&rest[..split])?;
+        let unit = match rest[split..].to_ascii_lowercase().as_str() {
+            "d" => OffsetUnit::Day,
+            "w" => OffsetUnit::Week,
+            "m" => OffsetUnit::Month,
+            "bd" => OffsetUnit::BusinessDay,
+            _ => return None,
+        };
+        return Some(DateTerm::Offset {
+            amount: signed(sign, amount),
+            unit,
+        });
+    }
+
+    let (rest, sign) = token
+        .strip_suffix('後')
+        .map(|rest| (rest, "+"))
+        .or_else(|| token.strip_suffix('前').map(|rest| (rest, "-")))?;
+    let split = rest.find(|character: char| !character.is_ascii_digit())?;
+    let amount = parse_number::<i64>(&rest[..split])?;
+    let unit = match &rest[split..] {
+        "日" => OffsetUnit::Day,
+        "週" | "週間" => OffsetUnit::Week,
+        "か月" | "ヶ月" | "ヵ月" | "カ月" => OffsetUnit::Month,
+        "営業日" => OffsetUnit::BusinessDay,
+        _ => return None,
+    };
+    Some(DateTerm::Offset {
+        amount: signed(sign, amount),
+        unit,
+    })
+}
+
+fn parse_slash_date(token: &str) -> Result<Option<DateTerm>, TemporalParseError> {
+    let separator = if token.contains('/') { '/' } else { '-' };
+    let parts = token.split(separator).collect::<Vec<_>>();
+    if parts.len() < 2 || parts.iter().any(|part| parse_number::<u32>(part).is_none()) {
+        return Ok(None);
+    }
+    let invalid = || TemporalParseError::InvalidDate(token.to_string());
+    match parts.as_slice() {
+        [month, day] if separator == '/' && month.len() <= 2 && day.len() <= 2 => {
+            let month = parse_number::<u32>(month).ok_or_else(invalid)?;
+            let day = parse_number::<u32>(day).ok_or_else(invalid)?;
+            // 存在しない月日は、解決時に年を決めてから判定する
+            if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+                return Err(invalid());
+            }
+            Ok(Some(DateTerm::MonthDay { month, day }))
+        }
+        [year, month, day]
+            if (2..=4).contains(&year.len()) && month.len() <= 2 && day.len() <= 2 =>
+        {
+            let raw_year = parse_number::<i32>(year).ok_or_else(invalid)?;
+            let year = if raw_year < 100 {
+                raw_year + 2000
+            } else {
+                raw_year
+            };
+            let month = parse_number::<u32>(month).ok_or_else(invalid)?;
+            let day = parse_number::<u32>(day).ok_or_else(invalid)?;
+            NaiveDate::from_ymd_opt(year, month, day)
+                .map(|date| Some(DateTerm::Date(date)))
+                .ok_or_else(invalid)
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_date_term(token: &str) -> Result<Option<DateTerm>, TemporalParseError> {
+    match token.to_ascii_lowercase().as_str() {
+        "今" | "今日" | "today" => return Ok(Some(DateTerm::Today)),
+        "明後" | "明後日" => {
+            return Ok(Some(DateTerm::Offset {
+                amount: 2,
+                unit: OffsetUnit::Day,
+            }))
+        }
+        "末" | "月末" | "eom" => return Ok(Some(DateTerm::MonthEnd)),
+        "tomorrow" => return Ok(Some(DateTerm::Tomorrow)),
+        _ => {}
+    }
+    // 「明日」「明朝」のように明で始まる語は、従来どおり翌日として扱う
+    if token.starts_with('明') {
+        return Ok(Some(DateTerm::Tomorrow));
+    }
+    if let Some(weekday) = parse_weekday(token) {
+        return Ok(Some(DateTerm::Weekday(weekday)));
+    }
+    if let Some(offset) = parse_offset(token) {
+        return Ok(Some(offset));
+    }
+    parse_slash_date(token)
+}
+
+#[cfg(test)]
+mod temporal_expression_contract_tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        // 2026/10/16は金曜日
+        Local.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap()
+    }
+
+    fn policy() -> BusinessDateTimePolicy {
+        BusinessDateTimePolicy::new(30).with_non_business_weekdays(&[Weekday::Sat, Weekday::Sun])
+    }
+
+    fn resolve(expression: &str, now: DateTime<Local>) -> NaiveDateTime {
+        TemporalExpression::parse_str(expression)
+            .unwrap()
+            .resolve(now, &policy(), ClockTime::business_day_start())
+            .unwrap()
+    }
+
+    fn datetime(month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn 日付と時刻の語を順序によらず1つずつ解釈する() {
+        assert_eq!(
+            TemporalExpression::parse(&["9:30", "明"]),
+            TemporalExpression::parse(&["明", "9:30"])
+        );
+        assert_eq!(
+            TemporalExpression::parse_str("金 25:30:15").unwrap(),
+            TemporalExpression {
+                date: Some(DateTerm::Weekday(Weekday::Fri)),
+                time: Some(ClockTime {
+                    hour: 25,
+                    minute: 30,
+                    second: 15,
+                }),
+            }
+        );
+        assert_eq!(
+            TemporalExpression::parse_str("明 火"),
+            Err(TemporalParseError::DuplicateDate("火".to_string()))
+        );
+        assert_eq!(
+            TemporalExpression::parse_str("9:00 10:00"),
+            Err(TemporalParseError::DuplicateTime("10:00".to_string()))
+        );
+        assert_eq!(
+            TemporalExpression::parse_str("そのうち"),
+            Err(TemporalParseError::UnknownToken("そのうち".to_string()))
+        );
+        assert_eq!(
+            TemporalExpression::parse_str("13:99"),
+            Err(TemporalParseError::InvalidTime("13:99".to_string()))
+        );
+        assert_eq!(
+            TemporalExpression::parse_str("2026/2/30"),
+            Err(TemporalParseError::InvalidDate("2026/2/30".to_string()))
+        );
+        assert_eq!(
+            TemporalExpression::parse(&[]),
+            Err(TemporalParseError::Empty)
+        );
+    }
+
+    #[test]
+    fn 相対offsetと月末を主観日から数える() {
+        assert_eq!(resolve("+3d", now()), datetime(10, 19, 6, 0));
+        assert_eq!(resolve("3日後 18:00", now()), datetime(10, 19, 18, 0));
+        assert_eq!(resolve("2週後", now()), datetime(10, 30, 6, 0));
+        assert_eq!(resolve("+1w", now()), datetime(10, 23, 6, 0));
+        assert_eq!(resolve("-2d", now()), datetime(10, 14, 6, 0));
+        assert_eq!(resolve("1か月後", now()), datetime(11, 16, 6, 0));
+        assert_eq!(resolve("末", now()), datetime(10, 31, 6, 0));
+        assert_eq!(resolve("明後日", now()), datetime(10, 18, 6, 0));
+
+        let before_business_day_start = Local.with_ymd_and_hms(2026, 11, 1, 2, 0, 0).unwrap();
+        assert_eq!(
+            resolve("末", before_business_day_start),
+            datetime(10, 31, 6, 0)
+        );
+        assert_eq!(
+            resolve("今", before_business_day_start),
+            datetime(10, 31, 6, 0)
+        );
+    }
+
+    #[test]
+    fn 営業日は設定した休みの曜日を飛ばす() {
+        assert_eq!(resolve("+1bd", now()), datetime(10, 19, 6, 0));
+        assert_eq!(resolve("3営業日後 9:00", now()), datetime(10, 21, 9, 0));
+        assert_eq!(resolve("1営業日前", now()), datetime(10, 15, 6, 0));
+    }
+
+    #[test]
+    fn 曜日は明日以降で最も近い日にする() {
+        assert_eq!(resolve("金", now()), datetime(10, 23, 6, 0));
+        assert_eq!(resolve("土曜", now()), datetime(10, 17, 6, 0));
+        assert_eq!(resolve("mon 9:00", now()), datetime(10, 19, 9, 0));
+    }
+
+    #[test]
+    fn 時刻だけならcalendar日の時刻にし24時以降は翌日へ繰り越す() {
+        assert_eq!(resolve("18:00", now()), datetime(10, 16, 18, 0));
+        assert_eq!(resolve("25:30", now()), datetime(10, 17, 1, 30));
+        assert_eq!(resolve("明 26:00", now()), datetime(10, 18, 2, 0));
+    }
+
+    #[test]
+    fn 年なし月日は解決した日時が現在以降になる年を選ぶ() {
+        assert_eq!(resolve("10/16 12:00", now()), datetime(10, 16, 12, 0));
+        assert_eq!(
+            resolve("10/16 11:59", now()),
+            NaiveDate::from_ymd_opt(2027, 10, 16)
+                .unwrap()
+                .and_hms_opt(11, 59, 0)
+                .unwrap()
+        );
+        assert_eq!(resolve("12/1", now()), datetime(12, 1, 6, 0));
+        assert_eq!(
+            TemporalExpression::parse_str("2/30").unwrap().resolve(
+                now(),
+                &policy(),
+                ClockTime::business_day_start()
+            ),
+            Err(TemporalResolveError::InvalidMonthDay { month: 2, day: 30 })
+        );
+    }
+
+    #[test]
+    fn 範囲外の日付は計算した操作名を返す() {
+        let now = Local
+            .from_local_datetime(&NaiveDate::MAX.and_hms_opt(12, 0, 0).unwrap())
+            .single()
+            .unwrap();
+
+        for (expression, operation) in [
+            ("明", "next_business_day_start"),
+            ("月", "next_business_day_start"),
+            ("+1d", "relative_date"),
+            ("12/31", "upcoming_calendar_date"),
+        ] {
+            let actual = TemporalExpression::parse_str(expression).unwrap().resolve(
+                now,
+                &policy(),
+                ClockTime::business_day_start(),
+            );
+            assert_eq!(
+                actual,
+                Err(TemporalResolveError::OutOfRange { operation }),
+                "{expression}"
+            );
+        }
+    }
+}
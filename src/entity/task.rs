@@ -7,7 +7,9 @@ use std::fmt;
 use uuid::Uuid;
 use yaml_rust::Yaml;
 
+use crate::entity::allowed_window::AllowedWindow;
 use crate::entity::datetime::{BusinessDateTimePolicy, DEFAULT_END_OF_DAY_OFFSET_MINUTES};
+use crate::entity::waiting::WaitingRecord;
 
 #[cfg(test)]
 use chrono::TimeZone;
@@ -898,6 +900,8 @@ pub struct TaskAttr {
     repetition_anchor: RepetitionAnchor,
     days_in_advance: i64, // 繰り返しタスクについて、何日前から着手開始可能とするか
     project_category_opt: Option<ProjectCategory>,
+    waiting_opt: Option<WaitingRecord>, // 相手ボールの相手と催促の予定
+    allowed_window_opt: Option<AllowedWindow>, // 作業してよい曜日と時間帯。子孫にも効く
     persistent_mutation_revision: u64,
 }
 
@@ -923,6 +927,8 @@ impl PartialEq for TaskAttr {
             && self.repetition_anchor == other.repetition_anchor
             && self.days_in_advance == other.days_in_advance
             && self.project_category_opt == other.project_category_opt
+            && self.waiting_opt == other.waiting_opt
+            && self.allowed_window_opt == other.allowed_window_opt
     }
 }
 
@@ -984,6 +990,8 @@ impl TaskAttr {
             repetition_anchor: RepetitionAnchor::Deadline,
             days_in_advance: 0,
             project_category_opt: None,
+            waiting_opt: None,
+            allowed_window_opt: None,
             persistent_mutation_revision: 0,
         }
     }
@@ -1179,6 +1187,22 @@ impl TaskAttr {
     pub fn get_project_category_opt(&self) -> Option<ProjectCategory> {
         self.project_category_opt
     }
+
+    pub fn set_waiting_opt(&mut self, waiting_opt: Option<WaitingRecord>) {
+        self.waiting_opt = waiting_opt;
+    }
+
+    pub fn get_waiting_opt(&self) -> &Option<WaitingRecord> {
+        &self.waiting_opt
+    }
+
+    pub fn set_allowed_window_opt(&mut self, allowed_window_opt: Option<AllowedWindow>) {
+        self.allowed_window_opt = allowed_window_opt;
+    }
+
+    pub fn get_allowed_window_opt(&self) -> &Option<AllowedWindow> {
+        &self.allowed_window_opt
+    }
 }
 
 #[test]
@@ -1669,6 +1693,16 @@ impl TaskHandle {
     }
 
     pub fn reparent_to(&mut self, parent_task: &Self) -> Result<(), TaskTreeError> {
+        self.reparent_to_position(parent_task, None)
+    }
+
+    /// Moves this subtree under `parent_task` so that it becomes the child at `position`.
+    /// A position past the last child appends it, like `reparent_to`.
+    pub fn reparent_to_position(
+        &mut self,
+        parent_task: &Self,
+        position: Option<usize>,
+    ) -> Result<(), TaskTreeError> {
         if self.node.ptr_eq(&parent_task.node)
             || parent_task
                 .node
@@ -1695,8 +1729,27 @@ impl TaskHandle {
             .bundle_new_hierarchy_edit_grant()
             .map_err(|_| TaskTreeError::HierarchyGrant)?;
 
+        let next_sibling_hot = position
+            .and_then(|position| {
+                parent_task
+                    .node
+                    .children()
+                    .filter(|child| !child.ptr_eq(&self.node))
+                    .nth(position)
+            })
+            .map(|sibling| {
+                sibling
+                    .bundle_new_hierarchy_edit_grant()
+                    .map_err(|_| TaskTreeError::HierarchyGrant)
+            })
+            .transpose()?;
+        let destination = match &next_sibling_hot {
+            Some(next_sibling_hot) => InsertAs::PreviousSiblingOf(next_sibling_hot),
+            None => InsertAs::LastChildOf(&parent_task_hot),
+        };
+
         self.node
-            .try_detach_insert_subtree(&self_grant, InsertAs::LastChildOf(&parent_task_hot))
+            .try_detach_insert_subtree(&self_grant, destination)
             .map_err(|_| TaskTreeError::Insert)?;
 
         source_root.mark_persistent_mutation()?;
@@ -1706,6 +1759,41 @@ impl TaskHandle {
         Ok(())
     }
 
+    /// Detaches this subtree from its parent into a new tree of its own, so that this task
+    /// becomes a root. Returns the position it had among its former siblings.
+    pub fn detach_from_parent(&mut self) -> Result<usize, TaskTreeError> {
+        let parent = self.parent()?.ok_or(TaskTreeError::RootOperation)?;
+        let position = parent
+            .node
+            .children()
+            .position(|child| child.ptr_eq(&self.node))
+            .ok_or(TaskTreeError::MissingDummyRootChild)?;
+        let source_root = self.root()?;
+        source_root.ensure_persistent_mutation_writable()?;
+
+        let attr = self.get_attr()?;
+        let dummy_attr = TaskAttr::with_identity(
+            format!("dummy-for-{}", attr.get_name()).as_str(),
+            Uuid::nil(),
+            *attr.get_create_time(),
+        );
+        let dummy_root_hot = Node::new_tree(dummy_attr)
+            .bundle_new_hierarchy_edit_grant()
+            .map_err(|_| TaskTreeError::HierarchyGrant)?;
+        let self_grant = self
+            .node
+            .tree()
+            .grant_hierarchy_edit()
+            .map_err(|_| TaskTreeError::HierarchyGrant)?;
+
+        self.node
+            .try_detach_insert_subtree(&self_grant, InsertAs::LastChildOf(&dummy_root_hot))
+            .map_err(|_| TaskTreeError::Insert)?;
+
+        source_root.mark_persistent_mutation()?;
+        Ok(position)
+    }
+
     /// Inserts a newly-created parent between this task and its current parent.
     pub fn create_parent(&mut self, task_attr: TaskAttr) -> Result<(), TaskTreeError> {
         let original_parent = self.parent()?.ok_or(TaskTreeError::RootOperation)?;
@@ -1873,6 +1961,57 @@ impl TaskHandle {
         })
     }
 
+    pub fn get_waiting_opt(&self) -> Result<Option<WaitingRecord>, TaskTreeError> {
+        self.node
+            .try_borrow_data()
+            .map(|attr| attr.get_waiting_opt().clone())
+            .map_err(|_| TaskTreeError::Borrow)
+    }
+
+    pub fn set_waiting_opt(&self, waiting_opt: Option<WaitingRecord>) -> Result<(), TaskTreeError> {
+        self.update(|attr| {
+            if attr.get_waiting_opt() == &waiting_opt {
+                false
+            } else {
+                attr.set_waiting_opt(waiting_opt);
+                true
+            }
+        })
+    }
+
+    pub fn get_allowed_window_opt(&self) -> Result<Option<AllowedWindow>, TaskTreeError> {
+        self.node
+            .try_borrow_data()
+            .map(|attr| attr.get_allowed_window_opt().clone())
+            .map_err(|_| TaskTreeError::Borrow)
+    }
+
+    pub fn set_allowed_window_opt(
+        &self,
+        allowed_window_opt: Option<AllowedWindow>,
+    ) -> Result<(), TaskTreeError> {
+        self.update(|attr| {
+            if attr.get_allowed_window_opt() == &allowed_window_opt {
+                false
+            } else {
+                attr.set_allowed_window_opt(allowed_window_opt);
+                true
+            }
+        })
+    }
+
+    // 自身に枠がなければ、最も近い祖先の枠に従う
+    pub fn get_effective_allowed_window_opt(&self) -> Result<Option<AllowedWindow>, TaskTreeError> {
+        let mut current_opt = Some(self.clone());
+        while let Some(current) = current_opt {
+            if let Some(allowed_window) = current.get_allowed_window_opt()? {
+                return Ok(Some(allowed_window));
+            }
+            current_opt = current.parent()?;
+        }
+        Ok(None)
+    }
+
     pub fn get_atomic(&self) -> Result<bool, TaskTreeError> {
         self.node
             .try_borrow_data()
@@ -2257,6 +2396,11 @@ impl TaskHandle {
         Ok(self.node.ptr_eq(&root.node))
     }
 
+    // 同じnodeを指すhandleかを返す。値が同じでも別に読み込んだnodeならfalse
+    pub fn ptr_eq(&self, other: &TaskHandle) -> bool {
+        self.node.ptr_eq(&other.node)
+    }
+
     pub fn tree_debug_pretty_print(&self) -> Result<String, TaskTreeError> {
         self.get_attr()?;
         Ok(format!("{:?}", self.node.tree().debug_pretty_print()))
@@ -2812,6 +2956,20 @@ pub fn task_to_yaml(task: &TaskHandle) -> Result<Yaml, TaskTreeError> {
         );
     }
 
+    if let Some(waiting) = task.get_waiting_opt()? {
+        task_hash.insert(
+            Yaml::String(String::from("waiting")),
+            waiting_record_to_yaml(&waiting),
+        );
+    }
+
+    if let Some(allowed_window) = task.get_allowed_window_opt()? {
+        task_hash.insert(
+            Yaml::String(String::from("allowed_window")),
+            Yaml::String(allowed_window.to_string()),
+        );
+    }
+
     let atomic = task.get_atomic()?;
     if atomic != default_attr.get_atomic() {
         task_hash.insert(Yaml::String(String::from("atomic")), Yaml::Boolean(atomic));
@@ -2932,6 +3090,37 @@ pub fn task_to_yaml(task: &TaskHandle) -> Result<Yaml, TaskTreeError> {
     Ok(Yaml::Hash(task_hash))
 }
 
+fn waiting_record_to_yaml(waiting: &WaitingRecord) -> Yaml {
+    let mut waiting_hash = LinkedHashMap::new();
+    waiting_hash.insert(
+        Yaml::String(String::from("counterpart")),
+        Yaml::String(waiting.get_counterpart().to_string()),
+    );
+    waiting_hash.insert(
+        Yaml::String(String::from("since")),
+        Yaml::String(waiting.get_since().format("%Y/%m/%d %H:%M:%S").to_string()),
+    );
+    if let Some(expected_reply_date) = waiting.get_expected_reply_date_opt() {
+        waiting_hash.insert(
+            Yaml::String(String::from("expected_reply_date")),
+            Yaml::String(expected_reply_date.format("%Y/%m/%d").to_string()),
+        );
+    }
+    if let Some(follow_up_interval_days) = waiting.get_follow_up_interval_days_opt() {
+        waiting_hash.insert(
+            Yaml::String(String::from("follow_up_interval_days")),
+            Yaml::Integer(follow_up_interval_days),
+        );
+    }
+    if let Some(last_followed_up_at) = waiting.get_last_followed_up_at_opt() {
+        waiting_hash.insert(
+            Yaml::String(String::from("last_followed_up_at")),
+            Yaml::String(last_followed_up_at.format("%Y/%m/%d %H:%M:%S").to_string()),
+        );
+    }
+    Yaml::Hash(waiting_hash)
+}
+
 #[test]
 fn test_task_to_yaml_正常系1_デフォルトの値と同じ場合は出力しない() {
     let mut task = new_test_task_handle("タスク1").unwrap();
@@ -3122,6 +3311,94 @@ start_time: '2023/05/19 01:23:45'
     assert_eq!(&actual, expected_yaml);
 }
 
+#[test]
+fn test_task_to_yaml_waiting() {
+    let mut task = new_test_task_handle("タスク1").unwrap();
+    let id: Uuid = uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8");
+    task.set_id(id).unwrap();
+    task.set_is_on_other_side(true).unwrap();
+    let now = Local.with_ymd_and_hms(2023, 5, 19, 1, 23, 45).unwrap();
+    let mut waiting = WaitingRecord::new(
+        "田中",
+        now,
+        chrono::NaiveDate::from_ymd_opt(2023, 5, 22),
+        Some(3),
+    );
+    waiting.set_last_followed_up_at_opt(Some(now + Duration::days(4)));
+    task.set_waiting_opt(Some(waiting)).unwrap();
+    task.set_create_time(now).unwrap();
+    task.set_start_time(now).unwrap();
+    let actual = task_to_yaml(&task).unwrap();
+
+    let s = "
+name: 'タスク1'
+id: 67e55044-10b1-426f-9247-bb680e5fe0c8
+is_on_other_side: true
+waiting:
+  counterpart: '田中'
+  since: '2023/05/19 01:23:45'
+  expected_reply_date: '2023/05/22'
+  follow_up_interval_days: 3
+  last_followed_up_at: '2023/05/23 01:23:45'
+create_time: '2023/05/19 01:23:45'
+start_time: '2023/05/19 01:23:45'
+";
+    let docs = YamlLoader::load_from_str(s).unwrap();
+    let expected_yaml: &Yaml = &docs[0];
+
+    assert_eq!(&actual, expected_yaml);
+}
+
+#[test]
+fn test_task_to_yaml_allowed_window() {
+    let mut task = new_test_task_handle("タスク1").unwrap();
+    let id: Uuid = uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8");
+    task.set_id(id).unwrap();
+    task.set_allowed_window_opt(Some(
+        AllowedWindow::parse("平日 09:00-12:00,13:00-18:00").unwrap(),
+    ))
+    .unwrap();
+    let now = Local.with_ymd_and_hms(2023, 5, 19, 1, 23, 45).unwrap();
+    task.set_create_time(now).unwrap();
+    task.set_start_time(now).unwrap();
+    let actual = task_to_yaml(&task).unwrap();
+
+    let s = "
+name: 'タスク1'
+id: 67e55044-10b1-426f-9247-bb680e5fe0c8
+allowed_window: '月火水木金 09:00-12:00,13:00-18:00'
+create_time: '2023/05/19 01:23:45'
+start_time: '2023/05/19 01:23:45'
+";
+    let docs = YamlLoader::load_from_str(s).unwrap();
+    let expected_yaml: &Yaml = &docs[0];
+
+    assert_eq!(&actual, expected_yaml);
+}
+
+#[test]
+fn test_get_effective_allowed_window_opt_自身になければ最も近い祖先の枠を使う() {
+    let root = new_test_task_handle("root").unwrap();
+    let child = root.create_child(new_test_task_attr("子")).unwrap();
+    let grandchild = child.create_child(new_test_task_attr("孫")).unwrap();
+    let morning = AllowedWindow::parse("06:00-09:00").unwrap();
+    let weekdays = AllowedWindow::parse("平日").unwrap();
+
+    assert_eq!(grandchild.get_effective_allowed_window_opt().unwrap(), None);
+
+    root.set_allowed_window_opt(Some(weekdays.clone())).unwrap();
+    assert_eq!(
+        grandchild.get_effective_allowed_window_opt().unwrap(),
+        Some(weekdays)
+    );
+
+    child.set_allowed_window_opt(Some(morning.clone())).unwrap();
+    assert_eq!(
+        grandchild.get_effective_allowed_window_opt().unwrap(),
+        Some(morning)
+    );
+}
+
 #[test]
 fn test_task_to_yaml_atomic() {
     let mut task = new_test_task_handle("タスク1").unwrap();
@@ -3747,6 +4024,56 @@ fn test_reparent_toは循環をerrorにして木とrevisionを変更しない()
     );
 }
 
+#[test]
+fn test_reparent_to_positionは指定した位置の兄弟の前に挿入する() {
+    let root = new_test_task_handle("親").unwrap();
+    let first = root.create_child(new_test_task_attr("一")).unwrap();
+    root.create_child(new_test_task_attr("二")).unwrap();
+    let mut moved = new_test_task_handle("移").unwrap();
+
+    moved.reparent_to_position(&root, Some(1)).unwrap();
+    let mut appended = new_test_task_handle("末").unwrap();
+    appended.reparent_to_position(&root, Some(10)).unwrap();
+
+    let names = root
+        .get_children()
+        .unwrap()
+        .iter()
+        .map(|child| child.get_name().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["一", "移", "二", "末"]);
+    assert!(moved.parent().unwrap().unwrap().ptr_eq(&root));
+    assert!(first.parent().unwrap().unwrap().ptr_eq(&root));
+}
+
+#[test]
+fn test_detach_from_parentは元の位置を返し部分木を独立したrootにする() {
+    let root = new_test_task_handle("親").unwrap();
+    root.create_child(new_test_task_attr("一")).unwrap();
+    let mut detached = root.create_child(new_test_task_attr("二")).unwrap();
+    let grandchild = detached.create_child(new_test_task_attr("孫")).unwrap();
+    root.create_child(new_test_task_attr("三")).unwrap();
+    let before_revision = root.get_persistent_mutation_revision().unwrap();
+
+    assert_eq!(detached.detach_from_parent(), Ok(1));
+
+    assert_eq!(root.num_children(), Ok(2));
+    assert_eq!(root.get_by_id(grandchild.get_id().unwrap()), Ok(None));
+    assert_eq!(detached.parent(), Ok(None));
+    assert!(grandchild.root().unwrap().ptr_eq(&detached));
+    assert_ne!(
+        root.get_persistent_mutation_revision().unwrap(),
+        before_revision
+    );
+}
+
+#[test]
+fn test_detach_from_parentはrootをerrorにする() {
+    let mut root = new_test_task_handle("親").unwrap();
+
+    assert_eq!(root.detach_from_parent(), Err(TaskTreeError::RootOperation));
+}
+
 #[test]
 fn test_deadline伝搬は子の借用競合時に部分更新とrevision更新をしない() {
     let root = new_test_task_handle("親").unwrap();
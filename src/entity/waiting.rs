@@ -0,0 +1,157 @@
+use crate::entity::datetime::{BusinessDateTimePolicy, DEFAULT_END_OF_DAY_OFFSET_MINUTES};
+#[cfg(test)]
+use chrono::TimeZone;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+
+// 相手ボールのタスクについて、誰をいつから待っていて、いつ催促するかの記録
+#[derive(Clone, Debug, PartialEq)]
+pub struct WaitingRecord {
+    counterpart: String,
+    since: DateTime<Local>,
+    expected_reply_date_opt: Option<NaiveDate>,
+    follow_up_interval_days_opt: Option<i64>,
+    last_followed_up_at_opt: Option<DateTime<Local>>,
+}
+
+impl WaitingRecord {
+    pub fn new(
+        counterpart: &str,
+        since: DateTime<Local>,
+        expected_reply_date_opt: Option<NaiveDate>,
+        follow_up_interval_days_opt: Option<i64>,
+    ) -> Self {
+        Self {
+            counterpart: counterpart.to_string(),
+            since,
+            expected_reply_date_opt,
+            follow_up_interval_days_opt,
+            last_followed_up_at_opt: None,
+        }
+    }
+
+    pub fn get_counterpart(&self) -> &str {
+        &self.counterpart
+    }
+
+    pub fn get_since(&self) -> DateTime<Local> {
+        self.since
+    }
+
+    pub fn get_expected_reply_date_opt(&self) -> Option<NaiveDate> {
+        self.expected_reply_date_opt
+    }
+
+    pub fn get_follow_up_interval_days_opt(&self) -> Option<i64> {
+        self.follow_up_interval_days_opt
+    }
+
+    pub fn get_last_followed_up_at_opt(&self) -> Option<DateTime<Local>> {
+        self.last_followed_up_at_opt
+    }
+
+    pub fn set_last_followed_up_at_opt(
+        &mut self,
+        last_followed_up_at_opt: Option<DateTime<Local>>,
+    ) {
+        self.last_followed_up_at_opt = last_followed_up_at_opt;
+    }
+
+    // 返答予定日を過ぎた翌日の業務日開始と、前回の催促(なければ待ち始め)から催促間隔が経った時刻のうち早いほう。
+    // 返答予定日による催促は、その時刻以降に一度催促すれば済んだものとする
+    pub fn next_follow_up_time(&self) -> Option<DateTime<Local>> {
+        let datetime_policy = BusinessDateTimePolicy::new(DEFAULT_END_OF_DAY_OFFSET_MINUTES);
+        let reply_follow_up_opt = self
+            .expected_reply_date_opt
+            .and_then(|date| date.succ_opt())
+            .and_then(|date| datetime_policy.subjective_date_start(date).earliest())
+            .filter(|time| {
+                self.last_followed_up_at_opt
+                    .is_none_or(|followed_up_at| followed_up_at < *time)
+            });
+        let interval_follow_up_opt = self
+            .follow_up_interval_days_opt
+            .and_then(Duration::try_days)
+            .and_then(|interval| {
+                self.last_followed_up_at_opt
+                    .unwrap_or(self.since)
+                    .checked_add_signed(interval)
+            });
+
+        match (reply_follow_up_opt, interval_follow_up_opt) {
+            (Some(reply), Some(interval)) => Some(reply.min(interval)),
+            (reply_opt, interval_opt) => reply_opt.or(interval_opt),
+        }
+    }
+
+    pub fn is_follow_up_due(&self, now: DateTime<Local>) -> bool {
+        self.next_follow_up_time()
+            .is_some_and(|follow_up_time| follow_up_time <= now)
+    }
+
+    pub fn age_days(&self, now: DateTime<Local>) -> i64 {
+        (now - self.since).num_days().max(0)
+    }
+}
+
+#[test]
+fn test_next_follow_up_timeは返答予定日の翌日開始と催促間隔の早いほうを返す() {
+    let since = Local.with_ymd_and_hms(2026, 8, 19, 10, 0, 0).unwrap();
+    let reply_date = NaiveDate::from_ymd_opt(2026, 8, 21);
+
+    let reply_only = WaitingRecord::new("田中", since, reply_date, None);
+    let interval_only = WaitingRecord::new("田中", since, None, Some(1));
+    let both = WaitingRecord::new("田中", since, reply_date, Some(5));
+    let neither = WaitingRecord::new("田中", since, None, None);
+
+    assert_eq!(
+        reply_only.next_follow_up_time(),
+        Some(Local.with_ymd_and_hms(2026, 8, 22, 6, 0, 0).unwrap())
+    );
+    assert_eq!(
+        interval_only.next_follow_up_time(),
+        Some(Local.with_ymd_and_hms(2026, 8, 20, 10, 0, 0).unwrap())
+    );
+    assert_eq!(
+        both.next_follow_up_time(),
+        Some(Local.with_ymd_and_hms(2026, 8, 22, 6, 0, 0).unwrap())
+    );
+    assert_eq!(neither.next_follow_up_time(), None);
+}
+
+#[test]
+fn test_催促後は返答予定日を使わず前回の催促から間隔を数える() {
+    let since = Local.with_ymd_and_hms(2026, 8, 19, 10, 0, 0).unwrap();
+    let followed_up_at = Local.with_ymd_and_hms(2026, 8, 22, 9, 0, 0).unwrap();
+    let mut record =
+        WaitingRecord::new("田中", since, NaiveDate::from_ymd_opt(2026, 8, 21), Some(3));
+
+    record.set_last_followed_up_at_opt(Some(followed_up_at));
+
+    assert_eq!(
+        record.next_follow_up_time(),
+        Some(Local.with_ymd_and_hms(2026, 8, 25, 9, 0, 0).unwrap())
+    );
+    assert!(!record.is_follow_up_due(followed_up_at));
+    assert!(record.is_follow_up_due(Local.with_ymd_and_hms(2026, 8, 25, 9, 0, 0).unwrap()));
+
+    let mut reply_only =
+        WaitingRecord::new("田中", since, NaiveDate::from_ymd_opt(2026, 8, 21), None);
+    reply_only.set_last_followed_up_at_opt(Some(followed_up_at));
+    assert_eq!(reply_only.next_follow_up_time(), None);
+}
+
+#[test]
+fn test_age_daysは待ち始めからの経過日数を返す() {
+    let since = Local.with_ymd_and_hms(2026, 8, 19, 10, 0, 0).unwrap();
+    let record = WaitingRecord::new("田中", since, None, None);
+
+    assert_eq!(record.age_days(since), 0);
+    assert_eq!(
+        record.age_days(Local.with_ymd_and_hms(2026, 8, 22, 9, 59, 59).unwrap()),
+        2
+    );
+    assert_eq!(
+        record.age_days(Local.with_ymd_and_hms(2026, 8, 18, 10, 0, 0).unwrap()),
+        0
+    );
+}
@@ -7,6 +7,10 @@ use chrono::{DateTime, Local, TimeZone};
 use std::cell::Cell;
 use uuid::Uuid;
 
+mod storage_dir;
+
+pub(crate) use storage_dir::TestStorageDir;
+
 fn next_task_id() -> Uuid {
     use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -111,6 +115,26 @@ impl TaskRepositoryTrait for TestTaskRepository {
         self.projects.push(root_task);
         Ok(())
     }
+
+    fn remove_project(&mut self, root_task_id: Uuid) -> Result<Option<String>, TaskTreeError> {
+        let Some(index) = self
+            .projects
+            .iter()
+            .position(|project| project.get_id() == Ok(root_task_id))
+        else {
+            return Ok(None);
+        };
+        self.projects.remove(index).get_name().map(Some)
+    }
+
+    fn restore_project(
+        &mut self,
+        project_key: &str,
+        root_task: TaskHandle,
+    ) -> Result<String, TaskTreeError> {
+        self.projects.push(root_task);
+        Ok(project_key.to_string())
+    }
 }
 
 pub(crate) struct TestFreeTimeManager {
@@ -174,4 +198,48 @@ impl FreeTimeManagerTrait for TestFreeTimeManager {
     ) -> Result<(), BusyTimeSlotLoadError> {
         Ok(())
     }
+
+    fn clear_registered_busy_time_slots(&mut self) {}
+}
+
+// 登録した区間だけが埋まっていて、それ以外はすべて空いている
+pub(crate) struct BusyIntervals(pub(crate) Vec<(DateTime<Local>, DateTime<Local>)>);
+
+impl FreeTimeManagerTrait for BusyIntervals {
+    fn get_free_minutes(&mut self, start: &DateTime<Local>, end: &DateTime<Local>) -> i64 {
+        let busy_minutes = self
+            .0
+            .iter()
+            .map(|(busy_start, busy_end)| {
+                ((*end).min(*busy_end) - (*start).max(*busy_start))
+                    .num_minutes()
+                    .max(0)
+            })
+            .sum::<i64>();
+        (*end - *start).num_minutes().max(0) - busy_minutes
+    }
+
+    fn get_busy_minutes(&mut self, start: &DateTime<Local>, end: &DateTime<Local>) -> i64 {
+        (*end - *start).num_minutes() - self.get_free_minutes(start, end)
+    }
+
+    fn register_busy_time_slot(
+        &mut self,
+        start: &DateTime<Local>,
+        end: &DateTime<Local>,
+    ) -> Result<(), BusyTimeSlotRegistrationError> {
+        self.0.push((*start, *end));
+        Ok(())
+    }
+
+    fn load_busy_time_slots_from_file(
+        &mut self,
+        _busy_time_slots_file_path: &str,
+    ) -> Result<(), BusyTimeSlotLoadError> {
+        Ok(())
+    }
+
+    fn clear_registered_busy_time_slots(&mut self) {
+        self.0.clear();
+    }
 }
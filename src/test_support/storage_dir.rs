@@ -0,0 +1,26 @@
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+// testごとの保存先。dropで中身ごと消す
+pub(crate) struct TestStorageDir {
+    pub(crate) path: PathBuf,
+}
+
+impl TestStorageDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("schronu-{name}-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub(crate) fn path_str(&self) -> &str {
+        self.path.to_str().expect("test path must be valid UTF-8")
+    }
+}
+
+impl Drop for TestStorageDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
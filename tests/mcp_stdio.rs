@@ -123,7 +123,10 @@ fn mcp_stdio_stdoutにinitializeとtools_listのjson_rpc応答だけを出力す
     assert_eq!(responses[0]["result"]["protocolVersion"], "2025-06-18");
     assert_eq!(responses[1]["jsonrpc"], "2.0");
     assert_eq!(responses[1]["id"], "tools-list");
-    assert_eq!(responses[1]["result"]["tools"].as_array().unwrap().len(), 9);
+    assert_eq!(
+        responses[1]["result"]["tools"].as_array().unwrap().len(),
+        27
+    );
 }
 
 #[test]
@@ -224,7 +227,10 @@ fn mcp_stdio_不正なinitialize_request後も同一processで正常に初期化
     assert_eq!(responses[1]["id"], "valid-initialize");
     assert_eq!(responses[1]["result"]["protocolVersion"], "2025-06-18");
     assert_eq!(responses[2]["id"], "tools-after-valid-initialize");
-    assert_eq!(responses[2]["result"]["tools"].as_array().unwrap().len(), 9);
+    assert_eq!(
+        responses[2]["result"]["tools"].as_array().unwrap().len(),
+        27
+    );
 }
 
 #[test]